@@ -0,0 +1,15 @@
+//! Generates the gRPC service code from `proto/` (see `src/grpc`)
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The protobuf compiler ships with the build dependencies, so building
+    // does not need `protoc` installed
+    let mut config = tonic_prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_prost_build::configure().compile_with_config(
+        config,
+        &["proto/attendance/v1/attendance.proto"],
+        &["proto"],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,2 @@
+-- Revert event_type check constraint on attendance_events
+ALTER TABLE attendance_events DROP CONSTRAINT IF EXISTS chk_attendance_events_event_type;
@@ -0,0 +1,8 @@
+-- Restrict attendance_events.event_type to the known event types
+-- The application maps this column to the `EventType` enum, so any other value
+-- would fail to decode. Enforcing it in the database keeps the audit trail clean
+-- even for rows written outside the API.
+
+ALTER TABLE attendance_events
+    ADD CONSTRAINT chk_attendance_events_event_type
+    CHECK (event_type IN ('clock_in', 'clock_out', 'break_start', 'break_end'));
@@ -0,0 +1,2 @@
+-- Revert attendance_corrections table creation
+DROP TABLE IF EXISTS attendance_corrections;
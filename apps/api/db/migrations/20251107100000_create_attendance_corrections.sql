@@ -0,0 +1,54 @@
+-- Create attendance_corrections table
+-- Attendance events are immutable, so mistakes are fixed by submitting a correction
+-- request that references the original event and proposes a new event type/time.
+-- The original event is never modified; approved corrections are applied by
+-- appending new events.
+
+CREATE TABLE attendance_corrections (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- The attendance event this correction refers to
+    -- ON DELETE CASCADE keeps corrections consistent with their events
+    event_id UUID NOT NULL REFERENCES attendance_events(id) ON DELETE CASCADE,
+
+    -- Owner of the original event (denormalized for per-user listing)
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Proposed replacement values for the original event
+    proposed_event_type VARCHAR(20) NOT NULL
+        CHECK (proposed_event_type IN ('clock_in', 'clock_out', 'break_start', 'break_end')),
+    proposed_event_time TIMESTAMP(6) WITH TIME ZONE NOT NULL,
+
+    -- Free-text explanation from the requester
+    reason TEXT NOT NULL,
+
+    -- Workflow status: 'pending', 'approved', 'rejected'
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+
+    -- Timestamp when the correction was requested
+    created_at TIMESTAMP(6) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Add table comment
+COMMENT ON TABLE attendance_corrections IS 'Correction requests for immutable attendance events';
+
+-- Add column comments
+COMMENT ON COLUMN attendance_corrections.id IS 'Unique identifier for the correction request (UUID)';
+COMMENT ON COLUMN attendance_corrections.event_id IS 'Attendance event the correction refers to';
+COMMENT ON COLUMN attendance_corrections.user_id IS 'Owner of the original attendance event';
+COMMENT ON COLUMN attendance_corrections.proposed_event_type IS 'Proposed event type: clock_in, clock_out, break_start, break_end';
+COMMENT ON COLUMN attendance_corrections.proposed_event_time IS 'Proposed event time';
+COMMENT ON COLUMN attendance_corrections.reason IS 'Reason given for the correction';
+COMMENT ON COLUMN attendance_corrections.status IS 'Workflow status: pending, approved, rejected';
+COMMENT ON COLUMN attendance_corrections.created_at IS 'Timestamp when the correction was requested';
+
+-- Index for listing a user's corrections, most recent first
+CREATE INDEX idx_attendance_corrections_user_created_at ON attendance_corrections(user_id, created_at DESC);
+
+-- Index for finding corrections of an event
+CREATE INDEX idx_attendance_corrections_event_id ON attendance_corrections(event_id);
+
+-- Partial index for the review queue of pending corrections
+CREATE INDEX idx_attendance_corrections_pending ON attendance_corrections(created_at) WHERE status = 'pending';
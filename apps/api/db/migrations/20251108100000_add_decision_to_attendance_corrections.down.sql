@@ -0,0 +1,6 @@
+-- Revert approval decision columns on attendance_corrections
+ALTER TABLE attendance_corrections
+    DROP COLUMN IF EXISTS applied_event_id,
+    DROP COLUMN IF EXISTS decision_comment,
+    DROP COLUMN IF EXISTS decided_at,
+    DROP COLUMN IF EXISTS approver_id;
@@ -0,0 +1,19 @@
+-- Add approval decision columns to attendance_corrections
+-- A manager approves or rejects a pending correction. On approval, a compensating
+-- attendance event is appended and linked here; the original event stays untouched.
+
+ALTER TABLE attendance_corrections
+    -- User who approved or rejected the correction (NULL while pending)
+    ADD COLUMN approver_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    -- Timestamp of the approval/rejection decision (NULL while pending)
+    ADD COLUMN decided_at TIMESTAMP(6) WITH TIME ZONE,
+    -- Optional comment from the approver
+    ADD COLUMN decision_comment TEXT,
+    -- Compensating attendance event appended on approval
+    ADD COLUMN applied_event_id UUID REFERENCES attendance_events(id) ON DELETE SET NULL;
+
+-- Add column comments
+COMMENT ON COLUMN attendance_corrections.approver_id IS 'User who approved or rejected the correction';
+COMMENT ON COLUMN attendance_corrections.decided_at IS 'Timestamp of the approval/rejection decision';
+COMMENT ON COLUMN attendance_corrections.decision_comment IS 'Optional comment from the approver';
+COMMENT ON COLUMN attendance_corrections.applied_event_id IS 'Compensating attendance event appended on approval';
@@ -0,0 +1,8 @@
+-- Revert geolocation on attendance_events
+
+ALTER TABLE attendance_events
+    DROP CONSTRAINT IF EXISTS chk_attendance_events_location_pair,
+    DROP CONSTRAINT IF EXISTS chk_attendance_events_longitude,
+    DROP CONSTRAINT IF EXISTS chk_attendance_events_latitude,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude;
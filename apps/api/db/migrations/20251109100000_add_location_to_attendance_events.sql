@@ -0,0 +1,21 @@
+-- Add optional geolocation to attendance_events
+-- Mobile clients report where a clock-in/out happened so events can be audited
+-- for location. Both coordinates are given together or not at all.
+
+ALTER TABLE attendance_events
+    -- Latitude in decimal degrees (WGS 84), -90 to 90
+    ADD COLUMN latitude DOUBLE PRECISION,
+
+    -- Longitude in decimal degrees (WGS 84), -180 to 180
+    ADD COLUMN longitude DOUBLE PRECISION,
+
+    ADD CONSTRAINT chk_attendance_events_latitude
+        CHECK (latitude BETWEEN -90 AND 90),
+    ADD CONSTRAINT chk_attendance_events_longitude
+        CHECK (longitude BETWEEN -180 AND 180),
+    ADD CONSTRAINT chk_attendance_events_location_pair
+        CHECK ((latitude IS NULL) = (longitude IS NULL));
+
+-- Add column comments
+COMMENT ON COLUMN attendance_events.latitude IS 'Latitude where the event was recorded (WGS 84 decimal degrees, optional)';
+COMMENT ON COLUMN attendance_events.longitude IS 'Longitude where the event was recorded (WGS 84 decimal degrees, optional)';
@@ -0,0 +1,6 @@
+-- Revert client metadata on attendance_events
+
+ALTER TABLE attendance_events
+    DROP COLUMN IF EXISTS device_id,
+    DROP COLUMN IF EXISTS user_agent,
+    DROP COLUMN IF EXISTS client_ip;
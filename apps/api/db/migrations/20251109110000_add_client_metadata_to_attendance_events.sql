@@ -0,0 +1,20 @@
+-- Add client metadata to attendance_events
+-- The address, user agent and device of the client that submitted an event are
+-- kept for auditing (e.g. spotting clock-ins submitted on behalf of others).
+-- All columns are nullable: events written before this migration, by imports
+-- without the information, or by approved corrections have no client metadata.
+
+ALTER TABLE attendance_events
+    -- Client IP address (IPv4 or IPv6 textual form)
+    ADD COLUMN client_ip VARCHAR(45),
+
+    -- User-Agent header of the request (truncated to 512 characters)
+    ADD COLUMN user_agent VARCHAR(512),
+
+    -- Device identifier sent by kiosks and mobile apps (X-Device-Id header)
+    ADD COLUMN device_id VARCHAR(255);
+
+-- Add column comments
+COMMENT ON COLUMN attendance_events.client_ip IS 'IP address of the client that submitted the event';
+COMMENT ON COLUMN attendance_events.user_agent IS 'User-Agent of the client that submitted the event';
+COMMENT ON COLUMN attendance_events.device_id IS 'Device identifier of the client that submitted the event';
@@ -0,0 +1,2 @@
+-- Revert kiosk_token_uses table creation
+DROP TABLE IF EXISTS kiosk_token_uses;
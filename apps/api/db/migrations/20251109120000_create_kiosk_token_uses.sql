@@ -0,0 +1,31 @@
+-- Create kiosk_token_uses table
+-- Records the id (jti) of every kiosk token that has been used to record an
+-- attendance event, so a scanned QR code cannot be replayed while it is still
+-- valid. Rows past their expiry are no longer needed and can be deleted.
+
+CREATE TABLE kiosk_token_uses (
+    -- Token id (jti claim) of the used token
+    jti UUID PRIMARY KEY,
+
+    -- Expiry of the used token; rows past this time may be purged
+    expires_at TIMESTAMP(6) WITH TIME ZONE NOT NULL,
+
+    -- The attendance event recorded with the token
+    -- ON DELETE CASCADE keeps this table consistent when events are removed with their user
+    event_id UUID NOT NULL REFERENCES attendance_events(id) ON DELETE CASCADE,
+
+    -- When the token was used
+    used_at TIMESTAMP(6) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Add table comment
+COMMENT ON TABLE kiosk_token_uses IS 'Used kiosk clock-in tokens (replay protection)';
+
+-- Add column comments
+COMMENT ON COLUMN kiosk_token_uses.jti IS 'Token id (jti claim) of the used kiosk token';
+COMMENT ON COLUMN kiosk_token_uses.expires_at IS 'Expiry of the used token (rows past this time may be purged)';
+COMMENT ON COLUMN kiosk_token_uses.event_id IS 'Attendance event recorded with the token';
+COMMENT ON COLUMN kiosk_token_uses.used_at IS 'When the token was used';
+
+-- Index for purging expired rows
+CREATE INDEX idx_kiosk_token_uses_expires_at ON kiosk_token_uses(expires_at);
@@ -0,0 +1,2 @@
+-- Revert attendance_anomalies table creation
+DROP TABLE IF EXISTS attendance_anomalies;
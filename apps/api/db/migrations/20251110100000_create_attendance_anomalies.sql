@@ -0,0 +1,42 @@
+-- Create attendance_anomalies table
+-- Irregularities detected in attendance events (e.g. a clock-in that was never
+-- followed by a clock-out) are recorded here for review. Detection never
+-- modifies the events themselves.
+
+CREATE TABLE attendance_anomalies (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- User the anomaly belongs to
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Kind of anomaly: 'missing_clock_out'
+    kind VARCHAR(30) NOT NULL
+        CHECK (kind IN ('missing_clock_out')),
+
+    -- The attendance event that triggered the anomaly
+    event_id UUID NOT NULL REFERENCES attendance_events(id) ON DELETE CASCADE,
+
+    -- Human-readable description of the anomaly
+    details TEXT NOT NULL,
+
+    -- When the anomaly was detected
+    detected_at TIMESTAMP(6) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Each event is reported at most once per kind, so repeated scans are idempotent
+    CONSTRAINT uq_attendance_anomalies_kind_event UNIQUE (kind, event_id)
+);
+
+-- Add table comment
+COMMENT ON TABLE attendance_anomalies IS 'Irregularities detected in attendance events';
+
+-- Add column comments
+COMMENT ON COLUMN attendance_anomalies.id IS 'Unique identifier for the anomaly (UUID)';
+COMMENT ON COLUMN attendance_anomalies.user_id IS 'User the anomaly belongs to';
+COMMENT ON COLUMN attendance_anomalies.kind IS 'Kind of anomaly: missing_clock_out';
+COMMENT ON COLUMN attendance_anomalies.event_id IS 'Attendance event that triggered the anomaly';
+COMMENT ON COLUMN attendance_anomalies.details IS 'Human-readable description of the anomaly';
+COMMENT ON COLUMN attendance_anomalies.detected_at IS 'When the anomaly was detected';
+
+-- Index for listing a user's anomalies, most recent first
+CREATE INDEX idx_attendance_anomalies_user_detected_at ON attendance_anomalies(user_id, detected_at DESC);
@@ -0,0 +1,10 @@
+-- Revert anomaly kinds of the rules engine
+DELETE FROM attendance_anomalies WHERE kind <> 'missing_clock_out';
+
+ALTER TABLE attendance_anomalies DROP CONSTRAINT IF EXISTS chk_attendance_anomalies_kind;
+
+ALTER TABLE attendance_anomalies
+    ADD CONSTRAINT attendance_anomalies_kind_check
+    CHECK (kind IN ('missing_clock_out'));
+
+COMMENT ON COLUMN attendance_anomalies.kind IS 'Kind of anomaly: missing_clock_out';
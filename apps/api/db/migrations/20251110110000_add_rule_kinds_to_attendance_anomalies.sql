@@ -0,0 +1,11 @@
+-- Allow the anomaly kinds reported by the rules engine
+-- Rules run when an event is recorded and report duplicate events, events
+-- ahead of the server clock, and overly long shifts.
+
+ALTER TABLE attendance_anomalies DROP CONSTRAINT attendance_anomalies_kind_check;
+
+ALTER TABLE attendance_anomalies
+    ADD CONSTRAINT chk_attendance_anomalies_kind
+    CHECK (kind IN ('missing_clock_out', 'duplicate_event', 'future_event', 'long_shift'));
+
+COMMENT ON COLUMN attendance_anomalies.kind IS 'Kind of anomaly: missing_clock_out, duplicate_event, future_event, long_shift';
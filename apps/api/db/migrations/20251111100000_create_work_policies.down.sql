@@ -0,0 +1,2 @@
+-- Revert work_policies table creation
+DROP TABLE IF EXISTS work_policies;
@@ -0,0 +1,59 @@
+-- Create work_policies table
+-- Working-hours rules used by the timesheet and overtime calculations.
+-- At most one policy is active at a time; when none is active the
+-- deployment's environment defaults apply.
+
+CREATE TABLE work_policies (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Display name of the policy (e.g. 'Standard', 'Part-time')
+    name VARCHAR(100) NOT NULL,
+
+    -- Standard working time per day; time beyond it is daily overtime
+    standard_daily_minutes INTEGER NOT NULL
+        CHECK (standard_daily_minutes BETWEEN 1 AND 1440),
+
+    -- Regular working time per week; regular time beyond it is weekly overtime
+    weekly_threshold_minutes INTEGER NOT NULL
+        CHECK (weekly_threshold_minutes BETWEEN 1 AND 10080),
+
+    -- First day of the work week: 'monday' .. 'sunday'
+    week_start VARCHAR(10) NOT NULL DEFAULT 'monday'
+        CHECK (week_start IN ('monday', 'tuesday', 'wednesday', 'thursday', 'friday', 'saturday', 'sunday')),
+
+    -- How daily worked time is rounded: 'none', 'down', 'up' or 'nearest'
+    rounding_mode VARCHAR(10) NOT NULL DEFAULT 'none'
+        CHECK (rounding_mode IN ('none', 'down', 'up', 'nearest')),
+
+    -- Rounding unit in minutes (e.g. 15 for quarter hours)
+    rounding_minutes INTEGER NOT NULL DEFAULT 1
+        CHECK (rounding_minutes BETWEEN 1 AND 60),
+
+    -- Whether this policy is the one in effect
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Timestamp when the policy was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Timestamp when the policy was last updated
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Add table comment
+COMMENT ON TABLE work_policies IS 'Working-hours rules for timesheet and overtime calculations';
+
+-- Add column comments
+COMMENT ON COLUMN work_policies.id IS 'Unique identifier for the policy (UUID)';
+COMMENT ON COLUMN work_policies.name IS 'Display name of the policy';
+COMMENT ON COLUMN work_policies.standard_daily_minutes IS 'Standard working minutes per day';
+COMMENT ON COLUMN work_policies.weekly_threshold_minutes IS 'Regular working minutes per week';
+COMMENT ON COLUMN work_policies.week_start IS 'First day of the work week: monday .. sunday';
+COMMENT ON COLUMN work_policies.rounding_mode IS 'Rounding of daily worked time: none, down, up, nearest';
+COMMENT ON COLUMN work_policies.rounding_minutes IS 'Rounding unit in minutes';
+COMMENT ON COLUMN work_policies.is_active IS 'Whether this policy is the one in effect';
+COMMENT ON COLUMN work_policies.created_at IS 'Timestamp when the policy was created';
+COMMENT ON COLUMN work_policies.updated_at IS 'Timestamp when the policy was last updated';
+
+-- Partial unique index: at most one policy can be active
+CREATE UNIQUE INDEX idx_work_policies_active ON work_policies(is_active) WHERE is_active;
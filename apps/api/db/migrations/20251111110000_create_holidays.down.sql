@@ -0,0 +1,2 @@
+-- Revert holidays table creation
+DROP TABLE IF EXISTS holidays;
@@ -0,0 +1,33 @@
+-- Create holidays table
+-- Company-wide non-working days. Work on a holiday counts entirely as
+-- overtime in the timesheet and overtime calculations.
+
+CREATE TABLE holidays (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Calendar day of the holiday (business timezone)
+    date DATE NOT NULL,
+
+    -- Display name of the holiday (e.g. 'Culture Day')
+    name VARCHAR(100) NOT NULL,
+
+    -- Timestamp when the holiday was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Timestamp when the holiday was last updated
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- At most one holiday per day
+    CONSTRAINT uq_holidays_date UNIQUE (date)
+);
+
+-- Add table comment
+COMMENT ON TABLE holidays IS 'Company-wide non-working days';
+
+-- Add column comments
+COMMENT ON COLUMN holidays.id IS 'Unique identifier for the holiday (UUID)';
+COMMENT ON COLUMN holidays.date IS 'Calendar day of the holiday';
+COMMENT ON COLUMN holidays.name IS 'Display name of the holiday';
+COMMENT ON COLUMN holidays.created_at IS 'Timestamp when the holiday was created';
+COMMENT ON COLUMN holidays.updated_at IS 'Timestamp when the holiday was last updated';
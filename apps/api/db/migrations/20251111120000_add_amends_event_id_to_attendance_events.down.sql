@@ -0,0 +1,5 @@
+-- Revert amends_event_id addition to attendance_events
+ALTER TABLE attendance_events
+    DROP CONSTRAINT IF EXISTS uq_attendance_events_amends_event_id,
+    DROP CONSTRAINT IF EXISTS chk_attendance_events_amends_other,
+    DROP COLUMN IF EXISTS amends_event_id;
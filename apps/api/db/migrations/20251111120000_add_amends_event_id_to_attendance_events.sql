@@ -0,0 +1,18 @@
+-- Add amends_event_id to attendance_events
+-- Events stay immutable: a mistaken event is superseded by a new event that
+-- amends it. Following amends_event_id links gives the full history of an
+-- event; the effective event is the one no other event amends.
+
+ALTER TABLE attendance_events
+    -- Event superseded by this one (NULL for original events)
+    ADD COLUMN amends_event_id UUID
+        CONSTRAINT fk_attendance_events_amends_event_id REFERENCES attendance_events(id),
+
+    -- An event cannot amend itself
+    ADD CONSTRAINT chk_attendance_events_amends_other CHECK (amends_event_id <> id),
+
+    -- Each event is superseded at most once, so amendments form a chain
+    ADD CONSTRAINT uq_attendance_events_amends_event_id UNIQUE (amends_event_id);
+
+-- Add column comment
+COMMENT ON COLUMN attendance_events.amends_event_id IS 'Event superseded by this one (NULL for original events)';
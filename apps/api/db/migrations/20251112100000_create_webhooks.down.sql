@@ -0,0 +1,3 @@
+-- Revert webhooks and webhook_deliveries table creation
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TABLE IF EXISTS webhooks;
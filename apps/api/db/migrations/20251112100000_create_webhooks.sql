@@ -0,0 +1,101 @@
+-- Create webhooks and webhook_deliveries tables
+-- Subscribers are notified with a signed JSON POST when attendance events are
+-- created. Every notification is logged as a delivery and retried with backoff
+-- until it succeeds or runs out of attempts.
+
+CREATE TABLE webhooks (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Subscriber endpoint notifications are POSTed to
+    url VARCHAR(2048) NOT NULL,
+
+    -- Shared secret used to sign payloads (HMAC-SHA256)
+    secret VARCHAR(255) NOT NULL,
+
+    -- Free-form note about the subscriber
+    description VARCHAR(255),
+
+    -- Inactive webhooks receive no new deliveries
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Timestamp when the webhook was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Timestamp when the webhook was last updated
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_deliveries (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Webhook the payload is delivered to; deliveries go with their webhook
+    webhook_id UUID NOT NULL,
+
+    -- Notification type (e.g. 'attendance_event.created')
+    event VARCHAR(50) NOT NULL,
+
+    -- JSON body exactly as signed and sent
+    payload TEXT NOT NULL,
+
+    -- Delivery state: 'pending', 'succeeded' or 'failed'
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+
+    -- Number of attempts made so far
+    attempts INTEGER NOT NULL DEFAULT 0,
+
+    -- HTTP status of the last attempt (NULL if no response was received)
+    last_status_code INTEGER,
+
+    -- Error of the last failed attempt
+    last_error TEXT,
+
+    -- When a pending delivery is due (next attempt or lease expiry)
+    next_attempt_at TIMESTAMP WITH TIME ZONE,
+
+    -- Timestamp when the delivery was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Timestamp when the delivery succeeded
+    delivered_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT fk_webhook_deliveries_webhook_id
+        FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE,
+
+    CONSTRAINT chk_webhook_deliveries_status
+        CHECK (status IN ('pending', 'succeeded', 'failed'))
+);
+
+-- Delivery log of a webhook, most recent first
+CREATE INDEX idx_webhook_deliveries_webhook_id_created_at
+    ON webhook_deliveries (webhook_id, created_at DESC);
+
+-- Due pending deliveries picked up by the retry job
+CREATE INDEX idx_webhook_deliveries_due
+    ON webhook_deliveries (next_attempt_at)
+    WHERE status = 'pending';
+
+-- Add table comments
+COMMENT ON TABLE webhooks IS 'Subscribers notified of attendance events';
+COMMENT ON TABLE webhook_deliveries IS 'Delivery log of webhook notifications';
+
+-- Add column comments
+COMMENT ON COLUMN webhooks.id IS 'Unique identifier for the webhook (UUID)';
+COMMENT ON COLUMN webhooks.url IS 'Subscriber endpoint notifications are POSTed to';
+COMMENT ON COLUMN webhooks.secret IS 'Shared secret used to sign payloads (HMAC-SHA256)';
+COMMENT ON COLUMN webhooks.description IS 'Free-form note about the subscriber';
+COMMENT ON COLUMN webhooks.is_active IS 'Whether new notifications are delivered';
+COMMENT ON COLUMN webhooks.created_at IS 'Timestamp when the webhook was created';
+COMMENT ON COLUMN webhooks.updated_at IS 'Timestamp when the webhook was last updated';
+COMMENT ON COLUMN webhook_deliveries.id IS 'Unique identifier for the delivery (UUID)';
+COMMENT ON COLUMN webhook_deliveries.webhook_id IS 'Webhook the payload is delivered to';
+COMMENT ON COLUMN webhook_deliveries.event IS 'Notification type';
+COMMENT ON COLUMN webhook_deliveries.payload IS 'JSON body exactly as signed and sent';
+COMMENT ON COLUMN webhook_deliveries.status IS 'Delivery state: pending, succeeded or failed';
+COMMENT ON COLUMN webhook_deliveries.attempts IS 'Number of attempts made so far';
+COMMENT ON COLUMN webhook_deliveries.last_status_code IS 'HTTP status of the last attempt';
+COMMENT ON COLUMN webhook_deliveries.last_error IS 'Error of the last failed attempt';
+COMMENT ON COLUMN webhook_deliveries.next_attempt_at IS 'When a pending delivery is due';
+COMMENT ON COLUMN webhook_deliveries.created_at IS 'Timestamp when the delivery was created';
+COMMENT ON COLUMN webhook_deliveries.delivered_at IS 'Timestamp when the delivery succeeded';
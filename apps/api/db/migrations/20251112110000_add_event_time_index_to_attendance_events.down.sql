@@ -0,0 +1,2 @@
+-- Revert attendance_events.event_time index
+DROP INDEX IF EXISTS idx_attendance_events_event_time;
@@ -0,0 +1,5 @@
+-- Add an index on attendance_events.event_time
+-- Lets exports across all users read events in time order from the index
+-- instead of sorting the whole table.
+
+CREATE INDEX idx_attendance_events_event_time ON attendance_events(event_time);
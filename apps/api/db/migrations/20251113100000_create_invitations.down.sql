@@ -0,0 +1,2 @@
+-- Revert invitations table creation
+DROP TABLE IF EXISTS invitations;
@@ -0,0 +1,50 @@
+-- Create invitations table
+-- An administrator invites a person by email with the role they will have.
+-- The invited person accepts with the token sent to them, completing their
+-- profile; this creates the user. Only a SHA-256 hash of the token is stored.
+
+CREATE TABLE invitations (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Email address the invitation was sent to; becomes the user's email
+    email VARCHAR(255) NOT NULL,
+
+    -- Role the user is given: 'admin', 'manager' or 'member'
+    role VARCHAR(20) NOT NULL,
+
+    -- Lowercase hex SHA-256 of the invitation token
+    token_hash VARCHAR(64) NOT NULL,
+
+    -- The invitation cannot be accepted after this time
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- When the invitation was accepted (NULL = pending)
+    accepted_at TIMESTAMP WITH TIME ZONE,
+
+    -- User created by accepting the invitation
+    -- ON DELETE SET NULL keeps the invitation when the user is purged
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+
+    -- Timestamp when the invitation was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT uq_invitations_token_hash UNIQUE (token_hash),
+    CONSTRAINT chk_invitations_role CHECK (role IN ('admin', 'manager', 'member'))
+);
+
+-- Add table comment
+COMMENT ON TABLE invitations IS 'Invitations for new users to join';
+
+-- Add column comments
+COMMENT ON COLUMN invitations.id IS 'Unique identifier for the invitation (UUID)';
+COMMENT ON COLUMN invitations.email IS 'Email address of the invited person';
+COMMENT ON COLUMN invitations.role IS 'Role given to the user (admin, manager, member)';
+COMMENT ON COLUMN invitations.token_hash IS 'SHA-256 hash of the invitation token (hex)';
+COMMENT ON COLUMN invitations.expires_at IS 'Time after which the invitation cannot be accepted';
+COMMENT ON COLUMN invitations.accepted_at IS 'Time the invitation was accepted (NULL = pending)';
+COMMENT ON COLUMN invitations.user_id IS 'User created by accepting the invitation';
+COMMENT ON COLUMN invitations.created_at IS 'Timestamp when the invitation was created';
+
+-- At most one pending invitation per email; a new invitation replaces it
+CREATE UNIQUE INDEX idx_invitations_pending_email ON invitations(email) WHERE accepted_at IS NULL;
@@ -0,0 +1,4 @@
+-- Revert password-based authentication on users
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS password_hash;
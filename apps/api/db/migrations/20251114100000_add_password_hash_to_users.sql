@@ -0,0 +1,10 @@
+-- Add password-based authentication to users
+-- Users who register with a password can log in; users created by other means
+-- (admin API, invitations, imports) have no password until one is set.
+
+ALTER TABLE users
+    -- Argon2id hash in PHC string format (NULL = no password set)
+    ADD COLUMN password_hash TEXT;
+
+-- Add column comment
+COMMENT ON COLUMN users.password_hash IS 'Argon2id password hash in PHC string format (NULL = password login disabled)';
@@ -0,0 +1,2 @@
+-- Revert password_reset_tokens table creation
+DROP TABLE IF EXISTS password_reset_tokens;
@@ -0,0 +1,41 @@
+-- Create password_reset_tokens table
+-- A user who forgot their password requests a reset token by email and sets a
+-- new password with it. Tokens are single-use and expire; only a SHA-256 hash
+-- of each token is stored.
+
+CREATE TABLE password_reset_tokens (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- User whose password the token resets
+    -- ON DELETE CASCADE removes the tokens with the user
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Lowercase hex SHA-256 of the token
+    token_hash VARCHAR(64) NOT NULL,
+
+    -- The token cannot be used after this time
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- When the token was used, or superseded by another reset (NULL = unused)
+    used_at TIMESTAMP WITH TIME ZONE,
+
+    -- Timestamp when the token was issued; also used for rate limiting
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT uq_password_reset_tokens_token_hash UNIQUE (token_hash)
+);
+
+-- Add table comment
+COMMENT ON TABLE password_reset_tokens IS 'Single-use tokens for resetting forgotten passwords';
+
+-- Add column comments
+COMMENT ON COLUMN password_reset_tokens.id IS 'Unique identifier for the token (UUID)';
+COMMENT ON COLUMN password_reset_tokens.user_id IS 'User whose password the token resets';
+COMMENT ON COLUMN password_reset_tokens.token_hash IS 'SHA-256 hash of the token (hex)';
+COMMENT ON COLUMN password_reset_tokens.expires_at IS 'Time after which the token cannot be used';
+COMMENT ON COLUMN password_reset_tokens.used_at IS 'Time the token was used or superseded (NULL = unused)';
+COMMENT ON COLUMN password_reset_tokens.created_at IS 'Timestamp when the token was issued';
+
+-- Counting recent tokens per user for rate limiting
+CREATE INDEX idx_password_reset_tokens_user_created ON password_reset_tokens(user_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- Revert roles on users
+
+ALTER TABLE users
+    DROP CONSTRAINT IF EXISTS chk_users_role,
+    DROP COLUMN IF EXISTS role;
@@ -0,0 +1,12 @@
+-- Add roles to users
+-- Admins manage users and all attendance records, managers read all attendance
+-- records, and members read only their own. Existing users become members.
+
+ALTER TABLE users
+    -- Role of the user: 'admin', 'manager' or 'member'
+    ADD COLUMN role VARCHAR(20) NOT NULL DEFAULT 'member',
+
+    ADD CONSTRAINT chk_users_role CHECK (role IN ('admin', 'manager', 'member'));
+
+-- Add column comment
+COMMENT ON COLUMN users.role IS 'Role of the user (admin, manager, member)';
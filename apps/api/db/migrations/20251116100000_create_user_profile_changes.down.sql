@@ -0,0 +1,2 @@
+-- Revert user_profile_changes table creation
+DROP TABLE IF EXISTS user_profile_changes;
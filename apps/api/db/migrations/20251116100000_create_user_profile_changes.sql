@@ -0,0 +1,38 @@
+-- Create user_profile_changes table
+-- Every change to a user's name, email, picture or role is recorded with the
+-- old and new value, so the user's activity timeline can show their profile
+-- history next to their attendance events.
+
+CREATE TABLE user_profile_changes (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- User whose profile changed
+    -- ON DELETE CASCADE removes the history with the user
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Changed field: 'name', 'email', 'picture' or 'role'
+    field VARCHAR(20) NOT NULL
+        CHECK (field IN ('name', 'email', 'picture', 'role')),
+
+    -- Value before and after the change (NULL = no value, e.g. no picture)
+    old_value TEXT,
+    new_value TEXT,
+
+    -- Timestamp when the change was made
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Add table comment
+COMMENT ON TABLE user_profile_changes IS 'History of changes to user profiles';
+
+-- Add column comments
+COMMENT ON COLUMN user_profile_changes.id IS 'Unique identifier for the change (UUID)';
+COMMENT ON COLUMN user_profile_changes.user_id IS 'User whose profile changed';
+COMMENT ON COLUMN user_profile_changes.field IS 'Changed field: name, email, picture, role';
+COMMENT ON COLUMN user_profile_changes.old_value IS 'Value before the change (NULL = no value)';
+COMMENT ON COLUMN user_profile_changes.new_value IS 'Value after the change (NULL = no value)';
+COMMENT ON COLUMN user_profile_changes.changed_at IS 'Timestamp when the change was made';
+
+-- Index for a user's history, most recent first
+CREATE INDEX idx_user_profile_changes_user_changed_at ON user_profile_changes(user_id, changed_at DESC);
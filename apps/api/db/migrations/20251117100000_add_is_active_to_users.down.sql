@@ -0,0 +1,13 @@
+-- Revert deactivation of users
+
+DELETE FROM user_profile_changes WHERE field = 'is_active';
+
+ALTER TABLE user_profile_changes
+    DROP CONSTRAINT user_profile_changes_field_check,
+    ADD CONSTRAINT user_profile_changes_field_check
+        CHECK (field IN ('name', 'email', 'picture', 'role'));
+
+COMMENT ON COLUMN user_profile_changes.field IS 'Changed field: name, email, picture, role';
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS is_active;
@@ -0,0 +1,19 @@
+-- Add deactivation to users
+-- A deactivated user keeps their data and still appears in listings, but can
+-- neither authenticate nor record attendance. Unlike deletion, deactivation is
+-- meant for temporary absences (e.g. leave) and is undone by activating the user.
+
+ALTER TABLE users
+    -- FALSE = deactivated
+    ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Add column comment
+COMMENT ON COLUMN users.is_active IS 'Whether the user can authenticate and record attendance (FALSE = deactivated)';
+
+-- Deactivation and activation appear in the profile history
+ALTER TABLE user_profile_changes
+    DROP CONSTRAINT user_profile_changes_field_check,
+    ADD CONSTRAINT user_profile_changes_field_check
+        CHECK (field IN ('name', 'email', 'picture', 'role', 'is_active'));
+
+COMMENT ON COLUMN user_profile_changes.field IS 'Changed field: name, email, picture, role, is_active';
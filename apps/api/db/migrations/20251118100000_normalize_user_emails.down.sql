@@ -0,0 +1,11 @@
+-- Revert to case-sensitive email indexes
+-- Email addresses stay normalized.
+
+DROP INDEX idx_invitations_pending_email;
+CREATE UNIQUE INDEX idx_invitations_pending_email ON invitations(email) WHERE accepted_at IS NULL;
+
+DROP INDEX idx_users_email;
+CREATE INDEX idx_users_email ON users(email) WHERE deleted_at IS NULL;
+
+DROP INDEX idx_users_active_email;
+CREATE UNIQUE INDEX idx_users_active_email ON users(email) WHERE deleted_at IS NULL;
@@ -0,0 +1,39 @@
+-- Compare email addresses case-insensitively
+-- The application now stores email addresses trimmed, lowercased and in
+-- Unicode NFC, and looks them up by lower(email), so "Foo@Bar.com" and
+-- "foo@bar.com" are the same account. Existing addresses are normalized the
+-- same way and the indexes move to lower(email).
+--
+-- Creating the unique indexes fails if two active users (or two pending
+-- invitations) have addresses differing only in case, surrounding whitespace
+-- or Unicode form; merge or delete one of them and run the migration again.
+
+DROP INDEX idx_users_active_email;
+DROP INDEX idx_users_email;
+DROP INDEX idx_invitations_pending_email;
+
+-- Unicode normalization needs a UTF8 database; with any other encoding
+-- addresses are only trimmed and lowercased
+DO $$
+BEGIN
+    IF current_setting('server_encoding') = 'UTF8' THEN
+        UPDATE users SET email = normalize(lower(btrim(email, E' \t\r\n\f')), NFC)
+        WHERE email <> normalize(lower(btrim(email, E' \t\r\n\f')), NFC);
+        UPDATE invitations SET email = normalize(lower(btrim(email, E' \t\r\n\f')), NFC)
+        WHERE email <> normalize(lower(btrim(email, E' \t\r\n\f')), NFC);
+    ELSE
+        UPDATE users SET email = lower(btrim(email, E' \t\r\n\f'))
+        WHERE email <> lower(btrim(email, E' \t\r\n\f'));
+        UPDATE invitations SET email = lower(btrim(email, E' \t\r\n\f'))
+        WHERE email <> lower(btrim(email, E' \t\r\n\f'));
+    END IF;
+END $$;
+
+-- Email must be unique among active users, ignoring case
+CREATE UNIQUE INDEX idx_users_active_email ON users(lower(email)) WHERE deleted_at IS NULL;
+
+-- Lookups by email, including deleted users
+CREATE INDEX idx_users_email ON users(lower(email));
+
+-- At most one pending invitation per email, ignoring case
+CREATE UNIQUE INDEX idx_invitations_pending_email ON invitations(lower(email)) WHERE accepted_at IS NULL;
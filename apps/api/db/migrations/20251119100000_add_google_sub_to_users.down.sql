@@ -0,0 +1,6 @@
+-- Revert Google sign-in on users
+
+DROP INDEX IF EXISTS idx_users_active_google_sub;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS google_sub;
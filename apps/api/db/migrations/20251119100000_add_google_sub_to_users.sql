@@ -0,0 +1,15 @@
+-- Add Google sign-in to users
+-- A user signing in with Google is linked to their Google account by its
+-- subject identifier, which, unlike the email address, never changes. Users are
+-- linked on their first Google sign-in, found by verified email address or
+-- created.
+
+ALTER TABLE users
+    -- Google account subject (`sub` claim); NULL = not linked
+    ADD COLUMN google_sub VARCHAR(255);
+
+-- A Google account is linked to at most one active user
+CREATE UNIQUE INDEX idx_users_active_google_sub ON users(google_sub) WHERE deleted_at IS NULL;
+
+-- Add column comment
+COMMENT ON COLUMN users.google_sub IS 'Subject identifier of the linked Google account (NULL = not linked)';
@@ -0,0 +1,2 @@
+-- Revert sessions table creation
+DROP TABLE IF EXISTS sessions;
@@ -0,0 +1,47 @@
+-- Create sessions table
+-- Browsers can log in with a session cookie instead of a bearer token. The
+-- cookie holds an opaque token; only a SHA-256 hash of it is stored. Sessions
+-- expire after a period of inactivity: every use pushes expires_at forward.
+
+CREATE TABLE sessions (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Logged-in user
+    -- ON DELETE CASCADE removes the sessions with the user
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Lowercase hex SHA-256 of the cookie token
+    token_hash VARCHAR(64) NOT NULL,
+
+    -- Client IP address and User-Agent at login (for listing and auditing)
+    client_ip VARCHAR(45),
+    user_agent VARCHAR(512),
+
+    -- Timestamp when the user logged in
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Last time the session was used (updated at most once a minute)
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- The session cannot be used after this time
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    CONSTRAINT uq_sessions_token_hash UNIQUE (token_hash)
+);
+
+-- Add table comment
+COMMENT ON TABLE sessions IS 'Cookie sessions of logged-in users';
+
+-- Add column comments
+COMMENT ON COLUMN sessions.id IS 'Unique identifier for the session (UUID)';
+COMMENT ON COLUMN sessions.user_id IS 'Logged-in user';
+COMMENT ON COLUMN sessions.token_hash IS 'SHA-256 hash of the session cookie token (hex)';
+COMMENT ON COLUMN sessions.client_ip IS 'IP address of the client that logged in';
+COMMENT ON COLUMN sessions.user_agent IS 'User-Agent of the client that logged in';
+COMMENT ON COLUMN sessions.created_at IS 'Timestamp when the user logged in';
+COMMENT ON COLUMN sessions.last_seen_at IS 'Last time the session was used';
+COMMENT ON COLUMN sessions.expires_at IS 'Time after which the session cannot be used';
+
+-- Removing the expired sessions of a user at login
+CREATE INDEX idx_sessions_user_expires ON sessions(user_id, expires_at);
@@ -0,0 +1,2 @@
+-- Revert api_keys table creation
+DROP TABLE IF EXISTS api_keys;
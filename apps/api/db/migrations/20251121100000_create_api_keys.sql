@@ -0,0 +1,50 @@
+-- Create api_keys table
+-- Machine-to-machine clients (kiosk devices, payroll integrations) authenticate
+-- with an API key in the X-Api-Key header instead of a user login. Each key is
+-- limited to the scopes it was created with. Only a SHA-256 hash of each key is
+-- stored; the key itself is shown once, when it is created.
+
+CREATE TABLE api_keys (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Name identifying the client (e.g. "Front door kiosk")
+    name VARCHAR(100) NOT NULL,
+
+    -- First characters of the key, so administrators can tell keys apart
+    key_prefix VARCHAR(16) NOT NULL,
+
+    -- Lowercase hex SHA-256 of the key
+    key_hash VARCHAR(64) NOT NULL,
+
+    -- Scopes granted to the key (e.g. kiosk:clock, payroll:read)
+    scopes VARCHAR(50)[] NOT NULL,
+
+    -- The key cannot be used after this time (NULL = does not expire)
+    expires_at TIMESTAMP WITH TIME ZONE,
+
+    -- When the key was revoked (NULL = not revoked)
+    revoked_at TIMESTAMP WITH TIME ZONE,
+
+    -- Last time the key authenticated a request (NULL = never used)
+    last_used_at TIMESTAMP WITH TIME ZONE,
+
+    -- Timestamp when the key was created
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT uq_api_keys_key_hash UNIQUE (key_hash)
+);
+
+-- Add table comment
+COMMENT ON TABLE api_keys IS 'Keys of machine-to-machine API clients';
+
+-- Add column comments
+COMMENT ON COLUMN api_keys.id IS 'Unique identifier for the key (UUID)';
+COMMENT ON COLUMN api_keys.name IS 'Name identifying the client';
+COMMENT ON COLUMN api_keys.key_prefix IS 'First characters of the key, for display';
+COMMENT ON COLUMN api_keys.key_hash IS 'SHA-256 hash of the key (hex)';
+COMMENT ON COLUMN api_keys.scopes IS 'Scopes granted to the key';
+COMMENT ON COLUMN api_keys.expires_at IS 'Time after which the key cannot be used (NULL = does not expire)';
+COMMENT ON COLUMN api_keys.revoked_at IS 'Time the key was revoked (NULL = not revoked)';
+COMMENT ON COLUMN api_keys.last_used_at IS 'Last time the key authenticated a request';
+COMMENT ON COLUMN api_keys.created_at IS 'Timestamp when the key was created';
@@ -0,0 +1,2 @@
+-- Revert role_permissions table creation
+DROP TABLE IF EXISTS role_permissions;
@@ -0,0 +1,39 @@
+-- Create role_permissions table
+-- On top of roles, routes require permissions (e.g. users:write). Each role is
+-- granted a set of permissions, which administrators can change. The defaults
+-- below keep the access each role had before permissions were introduced.
+
+CREATE TABLE role_permissions (
+    -- Role granted the permission (admin, manager, member)
+    role VARCHAR(20) NOT NULL,
+
+    -- Permission granted (e.g. users:read)
+    permission VARCHAR(50) NOT NULL,
+
+    -- Timestamp when the permission was granted
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (role, permission),
+    CONSTRAINT chk_role_permissions_role CHECK (role IN ('admin', 'manager', 'member'))
+);
+
+-- Add table comment
+COMMENT ON TABLE role_permissions IS 'Permissions granted to each role';
+
+-- Add column comments
+COMMENT ON COLUMN role_permissions.role IS 'Role granted the permission';
+COMMENT ON COLUMN role_permissions.permission IS 'Permission granted to the role';
+COMMENT ON COLUMN role_permissions.created_at IS 'Timestamp when the permission was granted';
+
+-- Default permissions
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'todos:read'),
+    ('admin', 'todos:write'),
+    ('admin', 'users:read'),
+    ('admin', 'users:write'),
+    ('manager', 'todos:read'),
+    ('manager', 'todos:write'),
+    ('manager', 'users:read'),
+    ('member', 'todos:read'),
+    ('member', 'todos:write'),
+    ('member', 'users:read');
@@ -0,0 +1,6 @@
+-- Revert login lockout on users
+
+ALTER TABLE users
+    DROP CONSTRAINT IF EXISTS chk_users_failed_login_attempts,
+    DROP COLUMN IF EXISTS locked_until,
+    DROP COLUMN IF EXISTS failed_login_attempts;
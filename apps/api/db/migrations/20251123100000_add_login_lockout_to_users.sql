@@ -0,0 +1,17 @@
+-- Add login lockout to users
+-- Failed logins are counted per account. After too many in a row, the account
+-- is locked for a cooldown (see LOGIN_LOCKOUT_MAX_ATTEMPTS), which slows down
+-- password guessing spread across many addresses.
+
+ALTER TABLE users
+    -- Failed logins since the last successful one or the last lock
+    ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+
+    -- End of the current lock (NULL or past = not locked)
+    ADD COLUMN locked_until TIMESTAMP WITH TIME ZONE,
+
+    ADD CONSTRAINT chk_users_failed_login_attempts CHECK (failed_login_attempts >= 0);
+
+-- Add column comments
+COMMENT ON COLUMN users.failed_login_attempts IS 'Failed logins since the last successful login or lock';
+COMMENT ON COLUMN users.locked_until IS 'Time until which logins are rejected after repeated failures';
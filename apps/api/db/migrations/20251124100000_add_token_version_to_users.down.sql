@@ -0,0 +1,4 @@
+-- Revert token_version on users
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS token_version;
@@ -0,0 +1,10 @@
+-- Add token_version to users
+-- Access tokens carry the token version of their user at issue time. Logging
+-- out everywhere increments it, so every token issued before is rejected.
+
+ALTER TABLE users
+    -- Incremented to revoke every access token of the user
+    ADD COLUMN token_version INTEGER NOT NULL DEFAULT 0;
+
+-- Add column comments
+COMMENT ON COLUMN users.token_version IS 'Version of the access tokens of the user; incremented to revoke them all';
@@ -0,0 +1,2 @@
+-- Revert idempotency_keys table creation
+DROP TABLE IF EXISTS idempotency_keys;
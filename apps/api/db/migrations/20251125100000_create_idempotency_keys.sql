@@ -0,0 +1,55 @@
+-- Create idempotency_keys table
+-- A POST request with an Idempotency-Key header is answered once; retries
+-- with the same key get the stored response instead of repeating the request.
+-- Keys are scoped to the credentials of the request, so clients cannot replay
+-- each other's responses.
+
+CREATE TABLE idempotency_keys (
+    -- Primary key: UUID generated automatically
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- Lowercase hex SHA-256 of the credentials the request was sent with
+    scope_hash VARCHAR(64) NOT NULL,
+
+    -- Idempotency-Key header chosen by the client
+    idempotency_key VARCHAR(255) NOT NULL,
+
+    -- Lowercase hex SHA-256 of the method, URI and body of the request
+    -- A retry with the same key but another request is rejected
+    request_hash VARCHAR(64) NOT NULL,
+
+    -- Stored response; NULL while the first request is being processed
+    status_code SMALLINT,
+    header_names TEXT[],
+    header_values TEXT[],
+    body BYTEA,
+
+    -- Timestamp when the first request was received
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- The key can be reused for a new request after this time
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    CONSTRAINT uq_idempotency_keys_scope_key UNIQUE (scope_hash, idempotency_key),
+    CONSTRAINT chk_idempotency_keys_headers CHECK (
+        cardinality(header_names) = cardinality(header_values)
+    )
+);
+
+-- Add table comment
+COMMENT ON TABLE idempotency_keys IS 'Responses stored for Idempotency-Key retries';
+
+-- Add column comments
+COMMENT ON COLUMN idempotency_keys.id IS 'Unique identifier for the stored response (UUID)';
+COMMENT ON COLUMN idempotency_keys.scope_hash IS 'SHA-256 hash of the request credentials (hex)';
+COMMENT ON COLUMN idempotency_keys.idempotency_key IS 'Idempotency-Key header of the request';
+COMMENT ON COLUMN idempotency_keys.request_hash IS 'SHA-256 hash of the method, URI and body (hex)';
+COMMENT ON COLUMN idempotency_keys.status_code IS 'Status of the stored response (NULL while processing)';
+COMMENT ON COLUMN idempotency_keys.header_names IS 'Header names of the stored response';
+COMMENT ON COLUMN idempotency_keys.header_values IS 'Header values of the stored response';
+COMMENT ON COLUMN idempotency_keys.body IS 'Body of the stored response';
+COMMENT ON COLUMN idempotency_keys.created_at IS 'Timestamp when the first request was received';
+COMMENT ON COLUMN idempotency_keys.expires_at IS 'Time after which the key can be reused';
+
+-- Removing expired keys
+CREATE INDEX idx_idempotency_keys_expires ON idempotency_keys(expires_at);
@@ -0,0 +1,3 @@
+-- Revert the attendance permissions
+DELETE FROM role_permissions
+WHERE permission IN ('attendance:read', 'attendance:write', 'corrections:approve', 'policies:write');
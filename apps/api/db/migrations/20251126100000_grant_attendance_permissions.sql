@@ -0,0 +1,17 @@
+-- Grant the attendance permissions
+-- Attendance, correction, holiday and work policy routes require permissions
+-- like the todo and user routes. The defaults keep the access each role had:
+-- every role reads and records attendance, managers and admins decide on
+-- corrections, and admins change holidays and work policies.
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'attendance:read'),
+    ('admin', 'attendance:write'),
+    ('admin', 'corrections:approve'),
+    ('admin', 'policies:write'),
+    ('manager', 'attendance:read'),
+    ('manager', 'attendance:write'),
+    ('manager', 'corrections:approve'),
+    ('member', 'attendance:read'),
+    ('member', 'attendance:write')
+ON CONFLICT (role, permission) DO NOTHING;
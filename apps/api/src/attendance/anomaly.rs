@@ -0,0 +1,371 @@
+//! Anomaly rules
+//!
+//! Each rule inspects a newly recorded event together with the user's nearby
+//! events and reports at most one anomaly. Rules are independent of each other
+//! and of the database, so each can be tested in isolation.
+
+use crate::models::{AnomalyKind, AttendanceEvent, EventType};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+/// Default window in which a second event of the same type is a duplicate (1 minute)
+const DEFAULT_DUPLICATE_WINDOW_SECONDS: i64 = 60;
+
+/// Default tolerance for event times ahead of the server clock (5 minutes)
+const DEFAULT_FUTURE_TOLERANCE_MINUTES: i64 = 5;
+
+/// Default longest plausible shift (16 hours)
+const DEFAULT_MAX_SHIFT_HOURS: i64 = 16;
+
+/// The event under inspection and its surroundings
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    /// The newly recorded event
+    pub event: &'a AttendanceEvent,
+    /// The user's events around `event` (may include `event` itself)
+    pub history: &'a [AttendanceEvent],
+}
+
+/// An anomaly reported by a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedAnomaly {
+    pub kind: AnomalyKind,
+    pub details: String,
+}
+
+/// A single anomaly detection rule
+pub trait AnomalyRule: Send + Sync {
+    /// Kind of anomaly the rule reports
+    fn kind(&self) -> AnomalyKind;
+
+    /// How far before the event the rule needs to see the user's history
+    fn lookback(&self) -> Duration {
+        Duration::zero()
+    }
+
+    /// Inspect the event; returns a description if it is anomalous
+    fn check(&self, ctx: &RuleContext<'_>) -> Option<String>;
+}
+
+/// Flags an event recorded shortly after (or before) another event of the same type
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicateEventRule {
+    pub window: Duration,
+}
+
+impl AnomalyRule for DuplicateEventRule {
+    fn kind(&self) -> AnomalyKind {
+        AnomalyKind::DuplicateEvent
+    }
+
+    fn lookback(&self) -> Duration {
+        self.window
+    }
+
+    fn check(&self, ctx: &RuleContext<'_>) -> Option<String> {
+        let event = ctx.event;
+        ctx.history
+            .iter()
+            .find(|other| {
+                other.id != event.id
+                    && other.event_type == event.event_type
+                    && (other.event_time - event.event_time).abs() <= self.window
+            })
+            .map(|other| {
+                format!(
+                    "{} at {} duplicates event {} at {}",
+                    event.event_type,
+                    rfc3339(event.event_time),
+                    other.id,
+                    rfc3339(other.event_time)
+                )
+            })
+    }
+}
+
+/// Flags an event whose time is ahead of the server time it was recorded at
+#[derive(Debug, Clone, Copy)]
+pub struct FutureEventRule {
+    pub tolerance: Duration,
+}
+
+impl AnomalyRule for FutureEventRule {
+    fn kind(&self) -> AnomalyKind {
+        AnomalyKind::FutureEvent
+    }
+
+    fn check(&self, ctx: &RuleContext<'_>) -> Option<String> {
+        let event = ctx.event;
+        (event.event_time - event.recorded_at > self.tolerance).then(|| {
+            format!(
+                "{} at {} was recorded earlier, at {}",
+                event.event_type,
+                rfc3339(event.event_time),
+                rfc3339(event.recorded_at)
+            )
+        })
+    }
+}
+
+/// Flags a clock-out ending a shift longer than the maximum
+#[derive(Debug, Clone, Copy)]
+pub struct LongShiftRule {
+    pub max_shift: Duration,
+}
+
+impl AnomalyRule for LongShiftRule {
+    fn kind(&self) -> AnomalyKind {
+        AnomalyKind::LongShift
+    }
+
+    fn lookback(&self) -> Duration {
+        // Shifts longer than the maximum must still be visible
+        self.max_shift * 2
+    }
+
+    fn check(&self, ctx: &RuleContext<'_>) -> Option<String> {
+        let event = ctx.event;
+        if event.event_type != EventType::ClockOut {
+            return None;
+        }
+
+        // The clock-in that opened the shift: the latest clock event before this
+        // clock-out, if it is a clock-in
+        let opening = ctx
+            .history
+            .iter()
+            .filter(|e| {
+                e.id != event.id
+                    && e.event_time < event.event_time
+                    && matches!(e.event_type, EventType::ClockIn | EventType::ClockOut)
+            })
+            .max_by_key(|e| e.event_time)
+            .filter(|e| e.event_type == EventType::ClockIn)?;
+
+        let shift = event.event_time - opening.event_time;
+        (shift > self.max_shift).then(|| {
+            format!(
+                "Shift from {} to {} lasted {}h {:02}m",
+                rfc3339(opening.event_time),
+                rfc3339(event.event_time),
+                shift.num_hours(),
+                shift.num_minutes() % 60
+            )
+        })
+    }
+}
+
+/// Enabled anomaly rules and their thresholds (see [`crate::config`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnomalySettings {
+    /// Rules run on new events; `missing_clock_out` is left to the background job
+    pub rules: Vec<AnomalyKind>,
+    /// Window in which a second event of the same type is a duplicate
+    pub duplicate_window: Duration,
+    /// Allowed clock skew of event times ahead of the server
+    pub future_tolerance: Duration,
+    /// Longest plausible shift
+    pub max_shift: Duration,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            rules: AnomalyKind::ALL.to_vec(),
+            duplicate_window: Duration::seconds(DEFAULT_DUPLICATE_WINDOW_SECONDS),
+            future_tolerance: Duration::minutes(DEFAULT_FUTURE_TOLERANCE_MINUTES),
+            max_shift: Duration::hours(DEFAULT_MAX_SHIFT_HOURS),
+        }
+    }
+}
+
+/// The set of enabled anomaly rules
+#[derive(Clone)]
+pub struct AnomalyRules {
+    rules: Arc<[Box<dyn AnomalyRule>]>,
+}
+
+impl std::fmt::Debug for AnomalyRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.rules.iter().map(|rule| rule.kind()))
+            .finish()
+    }
+}
+
+impl Default for AnomalyRules {
+    fn default() -> Self {
+        Self::from_settings(&AnomalySettings::default())
+    }
+}
+
+impl AnomalyRules {
+    /// Create a rule set from individual rules
+    #[must_use]
+    pub fn new(rules: Vec<Box<dyn AnomalyRule>>) -> Self {
+        Self {
+            rules: rules.into(),
+        }
+    }
+
+    /// Create the enabled rules with their thresholds
+    #[must_use]
+    pub fn from_settings(settings: &AnomalySettings) -> Self {
+        let mut rules: Vec<Box<dyn AnomalyRule>> = Vec::new();
+        for kind in &settings.rules {
+            match kind {
+                AnomalyKind::DuplicateEvent => rules.push(Box::new(DuplicateEventRule {
+                    window: settings.duplicate_window,
+                })),
+                AnomalyKind::FutureEvent => rules.push(Box::new(FutureEventRule {
+                    tolerance: settings.future_tolerance,
+                })),
+                AnomalyKind::LongShift => rules.push(Box::new(LongShiftRule {
+                    max_shift: settings.max_shift,
+                })),
+                // Detected by the periodic background job, not on event creation
+                AnomalyKind::MissingClockOut => {}
+            }
+        }
+
+        Self::new(rules)
+    }
+
+    /// How far before an event the rules need to see the user's history
+    #[must_use]
+    pub fn lookback(&self) -> Duration {
+        self.rules
+            .iter()
+            .map(|rule| rule.lookback())
+            .max()
+            .unwrap_or_else(Duration::zero)
+    }
+
+    /// Run every rule against the event
+    #[must_use]
+    pub fn evaluate(&self, ctx: &RuleContext<'_>) -> Vec<DetectedAnomaly> {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                rule.check(ctx).map(|details| DetectedAnomaly {
+                    kind: rule.kind(),
+                    details,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Format a timestamp for anomaly details
+fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::tests::event;
+
+    fn check(rule: &dyn AnomalyRule, event: &AttendanceEvent, history: &[AttendanceEvent]) -> bool {
+        rule.check(&RuleContext { event, history }).is_some()
+    }
+
+    #[test]
+    fn test_duplicate_event_rule() {
+        let rule = DuplicateEventRule {
+            window: Duration::seconds(60),
+        };
+        let first = event(EventType::ClockIn, "2025-11-05T00:00:00Z");
+        let repeat = event(EventType::ClockIn, "2025-11-05T00:00:30Z");
+        let later = event(EventType::ClockIn, "2025-11-05T00:05:00Z");
+        let other_type = event(EventType::ClockOut, "2025-11-05T00:00:30Z");
+
+        assert!(check(&rule, &repeat, &[first.clone(), repeat.clone()]));
+        assert!(!check(&rule, &later, &[first.clone(), later.clone()]));
+        assert!(!check(&rule, &other_type, &[first, other_type.clone()]));
+        // An event never duplicates itself
+        assert!(!check(&rule, &repeat, std::slice::from_ref(&repeat)));
+    }
+
+    #[test]
+    fn test_future_event_rule() {
+        let rule = FutureEventRule {
+            tolerance: Duration::minutes(5),
+        };
+        let mut future = event(EventType::ClockIn, "2025-11-05T09:00:00Z");
+        future.recorded_at = "2025-11-05T08:00:00Z".parse().unwrap();
+        let mut skewed = event(EventType::ClockIn, "2025-11-05T09:00:00Z");
+        skewed.recorded_at = "2025-11-05T08:58:00Z".parse().unwrap();
+        let past = event(EventType::ClockIn, "2025-11-05T09:00:00Z");
+
+        assert!(check(&rule, &future, &[]));
+        assert!(!check(&rule, &skewed, &[]));
+        assert!(!check(&rule, &past, &[]));
+    }
+
+    #[test]
+    fn test_long_shift_rule() {
+        let rule = LongShiftRule {
+            max_shift: Duration::hours(16),
+        };
+        let clock_in = event(EventType::ClockIn, "2025-11-05T00:00:00Z");
+        let long_out = event(EventType::ClockOut, "2025-11-05T17:00:00Z");
+        let normal_out = event(EventType::ClockOut, "2025-11-05T09:00:00Z");
+
+        assert!(check(
+            &rule,
+            &long_out,
+            &[clock_in.clone(), long_out.clone()]
+        ));
+        assert!(!check(
+            &rule,
+            &normal_out,
+            &[clock_in.clone(), normal_out.clone()]
+        ));
+        // Only clock-outs end a shift
+        assert!(!check(&rule, &clock_in, std::slice::from_ref(&clock_in)));
+        // A clock-out without an open clock-in is not a shift
+        let closed = event(EventType::ClockOut, "2025-11-05T01:00:00Z");
+        assert!(!check(
+            &rule,
+            &long_out,
+            &[clock_in, closed, long_out.clone()]
+        ));
+    }
+
+    #[test]
+    fn test_rules_evaluate_all() {
+        let rules = AnomalyRules::default();
+        let clock_in = event(EventType::ClockIn, "2025-11-05T00:00:00Z");
+        let first_out = event(EventType::ClockOut, "2025-11-05T20:00:00Z");
+        let second_out = event(EventType::ClockOut, "2025-11-05T20:00:10Z");
+        let history = [clock_in, first_out.clone(), second_out];
+
+        let detected = rules.evaluate(&RuleContext {
+            event: &first_out,
+            history: &history,
+        });
+        let kinds: Vec<AnomalyKind> = detected.iter().map(|a| a.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![AnomalyKind::DuplicateEvent, AnomalyKind::LongShift]
+        );
+        assert_eq!(rules.lookback(), Duration::hours(32));
+    }
+
+    #[test]
+    fn test_empty_rules_detect_nothing() {
+        let rules = AnomalyRules::new(Vec::new());
+        let clock_in = event(EventType::ClockIn, "2025-11-05T00:00:00Z");
+
+        assert!(
+            rules
+                .evaluate(&RuleContext {
+                    event: &clock_in,
+                    history: &[],
+                })
+                .is_empty()
+        );
+        assert_eq!(rules.lookback(), Duration::zero());
+    }
+}
@@ -0,0 +1,156 @@
+use crate::models::{AttendanceEvent, EventType};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+/// Check that a break event is allowed after the user's previous event
+///
+/// `previous` is the type of the user's latest event at or before the new
+/// event's time (`None` if there is none). Non-break events are always allowed.
+///
+/// # Errors
+/// Returns a validation message if:
+/// - A break starts while the user is not clocked in or is already on a break
+/// - A break ends without a matching break start
+pub fn validate_break_event(previous: Option<EventType>, next: EventType) -> Result<(), String> {
+    match next {
+        EventType::BreakStart => match previous {
+            Some(EventType::ClockIn | EventType::BreakEnd) => Ok(()),
+            Some(EventType::BreakStart) => Err("A break has already started".to_string()),
+            Some(EventType::ClockOut) | None => {
+                Err("Cannot start a break while not clocked in".to_string())
+            }
+        },
+        EventType::BreakEnd => match previous {
+            Some(EventType::BreakStart) => Ok(()),
+            _ => Err("Cannot end a break without a matching break start".to_string()),
+        },
+        EventType::ClockIn | EventType::ClockOut => Ok(()),
+    }
+}
+
+/// A single break on a day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakEntry {
+    pub start: DateTime<Utc>,
+    /// `None` while the break is still in progress
+    pub end: Option<DateTime<Utc>>,
+    /// `None` while the break is still in progress
+    pub minutes: Option<i64>,
+}
+
+/// Breaks taken on a single calendar day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BreakSummary {
+    pub date: NaiveDate,
+    pub breaks: Vec<BreakEntry>,
+    /// Total minutes of completed breaks
+    pub total_break_minutes: i64,
+}
+
+/// Summarize the breaks started on `date` (business timezone)
+///
+/// Each `break_start` is paired with the next `break_end`; a break without an
+/// end yet is reported as in progress and not counted in the total.
+#[must_use]
+pub fn summarize_breaks(date: NaiveDate, events: &[AttendanceEvent]) -> BreakSummary {
+    let mut sorted: Vec<&AttendanceEvent> = events.iter().collect();
+    sorted.sort_by_key(|e| e.event_time);
+
+    let mut breaks: Vec<BreakEntry> = Vec::new();
+    let mut open: Option<DateTime<Utc>> = None;
+
+    for event in sorted {
+        match event.event_type {
+            EventType::BreakStart if open.is_none() => open = Some(event.event_time),
+            EventType::BreakEnd | EventType::ClockOut => {
+                if let Some(start) = open.take() {
+                    breaks.push(BreakEntry {
+                        start,
+                        end: Some(event.event_time),
+                        minutes: Some((event.event_time - start).num_minutes()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = open {
+        breaks.push(BreakEntry {
+            start,
+            end: None,
+            minutes: None,
+        });
+    }
+
+    breaks.retain(|b| super::local_date(b.start) == date);
+    let total_break_minutes = breaks.iter().filter_map(|b| b.minutes).sum();
+
+    BreakSummary {
+        date,
+        breaks,
+        total_break_minutes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::tests::event;
+
+    #[test]
+    fn test_break_start_requires_clock_in() {
+        assert!(validate_break_event(Some(EventType::ClockIn), EventType::BreakStart).is_ok());
+        assert!(validate_break_event(Some(EventType::BreakEnd), EventType::BreakStart).is_ok());
+        assert!(validate_break_event(None, EventType::BreakStart).is_err());
+        assert!(validate_break_event(Some(EventType::ClockOut), EventType::BreakStart).is_err());
+        assert!(validate_break_event(Some(EventType::BreakStart), EventType::BreakStart).is_err());
+    }
+
+    #[test]
+    fn test_break_end_requires_break_start() {
+        assert!(validate_break_event(Some(EventType::BreakStart), EventType::BreakEnd).is_ok());
+        assert!(validate_break_event(None, EventType::BreakEnd).is_err());
+        assert!(validate_break_event(Some(EventType::ClockIn), EventType::BreakEnd).is_err());
+        assert!(validate_break_event(Some(EventType::BreakEnd), EventType::BreakEnd).is_err());
+    }
+
+    #[test]
+    fn test_clock_events_are_not_restricted() {
+        assert!(validate_break_event(None, EventType::ClockIn).is_ok());
+        assert!(validate_break_event(Some(EventType::BreakStart), EventType::ClockOut).is_ok());
+    }
+
+    #[test]
+    fn test_summarize_breaks() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::BreakStart, "2025-11-05T03:00:00Z"),
+            event(EventType::BreakEnd, "2025-11-05T03:45:00Z"),
+            event(EventType::BreakStart, "2025-11-05T06:00:00Z"),
+            event(EventType::BreakEnd, "2025-11-05T06:15:00Z"),
+            event(EventType::BreakStart, "2025-11-05T08:00:00Z"),
+        ];
+
+        let summary = summarize_breaks(date, &events);
+        assert_eq!(summary.breaks.len(), 3);
+        assert_eq!(summary.breaks[0].minutes, Some(45));
+        assert_eq!(summary.breaks[1].minutes, Some(15));
+        assert_eq!(summary.breaks[2].end, None);
+        assert_eq!(summary.total_break_minutes, 60);
+    }
+
+    #[test]
+    fn test_summarize_breaks_only_includes_requested_day() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 5).unwrap();
+        let events = vec![
+            // 11/06 01:00 JST
+            event(EventType::BreakStart, "2025-11-05T16:00:00Z"),
+            event(EventType::BreakEnd, "2025-11-05T16:30:00Z"),
+        ];
+
+        let summary = summarize_breaks(date, &events);
+        assert!(summary.breaks.is_empty());
+        assert_eq!(summary.total_break_minutes, 0);
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt;
+
+/// Default allowance for client clocks running ahead of the server (5 minutes)
+const DEFAULT_MAX_FUTURE_SKEW_SECONDS: i64 = 300;
+
+/// Plausible range of `event_time` relative to the server time
+///
+/// Events may be slightly in the future to allow for client clock skew, and
+/// may be backfilled up to `max_backfill` into the past (no limit if `None`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventTimeWindow {
+    pub max_future_skew: Duration,
+    pub max_backfill: Option<Duration>,
+}
+
+impl Default for EventTimeWindow {
+    fn default() -> Self {
+        Self {
+            max_future_skew: Duration::seconds(DEFAULT_MAX_FUTURE_SKEW_SECONDS),
+            max_backfill: None,
+        }
+    }
+}
+
+/// Why an `event_time` was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventTimeError {
+    /// Later than the latest allowed time
+    TooFarInFuture { latest: DateTime<Utc> },
+    /// Earlier than the backfill window allows
+    TooOld { earliest: DateTime<Utc> },
+}
+
+impl fmt::Display for EventTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFarInFuture { latest } => write!(
+                f,
+                "event_time is too far in the future (latest allowed: {})",
+                latest.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            Self::TooOld { earliest } => write!(
+                f,
+                "event_time is older than the backfill window (earliest allowed: {})",
+                earliest.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+        }
+    }
+}
+
+impl EventTimeWindow {
+    /// Check that an event time is plausible at server time `now`
+    ///
+    /// # Errors
+    /// Returns `EventTimeError` if the event time is outside the window
+    pub fn check(
+        &self,
+        event_time: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<(), EventTimeError> {
+        let latest = now + self.max_future_skew;
+        if event_time > latest {
+            return Err(EventTimeError::TooFarInFuture { latest });
+        }
+        if let Some(max_backfill) = self.max_backfill {
+            let earliest = now - max_backfill;
+            if event_time < earliest {
+                return Err(EventTimeError::TooOld { earliest });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_future_skew() {
+        let window = EventTimeWindow::default();
+        let now = time("2025-11-05T09:00:00Z");
+
+        assert!(window.check(time("2025-11-05T09:05:00Z"), now).is_ok());
+        assert_eq!(
+            window.check(time("2025-11-05T09:05:01Z"), now),
+            Err(EventTimeError::TooFarInFuture {
+                latest: time("2025-11-05T09:05:00Z")
+            })
+        );
+    }
+
+    #[test]
+    fn test_backfill_window() {
+        let now = time("2025-11-05T09:00:00Z");
+        let unlimited = EventTimeWindow::default();
+        assert!(unlimited.check(time("2000-01-01T00:00:00Z"), now).is_ok());
+
+        let window = EventTimeWindow {
+            max_backfill: Some(Duration::days(30)),
+            ..EventTimeWindow::default()
+        };
+        assert!(window.check(time("2025-10-06T09:00:00Z"), now).is_ok());
+        assert_eq!(
+            window.check(time("2025-10-06T08:59:59Z"), now),
+            Err(EventTimeError::TooOld {
+                earliest: time("2025-10-06T09:00:00Z")
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        let error = EventTimeError::TooFarInFuture {
+            latest: time("2025-11-05T09:05:00Z"),
+        };
+        assert_eq!(
+            error.to_string(),
+            "event_time is too far in the future (latest allowed: 2025-11-05T09:05:00Z)"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use crate::models::EventType;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Columns an import file must have, in any order
+const REQUIRED_COLUMNS: [&str; 3] = ["email", "event_type", "event_time"];
+
+/// A row of an import file that passed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRow {
+    /// Line number in the file (the header is line 1)
+    pub line: usize,
+    pub email: String,
+    pub event_type: EventType,
+    pub event_time: DateTime<Utc>,
+}
+
+/// A problem with a single row of an import file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RowError {
+    /// Line number in the file (the header is line 1)
+    pub line: usize,
+    pub message: String,
+}
+
+impl RowError {
+    #[must_use]
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// Parse a CSV import file of attendance events
+///
+/// The file must start with a header containing `email`, `event_type` and
+/// `event_time` columns (extra columns are ignored). `event_time` must be an
+/// RFC 3339 timestamp with an offset, e.g. `2025-11-05T09:00:00+09:00`.
+///
+/// Returns the valid rows and an error for every invalid row, so a caller can
+/// report all problems of a file at once.
+///
+/// # Errors
+/// Returns a message if the file cannot be read as CSV at all or the header
+/// lacks a required column
+pub fn parse_csv(data: &[u8]) -> Result<(Vec<ImportRow>, Vec<RowError>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {e}"))?
+        .clone();
+    let mut positions = [0; REQUIRED_COLUMNS.len()];
+    for (position, column) in positions.iter_mut().zip(REQUIRED_COLUMNS) {
+        *position = headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(column))
+            .ok_or_else(|| format!("Missing required column: {column}"))?;
+    }
+    let [email_at, event_type_at, event_time_at] = positions;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(RowError::new(line, format!("Invalid CSV row: {e}")));
+                continue;
+            }
+        };
+        let field = |at: usize| record.get(at).unwrap_or_default();
+
+        let email = field(email_at);
+        if email.is_empty() {
+            errors.push(RowError::new(line, "Email is required"));
+            continue;
+        }
+        let event_type = match field(event_type_at).parse::<EventType>() {
+            Ok(event_type) => event_type,
+            Err(message) => {
+                errors.push(RowError::new(line, message));
+                continue;
+            }
+        };
+        let Ok(event_time) = DateTime::parse_from_rfc3339(field(event_time_at)) else {
+            errors.push(RowError::new(
+                line,
+                format!("Invalid event time: {}", field(event_time_at)),
+            ));
+            continue;
+        };
+
+        rows.push(ImportRow {
+            line,
+            email: email.to_string(),
+            event_type,
+            event_time: event_time.with_timezone(&Utc),
+        });
+    }
+
+    Ok((rows, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let data = b"email,event_type,event_time\n\
+            alice@example.com,clock_in,2025-11-05T09:00:00+09:00\n\
+            alice@example.com, clock_out ,2025-11-05T18:00:00+09:00\n";
+
+        let (rows, errors) = parse_csv(data).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].event_type, EventType::ClockIn);
+        assert_eq!(
+            rows[0].event_time,
+            "2025-11-05T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(rows[1].event_type, EventType::ClockOut);
+    }
+
+    #[test]
+    fn test_parse_csv_reports_invalid_rows() {
+        let data = b"event_time,email,event_type\n\
+            2025-11-05T09:00:00Z,,clock_in\n\
+            2025-11-05T09:00:00Z,bob@example.com,lunch\n\
+            yesterday,bob@example.com,clock_in\n\
+            2025-11-05T09:00:00Z,bob@example.com,clock_in\n";
+
+        let (rows, errors) = parse_csv(data).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].line, 5);
+        assert_eq!(
+            errors.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_requires_columns() {
+        let err = parse_csv(b"email,event_type\nalice@example.com,clock_in\n").unwrap_err();
+        assert!(err.contains("event_time"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Attendance domain logic
+//!
+//! Pure calculations over attendance events (pairing, reports). Nothing in this
+//! module touches the database; handlers fetch events through the repositories
+//! and pass them in.
+
+pub mod anomaly;
+pub mod breaks;
+pub mod event_time;
+pub mod import;
+pub mod overtime;
+pub mod payroll;
+pub mod session;
+pub mod summary;
+pub mod timesheet;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// Offset of the business timezone (Asia/Tokyo, UTC+9, no daylight saving time)
+///
+/// Matches the `DATE(event_time AT TIME ZONE 'Asia/Tokyo')` index on `attendance_events`.
+const BUSINESS_UTC_OFFSET_SECONDS: i32 = 9 * 3600;
+
+/// Timezone used to assign attendance events to calendar days
+///
+/// # Panics
+/// Never panics in practice; the offset is a valid constant
+#[must_use]
+pub const fn business_timezone() -> FixedOffset {
+    FixedOffset::east_opt(BUSINESS_UTC_OFFSET_SECONDS).expect("valid business timezone offset")
+}
+
+/// Calendar date of an instant in the business timezone
+#[must_use]
+pub fn local_date(time: DateTime<Utc>) -> NaiveDate {
+    time.with_timezone(&business_timezone()).date_naive()
+}
+
+/// Instant at which a calendar date starts in the business timezone
+///
+/// # Panics
+/// Never panics in practice; a fixed offset maps every local time to exactly one instant
+#[must_use]
+pub fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    business_timezone()
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .single()
+        .expect("fixed offset has no ambiguous local times")
+        .with_timezone(&Utc)
+}
+
+/// First day of the month following `year`/`month`
+///
+/// Returns `None` if the month is out of range
+#[must_use]
+pub const fn next_month_start(year: i32, month: u32) -> Option<NaiveDate> {
+    if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_date_uses_business_timezone() {
+        // 2025-11-05 15:30 UTC is 2025-11-06 00:30 in Tokyo
+        let time = "2025-11-05T15:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            local_date(time),
+            NaiveDate::from_ymd_opt(2025, 11, 6).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_local_day_start() {
+        let date = NaiveDate::from_ymd_opt(2025, 11, 6).unwrap();
+        assert_eq!(
+            local_day_start(date),
+            "2025-11-05T15:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_month_start() {
+        assert_eq!(
+            next_month_start(2025, 12),
+            NaiveDate::from_ymd_opt(2026, 1, 1)
+        );
+        assert_eq!(
+            next_month_start(2025, 2),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+        assert_eq!(next_month_start(2025, 13), None);
+    }
+}
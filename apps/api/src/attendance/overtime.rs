@@ -0,0 +1,420 @@
+use super::session::build_sessions;
+use crate::models::{AttendanceEvent, RoundingMode, WorkPolicy};
+use chrono::{Duration, NaiveDate, Weekday};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::BuildHasher;
+use std::str::FromStr;
+
+/// Default standard working time per day (8 hours)
+const DEFAULT_DAILY_HOURS: u32 = 8;
+
+/// Default weekly threshold of regular working time (40 hours)
+const DEFAULT_WEEKLY_HOURS: u32 = 40;
+
+/// Rounding of the worked time of a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rounding {
+    pub mode: RoundingMode,
+    /// Rounding unit in minutes (e.g. 15 for quarter hours)
+    pub unit_minutes: i64,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self {
+            mode: RoundingMode::None,
+            unit_minutes: 1,
+        }
+    }
+}
+
+impl Rounding {
+    /// Round a number of worked minutes to a multiple of the unit
+    #[must_use]
+    pub const fn apply(self, minutes: i64) -> i64 {
+        let unit = self.unit_minutes;
+        if unit <= 1 {
+            return minutes;
+        }
+        match self.mode {
+            RoundingMode::None => minutes,
+            RoundingMode::Down => minutes.div_euclid(unit) * unit,
+            RoundingMode::Up => (minutes + unit - 1).div_euclid(unit) * unit,
+            RoundingMode::Nearest => (minutes + unit / 2).div_euclid(unit) * unit,
+        }
+    }
+}
+
+/// Overtime rules of a deployment
+///
+/// Time worked beyond `standard_daily_minutes` on a day is daily overtime.
+/// Regular (non-overtime) time beyond `weekly_threshold_minutes` in a week is
+/// weekly overtime, so no minute is counted twice. Each day's worked time is
+/// rounded before overtime is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OvertimePolicy {
+    pub standard_daily_minutes: i64,
+    pub weekly_threshold_minutes: i64,
+    pub week_start: Weekday,
+    pub rounding: Rounding,
+}
+
+impl Default for OvertimePolicy {
+    fn default() -> Self {
+        Self {
+            standard_daily_minutes: i64::from(DEFAULT_DAILY_HOURS) * 60,
+            weekly_threshold_minutes: i64::from(DEFAULT_WEEKLY_HOURS) * 60,
+            week_start: Weekday::Mon,
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+impl From<&WorkPolicy> for OvertimePolicy {
+    fn from(policy: &WorkPolicy) -> Self {
+        Self {
+            standard_daily_minutes: i64::from(policy.standard_daily_minutes),
+            weekly_threshold_minutes: i64::from(policy.weekly_threshold_minutes),
+            week_start: policy.week_start.weekday(),
+            rounding: Rounding {
+                mode: policy.rounding_mode,
+                unit_minutes: i64::from(policy.rounding_minutes),
+            },
+        }
+    }
+}
+
+impl OvertimePolicy {
+    /// Overtime of a day: time beyond the standard working time, or all of
+    /// the worked time on a holiday
+    #[must_use]
+    pub const fn daily_overtime(&self, worked_minutes: i64, is_holiday: bool) -> i64 {
+        if is_holiday {
+            worked_minutes
+        } else {
+            let overtime = worked_minutes - self.standard_daily_minutes;
+            if overtime > 0 { overtime } else { 0 }
+        }
+    }
+
+    /// First day of the week containing `date`
+    #[must_use]
+    pub const fn week_of(&self, date: NaiveDate) -> NaiveDate {
+        date.week(self.week_start).first_day()
+    }
+}
+
+/// A reporting period: a calendar month (`2025-11`) or an ISO week (`2025-W45`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    /// First day of the period
+    pub start: NaiveDate,
+    /// First day after the period
+    pub end: NaiveDate,
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Period must be a month (YYYY-MM) or an ISO week (YYYY-Www): {s}");
+
+        let (year, rest) = s.split_once('-').ok_or_else(invalid)?;
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+
+        if let Some(week) = rest.strip_prefix('W') {
+            let week = week.parse::<u32>().map_err(|_| invalid())?;
+            let start = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).ok_or_else(invalid)?;
+            return Ok(Self {
+                start,
+                end: start + Duration::days(7),
+            });
+        }
+
+        let month = rest.parse::<u32>().map_err(|_| invalid())?;
+        let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+        let end = super::next_month_start(year, month).ok_or_else(invalid)?;
+        Ok(Self { start, end })
+    }
+}
+
+/// Worked time and overtime on a single day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyOvertime {
+    pub date: NaiveDate,
+    /// Holidays are non-working days, so all time worked on them is overtime
+    pub is_holiday: bool,
+    pub worked_minutes: i64,
+    pub overtime_minutes: i64,
+}
+
+/// Worked time and overtime in a single week
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeeklyOvertime {
+    pub week_start: NaiveDate,
+    pub worked_minutes: i64,
+    /// Sum of the daily overtime of the week's days
+    pub daily_overtime_minutes: i64,
+    /// Regular time beyond the weekly threshold
+    pub weekly_overtime_minutes: i64,
+}
+
+/// Totals over a whole period
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OvertimeTotals {
+    pub worked_minutes: i64,
+    pub daily_overtime_minutes: i64,
+    pub weekly_overtime_minutes: i64,
+    pub overtime_minutes: i64,
+}
+
+/// Daily and weekly overtime over a period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OvertimeReport {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub standard_daily_minutes: i64,
+    pub weekly_threshold_minutes: i64,
+    /// Days with worked time, in date order
+    pub days: Vec<DailyOvertime>,
+    /// Weeks overlapping the period, in date order
+    pub weeks: Vec<WeeklyOvertime>,
+    pub totals: OvertimeTotals,
+}
+
+/// Compute daily and weekly overtime from attendance events
+///
+/// Only sessions started within the period count. Weeks overlapping the
+/// period boundaries only include the days inside the period. Days in
+/// `holidays` are non-working days: everything worked on them is overtime.
+#[must_use]
+pub fn calculate_overtime<S: BuildHasher>(
+    policy: &OvertimePolicy,
+    period: Period,
+    events: &[AttendanceEvent],
+    holidays: &HashSet<NaiveDate, S>,
+) -> OvertimeReport {
+    let mut worked_by_day: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    for session in build_sessions(events) {
+        let date = session.date();
+        if date >= period.start && date < period.end {
+            *worked_by_day.entry(date).or_default() += session.worked_duration().num_minutes();
+        }
+    }
+
+    let days: Vec<DailyOvertime> = worked_by_day
+        .into_iter()
+        .map(|(date, worked_minutes)| {
+            let worked_minutes = policy.rounding.apply(worked_minutes);
+            let is_holiday = holidays.contains(&date);
+            DailyOvertime {
+                date,
+                is_holiday,
+                worked_minutes,
+                overtime_minutes: policy.daily_overtime(worked_minutes, is_holiday),
+            }
+        })
+        .collect();
+
+    let mut weeks: BTreeMap<NaiveDate, WeeklyOvertime> = BTreeMap::new();
+    for day in &days {
+        let week_start = policy.week_of(day.date);
+        let week = weeks.entry(week_start).or_insert(WeeklyOvertime {
+            week_start,
+            worked_minutes: 0,
+            daily_overtime_minutes: 0,
+            weekly_overtime_minutes: 0,
+        });
+        week.worked_minutes += day.worked_minutes;
+        week.daily_overtime_minutes += day.overtime_minutes;
+    }
+
+    let mut totals = OvertimeTotals::default();
+    let weeks: Vec<WeeklyOvertime> = weeks
+        .into_values()
+        .map(|mut week| {
+            let regular_minutes = week.worked_minutes - week.daily_overtime_minutes;
+            week.weekly_overtime_minutes =
+                (regular_minutes - policy.weekly_threshold_minutes).max(0);
+
+            totals.worked_minutes += week.worked_minutes;
+            totals.daily_overtime_minutes += week.daily_overtime_minutes;
+            totals.weekly_overtime_minutes += week.weekly_overtime_minutes;
+            week
+        })
+        .collect();
+    totals.overtime_minutes = totals.daily_overtime_minutes + totals.weekly_overtime_minutes;
+
+    OvertimeReport {
+        period_start: period.start,
+        period_end: period.end,
+        standard_daily_minutes: policy.standard_daily_minutes,
+        weekly_threshold_minutes: policy.weekly_threshold_minutes,
+        days,
+        weeks,
+        totals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::tests::event;
+    use crate::models::EventType;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// A workday in JST from `start_hour` for `hours` hours without breaks
+    fn workday(day: &str, start_hour: u32, hours: i64) -> Vec<AttendanceEvent> {
+        let clock_in = format!("{day}T{start_hour:02}:00:00+09:00")
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap();
+        let clock_out = clock_in + Duration::hours(hours);
+        vec![
+            event(EventType::ClockIn, &clock_in.to_rfc3339()),
+            event(EventType::ClockOut, &clock_out.to_rfc3339()),
+        ]
+    }
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(
+            "2025-11".parse::<Period>(),
+            Ok(Period {
+                start: date(2025, 11, 1),
+                end: date(2025, 12, 1)
+            })
+        );
+        assert_eq!(
+            "2025-W45".parse::<Period>(),
+            Ok(Period {
+                start: date(2025, 11, 3),
+                end: date(2025, 11, 10)
+            })
+        );
+        assert!("2025-13".parse::<Period>().is_err());
+        assert!("2025-W54".parse::<Period>().is_err());
+        assert!("november".parse::<Period>().is_err());
+    }
+
+    #[test]
+    fn test_daily_overtime() {
+        let policy = OvertimePolicy::default();
+        let period = "2025-11".parse::<Period>().unwrap();
+        let events = workday("2025-11-05", 9, 10);
+
+        let report = calculate_overtime(&policy, period, &events, &HashSet::new());
+        assert_eq!(report.days.len(), 1);
+        assert_eq!(report.days[0].worked_minutes, 600);
+        assert_eq!(report.days[0].overtime_minutes, 120);
+        assert_eq!(report.totals.overtime_minutes, 120);
+        assert_eq!(report.totals.weekly_overtime_minutes, 0);
+    }
+
+    #[test]
+    fn test_weekly_overtime_does_not_double_count() {
+        let policy = OvertimePolicy::default();
+        let period = "2025-W45".parse::<Period>().unwrap();
+        // Monday-Saturday, 8 hours each, plus 2 extra hours on Monday
+        let mut events = workday("2025-11-03", 9, 10);
+        for day in ["04", "05", "06", "07", "08"] {
+            events.extend(workday(&format!("2025-11-{day}"), 9, 8));
+        }
+
+        let report = calculate_overtime(&policy, period, &events, &HashSet::new());
+        assert_eq!(report.weeks.len(), 1);
+        let week = &report.weeks[0];
+        assert_eq!(week.week_start, date(2025, 11, 3));
+        assert_eq!(week.worked_minutes, 50 * 60);
+        assert_eq!(week.daily_overtime_minutes, 120);
+        // 48 regular hours against a 40 hour threshold
+        assert_eq!(week.weekly_overtime_minutes, 8 * 60);
+        assert_eq!(report.totals.overtime_minutes, 10 * 60);
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = OvertimePolicy {
+            standard_daily_minutes: 7 * 60,
+            weekly_threshold_minutes: 35 * 60,
+            ..OvertimePolicy::default()
+        };
+        let period = "2025-11".parse::<Period>().unwrap();
+        let events = workday("2025-11-05", 9, 8);
+
+        let report = calculate_overtime(&policy, period, &events, &HashSet::new());
+        assert_eq!(report.totals.daily_overtime_minutes, 60);
+    }
+
+    #[test]
+    fn test_rounding() {
+        let rounding = |mode| Rounding {
+            mode,
+            unit_minutes: 15,
+        };
+        assert_eq!(rounding(RoundingMode::None).apply(487), 487);
+        assert_eq!(rounding(RoundingMode::Down).apply(487), 480);
+        assert_eq!(rounding(RoundingMode::Up).apply(481), 495);
+        assert_eq!(rounding(RoundingMode::Up).apply(480), 480);
+        assert_eq!(rounding(RoundingMode::Nearest).apply(487), 480);
+        assert_eq!(rounding(RoundingMode::Nearest).apply(488), 495);
+        assert_eq!(Rounding::default().apply(487), 487);
+    }
+
+    #[test]
+    fn test_rounding_applies_before_overtime() {
+        let policy = OvertimePolicy {
+            rounding: Rounding {
+                mode: RoundingMode::Down,
+                unit_minutes: 30,
+            },
+            ..OvertimePolicy::default()
+        };
+        let period = "2025-11".parse::<Period>().unwrap();
+        // 8h 20m of work
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T08:20:00Z"),
+        ];
+
+        let report = calculate_overtime(&policy, period, &events, &HashSet::new());
+        assert_eq!(report.days[0].worked_minutes, 480);
+        assert_eq!(report.totals.overtime_minutes, 0);
+    }
+
+    #[test]
+    fn test_holiday_work_is_overtime() {
+        let policy = OvertimePolicy::default();
+        let period = "2025-W45".parse::<Period>().unwrap();
+        // Monday is a holiday; Monday-Saturday, 8 hours each
+        let mut events = Vec::new();
+        for day in ["03", "04", "05", "06", "07", "08"] {
+            events.extend(workday(&format!("2025-11-{day}"), 9, 8));
+        }
+        let holidays = HashSet::from([date(2025, 11, 3)]);
+
+        let report = calculate_overtime(&policy, period, &events, &holidays);
+        assert!(report.days[0].is_holiday);
+        assert_eq!(report.days[0].overtime_minutes, 480);
+        assert!(!report.days[1].is_holiday);
+        assert_eq!(report.days[1].overtime_minutes, 0);
+        // Holiday work does not count towards the weekly threshold:
+        // 40 regular hours leave no weekly overtime
+        assert_eq!(report.weeks[0].weekly_overtime_minutes, 0);
+        assert_eq!(report.totals.overtime_minutes, 480);
+    }
+
+    #[test]
+    fn test_sessions_outside_period_are_ignored() {
+        let policy = OvertimePolicy::default();
+        let period = "2025-W45".parse::<Period>().unwrap();
+        let events = workday("2025-11-10", 9, 12);
+
+        let report = calculate_overtime(&policy, period, &events, &HashSet::new());
+        assert!(report.days.is_empty());
+        assert_eq!(report.totals, OvertimeTotals::default());
+    }
+}
@@ -0,0 +1,68 @@
+//! Monthly payroll figures
+//!
+//! Combines a user's timesheet and overtime report into the hours payroll
+//! systems import: regular time, overtime and paid leave.
+
+use super::overtime::OvertimeReport;
+use super::timesheet::Timesheet;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Payroll figures of one employee for one month
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PayrollEntry {
+    pub employee_id: Uuid,
+    /// Worked time that is not overtime
+    pub regular_minutes: i64,
+    pub overtime_minutes: i64,
+    /// Paid leave; always 0 as leave is not tracked yet
+    pub leave_minutes: i64,
+}
+
+impl PayrollEntry {
+    /// Build the payroll figures from a month's timesheet and overtime report
+    ///
+    /// Both must cover the same month and use the same rounding, so the
+    /// overtime is part of the timesheet's worked time.
+    #[must_use]
+    pub fn new(employee_id: Uuid, timesheet: &Timesheet, overtime: &OvertimeReport) -> Self {
+        let overtime_minutes = overtime.totals.overtime_minutes;
+        Self {
+            employee_id,
+            regular_minutes: (timesheet.totals.worked_minutes - overtime_minutes).max(0),
+            overtime_minutes,
+            leave_minutes: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::overtime::{OvertimePolicy, Period, calculate_overtime};
+    use crate::attendance::session::tests::event;
+    use crate::attendance::timesheet::build_monthly_timesheet;
+    use crate::models::EventType;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_regular_time_excludes_overtime() {
+        // 10h on Wednesday, 6h on Thursday (business timezone, UTC+9)
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T10:00:00Z"),
+            event(EventType::ClockIn, "2025-11-06T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-06T06:00:00Z"),
+        ];
+        let policy = OvertimePolicy::default();
+        let timesheet = build_monthly_timesheet(2025, 11, &events).unwrap();
+        let period: Period = "2025-11".parse().unwrap();
+        let overtime = calculate_overtime(&policy, period, &events, &HashSet::new());
+
+        let entry = PayrollEntry::new(Uuid::nil(), &timesheet, &overtime);
+
+        assert_eq!(entry.regular_minutes, 14 * 60);
+        assert_eq!(entry.overtime_minutes, 2 * 60);
+        assert_eq!(entry.leave_minutes, 0);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::models::{AttendanceEvent, EventType};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// A break taken inside a work session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl BreakPeriod {
+    /// Length of the break
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// A completed work session: a `clock_in` matched with the following `clock_out`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkSession {
+    pub clock_in: DateTime<Utc>,
+    pub clock_out: DateTime<Utc>,
+    pub breaks: Vec<BreakPeriod>,
+}
+
+impl WorkSession {
+    /// Calendar date (business timezone) the session is attributed to
+    ///
+    /// Sessions that cross midnight belong to the day they started.
+    #[must_use]
+    pub fn date(&self) -> NaiveDate {
+        super::local_date(self.clock_in)
+    }
+
+    /// Total time between clock-in and clock-out
+    #[must_use]
+    pub fn span(&self) -> Duration {
+        self.clock_out - self.clock_in
+    }
+
+    /// Total break time within the session
+    #[must_use]
+    pub fn break_duration(&self) -> Duration {
+        self.breaks
+            .iter()
+            .fold(Duration::zero(), |acc, b| acc + b.duration())
+    }
+
+    /// Time actually worked (span minus breaks)
+    #[must_use]
+    pub fn worked_duration(&self) -> Duration {
+        self.span() - self.break_duration()
+    }
+}
+
+/// Pair raw attendance events into completed work sessions
+///
+/// Events are processed in `event_time` order regardless of the input order.
+/// Events that cannot be paired are ignored:
+/// - a `clock_in` while already clocked in (the first one wins)
+/// - a `clock_out`, `break_start` or `break_end` while not clocked in
+/// - a session that has not been clocked out yet
+///
+/// A break still open at clock-out is closed at the clock-out time.
+#[must_use]
+pub fn build_sessions(events: &[AttendanceEvent]) -> Vec<WorkSession> {
+    let mut sorted: Vec<&AttendanceEvent> = events.iter().collect();
+    sorted.sort_by_key(|e| e.event_time);
+
+    let mut sessions = Vec::new();
+    let mut current: Option<(DateTime<Utc>, Vec<BreakPeriod>)> = None;
+    let mut break_start: Option<DateTime<Utc>> = None;
+
+    for event in sorted {
+        match event.event_type {
+            EventType::ClockIn => {
+                if current.is_none() {
+                    current = Some((event.event_time, Vec::new()));
+                    break_start = None;
+                }
+            }
+            EventType::BreakStart => {
+                if current.is_some() && break_start.is_none() {
+                    break_start = Some(event.event_time);
+                }
+            }
+            EventType::BreakEnd => {
+                if let (Some((_, breaks)), Some(start)) = (current.as_mut(), break_start.take()) {
+                    breaks.push(BreakPeriod {
+                        start,
+                        end: event.event_time,
+                    });
+                }
+            }
+            EventType::ClockOut => {
+                if let Some((clock_in, mut breaks)) = current.take() {
+                    if let Some(start) = break_start.take() {
+                        breaks.push(BreakPeriod {
+                            start,
+                            end: event.event_time,
+                        });
+                    }
+                    sessions.push(WorkSession {
+                        clock_in,
+                        clock_out: event.event_time,
+                        breaks,
+                    });
+                }
+            }
+        }
+    }
+
+    sessions
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Build an event for tests from an RFC 3339 timestamp
+    pub fn event(event_type: EventType, time: &str) -> AttendanceEvent {
+        let event_time = time.parse::<DateTime<Utc>>().unwrap();
+        AttendanceEvent {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            event_type,
+            event_time,
+            recorded_at: event_time,
+            created_at: event_time,
+            latitude: None,
+            longitude: None,
+            client_ip: None,
+            user_agent: None,
+            device_id: None,
+            amends_event_id: None,
+        }
+    }
+
+    #[test]
+    fn test_build_sessions_with_break() {
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::BreakStart, "2025-11-05T03:00:00Z"),
+            event(EventType::BreakEnd, "2025-11-05T04:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T09:00:00Z"),
+        ];
+
+        let sessions = build_sessions(&events);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].span(), Duration::hours(9));
+        assert_eq!(sessions[0].break_duration(), Duration::hours(1));
+        assert_eq!(sessions[0].worked_duration(), Duration::hours(8));
+    }
+
+    #[test]
+    fn test_build_sessions_sorts_events() {
+        let events = vec![
+            event(EventType::ClockOut, "2025-11-05T09:00:00Z"),
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+        ];
+
+        let sessions = build_sessions(&events);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].worked_duration(), Duration::hours(9));
+    }
+
+    #[test]
+    fn test_build_sessions_ignores_unpaired_events() {
+        let events = vec![
+            event(EventType::ClockOut, "2025-11-04T09:00:00Z"),
+            event(EventType::BreakStart, "2025-11-04T23:00:00Z"),
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::ClockIn, "2025-11-05T01:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T08:00:00Z"),
+            event(EventType::ClockIn, "2025-11-06T00:00:00Z"),
+        ];
+
+        let sessions = build_sessions(&events);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].clock_in,
+            "2025-11-05T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(sessions[0].worked_duration(), Duration::hours(8));
+    }
+
+    #[test]
+    fn test_build_sessions_closes_open_break_at_clock_out() {
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::BreakStart, "2025-11-05T08:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T09:00:00Z"),
+        ];
+
+        let sessions = build_sessions(&events);
+        assert_eq!(sessions[0].break_duration(), Duration::hours(1));
+        assert_eq!(sessions[0].worked_duration(), Duration::hours(8));
+    }
+
+    #[test]
+    fn test_session_date_uses_clock_in_day() {
+        // Night shift starting 22:00 JST on 11/05 and ending 06:00 JST on 11/06
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T13:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T21:00:00Z"),
+        ];
+
+        let sessions = build_sessions(&events);
+        assert_eq!(
+            sessions[0].date(),
+            NaiveDate::from_ymd_opt(2025, 11, 5).unwrap()
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::models::EventType;
+use serde::Serialize;
+
+/// Attendance status of a user at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// Clocked in and working
+    Present,
+    /// Clocked in and on a break
+    OnBreak,
+    /// Not clocked in (clocked out or no events)
+    Absent,
+}
+
+/// Derive a user's status from their latest event of the day
+///
+/// `None` means the user has no events in the day.
+#[must_use]
+pub const fn presence_status(latest: Option<EventType>) -> PresenceStatus {
+    match latest {
+        Some(EventType::ClockIn | EventType::BreakEnd) => PresenceStatus::Present,
+        Some(EventType::BreakStart) => PresenceStatus::OnBreak,
+        Some(EventType::ClockOut) | None => PresenceStatus::Absent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presence_status() {
+        assert_eq!(
+            presence_status(Some(EventType::ClockIn)),
+            PresenceStatus::Present
+        );
+        assert_eq!(
+            presence_status(Some(EventType::BreakEnd)),
+            PresenceStatus::Present
+        );
+        assert_eq!(
+            presence_status(Some(EventType::BreakStart)),
+            PresenceStatus::OnBreak
+        );
+        assert_eq!(
+            presence_status(Some(EventType::ClockOut)),
+            PresenceStatus::Absent
+        );
+        assert_eq!(presence_status(None), PresenceStatus::Absent);
+    }
+}
@@ -0,0 +1,230 @@
+use super::overtime::Rounding;
+use super::session::{WorkSession, build_sessions};
+use crate::models::AttendanceEvent;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// One calendar day of a timesheet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimesheetDay {
+    pub date: NaiveDate,
+    /// Whether the day is a holiday (a non-working day)
+    pub is_holiday: bool,
+    /// First clock-in of the day (`None` if the day has no completed session)
+    pub clock_in: Option<DateTime<Utc>>,
+    /// Last clock-out of sessions started on this day
+    pub clock_out: Option<DateTime<Utc>>,
+    pub worked_minutes: i64,
+    pub break_minutes: i64,
+}
+
+/// Totals over all days of a timesheet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TimesheetTotals {
+    pub worked_minutes: i64,
+    pub break_minutes: i64,
+    pub days_worked: u32,
+}
+
+/// Monthly per-day breakdown of worked time and breaks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Timesheet {
+    pub year: i32,
+    pub month: u32,
+    pub days: Vec<TimesheetDay>,
+    pub totals: TimesheetTotals,
+}
+
+impl Timesheet {
+    /// Round the worked time of every day and recompute the totals
+    #[must_use]
+    pub fn rounded(mut self, rounding: Rounding) -> Self {
+        for day in &mut self.days {
+            day.worked_minutes = rounding.apply(day.worked_minutes);
+        }
+        self.totals.worked_minutes = self.days.iter().map(|day| day.worked_minutes).sum();
+        self
+    }
+
+    /// Mark the days that are holidays
+    #[must_use]
+    pub fn with_holidays(mut self, holidays: &HashSet<NaiveDate>) -> Self {
+        for day in &mut self.days {
+            day.is_holiday = holidays.contains(&day.date);
+        }
+        self
+    }
+}
+
+/// Build a monthly timesheet from attendance events
+///
+/// Every calendar day of the month gets an entry, including days without
+/// attendance. Sessions are attributed to the day they started (business
+/// timezone); sessions starting outside the month are ignored, so callers may
+/// pass events from a slightly wider range to catch clock-outs after midnight
+/// on the last day.
+///
+/// Returns `None` if `year`/`month` do not form a valid month.
+#[must_use]
+pub fn build_monthly_timesheet(
+    year: i32,
+    month: u32,
+    events: &[AttendanceEvent],
+) -> Option<Timesheet> {
+    let first_day = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next_month = super::next_month_start(year, month)?;
+
+    let mut sessions_by_day: BTreeMap<NaiveDate, Vec<WorkSession>> = BTreeMap::new();
+    for session in build_sessions(events) {
+        sessions_by_day
+            .entry(session.date())
+            .or_default()
+            .push(session);
+    }
+
+    let mut totals = TimesheetTotals::default();
+    let days = first_day
+        .iter_days()
+        .take_while(|date| *date < next_month)
+        .map(|date| {
+            let day = summarize_day(date, sessions_by_day.get(&date).map_or(&[], Vec::as_slice));
+            totals.worked_minutes += day.worked_minutes;
+            totals.break_minutes += day.break_minutes;
+            if day.clock_in.is_some() {
+                totals.days_worked += 1;
+            }
+            day
+        })
+        .collect();
+
+    Some(Timesheet {
+        year,
+        month,
+        days,
+        totals,
+    })
+}
+
+/// Summarize the sessions started on a single day
+fn summarize_day(date: NaiveDate, sessions: &[WorkSession]) -> TimesheetDay {
+    TimesheetDay {
+        date,
+        is_holiday: false,
+        clock_in: sessions.iter().map(|s| s.clock_in).min(),
+        clock_out: sessions.iter().map(|s| s.clock_out).max(),
+        worked_minutes: sessions
+            .iter()
+            .map(|s| s.worked_duration().num_minutes())
+            .sum(),
+        break_minutes: sessions
+            .iter()
+            .map(|s| s.break_duration().num_minutes())
+            .sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::tests::event;
+    use crate::models::{EventType, RoundingMode};
+
+    #[test]
+    fn test_monthly_timesheet_covers_every_day() {
+        let timesheet = build_monthly_timesheet(2025, 2, &[]).unwrap();
+        assert_eq!(timesheet.days.len(), 28);
+        assert_eq!(
+            timesheet.days[0].date,
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()
+        );
+        assert_eq!(timesheet.totals, TimesheetTotals::default());
+    }
+
+    #[test]
+    fn test_monthly_timesheet_aggregates_days() {
+        let events = vec![
+            // 11/05 09:00-18:00 JST with a 1 hour break
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::BreakStart, "2025-11-05T03:00:00Z"),
+            event(EventType::BreakEnd, "2025-11-05T04:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T09:00:00Z"),
+            // 11/06 09:00-13:30 JST
+            event(EventType::ClockIn, "2025-11-06T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-06T04:30:00Z"),
+        ];
+
+        let timesheet = build_monthly_timesheet(2025, 11, &events).unwrap();
+        assert_eq!(timesheet.days.len(), 30);
+
+        let day5 = &timesheet.days[4];
+        assert_eq!(day5.worked_minutes, 480);
+        assert_eq!(day5.break_minutes, 60);
+        assert_eq!(day5.clock_in, Some("2025-11-05T00:00:00Z".parse().unwrap()));
+
+        let day6 = &timesheet.days[5];
+        assert_eq!(day6.worked_minutes, 270);
+        assert_eq!(day6.break_minutes, 0);
+
+        assert_eq!(
+            timesheet.totals,
+            TimesheetTotals {
+                worked_minutes: 750,
+                break_minutes: 60,
+                days_worked: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_monthly_timesheet_ignores_sessions_outside_month() {
+        let events = vec![
+            // Starts 12/01 00:30 JST, which belongs to December
+            event(EventType::ClockIn, "2025-11-30T15:30:00Z"),
+            event(EventType::ClockOut, "2025-11-30T20:00:00Z"),
+        ];
+
+        let timesheet = build_monthly_timesheet(2025, 11, &events).unwrap();
+        assert_eq!(timesheet.totals.days_worked, 0);
+    }
+
+    #[test]
+    fn test_rounded_timesheet() {
+        let events = vec![
+            // 11/05 09:00-17:10 JST
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T08:10:00Z"),
+        ];
+        let rounding = Rounding {
+            mode: RoundingMode::Up,
+            unit_minutes: 15,
+        };
+
+        let timesheet = build_monthly_timesheet(2025, 11, &events)
+            .unwrap()
+            .rounded(rounding);
+        assert_eq!(timesheet.days[4].worked_minutes, 495);
+        assert_eq!(timesheet.days[5].worked_minutes, 0);
+        assert_eq!(timesheet.totals.worked_minutes, 495);
+    }
+
+    #[test]
+    fn test_timesheet_with_holidays() {
+        let holiday = NaiveDate::from_ymd_opt(2025, 11, 3).unwrap();
+        let timesheet = build_monthly_timesheet(2025, 11, &[])
+            .unwrap()
+            .with_holidays(&HashSet::from([holiday]));
+
+        assert!(timesheet.days[2].is_holiday);
+        assert_eq!(
+            timesheet.days.iter().filter(|day| day.is_holiday).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_monthly_timesheet_invalid_month() {
+        assert!(build_monthly_timesheet(2025, 0, &[]).is_none());
+        assert!(build_monthly_timesheet(2025, 13, &[]).is_none());
+    }
+}
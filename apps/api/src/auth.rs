@@ -0,0 +1,177 @@
+//! Access tokens for authenticated users
+//!
+//! Logging in (or registering) returns a short-lived bearer token identifying
+//! the user. Tokens are signed (see [`crate::token`]) and carry a `typ` claim,
+//! so a kiosk token signed with the same secret is not accepted as an access token.
+
+use crate::token::{TokenError, TokenSettings, TokenSigner};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default lifetime of an access token (1 hour)
+pub(crate) const DEFAULT_TTL_SECONDS: i64 = 60 * 60;
+
+/// Longest configurable lifetime of an access token (7 days)
+pub(crate) const MAX_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// Value of the `typ` claim of access tokens
+const ACCESS_TOKEN_TYPE: &str = "access";
+
+/// Claims of an access token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessClaims {
+    /// The authenticated user
+    pub sub: Uuid,
+    /// Expiry as a Unix timestamp (seconds)
+    pub exp: i64,
+    /// Token type, always `access`
+    pub typ: String,
+    /// Token version of the user at issue time (see [`crate::models::User`]);
+    /// tokens issued before the version was incremented are revoked
+    #[serde(default)]
+    pub ver: i32,
+}
+
+/// An issued access token
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Reasons an access token is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTokenError {
+    Invalid(TokenError),
+    /// Signed with the right secret, but not an access token
+    WrongType,
+    Expired,
+}
+
+impl std::fmt::Display for AccessTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "Invalid access token: {e}"),
+            Self::WrongType => write!(f, "Not an access token"),
+            Self::Expired => write!(f, "Access token has expired"),
+        }
+    }
+}
+
+/// Issues and verifies access tokens
+#[derive(Debug, Clone)]
+pub struct AuthTokens {
+    signer: TokenSigner,
+    ttl: Duration,
+}
+
+impl AuthTokens {
+    /// Create an access token issuer with a signing secret and token lifetime
+    #[must_use]
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            signer: TokenSigner::new(secret),
+            ttl,
+        }
+    }
+
+    /// Create an access token issuer with the configured secret and lifetime
+    #[must_use]
+    pub fn from_settings(settings: &TokenSettings) -> Self {
+        Self::new(
+            settings.secret_or_random("auth.token_secret").as_bytes(),
+            settings.ttl,
+        )
+    }
+
+    /// Issue a token for a user with their current token version, valid from
+    /// `now` for the configured lifetime
+    #[must_use]
+    pub fn issue(&self, user_id: Uuid, token_version: i32, now: DateTime<Utc>) -> AccessToken {
+        let expires_at = now + self.ttl;
+        let claims = AccessClaims {
+            sub: user_id,
+            exp: expires_at.timestamp(),
+            typ: ACCESS_TOKEN_TYPE.to_string(),
+            ver: token_version,
+        };
+
+        AccessToken {
+            token: self.signer.sign(&claims),
+            expires_at,
+        }
+    }
+
+    /// Verify a presented token at `now`
+    ///
+    /// # Errors
+    /// Returns `AccessTokenError` if the token is malformed, forged, of another
+    /// type or expired
+    pub fn verify(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<AccessClaims, AccessTokenError> {
+        let claims: AccessClaims = self
+            .signer
+            .verify(token)
+            .map_err(AccessTokenError::Invalid)?;
+
+        if claims.typ != ACCESS_TOKEN_TYPE {
+            return Err(AccessTokenError::WrongType);
+        }
+        if now.timestamp() >= claims.exp {
+            return Err(AccessTokenError::Expired);
+        }
+
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kiosk::KioskTokens;
+
+    fn now() -> DateTime<Utc> {
+        "2025-11-05T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_issue_and_verify() {
+        let tokens = AuthTokens::new(b"secret", Duration::seconds(3600));
+        let user_id = Uuid::new_v4();
+
+        let issued = tokens.issue(user_id, 3, now());
+        assert_eq!(issued.expires_at, now() + Duration::seconds(3600));
+
+        let claims = tokens
+            .verify(&issued.token, now() + Duration::seconds(3599))
+            .unwrap();
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.ver, 3);
+    }
+
+    #[test]
+    fn test_verify_rejects_expired_token() {
+        let tokens = AuthTokens::new(b"secret", Duration::seconds(60));
+        let issued = tokens.issue(Uuid::new_v4(), 0, now());
+
+        assert_eq!(
+            tokens.verify(&issued.token, now() + Duration::seconds(60)),
+            Err(AccessTokenError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_kiosk_token_with_same_secret() {
+        let kiosk = KioskTokens::new(b"secret", Duration::seconds(60)).issue(Uuid::new_v4(), now());
+        let tokens = AuthTokens::new(b"secret", Duration::seconds(60));
+
+        assert!(matches!(
+            tokens.verify(&kiosk.token, now()),
+            Err(AccessTokenError::Invalid(TokenError::Malformed))
+        ));
+    }
+}
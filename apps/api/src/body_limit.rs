@@ -0,0 +1,46 @@
+//! Request body size limits
+//!
+//! Request bodies larger than the limit are rejected with `413 Payload Too
+//! Large` instead of being buffered. Imports and uploads (todo import, CSV
+//! attendance import, avatars) have a larger limit than the other routes.
+
+use tower_http::limit::RequestBodyLimitLayer;
+
+/// Limit of request bodies when `body_limits.default_bytes` is unset (1 MiB)
+const DEFAULT_LIMIT_BYTES: usize = 1024 * 1024;
+
+/// Limit of import and upload bodies when `body_limits.upload_bytes` is unset
+/// (16 MiB)
+const DEFAULT_UPLOAD_LIMIT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Body size limits (the `[body_limits]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    /// Largest body accepted by most routes, in bytes
+    pub default: usize,
+    /// Largest body accepted by import and upload routes, in bytes
+    pub upload: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_LIMIT_BYTES,
+            upload: DEFAULT_UPLOAD_LIMIT_BYTES,
+        }
+    }
+}
+
+impl BodyLimits {
+    /// Layer limiting the bodies of most routes
+    #[must_use]
+    pub fn layer(&self) -> RequestBodyLimitLayer {
+        RequestBodyLimitLayer::new(self.default)
+    }
+
+    /// Layer limiting the bodies of import and upload routes
+    #[must_use]
+    pub fn upload_layer(&self) -> RequestBodyLimitLayer {
+        RequestBodyLimitLayer::new(self.upload)
+    }
+}
@@ -0,0 +1,55 @@
+//! Response compression
+//!
+//! Responses are compressed with gzip or brotli when the client accepts it
+//! (`Accept-Encoding`), so large lists reach slow clients sooner. Small
+//! responses, images and formats that are already compressed (XLSX exports are
+//! ZIP archives) are sent as they are.
+
+use crate::export;
+use tower_http::compression::{
+    CompressionLayer,
+    predicate::{And, DefaultPredicate, NotForContentType, Predicate, SizeAbove},
+};
+
+/// Smallest response body compressed when `compression.min_size` is unset
+const DEFAULT_MIN_SIZE: u16 = 1024;
+
+/// Decides which responses are compressed
+pub type CompressionPredicate = And<And<DefaultPredicate, NotForContentType>, SizeAbove>;
+
+/// Compression settings (the `[compression]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionSettings {
+    pub enabled: bool,
+    /// Responses smaller than this many bytes are not compressed
+    pub min_size: u16,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_size: DEFAULT_MIN_SIZE,
+        }
+    }
+}
+
+impl CompressionSettings {
+    /// The compression layer for the router, or `None` when disabled
+    #[must_use]
+    pub fn layer(&self) -> Option<CompressionLayer<CompressionPredicate>> {
+        if !self.enabled {
+            return None;
+        }
+        let predicate = DefaultPredicate::new()
+            .and(NotForContentType::const_new(export::xlsx::CONTENT_TYPE))
+            .and(SizeAbove::new(self.min_size));
+
+        Some(
+            CompressionLayer::new()
+                .gzip(true)
+                .br(true)
+                .compress_when(predicate),
+        )
+    }
+}
@@ -0,0 +1,147 @@
+//! Cross-origin resource sharing (CORS)
+//!
+//! Browsers only let a frontend served from another origin (e.g. the SPA dev
+//! server) call the API if the API allows that origin. CORS is disabled unless
+//! `cors.allowed_origins` (`CORS_ALLOWED_ORIGINS`) is set.
+
+use crate::extract::{ADMIN_KEY_HEADER, API_KEY_HEADER, DEVICE_ID_HEADER, DEVICE_TOKEN_HEADER};
+use crate::idempotency::{IDEMPOTENCY_KEY_HEADER, REPLAYED_HEADER};
+use crate::request_id::REQUEST_ID_HEADER;
+use crate::session::CSRF_HEADER;
+use axum::http::{HeaderName, HeaderValue, Method, header};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Methods allowed when `cors.allowed_methods` is unset
+const DEFAULT_METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+];
+
+/// How long browsers may cache a preflight response (1 hour)
+const PREFLIGHT_MAX_AGE_SECONDS: u64 = 60 * 60;
+
+/// Origins allowed to call the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Every origin (`*`); cannot be combined with credentials
+    Any,
+    /// Exactly these origins (e.g. `http://localhost:5173`)
+    List(Vec<HeaderValue>),
+}
+
+/// CORS settings (the `[cors]` configuration section)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsSettings {
+    /// `None` disables CORS
+    pub origins: Option<AllowedOrigins>,
+    pub methods: Vec<Method>,
+    pub headers: Vec<HeaderName>,
+    /// Whether browsers send cookies (the session) with cross-origin requests
+    pub credentials: bool,
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        Self {
+            origins: None,
+            methods: DEFAULT_METHODS.to_vec(),
+            headers: default_headers(),
+            credentials: false,
+        }
+    }
+}
+
+impl AllowedOrigins {
+    /// Parse configured origins; a `*` entry allows any origin
+    ///
+    /// Invalid entries are skipped with a warning. Returns `None`, disabling
+    /// CORS, if no entries are given.
+    pub(crate) fn parse(setting: &str, entries: &[String]) -> Option<Self> {
+        if entries.iter().any(|entry| entry.trim() == "*") {
+            return Some(Self::Any);
+        }
+        if entries.iter().all(|entry| entry.trim().is_empty()) {
+            return None;
+        }
+        Some(Self::List(parse_list(setting, entries, |origin| {
+            HeaderValue::from_str(origin.trim_end_matches('/')).ok()
+        })))
+    }
+}
+
+impl CorsSettings {
+    /// The CORS layer for the router, or `None` when CORS is disabled
+    #[must_use]
+    pub fn layer(&self) -> Option<CorsLayer> {
+        let origin = match self.origins.as_ref()? {
+            AllowedOrigins::Any => AllowOrigin::any(),
+            AllowedOrigins::List(origins) => AllowOrigin::list(origins.iter().cloned()),
+        };
+
+        Some(
+            CorsLayer::new()
+                .allow_origin(origin)
+                .allow_methods(self.methods.clone())
+                .allow_headers(self.headers.clone())
+                .allow_credentials(self.credentials)
+                .expose_headers([
+                    header::LINK,
+                    header::LOCATION,
+                    header::RETRY_AFTER,
+                    REQUEST_ID_HEADER,
+                    HeaderName::from_static(REPLAYED_HEADER),
+                ])
+                .max_age(Duration::from_secs(PREFLIGHT_MAX_AGE_SECONDS)),
+        )
+    }
+}
+
+/// Request headers the API reads
+fn default_headers() -> Vec<HeaderName> {
+    vec![
+        header::AUTHORIZATION,
+        header::CONTENT_TYPE,
+        header::ACCEPT,
+        HeaderName::from_static(CSRF_HEADER),
+        HeaderName::from_static(ADMIN_KEY_HEADER),
+        HeaderName::from_static(API_KEY_HEADER),
+        HeaderName::from_static(DEVICE_ID_HEADER),
+        HeaderName::from_static(DEVICE_TOKEN_HEADER),
+        HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+    ]
+}
+
+/// Parse configured methods (case-insensitive), skipping invalid entries with
+/// a warning
+pub(crate) fn parse_methods(setting: &str, entries: &[String]) -> Vec<Method> {
+    parse_list(setting, entries, |method| {
+        Method::from_bytes(method.to_ascii_uppercase().as_bytes()).ok()
+    })
+}
+
+/// Parse configured request headers, skipping invalid entries with a warning
+pub(crate) fn parse_headers(setting: &str, entries: &[String]) -> Vec<HeaderName> {
+    parse_list(setting, entries, |name| {
+        HeaderName::from_bytes(name.as_bytes()).ok()
+    })
+}
+
+/// Parse a list of settings, skipping invalid entries with a warning
+fn parse_list<T>(setting: &str, entries: &[String], parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+    entries
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = parse(entry);
+            if parsed.is_none() {
+                tracing::warn!("{setting} contains an invalid entry {entry}, skipping it");
+            }
+            parsed
+        })
+        .collect()
+}
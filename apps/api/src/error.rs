@@ -1,55 +1,283 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde::Serialize;
 use std::fmt;
 
+/// データベースの接続プールが枯渇したときに再試行を促すまでの秒数
+const POOL_EXHAUSTED_RETRY_AFTER_SECONDS: u64 = 5;
+
+/// `statement_timeout`やキャンセルで打ち切られたクエリのSQLSTATE
+const QUERY_CANCELED: &str = "57014";
+
+/// クライアントが分岐に使う機械可読なエラーコード
+///
+/// エラーレスポンスの`code`フィールドとして`SCREAMING_SNAKE_CASE`で返す。
+/// 一度公開したコードは変更しない。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// 内部サーバーエラー
+    InternalError,
+    /// バリデーションエラー
+    ValidationFailed,
+    /// 認証エラー
+    Unauthorized,
+    /// 権限がない
+    Forbidden,
+    /// リソースが見つからない
+    NotFound,
+    /// リクエストが不正
+    BadRequest,
+    /// リソースの状態と競合する
+    Conflict,
+    /// リクエストが多すぎる
+    TooManyRequests,
+    /// アカウントがロックされている
+    AccountLocked,
+    /// 一時的に処理できない
+    ServiceUnavailable,
+    /// 処理が制限時間内に終わらなかった
+    GatewayTimeout,
+    /// TODOが見つからない
+    TodoNotFound,
+    /// ユーザーが見つからない
+    UserNotFound,
+    /// メールアドレスが他の有効なユーザーに使われている
+    EmailAlreadyExists,
+    /// Googleアカウントが他のユーザーに連携されている
+    GoogleAccountAlreadyLinked,
+    /// メールアドレスまたはパスワードが正しくない
+    InvalidCredentials,
+    /// アクセストークンが失効している
+    AccessTokenRevoked,
+    /// CSRFトークンがない、または正しくない
+    CsrfTokenInvalid,
+    /// キオスクトークンが使用済み
+    KioskTokenAlreadyUsed,
+    /// 冪等キーが別のリクエストに使われている
+    IdempotencyKeyReused,
+    /// 同じ冪等キーのリクエストが処理中
+    IdempotencyKeyInProgress,
+    /// リクエストのJSON、パスまたはクエリを解釈できない
+    MalformedRequest,
+    /// リクエストのJSONの形式は正しいが内容を解釈できない
+    UnprocessableEntity,
+    /// リクエストの`Content-Type`に対応していない
+    UnsupportedMediaType,
+    /// リクエストの本文が大きすぎる
+    PayloadTooLarge,
+}
+
 /// アプリケーション全体で使用するカスタムエラー型
 #[derive(Debug)]
 pub enum AppError {
     /// 内部サーバーエラー
     InternalServerError(String),
+    /// 原因となったエラーを保持する内部サーバーエラー（[`AppError::internal`]で作る）
+    ///
+    /// 原因の連鎖はログにのみ出力し、クライアントには返さない。
+    Internal {
+        context: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     /// バリデーションエラー
     ValidationError(String),
     /// 認証エラー
     Unauthorized(String),
+    /// 認証済みだが権限がない
+    Forbidden(String),
     /// リソースが見つからない
     NotFound(String),
     /// リクエストが不正
     BadRequest(String),
+    /// 現在のリソースの状態と競合する
+    Conflict(String),
+    /// リクエストが多すぎる（`retry_after` 秒後に再試行できる）
+    TooManyRequests { retry_after: u64 },
+    /// 失敗したログインが多すぎてアカウントがロックされている（`retry_after` 秒後に解除）
+    AccountLocked { retry_after: u64 },
+    /// 一時的に処理できない（`retry_after` があればその秒数後に再試行できる）
+    ServiceUnavailable {
+        message: String,
+        retry_after: Option<u64>,
+    },
+    /// 処理が制限時間内に終わらなかった（[`crate::timeout`]）
+    GatewayTimeout(String),
+    /// 抽出（JSON本文、パス、クエリ）に失敗したリクエスト（`status`はaxumの拒否のもの）
+    Rejected { status: StatusCode, message: String },
+    /// 既定のコードの代わりに`code`を返すエラー（[`AppError::with_code`]で作る）
+    Coded { code: ErrorCode, error: Box<Self> },
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InternalServerError(msg) => write!(f, "Internal server error: {msg}"),
+            Self::Internal { context, .. } => write!(f, "Internal server error: {context}"),
             Self::ValidationError(msg) => write!(f, "Validation error: {msg}"),
             Self::Unauthorized(msg) => write!(f, "Unauthorized: {msg}"),
+            Self::Forbidden(msg) => write!(f, "Forbidden: {msg}"),
             Self::NotFound(msg) => write!(f, "Not found: {msg}"),
             Self::BadRequest(msg) => write!(f, "Bad request: {msg}"),
+            Self::Conflict(msg) => write!(f, "Conflict: {msg}"),
+            Self::TooManyRequests { retry_after } => {
+                write!(f, "Too many requests: retry after {retry_after} seconds")
+            }
+            Self::AccountLocked { retry_after } => {
+                write!(f, "Account locked: retry after {retry_after} seconds")
+            }
+            Self::ServiceUnavailable { message, .. } => {
+                write!(f, "Service unavailable: {message}")
+            }
+            Self::GatewayTimeout(msg) => write!(f, "Gateway timeout: {msg}"),
+            Self::Rejected { message, .. } => write!(f, "Rejected request: {message}"),
+            Self::Coded { error, .. } => error.fmt(f),
         }
     }
 }
 
-impl std::error::Error for AppError {}
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Internal { source, .. } => Some(source.as_ref()),
+            Self::Coded { error, .. } => error.source(),
+            _ => None,
+        }
+    }
+}
 
 /// エラーレスポンスのJSON構造
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
+    code: ErrorCode,
     message: String,
+    /// 問い合わせ時に伝えるリクエストID（[`crate::request_id`]）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+/// エラーレスポンスの拡張に格納するエラーの内容
+///
+/// ミドルウェアがレスポンスを別の形式（[`crate::problem`]）に書き換えるときに使う。
+#[derive(Debug, Clone)]
+pub struct ErrorDetails {
+    pub code: ErrorCode,
+    pub message: String,
+    pub request_id: Option<String>,
 }
 
 impl AppError {
+    /// 原因となったエラーを保持する内部サーバーエラーを作る
+    pub fn internal(
+        context: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::Internal {
+            context: context.into(),
+            source: source.into(),
+        }
+    }
+
+    /// 原因の連鎖（`原因: 原因の原因: ...`）、原因がなければ`None`
+    fn source_chain(&self) -> Option<String> {
+        let mut source = std::error::Error::source(self)?;
+        let mut chain = source.to_string();
+        while let Some(next) = source.source() {
+            chain.push_str(": ");
+            chain.push_str(&next.to_string());
+            source = next;
+        }
+        Some(chain)
+    }
+
+    /// 既定のコードの代わりに`code`をレスポンスに含める
+    #[must_use]
+    pub fn with_code(self, code: ErrorCode) -> Self {
+        let error = match self {
+            Self::Coded { error, .. } => error,
+            error => Box::new(error),
+        };
+        Self::Coded { code, error }
+    }
+
+    /// レスポンスに含めるエラーコード
+    #[must_use]
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InternalServerError(_) | Self::Internal { .. } => ErrorCode::InternalError,
+            Self::ValidationError(_) => ErrorCode::ValidationFailed,
+            Self::Unauthorized(_) => ErrorCode::Unauthorized,
+            Self::Forbidden(_) => ErrorCode::Forbidden,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::BadRequest(_) => ErrorCode::BadRequest,
+            Self::Conflict(_) => ErrorCode::Conflict,
+            Self::TooManyRequests { .. } => ErrorCode::TooManyRequests,
+            Self::AccountLocked { .. } => ErrorCode::AccountLocked,
+            Self::ServiceUnavailable { .. } => ErrorCode::ServiceUnavailable,
+            Self::GatewayTimeout(_) => ErrorCode::GatewayTimeout,
+            Self::Rejected { status, .. } => match *status {
+                StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::UnprocessableEntity,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+                StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+                _ => ErrorCode::MalformedRequest,
+            },
+            Self::Coded { code, .. } => *code,
+        }
+    }
+
+    /// `Retry-After`ヘッダーに設定する秒数
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            Self::TooManyRequests { retry_after } | Self::AccountLocked { retry_after } => {
+                Some(*retry_after)
+            }
+            Self::ServiceUnavailable { retry_after, .. } => *retry_after,
+            Self::Coded { error, .. } => error.retry_after(),
+            _ => None,
+        }
+    }
+
     /// エラーのHTTPステータスコード、エラータイプ、メッセージを取得
-    #[allow(clippy::cognitive_complexity)]
+    ///
+    /// 5xxのエラーは登録されたレポーター（[`crate::reporting`]）に報告する。
     fn error_info(&self) -> (StatusCode, &'static str, String) {
+        let info = self.describe();
+        if info.0.is_server_error() {
+            crate::reporting::report(info.0, self);
+        }
+        info
+    }
+
+    /// エラーのHTTPステータスコード、エラータイプ、メッセージ（ログに記録する）
+    #[allow(clippy::cognitive_complexity)]
+    pub(crate) fn describe(&self) -> (StatusCode, &'static str, String) {
         match self {
-            Self::InternalServerError(_msg) => {
+            Self::Coded { error, .. } => error.describe(),
+            Self::Rejected { status, message } => {
+                tracing::warn!(error = %self, "Request rejected");
+                let error_type = match *status {
+                    StatusCode::UNPROCESSABLE_ENTITY => "unprocessable_entity",
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+                    StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+                    _ => "bad_request",
+                };
+                (*status, error_type, message.clone())
+            }
+            Self::InternalServerError(_) | Self::Internal { .. } => {
                 // 内部エラーはログに記録するが、詳細はクライアントに返さない
-                tracing::error!(error = %self, "Internal server error occurred");
+                if let Some(source) = self.source_chain() {
+                    tracing::error!(
+                        error = %self,
+                        source = %source,
+                        "Internal server error occurred"
+                    );
+                } else {
+                    tracing::error!(error = %self, "Internal server error occurred");
+                }
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     "internal_server_error",
@@ -64,6 +292,10 @@ impl AppError {
                 tracing::warn!(error = %self, "Unauthorized access attempt");
                 (StatusCode::UNAUTHORIZED, "unauthorized", msg.clone())
             }
+            Self::Forbidden(msg) => {
+                tracing::warn!(error = %self, "Forbidden access attempt");
+                (StatusCode::FORBIDDEN, "forbidden", msg.clone())
+            }
             Self::NotFound(msg) => {
                 tracing::debug!(error = %self, "Resource not found");
                 (StatusCode::NOT_FOUND, "not_found", msg.clone())
@@ -72,6 +304,40 @@ impl AppError {
                 tracing::warn!(error = %self, "Bad request");
                 (StatusCode::BAD_REQUEST, "bad_request", msg.clone())
             }
+            Self::Conflict(msg) => {
+                tracing::warn!(error = %self, "Conflict");
+                (StatusCode::CONFLICT, "conflict", msg.clone())
+            }
+            Self::TooManyRequests { retry_after } => {
+                tracing::warn!(error = %self, "Rate limit exceeded");
+                (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "too_many_requests",
+                    format!("Too many requests, retry after {retry_after} seconds"),
+                )
+            }
+            Self::AccountLocked { retry_after } => {
+                tracing::warn!(error = %self, "Login to a locked account");
+                (
+                    StatusCode::LOCKED,
+                    "account_locked",
+                    format!(
+                        "The account is locked after too many failed logins, retry after {retry_after} seconds"
+                    ),
+                )
+            }
+            Self::ServiceUnavailable { message, .. } => {
+                tracing::warn!(error = %self, "Service unavailable");
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "service_unavailable",
+                    message.clone(),
+                )
+            }
+            Self::GatewayTimeout(msg) => {
+                tracing::error!(error = %self, "Request timed out");
+                (StatusCode::GATEWAY_TIMEOUT, "gateway_timeout", msg.clone())
+            }
         }
     }
 }
@@ -80,12 +346,29 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = self.error_info();
 
+        let details = ErrorDetails {
+            code: self.code(),
+            message,
+            request_id: crate::request_id::current(),
+        };
         let body = Json(ErrorResponse {
             error: error_type.to_string(),
-            message,
+            code: details.code,
+            message: details.message.clone(),
+            request_id: details.request_id.clone(),
         });
 
-        (status, body).into_response()
+        let mut response = match self.retry_after() {
+            Some(retry_after) => (
+                status,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                body,
+            )
+                .into_response(),
+            None => (status, body).into_response(),
+        };
+        response.extensions_mut().insert(details);
+        response
     }
 }
 
@@ -95,8 +378,7 @@ pub type Result<T> = std::result::Result<T, AppError>;
 /// 一般的なエラーから`AppError`への変換を実装
 impl From<std::io::Error> for AppError {
     fn from(err: std::io::Error) -> Self {
-        tracing::error!(error = %err, "IO error occurred");
-        Self::InternalServerError(format!("IO error: {err}"))
+        Self::internal("IO error", err)
     }
 }
 
@@ -107,9 +389,85 @@ impl From<serde_json::Error> for AppError {
     }
 }
 
+/// 一意制約違反は`Conflict`、接続プールの枯渇は`ServiceUnavailable`、
+/// `statement_timeout`で打ち切られたクエリは`GatewayTimeout`になる
+/// （リポジトリはよく起きる一意制約違反をより具体的なメッセージに変換する）
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
-        tracing::error!(error = %err, "Database error occurred");
-        Self::InternalServerError("Database error".to_string())
+        crate::pool_stats::record_error(&err);
+        match err {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                tracing::warn!(error = %db_err, "Unique constraint violated");
+                Self::Conflict("A resource with the same unique value already exists".to_string())
+            }
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some(QUERY_CANCELED) => {
+                tracing::warn!(error = %db_err, "Query cancelled");
+                Self::GatewayTimeout("The query took too long to run".to_string())
+            }
+            sqlx::Error::PoolTimedOut => {
+                tracing::error!("Timed out waiting for a database connection");
+                Self::ServiceUnavailable {
+                    message: "The service is busy, please retry later".to_string(),
+                    retry_after: Some(POOL_EXHAUSTED_RETRY_AFTER_SECONDS),
+                }
+            }
+            err => Self::internal("Database error", err),
+        }
+    }
+}
+
+impl From<redis::RedisError> for AppError {
+    fn from(err: redis::RedisError) -> Self {
+        Self::internal("Redis error", err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Outer(std::io::Error);
+
+    impl fmt::Display for Outer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "query failed")
+        }
+    }
+
+    impl std::error::Error for Outer {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_internal_error_keeps_source_chain() {
+        let error = AppError::internal(
+            "Database error",
+            Outer(std::io::Error::other("connection reset")),
+        )
+        .with_code(ErrorCode::InternalError);
+
+        assert_eq!(error.to_string(), "Internal server error: Database error");
+        assert_eq!(
+            error.source_chain().as_deref(),
+            Some("query failed: connection reset")
+        );
+        let (status, error_type, message) = error.error_info();
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(error_type, "internal_server_error");
+        assert_eq!(message, "An internal server error occurred");
+    }
+
+    #[test]
+    fn test_database_errors_keep_source() {
+        let error = AppError::from(sqlx::Error::PoolClosed);
+
+        assert!(matches!(error, AppError::Internal { .. }));
+        assert_eq!(
+            error.source_chain().as_deref(),
+            Some(sqlx::Error::PoolClosed.to_string().as_str())
+        );
     }
 }
@@ -0,0 +1,160 @@
+use crate::attendance::session::WorkSession;
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// MIME type of iCalendar documents
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Product identifier written to the calendar (RFC 5545 `PRODID`)
+const PRODUCT_ID: &str = "-//expert-succotash//attendance//EN";
+
+/// Render worked sessions as an iCalendar (RFC 5545) document
+///
+/// Each completed work session becomes a `VEVENT` from clock-in to clock-out.
+/// Event UIDs are derived from the user and clock-in time, so re-importing an
+/// updated export replaces events instead of duplicating them.
+#[must_use]
+pub fn worked_sessions_calendar(
+    user_id: Uuid,
+    sessions: &[WorkSession],
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Attendance".to_string(),
+    ];
+
+    for session in sessions {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{user_id}-{}@expert-succotash",
+            session.clock_in.timestamp()
+        ));
+        lines.push(format!("DTSTAMP:{}", format_utc(generated_at)));
+        lines.push(format!("DTSTART:{}", format_utc(session.clock_in)));
+        lines.push(format!("DTEND:{}", format_utc(session.clock_out)));
+        lines.push("SUMMARY:Work".to_string());
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape_text(&format!(
+                "Worked {}, breaks {}",
+                format_duration(session.worked_duration()),
+                format_duration(session.break_duration())
+            ))
+        ));
+        lines.push("TRANSP:OPAQUE".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut document = String::new();
+    for line in lines {
+        document.push_str(&fold_line(&line));
+        document.push_str("\r\n");
+    }
+    document
+}
+
+/// Format an instant as an RFC 5545 UTC date-time (`20251105T090000Z`)
+fn format_utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Format a duration as `8h 05m`
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Escape a TEXT property value (RFC 5545 section 3.3.11)
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line longer than 75 octets (RFC 5545 section 3.1)
+fn fold_line(line: &str) -> String {
+    const MAX_OCTETS: usize = 75;
+
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        let _ = folded.write_char(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::BreakPeriod;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_worked_sessions_calendar() {
+        let sessions = vec![WorkSession {
+            clock_in: time("2025-11-05T00:00:00Z"),
+            clock_out: time("2025-11-05T09:00:00Z"),
+            breaks: vec![BreakPeriod {
+                start: time("2025-11-05T03:00:00Z"),
+                end: time("2025-11-05T04:00:00Z"),
+            }],
+        }];
+
+        let calendar =
+            worked_sessions_calendar(Uuid::nil(), &sessions, time("2025-11-06T00:00:00Z"));
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert!(calendar.contains("DTSTART:20251105T000000Z\r\n"));
+        assert!(calendar.contains("DTEND:20251105T090000Z\r\n"));
+        assert!(calendar.contains("DESCRIPTION:Worked 8h 00m\\, breaks 1h 00m\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn test_empty_calendar() {
+        let calendar = worked_sessions_calendar(Uuid::nil(), &[], time("2025-11-06T00:00:00Z"));
+        assert!(!calendar.contains("VEVENT"));
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = "X".repeat(100);
+        let folded = fold_line(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(parts[1], format!(" {}", "X".repeat(25)));
+    }
+}
@@ -0,0 +1,10 @@
+//! File export formats
+//!
+//! Renders already aggregated data (timesheets, reports) into downloadable
+//! documents. Handlers are responsible for fetching and aggregating the data.
+
+pub mod ical;
+pub mod ndjson;
+pub mod payroll;
+pub mod users;
+pub mod xlsx;
@@ -0,0 +1,37 @@
+//! Newline-delimited JSON (NDJSON)
+//!
+//! One JSON document per line, so large exports can be written and read a
+//! record at a time.
+
+use serde::Serialize;
+
+/// MIME type of NDJSON documents
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Serialize a record as one NDJSON line, including the trailing newline
+///
+/// # Errors
+/// Returns an error if the record cannot be serialized to JSON
+pub fn line<T: Serialize>(record: &T) -> serde_json::Result<Vec<u8>> {
+    // serde_json escapes newlines inside strings, so a record is always one line
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_line_is_single_json_line() {
+        let line = line(&json!({ "note": "first\nsecond", "n": 1 })).unwrap();
+        let text = String::from_utf8(line).unwrap();
+
+        assert!(text.ends_with('\n'));
+        assert_eq!(text.matches('\n').count(), 1);
+        let parsed: serde_json::Value = serde_json::from_str(text.trim_end()).unwrap();
+        assert_eq!(parsed["note"], "first\nsecond");
+    }
+}
@@ -0,0 +1,75 @@
+use crate::attendance::payroll::PayrollEntry;
+
+/// MIME type of the payroll CSV
+pub const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Column headers of the payroll CSV
+const HEADERS: [&str; 4] = [
+    "employee_id",
+    "regular_hours",
+    "overtime_hours",
+    "leave_hours",
+];
+
+/// Render payroll entries as a fixed-column CSV
+///
+/// One row per employee after a header row. Hours are decimal hours with two
+/// decimal places (e.g. `7.50` for 7h 30m), the format payroll imports expect.
+///
+/// # Errors
+/// Returns `csv::Error` if the document cannot be written
+pub fn payroll_csv(entries: &[PayrollEntry]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADERS)?;
+    for entry in entries {
+        writer.write_record([
+            entry.employee_id.to_string(),
+            decimal_hours(entry.regular_minutes),
+            decimal_hours(entry.overtime_minutes),
+            decimal_hours(entry.leave_minutes),
+        ])?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// Format minutes as decimal hours rounded to two decimal places
+fn decimal_hours(minutes: i64) -> String {
+    let hundredths = (minutes * 100 + 30).div_euclid(60);
+    format!("{}.{:02}", hundredths / 100, hundredths % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_decimal_hours() {
+        assert_eq!(decimal_hours(0), "0.00");
+        assert_eq!(decimal_hours(450), "7.50");
+        assert_eq!(decimal_hours(20), "0.33");
+        assert_eq!(decimal_hours(40), "0.67");
+        assert_eq!(decimal_hours(10_000), "166.67");
+    }
+
+    #[test]
+    fn test_payroll_csv() {
+        let entries = vec![PayrollEntry {
+            employee_id: Uuid::nil(),
+            regular_minutes: 9_600,
+            overtime_minutes: 90,
+            leave_minutes: 0,
+        }];
+
+        let csv = String::from_utf8(payroll_csv(&entries).unwrap()).unwrap();
+
+        assert_eq!(
+            csv,
+            "employee_id,regular_hours,overtime_hours,leave_hours\n\
+             00000000-0000-0000-0000-000000000000,160.00,1.50,0.00\n"
+        );
+    }
+}
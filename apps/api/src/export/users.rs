@@ -0,0 +1,85 @@
+//! User directory CSV for HR audits
+//!
+//! Rendered a row at a time, so the export can be streamed while the users are
+//! read from the database.
+
+use crate::models::ExportedUser;
+
+/// MIME type of the user CSV
+pub const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Column headers of the user CSV
+const HEADERS: [&str; 5] = ["id", "name", "email", "created_at", "deleted_at"];
+
+/// The header row, including the trailing newline
+///
+/// # Errors
+/// Returns `csv::Error` if the row cannot be written
+pub fn header() -> Result<Vec<u8>, csv::Error> {
+    write_row(HEADERS)
+}
+
+/// One user as a CSV row, including the trailing newline
+///
+/// Timestamps are RFC 3339; `deleted_at` is empty for active users. Names and
+/// emails that a spreadsheet would evaluate as a formula are prefixed with `'`.
+///
+/// # Errors
+/// Returns `csv::Error` if the row cannot be written
+pub fn row(user: &ExportedUser) -> Result<Vec<u8>, csv::Error> {
+    write_row([
+        user.id.to_string(),
+        neutralize_formula(&user.name),
+        neutralize_formula(&user.email),
+        user.created_at.to_rfc3339(),
+        user.deleted_at
+            .map(|deleted_at| deleted_at.to_rfc3339())
+            .unwrap_or_default(),
+    ])
+}
+
+fn write_row<I, T>(record: I) -> Result<Vec<u8>, csv::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(record)?;
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// Prefix values starting like a spreadsheet formula, so they are shown as text
+fn neutralize_formula(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_user_csv_rows() {
+        let user = ExportedUser {
+            id: Uuid::nil(),
+            name: "=HYPERLINK(\"x\")".to_string(),
+            email: "a, b@example.com".to_string(),
+            created_at: "2025-11-01T09:00:00Z".parse().unwrap(),
+            deleted_at: None,
+        };
+
+        let csv = [header().unwrap(), row(&user).unwrap()].concat();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "id,name,email,created_at,deleted_at\n\
+             00000000-0000-0000-0000-000000000000,\"'=HYPERLINK(\"\"x\"\")\",\"a, b@example.com\",2025-11-01T09:00:00+00:00,\n"
+        );
+    }
+}
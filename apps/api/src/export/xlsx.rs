@@ -0,0 +1,128 @@
+use crate::attendance::{self, overtime::OvertimePolicy, timesheet::Timesheet};
+use chrono::{DateTime, Utc};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+/// MIME type of xlsx documents
+pub const CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Minutes in a day; Excel stores durations as fractions of a day
+const MINUTES_PER_DAY: f64 = 1440.0;
+
+/// Column headers of the timesheet worksheet
+const HEADERS: [&str; 6] = ["Date", "In", "Out", "Breaks", "Total", "Overtime"];
+
+/// Render a monthly timesheet as an xlsx workbook
+///
+/// One row per calendar day with clock-in/out times (business timezone),
+/// break time, worked time and daily overtime according to `policy` (all
+/// worked time on holidays), followed by a totals row. Durations are written
+/// as Excel time values formatted as `[h]:mm` so they can be summed in
+/// spreadsheets.
+///
+/// # Errors
+/// Returns `XlsxError` if the workbook cannot be generated
+pub fn timesheet_workbook(
+    timesheet: &Timesheet,
+    policy: &OvertimePolicy,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let duration_format = Format::new().set_num_format("[h]:mm");
+    let total_format = Format::new().set_bold().set_num_format("[h]:mm");
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(format!("{}-{:02}", timesheet.year, timesheet.month))?;
+    worksheet.set_column_width(0, 12)?;
+
+    for (col, header) in (0u16..).zip(HEADERS) {
+        worksheet.write_string_with_format(0, col, header, &header_format)?;
+    }
+
+    let mut overtime_total = 0;
+    let mut row = 1;
+    for day in &timesheet.days {
+        let overtime = policy.daily_overtime(day.worked_minutes, day.is_holiday);
+        overtime_total += overtime;
+
+        worksheet.write_string(row, 0, day.date.to_string())?;
+        if let Some(clock_in) = day.clock_in {
+            worksheet.write_string(row, 1, local_time(clock_in))?;
+        }
+        if let Some(clock_out) = day.clock_out {
+            worksheet.write_string(row, 2, local_time(clock_out))?;
+        }
+        worksheet.write_number_with_format(row, 3, as_days(day.break_minutes), &duration_format)?;
+        worksheet.write_number_with_format(
+            row,
+            4,
+            as_days(day.worked_minutes),
+            &duration_format,
+        )?;
+        worksheet.write_number_with_format(row, 5, as_days(overtime), &duration_format)?;
+        row += 1;
+    }
+
+    let totals_row = row;
+    worksheet.write_string_with_format(totals_row, 0, "Total", &header_format)?;
+    worksheet.write_number_with_format(
+        totals_row,
+        3,
+        as_days(timesheet.totals.break_minutes),
+        &total_format,
+    )?;
+    worksheet.write_number_with_format(
+        totals_row,
+        4,
+        as_days(timesheet.totals.worked_minutes),
+        &total_format,
+    )?;
+    worksheet.write_number_with_format(totals_row, 5, as_days(overtime_total), &total_format)?;
+
+    workbook.save_to_buffer()
+}
+
+/// Format an instant as `HH:MM` in the business timezone
+fn local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&attendance::business_timezone())
+        .format("%H:%M")
+        .to_string()
+}
+
+/// Convert minutes to an Excel duration (fraction of a day)
+#[allow(clippy::cast_precision_loss)]
+fn as_days(minutes: i64) -> f64 {
+    minutes as f64 / MINUTES_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attendance::session::tests::event;
+    use crate::attendance::timesheet::build_monthly_timesheet;
+    use crate::models::EventType;
+
+    #[test]
+    fn test_timesheet_workbook_is_xlsx() {
+        let events = vec![
+            event(EventType::ClockIn, "2025-11-05T00:00:00Z"),
+            event(EventType::ClockOut, "2025-11-05T10:00:00Z"),
+        ];
+        let timesheet = build_monthly_timesheet(2025, 11, &events).unwrap();
+
+        let bytes = timesheet_workbook(&timesheet, &OvertimePolicy::default()).unwrap();
+
+        // xlsx files are zip archives
+        assert!(bytes.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn test_local_time_uses_business_timezone() {
+        let time = "2025-11-05T00:05:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(local_time(time), "09:05");
+    }
+
+    #[test]
+    fn test_as_days() {
+        assert!((as_days(720) - 0.5).abs() < f64::EPSILON);
+    }
+}
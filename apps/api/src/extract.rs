@@ -0,0 +1,800 @@
+//! Custom request extractors
+
+use crate::auth::AuthTokens;
+use crate::error::{AppError, ErrorCode, ErrorDetails};
+use crate::kiosk::{DeviceClaims, KioskTokenError, KioskTokens};
+use crate::models::{ApiKey, ApiKeyScope, Session, UserRole};
+use crate::repository::{ApiKeyRepository, UserRepository};
+use crate::token;
+use axum::{
+    body::to_bytes,
+    extract::{ConnectInfo, FromRef, FromRequestParts, Request},
+    http::{Extensions, HeaderMap, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use ipnet::IpNet;
+use serde::Deserialize;
+use std::marker::PhantomData;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Header a device (kiosk, mobile app) uses to identify itself
+pub const DEVICE_ID_HEADER: &str = "x-device-id";
+
+/// Header carrying the key for admin endpoints
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Header carrying the key of a machine-to-machine client
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the device token of a kiosk (see [`crate::kiosk`])
+pub const DEVICE_TOKEN_HEADER: &str = "x-device-token";
+
+/// Longest user agent stored; longer values are truncated
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Longest device id accepted; longer values are ignored
+pub const MAX_DEVICE_ID_LEN: usize = 255;
+
+/// Information about the client that sent a request, recorded for auditing
+///
+/// All fields are best effort and never reject a request:
+/// - `ip`: the peer address when the server is started with connect info; when
+///   the peer is a trusted proxy (see [`TrustedProxies`]), or there is no peer
+///   address because the request came through the Unix socket, the last address
+///   of `X-Forwarded-For` that is not a trusted proxy
+/// - `user_agent`: the `User-Agent` header, truncated to 512 characters
+/// - `device_id`: the `X-Device-Id` header, if at most 255 characters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientMetadata {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_id: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientMetadata
+where
+    TrustedProxies: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(
+            &parts.headers,
+            &parts.extensions,
+            &TrustedProxies::from_ref(state),
+        ))
+    }
+}
+
+impl ClientMetadata {
+    /// Read the client metadata from the headers and extensions of a request
+    /// (also used for gRPC calls, whose metadata are HTTP/2 headers)
+    #[must_use]
+    pub fn from_headers(
+        headers: &HeaderMap,
+        extensions: &Extensions,
+        trusted_proxies: &TrustedProxies,
+    ) -> Self {
+        let peer = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let ip = match peer {
+            Some(peer) if !trusted_proxies.contains(peer) => Some(peer),
+            _ => forwarded_for(headers, trusted_proxies).or(peer),
+        };
+
+        Self {
+            ip: ip.map(|ip| ip.to_string()),
+            user_agent: header_str(headers, "user-agent")
+                .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect()),
+            device_id: header_str(headers, DEVICE_ID_HEADER)
+                .filter(|id| id.len() <= MAX_DEVICE_ID_LEN)
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Reverse proxies whose `X-Forwarded-For` header is believed
+///
+/// Anyone can send the header, so it is only read when the peer of the
+/// connection is one of these networks; otherwise clients could choose the
+/// address recorded for them and reset their rate limit with every request.
+/// Configured as IP addresses or CIDR networks, e.g. `10.0.0.0/8,192.0.2.1`
+/// (see [`crate::config::ServerConfig`]); none are trusted by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct TrustedProxies(Arc<[IpNet]>);
+
+impl TrustedProxies {
+    /// Whether an address belongs to a trusted proxy
+    #[must_use]
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|network| network.contains(&ip))
+    }
+}
+
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    /// Parse a comma-separated list of addresses and networks
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+    }
+}
+
+impl TryFrom<Vec<String>> for TrustedProxies {
+    type Error = String;
+
+    fn try_from(entries: Vec<String>) -> Result<Self, Self::Error> {
+        entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| format!("{entry} is not an IP address or network"))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// The token of an `Authorization: Bearer` header, if present
+#[must_use]
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    header_str(headers, "authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// A non-empty, trimmed header value
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// The value of a cookie sent with the request, if present and non-empty
+#[must_use]
+pub fn cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+/// The originating client address from `X-Forwarded-For`
+///
+/// Each proxy appends the address it received the request from, so the client
+/// is the last address that is not a trusted proxy; addresses before it may
+/// have been sent by the client itself.
+fn forwarded_for(headers: &HeaderMap, trusted_proxies: &TrustedProxies) -> Option<IpAddr> {
+    let addresses = header_str(headers, "x-forwarded-for")?
+        .split(',')
+        .map(|address| address.trim().parse::<IpAddr>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    addresses
+        .iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(**ip))
+        .or_else(|| addresses.first())
+        .copied()
+}
+
+/// Bootstrap key for appointing the first admin, configured by
+/// `auth.admin_api_key` (`ADMIN_API_KEY`)
+///
+/// A shared key cannot be attributed to a person in the audit log or revoked
+/// per person, so it is accepted only by [`AdminOrBootstrapKey`]. Unset it once
+/// an admin exists; the bootstrap path is disabled when no key is configured.
+#[derive(Debug, Clone, Default)]
+pub struct AdminApiKey(Option<Arc<str>>);
+
+impl AdminApiKey {
+    /// Create a key; `None` disables the bootstrap path
+    #[must_use]
+    pub fn new(key: Option<&str>) -> Self {
+        Self(key.filter(|key| !key.is_empty()).map(Arc::from))
+    }
+
+    /// Check a presented key in constant time
+    pub(crate) fn matches(&self, presented: &str) -> bool {
+        self.0.as_deref().is_some_and(|key| {
+            key.len() == presented.len()
+                && key
+                    .bytes()
+                    .zip(presented.bytes())
+                    .fold(0, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        })
+    }
+}
+
+/// The user authenticated by the `Authorization: Bearer` access token, or
+/// else by the session cookie (see [`crate::session`])
+///
+/// The user is looked up on every request, so deleting or deactivating a user,
+/// changing their role or logging them out everywhere takes effect before their
+/// token expires. Rejects the request with `Unauthorized` if neither a token nor
+/// a session is presented, the token is invalid, expired or revoked, or the user
+/// no longer exists or is deactivated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthUser {
+    pub id: Uuid,
+    pub role: UserRole,
+}
+
+impl AuthUser {
+    /// Check that the user may read the records of another user
+    /// Members may read only their own records; managers and admins may read all.
+    ///
+    /// # Errors
+    /// Returns `Forbidden` if a member reads the records of another user
+    pub fn ensure_can_read(&self, user_id: Uuid) -> Result<(), AppError> {
+        if self.id == user_id || self.role.includes(UserRole::Manager) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(
+                "You can only access your own records".to_string(),
+            ))
+        }
+    }
+
+    /// Check that the user may record attendance for another user
+    /// Members may record only their own attendance; managers and admins may record for all.
+    ///
+    /// # Errors
+    /// Returns `Forbidden` if a member records attendance for another user
+    pub fn ensure_can_record(&self, user_id: Uuid) -> Result<(), AppError> {
+        if self.id == user_id || self.role.includes(UserRole::Manager) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(
+                "You can only record your own attendance".to_string(),
+            ))
+        }
+    }
+
+    /// Check that the user may change the profile of another user
+    /// Members and managers may change only their own profile; admins may change all.
+    ///
+    /// # Errors
+    /// Returns `Forbidden` if a member or manager changes another user
+    pub fn ensure_can_change(&self, user_id: Uuid) -> Result<(), AppError> {
+        if self.id == user_id || self.role.includes(UserRole::Admin) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden(
+                "You can only change your own profile".to_string(),
+            ))
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    AuthTokens: FromRef<S>,
+    UserRepository: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Already authenticated by `RequirePermission`
+        if let Some(user) = parts.extensions.get::<Self>() {
+            return Ok(*user);
+        }
+
+        let unauthorized = |msg: &str| AppError::Unauthorized(msg.to_string());
+
+        // Revoked sessions are deleted; tokens carry the version to compare
+        let (user_id, token_version) = match (
+            bearer_token(&parts.headers),
+            parts.extensions.get::<Session>(),
+        ) {
+            (Some(token), _) => {
+                let claims = AuthTokens::from_ref(state)
+                    .verify(token, Utc::now())
+                    .map_err(|e| unauthorized(&e.to_string()))?;
+                (claims.sub, Some(claims.ver))
+            }
+            (None, Some(session)) => (session.user_id, None),
+            (None, None) => {
+                return Err(unauthorized("A bearer token or session cookie is required"));
+            }
+        };
+
+        let user = UserRepository::from_ref(state)
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| unauthorized("User no longer exists"))?;
+        if !user.is_active {
+            return Err(unauthorized("User is deactivated"));
+        }
+        if token_version.is_some_and(|version| version != user.token_version) {
+            return Err(unauthorized("Access token has been revoked")
+                .with_code(ErrorCode::AccessTokenRevoked));
+        }
+
+        Ok(Self {
+            id: user.id,
+            role: user.role,
+        })
+    }
+}
+
+/// A minimum role for [`RequireRole`]
+pub trait RoleRequirement {
+    const ROLE: UserRole;
+}
+
+/// Requires the `admin` role
+#[derive(Debug, Clone, Copy)]
+pub struct Admin;
+
+impl RoleRequirement for Admin {
+    const ROLE: UserRole = UserRole::Admin;
+}
+
+/// Requires the `manager` role or higher
+#[derive(Debug, Clone, Copy)]
+pub struct Manager;
+
+impl RoleRequirement for Manager {
+    const ROLE: UserRole = UserRole::Manager;
+}
+
+/// Guard for endpoints restricted to a minimum role
+///
+/// Authenticates the user like [`AuthUser`], then rejects the request with
+/// `Forbidden` unless the user's role includes `R::ROLE`.
+#[derive(Debug, Clone, Copy)]
+pub struct RequireRole<R> {
+    pub user: AuthUser,
+    _role: PhantomData<R>,
+}
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    AuthTokens: FromRef<S>,
+    UserRepository: FromRef<S>,
+    S: Send + Sync,
+    R: RoleRequirement,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if !user.role.includes(R::ROLE) {
+            return Err(AppError::Forbidden(format!(
+                "The {} role is required",
+                R::ROLE
+            )));
+        }
+
+        Ok(Self {
+            user,
+            _role: PhantomData,
+        })
+    }
+}
+
+/// Guard for appointing admins
+///
+/// Accepts the `X-Admin-Key` header if it is present and matches the
+/// configured [`AdminApiKey`], so the first admin can be appointed before any
+/// user has the `admin` role; otherwise requires an admin like
+/// [`RequireRole<Admin>`]. Requests made with the key are logged, since the
+/// audit log cannot attribute them to a user.
+#[derive(Debug, Clone, Copy)]
+pub enum AdminOrBootstrapKey {
+    Admin(AuthUser),
+    BootstrapKey,
+}
+
+impl<S> FromRequestParts<S> for AdminOrBootstrapKey
+where
+    AdminApiKey: FromRef<S>,
+    AuthTokens: FromRef<S>,
+    UserRepository: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(presented) = header_str(&parts.headers, ADMIN_KEY_HEADER) {
+            if !AdminApiKey::from_ref(state).matches(presented) {
+                return Err(AppError::Unauthorized(
+                    "The admin key is invalid".to_string(),
+                ));
+            }
+            tracing::warn!(path = %parts.uri.path(), "Request authorized by the bootstrap admin key");
+            return Ok(Self::BootstrapKey);
+        }
+
+        RequireRole::<Admin>::from_request_parts(parts, state)
+            .await
+            .map(|admin| Self::Admin(admin.user))
+    }
+}
+
+/// A scope for [`RequireScope`]
+pub trait ScopeRequirement {
+    const SCOPE: ApiKeyScope;
+}
+
+/// Requires the `kiosk:clock` scope
+#[derive(Debug, Clone, Copy)]
+pub struct KioskClock;
+
+impl ScopeRequirement for KioskClock {
+    const SCOPE: ApiKeyScope = ApiKeyScope::KioskClock;
+}
+
+/// Requires the `payroll:read` scope
+#[derive(Debug, Clone, Copy)]
+pub struct PayrollRead;
+
+impl ScopeRequirement for PayrollRead {
+    const SCOPE: ApiKeyScope = ApiKeyScope::PayrollRead;
+}
+
+/// Guard for endpoints used by machine-to-machine clients
+///
+/// Rejects the request with `Unauthorized` unless the `X-Api-Key` header holds
+/// an API key that is neither revoked nor expired, and with `Forbidden` unless
+/// the key grants `R::SCOPE`. Every accepted request records when the key was
+/// last used.
+#[derive(Debug, Clone)]
+pub struct RequireScope<R> {
+    pub key: ApiKey,
+    _scope: PhantomData<R>,
+}
+
+impl<S, R> FromRequestParts<S> for RequireScope<R>
+where
+    ApiKeyRepository: FromRef<S>,
+    S: Send + Sync,
+    R: ScopeRequirement,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let key = authenticate_api_key(
+            &ApiKeyRepository::from_ref(state),
+            header_str(&parts.headers, API_KEY_HEADER),
+            R::SCOPE,
+        )
+        .await?;
+
+        Ok(Self {
+            key,
+            _scope: PhantomData,
+        })
+    }
+}
+
+/// Authenticate a presented API key that must grant `scope`, like
+/// [`RequireScope`]
+///
+/// # Errors
+/// Returns `Unauthorized` if no key is presented or the key is invalid,
+/// revoked or expired, and `Forbidden` if it lacks the scope
+pub(crate) async fn authenticate_api_key(
+    repo: &ApiKeyRepository,
+    presented: Option<&str>,
+    scope: ApiKeyScope,
+) -> Result<ApiKey, AppError> {
+    let presented =
+        presented.ok_or_else(|| AppError::Unauthorized("An API key is required".to_string()))?;
+    let key = repo
+        .authenticate(&token::hash_opaque(presented), Utc::now())
+        .await?
+        .ok_or_else(|| {
+            AppError::Unauthorized("API key is invalid, revoked or expired".to_string())
+        })?;
+    if !key.has_scope(scope) {
+        return Err(AppError::Forbidden(format!(
+            "The API key lacks the {scope} scope"
+        )));
+    }
+    Ok(key)
+}
+
+/// Guard for admin endpoints that machine-to-machine clients may also call
+///
+/// Accepts an API key granting `R::SCOPE` like [`RequireScope`] if the
+/// `X-Api-Key` header is present, and otherwise requires an admin like
+/// [`RequireRole<Admin>`].
+#[derive(Debug, Clone)]
+pub enum AdminOrScope<R> {
+    Admin(AuthUser),
+    ApiKey(RequireScope<R>),
+}
+
+impl<S, R> FromRequestParts<S> for AdminOrScope<R>
+where
+    ApiKeyRepository: FromRef<S>,
+    AuthTokens: FromRef<S>,
+    UserRepository: FromRef<S>,
+    S: Send + Sync,
+    R: ScopeRequirement,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if header_str(&parts.headers, API_KEY_HEADER).is_some() {
+            return RequireScope::from_request_parts(parts, state)
+                .await
+                .map(Self::ApiKey);
+        }
+        RequireRole::<Admin>::from_request_parts(parts, state)
+            .await
+            .map(|admin| Self::Admin(admin.user))
+    }
+}
+
+/// Guard for endpoints used by kiosk devices
+///
+/// Accepts an `X-Device-Token` header holding a device token issued by an
+/// admin, which must be valid and bound to the device named by the
+/// `X-Device-Id` header; otherwise an API key granting the `kiosk:clock` scope
+/// like [`RequireScope`]. Rejects the request with `Unauthorized` if the device
+/// token is invalid or expired, and with `Forbidden` if it belongs to another
+/// device.
+#[derive(Debug, Clone)]
+pub enum KioskDevice {
+    Token(DeviceClaims),
+    ApiKey(RequireScope<KioskClock>),
+}
+
+impl<S> FromRequestParts<S> for KioskDevice
+where
+    ApiKeyRepository: FromRef<S>,
+    KioskTokens: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(presented) = header_str(&parts.headers, DEVICE_TOKEN_HEADER) else {
+            return RequireScope::from_request_parts(parts, state)
+                .await
+                .map(Self::ApiKey);
+        };
+        let claims = KioskTokens::from_ref(state)
+            .verify_device(presented, Utc::now())
+            .map_err(|e| {
+                AppError::Unauthorized(match e {
+                    KioskTokenError::Expired => "Device token has expired".to_string(),
+                    KioskTokenError::Invalid(_) => "Device token is invalid".to_string(),
+                })
+            })?;
+        if header_str(&parts.headers, DEVICE_ID_HEADER) != Some(claims.device_id.as_str()) {
+            return Err(AppError::Forbidden(
+                "The device token belongs to another device".to_string(),
+            ));
+        }
+
+        Ok(Self::Token(claims))
+    }
+}
+
+/// Longest rejection message read from axum's plain-text rejection body
+const MAX_REJECTION_LEN: usize = 64 * 1024;
+
+/// Middleware turning axum's extractor rejections into `AppError` responses
+///
+/// `Json`, `Path`, `Query` and `Multipart` reject malformed requests with a
+/// plain-text body. Those responses are replaced by the JSON error envelope
+/// (see [`AppError::Rejected`]), keeping their status and message.
+pub async fn json_rejections(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+
+    let is_rejection = matches!(
+        response.status(),
+        StatusCode::BAD_REQUEST
+            | StatusCode::PAYLOAD_TOO_LARGE
+            | StatusCode::UNSUPPORTED_MEDIA_TYPE
+            | StatusCode::UNPROCESSABLE_ENTITY
+    ) && response.extensions().get::<ErrorDetails>().is_none()
+        && header_str(response.headers(), header::CONTENT_TYPE.as_str())
+            .is_some_and(|content_type| content_type.starts_with("text/plain"));
+    if !is_rejection {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let message = to_bytes(body, MAX_REJECTION_LEN).await.map_or_else(
+        |_| "The request could not be processed".to_string(),
+        |bytes| String::from_utf8_lossy(&bytes).into_owned(),
+    );
+    AppError::Rejected {
+        status: parts.status,
+        message,
+    }
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderValue, Request};
+
+    async fn metadata(request: Request<()>) -> ClientMetadata {
+        metadata_behind(request, "").await
+    }
+
+    async fn metadata_behind(request: Request<()>, trusted_proxies: &str) -> ClientMetadata {
+        let trusted_proxies: TrustedProxies = trusted_proxies.parse().unwrap();
+        let (mut parts, ()) = request.into_parts();
+        ClientMetadata::from_request_parts(&mut parts, &trusted_proxies)
+            .await
+            .unwrap()
+    }
+
+    fn from_peer(peer: [u8; 4], forwarded_for: &str) -> Request<()> {
+        let mut request = Request::builder()
+            .header("x-forwarded-for", forwarded_for)
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((peer, 54321))));
+        request
+    }
+
+    #[tokio::test]
+    async fn test_client_metadata_from_headers() {
+        let request = Request::builder()
+            .header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+            .header("user-agent", "Kiosk/1.0")
+            .header(DEVICE_ID_HEADER, "kiosk-01")
+            .body(())
+            .unwrap();
+
+        assert_eq!(
+            metadata_behind(request, "10.0.0.0/8").await,
+            ClientMetadata {
+                ip: Some("203.0.113.7".to_string()),
+                user_agent: Some("Kiosk/1.0".to_string()),
+                device_id: Some("kiosk-01".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_metadata_falls_back_to_peer_address() {
+        let metadata = metadata(from_peer([192, 0, 2, 1], "not-an-ip")).await;
+        assert_eq!(metadata.ip.as_deref(), Some("192.0.2.1"));
+        assert_eq!(metadata.user_agent, None);
+    }
+
+    #[tokio::test]
+    async fn test_client_metadata_believes_only_trusted_proxies() {
+        // Untrusted peers cannot choose their address
+        let spoofed = metadata(from_peer([192, 0, 2, 1], "203.0.113.7")).await;
+        // Addresses before the one the trusted proxy saw may be spoofed too
+        let proxied = metadata_behind(
+            from_peer([10, 0, 0, 1], "198.51.100.1, 203.0.113.7, 10.0.0.2"),
+            "10.0.0.0/8",
+        )
+        .await;
+        let all_trusted =
+            metadata_behind(from_peer([10, 0, 0, 1], "10.0.0.3, 10.0.0.2"), "10.0.0.0/8").await;
+
+        assert_eq!(spoofed.ip.as_deref(), Some("192.0.2.1"));
+        assert_eq!(proxied.ip.as_deref(), Some("203.0.113.7"));
+        assert_eq!(all_trusted.ip.as_deref(), Some("10.0.0.3"));
+    }
+
+    #[tokio::test]
+    async fn test_client_metadata_limits_lengths() {
+        let request = Request::builder()
+            .header(
+                "user-agent",
+                HeaderValue::from_str(&"a".repeat(600)).unwrap(),
+            )
+            .header(DEVICE_ID_HEADER, "d".repeat(300))
+            .body(())
+            .unwrap();
+
+        let metadata = metadata(request).await;
+        assert_eq!(metadata.user_agent.unwrap().len(), MAX_USER_AGENT_LEN);
+        assert_eq!(metadata.device_id, None);
+    }
+
+    #[test]
+    fn test_auth_user_can_read_own_records_only_as_member() {
+        let own = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let member = AuthUser {
+            id: own,
+            role: UserRole::Member,
+        };
+        let manager = AuthUser {
+            role: UserRole::Manager,
+            ..member
+        };
+
+        assert!(member.ensure_can_read(own).is_ok());
+        assert!(matches!(
+            member.ensure_can_read(other),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(manager.ensure_can_read(other).is_ok());
+    }
+
+    #[test]
+    fn test_auth_user_can_record_for_others_only_as_manager() {
+        let own = Uuid::new_v4();
+        let member = AuthUser {
+            id: own,
+            role: UserRole::Member,
+        };
+        let manager = AuthUser {
+            role: UserRole::Manager,
+            ..member
+        };
+
+        assert!(member.ensure_can_record(own).is_ok());
+        assert!(matches!(
+            member.ensure_can_record(Uuid::new_v4()),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(manager.ensure_can_record(Uuid::new_v4()).is_ok());
+    }
+
+    #[test]
+    fn test_auth_user_can_change_other_users_only_as_admin() {
+        let own = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let manager = AuthUser {
+            id: own,
+            role: UserRole::Manager,
+        };
+        let admin = AuthUser {
+            role: UserRole::Admin,
+            ..manager
+        };
+
+        assert!(manager.ensure_can_change(own).is_ok());
+        assert!(matches!(
+            manager.ensure_can_change(other),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(admin.ensure_can_change(other).is_ok());
+    }
+
+    #[test]
+    fn test_admin_api_key_matches() {
+        let key = AdminApiKey::new(Some("secret"));
+        assert!(key.matches("secret"));
+        assert!(!key.matches("secreT"));
+        assert!(!key.matches("secret2"));
+
+        let disabled = AdminApiKey::new(Some(""));
+        assert!(!disabled.matches(""));
+    }
+}
@@ -0,0 +1,158 @@
+//! `attendance.v1.AttendanceService`: record and read attendance events
+
+use super::proto::{self, attendance_service_server};
+use super::{authorize, parse_id, parse_timestamp, timestamp};
+use crate::error::AppError;
+use crate::extract::ClientMetadata;
+use crate::handlers::anomaly::detect_anomalies;
+use crate::handlers::attendance_event::{
+    AttendanceEventResponse, CreateAttendanceEventRequest, record_event,
+};
+use crate::models::{ApiKeyScope, AttendanceEvent, EventType};
+use crate::state::AppState;
+use crate::webhook::ATTENDANCE_EVENT_CREATED;
+use tonic::{Request, Response, Status};
+
+/// Implementation of `attendance.v1.AttendanceService`
+#[derive(Clone)]
+pub struct AttendanceService {
+    state: AppState,
+}
+
+impl AttendanceService {
+    #[must_use]
+    pub const fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl attendance_service_server::AttendanceService for AttendanceService {
+    async fn record_event(
+        &self,
+        request: Request<proto::RecordEventRequest>,
+    ) -> Result<Response<proto::AttendanceEvent>, Status> {
+        authorize(
+            &self.state,
+            request.metadata(),
+            ApiKeyScope::AttendanceWrite,
+        )
+        .await?;
+        let client = ClientMetadata::from_headers(
+            request.metadata().as_ref(),
+            request.extensions(),
+            &self.state.trusted_proxies,
+        );
+        let request = request.into_inner();
+
+        // Unspecified event types fail the validation shared with the HTTP API
+        let event_type = match request.event_type() {
+            proto::EventType::Unspecified => "",
+            proto::EventType::ClockIn => EventType::ClockIn.as_str(),
+            proto::EventType::ClockOut => EventType::ClockOut.as_str(),
+            proto::EventType::BreakStart => EventType::BreakStart.as_str(),
+            proto::EventType::BreakEnd => EventType::BreakEnd.as_str(),
+        };
+        let payload = CreateAttendanceEventRequest {
+            user_id: parse_id("user_id", &request.user_id)?,
+            event_type: event_type.to_string(),
+            event_time: parse_timestamp("event_time", request.event_time)?,
+            latitude: request.latitude,
+            longitude: request.longitude,
+            amends_event_id: request
+                .amends_event_id
+                .as_deref()
+                .map(|id| parse_id("amends_event_id", id))
+                .transpose()?,
+        };
+        tracing::debug!(user_id = %payload.user_id, event_type, "Recording attendance event over gRPC");
+
+        let state = &self.state;
+        let event = record_event(
+            &state.attendance_events,
+            &state.event_time_window,
+            payload,
+            client,
+        )
+        .await?;
+        detect_anomalies(
+            &state.attendance_events,
+            &state.attendance_anomalies,
+            &state.anomaly_rules,
+            &event,
+        )
+        .await;
+        state.webhook_dispatcher.notify(
+            ATTENDANCE_EVENT_CREATED,
+            &AttendanceEventResponse::from(event.clone()),
+        );
+        state
+            .presence
+            .publish(&state.attendance_events, event.user_id)
+            .await;
+
+        Ok(Response::new(event.into()))
+    }
+
+    async fn get_event(
+        &self,
+        request: Request<proto::GetEventRequest>,
+    ) -> Result<Response<proto::AttendanceEvent>, Status> {
+        authorize(&self.state, request.metadata(), ApiKeyScope::AttendanceRead).await?;
+        let id = parse_id("id", &request.get_ref().id)?;
+        tracing::debug!(event_id = %id, "Fetching attendance event over gRPC");
+
+        let event = self
+            .state
+            .attendance_events
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Attendance event with id {id} not found"))
+            })?;
+
+        Ok(Response::new(event.into()))
+    }
+
+    async fn list_user_events(
+        &self,
+        request: Request<proto::ListUserEventsRequest>,
+    ) -> Result<Response<proto::ListUserEventsResponse>, Status> {
+        authorize(&self.state, request.metadata(), ApiKeyScope::AttendanceRead).await?;
+        let user_id = parse_id("user_id", &request.get_ref().user_id)?;
+        let include_history = request.get_ref().include_history;
+        tracing::debug!(user_id = %user_id, include_history, "Fetching attendance events over gRPC");
+
+        let events = self
+            .state
+            .attendance_events
+            .find_by_user_id(user_id, include_history)
+            .await?;
+
+        Ok(Response::new(proto::ListUserEventsResponse {
+            events: events.into_iter().map(Into::into).collect(),
+        }))
+    }
+}
+
+impl From<AttendanceEvent> for proto::AttendanceEvent {
+    fn from(event: AttendanceEvent) -> Self {
+        let event_type = match event.event_type {
+            EventType::ClockIn => proto::EventType::ClockIn,
+            EventType::ClockOut => proto::EventType::ClockOut,
+            EventType::BreakStart => proto::EventType::BreakStart,
+            EventType::BreakEnd => proto::EventType::BreakEnd,
+        };
+        Self {
+            id: event.id.to_string(),
+            user_id: event.user_id.to_string(),
+            event_type: event_type.into(),
+            event_time: Some(timestamp(event.event_time)),
+            recorded_at: Some(timestamp(event.recorded_at)),
+            created_at: Some(timestamp(event.created_at)),
+            latitude: event.latitude,
+            longitude: event.longitude,
+            amends_event_id: event.amends_event_id.map(|id| id.to_string()),
+        }
+    }
+}
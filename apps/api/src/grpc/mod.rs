@@ -0,0 +1,161 @@
+//! gRPC service for internal services
+//!
+//! Exposes users and attendance events over gRPC, for internal services that
+//! prefer protobuf contracts to the JSON API. The contract is
+//! `proto/attendance/v1/attendance.proto`; `build.rs` generates the code in
+//! [`proto`].
+//!
+//! The server listens on its own port (`SERVER_GRPC_PORT`, see
+//! [`crate::config`]) and shares the [`AppState`] of the HTTP router, so both
+//! use the same repositories and validation. Calls authenticate with an API
+//! key in the `x-api-key` metadata granting the scope of the call
+//! (`users:read`, `attendance:read` or `attendance:write`). Errors map to the
+//! gRPC status closest to their HTTP status (e.g. `404` to `NOT_FOUND`).
+
+pub mod attendance;
+pub mod users;
+
+use crate::error::AppError;
+use crate::extract::{API_KEY_HEADER, authenticate_api_key};
+use crate::models::ApiKeyScope;
+use crate::state::AppState;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+use tonic::{Code, Status, metadata::MetadataMap, service::Routes};
+use tower_http::trace::TraceLayer;
+use uuid::Uuid;
+
+/// Code generated from `proto/attendance/v1/attendance.proto`
+#[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+pub mod proto {
+    tonic::include_proto!("attendance.v1");
+}
+
+/// Create the gRPC router, serving every service
+pub fn router(state: AppState) -> axum::Router {
+    Routes::new(proto::user_service_server::UserServiceServer::new(
+        users::UserService::new(state.clone()),
+    ))
+    .add_service(
+        proto::attendance_service_server::AttendanceServiceServer::new(
+            attendance::AttendanceService::new(state),
+        ),
+    )
+    .into_axum_router()
+    .layer(TraceLayer::new_for_grpc())
+}
+
+impl From<AppError> for Status {
+    fn from(error: AppError) -> Self {
+        let (status, _, message) = error.describe();
+        let code = match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+            StatusCode::FORBIDDEN => Code::PermissionDenied,
+            StatusCode::NOT_FOUND => Code::NotFound,
+            StatusCode::CONFLICT => Code::FailedPrecondition,
+            StatusCode::TOO_MANY_REQUESTS => Code::ResourceExhausted,
+            StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+            StatusCode::GATEWAY_TIMEOUT => Code::DeadlineExceeded,
+            _ => Code::Internal,
+        };
+        Self::new(code, message)
+    }
+}
+
+/// Authenticate a call by its `x-api-key` metadata
+///
+/// # Errors
+/// Returns `UNAUTHENTICATED` if no key is presented or the presented key is
+/// wrong, revoked or expired, and `PERMISSION_DENIED` if the key lacks `scope`
+async fn authorize(
+    state: &AppState,
+    metadata: &MetadataMap,
+    scope: ApiKeyScope,
+) -> Result<(), Status> {
+    let presented = metadata
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    authenticate_api_key(&state.api_keys, presented, scope).await?;
+    Ok(())
+}
+
+/// Parse an ID field
+///
+/// # Errors
+/// Returns `INVALID_ARGUMENT` if the field is not a UUID
+fn parse_id(field: &str, value: &str) -> Result<Uuid, Status> {
+    value
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("{field} must be a UUID")))
+}
+
+fn timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: time.timestamp(),
+        nanos: i32::try_from(time.timestamp_subsec_nanos()).unwrap_or(0),
+    }
+}
+
+/// Convert a required timestamp field
+///
+/// # Errors
+/// Returns `INVALID_ARGUMENT` if the field is missing or out of range
+fn parse_timestamp(field: &str, value: Option<Timestamp>) -> Result<DateTime<Utc>, Status> {
+    value
+        .and_then(|ts| DateTime::from_timestamp(ts.seconds, u32::try_from(ts.nanos).ok()?))
+        .ok_or_else(|| Status::invalid_argument(format!("{field} must be a valid timestamp")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_map_to_grpc_codes() {
+        let code = |error: AppError| Status::from(error).code();
+
+        assert_eq!(
+            code(AppError::ValidationError("bad".to_string())),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            code(AppError::NotFound("missing".to_string())),
+            Code::NotFound
+        );
+        assert_eq!(
+            code(AppError::Forbidden("no".to_string())),
+            Code::PermissionDenied
+        );
+        assert_eq!(
+            code(AppError::InternalServerError("boom".to_string())),
+            Code::Internal
+        );
+    }
+
+    #[test]
+    fn test_timestamps_round_trip() {
+        let time = "2025-11-05T09:00:00.123456789Z"
+            .parse::<DateTime<Utc>>()
+            .unwrap();
+
+        assert_eq!(
+            parse_timestamp("event_time", Some(timestamp(time))).unwrap(),
+            time
+        );
+        assert!(parse_timestamp("event_time", None).is_err());
+        assert!(
+            parse_timestamp(
+                "event_time",
+                Some(Timestamp {
+                    seconds: 0,
+                    nanos: -1
+                })
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! `attendance.v1.UserService`: read users
+
+use super::proto::{self, user_service_server};
+use super::{authorize, parse_id, timestamp};
+use crate::error::{AppError, ErrorCode};
+use crate::handlers::user::UserListQuery;
+use crate::models::{ApiKeyScope, User, UserRole};
+use crate::state::AppState;
+use tonic::{Request, Response, Status};
+
+/// Implementation of `attendance.v1.UserService`
+#[derive(Clone)]
+pub struct UserService {
+    state: AppState,
+}
+
+impl UserService {
+    #[must_use]
+    pub const fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[tonic::async_trait]
+impl user_service_server::UserService for UserService {
+    async fn get_user(
+        &self,
+        request: Request<proto::GetUserRequest>,
+    ) -> Result<Response<proto::User>, Status> {
+        authorize(&self.state, request.metadata(), ApiKeyScope::UsersRead).await?;
+        let id = parse_id("id", &request.get_ref().id)?;
+        tracing::debug!(user_id = %id, "Fetching user over gRPC");
+
+        let user = self.state.users.find_by_id(id).await?.ok_or_else(|| {
+            AppError::NotFound(format!("User with id {id} not found"))
+                .with_code(ErrorCode::UserNotFound)
+        })?;
+
+        Ok(Response::new(user.into()))
+    }
+
+    async fn list_users(
+        &self,
+        request: Request<proto::ListUsersRequest>,
+    ) -> Result<Response<proto::ListUsersResponse>, Status> {
+        authorize(&self.state, request.metadata(), ApiKeyScope::UsersRead).await?;
+        let request = request.into_inner();
+        tracing::debug!(q = ?request.q, page = ?request.page, "Listing users over gRPC");
+
+        let query = UserListQuery {
+            q: request.q,
+            sort: request.sort,
+            order: request.order,
+            page: request.page,
+            per_page: request.per_page,
+        };
+        let (q, sort, order, pagination) = query.validate()?;
+        let (users, total) = self
+            .state
+            .users
+            .search(q, sort, order, pagination.limit(), pagination.offset())
+            .await?;
+
+        Ok(Response::new(proto::ListUsersResponse {
+            users: users.into_iter().map(Into::into).collect(),
+            total: u64::try_from(total).unwrap_or(0),
+            page: pagination.page,
+            per_page: pagination.per_page,
+        }))
+    }
+}
+
+impl From<User> for proto::User {
+    fn from(user: User) -> Self {
+        let role = match user.role {
+            UserRole::Admin => proto::UserRole::Admin,
+            UserRole::Manager => proto::UserRole::Manager,
+            UserRole::Member => proto::UserRole::Member,
+        };
+        Self {
+            id: user.id.to_string(),
+            name: user.name,
+            email: user.email,
+            picture: user.picture,
+            role: role.into(),
+            is_active: user.is_active,
+            created_at: Some(timestamp(user.created_at)),
+            updated_at: Some(timestamp(user.updated_at)),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::attendance::anomaly::{AnomalyRules, RuleContext};
+use crate::error::{AppError, Result};
+use crate::extract::{AuthUser, Manager, RequireRole};
+use crate::models::{AnomalyKind, AttendanceAnomaly, AttendanceEvent, CreateAttendanceAnomaly};
+use crate::repository::{AttendanceAnomalyRepository, AttendanceEventRepository};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query parameters for listing anomalies
+#[derive(Debug, Deserialize)]
+pub struct ListAnomaliesQuery {
+    pub user_id: Option<Uuid>,
+    pub kind: Option<String>,
+}
+
+impl ListAnomaliesQuery {
+    /// Validate the list query
+    ///
+    /// Returns the parsed kind filter on success.
+    ///
+    /// # Errors
+    /// Returns validation error if the kind is not a known anomaly kind
+    fn validate(&self) -> Result<Option<AnomalyKind>> {
+        self.kind
+            .as_deref()
+            .map(str::parse::<AnomalyKind>)
+            .transpose()
+            .map_err(AppError::ValidationError)
+    }
+}
+
+/// Response payload for anomaly data
+#[derive(Debug, Serialize)]
+pub struct AttendanceAnomalyResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: AnomalyKind,
+    pub event_id: Uuid,
+    pub details: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl From<AttendanceAnomaly> for AttendanceAnomalyResponse {
+    fn from(anomaly: AttendanceAnomaly) -> Self {
+        Self {
+            id: anomaly.id,
+            user_id: anomaly.user_id,
+            kind: anomaly.kind,
+            event_id: anomaly.event_id,
+            details: anomaly.details,
+            detected_at: anomaly.detected_at,
+        }
+    }
+}
+
+/// GET `/api/attendance/anomalies?user_id=&kind=` - List detected attendance anomalies
+///
+/// Anomalies are returned most recent first.
+///
+/// Requires a bearer token of a user with the `manager` role or higher.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not a manager or admin
+/// Returns `ValidationError` if the kind filter is not a known anomaly kind
+/// Returns error if database operation fails
+pub async fn get_attendance_anomalies(
+    _manager: RequireRole<Manager>,
+    State(repo): State<AttendanceAnomalyRepository>,
+    Query(query): Query<ListAnomaliesQuery>,
+) -> Result<Json<Vec<AttendanceAnomalyResponse>>> {
+    tracing::debug!(user_id = ?query.user_id, kind = ?query.kind, "Listing attendance anomalies");
+
+    let kind = query.validate()?;
+    let anomalies = repo.list(query.user_id, kind).await?;
+
+    Ok(Json(anomalies.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/users/:id/attendance/anomalies?kind= - List anomalies detected for a user
+///
+/// Anomalies are returned most recent first.
+///
+/// Members can read only their own anomalies; managers and admins can read any
+/// user's anomalies.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the anomalies of another user
+/// Returns `ValidationError` if the kind filter is not a known anomaly kind
+/// Returns error if database operation fails
+pub async fn get_user_attendance_anomalies(
+    auth: AuthUser,
+    State(repo): State<AttendanceAnomalyRepository>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<ListAnomaliesQuery>,
+) -> Result<Json<Vec<AttendanceAnomalyResponse>>> {
+    tracing::debug!(user_id = %user_id, kind = ?query.kind, "Listing attendance anomalies for user");
+
+    auth.ensure_can_read(user_id)?;
+    let kind = query.validate()?;
+    let anomalies = repo.list(Some(user_id), kind).await?;
+
+    Ok(Json(anomalies.into_iter().map(Into::into).collect()))
+}
+
+/// Run the anomaly rules against a newly recorded event and store what they find
+///
+/// Detection is best effort: the event is already recorded, so failures are
+/// logged instead of failing the request.
+pub(crate) async fn detect_anomalies(
+    events: &AttendanceEventRepository,
+    anomalies: &AttendanceAnomalyRepository,
+    rules: &AnomalyRules,
+    event: &AttendanceEvent,
+) {
+    if let Err(e) = try_detect_anomalies(events, anomalies, rules, event).await {
+        tracing::error!(event_id = %event.id, error = %e, "Anomaly detection failed");
+    }
+}
+
+async fn try_detect_anomalies(
+    events: &AttendanceEventRepository,
+    anomalies: &AttendanceAnomalyRepository,
+    rules: &AnomalyRules,
+    event: &AttendanceEvent,
+) -> Result<()> {
+    let lookback = rules.lookback();
+    let history = events
+        .find_by_user_id_in_range(
+            event.user_id,
+            event.event_time - lookback,
+            event.event_time + lookback + chrono::Duration::seconds(1),
+        )
+        .await?;
+
+    let detected = rules.evaluate(&RuleContext {
+        event,
+        history: &history,
+    });
+    for anomaly in detected {
+        tracing::warn!(
+            user_id = %event.user_id,
+            event_id = %event.id,
+            kind = %anomaly.kind,
+            "Attendance anomaly detected: {}",
+            anomaly.details
+        );
+        anomalies
+            .create(CreateAttendanceAnomaly {
+                user_id: event.user_id,
+                kind: anomaly.kind,
+                event_id: event.id,
+                details: anomaly.details,
+            })
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::models::{ApiKey, ApiKeyScope, CreateApiKey};
+use crate::repository::ApiKeyRepository;
+use crate::token;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Marks API keys, so leaked keys are easy to recognize
+const KEY_PREFIX: &str = "ak_";
+
+/// Characters of a key shown when listing keys (the marker and 8 more)
+const DISPLAYED_KEY_LEN: usize = KEY_PREFIX.len() + 8;
+
+/// Request payload for creating an API key
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Scopes granted to the key (see [`ApiKeyScope`])
+    pub scopes: Vec<String>,
+    /// The key cannot be used after this time; `None` = does not expire
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response payload for API key data
+/// The key itself is only returned when the key is created
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// First characters of the key, to tell keys apart
+    pub key_prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    /// Whether the key is neither revoked nor expired
+    pub is_active: bool,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            is_active: key.is_active(Utc::now()),
+            id: key.id,
+            name: key.name,
+            key_prefix: key.key_prefix,
+            scopes: key.scopes,
+            expires_at: key.expires_at,
+            revoked_at: key.revoked_at,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+/// Response payload for a newly created API key, including the key
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    /// Sent by the client in the `X-Api-Key` header
+    pub key: String,
+}
+
+impl CreateApiKeyRequest {
+    /// Validate the create API key request
+    ///
+    /// Returns the parsed scopes, without duplicates, on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Name is empty or exceeds 100 characters
+    /// - No scope is given, or a scope is unknown
+    /// - The expiry is not in the future
+    fn validate(&self, now: DateTime<Utc>) -> Result<Vec<ApiKeyScope>> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Name cannot be empty".to_string(),
+            ));
+        }
+        if self.name.chars().count() > 100 {
+            return Err(AppError::ValidationError(
+                "Name must be 100 characters or less".to_string(),
+            ));
+        }
+
+        let mut scopes = Vec::new();
+        for scope in &self.scopes {
+            let scope = scope
+                .parse::<ApiKeyScope>()
+                .map_err(AppError::ValidationError)?;
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(AppError::ValidationError(
+                "At least one scope is required".to_string(),
+            ));
+        }
+
+        if self.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AppError::ValidationError(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+
+        Ok(scopes)
+    }
+}
+
+/// GET /api/admin/api-keys - List all API keys, newest first
+///
+/// Revoked and expired keys are included.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns error if database operation fails
+pub async fn get_api_keys(
+    _admin: RequireRole<Admin>,
+    State(repo): State<ApiKeyRepository>,
+) -> Result<Json<Vec<ApiKeyResponse>>> {
+    tracing::debug!("Listing API keys");
+
+    let keys = repo.list().await?;
+
+    Ok(Json(keys.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/admin/api-keys/:id - Get an API key by ID
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the API key with the specified ID does not exist
+pub async fn get_api_key(
+    _admin: RequireRole<Admin>,
+    State(repo): State<ApiKeyRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiKeyResponse>> {
+    tracing::debug!(api_key_id = %id, "Fetching API key");
+
+    let key = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("API key with id {id} not found")))?;
+
+    Ok(Json(key.into()))
+}
+
+/// POST /api/admin/api-keys - Create an API key for a machine-to-machine client
+///
+/// Responds with `201 Created`. The response contains the key, which is not
+/// returned again.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns error if database operation fails
+pub async fn create_api_key(
+    _admin: RequireRole<Admin>,
+    State(repo): State<ApiKeyRepository>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!(name = %payload.name, "Creating API key");
+
+    let scopes = payload.validate(Utc::now())?;
+
+    let key = format!("{KEY_PREFIX}{}", token::new_opaque());
+    let api_key = repo
+        .create(CreateApiKey {
+            name: payload.name.trim().to_string(),
+            key_prefix: key[..DISPLAYED_KEY_LEN].to_string(),
+            key_hash: token::hash_opaque(&key),
+            scopes,
+            expires_at: payload.expires_at,
+        })
+        .await?;
+
+    let location = format!("/api/admin/api-keys/{}", api_key.id);
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, location)],
+        Json(CreatedApiKeyResponse {
+            api_key: api_key.into(),
+            key,
+        }),
+    ))
+}
+
+/// DELETE /api/admin/api-keys/:id - Revoke an API key
+///
+/// The key stops working immediately. It stays listed, so its use can still be
+/// audited. Responds with `204 No Content`.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the API key with the specified ID does not exist
+pub async fn revoke_api_key(
+    _admin: RequireRole<Admin>,
+    State(repo): State<ApiKeyRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    tracing::debug!(api_key_id = %id, "Revoking API key");
+
+    repo.revoke(id, Utc::now()).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,306 @@
+use super::attendance_event::AttendanceEventResponse;
+use crate::error::{AppError, Result};
+use crate::extract::{AuthUser, Manager, RequireRole};
+use crate::models::{
+    AttendanceCorrection, CorrectionDecision, CorrectionStatus, CreateAttendanceCorrection,
+    EventType, UserRole,
+};
+use crate::presence::PresenceHub;
+use crate::repository::{AttendanceCorrectionRepository, AttendanceEventRepository};
+use crate::service::CorrectionService;
+use crate::webhook::{ATTENDANCE_EVENT_CREATED, WebhookDispatcher};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request payload for submitting a correction to an attendance event
+#[derive(Debug, Deserialize)]
+pub struct CreateAttendanceCorrectionRequest {
+    pub event_id: Uuid,
+    pub proposed_event_type: String,
+    pub proposed_event_time: DateTime<Utc>,
+    pub reason: String,
+}
+
+/// Request payload for approving or rejecting a correction request
+/// The approver is the authenticated manager, never a field of the payload.
+#[derive(Debug, Deserialize)]
+pub struct CorrectionDecisionRequest {
+    pub comment: Option<String>,
+}
+
+/// Query parameters for listing correction requests
+#[derive(Debug, Deserialize)]
+pub struct ListAttendanceCorrectionsQuery {
+    pub user_id: Option<Uuid>,
+    pub status: Option<String>,
+}
+
+/// Response payload for correction request data
+#[derive(Debug, Serialize)]
+pub struct AttendanceCorrectionResponse {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub proposed_event_type: EventType,
+    pub proposed_event_time: DateTime<Utc>,
+    pub reason: String,
+    pub status: CorrectionStatus,
+    pub created_at: DateTime<Utc>,
+    pub approver_id: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decision_comment: Option<String>,
+    pub applied_event_id: Option<Uuid>,
+}
+
+impl From<AttendanceCorrection> for AttendanceCorrectionResponse {
+    fn from(correction: AttendanceCorrection) -> Self {
+        Self {
+            id: correction.id,
+            event_id: correction.event_id,
+            user_id: correction.user_id,
+            proposed_event_type: correction.proposed_event_type,
+            proposed_event_time: correction.proposed_event_time,
+            reason: correction.reason,
+            status: correction.status,
+            created_at: correction.created_at,
+            approver_id: correction.approver_id,
+            decided_at: correction.decided_at,
+            decision_comment: correction.decision_comment,
+            applied_event_id: correction.applied_event_id,
+        }
+    }
+}
+
+impl CreateAttendanceCorrectionRequest {
+    /// Validate the create correction request
+    ///
+    /// Returns the parsed proposed event type on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Proposed event type is not a valid event type
+    /// - Reason is empty or only whitespace
+    /// - Reason exceeds 1000 characters
+    fn validate(&self) -> Result<EventType> {
+        let event_type = self
+            .proposed_event_type
+            .parse::<EventType>()
+            .map_err(AppError::ValidationError)?;
+
+        if self.reason.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Reason cannot be empty".to_string(),
+            ));
+        }
+        if self.reason.len() > 1000 {
+            return Err(AppError::ValidationError(
+                "Reason must be 1000 characters or less".to_string(),
+            ));
+        }
+
+        Ok(event_type)
+    }
+}
+
+impl CorrectionDecisionRequest {
+    /// Validate the decision request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Comment exceeds 1000 characters
+    fn validate(&self) -> Result<()> {
+        if let Some(comment) = &self.comment
+            && comment.len() > 1000
+        {
+            return Err(AppError::ValidationError(
+                "Comment must be 1000 characters or less".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Convert the request into a decision made by the approver
+    fn into_decision(self, approver_id: Uuid) -> CorrectionDecision {
+        CorrectionDecision {
+            approver_id,
+            comment: self.comment,
+        }
+    }
+}
+
+/// POST /api/attendance-corrections - Submit a correction for an attendance event
+///
+/// Members can correct only their own events; managers and admins can correct any user's events.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `NotFound` if the referenced attendance event does not exist
+/// Returns `Forbidden` if a member submits a correction for another user's event
+/// Returns error if database operation fails
+pub async fn create_attendance_correction(
+    auth: AuthUser,
+    State(repo): State<AttendanceCorrectionRepository>,
+    State(events): State<AttendanceEventRepository>,
+    Json(payload): Json<CreateAttendanceCorrectionRequest>,
+) -> Result<Json<AttendanceCorrectionResponse>> {
+    tracing::debug!(event_id = %payload.event_id, "Creating attendance correction");
+
+    // Validation
+    let proposed_event_type = payload.validate()?;
+
+    let event = events.find_by_id(payload.event_id).await?.ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Attendance event with id {} not found",
+            payload.event_id
+        ))
+    })?;
+    auth.ensure_can_record(event.user_id)?;
+
+    let create_correction = CreateAttendanceCorrection {
+        event_id: payload.event_id,
+        proposed_event_type,
+        proposed_event_time: payload.proposed_event_time,
+        reason: payload.reason,
+    };
+
+    let correction = repo.create(create_correction).await?;
+
+    Ok(Json(correction.into()))
+}
+
+/// GET /api/attendance-corrections - List correction requests
+///
+/// Supports optional `user_id` and `status` filters.
+/// Members see only their own corrections; managers and admins can list any user's corrections.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the status filter is invalid
+/// Returns `Forbidden` if a member filters by another user
+/// Returns error if database operation fails
+pub async fn get_attendance_corrections(
+    auth: AuthUser,
+    State(repo): State<AttendanceCorrectionRepository>,
+    Query(query): Query<ListAttendanceCorrectionsQuery>,
+) -> Result<Json<Vec<AttendanceCorrectionResponse>>> {
+    tracing::debug!(?query, "Listing attendance corrections");
+
+    let user_id = match query.user_id {
+        Some(user_id) => {
+            auth.ensure_can_read(user_id)?;
+            Some(user_id)
+        }
+        None if auth.role.includes(UserRole::Manager) => None,
+        None => Some(auth.id),
+    };
+
+    let status = query
+        .status
+        .as_deref()
+        .map(str::parse::<CorrectionStatus>)
+        .transpose()
+        .map_err(AppError::ValidationError)?;
+
+    let corrections = repo.list(user_id, status).await?;
+
+    Ok(Json(corrections.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/attendance-corrections/:id - Get a specific correction request by ID
+///
+/// Members can read only their own corrections; managers and admins can read any user's corrections.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `NotFound` error if the correction with the specified ID does not exist
+/// Returns `Forbidden` if a member reads another user's correction
+pub async fn get_attendance_correction(
+    auth: AuthUser,
+    State(repo): State<AttendanceCorrectionRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AttendanceCorrectionResponse>> {
+    tracing::debug!(correction_id = %id, "Fetching attendance correction");
+
+    let correction = repo.find_by_id(id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("Attendance correction with id {id} not found"))
+    })?;
+    auth.ensure_can_read(correction.user_id)?;
+
+    Ok(Json(correction.into()))
+}
+
+/// POST /api/attendance-corrections/:id/approve - Approve a pending correction
+///
+/// Appends a compensating attendance event with the proposed type and time.
+/// Webhook and presence subscribers are notified of the appended event like of
+/// any recorded event.
+/// Managers and admins only: the authenticated user is recorded as the approver.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not a manager or admin
+/// Returns `ValidationError` if the payload validation fails or the approver is the requester
+/// Returns `NotFound` if the correction does not exist
+/// Returns `BadRequest` if the correction has already been decided
+/// Returns `Forbidden` if the user of the correction is deactivated
+/// Returns error if database operation fails
+pub async fn approve_attendance_correction(
+    RequireRole { user: approver, .. }: RequireRole<Manager>,
+    State(service): State<CorrectionService>,
+    State(events): State<AttendanceEventRepository>,
+    State(webhooks): State<WebhookDispatcher>,
+    State(presence): State<PresenceHub>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CorrectionDecisionRequest>,
+) -> Result<Json<AttendanceCorrectionResponse>> {
+    tracing::debug!(correction_id = %id, approver_id = %approver.id, "Approving attendance correction");
+
+    // Validation
+    payload.validate()?;
+
+    let (correction, event) = service
+        .approve(id, payload.into_decision(approver.id))
+        .await?;
+
+    let event = AttendanceEventResponse::from(event);
+    webhooks.notify(ATTENDANCE_EVENT_CREATED, &event);
+    presence.publish(&events, event.user_id).await;
+
+    Ok(Json(correction.into()))
+}
+
+/// POST /api/attendance-corrections/:id/reject - Reject a pending correction
+///
+/// Managers and admins only: the authenticated user is recorded as the approver.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not a manager or admin
+/// Returns `ValidationError` if the payload validation fails or the approver is the requester
+/// Returns `NotFound` if the correction does not exist
+/// Returns `BadRequest` if the correction has already been decided
+/// Returns error if database operation fails
+pub async fn reject_attendance_correction(
+    RequireRole { user: approver, .. }: RequireRole<Manager>,
+    State(service): State<CorrectionService>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CorrectionDecisionRequest>,
+) -> Result<Json<AttendanceCorrectionResponse>> {
+    tracing::debug!(correction_id = %id, approver_id = %approver.id, "Rejecting attendance correction");
+
+    // Validation
+    payload.validate()?;
+
+    let correction = service
+        .reject(id, payload.into_decision(approver.id))
+        .await?;
+
+    Ok(Json(correction.into()))
+}
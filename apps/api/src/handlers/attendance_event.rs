@@ -0,0 +1,574 @@
+use super::anomaly::detect_anomalies;
+use crate::attendance::anomaly::AnomalyRules;
+use crate::attendance::breaks::validate_break_event;
+use crate::attendance::event_time::EventTimeWindow;
+use crate::attendance::import::{RowError, parse_csv};
+use crate::error::{AppError, Result};
+use crate::export;
+use crate::extract::{Admin, AuthUser, ClientMetadata, Manager, RequireRole};
+use crate::models::{AttendanceEvent, CreateAttendanceEvent, EventType, normalize_email};
+use crate::pagination::{Paginated, Pagination};
+use crate::presence::PresenceHub;
+use crate::repository::{AttendanceAnomalyRepository, AttendanceEventRepository, UserRepository};
+use crate::webhook::{ATTENDANCE_EVENT_CREATED, WebhookDispatcher};
+use axum::{
+    Json,
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Request payload for recording a new attendance event
+#[derive(Debug, Deserialize)]
+pub struct CreateAttendanceEventRequest {
+    pub user_id: Uuid,
+    pub event_type: String,
+    pub event_time: DateTime<Utc>,
+    /// Latitude in decimal degrees; must be given together with `longitude`
+    pub latitude: Option<f64>,
+    /// Longitude in decimal degrees; must be given together with `latitude`
+    pub longitude: Option<f64>,
+    /// Earlier event of the same user that this event supersedes
+    pub amends_event_id: Option<Uuid>,
+}
+
+/// Query parameters for listing a user's attendance events
+#[derive(Debug, Deserialize)]
+pub struct UserEventsQuery {
+    /// Also return events superseded by an amendment (default: false)
+    #[serde(default)]
+    pub include_history: bool,
+    /// 1-based page number (default: 1)
+    pub page: Option<u32>,
+    /// Events per page (1-100, default: 20)
+    pub per_page: Option<u32>,
+}
+
+/// Query parameters for exporting attendance events
+#[derive(Debug, Deserialize)]
+pub struct ExportEventsQuery {
+    /// Only export events of this user
+    pub user_id: Option<Uuid>,
+    /// Inclusive lower bound of `event_time`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound of `event_time`
+    pub to: Option<DateTime<Utc>>,
+    /// Also export events superseded by an amendment (default: false)
+    #[serde(default)]
+    pub include_history: bool,
+}
+
+impl ExportEventsQuery {
+    /// Validate the export query
+    ///
+    /// # Errors
+    /// Returns validation error if `from` is not before `to`
+    fn validate(&self) -> Result<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to)
+            && from >= to
+        {
+            return Err(AppError::ValidationError(
+                "from must be before to".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Admin view of an attendance event, including client metadata
+#[derive(Debug, Serialize)]
+pub struct AttendanceEventDetailResponse {
+    #[serde(flatten)]
+    pub event: AttendanceEventResponse,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_id: Option<String>,
+}
+
+impl From<AttendanceEvent> for AttendanceEventDetailResponse {
+    fn from(event: AttendanceEvent) -> Self {
+        let client_ip = event.client_ip.clone();
+        let user_agent = event.user_agent.clone();
+        let device_id = event.device_id.clone();
+        Self {
+            event: event.into(),
+            client_ip,
+            user_agent,
+            device_id,
+        }
+    }
+}
+
+/// Maximum number of data rows accepted in a single import file
+const MAX_IMPORT_ROWS: usize = 10_000;
+
+/// Response payload for a bulk import
+///
+/// Either all rows were imported (`errors` is empty) or none was.
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub imported: u64,
+    pub errors: Vec<RowError>,
+}
+
+/// Response payload for attendance event data
+#[derive(Debug, Serialize)]
+pub struct AttendanceEventResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub event_type: EventType,
+    pub event_time: DateTime<Utc>,
+    pub recorded_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub amends_event_id: Option<Uuid>,
+}
+
+impl From<AttendanceEvent> for AttendanceEventResponse {
+    fn from(event: AttendanceEvent) -> Self {
+        Self {
+            id: event.id,
+            user_id: event.user_id,
+            event_type: event.event_type,
+            event_time: event.event_time,
+            recorded_at: event.recorded_at,
+            created_at: event.created_at,
+            latitude: event.latitude,
+            longitude: event.longitude,
+            amends_event_id: event.amends_event_id,
+        }
+    }
+}
+
+impl CreateAttendanceEventRequest {
+    /// Validate the create attendance event request
+    ///
+    /// Returns the parsed event type on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Event type is not one of `clock_in`, `clock_out`, `break_start`, `break_end`
+    /// - Only one of latitude and longitude is given
+    /// - Latitude is outside -90 to 90 or longitude is outside -180 to 180
+    fn validate(&self) -> Result<EventType> {
+        let event_type = self
+            .event_type
+            .parse::<EventType>()
+            .map_err(AppError::ValidationError)?;
+
+        match (self.latitude, self.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                if !(-90.0..=90.0).contains(&latitude) {
+                    return Err(AppError::ValidationError(
+                        "Latitude must be between -90 and 90".to_string(),
+                    ));
+                }
+                if !(-180.0..=180.0).contains(&longitude) {
+                    return Err(AppError::ValidationError(
+                        "Longitude must be between -180 and 180".to_string(),
+                    ));
+                }
+            }
+            (None, None) => {}
+            _ => {
+                return Err(AppError::ValidationError(
+                    "Latitude and longitude must be given together".to_string(),
+                ));
+            }
+        }
+
+        Ok(event_type)
+    }
+}
+
+/// Check that a break event pairs with the user's state at the event time
+///
+/// When the event amends an earlier one, the amended event is ignored, as it
+/// will no longer be effective.
+///
+/// # Errors
+/// Returns `ValidationError` if a break starts while not clocked in or already on
+/// a break, or ends without a matching break start
+pub(crate) async fn check_break_pairing(
+    repo: &AttendanceEventRepository,
+    user_id: Uuid,
+    event_type: EventType,
+    event_time: DateTime<Utc>,
+    amends_event_id: Option<Uuid>,
+) -> Result<()> {
+    if matches!(event_type, EventType::BreakStart | EventType::BreakEnd) {
+        let previous = repo
+            .find_latest_before(user_id, event_time, amends_event_id)
+            .await?;
+        validate_break_event(previous.map(|e| e.event_type), event_type)
+            .map_err(AppError::ValidationError)?;
+    }
+    Ok(())
+}
+
+/// Validate and store a new attendance event
+///
+/// Shared by [`create_attendance_event`] and the gRPC service, which run the
+/// anomaly rules and notify subscribers afterwards.
+///
+/// # Errors
+/// Returns the validation and database errors of [`create_attendance_event`]
+pub(crate) async fn record_event(
+    repo: &AttendanceEventRepository,
+    window: &EventTimeWindow,
+    payload: CreateAttendanceEventRequest,
+    client: ClientMetadata,
+) -> Result<AttendanceEvent> {
+    // Validation
+    let event_type = payload.validate()?;
+    window
+        .check(payload.event_time, Utc::now())
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    check_break_pairing(
+        repo,
+        payload.user_id,
+        event_type,
+        payload.event_time,
+        payload.amends_event_id,
+    )
+    .await?;
+
+    let create_event = CreateAttendanceEvent {
+        user_id: payload.user_id,
+        event_type,
+        event_time: payload.event_time,
+        latitude: payload.latitude,
+        longitude: payload.longitude,
+        client_ip: client.ip,
+        user_agent: client.user_agent,
+        device_id: client.device_id,
+        amends_event_id: payload.amends_event_id,
+    };
+
+    repo.create(create_event).await
+}
+
+/// POST /api/attendance-events - Record a new attendance event
+///
+/// The client's IP address, user agent and device id are recorded with the event.
+/// With `amends_event_id`, the new event supersedes an earlier event of the same
+/// user; the earlier event is kept but no longer effective.
+/// The anomaly rules run against the recorded event; detected anomalies are stored
+/// but do not reject the event. Webhook subscribers are notified in the background.
+///
+/// Members can record only their own events; managers and admins can record
+/// events of any user.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member records an event of another user
+/// Returns `ValidationError` if the payload validation fails, if `event_time` is
+/// outside the deployment's `EventTimeWindow` (too far in the future or older than
+/// the backfill window), or if a break event does not follow the user's current
+/// state (a break can only start while clocked in and can only end after a
+/// matching break start)
+/// Returns `NotFound` if the referenced user or amended event does not exist
+/// Returns `Forbidden` if the user is deactivated
+/// Returns `BadRequest` if the amended event has already been superseded
+/// Returns error if database operation fails
+#[allow(clippy::too_many_arguments)]
+pub async fn create_attendance_event(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    State(anomalies): State<AttendanceAnomalyRepository>,
+    State(rules): State<AnomalyRules>,
+    State(window): State<EventTimeWindow>,
+    State(webhooks): State<WebhookDispatcher>,
+    State(presence): State<PresenceHub>,
+    client: ClientMetadata,
+    Json(payload): Json<CreateAttendanceEventRequest>,
+) -> Result<Json<AttendanceEventResponse>> {
+    tracing::debug!(
+        user_id = %payload.user_id,
+        event_type = %payload.event_type,
+        "Creating new attendance event"
+    );
+
+    auth.ensure_can_record(payload.user_id)?;
+    let event = record_event(&repo, &window, payload, client).await?;
+    detect_anomalies(&repo, &anomalies, &rules, &event).await;
+
+    let response = AttendanceEventResponse::from(event);
+    webhooks.notify(ATTENDANCE_EVENT_CREATED, &response);
+    presence.publish(&repo, response.user_id).await;
+
+    Ok(Json(response))
+}
+
+/// POST /api/attendance-events/import - Bulk import attendance events from CSV
+///
+/// Accepts a `multipart/form-data` upload with the CSV in a `file` field. The CSV
+/// needs `email`, `event_type` and `event_time` columns (see
+/// [`parse_csv`]). Rows are validated up front and every invalid row is
+/// reported with its line number; events are only inserted if the whole file is
+/// valid, in a single statement. Break pairing is not checked, as legacy data is
+/// imported as-is. The uploader's client metadata is recorded on every event.
+///
+/// Responds with `200 OK` and the number of imported events, or `400 Bad Request`
+/// and the per-row errors if any row is invalid.
+///
+/// Requires a bearer token of a user with the `manager` role or higher.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not a manager or admin
+/// Returns `BadRequest` if the upload has no `file` field or is not valid CSV
+/// Returns `ValidationError` if the file has no data rows or too many rows
+/// Returns error if database operation fails
+pub async fn import_attendance_events(
+    _manager: RequireRole<Manager>,
+    State(repo): State<AttendanceEventRepository>,
+    State(users): State<UserRepository>,
+    client: ClientMetadata,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ImportReport>)> {
+    let mut data = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {e}")))?
+    {
+        if field.name() == Some("file") {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {e}")))?;
+            data = Some(bytes);
+            break;
+        }
+    }
+    let data = data.ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+
+    let (rows, mut errors) = parse_csv(&data).map_err(AppError::BadRequest)?;
+    let total = rows.len() + errors.len();
+    if total == 0 {
+        return Err(AppError::ValidationError(
+            "Import file has no data rows".to_string(),
+        ));
+    }
+    if total > MAX_IMPORT_ROWS {
+        return Err(AppError::ValidationError(format!(
+            "Import file must have {MAX_IMPORT_ROWS} rows or less"
+        )));
+    }
+    tracing::debug!(rows = total, "Importing attendance events");
+
+    let mut emails: Vec<String> = rows.iter().map(|row| row.email.clone()).collect();
+    emails.sort();
+    emails.dedup();
+    let user_ids = users.find_ids_by_emails(&emails).await?;
+    let ids: Vec<Uuid> = user_ids.values().copied().collect();
+    let deactivated = users.find_deactivated_ids(&ids).await?;
+
+    let mut events = Vec::with_capacity(rows.len());
+    for row in rows {
+        match user_ids.get(&normalize_email(&row.email)) {
+            Some(user_id) if deactivated.contains(user_id) => errors.push(RowError::new(
+                row.line,
+                format!("User with email {} is deactivated", row.email),
+            )),
+            Some(&user_id) => events.push(CreateAttendanceEvent {
+                user_id,
+                event_type: row.event_type,
+                event_time: row.event_time,
+                latitude: None,
+                longitude: None,
+                client_ip: client.ip.clone(),
+                user_agent: client.user_agent.clone(),
+                device_id: client.device_id.clone(),
+                amends_event_id: None,
+            }),
+            None => errors.push(RowError::new(
+                row.line,
+                format!("User with email {} not found", row.email),
+            )),
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.line);
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            Json(ImportReport {
+                imported: 0,
+                errors,
+            }),
+        ));
+    }
+
+    let imported = repo.create_many(&events).await?;
+    tracing::info!(imported, "Imported attendance events");
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportReport {
+            imported,
+            errors: Vec::new(),
+        }),
+    ))
+}
+
+/// GET /api/attendance-events/:id - Get a specific attendance event by ID
+///
+/// Members can read only their own events; managers and admins can read any event.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads an event of another user
+/// Returns `NotFound` error if the attendance event with the specified ID does not exist
+pub async fn get_attendance_event(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AttendanceEventResponse>> {
+    tracing::debug!(event_id = %id, "Fetching attendance event");
+
+    let event = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Attendance event with id {id} not found")))?;
+    auth.ensure_can_read(event.user_id)?;
+
+    Ok(Json(event.into()))
+}
+
+/// GET /api/admin/attendance-events/:id - Get an attendance event with client metadata
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the attendance event with the specified ID does not exist
+pub async fn get_attendance_event_detail(
+    _admin: RequireRole<Admin>,
+    State(repo): State<AttendanceEventRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AttendanceEventDetailResponse>> {
+    tracing::debug!(event_id = %id, "Fetching attendance event detail");
+
+    let event = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Attendance event with id {id} not found")))?;
+
+    Ok(Json(event.into()))
+}
+
+/// Number of encoded rows buffered between the database and a slow client
+const EXPORT_BUFFER_ROWS: usize = 256;
+
+/// GET /api/admin/attendance-events/export.ndjson?user_id=&from=&to=&include_history= - Export attendance events
+///
+/// Streams the events of all users (or of `user_id`) as NDJSON, one admin view
+/// of an event per line, ordered by `event_time`. Rows are read from the
+/// database while the response is written, so memory use does not grow with the
+/// size of the export. Superseded events are left out unless `include_history=true`.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// A database error before the first row is returned as an error response; a
+/// later error aborts the response, so a truncated export never ends cleanly.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the time range is invalid
+/// Returns error if the database query fails before the first row
+pub async fn export_attendance_events_ndjson(
+    _admin: RequireRole<Admin>,
+    State(repo): State<AttendanceEventRepository>,
+    Query(query): Query<ExportEventsQuery>,
+) -> Result<impl IntoResponse> {
+    query.validate()?;
+    tracing::debug!(user_id = ?query.user_id, from = ?query.from, to = ?query.to, "Exporting attendance events");
+
+    // The row stream borrows the repository, so it is drained by its own task;
+    // the bounded channel pauses the query while the client is slow
+    let (tx, mut rx) = mpsc::channel::<Result<Vec<u8>>>(EXPORT_BUFFER_ROWS);
+    tokio::spawn(async move {
+        let events = repo.stream_all(query.user_id, query.from, query.to, query.include_history);
+        let mut events = std::pin::pin!(events);
+        while let Some(row) = events.next().await {
+            let line = row.and_then(|event| {
+                Ok(export::ndjson::line(&AttendanceEventDetailResponse::from(
+                    event,
+                ))?)
+            });
+            let failed = line.is_err();
+            if let Err(e) = &line {
+                tracing::error!(error = %e, "Attendance event export failed");
+            }
+            // The client has gone away
+            if tx.send(line).await.is_err() || failed {
+                return;
+            }
+        }
+    });
+
+    let first = rx.recv().await.transpose()?;
+    let rest = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|line| (line, rx))
+    });
+    let body = Body::from_stream(stream::iter(first.map(Ok)).chain(rest));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, export::ndjson::CONTENT_TYPE),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"attendance-events.ndjson\"",
+            ),
+        ],
+        body,
+    ))
+}
+
+/// GET `/api/users/:id/attendance-events?include_history=&page=&per_page=` - Get attendance events for a user
+///
+/// Events are returned most recent first, a page at a time. Only effective
+/// events are returned unless `include_history=true`, which adds the events
+/// superseded by amendments.
+///
+/// Members can read only their own events; managers and admins can read any
+/// user's events.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the events of another user
+/// Returns `ValidationError` if the pagination parameters are invalid
+/// Returns an error if the database query fails
+pub async fn get_user_attendance_events(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<UserEventsQuery>,
+) -> Result<Paginated<AttendanceEventResponse>> {
+    tracing::debug!(user_id = %user_id, include_history = query.include_history, page = ?query.page, "Fetching attendance events for user");
+
+    auth.ensure_can_read(user_id)?;
+    let pagination = Pagination::from_query(query.page, query.per_page)?;
+    let (events, total) = repo
+        .find_page_by_user_id(
+            user_id,
+            query.include_history,
+            pagination.limit(),
+            pagination.offset(),
+        )
+        .await?;
+
+    Ok(Paginated::page(events, pagination, total))
+}
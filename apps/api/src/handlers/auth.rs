@@ -0,0 +1,467 @@
+use super::user::UserResponse;
+use crate::auth::AuthTokens;
+use crate::error::{AppError, ErrorCode, Result};
+use crate::extract;
+use crate::extract::{AuthUser, ClientMetadata};
+use crate::mail::{Email, Mailer};
+use crate::models::{CreateUser, LoginOutcome, Session, User};
+use crate::oauth::{self, GoogleOAuth, GoogleProfile};
+use crate::password::{self, LockoutPolicy, ResetSettings};
+use crate::repository::{PasswordResetRepository, SessionRepository, UserRepository};
+use crate::session::{self, SESSION_COOKIE, SessionSettings};
+use crate::token;
+use crate::validation;
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request payload for registering with a password
+#[derive(Deserialize)]
+pub struct RegisterRequest {
+    pub name: String,
+    pub email: String,
+    pub password: String,
+    pub picture: Option<String>,
+}
+
+/// Request payload for logging in
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// Request payload for requesting a password reset
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+/// Request payload for setting a new password with a reset token
+#[derive(Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Query parameters of the redirect back from Google
+#[derive(Deserialize)]
+pub struct GoogleCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set instead of `code` when the user denied access
+    pub error: Option<String>,
+}
+
+/// Response payload for a successful registration or login
+#[derive(Debug, Serialize)]
+pub struct AuthResponse {
+    /// Bearer token for the `Authorization` header
+    pub access_token: String,
+    /// Always `Bearer`
+    pub token_type: &'static str,
+    pub expires_at: DateTime<Utc>,
+    pub user: UserResponse,
+}
+
+impl RegisterRequest {
+    /// Validate the register request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Name is empty or exceeds 100 characters
+    /// - Email is empty, not a valid email address (see [`validation::is_email`])
+    ///   or exceeds 255 characters
+    /// - Password does not meet the password rules (see [`password::validate`])
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Name cannot be empty".to_string(),
+            ));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::ValidationError(
+                "Name must be 100 characters or less".to_string(),
+            ));
+        }
+
+        validation::email(&self.email)?;
+
+        password::validate(&self.password)
+    }
+}
+
+/// POST /api/auth/register - Create a user who logs in with a password
+///
+/// Responds like a login, so the new user is logged in right away.
+///
+/// # Errors
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `Conflict` if an active user already uses the email address
+/// Returns error if hashing the password or the database operation fails
+pub async fn register(
+    State(repo): State<UserRepository>,
+    State(tokens): State<AuthTokens>,
+    State(sessions): State<SessionRepository>,
+    State(settings): State<SessionSettings>,
+    client: ClientMetadata,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<Response> {
+    tracing::debug!(email = %payload.email, "Registering user");
+
+    payload.validate()?;
+
+    let user = repo
+        .create(CreateUser {
+            name: payload.name,
+            email: payload.email,
+            picture: payload.picture,
+            password: Some(payload.password),
+        })
+        .await?;
+    tracing::info!(user_id = %user.id, "User registered");
+
+    sign_in(user, &tokens, &sessions, &settings, &client).await
+}
+
+/// POST /api/auth/login - Log in with an email address and password
+///
+/// Responds with an access token for the `Authorization` header and also sets
+/// a session cookie (see [`crate::session`]), so browsers can use either.
+///
+/// Repeated failed logins lock the account for a while (see
+/// [`password::LockoutPolicy`]); administrators can unlock it earlier.
+///
+/// # Errors
+/// Returns `Unauthorized` if the email address or password is wrong, or the
+/// user has no password
+/// Returns `AccountLocked` (`423 Locked`, with `Retry-After`) if the account is
+/// locked after too many failed logins
+/// Returns error if database operation fails
+pub async fn login(
+    State(repo): State<UserRepository>,
+    State(tokens): State<AuthTokens>,
+    State(sessions): State<SessionRepository>,
+    State(settings): State<SessionSettings>,
+    State(policy): State<LockoutPolicy>,
+    client: ClientMetadata,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Response> {
+    tracing::debug!(email = %payload.email, "Logging in");
+
+    // Overlong passwords are not hashed, to bound the work per request
+    let now = Utc::now();
+    let outcome = if payload.password.chars().count() > password::MAX_LENGTH {
+        LoginOutcome::Failed
+    } else {
+        repo.authenticate(payload.email.trim(), &payload.password, policy, now)
+            .await?
+    };
+
+    match outcome {
+        LoginOutcome::Succeeded(user) => {
+            sign_in(user, &tokens, &sessions, &settings, &client).await
+        }
+        LoginOutcome::Failed => Err(AppError::Unauthorized(
+            "Invalid email or password".to_string(),
+        )
+        .with_code(ErrorCode::InvalidCredentials)),
+        LoginOutcome::Locked { until } => Err(AppError::AccountLocked {
+            retry_after: u64::try_from((until - now).num_seconds() + 1).unwrap_or(1),
+        }),
+    }
+}
+
+/// POST /api/auth/logout - End the session of the session cookie
+///
+/// Always responds with `204 No Content` and removes the cookie. Access tokens
+/// stay valid until they expire; use `POST /api/auth/logout-all` to revoke them.
+///
+/// # Errors
+/// Returns error if database operation fails
+pub async fn logout(
+    State(sessions): State<SessionRepository>,
+    State(settings): State<SessionSettings>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse> {
+    if let Some(session_token) = extract::cookie(&headers, SESSION_COOKIE)
+        && sessions.delete(&token::hash_opaque(session_token)).await?
+    {
+        tracing::info!("Logged out");
+    }
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, settings.clear_cookie())],
+    ))
+}
+
+/// POST /api/auth/logout-all - Log the authenticated user out everywhere
+///
+/// Revokes every access token issued to the user so far and ends all of their
+/// cookie sessions, e.g. after a device is lost. Responds with `204 No Content`
+/// and removes the cookie; the user has to log in again.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token or session cookie is missing or invalid
+/// Returns error if database operation fails
+pub async fn logout_all(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+    State(settings): State<SessionSettings>,
+) -> Result<impl IntoResponse> {
+    repo.revoke_tokens(auth.id).await?;
+    tracing::info!(user_id = %auth.id, "Logged out everywhere");
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, settings.clear_cookie())],
+    ))
+}
+
+/// Response payload of the CSRF token endpoint
+#[derive(Serialize)]
+pub struct CsrfTokenResponse {
+    pub csrf_token: String,
+}
+
+/// GET /api/auth/csrf - Get the CSRF token of the session cookie
+///
+/// Clients authenticated by the session cookie send the token in the
+/// `X-CSRF-Token` header with every `POST`, `PUT`, `PATCH` and `DELETE`
+/// request. The token stays the same for the whole session.
+///
+/// # Errors
+/// Returns `Unauthorized` if the session cookie is missing, unknown or expired
+pub async fn get_csrf_token(
+    session: Option<Extension<Session>>,
+    headers: HeaderMap,
+) -> Result<Json<CsrfTokenResponse>> {
+    let session_token = extract::cookie(&headers, SESSION_COOKIE)
+        .filter(|_| session.is_some())
+        .ok_or_else(|| AppError::Unauthorized("A session cookie is required".to_string()))?;
+
+    Ok(Json(CsrfTokenResponse {
+        csrf_token: session::csrf_token(session_token),
+    }))
+}
+
+/// Log a user in: issue an access token and start a cookie session
+async fn sign_in(
+    user: User,
+    tokens: &AuthTokens,
+    sessions: &SessionRepository,
+    settings: &SessionSettings,
+    client: &ClientMetadata,
+) -> Result<Response> {
+    let now = Utc::now();
+    let session_token = token::new_opaque();
+    sessions
+        .create(
+            user.id,
+            &token::hash_opaque(&session_token),
+            client,
+            now,
+            settings.ttl,
+        )
+        .await?;
+
+    let token = tokens.issue(user.id, user.token_version, now);
+    Ok((
+        [(header::SET_COOKIE, settings.cookie(&session_token))],
+        Json(AuthResponse {
+            access_token: token.token,
+            token_type: "Bearer",
+            expires_at: token.expires_at,
+            user: user.into(),
+        }),
+    )
+        .into_response())
+}
+
+/// GET /api/auth/google - Start signing in with Google
+///
+/// Redirects to Google's consent page, which redirects back to
+/// `GET /api/auth/google/callback`.
+///
+/// # Errors
+/// Returns `NotFound` if Google sign-in is not configured
+pub async fn google_sign_in(State(google): State<Option<GoogleOAuth>>) -> Result<Response> {
+    let google = google_configured(google)?;
+
+    let state = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    Ok((
+        [(header::SET_COOKIE, google.state_cookie(&state))],
+        Redirect::to(&google.authorization_url(&state)),
+    )
+        .into_response())
+}
+
+/// GET /api/auth/google/callback - Finish signing in with Google
+///
+/// Signs in the user linked to the Google account. Otherwise the account is
+/// linked to the active user with its verified email address, or a new user is
+/// created for it. Responds like a login, including the session cookie.
+///
+/// # Errors
+/// Returns `NotFound` if Google sign-in is not configured
+/// Returns `Unauthorized` if the user denied access, the state does not match
+/// the sign-in started by this browser, Google rejects the code, the Google
+/// account has no verified email address or the user is deactivated
+/// Returns `BadRequest` if the code is missing
+/// Returns `Conflict` if the user with the email address is linked to another
+/// Google account
+/// Returns error if Google cannot be reached or database operation fails
+pub async fn google_callback(
+    State(google): State<Option<GoogleOAuth>>,
+    State(repo): State<UserRepository>,
+    State(tokens): State<AuthTokens>,
+    State(sessions): State<SessionRepository>,
+    State(settings): State<SessionSettings>,
+    client: ClientMetadata,
+    headers: HeaderMap,
+    Query(query): Query<GoogleCallbackQuery>,
+) -> Result<Response> {
+    let google = google_configured(google)?;
+
+    if let Some(error) = query.error {
+        tracing::info!(%error, "Google sign-in was not completed");
+        return Err(AppError::Unauthorized(format!(
+            "Google sign-in was not completed: {error}"
+        )));
+    }
+    let expected = oauth::state_from_cookies(&headers);
+    if expected.is_none() || query.state.as_deref() != expected {
+        return Err(AppError::Unauthorized("Invalid OAuth state".to_string()));
+    }
+    let code = query
+        .code
+        .ok_or_else(|| AppError::BadRequest("Missing authorization code".to_string()))?;
+
+    let profile = google.fetch_profile(&code).await?;
+    let user = google_user(&repo, &profile).await?;
+    if !user.is_active {
+        return Err(AppError::Unauthorized("User is deactivated".to_string()));
+    }
+    tracing::info!(user_id = %user.id, "Signed in with Google");
+
+    let mut response = sign_in(user, &tokens, &sessions, &settings, &client).await?;
+    if let Ok(cookie) = HeaderValue::from_str(&google.clear_state_cookie()) {
+        response.headers_mut().append(header::SET_COOKIE, cookie);
+    }
+    Ok(response)
+}
+
+fn google_configured(google: Option<GoogleOAuth>) -> Result<GoogleOAuth> {
+    google.ok_or_else(|| AppError::NotFound("Google sign-in is not configured".to_string()))
+}
+
+/// The user signing in with a Google account, linking or creating one if needed
+async fn google_user(repo: &UserRepository, profile: &GoogleProfile) -> Result<User> {
+    if let Some(user) = repo.find_by_google_sub(&profile.sub).await? {
+        return Ok(user);
+    }
+
+    let email = profile.verified_email().ok_or_else(|| {
+        AppError::Unauthorized("The Google account has no verified email address".to_string())
+    })?;
+    let user = if let Some(user) = repo.find_by_email(email).await? {
+        user
+    } else {
+        let user = repo
+            .create(CreateUser {
+                name: profile.name.clone().unwrap_or_else(|| email.to_string()),
+                email: email.to_string(),
+                picture: profile.picture.clone(),
+                password: None,
+            })
+            .await?;
+        tracing::info!(user_id = %user.id, "User registered with Google");
+        user
+    };
+    repo.link_google(user.id, &profile.sub).await?;
+    Ok(user)
+}
+
+/// POST /api/auth/forgot-password - Email a password reset token
+///
+/// Always responds with `202 Accepted`, whether or not the email address is
+/// registered, so the endpoint cannot be used to find accounts. A user is sent
+/// at most three reset emails per hour; further requests are ignored.
+///
+/// # Errors
+/// Returns error if database operation fails
+pub async fn forgot_password(
+    State(repo): State<PasswordResetRepository>,
+    State(settings): State<ResetSettings>,
+    State(mailer): State<Mailer>,
+    Json(payload): Json<ForgotPasswordRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>)> {
+    tracing::debug!("Password reset requested");
+
+    let token = token::new_opaque();
+    let recipient = repo
+        .issue(
+            payload.email.trim(),
+            &token::hash_opaque(&token),
+            Utc::now(),
+            settings.ttl,
+            settings.max_per_hour,
+        )
+        .await?;
+
+    // Sent in the background, so the response time does not reveal whether a
+    // user was found
+    if let Some(recipient) = recipient {
+        mailer.send_in_background(Email {
+            to: recipient.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hello {},\n\n{}",
+                recipient.name,
+                settings.email_body(&token)
+            ),
+        });
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({
+            "message": "If the email address is registered, a reset link has been sent"
+        })),
+    ))
+}
+
+/// POST /api/auth/reset-password - Set a new password with a reset token
+///
+/// The token can be used once; all other unused tokens of the user stop working.
+///
+/// # Errors
+/// Returns `ValidationError` if the new password does not meet the password rules
+/// Returns `BadRequest` if the token is unknown, used or expired
+/// Returns error if hashing the password or the database operation fails
+pub async fn reset_password(
+    State(repo): State<PasswordResetRepository>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!("Resetting password");
+
+    password::validate(&payload.new_password)?;
+
+    repo.reset(
+        &token::hash_opaque(payload.token.trim()),
+        &payload.new_password,
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Password reset successfully"
+    })))
+}
@@ -0,0 +1,51 @@
+use crate::health::{ComponentHealth, HealthCheck, HealthStatus};
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+/// Response payload for liveness checks
+#[derive(Debug, Serialize)]
+pub struct LivenessResponse {
+    pub status: HealthStatus,
+}
+
+/// Status of each component checked for readiness
+#[derive(Debug, Serialize)]
+pub struct ReadinessComponents {
+    pub database: ComponentHealth,
+}
+
+/// Response payload for readiness checks
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    /// `ok` only if every component is
+    pub status: HealthStatus,
+    pub components: ReadinessComponents,
+}
+
+/// GET /health/live - Check that the process serves requests
+pub async fn liveness() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: HealthStatus::Ok,
+    })
+}
+
+/// GET /health/ready - Check that the API can serve traffic
+///
+/// Answers `503 Service Unavailable` with the failing components when a
+/// component (e.g. the database) is unavailable.
+pub async fn readiness(State(health): State<HealthCheck>) -> (StatusCode, Json<ReadinessResponse>) {
+    let database = health.database().await;
+
+    let status = database.status;
+    let code = match status {
+        HealthStatus::Ok => StatusCode::OK,
+        HealthStatus::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (
+        code,
+        Json(ReadinessResponse {
+            status,
+            components: ReadinessComponents { database },
+        }),
+    )
+}
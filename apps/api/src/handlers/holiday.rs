@@ -0,0 +1,304 @@
+use crate::attendance;
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::models::{CreateHoliday, Holiday, UpdateHoliday};
+use crate::repository::HolidayRepository;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Maximum number of holidays in a single bulk request (one per day of a leap year)
+const MAX_BULK_HOLIDAYS: usize = 366;
+
+/// Query parameters for listing holidays
+#[derive(Debug, Deserialize)]
+pub struct HolidayQuery {
+    /// Calendar year (defaults to the current year in the business timezone)
+    pub year: Option<i32>,
+}
+
+/// Request payload for creating a holiday
+#[derive(Debug, Deserialize)]
+pub struct CreateHolidayRequest {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// Request payload for creating a year's holidays at once
+#[derive(Debug, Deserialize)]
+pub struct BulkCreateHolidaysRequest {
+    pub year: i32,
+    pub holidays: Vec<CreateHolidayRequest>,
+}
+
+/// Request payload for updating a holiday
+#[derive(Debug, Deserialize)]
+pub struct UpdateHolidayRequest {
+    pub date: Option<NaiveDate>,
+    pub name: Option<String>,
+}
+
+/// Response payload for holiday data
+#[derive(Debug, Serialize)]
+pub struct HolidayResponse {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Holiday> for HolidayResponse {
+    fn from(holiday: Holiday) -> Self {
+        Self {
+            id: holiday.id,
+            date: holiday.date,
+            name: holiday.name,
+            created_at: holiday.created_at,
+            updated_at: holiday.updated_at,
+        }
+    }
+}
+
+impl CreateHolidayRequest {
+    /// Validate the create holiday request
+    ///
+    /// # Errors
+    /// Returns validation error if the name is empty or exceeds 100 characters
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)
+    }
+}
+
+impl BulkCreateHolidaysRequest {
+    /// Validate the bulk create request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - No holiday is given, or more than 366
+    /// - A holiday falls outside the given year
+    /// - Two holidays share a date
+    /// - A name is empty or exceeds 100 characters
+    fn validate(&self) -> Result<()> {
+        if self.holidays.is_empty() || self.holidays.len() > MAX_BULK_HOLIDAYS {
+            return Err(AppError::ValidationError(format!(
+                "Between 1 and {MAX_BULK_HOLIDAYS} holidays are required"
+            )));
+        }
+
+        let mut dates = HashSet::new();
+        for holiday in &self.holidays {
+            holiday.validate()?;
+            if holiday.date.year() != self.year {
+                return Err(AppError::ValidationError(format!(
+                    "Holiday {} is not in {}",
+                    holiday.date, self.year
+                )));
+            }
+            if !dates.insert(holiday.date) {
+                return Err(AppError::ValidationError(format!(
+                    "Duplicate holiday on {}",
+                    holiday.date
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl UpdateHolidayRequest {
+    /// Validate the update holiday request
+    ///
+    /// # Errors
+    /// Returns validation error if the name is provided but invalid
+    fn validate(&self) -> Result<()> {
+        if let Some(name) = &self.name {
+            validate_name(name)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::ValidationError(
+            "Name cannot be empty".to_string(),
+        ));
+    }
+    if name.len() > 100 {
+        return Err(AppError::ValidationError(
+            "Name must be 100 characters or less".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/holidays?year= - List the holidays of a year
+///
+/// # Errors
+/// Returns `ValidationError` if the year is invalid
+/// Returns error if database operation fails
+pub async fn get_holidays(
+    State(repo): State<HolidayRepository>,
+    Query(query): Query<HolidayQuery>,
+) -> Result<Json<Vec<HolidayResponse>>> {
+    let year = query
+        .year
+        .unwrap_or_else(|| attendance::local_date(Utc::now()).year());
+    tracing::debug!(year, "Listing holidays");
+
+    let invalid_year = || AppError::ValidationError(format!("Invalid year: {year}"));
+    let from = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid_year)?;
+    let to = NaiveDate::from_ymd_opt(year + 1, 1, 1).ok_or_else(invalid_year)?;
+    let holidays = repo.list(from, to).await?;
+
+    Ok(Json(holidays.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/holidays/:id - Get a specific holiday by ID
+///
+/// # Errors
+/// Returns `NotFound` error if the holiday with the specified ID does not exist
+pub async fn get_holiday(
+    State(repo): State<HolidayRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HolidayResponse>> {
+    tracing::debug!(holiday_id = %id, "Fetching holiday");
+
+    let holiday = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Holiday with id {id} not found")))?;
+
+    Ok(Json(holiday.into()))
+}
+
+/// POST /api/holidays - Create a new holiday
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `BadRequest` if a holiday already exists on the date
+/// Returns error if database operation fails
+pub async fn create_holiday(
+    _admin: RequireRole<Admin>,
+    State(repo): State<HolidayRepository>,
+    Json(payload): Json<CreateHolidayRequest>,
+) -> Result<Json<HolidayResponse>> {
+    tracing::debug!(date = %payload.date, "Creating holiday");
+
+    payload.validate()?;
+
+    let holiday = repo
+        .create(CreateHoliday {
+            date: payload.date,
+            name: payload.name.trim().to_string(),
+        })
+        .await?;
+
+    Ok(Json(holiday.into()))
+}
+
+/// POST /api/holidays/bulk - Create a year's holidays in one request
+///
+/// Either all holidays are created or none.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `BadRequest` if a holiday already exists on one of the dates
+/// Returns error if database operation fails
+pub async fn create_holidays_bulk(
+    _admin: RequireRole<Admin>,
+    State(repo): State<HolidayRepository>,
+    Json(payload): Json<BulkCreateHolidaysRequest>,
+) -> Result<Json<Vec<HolidayResponse>>> {
+    tracing::debug!(
+        year = payload.year,
+        count = payload.holidays.len(),
+        "Creating holidays in bulk"
+    );
+
+    payload.validate()?;
+
+    let holidays: Vec<CreateHoliday> = payload
+        .holidays
+        .into_iter()
+        .map(|holiday| CreateHoliday {
+            date: holiday.date,
+            name: holiday.name.trim().to_string(),
+        })
+        .collect();
+    let created = repo.create_many(&holidays).await?;
+
+    Ok(Json(created.into_iter().map(Into::into).collect()))
+}
+
+/// PUT /api/holidays/:id - Update an existing holiday
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `NotFound` if the holiday with the specified ID does not exist
+/// Returns `BadRequest` if another holiday already exists on the new date
+/// Returns error if database operation fails
+pub async fn update_holiday(
+    _admin: RequireRole<Admin>,
+    State(repo): State<HolidayRepository>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateHolidayRequest>,
+) -> Result<Json<HolidayResponse>> {
+    tracing::debug!(holiday_id = %id, "Updating holiday");
+
+    payload.validate()?;
+
+    let holiday = repo
+        .update(
+            id,
+            UpdateHoliday {
+                date: payload.date,
+                name: payload.name.map(|name| name.trim().to_string()),
+            },
+        )
+        .await?;
+
+    Ok(Json(holiday.into()))
+}
+
+/// DELETE /api/holidays/:id - Delete a holiday
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the holiday with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn delete_holiday(
+    _admin: RequireRole<Admin>,
+    State(repo): State<HolidayRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!(holiday_id = %id, "Deleting holiday");
+
+    repo.delete(id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Holiday with id {id} deleted successfully")
+    })))
+}
@@ -0,0 +1,187 @@
+use super::user::UserResponse;
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::invitation::{DEFAULT_TTL_HOURS, MAX_TTL_HOURS};
+use crate::models::{AcceptInvitation, CreateInvitation, Invitation, UserRole};
+use crate::repository::InvitationRepository;
+use crate::token;
+use crate::validation;
+use axum::{Json, extract::State};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Request payload for inviting a person
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitationRequest {
+    pub email: String,
+    pub role: UserRole,
+    /// Hours until the invitation expires (1-720, default: 168)
+    pub expires_in_hours: Option<u32>,
+}
+
+/// Request payload for accepting an invitation
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitationRequest {
+    /// Token handed to the invited person
+    pub token: String,
+    pub name: String,
+    pub picture: Option<String>,
+}
+
+/// Response payload for invitation data
+#[derive(Debug, Serialize)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Invitation> for InvitationResponse {
+    fn from(invitation: Invitation) -> Self {
+        Self {
+            id: invitation.id,
+            email: invitation.email,
+            role: invitation.role,
+            expires_at: invitation.expires_at,
+            accepted_at: invitation.accepted_at,
+            user_id: invitation.user_id,
+            created_at: invitation.created_at,
+        }
+    }
+}
+
+/// Response payload for a new invitation, including its token
+#[derive(Debug, Serialize)]
+pub struct CreatedInvitationResponse {
+    #[serde(flatten)]
+    pub invitation: InvitationResponse,
+    pub token: String,
+}
+
+impl CreateInvitationRequest {
+    /// Validate the create invitation request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Email is empty, not a valid email address (see [`validation::is_email`])
+    ///   or exceeds 255 characters
+    /// - `expires_in_hours` is outside 1-720
+    fn validate(&self) -> Result<()> {
+        validation::email(&self.email)?;
+
+        if let Some(hours) = self.expires_in_hours
+            && !(1..=MAX_TTL_HOURS).contains(&hours)
+        {
+            return Err(AppError::ValidationError(format!(
+                "expires_in_hours must be between 1 and {MAX_TTL_HOURS}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl AcceptInvitationRequest {
+    /// Validate the accept invitation request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Token is empty
+    /// - Name is empty or exceeds 100 characters
+    fn validate(&self) -> Result<()> {
+        if self.token.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Token cannot be empty".to_string(),
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Name cannot be empty".to_string(),
+            ));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::ValidationError(
+                "Name must be 100 characters or less".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// POST /api/invitations - Invite a person to join
+///
+/// The response contains the invitation token, which is not returned again; it
+/// is to be handed to the invited person. Inviting an email address with a
+/// pending invitation replaces that invitation.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `Conflict` if an active user already uses the email address
+/// Returns error if database operation fails
+pub async fn create_invitation(
+    _admin: RequireRole<Admin>,
+    State(repo): State<InvitationRepository>,
+    Json(payload): Json<CreateInvitationRequest>,
+) -> Result<Json<CreatedInvitationResponse>> {
+    tracing::debug!(email = %payload.email, role = %payload.role, "Creating invitation");
+
+    payload.validate()?;
+
+    let token = token::new_opaque();
+    let hours = payload.expires_in_hours.unwrap_or(DEFAULT_TTL_HOURS);
+    let created = repo
+        .create(CreateInvitation {
+            email: payload.email.trim().to_string(),
+            role: payload.role,
+            token_hash: token::hash_opaque(&token),
+            expires_at: Utc::now() + Duration::hours(i64::from(hours)),
+        })
+        .await?;
+
+    Ok(Json(CreatedInvitationResponse {
+        invitation: created.into(),
+        token,
+    }))
+}
+
+/// POST /api/invitations/accept - Accept an invitation and become a user
+///
+/// The invited person completes their profile; the user is created with the
+/// email address of the invitation.
+///
+/// # Errors
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `NotFound` if no pending invitation has the token
+/// Returns `BadRequest` if the invitation has expired
+/// Returns `Conflict` if an active user already uses the email address
+/// Returns error if database operation fails
+pub async fn accept_invitation(
+    State(repo): State<InvitationRepository>,
+    Json(payload): Json<AcceptInvitationRequest>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!("Accepting invitation");
+
+    payload.validate()?;
+
+    let user = repo
+        .accept(
+            AcceptInvitation {
+                token_hash: token::hash_opaque(payload.token.trim()),
+                name: payload.name.trim().to_string(),
+                picture: payload.picture,
+            },
+            Utc::now(),
+        )
+        .await?;
+
+    Ok(Json(user.into()))
+}
@@ -0,0 +1,242 @@
+use super::anomaly::detect_anomalies;
+use super::attendance_event::{AttendanceEventResponse, check_break_pairing};
+use crate::attendance::anomaly::AnomalyRules;
+use crate::error::{AppError, ErrorCode, Result};
+use crate::extract::{
+    Admin, AuthUser, ClientMetadata, KioskDevice, MAX_DEVICE_ID_LEN, RequireRole,
+};
+use crate::kiosk::{KioskTokens, MAX_DEVICE_TOKEN_TTL_MINUTES};
+use crate::models::{CreateAttendanceEvent, EventType};
+use crate::presence::PresenceHub;
+use crate::repository::{AttendanceAnomalyRepository, AttendanceEventRepository, UserRepository};
+use crate::webhook::{ATTENDANCE_EVENT_CREATED, WebhookDispatcher};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Response payload for an issued kiosk token
+#[derive(Debug, Serialize)]
+pub struct KioskTokenResponse {
+    /// Payload to encode as a QR code
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request payload submitted by a kiosk after scanning a QR code
+#[derive(Debug, Deserialize)]
+pub struct KioskClockRequest {
+    pub token: String,
+    pub event_type: String,
+}
+
+impl KioskClockRequest {
+    /// Validate the kiosk clock request
+    ///
+    /// Returns the parsed event type on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Token is empty
+    /// - Event type is not one of `clock_in`, `clock_out`, `break_start`, `break_end`
+    fn validate(&self) -> Result<EventType> {
+        if self.token.trim().is_empty() {
+            return Err(AppError::ValidationError("Token is required".to_string()));
+        }
+        self.event_type
+            .parse::<EventType>()
+            .map_err(AppError::ValidationError)
+    }
+}
+
+/// Default lifetime of a device token (12 hours)
+const DEFAULT_DEVICE_TOKEN_TTL_MINUTES: i64 = 12 * 60;
+
+/// Request payload for issuing a device token to a kiosk
+#[derive(Debug, Deserialize)]
+pub struct IssueDeviceTokenRequest {
+    /// The kiosk's `X-Device-Id`
+    pub device_id: String,
+    /// Lifetime of the token in minutes (default 720)
+    pub ttl_minutes: Option<i64>,
+}
+
+impl IssueDeviceTokenRequest {
+    /// Validate the device token request
+    ///
+    /// Returns the token lifetime on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Device id is empty or longer than 255 characters
+    /// - Lifetime is not between 1 minute and 24 hours
+    fn validate(&self) -> Result<Duration> {
+        let device_id = self.device_id.trim();
+        if device_id.is_empty() {
+            return Err(AppError::ValidationError(
+                "Device id is required".to_string(),
+            ));
+        }
+        if device_id.len() > MAX_DEVICE_ID_LEN {
+            return Err(AppError::ValidationError(format!(
+                "Device id must be at most {MAX_DEVICE_ID_LEN} characters"
+            )));
+        }
+        let minutes = self.ttl_minutes.unwrap_or(DEFAULT_DEVICE_TOKEN_TTL_MINUTES);
+        if !(1..=MAX_DEVICE_TOKEN_TTL_MINUTES).contains(&minutes) {
+            return Err(AppError::ValidationError(format!(
+                "ttl_minutes must be between 1 and {MAX_DEVICE_TOKEN_TTL_MINUTES}"
+            )));
+        }
+        Ok(Duration::minutes(minutes))
+    }
+}
+
+/// Response payload for an issued device token
+#[derive(Debug, Serialize)]
+pub struct DeviceTokenResponse {
+    /// Token for the kiosk to send in the `X-Device-Token` header
+    pub token: String,
+    pub device_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// POST /api/users/:id/kiosk-token - Issue a short-lived kiosk token for a user
+///
+/// The user's device shows the token as a QR code for a kiosk to scan.
+///
+/// Members can issue tokens only for themselves; managers and admins can issue
+/// tokens for any user.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member issues a token for another user
+/// Returns `NotFound` if the user does not exist
+/// Returns `Forbidden` if the user is deactivated
+/// Returns error if database operation fails
+pub async fn issue_kiosk_token(
+    auth: AuthUser,
+    State(users): State<UserRepository>,
+    State(tokens): State<KioskTokens>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<KioskTokenResponse>> {
+    tracing::debug!(user_id = %user_id, "Issuing kiosk token");
+
+    auth.ensure_can_record(user_id)?;
+
+    let user = users.find_by_id(user_id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("User with id {user_id} not found"))
+            .with_code(ErrorCode::UserNotFound)
+    })?;
+    if !user.is_active {
+        return Err(AppError::Forbidden(format!(
+            "User with id {user_id} is deactivated"
+        )));
+    }
+
+    let issued = tokens.issue(user_id, Utc::now());
+
+    Ok(Json(KioskTokenResponse {
+        token: issued.token,
+        expires_at: issued.expires_at,
+    }))
+}
+
+/// POST /api/admin/device-tokens - Issue a device token to a kiosk
+///
+/// The token only lets the kiosk record clock events (see
+/// [`kiosk_clock`]) and only together with its own `X-Device-Id`.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+pub async fn issue_device_token(
+    _admin: RequireRole<Admin>,
+    State(tokens): State<KioskTokens>,
+    Json(payload): Json<IssueDeviceTokenRequest>,
+) -> Result<Json<DeviceTokenResponse>> {
+    let ttl = payload.validate()?;
+    let device_id = payload.device_id.trim();
+    tracing::debug!(device_id = %device_id, "Issuing device token");
+
+    let issued = tokens.issue_device(device_id, ttl, Utc::now());
+
+    Ok(Json(DeviceTokenResponse {
+        token: issued.token,
+        device_id: device_id.to_string(),
+        expires_at: issued.expires_at,
+    }))
+}
+
+/// POST /api/attendance/kiosk-clock - Record an attendance event from a scanned kiosk token
+///
+/// The event is recorded for the token's user at the current server time, with
+/// the kiosk's client metadata. Each token can be used once. The anomaly rules
+/// run against the recorded event and webhook subscribers are notified.
+///
+/// Only kiosk devices may record events: requires an `X-Device-Token` issued
+/// to the device sending it in `X-Device-Id`, or an `X-Api-Key` with the
+/// `kiosk:clock` scope.
+///
+/// # Errors
+/// Returns `ValidationError` if the payload validation fails or a break event does
+/// not follow the user's current state
+/// Returns `Unauthorized` if neither credential is presented, the device token
+/// is invalid or expired, the API key is wrong, revoked or expired, or the
+/// kiosk token is invalid or expired
+/// Returns `Forbidden` if the device token belongs to another device or the API
+/// key lacks the `kiosk:clock` scope
+/// Returns `BadRequest` if the token has already been used
+/// Returns `Forbidden` if the user has been deactivated since the token was issued
+/// Returns error if database operation fails
+#[allow(clippy::too_many_arguments)]
+pub async fn kiosk_clock(
+    _device: KioskDevice,
+    State(repo): State<AttendanceEventRepository>,
+    State(anomalies): State<AttendanceAnomalyRepository>,
+    State(rules): State<AnomalyRules>,
+    State(tokens): State<KioskTokens>,
+    State(webhooks): State<WebhookDispatcher>,
+    State(presence): State<PresenceHub>,
+    client: ClientMetadata,
+    Json(payload): Json<KioskClockRequest>,
+) -> Result<Json<AttendanceEventResponse>> {
+    let event_type = payload.validate()?;
+
+    let now = Utc::now();
+    let claims = tokens
+        .verify(payload.token.trim(), now)
+        .map_err(|e| AppError::Unauthorized(e.to_string()))?;
+    tracing::debug!(user_id = %claims.sub, event_type = %event_type, "Recording kiosk event");
+
+    check_break_pairing(&repo, claims.sub, event_type, now, None).await?;
+
+    let create_event = CreateAttendanceEvent {
+        user_id: claims.sub,
+        event_type,
+        event_time: now,
+        latitude: None,
+        longitude: None,
+        client_ip: client.ip,
+        user_agent: client.user_agent,
+        device_id: client.device_id,
+        amends_event_id: None,
+    };
+
+    let event = repo
+        .create_with_kiosk_token(create_event, claims.jti, claims.expires_at())
+        .await?;
+    detect_anomalies(&repo, &anomalies, &rules, &event).await;
+
+    let response = AttendanceEventResponse::from(event);
+    webhooks.notify(ATTENDANCE_EVENT_CREATED, &response);
+    presence.publish(&repo, response.user_id).await;
+
+    Ok(Json(response))
+}
@@ -0,0 +1,34 @@
+use crate::extract::{Admin, RequireRole};
+use crate::pool_stats::{PoolMonitor, PoolStats};
+use axum::{
+    Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+/// Content type of the Prometheus text format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// GET /api/admin/db/pool - Report the state of the database connection pool
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+pub async fn get_db_pool_stats(
+    _admin: RequireRole<Admin>,
+    State(monitor): State<PoolMonitor>,
+) -> Json<PoolStats> {
+    Json(monitor.stats().await)
+}
+
+/// GET /metrics - Report the database pool statistics to Prometheus
+pub async fn metrics(State(monitor): State<PoolMonitor>) -> Response {
+    (
+        [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        monitor.stats().await.to_prometheus(),
+    )
+        .into_response()
+}
@@ -1,8 +1,93 @@
+pub mod anomaly;
+pub mod api_key;
+pub mod attendance_correction;
+pub mod attendance_event;
+pub mod auth;
+pub mod health;
+pub mod holiday;
+pub mod invitation;
+pub mod kiosk;
+pub mod metrics;
+pub mod permission;
+pub mod presence;
+pub mod report;
 pub mod todo;
 pub mod user;
+pub mod webhook;
+pub mod work_policy;
 
 // Re-export todo handlers for backward compatibility
 pub use todo::*;
 
 // Re-export user handlers
-pub use user::{create_user, delete_user, get_user, get_users, update_user};
+pub use user::{
+    activate_user, change_password, create_user, create_users_bulk, deactivate_user, delete_user,
+    export_users_csv, get_deleted_users, get_me, get_user, get_user_activity, get_user_stats,
+    get_users, purge_user, restore_user, set_user_role, unlock_user, update_me, update_user,
+    upload_avatar,
+};
+
+// Re-export attendance event handlers
+pub use attendance_event::{
+    create_attendance_event, export_attendance_events_ndjson, get_attendance_event,
+    get_attendance_event_detail, get_user_attendance_events, import_attendance_events,
+};
+
+// Re-export authentication handlers
+pub use auth::{
+    forgot_password, get_csrf_token, google_callback, google_sign_in, login, logout, logout_all,
+    register, reset_password,
+};
+
+// Re-export health check handlers
+pub use health::{liveness, readiness};
+
+// Re-export metrics handlers
+pub use metrics::{get_db_pool_stats, metrics};
+
+// Re-export presence handlers
+pub use presence::presence_socket;
+
+// Re-export invitation handlers
+pub use invitation::{accept_invitation, create_invitation};
+
+// Re-export kiosk handlers
+pub use kiosk::{issue_device_token, issue_kiosk_token, kiosk_clock};
+
+// Re-export anomaly handlers
+pub use anomaly::{get_attendance_anomalies, get_user_attendance_anomalies};
+
+// Re-export attendance correction handlers
+pub use attendance_correction::{
+    approve_attendance_correction, create_attendance_correction, get_attendance_correction,
+    get_attendance_corrections, reject_attendance_correction,
+};
+
+// Re-export attendance report handlers
+pub use report::{
+    export_calendar_ics, export_payroll_csv, export_timesheet_xlsx, get_attendance_summary,
+    get_break_summary, get_overtime, get_timesheet,
+};
+
+// Re-export work policy handlers
+pub use work_policy::{
+    activate_work_policy, create_work_policy, delete_work_policy, get_active_work_policy,
+    get_work_policies, get_work_policy, update_work_policy,
+};
+
+// Re-export holiday handlers
+pub use holiday::{
+    create_holiday, create_holidays_bulk, delete_holiday, get_holiday, get_holidays, update_holiday,
+};
+
+// Re-export webhook handlers
+pub use webhook::{
+    create_webhook, delete_webhook, get_webhook, get_webhook_deliveries, get_webhooks,
+    retry_webhook_delivery, update_webhook,
+};
+
+// Re-export API key handlers
+pub use api_key::{create_api_key, get_api_key, get_api_keys, revoke_api_key};
+
+// Re-export permission handlers
+pub use permission::{get_role_permissions, set_role_permissions};
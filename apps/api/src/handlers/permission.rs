@@ -0,0 +1,103 @@
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::models::{Permission, UserRole};
+use crate::repository::PermissionRepository;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use serde::{Deserialize, Serialize};
+
+/// Request payload for replacing the permissions of a role
+#[derive(Debug, Deserialize)]
+pub struct SetRolePermissionsRequest {
+    /// The complete set of permissions the role is granted (see [`Permission`])
+    pub permissions: Vec<String>,
+}
+
+/// Response payload for the permissions granted to a role
+#[derive(Debug, Serialize)]
+pub struct RolePermissionsResponse {
+    pub role: UserRole,
+    pub permissions: Vec<Permission>,
+}
+
+impl SetRolePermissionsRequest {
+    /// Validate the request
+    ///
+    /// Returns the parsed permissions, without duplicates, on success. An empty
+    /// list is valid and revokes every permission of the role.
+    ///
+    /// # Errors
+    /// Returns validation error if a permission is unknown
+    fn validate(&self) -> Result<Vec<Permission>> {
+        let mut permissions = Vec::new();
+        for permission in &self.permissions {
+            let permission = permission
+                .parse::<Permission>()
+                .map_err(AppError::ValidationError)?;
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
+        Ok(permissions)
+    }
+}
+
+/// GET /api/admin/permissions - List the permissions granted to each role
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns error if database operation fails
+pub async fn get_role_permissions(
+    _admin: RequireRole<Admin>,
+    State(repo): State<PermissionRepository>,
+) -> Result<Json<Vec<RolePermissionsResponse>>> {
+    tracing::debug!("Listing role permissions");
+
+    let mut roles = Vec::new();
+    for role in UserRole::ALL {
+        roles.push(RolePermissionsResponse {
+            role,
+            permissions: repo.list_for_role(role).await?,
+        });
+    }
+
+    Ok(Json(roles))
+}
+
+/// PUT /api/admin/roles/:role/permissions - Replace the permissions of a role
+///
+/// Takes effect on the next request of every user with the role.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role. Admin
+/// routes check the role rather than permissions, so administrators cannot
+/// lock themselves out by revoking their own permissions.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` if the role does not exist
+/// Returns `ValidationError` if a permission is unknown
+/// Returns error if database operation fails
+pub async fn set_role_permissions(
+    _admin: RequireRole<Admin>,
+    State(repo): State<PermissionRepository>,
+    Path(role): Path<String>,
+    Json(payload): Json<SetRolePermissionsRequest>,
+) -> Result<Json<RolePermissionsResponse>> {
+    tracing::debug!(%role, "Setting role permissions");
+
+    let role = role.parse::<UserRole>().map_err(AppError::NotFound)?;
+    let permissions = payload.validate()?;
+
+    repo.set_for_role(role, &permissions).await?;
+
+    Ok(Json(RolePermissionsResponse {
+        role,
+        permissions: repo.list_for_role(role).await?,
+    }))
+}
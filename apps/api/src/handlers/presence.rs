@@ -0,0 +1,207 @@
+use crate::extract::AuthUser;
+use crate::presence::{self, PresenceHub, UserPresence};
+use crate::repository::AttendanceEventRepository;
+use axum::{
+    body::Bytes,
+    extract::{
+        State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{Instant, MissedTickBehavior},
+};
+use uuid::Uuid;
+
+/// Most users a connection may subscribe to
+const MAX_SUBSCRIPTIONS: usize = 500;
+
+/// Message sent by a client
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Receive the presence of these users, starting with a snapshot
+    Subscribe { user_ids: Vec<Uuid> },
+    /// Stop receiving the presence of these users
+    Unsubscribe { user_ids: Vec<Uuid> },
+    /// Application-level heartbeat for clients that cannot send ping frames
+    /// (browsers); answered with `pong`
+    Ping,
+}
+
+/// Message sent to a client
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Current presence of newly subscribed users
+    Snapshot {
+        users: Vec<UserPresence>,
+    },
+    /// Changed presence of a subscribed user
+    Presence(UserPresence),
+    Pong,
+    /// A client message was rejected; the connection stays open
+    Error {
+        message: String,
+    },
+}
+
+/// GET /api/ws - Realtime presence over WebSocket
+///
+/// After the upgrade the client sends JSON text messages:
+///
+/// - `{"type":"subscribe","user_ids":[...]}` answered with a `snapshot` of
+///   the users' current presence; afterwards every change is pushed as a
+///   `presence` message
+/// - `{"type":"unsubscribe","user_ids":[...]}`
+/// - `{"type":"ping"}` answered with `{"type":"pong"}`
+///
+/// Members may subscribe only to themselves; managers and admins to anyone.
+/// The server pings the connection every `WS_HEARTBEAT_SECONDS` and closes it
+/// when nothing was received for two intervals.
+///
+/// # Errors
+/// Returns `Unauthorized` without a valid bearer token or session cookie
+pub async fn presence_socket(
+    user: AuthUser,
+    State(hub): State<PresenceHub>,
+    State(repo): State<AttendanceEventRepository>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    tracing::debug!(user_id = %user.id, "Opening presence connection");
+    ws.on_upgrade(move |socket| serve(socket, user, hub, repo))
+}
+
+/// Serve one connection until it closes or misses its heartbeats
+async fn serve(
+    mut socket: WebSocket,
+    user: AuthUser,
+    hub: PresenceHub,
+    repo: AttendanceEventRepository,
+) {
+    let mut updates = hub.subscribe();
+    let mut subscriptions = HashSet::new();
+    let mut heartbeat = tokio::time::interval_at(Instant::now() + hub.heartbeat, hub.heartbeat);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_received = Instant::now();
+
+    loop {
+        let reply = tokio::select! {
+            message = socket.recv() => {
+                let Some(Ok(message)) = message else { break };
+                last_received = Instant::now();
+                match message {
+                    Message::Text(text) => {
+                        handle(&text, user, &repo, &mut subscriptions).await
+                    }
+                    Message::Binary(_) => Some(ServerMessage::Error {
+                        message: "Messages must be JSON text".to_string(),
+                    }),
+                    Message::Close(_) => break,
+                    // Pings are answered by the WebSocket library
+                    Message::Ping(_) | Message::Pong(_) => None,
+                }
+            }
+            update = updates.recv() => match update {
+                Ok(presence) if subscriptions.contains(&presence.user_id) => {
+                    Some(ServerMessage::Presence(presence))
+                }
+                Ok(_) => None,
+                // Too slow to keep up; send the current state instead of the
+                // missed updates
+                Err(RecvError::Lagged(_)) => Some(snapshot(&repo, &subscriptions).await),
+                Err(RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => {
+                if last_received.elapsed() >= hub.heartbeat * 2 {
+                    tracing::debug!(user_id = %user.id, "Closing unresponsive presence connection");
+                    break;
+                }
+                if socket.send(Message::Ping(Bytes::new())).await.is_err() {
+                    break;
+                }
+                None
+            }
+        };
+
+        if let Some(reply) = reply
+            && send(&mut socket, &reply).await.is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Apply a client message, returning the reply
+async fn handle(
+    text: &str,
+    user: AuthUser,
+    repo: &AttendanceEventRepository,
+    subscriptions: &mut HashSet<Uuid>,
+) -> Option<ServerMessage> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(ServerMessage::Error {
+                message: format!("Invalid message: {e}"),
+            });
+        }
+    };
+
+    match message {
+        ClientMessage::Subscribe { user_ids } => {
+            if let Some(e) = user_ids
+                .iter()
+                .find_map(|&user_id| user.ensure_can_read(user_id).err())
+            {
+                return Some(ServerMessage::Error {
+                    message: e.to_string(),
+                });
+            }
+            let added: HashSet<Uuid> = user_ids
+                .into_iter()
+                .filter(|user_id| !subscriptions.contains(user_id))
+                .collect();
+            if subscriptions.len() + added.len() > MAX_SUBSCRIPTIONS {
+                return Some(ServerMessage::Error {
+                    message: format!(
+                        "A connection may subscribe to at most {MAX_SUBSCRIPTIONS} users"
+                    ),
+                });
+            }
+            subscriptions.extend(&added);
+            Some(snapshot(repo, &added).await)
+        }
+        ClientMessage::Unsubscribe { user_ids } => {
+            for user_id in &user_ids {
+                subscriptions.remove(user_id);
+            }
+            None
+        }
+        ClientMessage::Ping => Some(ServerMessage::Pong),
+    }
+}
+
+/// Current presence of the given users
+async fn snapshot(repo: &AttendanceEventRepository, user_ids: &HashSet<Uuid>) -> ServerMessage {
+    let user_ids: Vec<Uuid> = user_ids.iter().copied().collect();
+    match presence::current(repo, &user_ids, Utc::now()).await {
+        Ok(users) => ServerMessage::Snapshot { users },
+        Err(e) => {
+            tracing::warn!("Failed to load presence snapshot: {e}");
+            ServerMessage::Error {
+                message: "Failed to load the current presence".to_string(),
+            }
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).expect("server messages serialize to JSON");
+    socket.send(Message::Text(text.into())).await
+}
@@ -0,0 +1,570 @@
+use crate::attendance::{
+    self,
+    breaks::BreakSummary,
+    overtime::{OvertimePolicy, OvertimeReport, Period},
+    payroll::PayrollEntry,
+    summary::{PresenceStatus, presence_status},
+    timesheet::Timesheet,
+};
+use crate::error::{AppError, Result};
+use crate::export;
+use crate::extract::{AdminOrScope, AuthUser, PayrollRead};
+use crate::models::AttendanceEvent;
+use crate::repository::{
+    AttendanceEventRepository, HolidayRepository, UserRepository, WorkPolicyRepository,
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Query parameters selecting a calendar month
+#[derive(Debug, Deserialize)]
+pub struct MonthQuery {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl MonthQuery {
+    /// Validate the month query
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Year is outside 2000..=9999
+    /// - Month is outside 1..=12
+    fn validate(&self) -> Result<()> {
+        if !(2000..=9999).contains(&self.year) {
+            return Err(AppError::ValidationError(
+                "Year must be between 2000 and 9999".to_string(),
+            ));
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(AppError::ValidationError(
+                "Month must be between 1 and 12".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Query parameters selecting a calendar day
+#[derive(Debug, Deserialize)]
+pub struct DateQuery {
+    pub date: NaiveDate,
+}
+
+/// Maximum number of users in a single summary request
+const MAX_SUMMARY_USERS: usize = 200;
+
+/// Query parameters for the multi-user attendance summary
+#[derive(Debug, Deserialize)]
+pub struct SummaryQuery {
+    /// Comma-separated user IDs
+    pub user_ids: String,
+    /// Day to summarize (defaults to today in the business timezone)
+    pub date: Option<NaiveDate>,
+}
+
+impl SummaryQuery {
+    /// Validate the summary query
+    ///
+    /// Returns the parsed user IDs on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - No user ID is given, or more than 200
+    /// - A user ID is not a valid UUID
+    fn validate(&self) -> Result<Vec<Uuid>> {
+        let user_ids = self
+            .user_ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<Uuid>()
+                    .map_err(|_| AppError::ValidationError(format!("Invalid user id: {id}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if user_ids.is_empty() {
+            return Err(AppError::ValidationError(
+                "At least one user id is required".to_string(),
+            ));
+        }
+        if user_ids.len() > MAX_SUMMARY_USERS {
+            return Err(AppError::ValidationError(format!(
+                "At most {MAX_SUMMARY_USERS} user ids are allowed"
+            )));
+        }
+
+        Ok(user_ids)
+    }
+}
+
+/// Longest date range accepted by range queries (about one year)
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Query parameters selecting an inclusive date range
+///
+/// Both bounds are optional; the default is the last 30 days up to today.
+#[derive(Debug, Deserialize)]
+pub struct DateRangeQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRangeQuery {
+    /// Resolve the range, applying defaults
+    ///
+    /// Returns the first day and the day after the last day.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - `from` is after `to`
+    /// - The range exceeds 366 days
+    fn resolve(&self) -> Result<(NaiveDate, NaiveDate)> {
+        let to = self
+            .to
+            .unwrap_or_else(|| attendance::local_date(Utc::now()));
+        let from = self.from.unwrap_or(to - Duration::days(30));
+
+        if from > to {
+            return Err(AppError::ValidationError(
+                "'from' must not be after 'to'".to_string(),
+            ));
+        }
+        if (to - from).num_days() >= MAX_RANGE_DAYS {
+            return Err(AppError::ValidationError(format!(
+                "Date range must be {MAX_RANGE_DAYS} days or less"
+            )));
+        }
+
+        Ok((from, to + Duration::days(1)))
+    }
+}
+
+/// Query parameters selecting an overtime period
+#[derive(Debug, Deserialize)]
+pub struct PeriodQuery {
+    /// A month (`2025-11`) or an ISO week (`2025-W45`)
+    pub period: String,
+}
+
+/// Response payload for a monthly timesheet
+#[derive(Debug, Serialize)]
+pub struct TimesheetResponse {
+    pub user_id: Uuid,
+    #[serde(flatten)]
+    pub timesheet: Timesheet,
+}
+
+/// Response payload for a daily break summary
+#[derive(Debug, Serialize)]
+pub struct BreakSummaryResponse {
+    pub user_id: Uuid,
+    #[serde(flatten)]
+    pub summary: BreakSummary,
+}
+
+/// Response payload for an overtime report
+#[derive(Debug, Serialize)]
+pub struct OvertimeResponse {
+    pub user_id: Uuid,
+    #[serde(flatten)]
+    pub report: OvertimeReport,
+}
+
+/// GET /api/users/:id/attendance/timesheet?year=&month= - Monthly timesheet for a user
+///
+/// Daily worked time is rounded according to the active work policy, and
+/// holidays are flagged.
+///
+/// Members can read only their own timesheets; managers and admins can read any
+/// user's timesheets.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the timesheets of another user
+/// Returns `ValidationError` if the year or month is invalid
+/// Returns error if database operation fails
+pub async fn get_timesheet(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    State(policies): State<WorkPolicyRepository>,
+    State(holidays): State<HolidayRepository>,
+    State(default_policy): State<OvertimePolicy>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<MonthQuery>,
+) -> Result<Json<TimesheetResponse>> {
+    tracing::debug!(user_id = %user_id, year = query.year, month = query.month, "Building timesheet");
+
+    auth.ensure_can_read(user_id)?;
+    query.validate()?;
+
+    let policy = effective_policy(&policies, default_policy).await?;
+    let timesheet = load_monthly_timesheet(&repo, &holidays, user_id, query.year, query.month)
+        .await?
+        .rounded(policy.rounding);
+
+    Ok(Json(TimesheetResponse { user_id, timesheet }))
+}
+
+/// GET /api/users/:id/attendance/timesheet.xlsx?year=&month= - Monthly timesheet as xlsx
+///
+/// Columns: date, in, out, breaks, total, overtime. Rounding and daily overtime
+/// follow the active work policy; all time worked on holidays is overtime.
+///
+/// Members can read only their own timesheets; managers and admins can read any
+/// user's timesheets.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the timesheets of another user
+/// Returns `ValidationError` if the year or month is invalid
+/// Returns error if database operation or workbook generation fails
+pub async fn export_timesheet_xlsx(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    State(policies): State<WorkPolicyRepository>,
+    State(holidays): State<HolidayRepository>,
+    State(default_policy): State<OvertimePolicy>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<MonthQuery>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!(user_id = %user_id, year = query.year, month = query.month, "Exporting timesheet as xlsx");
+
+    auth.ensure_can_read(user_id)?;
+    query.validate()?;
+
+    let policy = effective_policy(&policies, default_policy).await?;
+    let timesheet = load_monthly_timesheet(&repo, &holidays, user_id, query.year, query.month)
+        .await?
+        .rounded(policy.rounding);
+    let bytes = export::xlsx::timesheet_workbook(&timesheet, &policy)
+        .map_err(|e| AppError::internal("Failed to generate xlsx timesheet", e))?;
+
+    let disposition = format!(
+        "attachment; filename=\"timesheet-{user_id}-{}-{:02}.xlsx\"",
+        query.year, query.month
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, export::xlsx::CONTENT_TYPE.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        bytes,
+    ))
+}
+
+/// GET /api/attendance/payroll-export?year=&month= - Monthly payroll CSV for all users
+///
+/// One row per active user with regular, overtime and leave hours
+/// (`employee_id,regular_hours,overtime_hours,leave_hours`), aggregated like the
+/// monthly timesheet and overtime report under the active work policy. Leave
+/// is not tracked yet, so leave hours are always 0.
+///
+/// Requires a bearer token of a user with the `admin` role, or an `X-Api-Key`
+/// with the `payroll:read` scope for payroll integrations.
+///
+/// # Errors
+/// Returns `Unauthorized` if neither a bearer token nor an API key is
+/// presented, or the presented one is invalid, revoked or expired
+/// Returns `Forbidden` if the user is not an admin, or the API key lacks the
+/// `payroll:read` scope
+/// Returns `ValidationError` if the year or month is invalid
+/// Returns error if database operation or CSV generation fails
+pub async fn export_payroll_csv(
+    _access: AdminOrScope<PayrollRead>,
+    State(users): State<UserRepository>,
+    State(repo): State<AttendanceEventRepository>,
+    State(policies): State<WorkPolicyRepository>,
+    State(holidays): State<HolidayRepository>,
+    State(default_policy): State<OvertimePolicy>,
+    Query(query): Query<MonthQuery>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!(
+        year = query.year,
+        month = query.month,
+        "Exporting payroll CSV"
+    );
+
+    query.validate()?;
+    let (year, month) = (query.year, query.month);
+    let invalid_month = || AppError::ValidationError(format!("Invalid month: {year}-{month}"));
+    let period = format!("{year}-{month:02}")
+        .parse::<Period>()
+        .map_err(AppError::ValidationError)?;
+
+    // Fetch one extra day so sessions running past midnight are closed
+    let from = attendance::local_day_start(period.start);
+    let to = attendance::local_day_start(period.end) + Duration::days(1);
+    let mut events_by_user: HashMap<Uuid, Vec<AttendanceEvent>> = HashMap::new();
+    for event in repo.find_all_in_range(from, to).await? {
+        events_by_user.entry(event.user_id).or_default().push(event);
+    }
+
+    let holiday_dates = holidays
+        .find_dates_in_range(period.start, period.end)
+        .await?;
+    let policy = effective_policy(&policies, default_policy).await?;
+
+    let entries = users
+        .find_all()
+        .await?
+        .into_iter()
+        .map(|user| {
+            let events = events_by_user.remove(&user.id).unwrap_or_default();
+            let timesheet = attendance::timesheet::build_monthly_timesheet(year, month, &events)
+                .ok_or_else(invalid_month)?
+                .rounded(policy.rounding);
+            let overtime =
+                attendance::overtime::calculate_overtime(&policy, period, &events, &holiday_dates);
+            Ok(PayrollEntry::new(user.id, &timesheet, &overtime))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let csv = export::payroll::payroll_csv(&entries)
+        .map_err(|e| AppError::internal("Failed to generate payroll CSV", e))?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                export::payroll::CONTENT_TYPE.to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"payroll-{year}-{month:02}.csv\""),
+            ),
+        ],
+        csv,
+    ))
+}
+
+/// GET /api/users/:id/attendance/calendar.ics?from=&to= - Worked sessions as iCalendar
+///
+/// Each completed work session started within the (inclusive) date range is
+/// rendered as a `VEVENT` for overlaying attendance in calendar applications.
+///
+/// Members can read only their own sessions; managers and admins can read any
+/// user's sessions.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the sessions of another user
+/// Returns `ValidationError` if the date range is invalid
+/// Returns error if database operation fails
+pub async fn export_calendar_ics(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<impl IntoResponse> {
+    auth.ensure_can_read(user_id)?;
+    let (start, end) = query.resolve()?;
+    tracing::debug!(user_id = %user_id, %start, %end, "Exporting attendance calendar");
+
+    // Fetch one extra day so sessions running past midnight are closed
+    let from = attendance::local_day_start(start);
+    let to = attendance::local_day_start(end) + Duration::days(1);
+    let events = repo.find_by_user_id_in_range(user_id, from, to).await?;
+
+    let sessions: Vec<_> = attendance::session::build_sessions(&events)
+        .into_iter()
+        .filter(|session| (start..end).contains(&session.date()))
+        .collect();
+    let calendar = export::ical::worked_sessions_calendar(user_id, &sessions, Utc::now());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, export::ical::CONTENT_TYPE.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"attendance.ics\"".to_string(),
+            ),
+        ],
+        calendar,
+    ))
+}
+
+/// Attendance status of one user in a summary
+#[derive(Debug, Serialize)]
+pub struct UserStatusResponse {
+    pub user_id: Uuid,
+    pub status: PresenceStatus,
+    pub first_clock_in: Option<DateTime<Utc>>,
+    pub last_event_at: Option<DateTime<Utc>>,
+}
+
+/// Response payload for the multi-user attendance summary
+#[derive(Debug, Serialize)]
+pub struct AttendanceSummaryResponse {
+    pub date: NaiveDate,
+    pub users: Vec<UserStatusResponse>,
+}
+
+/// GET `/api/attendance/summary?user_ids=&date=` - Attendance status of several users
+///
+/// Returns `present`, `on_break` or `absent` for each user, based on their latest
+/// event of the day. For today the status is as of now; for past days it is as
+/// of the end of the day. Unknown or deleted users are omitted.
+///
+/// Members can read only their own status; managers and admins can read any
+/// user's status.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the status of another user
+/// Returns `ValidationError` if the user ID list is invalid
+/// Returns error if database operation fails
+pub async fn get_attendance_summary(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    Query(query): Query<SummaryQuery>,
+) -> Result<Json<AttendanceSummaryResponse>> {
+    let user_ids = query.validate()?;
+    for &user_id in &user_ids {
+        auth.ensure_can_read(user_id)?;
+    }
+    let now = Utc::now();
+    let date = query.date.unwrap_or_else(|| attendance::local_date(now));
+    tracing::debug!(users = user_ids.len(), %date, "Fetching attendance summary");
+
+    let from = attendance::local_day_start(date);
+    let to = (from + Duration::days(1)).min(now);
+    let activity = repo.find_activity_for_users(&user_ids, from, to).await?;
+
+    let users = activity
+        .into_iter()
+        .map(|a| UserStatusResponse {
+            user_id: a.user_id,
+            status: presence_status(a.latest_event_type),
+            first_clock_in: a.first_clock_in,
+            last_event_at: a.latest_event_time,
+        })
+        .collect();
+
+    Ok(Json(AttendanceSummaryResponse { date, users }))
+}
+
+/// GET /api/users/:id/attendance/breaks?date= - Break durations of a user on a day
+///
+/// Members can read only their own breaks; managers and admins can read any
+/// user's breaks.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the breaks of another user
+/// Returns error if database operation fails
+pub async fn get_break_summary(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<DateQuery>,
+) -> Result<Json<BreakSummaryResponse>> {
+    tracing::debug!(user_id = %user_id, date = %query.date, "Building break summary");
+
+    auth.ensure_can_read(user_id)?;
+    // Fetch one extra day so breaks running past midnight are closed
+    let from = attendance::local_day_start(query.date);
+    let to = from + Duration::days(2);
+    let events = repo.find_by_user_id_in_range(user_id, from, to).await?;
+
+    let summary = attendance::breaks::summarize_breaks(query.date, &events);
+
+    Ok(Json(BreakSummaryResponse { user_id, summary }))
+}
+
+/// GET /api/users/:id/attendance/overtime?period= - Daily and weekly overtime of a user
+///
+/// The period is a month (`2025-11`) or an ISO week (`2025-W45`); the
+/// active work policy defines the thresholds and rounding. Holidays are
+/// non-working days, so all time worked on them is overtime.
+///
+/// Members can read only their own overtime; managers and admins can read any
+/// user's overtime.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads the overtime of another user
+/// Returns `ValidationError` if the period is invalid
+/// Returns error if database operation fails
+pub async fn get_overtime(
+    auth: AuthUser,
+    State(repo): State<AttendanceEventRepository>,
+    State(policies): State<WorkPolicyRepository>,
+    State(holidays): State<HolidayRepository>,
+    State(default_policy): State<OvertimePolicy>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<PeriodQuery>,
+) -> Result<Json<OvertimeResponse>> {
+    tracing::debug!(user_id = %user_id, period = %query.period, "Calculating overtime");
+
+    auth.ensure_can_read(user_id)?;
+    let period = query
+        .period
+        .parse::<Period>()
+        .map_err(AppError::ValidationError)?;
+
+    // Fetch one extra day so sessions running past midnight are closed
+    let from = attendance::local_day_start(period.start);
+    let to = attendance::local_day_start(period.end) + Duration::days(1);
+    let events = repo.find_by_user_id_in_range(user_id, from, to).await?;
+
+    let holiday_dates = holidays
+        .find_dates_in_range(period.start, period.end)
+        .await?;
+
+    let policy = effective_policy(&policies, default_policy).await?;
+    let report = attendance::overtime::calculate_overtime(&policy, period, &events, &holiday_dates);
+
+    Ok(Json(OvertimeResponse { user_id, report }))
+}
+
+/// The overtime policy in effect: the active work policy, or the deployment
+/// default configured through the environment when no policy is active
+async fn effective_policy(
+    policies: &WorkPolicyRepository,
+    default: OvertimePolicy,
+) -> Result<OvertimePolicy> {
+    Ok(policies
+        .find_active()
+        .await?
+        .map_or(default, |policy| OvertimePolicy::from(&policy)))
+}
+
+/// Fetch the events of a month and aggregate them into a timesheet with the
+/// month's holidays flagged
+///
+/// One extra day after the month is fetched so that a session started on the
+/// last day of the month can be closed by a clock-out after midnight.
+async fn load_monthly_timesheet(
+    repo: &AttendanceEventRepository,
+    holidays: &HolidayRepository,
+    user_id: Uuid,
+    year: i32,
+    month: u32,
+) -> Result<Timesheet> {
+    let invalid_month = || AppError::ValidationError(format!("Invalid month: {year}-{month}"));
+
+    let first_day = chrono::NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid_month)?;
+    let next_month = attendance::next_month_start(year, month).ok_or_else(invalid_month)?;
+
+    let from = attendance::local_day_start(first_day);
+    let to = attendance::local_day_start(next_month) + Duration::days(1);
+    let events = repo.find_by_user_id_in_range(user_id, from, to).await?;
+
+    let holiday_dates = holidays.find_dates_in_range(first_day, next_month).await?;
+
+    attendance::timesheet::build_monthly_timesheet(year, month, &events)
+        .map(|timesheet| timesheet.with_holidays(&holiday_dates))
+        .ok_or_else(invalid_month)
+}
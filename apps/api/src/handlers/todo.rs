@@ -1,87 +1,677 @@
-use crate::error::{AppError, Result};
-use crate::models::{CreateTodoRequest, Todo, UpdateTodoRequest};
-use crate::store::TodoStore;
+use crate::error::{AppError, ErrorCode, Result};
+use crate::extract::AuthUser;
+use crate::models::{
+    CreateTodoItemRequest, CreateTodoRequest, ImportConflict, ImportOutcome, ImportedTodo,
+    MAX_TODO_ITEMS, SortOrder, TODO_EXPORT_VERSION, Todo, TodoExport, TodoSort,
+    UpdateTodoItemRequest, UpdateTodoRequest, UserRole,
+};
+use crate::pagination::{OffsetPagination, Paginated};
+use crate::store::{TodoFilter, TodoStore};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
 };
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// A todo with the completion of its checklist rolled up
+#[derive(Debug, Serialize)]
+pub struct TodoResponse {
+    #[serde(flatten)]
+    pub todo: Todo,
+    /// Percentage of checklist items done, rounded down (`null` without items)
+    pub completion_percentage: Option<u8>,
+}
+
+impl From<Todo> for TodoResponse {
+    fn from(todo: Todo) -> Self {
+        Self {
+            completion_percentage: todo.completion_percentage(),
+            todo,
+        }
+    }
+}
+
+/// Maximum number of todos in a single bulk request
+const MAX_BULK_TODOS: usize = 100;
+
+/// Request payload for changing many todos at once
+#[derive(Debug, Deserialize)]
+pub struct BulkTodoRequest {
+    pub ids: Vec<u64>,
+}
+
+impl BulkTodoRequest {
+    /// Validate the number of IDs and return them without repeats, keeping the
+    /// first occurrence
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if no ID is given, or more than 100
+    fn unique_ids(&self) -> Result<Vec<u64>> {
+        if self.ids.is_empty() || self.ids.len() > MAX_BULK_TODOS {
+            return Err(AppError::ValidationError(format!(
+                "Between 1 and {MAX_BULK_TODOS} todo IDs are required"
+            )));
+        }
+        let mut seen = HashSet::new();
+        Ok(self
+            .ids
+            .iter()
+            .copied()
+            .filter(|id| seen.insert(*id))
+            .collect())
+    }
+}
+
+/// Response payload for a bulk todo completion
+#[derive(Debug, Serialize)]
+pub struct BulkCompleteTodosReport {
+    /// IDs of the todos marked completed
+    pub updated: Vec<u64>,
+    /// IDs with no todo of the user
+    pub not_found: Vec<u64>,
+}
+
+/// Response payload for a bulk todo deletion
+#[derive(Debug, Serialize)]
+pub struct BulkDeleteTodosReport {
+    /// IDs of the deleted todos
+    pub deleted: Vec<u64>,
+    /// IDs with no todo of the user
+    pub not_found: Vec<u64>,
+}
+
+/// Maximum number of todos in a single import
+const MAX_IMPORT_TODOS: usize = 1000;
+
+/// Query parameters for importing todos
+#[derive(Debug, Deserialize)]
+pub struct TodoImportQuery {
+    /// What to do with a todo titled like an existing one: `skip` (default),
+    /// `replace` or `keep_both`
+    pub on_conflict: Option<String>,
+}
+
+/// Response payload for a todo import
+#[derive(Debug, Serialize)]
+pub struct TodoImportReport {
+    pub created: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    /// Outcome and new ID of each imported todo, in document order
+    pub todos: Vec<ImportedTodo>,
+}
+
+/// Query parameters for listing todos
+#[derive(Debug, Deserialize)]
+pub struct TodoListQuery {
+    /// Only list todos with this priority (`low`, `medium`, `high` or `urgent`)
+    pub priority: Option<String>,
+    /// Only list todos with this tag
+    pub tag: Option<String>,
+    /// Only list completed (`true`) or open (`false`) todos
+    pub completed: Option<bool>,
+    /// List archived (`true`) instead of unarchived (`false`, default) todos
+    pub archived: Option<bool>,
+    /// Words that must all appear in the title or description, ignoring case
+    pub q: Option<String>,
+    /// Sort key: `priority` (default), `created`, `title` or `due_date`
+    pub sort: Option<String>,
+    /// Sort direction: `asc` or `desc` (default: `desc` for `priority`, `asc` otherwise)
+    pub order: Option<String>,
+    /// Todos per page (1-100, default: 20)
+    pub limit: Option<u32>,
+    /// Number of todos to skip (default: 0)
+    pub offset: Option<u64>,
+}
+
+/// Maximum length of the todo search text
+const MAX_SEARCH_LENGTH: usize = 100;
+
+impl TodoListQuery {
+    /// Validate the todo list query and build the filter it describes,
+    /// limited to the todos of `owner_id` if given
+    ///
+    /// A blank search text does not filter.
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if:
+    /// - The priority is not a known priority
+    /// - The search text exceeds 100 characters
+    fn filter(&self, owner_id: Option<Uuid>) -> Result<TodoFilter> {
+        let priority = self
+            .priority
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(AppError::ValidationError)?;
+
+        let q = self.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+        if q.is_some_and(|q| q.chars().count() > MAX_SEARCH_LENGTH) {
+            return Err(AppError::ValidationError(format!(
+                "Search query must be {MAX_SEARCH_LENGTH} characters or less"
+            )));
+        }
+
+        Ok(TodoFilter {
+            owner_id,
+            priority,
+            tag: self.tag.clone(),
+            completed: self.completed,
+            archived: self.archived.unwrap_or(false),
+            q: q.map(str::to_string),
+        })
+    }
+
+    /// Validate the sort key and direction, applying defaults
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if `sort` or `order` is not a known value
+    fn sort(&self) -> Result<(TodoSort, SortOrder)> {
+        let sort = self
+            .sort
+            .as_deref()
+            .map(str::parse::<TodoSort>)
+            .transpose()
+            .map_err(AppError::ValidationError)?
+            .unwrap_or_default();
+        let order = self
+            .order
+            .as_deref()
+            .map(str::parse::<SortOrder>)
+            .transpose()
+            .map_err(AppError::ValidationError)?
+            .unwrap_or_else(|| sort.default_order());
+
+        Ok((sort, order))
+    }
+}
 
-/// GET /api/todos - Get all todos
+/// GET /api/todos?priority=&tag=&completed=&archived=&q=&sort=&order=&limit=&offset= - List todos
+///
+/// Archived todos are only listed, instead of the others, with `archived=true`.
+/// Only todos meeting every given filter are listed: the priority, a tag, the
+/// completed flag, and a search text whose words must all appear in the title
+/// or description. Todos are sorted by `sort` in `order`, highest priority
+/// first by default, with ties in creation order. They are returned one page at
+/// a time with the total number of matches and the offset of the next page.
+///
+/// Users see only their own todos; admins see everyone's.
 ///
 /// # Errors
-/// Returns an error if the operation fails
-pub async fn get_todos(State(store): State<TodoStore>) -> Result<Json<Vec<Todo>>> {
-    tracing::debug!("Fetching all todos");
-    let todos = store.get_all();
-    Ok(Json(todos))
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the priority, `sort` or `order` is not a known
+/// value, the search text is too long or `limit` is out of range
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn get_todos(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Query(query): Query<TodoListQuery>,
+) -> Result<Paginated<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, ?query, "Fetching all todos");
+
+    let filter = query.filter(visible_owner(&user))?;
+    let (sort, order) = query.sort()?;
+    let pagination = OffsetPagination::from_query(query.limit, query.offset)?;
+    let (todos, total) = store.get_all(&filter, sort, order, pagination).await?;
+    Ok(Paginated::offset(todos, pagination, total))
+}
+
+/// GET /api/todos/tags - Get every distinct tag in use, sorted
+///
+/// Only the tags of the user's own todos are listed; admins get everyone's.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn get_todo_tags(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+) -> Result<Json<Vec<String>>> {
+    tracing::debug!(user_id = %user.id, "Fetching todo tags");
+    Ok(Json(store.get_tags(visible_owner(&user)).await?))
 }
 
 /// GET /api/todos/:id - Get a specific todo by ID
 ///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
 /// Returns `NotFound` error if the todo with the specified ID does not exist
-pub async fn get_todo(State(store): State<TodoStore>, Path(id): Path<u64>) -> Result<Json<Todo>> {
-    tracing::debug!(todo_id = id, "Fetching todo by id");
+/// or belongs to another user (unless the user is an admin)
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn get_todo(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path(id): Path<u64>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Fetching todo by id");
 
-    store
-        .get_by_id(id)
-        .map(Json)
-        .ok_or_else(|| AppError::NotFound(format!("Todo with id {id} not found")))
+    find_visible(&store, &user, id)
+        .await
+        .map(|todo| Json(todo.into()))
 }
 
-/// POST /api/todos - Create a new todo
+/// POST /api/todos - Create a new todo owned by the user
+///
+/// Responds with `201 Created` and the URL of the new todo in `Location`.
 ///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
 /// Returns `ValidationError` if the payload validation fails
+/// Returns `InternalServerError` if the todo store lock is poisoned
 pub async fn create_todo(
+    user: AuthUser,
     State(store): State<TodoStore>,
     Json(payload): Json<CreateTodoRequest>,
-) -> Result<Json<Todo>> {
-    tracing::debug!(title = %payload.title, "Creating new todo");
+) -> Result<(
+    StatusCode,
+    [(header::HeaderName, String); 1],
+    Json<TodoResponse>,
+)> {
+    tracing::debug!(user_id = %user.id, title = %payload.title, "Creating new todo");
 
     // Validation
     payload.validate().map_err(AppError::ValidationError)?;
 
-    let todo = store.create(payload.title, payload.description);
-    Ok(Json(todo))
+    let todo = store.create(user.id, payload).await?;
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, format!("/api/todos/{}", todo.id))],
+        Json(todo.into()),
+    ))
 }
 
-/// PUT /api/todos/:id - Update an existing todo
+/// PUT /api/todos/:id - Update an existing todo of the user
 ///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
 /// Returns `ValidationError` if the payload validation fails,
-/// or `NotFound` if the todo with the specified ID does not exist
+/// `NotFound` if the todo with the specified ID does not exist or is not
+/// visible to the user, or `Forbidden` if an admin updates another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
 pub async fn update_todo(
+    user: AuthUser,
     State(store): State<TodoStore>,
     Path(id): Path<u64>,
     Json(payload): Json<UpdateTodoRequest>,
-) -> Result<Json<Todo>> {
-    tracing::debug!(todo_id = id, "Updating todo");
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Updating todo");
+
+    apply_update(&store, &user, id, payload).await
+}
+
+/// PATCH /api/todos/:id - Update a todo of the user with a JSON Merge Patch
+///
+/// Follows RFC 7386: members left out stay unchanged, `null` removes the
+/// description or due date, and any other value replaces the member. The
+/// patch is validated like a PUT payload.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the patch is not an object, removes a required
+/// member or fails validation, `NotFound` if the todo with the specified ID
+/// does not exist or is not visible to the user, or `Forbidden` if an admin
+/// updates another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn patch_todo(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path(id): Path<u64>,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Patching todo");
 
+    let payload = UpdateTodoRequest::from_merge_patch(patch).map_err(AppError::ValidationError)?;
+    apply_update(&store, &user, id, payload).await
+}
+
+/// Validate an update of a todo and apply it if the user may change the todo
+async fn apply_update(
+    store: &TodoStore,
+    user: &AuthUser,
+    id: u64,
+    payload: UpdateTodoRequest,
+) -> Result<Json<TodoResponse>> {
     // Validation
     payload.validate().map_err(AppError::ValidationError)?;
+    find_own(store, user, id).await?;
 
     store
-        .update(id, payload.title, payload.description, payload.completed)
-        .map(Json)
-        .ok_or_else(|| AppError::NotFound(format!("Todo with id {id} not found")))
+        .update(id, payload)
+        .await?
+        .map(|todo| Json(todo.into()))
+        .ok_or_else(|| todo_not_found(id))
 }
 
-/// DELETE /api/todos/:id - Delete a todo by ID
+/// DELETE /api/todos/:id - Delete a todo of the user by ID
+///
+/// Responds with `204 No Content`.
 ///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
 /// Returns `NotFound` error if the todo with the specified ID does not exist
+/// or is not visible to the user, or `Forbidden` if an admin deletes another
+/// user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
 pub async fn delete_todo(
+    user: AuthUser,
     State(store): State<TodoStore>,
     Path(id): Path<u64>,
-) -> Result<Json<serde_json::Value>> {
-    tracing::debug!(todo_id = id, "Deleting todo");
+) -> Result<StatusCode> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Deleting todo");
 
-    if store.delete(id) {
-        Ok(Json(serde_json::json!({
-            "message": format!("Todo with id {id} deleted successfully")
-        })))
+    find_own(&store, &user, id).await?;
+
+    if store.delete(id).await? {
+        Ok(StatusCode::NO_CONTENT)
     } else {
-        Err(AppError::NotFound(format!("Todo with id {id} not found")))
+        Err(todo_not_found(id))
+    }
+}
+
+/// POST /api/todos/:id/archive - Archive a todo of the user
+///
+/// Archived todos keep their data but are left out of the todo list unless
+/// `archived=true` is given.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `NotFound` if the todo does not exist or is not visible to the user,
+/// or `Forbidden` if an admin archives another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn archive_todo(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path(id): Path<u64>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Archiving todo");
+    set_archived(&store, &user, id, true).await
+}
+
+/// POST /api/todos/:id/unarchive - Bring an archived todo of the user back to the todo list
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `NotFound` if the todo does not exist or is not visible to the user,
+/// or `Forbidden` if an admin unarchives another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn unarchive_todo(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path(id): Path<u64>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Unarchiving todo");
+    set_archived(&store, &user, id, false).await
+}
+
+async fn set_archived(
+    store: &TodoStore,
+    user: &AuthUser,
+    id: u64,
+    archived: bool,
+) -> Result<Json<TodoResponse>> {
+    find_own(store, user, id).await?;
+
+    store
+        .set_archived(id, archived)
+        .await?
+        .map(|todo| Json(todo.into()))
+        .ok_or_else(|| todo_not_found(id))
+}
+
+/// POST /api/todos/bulk/complete - Mark many todos of the user as completed
+///
+/// Repeated IDs count once. All of the user's todos among the IDs are completed
+/// together; IDs of todos that do not exist or belong to another user, even
+/// for admins, are reported as not found.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if no ID is given, or more than 100
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn complete_todos_bulk(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Json(payload): Json<BulkTodoRequest>,
+) -> Result<Json<BulkCompleteTodosReport>> {
+    let ids = payload.unique_ids()?;
+    tracing::debug!(user_id = %user.id, count = ids.len(), "Completing todos in bulk");
+
+    let (updated, not_found) = store.complete_many(user.id, &ids).await?;
+    Ok(Json(BulkCompleteTodosReport { updated, not_found }))
+}
+
+/// POST /api/todos/bulk/delete - Delete many todos of the user
+///
+/// Repeated IDs count once. All of the user's todos among the IDs are deleted
+/// together; IDs of todos that do not exist or belong to another user, even
+/// for admins, are reported as not found.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if no ID is given, or more than 100
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn delete_todos_bulk(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Json(payload): Json<BulkTodoRequest>,
+) -> Result<Json<BulkDeleteTodosReport>> {
+    let ids = payload.unique_ids()?;
+    tracing::debug!(user_id = %user.id, count = ids.len(), "Deleting todos in bulk");
+
+    let (deleted, not_found) = store.delete_many(user.id, &ids).await?;
+    Ok(Json(BulkDeleteTodosReport { deleted, not_found }))
+}
+
+/// GET /api/todos/export - Export every todo of the user as a JSON document
+///
+/// Archived todos and checklist items are included. Admins export only their
+/// own todos too. The document can be restored with `POST /api/todos/import`.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn export_todos(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+) -> Result<([(header::HeaderName, &'static str); 1], Json<TodoExport>)> {
+    tracing::debug!(user_id = %user.id, "Exporting todos");
+
+    let todos = store.get_by_owner(user.id).await?;
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"todos.json\"",
+        )],
+        Json(TodoExport {
+            version: TODO_EXPORT_VERSION,
+            exported_at: Some(Utc::now()),
+            todos: todos.into_iter().map(Into::into).collect(),
+        }),
+    ))
+}
+
+/// POST `/api/todos/import?on_conflict=` - Import an exported JSON document as todos of the user
+///
+/// Every todo is validated up front and nothing is imported if one is
+/// invalid. Todos get new IDs; the report maps each exported ID to the new
+/// one. A todo titled like an existing todo of the user is skipped, replaces
+/// the existing todo or is imported next to it, depending on `on_conflict`.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if `on_conflict` is not a known value, the
+/// document version is not supported, it has more than 1000 todos or a todo
+/// is invalid
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn import_todos(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Query(query): Query<TodoImportQuery>,
+    Json(payload): Json<TodoExport>,
+) -> Result<Json<TodoImportReport>> {
+    tracing::debug!(user_id = %user.id, count = payload.todos.len(), "Importing todos");
+
+    // Validation
+    let on_conflict = query
+        .on_conflict
+        .as_deref()
+        .map(str::parse::<ImportConflict>)
+        .transpose()
+        .map_err(AppError::ValidationError)?
+        .unwrap_or_default();
+    if payload.version != TODO_EXPORT_VERSION {
+        return Err(AppError::ValidationError(format!(
+            "Unsupported export version {}, expected {TODO_EXPORT_VERSION}",
+            payload.version
+        )));
+    }
+    if payload.todos.len() > MAX_IMPORT_TODOS {
+        return Err(AppError::ValidationError(format!(
+            "At most {MAX_IMPORT_TODOS} todos can be imported at once"
+        )));
+    }
+    for todo in &payload.todos {
+        todo.validate()
+            .map_err(|e| AppError::ValidationError(format!("Todo {}: {e}", todo.id)))?;
+    }
+
+    let todos = store.import(user.id, payload.todos, on_conflict).await?;
+    let count = |outcome| todos.iter().filter(|todo| todo.outcome == outcome).count();
+    Ok(Json(TodoImportReport {
+        created: count(ImportOutcome::Created),
+        replaced: count(ImportOutcome::Replaced),
+        skipped: count(ImportOutcome::Skipped),
+        todos,
+    }))
+}
+
+/// POST /api/todos/:id/items - Add a checklist item to a todo of the user
+///
+/// The item starts out not done.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the payload validation fails or the todo
+/// already has 100 items, `NotFound` if the todo does not exist or is not
+/// visible to the user, or `Forbidden` if an admin changes another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn add_todo_item(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path(id): Path<u64>,
+    Json(payload): Json<CreateTodoItemRequest>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, "Adding todo item");
+
+    // Validation
+    payload.validate().map_err(AppError::ValidationError)?;
+    let todo = find_own(&store, &user, id).await?;
+    if todo.items.len() >= MAX_TODO_ITEMS {
+        return Err(AppError::ValidationError(format!(
+            "A todo can have at most {MAX_TODO_ITEMS} items"
+        )));
+    }
+
+    store
+        .add_item(id, payload.text)
+        .await?
+        .map(|todo| Json(todo.into()))
+        .ok_or_else(|| todo_not_found(id))
+}
+
+/// PUT `/api/todos/:id/items/:item_id` - Update a checklist item of a todo of the user
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the payload validation fails, `NotFound` if
+/// the todo or item does not exist or the todo is not visible to the user, or
+/// `Forbidden` if an admin changes another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn update_todo_item(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path((id, item_id)): Path<(u64, u64)>,
+    Json(payload): Json<UpdateTodoItemRequest>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, item_id, "Updating todo item");
+
+    // Validation
+    payload.validate().map_err(AppError::ValidationError)?;
+    find_own(&store, &user, id).await?;
+
+    store
+        .update_item(id, item_id, payload)
+        .await?
+        .map(|todo| Json(todo.into()))
+        .ok_or_else(|| item_not_found(item_id))
+}
+
+/// DELETE `/api/todos/:id/items/:item_id` - Delete a checklist item of a todo of the user
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `NotFound` if the todo or item does not exist or the todo is not
+/// visible to the user, or `Forbidden` if an admin changes another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+pub async fn delete_todo_item(
+    user: AuthUser,
+    State(store): State<TodoStore>,
+    Path((id, item_id)): Path<(u64, u64)>,
+) -> Result<Json<TodoResponse>> {
+    tracing::debug!(user_id = %user.id, todo_id = id, item_id, "Deleting todo item");
+
+    find_own(&store, &user, id).await?;
+
+    store
+        .delete_item(id, item_id)
+        .await?
+        .map(|todo| Json(todo.into()))
+        .ok_or_else(|| item_not_found(item_id))
+}
+
+/// Owner whose todos the user sees (`None` = everyone's, for admins)
+fn visible_owner(user: &AuthUser) -> Option<Uuid> {
+    (!user.role.includes(UserRole::Admin)).then_some(user.id)
+}
+
+fn todo_not_found(id: u64) -> AppError {
+    AppError::NotFound(format!("Todo with id {id} not found")).with_code(ErrorCode::TodoNotFound)
+}
+
+fn item_not_found(item_id: u64) -> AppError {
+    AppError::NotFound(format!("Item with id {item_id} not found"))
+}
+
+/// Find a todo the user may read
+/// Todos of other users are reported as missing, so their IDs are not revealed
+///
+/// # Errors
+/// Returns `NotFound` if the todo does not exist or the user may not read it
+/// Returns `InternalServerError` if the todo store lock is poisoned
+async fn find_visible(store: &TodoStore, user: &AuthUser, id: u64) -> Result<Todo> {
+    store
+        .get_by_id(id)
+        .await?
+        .filter(|todo| visible_owner(user).is_none_or(|owner| todo.owner_id == owner))
+        .ok_or_else(|| todo_not_found(id))
+}
+
+/// Find a todo the user may change: only the owner may
+///
+/// # Errors
+/// Returns `NotFound` if the todo does not exist or the user may not read it,
+/// or `Forbidden` if an admin changes another user's todo
+/// Returns `InternalServerError` if the todo store lock is poisoned
+async fn find_own(store: &TodoStore, user: &AuthUser, id: u64) -> Result<Todo> {
+    let todo = find_visible(store, user, id).await?;
+    if todo.owner_id != user.id {
+        return Err(AppError::Forbidden(
+            "You can only change your own todos".to_string(),
+        ));
     }
+    Ok(todo)
 }
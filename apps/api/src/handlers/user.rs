@@ -1,11 +1,26 @@
-use crate::error::{AppError, Result};
-use crate::models::{CreateUser, UpdateUser, User};
+use crate::error::{AppError, ErrorCode, Result};
+use crate::export;
+use crate::extract::{Admin, AdminOrBootstrapKey, AuthUser, RequireRole};
+use crate::models::{
+    ActivityKind, CreateUser, DeletedUser, EventType, LoginOutcome, SortOrder, UpdateUser, User,
+    UserActivity, UserRole, UserSort, UserStats, normalize_email, nullable,
+};
+use crate::pagination::{Paginated, Pagination};
+use crate::password::{self, LockoutPolicy};
 use crate::repository::UserRepository;
+use crate::storage::Storage;
+use crate::validation;
 use axum::{
     Json,
-    extract::{Path, State},
+    body::Body,
+    extract::{Multipart, Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
 };
+use futures_util::{StreamExt, stream};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 /// Request payload for creating a new user
@@ -17,11 +32,144 @@ pub struct CreateUserRequest {
 }
 
 /// Request payload for updating an existing user
+/// Omitted fields are left unchanged; `"picture": null` removes the picture
 #[derive(Debug, Deserialize)]
 pub struct UpdateUserRequest {
     pub name: Option<String>,
     pub email: Option<String>,
-    pub picture: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub picture: Option<Option<String>>,
+}
+
+/// Request payload for setting a user's role
+#[derive(Debug, Deserialize)]
+pub struct SetRoleRequest {
+    pub role: UserRole,
+}
+
+/// Maximum number of users in a single bulk request
+const MAX_BULK_USERS: usize = 500;
+
+/// Request payload for changing a user's password
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+impl ChangePasswordRequest {
+    /// Validate the change password request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - The new password does not meet the password rules (see [`password::validate`])
+    /// - The new password is the same as the current one
+    fn validate(&self) -> Result<()> {
+        password::validate(&self.new_password)?;
+        if self.new_password == self.current_password {
+            return Err(AppError::ValidationError(
+                "New password must differ from the current password".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Largest accepted avatar image (1 MiB), well within the default request body limit
+const MAX_AVATAR_BYTES: usize = 1024 * 1024;
+
+/// Maximum length of a user search query
+const MAX_SEARCH_LENGTH: usize = 100;
+
+/// Query parameters for listing users
+#[derive(Debug, Deserialize)]
+pub struct UserListQuery {
+    /// Case-insensitive text to look for in the name or email
+    pub q: Option<String>,
+    /// Column to sort by: `name`, `email` or `created_at` (default)
+    pub sort: Option<String>,
+    /// Sort direction: `asc` (default) or `desc`
+    pub order: Option<String>,
+    /// 1-based page number (default: 1)
+    pub page: Option<u32>,
+    /// Users per page (1-100, default: 20)
+    pub per_page: Option<u32>,
+}
+
+/// Query parameters for a user's activity timeline
+#[derive(Debug, Deserialize)]
+pub struct ActivityQuery {
+    /// 1-based page number (default: 1)
+    pub page: Option<u32>,
+    /// Entries per page (1-100, default: 20)
+    pub per_page: Option<u32>,
+}
+
+/// Query parameters for exporting users
+#[derive(Debug, Deserialize)]
+pub struct UserExportQuery {
+    /// Also export soft-deleted users (default: false)
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+/// Query parameters for the user statistics
+#[derive(Debug, Deserialize)]
+pub struct UserStatsQuery {
+    /// Length in days of the period counted as recent (1-365, default: 30)
+    pub recent_days: Option<u32>,
+}
+
+/// Query parameters for listing soft-deleted users
+#[derive(Debug, Deserialize)]
+pub struct DeletedUserListQuery {
+    /// 1-based page number (default: 1)
+    pub page: Option<u32>,
+    /// Users per page (1-100, default: 20)
+    pub per_page: Option<u32>,
+}
+
+impl UserListQuery {
+    /// Validate the user list query
+    ///
+    /// Returns the trimmed search text (`None` if blank), the sort column and
+    /// direction, and the pagination on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - The search text exceeds 100 characters
+    /// - `sort` or `order` is not a known value
+    /// - `page` or `per_page` is out of range
+    pub(crate) fn validate(&self) -> Result<(Option<&str>, UserSort, SortOrder, Pagination)> {
+        let q = self.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+        if q.is_some_and(|q| q.chars().count() > MAX_SEARCH_LENGTH) {
+            return Err(AppError::ValidationError(format!(
+                "Search query must be {MAX_SEARCH_LENGTH} characters or less"
+            )));
+        }
+
+        let sort = self
+            .sort
+            .as_deref()
+            .map(str::parse::<UserSort>)
+            .transpose()
+            .map_err(AppError::ValidationError)?
+            .unwrap_or_default();
+        let order = self
+            .order
+            .as_deref()
+            .map(str::parse::<SortOrder>)
+            .transpose()
+            .map_err(AppError::ValidationError)?
+            .unwrap_or_default();
+
+        Ok((
+            q,
+            sort,
+            order,
+            Pagination::from_query(self.page, self.per_page)?,
+        ))
+    }
 }
 
 /// Response payload for user data
@@ -32,12 +180,100 @@ pub struct UserResponse {
     pub name: String,
     pub email: String,
     pub picture: Option<String>,
+    pub role: UserRole,
+    /// `false` if the user is deactivated
+    pub is_active: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl From<User> for UserResponse {
     fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            picture: user.picture,
+            role: user.role,
+            is_active: user.is_active,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// Response payload for an entry of a user's activity timeline
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ActivityResponse {
+    ProfileChanged {
+        occurred_at: chrono::DateTime<chrono::Utc>,
+        field: String,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+    AttendanceEvent {
+        occurred_at: chrono::DateTime<chrono::Utc>,
+        event_id: Option<Uuid>,
+        event_type: Option<EventType>,
+        amends_event_id: Option<Uuid>,
+    },
+}
+
+impl From<UserActivity> for ActivityResponse {
+    fn from(activity: UserActivity) -> Self {
+        match activity.kind {
+            ActivityKind::ProfileChanged => Self::ProfileChanged {
+                occurred_at: activity.occurred_at,
+                field: activity.field.unwrap_or_default(),
+                old_value: activity.old_value,
+                new_value: activity.new_value,
+            },
+            ActivityKind::AttendanceEvent => Self::AttendanceEvent {
+                occurred_at: activity.occurred_at,
+                event_id: activity.event_id,
+                event_type: activity.event_type,
+                amends_event_id: activity.amends_event_id,
+            },
+        }
+    }
+}
+
+/// Result of one entry of a bulk user creation
+#[derive(Debug, Serialize)]
+pub struct BulkUserResult {
+    /// Position of the entry in the request array (0-based)
+    pub index: usize,
+    /// ID of the created user; only set when the whole batch was created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response payload for a bulk user creation
+///
+/// Either all users were created (every result has an `id`) or none was.
+#[derive(Debug, Serialize)]
+pub struct BulkCreateUsersReport {
+    pub created: usize,
+    pub results: Vec<BulkUserResult>,
+}
+
+/// Response payload for soft-deleted user data (admin only)
+#[derive(Debug, Serialize)]
+pub struct DeletedUserResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub picture: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DeletedUser> for DeletedUserResponse {
+    fn from(user: DeletedUser) -> Self {
         Self {
             id: user.id,
             name: user.name,
@@ -45,6 +281,7 @@ impl From<User> for UserResponse {
             picture: user.picture,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
         }
     }
 }
@@ -57,7 +294,7 @@ impl CreateUserRequest {
     /// - Name is empty or only whitespace
     /// - Name exceeds 100 characters
     /// - Email is empty or only whitespace
-    /// - Email is not a valid email format
+    /// - Email is not a valid email address (see [`validation::is_email`])
     /// - Email exceeds 255 characters
     fn validate(&self) -> Result<()> {
         // Validate name
@@ -73,27 +310,22 @@ impl CreateUserRequest {
         }
 
         // Validate email
-        if self.email.trim().is_empty() {
-            return Err(AppError::ValidationError(
-                "Email cannot be empty".to_string(),
-            ));
-        }
-        if self.email.len() > 255 {
-            return Err(AppError::ValidationError(
-                "Email must be 255 characters or less".to_string(),
-            ));
-        }
-        // Basic email format validation
-        if !self.email.contains('@') || !self.email.contains('.') {
-            return Err(AppError::ValidationError(
-                "Email must be a valid email address".to_string(),
-            ));
-        }
+        validation::email(&self.email)?;
 
         Ok(())
     }
 }
 
+impl From<UpdateUserRequest> for UpdateUser {
+    fn from(request: UpdateUserRequest) -> Self {
+        Self {
+            name: request.name,
+            email: request.email,
+            picture: request.picture,
+        }
+    }
+}
+
 impl UpdateUserRequest {
     /// Validate the update user request
     ///
@@ -102,7 +334,7 @@ impl UpdateUserRequest {
     /// - Name is empty or only whitespace
     /// - Name exceeds 100 characters
     /// - Email is empty or only whitespace
-    /// - Email is not a valid email format
+    /// - Email is not a valid email address (see [`validation::is_email`])
     /// - Email exceeds 255 characters
     fn validate(&self) -> Result<()> {
         // Validate name if provided
@@ -121,69 +353,262 @@ impl UpdateUserRequest {
 
         // Validate email if provided
         if let Some(email) = &self.email {
-            if email.trim().is_empty() {
-                return Err(AppError::ValidationError(
-                    "Email cannot be empty".to_string(),
-                ));
-            }
-            if email.len() > 255 {
-                return Err(AppError::ValidationError(
-                    "Email must be 255 characters or less".to_string(),
-                ));
-            }
-            // Basic email format validation
-            if !email.contains('@') || !email.contains('.') {
-                return Err(AppError::ValidationError(
-                    "Email must be a valid email address".to_string(),
-                ));
-            }
+            validation::email(email)?;
         }
 
         Ok(())
     }
 }
 
-/// GET /api/users - Get all users
+/// GET `/api/users?q=&sort=&order=&page=&per_page=` - List users, optionally filtered by a search
+///
+/// `q` matches case-insensitively anywhere in the name or email. Users are
+/// sorted by `sort` (`name`, `email` or `created_at`) in `order` (`asc` or
+/// `desc`), oldest first by default, and returned one page at a time with the
+/// total number of matches.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
 ///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the search text, sort or pagination is invalid
 /// Returns an error if the database query fails
-pub async fn get_users(State(_repo): State<UserRepository>) -> Result<Json<Vec<UserResponse>>> {
-    tracing::debug!("Fetching all users");
+pub async fn get_users(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Query(query): Query<UserListQuery>,
+) -> Result<Paginated<UserResponse>> {
+    tracing::debug!(q = ?query.q, sort = ?query.sort, order = ?query.order, page = ?query.page, "Listing users");
+
+    let (q, sort, order, pagination) = query.validate()?;
+    let (users, total) = repo
+        .search(q, sort, order, pagination.limit(), pagination.offset())
+        .await?;
+
+    Ok(Paginated::page(users, pagination, total))
+}
+
+/// GET `/api/admin/users/deleted?page=&per_page=` - List soft-deleted users pending purge
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the pagination parameters are invalid
+/// Returns error if database operation fails
+pub async fn get_deleted_users(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Query(query): Query<DeletedUserListQuery>,
+) -> Result<Paginated<DeletedUserResponse>> {
+    tracing::debug!(page = ?query.page, "Listing deleted users");
+
+    let pagination = Pagination::from_query(query.page, query.per_page)?;
+    let (users, total) = repo
+        .find_deleted(pagination.limit(), pagination.offset())
+        .await?;
+
+    Ok(Paginated::page(users, pagination, total))
+}
+
+/// Default length in days of the period whose new users are counted as recent
+const DEFAULT_RECENT_DAYS: u32 = 30;
+
+/// Longest period in days whose new users can be counted as recent
+const MAX_RECENT_DAYS: u32 = 365;
+
+/// Response of the user statistics
+#[derive(Debug, Serialize)]
+pub struct UserStatsResponse {
+    #[serde(flatten)]
+    pub stats: UserStats,
+    /// Length in days of the period counted in `created_recently`
+    pub recent_days: u32,
+}
+
+/// GET `/api/admin/users/stats?recent_days=` - Count users by state for dashboards
+///
+/// Returns the number of active, deactivated and soft-deleted users, and of
+/// the users created in the last `recent_days` days that are not deleted.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if `recent_days` is outside 1-365
+/// Returns error if database operation fails
+pub async fn get_user_stats(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Query(query): Query<UserStatsQuery>,
+) -> Result<Json<UserStatsResponse>> {
+    let recent_days = query.recent_days.unwrap_or(DEFAULT_RECENT_DAYS);
+    if !(1..=MAX_RECENT_DAYS).contains(&recent_days) {
+        return Err(AppError::ValidationError(format!(
+            "recent_days must be between 1 and {MAX_RECENT_DAYS}"
+        )));
+    }
+    tracing::debug!(recent_days, "Counting users");
+
+    let created_since = chrono::Utc::now() - chrono::Duration::days(i64::from(recent_days));
+    let stats = repo.stats(created_since).await?;
+
+    Ok(Json(UserStatsResponse { stats, recent_days }))
+}
+
+/// Number of encoded rows buffered between the database and a slow client
+const EXPORT_BUFFER_ROWS: usize = 256;
+
+/// GET `/api/admin/users/export.csv?include_deleted=` - Export users as CSV for audits
+///
+/// Streams the active users (and the soft-deleted ones with
+/// `include_deleted=true`), oldest first, as `id,name,email,created_at,deleted_at`.
+/// Rows are read from the database while the response is written.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// A database error before the first row is returned as an error response; a
+/// later error aborts the response, so a truncated export never ends cleanly.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns error if the header cannot be written or the database query fails
+/// before the first row
+pub async fn export_users_csv(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Query(query): Query<UserExportQuery>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!(include_deleted = query.include_deleted, "Exporting users");
+
+    let csv_error = |e: csv::Error| AppError::internal("Failed to write user CSV", e);
+    let header_row = export::users::header().map_err(csv_error)?;
+
+    // The row stream borrows the repository, so it is drained by its own task;
+    // the bounded channel pauses the query while the client is slow
+    let (tx, mut rx) = mpsc::channel::<Result<Vec<u8>>>(EXPORT_BUFFER_ROWS);
+    tokio::spawn(async move {
+        let users = repo.stream_for_export(query.include_deleted);
+        let mut users = std::pin::pin!(users);
+        while let Some(row) = users.next().await {
+            let line = row.and_then(|user| export::users::row(&user).map_err(csv_error));
+            let failed = line.is_err();
+            if let Err(e) = &line {
+                tracing::error!(error = %e, "User export failed");
+            }
+            // The client has gone away
+            if tx.send(line).await.is_err() || failed {
+                return;
+            }
+        }
+    });
 
-    // Note: We need to add a list_all method to UserRepository
-    // For now, we'll return an error indicating this needs to be implemented
-    Err(AppError::InternalServerError(
-        "List all users not yet implemented".to_string(),
+    let first = rx.recv().await.transpose()?;
+    let rest = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|line| (line, rx))
+    });
+    let body = Body::from_stream(
+        stream::iter(std::iter::once(Ok(header_row)).chain(first.map(Ok))).chain(rest),
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, export::users::CONTENT_TYPE),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"users.csv\"",
+            ),
+        ],
+        body,
     ))
 }
 
 /// GET /api/users/:id - Get a specific user by ID
 ///
+/// Members can read only their own user; managers and admins can read any user.
+///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads another user
 /// Returns `NotFound` error if the user with the specified ID does not exist
 pub async fn get_user(
+    auth: AuthUser,
     State(repo): State<UserRepository>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<UserResponse>> {
     tracing::debug!("Fetching user with id: {id}");
 
-    let user = repo
-        .find_by_id(id)
-        .await?
-        .ok_or_else(|| AppError::NotFound(format!("User with id {id} not found")))?;
+    auth.ensure_can_read(id)?;
+
+    let user = repo.find_by_id(id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("User with id {id} not found"))
+            .with_code(ErrorCode::UserNotFound)
+    })?;
 
     Ok(Json(user.into()))
 }
 
+/// GET `/api/users/:id/activity?page=&per_page=` - Activity timeline of a user
+///
+/// Profile changes and attendance events (including superseded ones) in one
+/// feed, most recent first. Attendance events are placed at their event time.
+///
+/// Members can read only their own activity; managers and admins can read any
+/// user's activity.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member reads another user's activity
+/// Returns `ValidationError` if the pagination parameters are invalid
+/// Returns `NotFound` error if the user with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn get_user_activity(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Paginated<ActivityResponse>> {
+    tracing::debug!(user_id = %id, page = ?query.page, "Fetching user activity");
+
+    auth.ensure_can_read(id)?;
+    let pagination = Pagination::from_query(query.page, query.per_page)?;
+
+    if repo.find_by_id(id).await?.is_none() {
+        return Err(AppError::NotFound(format!("User with id {id} not found"))
+            .with_code(ErrorCode::UserNotFound));
+    }
+    let (activity, total) = repo
+        .find_activity(id, pagination.limit(), pagination.offset())
+        .await?;
+
+    Ok(Paginated::page(activity, pagination, total))
+}
+
 /// POST /api/users - Create a new user
 ///
+/// Responds with `201 Created` and the URL of the new user in `Location`.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
 /// Returns `ValidationError` if the payload validation fails
+/// Returns `Conflict` if an active user already uses the email address
 /// Returns error if database operation fails
 pub async fn create_user(
+    _admin: RequireRole<Admin>,
     State(repo): State<UserRepository>,
     Json(payload): Json<CreateUserRequest>,
-) -> Result<Json<UserResponse>> {
+) -> Result<(
+    StatusCode,
+    [(header::HeaderName, String); 1],
+    Json<UserResponse>,
+)> {
     tracing::debug!(name = %payload.name, email = %payload.email, "Creating new user");
 
     // Validation
@@ -194,55 +619,547 @@ pub async fn create_user(
         name: payload.name,
         email: payload.email,
         picture: payload.picture,
+        password: None,
     };
 
     let user = repo.create(create_user).await?;
 
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, format!("/api/users/{}", user.id))],
+        Json(user.into()),
+    ))
+}
+
+/// POST /api/users/bulk - Create many users at once
+///
+/// Every entry is validated up front, including email addresses repeated within
+/// the request or already used by an active user. Users are only created if all
+/// entries are valid, in a single transaction.
+///
+/// Responds with `200 OK` and the created IDs, or `400 Bad Request` and the
+/// per-entry errors if any entry is invalid.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if no entry is given, or more than 500
+/// Returns `Conflict` if an email address was taken while the users were created
+/// Returns error if database operation fails
+pub async fn create_users_bulk(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Json(payload): Json<Vec<CreateUserRequest>>,
+) -> Result<(StatusCode, Json<BulkCreateUsersReport>)> {
+    if payload.is_empty() || payload.len() > MAX_BULK_USERS {
+        return Err(AppError::ValidationError(format!(
+            "Between 1 and {MAX_BULK_USERS} users are required"
+        )));
+    }
+    tracing::debug!(count = payload.len(), "Creating users in bulk");
+
+    let emails: Vec<String> = payload.iter().map(|user| user.email.clone()).collect();
+    let existing = repo.find_ids_by_emails(&emails).await?;
+
+    let mut seen = HashSet::new();
+    let errors: Vec<Option<String>> = payload
+        .iter()
+        .map(|user| {
+            if let Err(e) = user.validate() {
+                return Some(match e {
+                    AppError::ValidationError(msg) => msg,
+                    e => e.to_string(),
+                });
+            }
+            let email = normalize_email(&user.email);
+            if !seen.insert(email.clone()) {
+                return Some(format!("Email {} appears more than once", user.email));
+            }
+            if existing.contains_key(&email) {
+                return Some(format!("A user with email {} already exists", user.email));
+            }
+            None
+        })
+        .collect();
+
+    if errors.iter().any(Option::is_some) {
+        let results = errors
+            .into_iter()
+            .enumerate()
+            .map(|(index, error)| BulkUserResult {
+                index,
+                id: None,
+                error,
+            })
+            .collect();
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            Json(BulkCreateUsersReport {
+                created: 0,
+                results,
+            }),
+        ));
+    }
+
+    let users: Vec<CreateUser> = payload
+        .into_iter()
+        .map(|user| CreateUser {
+            name: user.name,
+            email: user.email,
+            picture: user.picture,
+            password: None,
+        })
+        .collect();
+    let created = repo.create_many(&users).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(BulkCreateUsersReport {
+            created: created.len(),
+            results: created
+                .into_iter()
+                .enumerate()
+                .map(|(index, user)| BulkUserResult {
+                    index,
+                    id: Some(user.id),
+                    error: None,
+                })
+                .collect(),
+        }),
+    ))
+}
+
+/// POST /api/users/:id/password - Change a user's password
+///
+/// Users can change only their own password. A wrong current password counts
+/// as a failed login and may lock the account (see [`LockoutPolicy`]). Changing
+/// the password revokes every access token and session of the user, so they
+/// log in again with the new password.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the password of another user is changed
+/// Returns `ValidationError` if the new password is invalid
+/// Returns `NotFound` if the user with the specified ID does not exist
+/// Returns `Unauthorized` if the current password is wrong, or the user has no
+/// password or is deactivated
+/// Returns `AccountLocked` (`423 Locked`, with `Retry-After`) if the account is
+/// locked after too many failed logins
+/// Returns error if hashing the password or the database operation fails
+pub async fn change_password(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+    State(policy): State<LockoutPolicy>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!(user_id = %id, "Changing password");
+
+    if auth.id != id {
+        return Err(AppError::Forbidden(
+            "You can only change your own password".to_string(),
+        ));
+    }
+
+    payload.validate()?;
+    // Overlong passwords are not hashed, to bound the work per request
+    if payload.current_password.chars().count() > password::MAX_LENGTH {
+        return Err(AppError::Unauthorized(
+            "Current password is incorrect".to_string(),
+        ));
+    }
+
+    let now = chrono::Utc::now();
+    let outcome = repo
+        .change_password(
+            id,
+            &payload.current_password,
+            &payload.new_password,
+            policy,
+            now,
+        )
+        .await?;
+
+    match outcome {
+        LoginOutcome::Succeeded(_) => Ok(Json(serde_json::json!({
+            "message": "Password changed successfully"
+        }))),
+        LoginOutcome::Failed => Err(AppError::Unauthorized(
+            "Current password is incorrect".to_string(),
+        )),
+        LoginOutcome::Locked { until } => Err(AppError::AccountLocked {
+            retry_after: u64::try_from((until - now).num_seconds() + 1).unwrap_or(1),
+        }),
+    }
+}
+
+/// POST /api/users/:id/avatar - Upload a profile picture
+///
+/// Accepts a `multipart/form-data` upload with the image in a `file` field.
+/// PNG, JPEG, GIF and WebP images up to 1 MiB are accepted; the declared content
+/// type must match the file contents. The image is stored under a new name and
+/// the user's `picture` is set to its URL.
+///
+/// Users can change only their own avatar; admins can change any user's avatar.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member or manager changes another user's avatar
+/// Returns `NotFound` if the user with the specified ID does not exist
+/// Returns `BadRequest` if the upload has no `file` field or cannot be read
+/// Returns `ValidationError` if the file is not a supported image or is too large
+/// Returns error if storing the file or the database operation fails
+pub async fn upload_avatar(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+    State(storage): State<Storage>,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %id, "Uploading avatar");
+
+    auth.ensure_can_change(id)?;
+
+    repo.find_by_id(id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("User with id {id} not found"))
+            .with_code(ErrorCode::UserNotFound)
+    })?;
+
+    let mut upload = None;
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {e}")))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let content_type = field.content_type().unwrap_or_default().to_string();
+        let mut data = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {e}")))?
+        {
+            if data.len() + chunk.len() > MAX_AVATAR_BYTES {
+                return Err(AppError::ValidationError(format!(
+                    "Avatar must be {MAX_AVATAR_BYTES} bytes or less"
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        upload = Some((content_type, data));
+        break;
+    }
+    let (content_type, data) =
+        upload.ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+
+    let extension = image_extension(&content_type, &data)?;
+    // A new name per upload keeps cached copies of the previous avatar from being served
+    let key = format!("avatars/{id}-{}.{extension}", Uuid::new_v4().simple());
+    let url = storage.put(&key, &data).await?;
+
+    let user = repo
+        .update(
+            id,
+            UpdateUser {
+                name: None,
+                email: None,
+                picture: Some(Some(url)),
+            },
+        )
+        .await?;
+    tracing::info!(user_id = %id, bytes = data.len(), "Avatar uploaded");
+
     Ok(Json(user.into()))
 }
 
+/// File extension of an avatar upload
+///
+/// # Errors
+/// Returns validation error if the content type is not a supported image type
+/// or the file contents do not match it
+fn image_extension(content_type: &str, data: &[u8]) -> Result<&'static str> {
+    let (extension, matches) = match content_type {
+        "image/png" => ("png", data.starts_with(b"\x89PNG\r\n\x1a\n")),
+        "image/jpeg" => ("jpg", data.starts_with(&[0xFF, 0xD8, 0xFF])),
+        "image/gif" => (
+            "gif",
+            data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a"),
+        ),
+        "image/webp" => (
+            "webp",
+            data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP",
+        ),
+        _ => {
+            return Err(AppError::ValidationError(
+                "Avatar must be a PNG, JPEG, GIF or WebP image".to_string(),
+            ));
+        }
+    };
+
+    if !matches {
+        return Err(AppError::ValidationError(format!(
+            "File contents are not a valid {content_type} image"
+        )));
+    }
+    Ok(extension)
+}
+
 /// PUT /api/users/:id - Update an existing user
 ///
+/// Users can update only their own profile; admins can update any user.
+///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if a member or manager updates another user
 /// Returns `ValidationError` if the payload validation fails
 /// Returns `NotFound` if the user with the specified ID does not exist
 /// Returns error if database operation fails
 pub async fn update_user(
+    auth: AuthUser,
     State(repo): State<UserRepository>,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateUserRequest>,
 ) -> Result<Json<UserResponse>> {
     tracing::debug!(user_id = %id, "Updating user");
 
+    auth.ensure_can_change(id)?;
+
     // Validation
     payload.validate()?;
 
     // Update user in database
-    let update_user = UpdateUser {
-        name: payload.name,
-        email: payload.email,
-        picture: payload.picture,
-    };
+    let user = repo.update(id, payload.into()).await?;
+
+    Ok(Json(user.into()))
+}
+
+/// GET /api/me - Get the profile of the authenticated user
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns error if database operation fails
+pub async fn get_me(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %auth.id, "Fetching own profile");
 
-    let user = repo.update(id, update_user).await?;
+    let user = repo.find_by_id(auth.id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("User with id {} not found", auth.id))
+            .with_code(ErrorCode::UserNotFound)
+    })?;
+
+    Ok(Json(user.into()))
+}
+
+/// PUT /api/me - Update the profile of the authenticated user
+///
+/// Accepts the same payload as `PUT /api/users/:id`.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `ValidationError` if the payload validation fails
+/// Returns error if database operation fails
+pub async fn update_me(
+    auth: AuthUser,
+    State(repo): State<UserRepository>,
+    Json(payload): Json<UpdateUserRequest>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %auth.id, "Updating own profile");
+
+    payload.validate()?;
+
+    let user = repo.update(auth.id, payload.into()).await?;
 
     Ok(Json(user.into()))
 }
 
 /// DELETE /api/users/:id - Delete a user by ID (soft delete)
 ///
+/// Responds with `204 No Content`.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
 /// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
 /// Returns `NotFound` error if the user with the specified ID does not exist
 /// Returns error if database operation fails
 pub async fn delete_user(
+    _admin: RequireRole<Admin>,
     State(repo): State<UserRepository>,
     Path(id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<StatusCode> {
     tracing::debug!(user_id = %id, "Deleting user");
 
     repo.delete(id).await?;
 
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/admin/users/:id/role - Set the role of a user
+///
+/// Admin only: requires a bearer token of a user with the `admin` role. The
+/// first admin is appointed with the bootstrap `X-Admin-Key` header instead
+/// (see [`AdminOrBootstrapKey`]).
+///
+/// # Errors
+/// Returns `Unauthorized` if neither a bearer token nor the admin key is
+/// presented, or the presented one is invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the user with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn set_user_role(
+    _admin: AdminOrBootstrapKey,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetRoleRequest>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %id, role = %payload.role, "Setting user role");
+
+    let user = repo.set_role(id, payload.role).await?;
+    tracing::info!(user_id = %id, role = %user.role, "User role changed");
+
+    Ok(Json(user.into()))
+}
+
+/// POST /api/admin/users/:id/unlock - Unlock an account locked after failed logins
+///
+/// Also resets the user's count of failed logins. Unlocking an account that is
+/// not locked has no effect.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the user with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn unlock_user(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    tracing::debug!(user_id = %id, "Unlocking user");
+
+    if !repo.unlock(id).await? {
+        return Err(AppError::NotFound(format!("User with id {id} not found"))
+            .with_code(ErrorCode::UserNotFound));
+    }
+    tracing::info!(user_id = %id, "User unlocked");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/users/:id/deactivate - Deactivate a user
+///
+/// The user keeps their data and still appears in listings, but can neither
+/// authenticate nor record attendance until activated again. Deactivating a
+/// deactivated user has no effect.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `BadRequest` if admins deactivate themselves
+/// Returns `NotFound` error if the user with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn deactivate_user(
+    RequireRole { user: admin, .. }: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %id, "Deactivating user");
+
+    // Keeps the last admin from locking everyone out
+    if admin.id == id {
+        return Err(AppError::BadRequest(
+            "You cannot deactivate yourself".to_string(),
+        ));
+    }
+
+    let user = repo.set_active(id, false).await?;
+    tracing::info!(user_id = %id, "User deactivated");
+
+    Ok(Json(user.into()))
+}
+
+/// POST /api/users/:id/activate - Activate a deactivated user
+///
+/// Activating an active user has no effect.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the user with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn activate_user(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %id, "Activating user");
+
+    let user = repo.set_active(id, true).await?;
+    tracing::info!(user_id = %id, "User activated");
+
+    Ok(Json(user.into()))
+}
+
+/// POST /api/users/:id/restore - Restore a soft-deleted user
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if no deleted user has the specified ID
+/// Returns `Conflict` error if an active user already uses the deleted user's email
+/// Returns error if database operation fails
+pub async fn restore_user(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserResponse>> {
+    tracing::debug!(user_id = %id, "Restoring user");
+
+    let user = repo.restore(id).await?;
+
+    Ok(Json(user.into()))
+}
+
+/// DELETE /api/users/:id/purge - Permanently remove a soft-deleted user (admin only)
+///
+/// Used for erasure requests: the user's attendance events and related records
+/// are removed along with the user. The user must be soft-deleted first.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if no soft-deleted user has the specified ID
+/// Returns error if database operation fails
+pub async fn purge_user(
+    _admin: RequireRole<Admin>,
+    State(repo): State<UserRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!(user_id = %id, "Purging user");
+
+    let events_deleted = repo.purge(id).await?;
+    tracing::info!(user_id = %id, events_deleted, "User purged");
+
     Ok(Json(serde_json::json!({
-        "message": format!("User with id {id} deleted successfully")
+        "message": format!("User with id {id} purged successfully"),
+        "attendance_events_deleted": events_deleted
     })))
 }
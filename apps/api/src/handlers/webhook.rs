@@ -0,0 +1,392 @@
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::models::{CreateWebhook, DeliveryStatus, UpdateWebhook, Webhook, WebhookDelivery};
+use crate::repository::WebhookRepository;
+use crate::webhook::WebhookDispatcher;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default number of deliveries returned by the delivery log
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+
+/// Maximum number of deliveries returned by the delivery log
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+/// Request payload for registering a webhook
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Signing secret; generated when omitted
+    pub secret: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Request payload for updating a webhook
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Query parameters for the delivery log
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    /// Only return deliveries in this state (`pending`, `succeeded`, `failed`)
+    pub status: Option<String>,
+    /// Maximum number of deliveries (1-200, default 50)
+    pub limit: Option<i64>,
+}
+
+/// Response payload for webhook data
+/// The signing secret is only returned when the webhook is created
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            description: webhook.description,
+            is_active: webhook.is_active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// Response payload for a newly registered webhook, including its signing secret
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+/// Response payload for a webhook delivery
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            event: delivery.event,
+            payload: delivery.payload,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_status_code: delivery.last_status_code,
+            last_error: delivery.last_error,
+            next_attempt_at: delivery.next_attempt_at,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+        }
+    }
+}
+
+impl CreateWebhookRequest {
+    /// Validate the create webhook request
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - URL is not an absolute `http` or `https` URL or exceeds 2048 characters
+    /// - Secret is given but shorter than 16 or longer than 255 characters
+    /// - Description exceeds 255 characters
+    fn validate(&self) -> Result<()> {
+        validate_url(&self.url)?;
+        if let Some(secret) = &self.secret
+            && !(16..=255).contains(&secret.len())
+        {
+            return Err(AppError::ValidationError(
+                "Secret must be between 16 and 255 characters".to_string(),
+            ));
+        }
+        validate_description(self.description.as_deref())
+    }
+}
+
+impl UpdateWebhookRequest {
+    /// Validate the update webhook request
+    ///
+    /// # Errors
+    /// Returns validation error if the URL or description is provided but invalid
+    fn validate(&self) -> Result<()> {
+        if let Some(url) = &self.url {
+            validate_url(url)?;
+        }
+        validate_description(self.description.as_deref())
+    }
+}
+
+impl WebhookDeliveriesQuery {
+    /// Validate the delivery log query
+    ///
+    /// Returns the parsed status filter and limit on success.
+    ///
+    /// # Errors
+    /// Returns validation error if the status is unknown or the limit is outside 1-200
+    fn validate(&self) -> Result<(Option<DeliveryStatus>, i64)> {
+        let status = self
+            .status
+            .as_deref()
+            .map(str::parse::<DeliveryStatus>)
+            .transpose()
+            .map_err(AppError::ValidationError)?;
+
+        let limit = self.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT);
+        if !(1..=MAX_DELIVERY_LIMIT).contains(&limit) {
+            return Err(AppError::ValidationError(format!(
+                "Limit must be between 1 and {MAX_DELIVERY_LIMIT}"
+            )));
+        }
+
+        Ok((status, limit))
+    }
+}
+
+fn validate_url(url: &str) -> Result<()> {
+    if url.len() > 2048 {
+        return Err(AppError::ValidationError(
+            "URL must be 2048 characters or less".to_string(),
+        ));
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(AppError::ValidationError(
+            "URL must be an absolute http or https URL".to_string(),
+        )),
+    }
+}
+
+fn validate_description(description: Option<&str>) -> Result<()> {
+    if description.is_some_and(|d| d.len() > 255) {
+        return Err(AppError::ValidationError(
+            "Description must be 255 characters or less".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/admin/webhooks - List all webhooks
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns error if database operation fails
+pub async fn get_webhooks(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+) -> Result<Json<Vec<WebhookResponse>>> {
+    tracing::debug!("Listing webhooks");
+
+    let webhooks = repo.list().await?;
+
+    Ok(Json(webhooks.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/admin/webhooks/:id - Get a webhook by ID
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the webhook with the specified ID does not exist
+pub async fn get_webhook(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookResponse>> {
+    tracing::debug!(webhook_id = %id, "Fetching webhook");
+
+    let webhook = find_webhook(&repo, id).await?;
+
+    Ok(Json(webhook.into()))
+}
+
+/// POST /api/admin/webhooks - Register a webhook
+///
+/// The response contains the signing secret, which is not returned again.
+/// A random secret is generated when none is given.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns error if database operation fails
+pub async fn create_webhook(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<Json<CreatedWebhookResponse>> {
+    tracing::debug!(url = %payload.url, "Creating webhook");
+
+    payload.validate()?;
+
+    let secret = payload
+        .secret
+        .unwrap_or_else(|| format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()));
+    let webhook = repo
+        .create(CreateWebhook {
+            url: payload.url,
+            secret: secret.clone(),
+            description: payload.description,
+        })
+        .await?;
+
+    Ok(Json(CreatedWebhookResponse {
+        webhook: webhook.into(),
+        secret,
+    }))
+}
+
+/// PUT /api/admin/webhooks/:id - Update a webhook
+///
+/// Deactivated webhooks receive no new deliveries and their pending deliveries
+/// are not retried until they are activated again.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `NotFound` error if the webhook with the specified ID does not exist
+pub async fn update_webhook(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookResponse>> {
+    tracing::debug!(webhook_id = %id, "Updating webhook");
+
+    payload.validate()?;
+
+    let webhook = repo
+        .update(
+            id,
+            UpdateWebhook {
+                url: payload.url,
+                description: payload.description,
+                is_active: payload.is_active,
+            },
+        )
+        .await?;
+
+    Ok(Json(webhook.into()))
+}
+
+/// DELETE /api/admin/webhooks/:id - Delete a webhook and its delivery log
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the webhook with the specified ID does not exist
+pub async fn delete_webhook(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!(webhook_id = %id, "Deleting webhook");
+
+    repo.delete(id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Webhook with id {id} deleted successfully")
+    })))
+}
+
+/// GET /api/admin/webhooks/:id/deliveries?status=&limit= - Get the delivery log of a webhook
+///
+/// Deliveries are returned most recent first.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the status or limit is invalid
+/// Returns `NotFound` error if the webhook with the specified ID does not exist
+pub async fn get_webhook_deliveries(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDeliveryResponse>>> {
+    tracing::debug!(webhook_id = %id, status = ?query.status, "Listing webhook deliveries");
+
+    let (status, limit) = query.validate()?;
+    find_webhook(&repo, id).await?;
+
+    let deliveries = repo.list_deliveries(id, status, limit).await?;
+
+    Ok(Json(deliveries.into_iter().map(Into::into).collect()))
+}
+
+/// POST `/api/admin/webhooks/:id/deliveries/:delivery_id/retry` - Retry a delivery now
+///
+/// Sends a pending or failed delivery once more and returns it with the outcome
+/// of the attempt. A failed delivery stays failed if this attempt fails too.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the webhook or delivery does not exist
+/// Returns `BadRequest` if the delivery has already succeeded
+pub async fn retry_webhook_delivery(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WebhookRepository>,
+    State(dispatcher): State<WebhookDispatcher>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<WebhookDeliveryResponse>> {
+    tracing::debug!(webhook_id = %id, delivery_id = %delivery_id, "Retrying webhook delivery");
+
+    let webhook = find_webhook(&repo, id).await?;
+    let delivery = repo.find_delivery(id, delivery_id).await?.ok_or_else(|| {
+        AppError::NotFound(format!("Webhook delivery with id {delivery_id} not found"))
+    })?;
+
+    let delivery = dispatcher.redeliver(&webhook, &delivery).await?;
+
+    Ok(Json(delivery.into()))
+}
+
+async fn find_webhook(repo: &WebhookRepository, id: Uuid) -> Result<Webhook> {
+    repo.find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Webhook with id {id} not found")))
+}
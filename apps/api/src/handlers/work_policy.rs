@@ -0,0 +1,326 @@
+use crate::error::{AppError, Result};
+use crate::extract::{Admin, RequireRole};
+use crate::models::{CreateWorkPolicy, RoundingMode, UpdateWorkPolicy, WeekStart, WorkPolicy};
+use crate::repository::WorkPolicyRepository;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum standard working time per day (24 hours)
+const MAX_DAILY_MINUTES: i32 = 24 * 60;
+
+/// Maximum regular working time per week (7 days)
+const MAX_WEEKLY_MINUTES: i32 = 7 * 24 * 60;
+
+/// Maximum rounding unit (1 hour)
+const MAX_ROUNDING_MINUTES: i32 = 60;
+
+/// Request payload for creating a work policy
+#[derive(Debug, Deserialize)]
+pub struct CreateWorkPolicyRequest {
+    pub name: String,
+    pub standard_daily_minutes: i32,
+    pub weekly_threshold_minutes: i32,
+    /// Defaults to `monday`
+    pub week_start: Option<String>,
+    /// Defaults to `none`
+    pub rounding_mode: Option<String>,
+    /// Defaults to 1 (no rounding)
+    pub rounding_minutes: Option<i32>,
+}
+
+/// Request payload for updating a work policy
+#[derive(Debug, Deserialize)]
+pub struct UpdateWorkPolicyRequest {
+    pub name: Option<String>,
+    pub standard_daily_minutes: Option<i32>,
+    pub weekly_threshold_minutes: Option<i32>,
+    pub week_start: Option<String>,
+    pub rounding_mode: Option<String>,
+    pub rounding_minutes: Option<i32>,
+}
+
+/// Response payload for work policy data
+#[derive(Debug, Serialize)]
+pub struct WorkPolicyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub standard_daily_minutes: i32,
+    pub weekly_threshold_minutes: i32,
+    pub week_start: WeekStart,
+    pub rounding_mode: RoundingMode,
+    pub rounding_minutes: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WorkPolicy> for WorkPolicyResponse {
+    fn from(policy: WorkPolicy) -> Self {
+        Self {
+            id: policy.id,
+            name: policy.name,
+            standard_daily_minutes: policy.standard_daily_minutes,
+            weekly_threshold_minutes: policy.weekly_threshold_minutes,
+            week_start: policy.week_start,
+            rounding_mode: policy.rounding_mode,
+            rounding_minutes: policy.rounding_minutes,
+            is_active: policy.is_active,
+            created_at: policy.created_at,
+            updated_at: policy.updated_at,
+        }
+    }
+}
+
+impl CreateWorkPolicyRequest {
+    /// Validate the create work policy request
+    ///
+    /// Returns the policy to create on success.
+    ///
+    /// # Errors
+    /// Returns validation error if:
+    /// - Name is empty or exceeds 100 characters
+    /// - A minute value is outside its allowed range
+    /// - Week start or rounding mode is not a known value
+    fn validate(&self) -> Result<CreateWorkPolicy> {
+        validate_name(&self.name)?;
+        validate_minutes(
+            "Standard daily minutes",
+            self.standard_daily_minutes,
+            MAX_DAILY_MINUTES,
+        )?;
+        validate_minutes(
+            "Weekly threshold minutes",
+            self.weekly_threshold_minutes,
+            MAX_WEEKLY_MINUTES,
+        )?;
+        let rounding_minutes = self.rounding_minutes.unwrap_or(1);
+        validate_minutes("Rounding minutes", rounding_minutes, MAX_ROUNDING_MINUTES)?;
+
+        Ok(CreateWorkPolicy {
+            name: self.name.trim().to_string(),
+            standard_daily_minutes: self.standard_daily_minutes,
+            weekly_threshold_minutes: self.weekly_threshold_minutes,
+            week_start: parse_week_start(self.week_start.as_deref())?.unwrap_or(WeekStart::Monday),
+            rounding_mode: parse_rounding_mode(self.rounding_mode.as_deref())?.unwrap_or_default(),
+            rounding_minutes,
+        })
+    }
+}
+
+impl UpdateWorkPolicyRequest {
+    /// Validate the update work policy request
+    ///
+    /// Returns the changes to apply on success.
+    ///
+    /// # Errors
+    /// Returns validation error if a provided field is invalid
+    fn validate(&self) -> Result<UpdateWorkPolicy> {
+        if let Some(name) = &self.name {
+            validate_name(name)?;
+        }
+        if let Some(minutes) = self.standard_daily_minutes {
+            validate_minutes("Standard daily minutes", minutes, MAX_DAILY_MINUTES)?;
+        }
+        if let Some(minutes) = self.weekly_threshold_minutes {
+            validate_minutes("Weekly threshold minutes", minutes, MAX_WEEKLY_MINUTES)?;
+        }
+        if let Some(minutes) = self.rounding_minutes {
+            validate_minutes("Rounding minutes", minutes, MAX_ROUNDING_MINUTES)?;
+        }
+
+        Ok(UpdateWorkPolicy {
+            name: self.name.as_deref().map(|name| name.trim().to_string()),
+            standard_daily_minutes: self.standard_daily_minutes,
+            weekly_threshold_minutes: self.weekly_threshold_minutes,
+            week_start: parse_week_start(self.week_start.as_deref())?,
+            rounding_mode: parse_rounding_mode(self.rounding_mode.as_deref())?,
+            rounding_minutes: self.rounding_minutes,
+        })
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::ValidationError(
+            "Name cannot be empty".to_string(),
+        ));
+    }
+    if name.len() > 100 {
+        return Err(AppError::ValidationError(
+            "Name must be 100 characters or less".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_minutes(field: &str, minutes: i32, max: i32) -> Result<()> {
+    if !(1..=max).contains(&minutes) {
+        return Err(AppError::ValidationError(format!(
+            "{field} must be between 1 and {max}"
+        )));
+    }
+    Ok(())
+}
+
+fn parse_week_start(value: Option<&str>) -> Result<Option<WeekStart>> {
+    value
+        .map(str::parse::<WeekStart>)
+        .transpose()
+        .map_err(AppError::ValidationError)
+}
+
+fn parse_rounding_mode(value: Option<&str>) -> Result<Option<RoundingMode>> {
+    value
+        .map(str::parse::<RoundingMode>)
+        .transpose()
+        .map_err(AppError::ValidationError)
+}
+
+/// GET /api/work-policies - List all work policies
+///
+/// # Errors
+/// Returns error if database operation fails
+pub async fn get_work_policies(
+    State(repo): State<WorkPolicyRepository>,
+) -> Result<Json<Vec<WorkPolicyResponse>>> {
+    tracing::debug!("Listing work policies");
+
+    let policies = repo.list().await?;
+
+    Ok(Json(policies.into_iter().map(Into::into).collect()))
+}
+
+/// GET /api/work-policies/active - Get the policy currently in effect
+///
+/// # Errors
+/// Returns `NotFound` if no policy is active (the deployment defaults apply)
+/// Returns error if database operation fails
+pub async fn get_active_work_policy(
+    State(repo): State<WorkPolicyRepository>,
+) -> Result<Json<WorkPolicyResponse>> {
+    tracing::debug!("Fetching active work policy");
+
+    let policy = repo
+        .find_active()
+        .await?
+        .ok_or_else(|| AppError::NotFound("No work policy is active".to_string()))?;
+
+    Ok(Json(policy.into()))
+}
+
+/// GET /api/work-policies/:id - Get a specific work policy by ID
+///
+/// # Errors
+/// Returns `NotFound` error if the policy with the specified ID does not exist
+pub async fn get_work_policy(
+    State(repo): State<WorkPolicyRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkPolicyResponse>> {
+    tracing::debug!(policy_id = %id, "Fetching work policy");
+
+    let policy = repo
+        .find_by_id(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Work policy with id {id} not found")))?;
+
+    Ok(Json(policy.into()))
+}
+
+/// POST /api/work-policies - Create a new work policy
+///
+/// The policy is created inactive; activate it to put it into effect.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns error if database operation fails
+pub async fn create_work_policy(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WorkPolicyRepository>,
+    Json(payload): Json<CreateWorkPolicyRequest>,
+) -> Result<Json<WorkPolicyResponse>> {
+    tracing::debug!(name = %payload.name, "Creating work policy");
+
+    let policy = payload.validate()?;
+    let created = repo.create(policy).await?;
+
+    Ok(Json(created.into()))
+}
+
+/// PUT /api/work-policies/:id - Update an existing work policy
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `ValidationError` if the payload validation fails
+/// Returns `NotFound` if the policy with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn update_work_policy(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WorkPolicyRepository>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkPolicyRequest>,
+) -> Result<Json<WorkPolicyResponse>> {
+    tracing::debug!(policy_id = %id, "Updating work policy");
+
+    let policy = payload.validate()?;
+    let updated = repo.update(id, policy).await?;
+
+    Ok(Json(updated.into()))
+}
+
+/// POST /api/work-policies/:id/activate - Put a work policy into effect
+///
+/// The previously active policy, if any, is deactivated.
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` if the policy with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn activate_work_policy(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WorkPolicyRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WorkPolicyResponse>> {
+    tracing::debug!(policy_id = %id, "Activating work policy");
+
+    let activated = repo.activate(id).await?;
+
+    Ok(Json(activated.into()))
+}
+
+/// DELETE /api/work-policies/:id - Delete a work policy
+///
+/// Admin only: requires a bearer token of a user with the `admin` role.
+///
+/// # Errors
+/// Returns `Unauthorized` if the bearer token is missing or invalid
+/// Returns `Forbidden` if the user is not an admin
+/// Returns `NotFound` error if the policy with the specified ID does not exist
+/// Returns error if database operation fails
+pub async fn delete_work_policy(
+    _admin: RequireRole<Admin>,
+    State(repo): State<WorkPolicyRepository>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>> {
+    tracing::debug!(policy_id = %id, "Deleting work policy");
+
+    repo.delete(id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Work policy with id {id} deleted successfully")
+    })))
+}
@@ -0,0 +1,81 @@
+//! Health checks for orchestrators
+//!
+//! `/health/live` answers as long as the process serves requests, while
+//! `/health/ready` also checks the components the API depends on, so load
+//! balancers stop routing traffic to an instance that cannot reach Postgres.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+
+/// How long the database may take to answer a readiness check
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Status of the API or one of its components
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Unavailable,
+}
+
+/// Result of checking one component
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    /// How long the check took
+    pub latency_ms: u64,
+    /// Why the component is unavailable (details are only logged)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'static str>,
+}
+
+/// Checks the components the API depends on
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pool: PgPool,
+    /// How long the database may take to answer
+    pub database_timeout: Duration,
+}
+
+impl HealthCheck {
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            database_timeout: DATABASE_TIMEOUT,
+        }
+    }
+
+    /// Check that the database answers `SELECT 1` within the timeout
+    pub async fn database(&self) -> ComponentHealth {
+        let started = Instant::now();
+        let result = tokio::time::timeout(
+            self.database_timeout,
+            sqlx::query!("SELECT 1 AS one").fetch_one(&self.pool),
+        )
+        .await;
+        let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let error = match result {
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => {
+                tracing::warn!("Database readiness check failed: {e}");
+                Some("query failed")
+            }
+            Err(_) => {
+                tracing::warn!("Database readiness check timed out");
+                Some("timed out")
+            }
+        };
+        ComponentHealth {
+            status: if error.is_none() {
+                HealthStatus::Ok
+            } else {
+                HealthStatus::Unavailable
+            },
+            latency_ms,
+            error,
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Idempotent retries of POST requests
+//!
+//! A client that may retry a POST request (e.g. a clock-in sent over a flaky
+//! mobile network) sends a unique `Idempotency-Key` header with it. The first
+//! request with a key is processed and its response stored for 24 hours;
+//! retries with the same key get the stored response, marked with
+//! `Idempotent-Replayed: true`, instead of repeating the request.
+//!
+//! - Keys are scoped to the credentials of the request (bearer token, API key,
+//!   session cookie, ...), so clients cannot replay each other's responses.
+//!   Requests without credentials are scoped to the client IP; when that is
+//!   unknown too, the key is ignored.
+//! - Reusing a key for a different request (method, URI or body) is rejected
+//!   with `400 Bad Request`, and a retry sent while the first request is still
+//!   processed with `409 Conflict`.
+//! - A request holds its key for no longer than the longest request timeout,
+//!   so a retry is processed again if the first request never finished (e.g.
+//!   the client disconnected).
+//! - Server errors (5xx) are not stored, so the retry is processed again.
+//! - Sign-in and registration routes are excluded, as their responses carry
+//!   credentials that must not be stored.
+
+use crate::body_limit::BodyLimits;
+use crate::error::{AppError, ErrorCode};
+use crate::extract::{
+    ADMIN_KEY_HEADER, API_KEY_HEADER, ClientMetadata, DEVICE_ID_HEADER, DEVICE_TOKEN_HEADER,
+};
+use crate::models::{IdempotencyRecord, StoredResponse};
+use crate::rate_limit::RouteGroup;
+use crate::repository::{IdempotencyClaim, IdempotencyKeyRepository};
+use crate::request_id::REQUEST_ID_HEADER;
+use crate::session::SESSION_COOKIE;
+use crate::timeout::Timeouts;
+use axum::{
+    body::{Body, Bytes, HttpBody, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Header carrying the idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header marking a replayed response
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest idempotency key accepted
+const MAX_KEY_LEN: usize = 255;
+
+/// How long a stored response is replayed (24 hours)
+const KEY_TTL_HOURS: i64 = 24;
+
+/// Largest response body stored; larger responses are not replayed
+const MAX_STORED_BODY_BYTES: usize = 1024 * 1024;
+
+/// Request headers holding credentials, which scope the keys
+const CREDENTIAL_HEADERS: [&str; 5] = [
+    "authorization",
+    API_KEY_HEADER,
+    ADMIN_KEY_HEADER,
+    DEVICE_TOKEN_HEADER,
+    DEVICE_ID_HEADER,
+];
+
+/// Middleware answering retried POST requests with the stored response
+///
+/// Must run after the CSRF check and rate limits, so their rejections are not
+/// stored.
+pub async fn idempotent_requests(
+    State(keys): State<IdempotencyKeyRepository>,
+    State(limits): State<BodyLimits>,
+    State(timeouts): State<Timeouts>,
+    client: ClientMetadata,
+    request: Request,
+    next: Next,
+) -> Response {
+    let excluded = request.method() != Method::POST
+        || RouteGroup::for_path(request.uri().path()) == Some(RouteGroup::Auth);
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .filter(|_| !excluded)
+    else {
+        return next.run(request).await;
+    };
+    let Some(key) = key
+        .to_str()
+        .ok()
+        .map(str::to_string)
+        .filter(|key| is_valid(key))
+    else {
+        return AppError::BadRequest(format!(
+            "Idempotency-Key must be 1 to {MAX_KEY_LEN} visible ASCII characters"
+        ))
+        .into_response();
+    };
+
+    let Some(scope_hash) = scope_hash(request.headers(), client.ip.as_deref()) else {
+        return next.run(request).await;
+    };
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, limits.upload).await else {
+        return AppError::Rejected {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            message: "Failed to buffer the request body: length limit exceeded".to_string(),
+        }
+        .into_response();
+    };
+    let request_hash = sha256_hex(&[
+        parts.method.as_str().as_bytes(),
+        b" ",
+        parts.uri.to_string().as_bytes(),
+        b"\n",
+        &body,
+    ]);
+
+    // The request is cancelled after its timeout, so it cannot hold the key longer
+    let lease = Duration::from_std(timeouts.request.max(timeouts.export))
+        .unwrap_or_else(|_| Duration::hours(KEY_TTL_HOURS));
+    let claim = keys
+        .claim(&scope_hash, &key, &request_hash, Utc::now(), lease)
+        .await;
+    let id = match claim {
+        Ok(IdempotencyClaim::Claimed(id)) => id,
+        Ok(IdempotencyClaim::Existing(record)) => return replay(record, &request_hash),
+        Err(e) => return e.into_response(),
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    store(&keys, id, response).await
+}
+
+fn is_valid(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Hash of the credentials of a request, or of the client IP for anonymous
+/// requests (`None` if that is unknown too)
+fn scope_hash(headers: &HeaderMap, client_ip: Option<&str>) -> Option<String> {
+    let session = crate::extract::cookie(headers, SESSION_COOKIE);
+    let anonymous = session.is_none()
+        && CREDENTIAL_HEADERS
+            .iter()
+            .all(|name| !headers.contains_key(*name));
+    if anonymous {
+        return client_ip.map(|ip| sha256_hex(&[b"ip=", ip.as_bytes()]));
+    }
+
+    let session = session.unwrap_or_default();
+    let mut parts: Vec<&[u8]> = vec![b"session=", session.as_bytes()];
+    for name in CREDENTIAL_HEADERS {
+        let value = headers.get(name).map_or(&[][..], HeaderValue::as_bytes);
+        parts.extend([b"\n".as_slice(), name.as_bytes(), b"=", value]);
+    }
+    Some(sha256_hex(&parts))
+}
+
+/// Lowercase hex SHA-256 of the concatenated parts
+fn sha256_hex(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// The stored response of an earlier request with the key
+fn replay(record: IdempotencyRecord, request_hash: &str) -> Response {
+    if record.request_hash != request_hash {
+        return AppError::BadRequest(
+            "Idempotency-Key was already used for a different request".to_string(),
+        )
+        .with_code(ErrorCode::IdempotencyKeyReused)
+        .into_response();
+    }
+    let Some(stored) = record.response else {
+        return AppError::Conflict(
+            "A request with this Idempotency-Key is still being processed".to_string(),
+        )
+        .with_code(ErrorCode::IdempotencyKeyInProgress)
+        .into_response();
+    };
+
+    let Some(status) = u16::try_from(stored.status_code)
+        .ok()
+        .and_then(|status| StatusCode::from_u16(status).ok())
+    else {
+        return AppError::InternalServerError(format!(
+            "Stored response has an invalid status {}",
+            stored.status_code
+        ))
+        .into_response();
+    };
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = status;
+    let headers = response.headers_mut();
+    for (name, value) in stored.header_names.iter().zip(&stored.header_values) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Store the response of a claimed key, or release the key if the response
+/// cannot be replayed
+async fn store(keys: &IdempotencyKeyRepository, id: Uuid, response: Response) -> Response {
+    let storable =
+        !response.status().is_server_error()
+            && response.body().size_hint().exact().is_some_and(|len| {
+                usize::try_from(len).is_ok_and(|len| len <= MAX_STORED_BODY_BYTES)
+            });
+    if !storable {
+        release(keys, id).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body: Bytes = match to_bytes(body, MAX_STORED_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(keys, id).await;
+            return AppError::internal("Failed to buffer the response body", e).into_response();
+        }
+    };
+
+    let (header_names, header_values) = parts
+        .headers
+        .iter()
+        .filter(|(name, _)| is_stored_header(name))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .unzip();
+    let stored = StoredResponse {
+        status_code: i16::try_from(parts.status.as_u16()).expect("status codes are below 1000"),
+        header_names,
+        header_values,
+        body: body.to_vec(),
+    };
+    let expires_at = Utc::now() + Duration::hours(KEY_TTL_HOURS);
+    if let Err(e) = keys.complete(id, &stored, expires_at).await {
+        tracing::warn!("Failed to store the response of an idempotency key: {e}");
+        release(keys, id).await;
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Headers replayed with a stored response; the others are set again by the
+/// server (e.g. the request id) or must not be stored (cookies)
+fn is_stored_header(name: &HeaderName) -> bool {
+    name != header::CONTENT_LENGTH
+        && name != header::DATE
+        && name != header::SET_COOKIE
+        && name != REQUEST_ID_HEADER
+}
+
+async fn release(keys: &IdempotencyKeyRepository, id: Uuid) {
+    if let Err(e) = keys.release(id).await {
+        tracing::warn!("Failed to release an idempotency key: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_scoped_to_credentials() {
+        let mut alice = HeaderMap::new();
+        alice.insert("authorization", HeaderValue::from_static("Bearer alice"));
+        let mut bob = HeaderMap::new();
+        bob.insert("authorization", HeaderValue::from_static("Bearer bob"));
+        let mut session = HeaderMap::new();
+        session.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; session=alice"),
+        );
+
+        let anonymous = HeaderMap::new();
+        let ip = Some("203.0.113.7");
+
+        assert_eq!(scope_hash(&alice, ip), scope_hash(&alice.clone(), None));
+        assert_ne!(scope_hash(&alice, ip), scope_hash(&bob, ip));
+        assert_ne!(scope_hash(&alice, ip), scope_hash(&anonymous, ip));
+        assert_ne!(scope_hash(&session, ip), scope_hash(&anonymous, ip));
+        assert!(scope_hash(&session, None).is_some());
+    }
+
+    #[test]
+    fn test_anonymous_keys_are_scoped_to_the_client_ip() {
+        let anonymous = HeaderMap::new();
+
+        assert_eq!(
+            scope_hash(&anonymous, Some("203.0.113.7")),
+            scope_hash(&anonymous, Some("203.0.113.7"))
+        );
+        assert_ne!(
+            scope_hash(&anonymous, Some("203.0.113.7")),
+            scope_hash(&anonymous, Some("203.0.113.8"))
+        );
+        assert_eq!(scope_hash(&anonymous, None), None);
+    }
+
+    #[test]
+    fn test_idempotency_keys_are_validated() {
+        assert!(is_valid("6f1c2a9e-clock-in-1"));
+        assert!(!is_valid(""));
+        assert!(!is_valid("has space"));
+        assert!(!is_valid(&"a".repeat(MAX_KEY_LEN + 1)));
+    }
+}
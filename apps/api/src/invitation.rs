@@ -0,0 +1,10 @@
+//! Invitation settings
+//!
+//! An invitation is accepted with an opaque token (see [`crate::token`]) handed
+//! to the invited person, e.g. in an email link.
+
+/// Validity of an invitation when no other is requested (7 days)
+pub const DEFAULT_TTL_HOURS: u32 = 7 * 24;
+
+/// Longest validity of an invitation (30 days)
+pub const MAX_TTL_HOURS: u32 = 30 * 24;
@@ -0,0 +1,93 @@
+use super::JobSettings;
+use crate::error::Result;
+use crate::models::AttendanceAnomaly;
+use crate::repository::AttendanceAnomalyRepository;
+use chrono::{DateTime, Duration, Utc};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Detects users who clocked in and never clocked out
+///
+/// Every scan records a `missing_clock_out` anomaly for each user whose latest
+/// clock event is a clock-in older than the threshold, and logs a warning for
+/// each newly detected one.
+#[derive(Clone)]
+pub struct MissingClockOutJob {
+    repo: AttendanceAnomalyRepository,
+    threshold: Duration,
+    interval: std::time::Duration,
+}
+
+impl MissingClockOutJob {
+    /// Create the job with an explicit threshold and scan interval
+    #[must_use]
+    pub const fn new(
+        repo: AttendanceAnomalyRepository,
+        threshold: Duration,
+        interval: std::time::Duration,
+    ) -> Self {
+        Self {
+            repo,
+            threshold,
+            interval,
+        }
+    }
+
+    /// Create the job with the configured threshold and scan interval
+    #[must_use]
+    pub const fn from_settings(repo: AttendanceAnomalyRepository, settings: &JobSettings) -> Self {
+        Self::new(
+            repo,
+            settings.missing_clock_out_after,
+            settings.anomaly_scan_interval,
+        )
+    }
+
+    /// Run a single scan as of `now`
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceAnomaly>)` - The newly detected anomalies
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<Vec<AttendanceAnomaly>> {
+        let anomalies = self
+            .repo
+            .record_missing_clock_outs(now - self.threshold)
+            .await?;
+
+        for anomaly in &anomalies {
+            tracing::warn!(
+                user_id = %anomaly.user_id,
+                event_id = %anomaly.event_id,
+                "Missing clock-out detected: {}",
+                anomaly.details
+            );
+        }
+
+        Ok(anomalies)
+    }
+
+    /// Spawn the job on the Tokio runtime, scanning at the configured interval
+    ///
+    /// Scan failures are logged and retried at the next tick.
+    #[must_use]
+    pub fn spawn(self) -> JoinHandle<()> {
+        tracing::info!(
+            threshold_hours = self.threshold.num_hours(),
+            interval_seconds = self.interval.as_secs(),
+            "Starting missing clock-out detection"
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once(Utc::now()).await {
+                    tracing::error!(error = %e, "Missing clock-out detection failed");
+                }
+            }
+        })
+    }
+}
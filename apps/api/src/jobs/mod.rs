@@ -0,0 +1,43 @@
+//! Periodic background jobs
+//!
+//! Jobs are spawned by the server binary, not by `create_router`, so tests and
+//! other embedders of the router do not start them implicitly.
+
+pub mod missing_clock_out;
+pub mod webhook_retry;
+
+use chrono::Duration;
+
+/// Default time after which an open clock-in is reported (12 hours)
+const DEFAULT_MISSING_CLOCK_OUT_HOURS: i64 = 12;
+
+/// Default interval between anomaly scans (5 minutes)
+const DEFAULT_ANOMALY_SCAN_INTERVAL_SECONDS: u64 = 300;
+
+/// Default interval between webhook retry runs (30 seconds)
+const DEFAULT_WEBHOOK_RETRY_INTERVAL_SECONDS: u64 = 30;
+
+/// Thresholds and intervals of the jobs (the `[jobs]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobSettings {
+    /// Time after which an open clock-in is reported as a missing clock-out
+    pub missing_clock_out_after: Duration,
+    /// Interval between scans for missing clock-outs
+    pub anomaly_scan_interval: std::time::Duration,
+    /// Interval between webhook retry runs
+    pub webhook_retry_interval: std::time::Duration,
+}
+
+impl Default for JobSettings {
+    fn default() -> Self {
+        Self {
+            missing_clock_out_after: Duration::hours(DEFAULT_MISSING_CLOCK_OUT_HOURS),
+            anomaly_scan_interval: std::time::Duration::from_secs(
+                DEFAULT_ANOMALY_SCAN_INTERVAL_SECONDS,
+            ),
+            webhook_retry_interval: std::time::Duration::from_secs(
+                DEFAULT_WEBHOOK_RETRY_INTERVAL_SECONDS,
+            ),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::JobSettings;
+use crate::error::Result;
+use crate::webhook::WebhookDispatcher;
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Maximum number of deliveries attempted per run
+const BATCH_SIZE: i64 = 100;
+
+/// Retries webhook deliveries whose previous attempt failed
+///
+/// Every run attempts the pending deliveries that are due, including ones whose
+/// first attempt was interrupted (e.g. by a restart).
+#[derive(Clone)]
+pub struct WebhookRetryJob {
+    dispatcher: WebhookDispatcher,
+    interval: std::time::Duration,
+}
+
+impl WebhookRetryJob {
+    /// Create the job with an explicit run interval
+    #[must_use]
+    pub const fn new(dispatcher: WebhookDispatcher, interval: std::time::Duration) -> Self {
+        Self {
+            dispatcher,
+            interval,
+        }
+    }
+
+    /// Create the job with the configured run interval
+    #[must_use]
+    pub const fn from_settings(dispatcher: WebhookDispatcher, settings: &JobSettings) -> Self {
+        Self::new(dispatcher, settings.webhook_retry_interval)
+    }
+
+    /// Run a single retry pass as of `now`
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of deliveries attempted
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize> {
+        let attempted = self.dispatcher.retry_due(now, BATCH_SIZE).await?;
+        Ok(attempted.len())
+    }
+
+    /// Spawn the job on the Tokio runtime, running at the configured interval
+    ///
+    /// Run failures are logged and retried at the next tick.
+    #[must_use]
+    pub fn spawn(self) -> JoinHandle<()> {
+        tracing::info!(
+            interval_seconds = self.interval.as_secs(),
+            "Starting webhook delivery retries"
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_once(Utc::now()).await {
+                    tracing::error!(error = %e, "Webhook delivery retry failed");
+                }
+            }
+        })
+    }
+}
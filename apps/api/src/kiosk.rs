@@ -0,0 +1,242 @@
+//! QR-code kiosk clock-in tokens and kiosk device tokens
+//!
+//! A user's device requests a short-lived token and shows it as a QR code; a
+//! shared kiosk scans it and submits it to record an attendance event for that
+//! user. Each token can be used once (its `jti` is recorded when used).
+//!
+//! Kiosks themselves authenticate with a device token issued by an admin. A
+//! device token is bound to one device id and only lets the kiosk record clock
+//! events, so a compromised kiosk cannot read user data or change anything
+//! else. Device tokens cannot be revoked individually; they are short-lived
+//! instead (at most [`MAX_DEVICE_TOKEN_TTL_MINUTES`]).
+
+use crate::token::{TokenError, TokenSettings, TokenSigner};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default lifetime of a kiosk token (60 seconds)
+pub(crate) const DEFAULT_TTL_SECONDS: i64 = 60;
+
+/// Longest configurable lifetime of a kiosk token (1 hour)
+pub(crate) const MAX_TTL_SECONDS: i64 = 60 * 60;
+
+/// Longest lifetime of a device token (24 hours)
+pub const MAX_DEVICE_TOKEN_TTL_MINUTES: i64 = 24 * 60;
+
+/// Value of the `typ` claim of device tokens
+const DEVICE_TOKEN_TYPE: &str = "device";
+
+/// Claims of a kiosk token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KioskClaims {
+    /// The user the event is recorded for
+    pub sub: Uuid,
+    /// Expiry as a Unix timestamp (seconds)
+    pub exp: i64,
+    /// Unique token id, used to reject replays
+    pub jti: Uuid,
+}
+
+impl KioskClaims {
+    /// Expiry as a timestamp
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp, 0).unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+}
+
+/// Claims of a device token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceClaims {
+    /// The device the token is bound to (its `X-Device-Id` header)
+    pub device_id: String,
+    /// Expiry as a Unix timestamp (seconds)
+    pub exp: i64,
+    /// Token type, always `device`
+    pub typ: String,
+}
+
+/// An issued kiosk or device token
+#[derive(Debug, Clone)]
+pub struct KioskToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Reasons a kiosk token is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KioskTokenError {
+    Invalid(TokenError),
+    Expired,
+}
+
+impl std::fmt::Display for KioskTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "Invalid kiosk token: {e}"),
+            Self::Expired => write!(f, "Kiosk token has expired"),
+        }
+    }
+}
+
+/// Issues and verifies kiosk tokens
+#[derive(Debug, Clone)]
+pub struct KioskTokens {
+    signer: TokenSigner,
+    ttl: Duration,
+}
+
+impl KioskTokens {
+    /// Create a kiosk token issuer with a signing secret and token lifetime
+    #[must_use]
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            signer: TokenSigner::new(secret),
+            ttl,
+        }
+    }
+
+    /// Create a kiosk token issuer with the configured secret and lifetime
+    #[must_use]
+    pub fn from_settings(settings: &TokenSettings) -> Self {
+        Self::new(
+            settings.secret_or_random("kiosk.token_secret").as_bytes(),
+            settings.ttl,
+        )
+    }
+
+    /// Issue a token for a user, valid from `now` for the configured lifetime
+    #[must_use]
+    pub fn issue(&self, user_id: Uuid, now: DateTime<Utc>) -> KioskToken {
+        let expires_at = now + self.ttl;
+        let claims = KioskClaims {
+            sub: user_id,
+            exp: expires_at.timestamp(),
+            jti: Uuid::new_v4(),
+        };
+
+        KioskToken {
+            token: self.signer.sign(&claims),
+            expires_at,
+        }
+    }
+
+    /// Verify a scanned token at `now`
+    ///
+    /// # Errors
+    /// Returns `KioskTokenError` if the token is malformed, forged or expired
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Result<KioskClaims, KioskTokenError> {
+        let claims: KioskClaims = self
+            .signer
+            .verify(token)
+            .map_err(KioskTokenError::Invalid)?;
+
+        if now.timestamp() >= claims.exp {
+            return Err(KioskTokenError::Expired);
+        }
+
+        Ok(claims)
+    }
+    /// Issue a device token for a kiosk, valid from `now` for `ttl`
+    #[must_use]
+    pub fn issue_device(&self, device_id: &str, ttl: Duration, now: DateTime<Utc>) -> KioskToken {
+        let expires_at = now + ttl;
+        let claims = DeviceClaims {
+            device_id: device_id.to_string(),
+            exp: expires_at.timestamp(),
+            typ: DEVICE_TOKEN_TYPE.to_string(),
+        };
+
+        KioskToken {
+            token: self.signer.sign(&claims),
+            expires_at,
+        }
+    }
+
+    /// Verify a device token at `now`
+    ///
+    /// # Errors
+    /// Returns `KioskTokenError` if the token is malformed, forged, not a device
+    /// token or expired
+    pub fn verify_device(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<DeviceClaims, KioskTokenError> {
+        let claims: DeviceClaims = self
+            .signer
+            .verify(token)
+            .map_err(KioskTokenError::Invalid)?;
+
+        if claims.typ != DEVICE_TOKEN_TYPE {
+            return Err(KioskTokenError::Invalid(TokenError::Malformed));
+        }
+        if now.timestamp() >= claims.exp {
+            return Err(KioskTokenError::Expired);
+        }
+
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        "2025-11-05T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_issue_and_verify() {
+        let tokens = KioskTokens::new(b"secret", Duration::seconds(60));
+        let user_id = Uuid::new_v4();
+
+        let issued = tokens.issue(user_id, now());
+        assert_eq!(issued.expires_at, now() + Duration::seconds(60));
+
+        let claims = tokens
+            .verify(&issued.token, now() + Duration::seconds(59))
+            .unwrap();
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.expires_at(), issued.expires_at);
+    }
+
+    #[test]
+    fn test_verify_rejects_expired_token() {
+        let tokens = KioskTokens::new(b"secret", Duration::seconds(60));
+        let issued = tokens.issue(Uuid::new_v4(), now());
+
+        assert_eq!(
+            tokens.verify(&issued.token, now() + Duration::seconds(60)),
+            Err(KioskTokenError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_token_from_other_secret() {
+        let issued = KioskTokens::new(b"other", Duration::seconds(60)).issue(Uuid::new_v4(), now());
+        let tokens = KioskTokens::new(b"secret", Duration::seconds(60));
+
+        assert_eq!(
+            tokens.verify(&issued.token, now()),
+            Err(KioskTokenError::Invalid(TokenError::InvalidSignature))
+        );
+    }
+    #[test]
+    fn test_device_tokens_are_not_kiosk_tokens() {
+        let tokens = KioskTokens::new(b"secret", Duration::seconds(60));
+        let device = tokens.issue_device("kiosk-1", Duration::hours(8), now());
+        let kiosk = tokens.issue(Uuid::new_v4(), now());
+
+        let claims = tokens.verify_device(&device.token, now()).unwrap();
+        assert_eq!(claims.device_id, "kiosk-1");
+        assert_eq!(
+            tokens.verify_device(&device.token, now() + Duration::hours(8)),
+            Err(KioskTokenError::Expired)
+        );
+        assert!(tokens.verify(&device.token, now()).is_err());
+        assert!(tokens.verify_device(&kiosk.token, now()).is_err());
+    }
+}
@@ -1,21 +1,69 @@
+pub mod attendance;
+pub mod auth;
+pub mod body_limit;
+pub mod compression;
+pub mod config;
+pub mod cors;
 pub mod db;
 pub mod error;
+pub mod export;
+pub mod extract;
+pub mod grpc;
 pub mod handlers;
+pub mod health;
+pub mod idempotency;
+pub mod invitation;
+pub mod jobs;
+pub mod kiosk;
+pub mod load_shed;
+pub mod mail;
 pub mod models;
+pub mod oauth;
+pub mod pagination;
+pub mod password;
+pub mod permission;
+pub mod pool_stats;
+pub mod presence;
+pub mod problem;
+pub mod rate_limit;
+pub mod reporting;
 pub mod repository;
+pub mod request_id;
+pub mod service;
+pub mod session;
+pub mod spa;
+pub mod state;
+pub mod storage;
 pub mod store;
+pub mod timeout;
+pub mod token;
+pub mod validation;
+pub mod webhook;
 
 use axum::{
     Json, Router,
-    routing::{delete, get, post, put},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{any, delete, get, patch, post, put},
 };
-pub use db::init_db_pool;
+pub use config::AppConfig;
+use db::ReadPool;
+pub use db::{init_db_pool, init_read_pool, run_migrations};
 use error::Result;
-pub use repository::{AttendanceEventRepository, UserRepository};
+use models::Permission;
+use permission::RequirePermission;
+pub use repository::{
+    ApiKeyRepository, AttendanceAnomalyRepository, AttendanceCorrectionRepository,
+    AttendanceEventRepository, HolidayRepository, IdempotencyKeyRepository, InvitationRepository,
+    PasswordResetRepository, PermissionRepository, SessionRepository, UserRepository,
+    WebhookRepository, WorkPolicyRepository,
+};
 use serde::Serialize;
 use sqlx::PgPool;
-pub use store::TodoStore;
-use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
+pub use state::AppState;
+pub use store::{InMemoryTodoBackend, TodoBackend, TodoStore};
+use tower_http::services::ServeDir;
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
 use tracing::Level;
 
 #[derive(Serialize)]
@@ -53,6 +101,11 @@ async fn test_error_unauthorized() -> Result<Json<HealthResponse>> {
     ))
 }
 
+#[cfg(any(debug_assertions, test))]
+async fn test_error_forbidden() -> Result<Json<HealthResponse>> {
+    Err(error::AppError::Forbidden("Access denied".to_string()))
+}
+
 #[cfg(any(debug_assertions, test))]
 async fn test_error_notfound() -> Result<Json<HealthResponse>> {
     Err(error::AppError::NotFound("Resource not found".to_string()))
@@ -65,34 +118,109 @@ async fn test_error_badrequest() -> Result<Json<HealthResponse>> {
     ))
 }
 
+#[cfg(any(debug_assertions, test))]
+async fn test_error_conflict() -> Result<Json<HealthResponse>> {
+    Err(error::AppError::Conflict(
+        "Resource state conflict".to_string(),
+    ))
+}
+
+#[cfg(any(debug_assertions, test))]
+async fn test_error_service_unavailable() -> Result<Json<HealthResponse>> {
+    Err(error::AppError::ServiceUnavailable {
+        message: "Service temporarily unavailable".to_string(),
+        retry_after: Some(30),
+    })
+}
+
 /// Create the application router
 /// This function is public to allow testing
 ///
 /// # Arguments
-/// * `store` - `TodoStore` for in-memory todo operations
-/// * `pool` - Database connection pool for user operations
-pub fn create_router(store: TodoStore, pool: PgPool) -> Router {
-    // Create repositories
-    let user_repo = UserRepository::new(pool);
-
-    // Router configuration
-    #[cfg_attr(not(any(debug_assertions, test)), allow(unused_mut))]
-    let mut app = Router::new()
+/// * `config` - Loaded configuration; deployment settings and policies come from it
+/// * `store` - `TodoStore` for todo operations
+/// * `pool` - Database connection pool for user and attendance operations
+pub fn create_router(config: &AppConfig, store: TodoStore, pool: PgPool) -> Router {
+    router(AppState::from_config(
+        config,
+        store,
+        pool.clone(),
+        ReadPool::primary(pool),
+    ))
+}
+
+/// Create the application router from a prepared state
+/// Allows tests to override deployment settings such as the admin API key
+///
+/// # Arguments
+/// * `state` - Shared state; handlers extract the repositories and policies they need
+pub fn router(state: AppState) -> Router {
+    // Uploads kept on local disk are served by the API itself
+    let uploads_dir = state.storage.local_dir().map(ToOwned::to_owned);
+    let spa = state.spa.service();
+    let error_format = state.error_format;
+    let cors = state.cors.layer();
+    let compression = state.compression.layer();
+    let load_shed = state.load_shedding.layer();
+
+    // Health checks and metrics answer even when requests are shed
+    let health = Router::new()
         .route("/health", get(health_check))
-        // Todo CRUD endpoints (using TodoStore state)
-        .route("/api/todos", get(handlers::get_todos))
-        .route("/api/todos", post(handlers::create_todo))
-        .route("/api/todos/{id}", get(handlers::get_todo))
-        .route("/api/todos/{id}", put(handlers::update_todo))
-        .route("/api/todos/{id}", delete(handlers::delete_todo))
-        .with_state(store)
-        // User CRUD endpoints (using UserRepository state)
-        .route("/api/users", get(handlers::get_users))
-        .route("/api/users", post(handlers::create_user))
-        .route("/api/users/{id}", get(handlers::get_user))
-        .route("/api/users/{id}", put(handlers::update_user))
-        .route("/api/users/{id}", delete(handlers::delete_user))
-        .with_state(user_repo);
+        // Liveness and readiness checks for orchestrators (using HealthCheck)
+        .route("/health/live", get(handlers::liveness))
+        .route("/health/ready", get(handlers::readiness))
+        // Pool statistics for Prometheus
+        .route("/metrics", get(handlers::metrics))
+        .layer(state.timeouts.layer());
+
+    // Router configuration; routes that authenticate users require a
+    // permission (see `permission`)
+    let mut api = Router::new()
+        .merge(todo_routes(&state))
+        .merge(user_routes(&state))
+        .merge(attendance_routes(&state))
+        .merge(admin_routes())
+        .layer(state.timeouts.layer())
+        .merge(export_routes(&state))
+        // Bodies are limited by these layers instead of axum's default limit
+        .layer(state.body_limits.layer())
+        .merge(upload_routes(&state))
+        .layer(DefaultBodyLimit::disable())
+        // Retried POST requests with an Idempotency-Key get the stored response
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            idempotency::idempotent_requests,
+        ))
+        // State-changing requests authenticated by the session cookie need the
+        // session's CSRF token
+        .layer(middleware::from_fn(session::require_csrf_token))
+        // Rate limits apply per user, identified by the session loaded below
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_requests,
+        ))
+        // Cookie sessions are loaded for every route, so `AuthUser` accepts them
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            session::load_session,
+        ));
+
+    // Excess requests fail fast instead of piling up on a slow database
+    if let Some(load_shed) = load_shed {
+        api = api.layer(load_shed);
+    }
+    let mut app = api.merge(health).with_state(state);
+
+    if let Some(dir) = uploads_dir {
+        app = app.nest_service(storage::LOCAL_UPLOADS_PATH, ServeDir::new(dir));
+    }
+
+    // The frontend, when configured, answers every path no route matches
+    if let Some(spa) = spa {
+        app = app
+            .route("/api/{*path}", any(spa::api_not_found))
+            .fallback_service(spa);
+    }
 
     // Error handling test endpoints (only available in debug builds or test environments)
     #[cfg(any(debug_assertions, test))]
@@ -102,14 +230,417 @@ pub fn create_router(store: TodoStore, pool: PgPool) -> Router {
             .route("/test/error/internal", get(test_error_internal))
             .route("/test/error/validation", get(test_error_validation))
             .route("/test/error/unauthorized", get(test_error_unauthorized))
+            .route("/test/error/forbidden", get(test_error_forbidden))
             .route("/test/error/notfound", get(test_error_notfound))
-            .route("/test/error/badrequest", get(test_error_badrequest));
+            .route("/test/error/badrequest", get(test_error_badrequest))
+            .route("/test/error/conflict", get(test_error_conflict))
+            .route(
+                "/test/error/service-unavailable",
+                get(test_error_service_unavailable),
+            );
+    }
+
+    // Server errors are reported with the request they occurred in
+    app = app.layer(middleware::from_fn(reporting::capture_request));
+
+    // Extractor rejections and timeouts use the JSON error envelope like every
+    // other error
+    app = app
+        .layer(middleware::from_fn(extract::json_rejections))
+        .layer(middleware::from_fn(timeout::json_timeouts));
+
+    // Pages of list responses link to their neighbours
+    app = app.layer(middleware::from_fn(pagination::link_headers));
+
+    // Error responses become problem details when the client asks for them
+    app = app.layer(middleware::from_fn_with_state(
+        error_format,
+        problem::problem_details,
+    ));
+
+    // Cross-origin requests from allowed origins (e.g. the SPA dev server)
+    if let Some(cors) = cors {
+        app = app.layer(cors);
     }
 
     // Add HTTP request/response tracing
-    app.layer(
+    app = app.layer(
         TraceLayer::new_for_http()
-            .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
+            .make_span_with(request_id::RequestSpan)
             .on_response(DefaultOnResponse::new().level(Level::INFO)),
-    )
+    );
+
+    // Compress responses for clients that accept it
+    if let Some(compression) = compression {
+        app = app.layer(compression);
+    }
+
+    // Outermost, so every layer and error response sees the request id
+    app.layer(middleware::from_fn(request_id::assign_request_id))
+}
+
+/// Todo routes (using `TodoStore`)
+fn todo_routes(state: &AppState) -> Router<AppState> {
+    let require = |permission| RequirePermission::new(state, permission);
+    let (todos_read, todos_write) = (
+        require(Permission::TodosRead),
+        require(Permission::TodosWrite),
+    );
+
+    Router::new()
+        .route(
+            "/api/todos",
+            get(handlers::get_todos).route_layer(todos_read.clone()),
+        )
+        .route(
+            "/api/todos",
+            post(handlers::create_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/tags",
+            get(handlers::get_todo_tags).route_layer(todos_read.clone()),
+        )
+        .route(
+            "/api/todos/bulk/complete",
+            post(handlers::complete_todos_bulk).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/bulk/delete",
+            post(handlers::delete_todos_bulk).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}",
+            get(handlers::get_todo).route_layer(todos_read),
+        )
+        .route(
+            "/api/todos/{id}",
+            put(handlers::update_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}",
+            patch(handlers::patch_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}",
+            delete(handlers::delete_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}/archive",
+            post(handlers::archive_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}/unarchive",
+            post(handlers::unarchive_todo).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}/items",
+            post(handlers::add_todo_item).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}/items/{item_id}",
+            put(handlers::update_todo_item).route_layer(todos_write.clone()),
+        )
+        .route(
+            "/api/todos/{id}/items/{item_id}",
+            delete(handlers::delete_todo_item).route_layer(todos_write),
+        )
+}
+
+/// User, profile, authentication and invitation routes
+fn user_routes(state: &AppState) -> Router<AppState> {
+    let require = |permission| RequirePermission::new(state, permission);
+    let (users_read, users_write) = (
+        require(Permission::UsersRead),
+        require(Permission::UsersWrite),
+    );
+
+    Router::new()
+        // User CRUD endpoints (using UserRepository)
+        .route(
+            "/api/users",
+            get(handlers::get_users).route_layer(users_read.clone()),
+        )
+        .route(
+            "/api/users",
+            post(handlers::create_user).route_layer(users_write.clone()),
+        )
+        .route(
+            "/api/users/bulk",
+            post(handlers::create_users_bulk).route_layer(users_write.clone()),
+        )
+        .route(
+            "/api/users/{id}",
+            get(handlers::get_user).route_layer(users_read.clone()),
+        )
+        .route("/api/users/{id}", put(handlers::update_user))
+        .route(
+            "/api/users/{id}",
+            delete(handlers::delete_user).route_layer(users_write.clone()),
+        )
+        .route(
+            "/api/users/{id}/restore",
+            post(handlers::restore_user).route_layer(users_write.clone()),
+        )
+        .route(
+            "/api/users/{id}/deactivate",
+            post(handlers::deactivate_user).route_layer(users_write.clone()),
+        )
+        .route(
+            "/api/users/{id}/activate",
+            post(handlers::activate_user).route_layer(users_write.clone()),
+        )
+        .route("/api/users/{id}/password", post(handlers::change_password))
+        .route("/api/users/{id}/purge", delete(handlers::purge_user))
+        .route(
+            "/api/users/{id}/activity",
+            get(handlers::get_user_activity).route_layer(users_read),
+        )
+        // Profile of the authenticated user (using UserRepository and AuthTokens)
+        .route("/api/me", get(handlers::get_me))
+        .route("/api/me", put(handlers::update_me))
+        // Authentication endpoints (using UserRepository, PasswordResetRepository,
+        // SessionRepository and AuthTokens)
+        .route("/api/auth/register", post(handlers::register))
+        .route("/api/auth/login", post(handlers::login))
+        .route("/api/auth/logout", post(handlers::logout))
+        .route("/api/auth/logout-all", post(handlers::logout_all))
+        .route("/api/auth/csrf", get(handlers::get_csrf_token))
+        .route("/api/auth/google", get(handlers::google_sign_in))
+        .route("/api/auth/google/callback", get(handlers::google_callback))
+        .route(
+            "/api/auth/forgot-password",
+            post(handlers::forgot_password),
+        )
+        .route("/api/auth/reset-password", post(handlers::reset_password))
+        // Invitation endpoints (using InvitationRepository)
+        .route("/api/invitations", post(handlers::create_invitation).route_layer(users_write))
+        .route(
+            "/api/invitations/accept",
+            post(handlers::accept_invitation),
+        )
+}
+
+/// Attendance, presence, kiosk, correction, work policy and holiday routes
+fn attendance_routes(state: &AppState) -> Router<AppState> {
+    let require = |permission| RequirePermission::new(state, permission);
+    let (attendance_read, attendance_write) = (
+        require(Permission::AttendanceRead),
+        require(Permission::AttendanceWrite),
+    );
+    let (corrections_approve, policies_write) = (
+        require(Permission::CorrectionsApprove),
+        require(Permission::PoliciesWrite),
+    );
+
+    Router::new()
+        // Attendance event endpoints (using AttendanceEventRepository)
+        .route(
+            "/api/attendance-events",
+            post(handlers::create_attendance_event).route_layer(attendance_write.clone()),
+        )
+        .route(
+            "/api/attendance-events/{id}",
+            get(handlers::get_attendance_event).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance-events",
+            get(handlers::get_user_attendance_events).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance/timesheet",
+            get(handlers::get_timesheet).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance/breaks",
+            get(handlers::get_break_summary).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance/overtime",
+            get(handlers::get_overtime).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance/anomalies",
+            get(handlers::get_user_attendance_anomalies).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/attendance/summary",
+            get(handlers::get_attendance_summary).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/attendance/anomalies",
+            get(handlers::get_attendance_anomalies).route_layer(attendance_read.clone()),
+        )
+        // Realtime presence (using PresenceHub)
+        .route("/api/ws", get(handlers::presence_socket))
+        // Kiosk endpoints (using AttendanceEventRepository and KioskTokens)
+        .route(
+            "/api/users/{id}/kiosk-token",
+            post(handlers::issue_kiosk_token),
+        )
+        .route("/api/attendance/kiosk-clock", post(handlers::kiosk_clock))
+        // Attendance correction endpoints (using AttendanceCorrectionRepository)
+        .route(
+            "/api/attendance-corrections",
+            get(handlers::get_attendance_corrections).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/attendance-corrections",
+            post(handlers::create_attendance_correction).route_layer(attendance_write),
+        )
+        .route(
+            "/api/attendance-corrections/{id}",
+            get(handlers::get_attendance_correction).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/attendance-corrections/{id}/approve",
+            post(handlers::approve_attendance_correction).route_layer(corrections_approve.clone()),
+        )
+        .route(
+            "/api/attendance-corrections/{id}/reject",
+            post(handlers::reject_attendance_correction).route_layer(corrections_approve),
+        )
+        // Work policy endpoints (using WorkPolicyRepository)
+        .route(
+            "/api/work-policies",
+            get(handlers::get_work_policies).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/work-policies",
+            post(handlers::create_work_policy).route_layer(policies_write.clone()),
+        )
+        .route(
+            "/api/work-policies/active",
+            get(handlers::get_active_work_policy).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/work-policies/{id}",
+            get(handlers::get_work_policy).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/work-policies/{id}",
+            put(handlers::update_work_policy).route_layer(policies_write.clone()),
+        )
+        .route(
+            "/api/work-policies/{id}",
+            delete(handlers::delete_work_policy).route_layer(policies_write.clone()),
+        )
+        .route(
+            "/api/work-policies/{id}/activate",
+            post(handlers::activate_work_policy).route_layer(policies_write.clone()),
+        )
+        // Holiday endpoints (using HolidayRepository)
+        .route("/api/holidays", get(handlers::get_holidays).route_layer(attendance_read.clone()))
+        .route("/api/holidays", post(handlers::create_holiday).route_layer(policies_write.clone()))
+        .route(
+            "/api/holidays/bulk",
+            post(handlers::create_holidays_bulk).route_layer(policies_write.clone()),
+        )
+        .route("/api/holidays/{id}", get(handlers::get_holiday).route_layer(attendance_read))
+        .route(
+            "/api/holidays/{id}",
+            put(handlers::update_holiday).route_layer(policies_write.clone()),
+        )
+        .route("/api/holidays/{id}", delete(handlers::delete_holiday).route_layer(policies_write))
+}
+
+/// Admin routes (require an admin bearer token)
+fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/admin/attendance-events/{id}",
+            get(handlers::get_attendance_event_detail),
+        )
+        .route("/api/admin/users/deleted", get(handlers::get_deleted_users))
+        .route("/api/admin/users/stats", get(handlers::get_user_stats))
+        .route("/api/admin/db/pool", get(handlers::get_db_pool_stats))
+        .route("/api/admin/users/{id}/role", put(handlers::set_user_role))
+        .route("/api/admin/users/{id}/unlock", post(handlers::unlock_user))
+        .route(
+            "/api/admin/permissions",
+            get(handlers::get_role_permissions),
+        )
+        .route(
+            "/api/admin/roles/{role}/permissions",
+            put(handlers::set_role_permissions),
+        )
+        .route(
+            "/api/admin/device-tokens",
+            post(handlers::issue_device_token),
+        )
+        .route("/api/admin/api-keys", get(handlers::get_api_keys))
+        .route("/api/admin/api-keys", post(handlers::create_api_key))
+        .route("/api/admin/api-keys/{id}", get(handlers::get_api_key))
+        .route("/api/admin/api-keys/{id}", delete(handlers::revoke_api_key))
+        .route("/api/admin/webhooks", get(handlers::get_webhooks))
+        .route("/api/admin/webhooks", post(handlers::create_webhook))
+        .route("/api/admin/webhooks/{id}", get(handlers::get_webhook))
+        .route("/api/admin/webhooks/{id}", put(handlers::update_webhook))
+        .route("/api/admin/webhooks/{id}", delete(handlers::delete_webhook))
+        .route(
+            "/api/admin/webhooks/{id}/deliveries",
+            get(handlers::get_webhook_deliveries),
+        )
+        .route(
+            "/api/admin/webhooks/{id}/deliveries/{delivery_id}/retry",
+            post(handlers::retry_webhook_delivery),
+        )
+}
+
+/// Export routes, which may take longer than the other routes
+fn export_routes(state: &AppState) -> Router<AppState> {
+    let require = |permission| RequirePermission::new(state, permission);
+    let (todos_read, attendance_read) = (
+        require(Permission::TodosRead),
+        require(Permission::AttendanceRead),
+    );
+
+    Router::new()
+        .route(
+            "/api/todos/export",
+            get(handlers::export_todos).route_layer(todos_read),
+        )
+        .route(
+            "/api/users/{id}/attendance/timesheet.xlsx",
+            get(handlers::export_timesheet_xlsx).route_layer(attendance_read.clone()),
+        )
+        .route(
+            "/api/users/{id}/attendance/calendar.ics",
+            get(handlers::export_calendar_ics).route_layer(attendance_read),
+        )
+        .route(
+            "/api/attendance/payroll-export",
+            get(handlers::export_payroll_csv),
+        )
+        .route(
+            "/api/admin/attendance-events/export.ndjson",
+            get(handlers::export_attendance_events_ndjson),
+        )
+        .route(
+            "/api/admin/users/export.csv",
+            get(handlers::export_users_csv),
+        )
+        .layer(state.timeouts.export_layer())
+}
+
+/// Import and upload routes, which accept larger bodies and may take longer
+/// than the other routes
+fn upload_routes(state: &AppState) -> Router<AppState> {
+    let require = |permission| RequirePermission::new(state, permission);
+    let (todos_write, attendance_write) = (
+        require(Permission::TodosWrite),
+        require(Permission::AttendanceWrite),
+    );
+
+    Router::new()
+        .route(
+            "/api/todos/import",
+            post(handlers::import_todos).route_layer(todos_write),
+        )
+        .route(
+            "/api/attendance-events/import",
+            post(handlers::import_attendance_events).route_layer(attendance_write),
+        )
+        .route("/api/users/{id}/avatar", post(handlers::upload_avatar))
+        .layer(state.body_limits.upload_layer())
+        .layer(state.timeouts.export_layer())
 }
@@ -0,0 +1,105 @@
+//! Concurrency limit and load shedding
+//!
+//! When Postgres slows down, requests take longer and pile up, each holding
+//! memory and waiting for a connection. At most
+//! `load_shedding.max_concurrent_requests` (`MAX_CONCURRENT_REQUESTS`)
+//! requests are processed at a time; requests beyond the limit are not queued
+//! but fail at once with `503 Service Unavailable` and a `Retry-After` header,
+//! so clients back off and the server recovers when the database does.
+//!
+//! The limit is shared by all routes except the health checks, which
+//! orchestrators must reach even when the server is overloaded.
+
+use crate::error::AppError;
+use axum::{BoxError, error_handling::HandleErrorLayer};
+use std::future::{Ready, ready};
+use tower::{
+    ServiceBuilder,
+    layer::util::{Identity, Stack},
+    limit::GlobalConcurrencyLimitLayer,
+    load_shed::LoadShedLayer,
+};
+
+/// Limit of concurrent requests when `load_shedding.max_concurrent_requests` is
+/// unset
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 512;
+
+/// Seconds clients are asked to wait before retrying a shed request
+const RETRY_AFTER_SECONDS: u64 = 1;
+
+/// Turns the error of a shed request into a response
+type HandleOverloaded = fn(BoxError) -> Ready<AppError>;
+
+/// Layer limiting concurrent requests and shedding the excess
+pub type LoadShedStack = Stack<
+    GlobalConcurrencyLimitLayer,
+    Stack<LoadShedLayer, Stack<HandleErrorLayer<HandleOverloaded, ()>, Identity>>,
+>;
+
+/// Load shedding settings (the `[load_shedding]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadShedding {
+    /// Requests processed at a time (`None` = unlimited)
+    pub max_concurrent_requests: Option<usize>,
+}
+
+impl Default for LoadShedding {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: Some(DEFAULT_MAX_CONCURRENT_REQUESTS),
+        }
+    }
+}
+
+impl LoadShedding {
+    /// The layer for the router, or `None` when requests are unlimited
+    #[must_use]
+    pub fn layer(&self) -> Option<LoadShedStack> {
+        let max = self.max_concurrent_requests?;
+        Some(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(overloaded as HandleOverloaded))
+                .layer(LoadShedLayer::new())
+                .layer(GlobalConcurrencyLimitLayer::new(max))
+                .into_inner(),
+        )
+    }
+}
+
+/// Turn the error of a shed request into a `503` response
+fn overloaded(error: BoxError) -> Ready<AppError> {
+    ready(
+        match error.downcast::<tower::load_shed::error::Overloaded>() {
+            Ok(_) => {
+                tracing::warn!("Too many concurrent requests, shedding load");
+                AppError::ServiceUnavailable {
+                    message: "The server is overloaded, please retry later".to_string(),
+                    retry_after: Some(RETRY_AFTER_SECONDS),
+                }
+            }
+            Err(error) => {
+                AppError::InternalServerError(format!("Unhandled middleware error: {error}"))
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_limit() {
+        assert_eq!(
+            LoadShedding::default().max_concurrent_requests,
+            Some(DEFAULT_MAX_CONCURRENT_REQUESTS)
+        );
+        assert!(
+            LoadShedding {
+                max_concurrent_requests: None
+            }
+            .layer()
+            .is_none()
+        );
+    }
+}
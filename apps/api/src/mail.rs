@@ -0,0 +1,78 @@
+//! Outgoing email
+//!
+//! Emails go through [`Mailer`], a cheap-to-clone handle over a
+//! [`MailTransport`]. The only built-in transport, [`LogTransport`], writes
+//! messages to the log and is meant for development; deployments plug in a
+//! transport for their mail service.
+
+use crate::error::Result;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+
+/// A plain-text email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// A way of delivering emails
+pub trait MailTransport: Send + Sync {
+    /// Deliver an email
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Writes emails to the log instead of sending them
+///
+/// The recipient and subject are logged at `INFO`, the body (which may hold
+/// secrets such as reset links) only at `DEBUG`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogTransport;
+
+impl MailTransport for LogTransport {
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            tracing::info!(to = %email.to, subject = %email.subject, "Email not sent (log transport)");
+            tracing::debug!(to = %email.to, body = %email.body, "Email body");
+            Ok(())
+        })
+    }
+}
+
+/// Shared handle to the configured mail transport
+#[derive(Clone)]
+pub struct Mailer(Arc<dyn MailTransport>);
+
+impl Mailer {
+    #[must_use]
+    pub fn new(transport: impl MailTransport + 'static) -> Self {
+        Self(Arc::new(transport))
+    }
+
+    /// Send an email
+    ///
+    /// # Errors
+    /// Returns error if the transport fails to deliver the email
+    pub async fn send(&self, email: &Email) -> Result<()> {
+        self.0.send(email).await
+    }
+
+    /// Send an email in the background
+    ///
+    /// Delivery is best effort: failures are logged instead of being returned.
+    pub fn send_in_background(&self, email: Email) {
+        let mailer = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(&email).await {
+                tracing::error!(to = %email.to, error = %e, "Failed to send email");
+            }
+        });
+    }
+}
+
+impl Default for Mailer {
+    fn default() -> Self {
+        Self::new(LogTransport)
+    }
+}
@@ -1,7 +1,44 @@
-use api::{create_router, error::Result, init_db_pool, store::TodoStore};
+use api::{
+    AppConfig, AppState, AttendanceAnomalyRepository, WebhookRepository,
+    error::Result,
+    grpc, init_db_pool, init_read_pool,
+    jobs::{missing_clock_out::MissingClockOutJob, webhook_retry::WebhookRetryJob},
+    run_migrations,
+    store::TodoStore,
+    webhook::WebhookDispatcher,
+};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
 use std::net::SocketAddr;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Serve a router on `addr`, terminating TLS when certificates are configured
+async fn serve(router: Router, addr: SocketAddr, tls: Option<RustlsConfig>) -> std::io::Result<()> {
+    // Connect info provides the peer address recorded with attendance events
+    let service = router.into_make_service_with_connect_info::<SocketAddr>();
+    if let Some(tls) = tls {
+        axum_server::bind_rustls(addr, tls).serve(service).await
+    } else {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, service).await
+    }
+}
+
+/// Serve a router as plain HTTP on a Unix socket at `path`
+///
+/// A socket left behind by a previous run is replaced. The proxy in front
+/// passes the client address in `X-Forwarded-For`, as there is no peer address;
+/// only processes allowed to open the socket can connect, so it is believed.
+async fn serve_unix(router: Router, path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    axum::serve(listener, router.into_make_service()).await
+}
+
 /// Initialize tracing
 fn init_tracing() {
     tracing_subscriber::registry()
@@ -18,30 +55,116 @@ async fn main() -> Result<()> {
     // Initialize tracing
     init_tracing();
 
+    // Report server errors to Sentry when configured
+    #[cfg(feature = "sentry")]
+    let _sentry = api::reporting::sentry::init_from_env();
+
     tracing::info!("Starting API server");
 
+    // Load configuration (defaults, CONFIG_FILE, environment)
+    let config = AppConfig::load().map_err(|e| {
+        tracing::error!("Failed to load configuration: {e}");
+        std::io::Error::other(format!("Invalid configuration: {e}"))
+    })?;
+
     // Initialize database connection pool
-    let db_pool = init_db_pool().await.map_err(|e| {
+    let db_pool = init_db_pool(&config.database).await.map_err(|e| {
         tracing::error!("Failed to initialize database connection pool: {e}");
         std::io::Error::other(format!("Database connection failed: {e}"))
     })?;
 
     tracing::info!("Database connection pool established");
 
+    // Single-container deployments migrate on startup instead of in a separate step
+    if config.database.run_migrations {
+        run_migrations(&db_pool).await.map_err(|e| {
+            tracing::error!("Failed to run database migrations: {e}");
+            std::io::Error::other(format!("Database migration failed: {e}"))
+        })?;
+    }
+
+    // Lists, reports and exports read from the replica when configured
+    let read_pool = init_read_pool(&config.database, db_pool.clone()).map_err(|e| {
+        tracing::error!("Invalid read replica configuration: {e}");
+        std::io::Error::other(format!("Invalid read replica URL: {e}"))
+    })?;
+    let _replica_health = read_pool.spawn_health_check();
+
+    // Start background jobs
+    let _missing_clock_out = MissingClockOutJob::from_settings(
+        AttendanceAnomalyRepository::new(db_pool.clone()),
+        &config.jobs,
+    )
+    .spawn();
+    let _webhook_retry = WebhookRetryJob::from_settings(
+        WebhookDispatcher::from_settings(WebhookRepository::new(db_pool.clone()), &config.webhooks),
+        &config.jobs,
+    )
+    .spawn();
+
     // Initialize data store (in-memory store for todos)
     let store = TodoStore::new();
 
-    // Create router with both TodoStore and database pool
-    let app = create_router(store, db_pool);
+    // The HTTP and gRPC servers share the state (repositories, presence updates)
+    let state = AppState::from_config(&config, store, db_pool, read_pool);
+
+    // Load the TLS certificate when configured
+    let tls_config = match &config.server.tls {
+        Some(tls) => {
+            rustls::crypto::ring::default_provider()
+                .install_default()
+                .map_err(|_| std::io::Error::other("A TLS crypto provider is already installed"))?;
+            let tls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to load TLS certificate and key: {e}");
+                    e
+                })?;
+            tracing::info!("Serving HTTPS");
+            Some(tls_config)
+        }
+        None => None,
+    };
 
     // Configure server address
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    tracing::info!("Server listening on {}", addr);
+    let app = api::router(state.clone());
+    let http = {
+        let app = app.clone();
+        let tls_config = tls_config.clone();
+        async {
+            if config.server.tcp {
+                let addr = config.server.addr();
+                tracing::info!("Server listening on {}", addr);
+                serve(app, addr, tls_config).await
+            } else {
+                Ok(())
+            }
+        }
+    };
+
+    // Serve the same API on a Unix socket when configured
+    let unix = async {
+        match &config.server.unix_socket {
+            Some(path) => {
+                tracing::info!("Server listening on {}", path.display());
+                serve_unix(app, path).await
+            }
+            None => Ok(()),
+        }
+    };
 
-    // Start server
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    // Start the gRPC server on its own port when configured
+    let grpc = async {
+        match config.server.grpc_addr() {
+            Some(grpc_addr) => {
+                tracing::info!("gRPC server listening on {}", grpc_addr);
+                serve(grpc::router(state), grpc_addr, tls_config).await
+            }
+            None => Ok(()),
+        }
+    };
 
-    axum::serve(listener, app).await?;
+    tokio::try_join!(http, unix, grpc)?;
 
     Ok(())
 }
@@ -1,14 +1,167 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
 use uuid::Uuid;
 
 /// Todo リソースのデータモデル
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Todo {
     pub id: u64,
+    /// User who created the todo
+    pub owner_id: Uuid,
     pub title: String,
     pub description: Option<String>,
     pub completed: bool,
+    /// Archived todos are hidden from the todo list unless asked for
+    pub archived: bool,
+    pub priority: TodoPriority,
+    pub tags: Vec<String>,
+    pub due_date: Option<NaiveDate>,
+    /// Checklist of subtasks, in the order they were added
+    pub items: Vec<TodoItem>,
+    pub created_at: DateTime<Utc>,
+    /// Last change to the todo, its checklist or its archive state
+    pub updated_at: DateTime<Utc>,
+    /// When the todo was last completed (`None` while it is open)
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Todo {
+    /// Set the completed flag
+    /// `completed_at` is set when the todo becomes completed, kept while it
+    /// stays completed and cleared when it is reopened
+    pub const fn set_completed(&mut self, completed: bool, now: DateTime<Utc>) {
+        if !completed {
+            self.completed_at = None;
+        } else if !self.completed {
+            self.completed_at = Some(now);
+        }
+        self.completed = completed;
+    }
+
+    /// Percentage of the checklist items that are done, rounded down
+    /// (`None` if the todo has no items)
+    #[must_use]
+    pub fn completion_percentage(&self) -> Option<u8> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let done = self.items.iter().filter(|item| item.done).count();
+        u8::try_from(done * 100 / self.items.len()).ok()
+    }
+}
+
+/// Todo のチェックリスト項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoItem {
+    pub id: u64,
+    pub text: String,
+    pub done: bool,
+}
+
+/// Todo 一覧の並び順のキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TodoSort {
+    /// Highest priority first unless `order=asc` is given
+    #[default]
+    Priority,
+    /// Creation order
+    Created,
+    /// Title, ignoring case
+    Title,
+    /// Due date; todos without one come last in either order
+    DueDate,
+}
+
+impl TodoSort {
+    /// All sort keys
+    pub const ALL: [Self; 4] = [Self::Priority, Self::Created, Self::Title, Self::DueDate];
+
+    /// The string representation used in the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::Created => "created",
+            Self::Title => "title",
+            Self::DueDate => "due_date",
+        }
+    }
+
+    /// Direction used when no order is given
+    #[must_use]
+    pub const fn default_order(self) -> SortOrder {
+        match self {
+            Self::Priority => SortOrder::Desc,
+            Self::Created | Self::Title | Self::DueDate => SortOrder::Asc,
+        }
+    }
+}
+
+impl FromStr for TodoSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|sort| sort.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("Sort must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Todo の優先度
+/// Ordered from lowest to highest, so the highest priority compares greatest
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Urgent,
+}
+
+impl TodoPriority {
+    /// All priorities, lowest first
+    pub const ALL: [Self; 4] = [Self::Low, Self::Medium, Self::High, Self::Urgent];
+
+    /// The string representation used in the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+impl fmt::Display for TodoPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TodoPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|priority| priority.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|p| p.as_str()).collect();
+                format!("Priority must be one of: {}", valid.join(", "))
+            })
+    }
 }
 
 /// User entity from database
@@ -19,25 +172,272 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub picture: Option<String>,
+    pub role: UserRole,
+    /// `false` if the user is deactivated
+    pub is_active: bool,
+    /// Version of the user's access tokens; tokens of older versions are revoked
+    #[serde(skip)]
+    pub token_version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     // Note: deleted_at is used internally for soft delete but not exposed in public API
 }
 
+/// Outcome of a login with an email address and password
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    /// The password matches
+    Succeeded(User),
+    /// No active user has the email, the user has no password, or the password
+    /// does not match
+    Failed,
+    /// Too many logins failed in a row; logins are rejected until `until`
+    Locked { until: DateTime<Utc> },
+}
+
+/// Soft-deleted user, as reviewed by administrators before purging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedUser {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub picture: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Number of users in each state, for admin dashboards
+/// The states do not overlap: a soft-deleted user is counted as deleted only
+#[derive(Debug, Clone, Serialize)]
+pub struct UserStats {
+    /// Users that are neither deactivated nor soft-deleted
+    pub active: i64,
+    /// Deactivated users that are not soft-deleted
+    pub deactivated: i64,
+    /// Soft-deleted users pending purge
+    pub deleted: i64,
+    /// Users created in the recent period that are not soft-deleted
+    pub created_recently: i64,
+}
+
+/// User as listed in the user export, active or soft-deleted
+#[derive(Debug, Clone)]
+pub struct ExportedUser {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Kind of an entry in a user's activity timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// A field of the user's profile changed
+    ProfileChanged,
+    /// The user recorded an attendance event
+    AttendanceEvent,
+}
+
+/// Entry of a user's activity timeline
+/// Profile fields are set for `ProfileChanged`, event fields for `AttendanceEvent`
+#[derive(Debug, Clone)]
+pub struct UserActivity {
+    pub kind: ActivityKind,
+    pub occurred_at: DateTime<Utc>,
+    pub field: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub event_id: Option<Uuid>,
+    pub event_type: Option<EventType>,
+    pub amends_event_id: Option<Uuid>,
+}
+
+/// Column a user list is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserSort {
+    Name,
+    Email,
+    #[default]
+    CreatedAt,
+}
+
+impl UserSort {
+    /// All sort keys
+    pub const ALL: [Self; 3] = [Self::Name, Self::Email, Self::CreatedAt];
+
+    /// The string representation used in the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Email => "email",
+            Self::CreatedAt => "created_at",
+        }
+    }
+}
+
+impl FromStr for UserSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|sort| sort.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("Sort must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Direction of a sorted list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    /// All sort orders
+    pub const ALL: [Self; 2] = [Self::Asc, Self::Desc];
+
+    /// The string representation used in the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|o| o.as_str()).collect();
+                format!("Order must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
 /// User creation request
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
 pub struct CreateUser {
     pub name: String,
     pub email: String,
     pub picture: Option<String>,
+    /// Plain-text password, hashed by the repository (`None` = password login disabled)
+    pub password: Option<String>,
+}
+
+// Written by hand so the password never ends up in logs
+impl fmt::Debug for CreateUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CreateUser")
+            .field("name", &self.name)
+            .field("email", &self.email)
+            .field("picture", &self.picture)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// Normalize an email address for storage and lookup
+///
+/// Surrounding whitespace is removed and the address is lowercased and put in
+/// Unicode NFC, so `" Foo@Bar.com"` and `"foo@bar.com"` are the same address.
+#[must_use]
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase().nfc().collect()
+}
+
+/// Deserialize an optional field of an update that can also be cleared
+///
+/// Use with `#[serde(default, deserialize_with = "nullable")]`: a missing field
+/// leaves the value unchanged (`None`), `null` clears it (`Some(None)`) and any
+/// other value sets it (`Some(Some(value))`).
+///
+/// # Errors
+/// Returns the deserializer's error if the value is neither `null` nor a `T`
+pub fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 /// User update request
+/// `None` leaves a field unchanged; `picture: Some(None)` clears the picture
 #[derive(Debug, Deserialize)]
 pub struct UpdateUser {
     pub name: Option<String>,
     pub email: Option<String>,
-    pub picture: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub picture: Option<Option<String>>,
+}
+
+/// Type of an attendance event
+/// Stored as a lowercase `snake_case` string in `attendance_events.event_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum EventType {
+    ClockIn,
+    ClockOut,
+    BreakStart,
+    BreakEnd,
+}
+
+impl EventType {
+    /// All event types, in the order they usually occur during a working day
+    pub const ALL: [Self; 4] = [
+        Self::ClockIn,
+        Self::ClockOut,
+        Self::BreakStart,
+        Self::BreakEnd,
+    ];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ClockIn => "clock_in",
+            Self::ClockOut => "clock_out",
+            Self::BreakStart => "break_start",
+            Self::BreakEnd => "break_end",
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|event_type| event_type.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|t| t.as_str()).collect();
+                format!("Event type must be one of: {}", valid.join(", "))
+            })
+    }
 }
 
 /// Attendance event entity from database
@@ -46,34 +446,560 @@ pub struct UpdateUser {
 pub struct AttendanceEvent {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub event_type: String,
+    pub event_type: EventType,
     pub event_time: DateTime<Utc>,
     pub recorded_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_id: Option<String>,
+    /// Event superseded by this one
+    pub amends_event_id: Option<Uuid>,
+}
+
+/// A user's attendance activity within a time window (one row per requested user)
+#[derive(Debug, Clone)]
+pub struct UserDayActivity {
+    pub user_id: Uuid,
+    /// Type of the latest event in the window, if any
+    pub latest_event_type: Option<EventType>,
+    /// Time of the latest event in the window, if any
+    pub latest_event_time: Option<DateTime<Utc>>,
+    /// First clock-in in the window, if any
+    pub first_clock_in: Option<DateTime<Utc>>,
 }
 
 /// Attendance event creation request
 #[derive(Debug, Deserialize)]
 pub struct CreateAttendanceEvent {
     pub user_id: Uuid,
-    pub event_type: String,
+    pub event_type: EventType,
     pub event_time: DateTime<Utc>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_id: Option<String>,
+    /// Event to supersede; it must belong to the same user
+    pub amends_event_id: Option<Uuid>,
     // Note: recorded_at and created_at are set by the server
 }
 
+/// Kind of an attendance anomaly
+/// Stored as a lowercase `snake_case` string in `attendance_anomalies.kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Clocked in without clocking out for too long
+    MissingClockOut,
+    /// Recorded shortly after another event of the same type
+    DuplicateEvent,
+    /// Event time ahead of the server time it was recorded at
+    FutureEvent,
+    /// Shift longer than the configured maximum
+    LongShift,
+}
+
+impl AnomalyKind {
+    /// All anomaly kinds
+    pub const ALL: [Self; 4] = [
+        Self::MissingClockOut,
+        Self::DuplicateEvent,
+        Self::FutureEvent,
+        Self::LongShift,
+    ];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingClockOut => "missing_clock_out",
+            Self::DuplicateEvent => "duplicate_event",
+            Self::FutureEvent => "future_event",
+            Self::LongShift => "long_shift",
+        }
+    }
+}
+
+impl fmt::Display for AnomalyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AnomalyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|k| k.as_str()).collect();
+                format!("Anomaly kind must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Attendance anomaly entity from database
+/// Matches the schema in `20251110100000_create_attendance_anomalies.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceAnomaly {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: AnomalyKind,
+    pub event_id: Uuid,
+    pub details: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Attendance anomaly creation request
+#[derive(Debug)]
+pub struct CreateAttendanceAnomaly {
+    pub user_id: Uuid,
+    pub kind: AnomalyKind,
+    pub event_id: Uuid,
+    pub details: String,
+}
+
+/// First day of a work week
+/// Stored as a lowercase day name in `work_policies.week_start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum WeekStart {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// All days of the week
+    pub const ALL: [Self; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Monday => "monday",
+            Self::Tuesday => "tuesday",
+            Self::Wednesday => "wednesday",
+            Self::Thursday => "thursday",
+            Self::Friday => "friday",
+            Self::Saturday => "saturday",
+            Self::Sunday => "sunday",
+        }
+    }
+
+    /// The corresponding `chrono` weekday
+    #[must_use]
+    pub const fn weekday(self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Tuesday => Weekday::Tue,
+            Self::Wednesday => Weekday::Wed,
+            Self::Thursday => Weekday::Thu,
+            Self::Friday => Weekday::Fri,
+            Self::Saturday => Weekday::Sat,
+            Self::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+impl fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WeekStart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|day| day.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|d| d.as_str()).collect();
+                format!("Week start must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// How daily worked time is rounded
+/// Stored as a lowercase string in `work_policies.rounding_mode`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Keep the exact number of minutes
+    #[default]
+    None,
+    /// Round down to a multiple of the rounding unit
+    Down,
+    /// Round up to a multiple of the rounding unit
+    Up,
+    /// Round to the nearest multiple of the rounding unit (halves round up)
+    Nearest,
+}
+
+impl RoundingMode {
+    /// All rounding modes
+    pub const ALL: [Self; 4] = [Self::None, Self::Down, Self::Up, Self::Nearest];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::Nearest => "nearest",
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|m| m.as_str()).collect();
+                format!("Rounding mode must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Work policy entity from database
+/// Matches the schema in `20251111100000_create_work_policies.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkPolicy {
+    pub id: Uuid,
+    pub name: String,
+    pub standard_daily_minutes: i32,
+    pub weekly_threshold_minutes: i32,
+    pub week_start: WeekStart,
+    pub rounding_mode: RoundingMode,
+    pub rounding_minutes: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Work policy creation request
+#[derive(Debug)]
+pub struct CreateWorkPolicy {
+    pub name: String,
+    pub standard_daily_minutes: i32,
+    pub weekly_threshold_minutes: i32,
+    pub week_start: WeekStart,
+    pub rounding_mode: RoundingMode,
+    pub rounding_minutes: i32,
+}
+
+/// Work policy update request
+#[derive(Debug)]
+pub struct UpdateWorkPolicy {
+    pub name: Option<String>,
+    pub standard_daily_minutes: Option<i32>,
+    pub weekly_threshold_minutes: Option<i32>,
+    pub week_start: Option<WeekStart>,
+    pub rounding_mode: Option<RoundingMode>,
+    pub rounding_minutes: Option<i32>,
+}
+
+/// Holiday entity from database
+/// Matches the schema in `20251111110000_create_holidays.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    pub id: Uuid,
+    pub date: NaiveDate,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Holiday creation request
+#[derive(Debug, Clone)]
+pub struct CreateHoliday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// Holiday update request
+#[derive(Debug)]
+pub struct UpdateHoliday {
+    pub date: Option<NaiveDate>,
+    pub name: Option<String>,
+}
+
+/// Status of an attendance correction request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum CorrectionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl CorrectionStatus {
+    /// All correction statuses
+    pub const ALL: [Self; 3] = [Self::Pending, Self::Approved, Self::Rejected];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl fmt::Display for CorrectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CorrectionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("Status must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Attendance correction request entity from database
+/// Matches the schema in `20251107100000_create_attendance_corrections.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceCorrection {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub proposed_event_type: EventType,
+    pub proposed_event_time: DateTime<Utc>,
+    pub reason: String,
+    pub status: CorrectionStatus,
+    pub created_at: DateTime<Utc>,
+    pub approver_id: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decision_comment: Option<String>,
+    pub applied_event_id: Option<Uuid>,
+}
+
+/// Attendance correction creation request
+#[derive(Debug, Deserialize)]
+pub struct CreateAttendanceCorrection {
+    pub event_id: Uuid,
+    pub proposed_event_type: EventType,
+    pub proposed_event_time: DateTime<Utc>,
+    pub reason: String,
+    // Note: user_id is taken from the referenced event
+}
+
+/// Approval or rejection decision on a correction request
+#[derive(Debug, Deserialize)]
+pub struct CorrectionDecision {
+    pub approver_id: Uuid,
+    pub comment: Option<String>,
+}
+
+/// Webhook subscriber entity from database
+/// Matches the schema in `20251112100000_create_webhooks.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    /// Shared secret used to sign payloads; never returned after creation
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Webhook creation request
+#[derive(Debug)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub secret: String,
+    pub description: Option<String>,
+}
+
+/// Webhook update request
+#[derive(Debug)]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// State of a webhook delivery
+/// Stored as a lowercase string in `webhook_deliveries.status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Not delivered yet; retried when due
+    Pending,
+    /// The subscriber answered with a 2xx status
+    Succeeded,
+    /// All attempts failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// All delivery statuses
+    pub const ALL: [Self; 3] = [Self::Pending, Self::Succeeded, Self::Failed];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("Status must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Webhook delivery entity from database
+/// Matches the schema in `20251112100000_create_webhooks.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a single delivery attempt
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    /// HTTP status of the response, if one was received
+    pub status_code: Option<i32>,
+    /// Why the attempt failed (`None` on success)
+    pub error: Option<String>,
+}
+
 /// Todo作成時のリクエストボディ
 #[derive(Debug, Deserialize)]
 pub struct CreateTodoRequest {
     pub title: String,
     pub description: Option<String>,
+    /// 省略時は `medium`
+    #[serde(default)]
+    pub priority: TodoPriority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub due_date: Option<NaiveDate>,
 }
 
 /// Todo更新時のリクエストボディ
+/// `description` に `null` を指定すると説明を削除する
 #[derive(Debug, Deserialize)]
 pub struct UpdateTodoRequest {
     pub title: Option<String>,
-    pub description: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: Option<Option<String>>,
     pub completed: Option<bool>,
+    pub priority: Option<TodoPriority>,
+    /// 指定するとタグをすべて置き換える
+    pub tags: Option<Vec<String>>,
+    /// `null` を指定すると期日を削除する
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_date: Option<Option<NaiveDate>>,
+}
+
+/// Maximum number of tags on a todo
+const MAX_TODO_TAGS: usize = 20;
+
+/// Maximum length of a todo tag
+const MAX_TODO_TAG_LENGTH: usize = 50;
+
+/// Validate the tags of a todo
+///
+/// # Errors
+/// Returns an error string if validation fails:
+/// - There are more than 20 tags
+/// - A tag is empty or only whitespace
+/// - A tag exceeds 50 characters
+fn validate_tags(tags: &[String]) -> Result<(), String> {
+    if tags.len() > MAX_TODO_TAGS {
+        return Err(format!("A todo can have at most {MAX_TODO_TAGS} tags"));
+    }
+    for tag in tags {
+        if tag.trim().is_empty() {
+            return Err("Tags cannot be empty".to_string());
+        }
+        if tag.chars().count() > MAX_TODO_TAG_LENGTH {
+            return Err(format!(
+                "Tags must be {MAX_TODO_TAG_LENGTH} characters or less"
+            ));
+        }
+    }
+    Ok(())
 }
 
 impl CreateTodoRequest {
@@ -84,20 +1010,33 @@ impl CreateTodoRequest {
     /// - Title is empty or only whitespace
     /// - Title exceeds 200 characters
     /// - Description exceeds 1000 characters
+    /// - Tags are invalid (more than 20, or one is empty or exceeds 50 characters)
     pub fn validate(&self) -> Result<(), String> {
-        if self.title.trim().is_empty() {
-            return Err("Title cannot be empty".to_string());
-        }
-        if self.title.len() > 200 {
-            return Err("Title must be 200 characters or less".to_string());
-        }
-        if let Some(desc) = &self.description
-            && desc.len() > 1000
-        {
-            return Err("Description must be 1000 characters or less".to_string());
-        }
-        Ok(())
+        validate_new_todo(&self.title, self.description.as_deref(), &self.tags)
+    }
+}
+
+/// Validate the fields of a new todo
+///
+/// # Errors
+/// Returns an error string if validation fails (see [`CreateTodoRequest::validate`])
+fn validate_new_todo(
+    title: &str,
+    description: Option<&str>,
+    tags: &[String],
+) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    if title.len() > 200 {
+        return Err("Title must be 200 characters or less".to_string());
     }
+    if let Some(desc) = description
+        && desc.len() > 1000
+    {
+        return Err("Description must be 1000 characters or less".to_string());
+    }
+    validate_tags(tags)
 }
 
 impl UpdateTodoRequest {
@@ -108,6 +1047,7 @@ impl UpdateTodoRequest {
     /// - Title is empty or only whitespace
     /// - Title exceeds 200 characters
     /// - Description exceeds 1000 characters
+    /// - Tags are invalid (more than 20, or one is empty or exceeds 50 characters)
     pub fn validate(&self) -> Result<(), String> {
         if let Some(title) = &self.title {
             if title.trim().is_empty() {
@@ -117,11 +1057,677 @@ impl UpdateTodoRequest {
                 return Err("Title must be 200 characters or less".to_string());
             }
         }
-        if let Some(desc) = &self.description
+        if let Some(Some(desc)) = &self.description
             && desc.len() > 1000
         {
             return Err("Description must be 1000 characters or less".to_string());
         }
+        if let Some(tags) = &self.tags {
+            validate_tags(tags)?;
+        }
+        Ok(())
+    }
+
+    /// Read a JSON Merge Patch (RFC 7386) of a todo
+    ///
+    /// Members left out stay unchanged, `null` removes `description` or
+    /// `due_date`, and any other value replaces the member, `tags` as a whole.
+    ///
+    /// # Errors
+    /// Returns an error string if the patch is not an object, removes a member
+    /// every todo has, or has a value of the wrong type
+    pub fn from_merge_patch(patch: serde_json::Value) -> Result<Self, String> {
+        let serde_json::Value::Object(members) = &patch else {
+            return Err("Merge patch must be a JSON object".to_string());
+        };
+        for field in ["title", "completed", "priority", "tags"] {
+            if members.get(field).is_some_and(serde_json::Value::is_null) {
+                return Err(format!("{field} cannot be removed"));
+            }
+        }
+        serde_json::from_value(patch).map_err(|e| format!("Invalid merge patch: {e}"))
+    }
+}
+
+/// Maximum number of checklist items on a todo
+pub const MAX_TODO_ITEMS: usize = 100;
+
+/// チェックリスト項目作成時のリクエストボディ
+#[derive(Debug, Deserialize)]
+pub struct CreateTodoItemRequest {
+    pub text: String,
+}
+
+/// チェックリスト項目更新時のリクエストボディ
+#[derive(Debug, Deserialize)]
+pub struct UpdateTodoItemRequest {
+    pub text: Option<String>,
+    pub done: Option<bool>,
+}
+
+/// Validate the text of a checklist item
+///
+/// # Errors
+/// Returns an error string if the text is empty or only whitespace, or exceeds
+/// 200 characters
+fn validate_item_text(text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Item text cannot be empty".to_string());
+    }
+    if text.len() > 200 {
+        return Err("Item text must be 200 characters or less".to_string());
+    }
+    Ok(())
+}
+
+impl CreateTodoItemRequest {
+    /// Validate the create checklist item request
+    ///
+    /// # Errors
+    /// Returns an error string if the text is empty or exceeds 200 characters
+    pub fn validate(&self) -> Result<(), String> {
+        validate_item_text(&self.text)
+    }
+}
+
+impl UpdateTodoItemRequest {
+    /// Validate the update checklist item request
+    ///
+    /// # Errors
+    /// Returns an error string if the text is empty or exceeds 200 characters
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(text) = &self.text {
+            validate_item_text(text)?;
+        }
         Ok(())
     }
 }
+
+/// Version of the todo export format written by this API
+pub const TODO_EXPORT_VERSION: u32 = 1;
+
+/// Todo のエクスポート文書
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodoExport {
+    /// Format version; only [`TODO_EXPORT_VERSION`] can be imported
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    pub todos: Vec<ExportedTodo>,
+}
+
+/// エクスポートされた Todo
+/// 所有者は含まず、インポート時に新しい ID が振られる
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTodo {
+    /// ID when exported
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub priority: TodoPriority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub items: Vec<ExportedTodoItem>,
+    /// Kept on import (default: the time of the import)
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Kept on import for completed todos (default: the time of the import)
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// エクスポートされたチェックリスト項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTodoItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl From<Todo> for ExportedTodo {
+    fn from(todo: Todo) -> Self {
+        Self {
+            id: todo.id,
+            title: todo.title,
+            description: todo.description,
+            completed: todo.completed,
+            archived: todo.archived,
+            priority: todo.priority,
+            tags: todo.tags,
+            due_date: todo.due_date,
+            items: todo
+                .items
+                .into_iter()
+                .map(|item| ExportedTodoItem {
+                    text: item.text,
+                    done: item.done,
+                })
+                .collect(),
+            created_at: Some(todo.created_at),
+            completed_at: todo.completed_at,
+        }
+    }
+}
+
+impl ExportedTodo {
+    /// Validate an exported todo like a new todo and its checklist items
+    ///
+    /// # Errors
+    /// Returns an error string if the title, description or tags are invalid
+    /// (see [`CreateTodoRequest::validate`]), there are more than 100 items or
+    /// an item text is empty or exceeds 200 characters
+    pub fn validate(&self) -> Result<(), String> {
+        validate_new_todo(&self.title, self.description.as_deref(), &self.tags)?;
+        if self.items.len() > MAX_TODO_ITEMS {
+            return Err(format!("A todo can have at most {MAX_TODO_ITEMS} items"));
+        }
+        for item in &self.items {
+            validate_item_text(&item.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// How to import a todo with the same title as an existing todo of the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportConflict {
+    /// Keep the existing todo and leave out the imported one
+    #[default]
+    Skip,
+    /// Overwrite the existing todo, keeping its ID
+    Replace,
+    /// Import the todo next to the existing one
+    KeepBoth,
+}
+
+impl ImportConflict {
+    /// All conflict strategies
+    pub const ALL: [Self; 3] = [Self::Skip, Self::Replace, Self::KeepBoth];
+
+    /// The string representation used in the API
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Replace => "replace",
+            Self::KeepBoth => "keep_both",
+        }
+    }
+}
+
+impl FromStr for ImportConflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|conflict| conflict.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|c| c.as_str()).collect();
+                format!("on_conflict must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// What became of an imported todo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Created,
+    Replaced,
+    Skipped,
+}
+
+/// Result of importing one todo
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedTodo {
+    /// ID in the export
+    pub old_id: u64,
+    /// ID of the created or replaced todo, or of the existing todo it was
+    /// skipped for
+    pub new_id: u64,
+    pub outcome: ImportOutcome,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_round_trip() {
+        for event_type in EventType::ALL {
+            assert_eq!(event_type.as_str().parse::<EventType>(), Ok(event_type));
+            assert_eq!(
+                serde_json::to_value(event_type).unwrap(),
+                serde_json::Value::String(event_type.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_nullable_distinguishes_missing_and_null() {
+        let parse = |json: &str| serde_json::from_str::<UpdateTodoRequest>(json).unwrap();
+
+        assert_eq!(parse("{}").description, None);
+        assert_eq!(parse(r#"{"description": null}"#).description, Some(None));
+        assert_eq!(
+            parse(r#"{"description": "text"}"#).description,
+            Some(Some("text".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email(" Foo@Bar.COM\n"), "foo@bar.com");
+        // "e" followed by a combining acute accent composes to "é"
+        assert_eq!(
+            normalize_email("Re\u{301}my@example.com"),
+            "r\u{e9}my@example.com"
+        );
+        assert_eq!(normalize_email("foo@bar.com"), "foo@bar.com");
+    }
+
+    #[test]
+    fn test_event_type_rejects_unknown_values() {
+        assert!("lunch".parse::<EventType>().is_err());
+        assert!("Clock_In".parse::<EventType>().is_err());
+        assert!("".parse::<EventType>().is_err());
+    }
+
+    #[test]
+    fn test_set_completed_tracks_completed_at() {
+        let created = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut todo = Todo {
+            id: 1,
+            owner_id: Uuid::new_v4(),
+            title: "Track me".to_string(),
+            description: None,
+            completed: false,
+            archived: false,
+            priority: TodoPriority::default(),
+            tags: Vec::new(),
+            due_date: None,
+            items: Vec::new(),
+            created_at: created,
+            updated_at: created,
+            completed_at: None,
+        };
+        let first = created + chrono::Duration::hours(1);
+        let second = first + chrono::Duration::hours(1);
+
+        todo.set_completed(true, first);
+        assert_eq!(todo.completed_at, Some(first));
+        todo.set_completed(true, second);
+        assert_eq!(todo.completed_at, Some(first));
+        todo.set_completed(false, second);
+        assert!(!todo.completed);
+        assert_eq!(todo.completed_at, None);
+    }
+
+    #[test]
+    fn test_todo_priority_round_trip_and_order() {
+        for priority in TodoPriority::ALL {
+            assert_eq!(priority.as_str().parse::<TodoPriority>(), Ok(priority));
+        }
+        assert!(TodoPriority::ALL.is_sorted());
+        assert!("critical".parse::<TodoPriority>().is_err());
+    }
+
+    #[test]
+    fn test_api_key_scopes_and_activity() {
+        let now = Utc::now();
+        for scope in ApiKeyScope::ALL {
+            assert_eq!(scope.as_str().parse::<ApiKeyScope>(), Ok(scope));
+            assert_eq!(
+                serde_json::to_value(scope).unwrap(),
+                serde_json::json!(scope.as_str())
+            );
+        }
+        let mut key = ApiKey {
+            id: Uuid::new_v4(),
+            name: "Payroll".to_string(),
+            key_prefix: "ak_12345678".to_string(),
+            scopes: vec![ApiKeyScope::PayrollRead],
+            expires_at: Some(now + chrono::Duration::days(1)),
+            revoked_at: None,
+            last_used_at: None,
+            created_at: now,
+        };
+
+        assert!(key.has_scope(ApiKeyScope::PayrollRead));
+        assert!(!key.has_scope(ApiKeyScope::KioskClock));
+        assert!(key.is_active(now));
+        assert!(!key.is_active(now + chrono::Duration::days(2)));
+        key.revoked_at = Some(now);
+        assert!(!key.is_active(now));
+    }
+
+    #[test]
+    fn test_permission_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(permission.as_str().parse::<Permission>(), Ok(permission));
+            assert_eq!(
+                serde_json::to_value(permission).unwrap(),
+                serde_json::json!(permission.as_str())
+            );
+        }
+        assert!("attendance:fly".parse::<Permission>().is_err());
+    }
+}
+
+/// Role of a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum UserRole {
+    /// Manages users and all attendance records
+    Admin,
+    /// Reviews the attendance of their team
+    Manager,
+    /// Records and reads their own attendance
+    Member,
+}
+
+impl UserRole {
+    /// All roles
+    pub const ALL: [Self; 3] = [Self::Admin, Self::Manager, Self::Member];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Manager => "manager",
+            Self::Member => "member",
+        }
+    }
+
+    /// Whether the role has at least the permissions of `required`
+    /// Admins include managers, and managers include members.
+    #[must_use]
+    pub const fn includes(self, required: Self) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    const fn rank(self) -> u8 {
+        match self {
+            Self::Admin => 2,
+            Self::Manager => 1,
+            Self::Member => 0,
+        }
+    }
+}
+
+impl fmt::Display for UserRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for UserRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|r| r.as_str()).collect();
+                format!("Role must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Permission required by a route, granted to roles
+/// Stored as a string in `role_permissions.permission`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    /// Read todos
+    #[serde(rename = "todos:read")]
+    TodosRead,
+    /// Create, change and delete todos
+    #[serde(rename = "todos:write")]
+    TodosWrite,
+    /// Read user profiles and activity
+    #[serde(rename = "users:read")]
+    UsersRead,
+    /// Delete, deactivate and activate users
+    #[serde(rename = "users:write")]
+    UsersWrite,
+    /// Read attendance events, reports, corrections, holidays and work policies
+    #[serde(rename = "attendance:read")]
+    AttendanceRead,
+    /// Record attendance events and submit corrections
+    #[serde(rename = "attendance:write")]
+    AttendanceWrite,
+    /// Approve and reject attendance corrections
+    #[serde(rename = "corrections:approve")]
+    CorrectionsApprove,
+    /// Create, change and delete holidays and work policies
+    #[serde(rename = "policies:write")]
+    PoliciesWrite,
+}
+
+impl Permission {
+    /// All permissions
+    pub const ALL: [Self; 8] = [
+        Self::TodosRead,
+        Self::TodosWrite,
+        Self::UsersRead,
+        Self::UsersWrite,
+        Self::AttendanceRead,
+        Self::AttendanceWrite,
+        Self::CorrectionsApprove,
+        Self::PoliciesWrite,
+    ];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::TodosRead => "todos:read",
+            Self::TodosWrite => "todos:write",
+            Self::UsersRead => "users:read",
+            Self::UsersWrite => "users:write",
+            Self::AttendanceRead => "attendance:read",
+            Self::AttendanceWrite => "attendance:write",
+            Self::CorrectionsApprove => "corrections:approve",
+            Self::PoliciesWrite => "policies:write",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|p| p.as_str()).collect();
+                format!("Permission must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// Invitation entity from database
+/// Matches the schema in `20251113100000_create_invitations.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub email: String,
+    pub role: UserRole,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Invitation creation request
+#[derive(Debug, Clone)]
+pub struct CreateInvitation {
+    pub email: String,
+    pub role: UserRole,
+    /// Hash of the token sent to the invited person (see `token::hash_opaque`)
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Profile completed by the invited person when accepting an invitation
+#[derive(Debug, Clone)]
+pub struct AcceptInvitation {
+    pub token_hash: String,
+    pub name: String,
+    pub picture: Option<String>,
+}
+
+/// Session entity from database
+/// Matches the schema in `20251120100000_create_sessions.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Scope granted to an API key
+/// Stored as a string in `api_keys.scopes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiKeyScope {
+    /// Record attendance events from scanned kiosk tokens
+    #[serde(rename = "kiosk:clock")]
+    KioskClock,
+    /// Export the monthly payroll
+    #[serde(rename = "payroll:read")]
+    PayrollRead,
+    /// Read users over gRPC
+    #[serde(rename = "users:read")]
+    UsersRead,
+    /// Read attendance events over gRPC
+    #[serde(rename = "attendance:read")]
+    AttendanceRead,
+    /// Record attendance events over gRPC
+    #[serde(rename = "attendance:write")]
+    AttendanceWrite,
+}
+
+impl ApiKeyScope {
+    /// All scopes
+    pub const ALL: [Self; 5] = [
+        Self::KioskClock,
+        Self::PayrollRead,
+        Self::UsersRead,
+        Self::AttendanceRead,
+        Self::AttendanceWrite,
+    ];
+
+    /// The string representation used in the API and the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::KioskClock => "kiosk:clock",
+            Self::PayrollRead => "payroll:read",
+            Self::UsersRead => "users:read",
+            Self::AttendanceRead => "attendance:read",
+            Self::AttendanceWrite => "attendance:write",
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("Scope must be one of: {}", valid.join(", "))
+            })
+    }
+}
+
+/// API key entity from database (without the key hash)
+/// Matches the schema in `20251121100000_create_api_keys.sql`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    /// Whether the key grants a scope
+    #[must_use]
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Whether the key can still be used at `now`
+    #[must_use]
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// API key creation request
+#[derive(Debug, Clone)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub key_prefix: String,
+    /// Hash of the key handed to the client (see `token::hash_opaque`)
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Response stored for an idempotency key
+/// Matches the schema in `20251125100000_create_idempotency_keys.sql`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status_code: i16,
+    pub header_names: Vec<String>,
+    pub header_values: Vec<String>,
+    pub body: Vec<u8>,
+}
+
+/// Idempotency key already used by an earlier request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    /// Hash of the earlier request (see `idempotency`)
+    pub request_hash: String,
+    /// `None` while the earlier request is being processed
+    pub response: Option<StoredResponse>,
+}
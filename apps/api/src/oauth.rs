@@ -0,0 +1,270 @@
+//! Google sign-in (OAuth 2.0 authorization code flow)
+//!
+//! `GET /api/auth/google` redirects the browser to Google with a random `state`
+//! that is also kept in a short-lived cookie. Google redirects back to
+//! `GET /api/auth/google/callback` with a code, which [`GoogleOAuth`] exchanges
+//! for the profile of the Google account. The callback is only accepted if its
+//! `state` matches the cookie, so another site cannot sign a browser in to an
+//! account of its choosing.
+
+use crate::error::{AppError, Result};
+use crate::extract;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Cookie holding the `state` of a pending sign-in
+pub const STATE_COOKIE: &str = "google_oauth_state";
+
+/// How long a sign-in may take before its state cookie expires (10 minutes)
+const STATE_MAX_AGE_SECONDS: u32 = 10 * 60;
+
+/// Path the state cookie is sent to
+const STATE_COOKIE_PATH: &str = "/api/auth/google";
+
+const DEFAULT_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const DEFAULT_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const DEFAULT_USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+
+/// Timeout of each request to Google
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings of the Google OAuth client
+#[derive(Clone, PartialEq, Eq)]
+pub struct GoogleOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// URL of `GET /api/auth/google/callback` as registered with Google
+    pub redirect_uri: String,
+    pub auth_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+}
+
+impl fmt::Debug for GoogleOAuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GoogleOAuthConfig")
+            .field("client_id", &self.client_id)
+            .field("redirect_uri", &self.redirect_uri)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GoogleOAuthConfig {
+    /// Settings for a client registered with Google, using Google's endpoints
+    #[must_use]
+    pub fn new(client_id: &str, client_secret: &str, redirect_uri: &str) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            auth_url: DEFAULT_AUTH_URL.to_string(),
+            token_url: DEFAULT_TOKEN_URL.to_string(),
+            userinfo_url: DEFAULT_USERINFO_URL.to_string(),
+        }
+    }
+}
+
+/// Profile of a Google account, as returned by the userinfo endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleProfile {
+    /// Stable identifier of the account
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+}
+
+impl GoogleProfile {
+    /// The email address, if Google has verified it
+    #[must_use]
+    pub fn verified_email(&self) -> Option<&str> {
+        self.email.as_deref().filter(|_| self.email_verified)
+    }
+}
+
+/// Response of the token endpoint (other fields are ignored)
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// Client for signing in with Google
+#[derive(Debug, Clone)]
+pub struct GoogleOAuth {
+    config: Arc<GoogleOAuthConfig>,
+    client: reqwest::Client,
+}
+
+impl GoogleOAuth {
+    /// Create a client with the given settings
+    ///
+    /// # Panics
+    /// Panics if the HTTP client cannot be initialized (no TLS backend available)
+    #[must_use]
+    pub fn new(config: GoogleOAuthConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("HTTP client must initialize");
+
+        Self {
+            config: Arc::new(config),
+            client,
+        }
+    }
+
+    /// URL of Google's consent page for a sign-in with `state`
+    #[must_use]
+    pub fn authorization_url(&self, state: &str) -> String {
+        let params = [
+            ("client_id", self.config.client_id.as_str()),
+            ("redirect_uri", self.config.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", "openid email profile"),
+            ("state", state),
+            ("prompt", "select_account"),
+        ];
+        reqwest::Url::parse_with_params(&self.config.auth_url, params)
+            .map_or_else(|_| self.config.auth_url.clone(), |url| url.to_string())
+    }
+
+    /// `Set-Cookie` value keeping the state of a new sign-in
+    #[must_use]
+    pub fn state_cookie(&self, state: &str) -> String {
+        format!(
+            "{STATE_COOKIE}={state}; Path={STATE_COOKIE_PATH}; Max-Age={STATE_MAX_AGE_SECONDS}; HttpOnly; SameSite=Lax{}",
+            self.secure_attribute()
+        )
+    }
+
+    /// `Set-Cookie` value removing the state cookie once the sign-in is over
+    #[must_use]
+    pub fn clear_state_cookie(&self) -> String {
+        format!(
+            "{STATE_COOKIE}=; Path={STATE_COOKIE_PATH}; Max-Age=0; HttpOnly; SameSite=Lax{}",
+            self.secure_attribute()
+        )
+    }
+
+    /// Cookies are only sent over HTTPS when the callback is served over HTTPS
+    fn secure_attribute(&self) -> &'static str {
+        if self.config.redirect_uri.starts_with("https://") {
+            "; Secure"
+        } else {
+            ""
+        }
+    }
+
+    /// Exchange the code of a callback for the profile of the Google account
+    ///
+    /// # Errors
+    /// Returns `Unauthorized` if Google rejects the code (e.g. it is expired or
+    /// was already used)
+    /// Returns `InternalServerError` if Google cannot be reached or answers
+    /// unexpectedly
+    pub async fn fetch_profile(&self, code: &str) -> Result<GoogleProfile> {
+        let response = self
+            .client
+            .post(&self.config.token_url)
+            .form(&[
+                ("code", code),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("redirect_uri", &self.config.redirect_uri),
+                ("grant_type", "authorization_code"),
+            ])
+            .send()
+            .await
+            .map_err(request_failed)?;
+        if response.status().is_client_error() {
+            tracing::warn!(status = %response.status(), "Google rejected the authorization code");
+            return Err(AppError::Unauthorized(
+                "Google sign-in failed: the code is invalid or expired".to_string(),
+            ));
+        }
+        let token: TokenResponse = read_json(response).await?;
+
+        let response = self
+            .client
+            .get(&self.config.userinfo_url)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await
+            .map_err(request_failed)?;
+        read_json(response).await
+    }
+}
+
+fn request_failed(err: reqwest::Error) -> AppError {
+    AppError::internal("Google request failed", err)
+}
+
+/// Read the JSON body of a successful response from Google
+async fn read_json<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::InternalServerError(format!(
+            "Google responded with {status}"
+        )));
+    }
+    let body = response.bytes().await.map_err(request_failed)?;
+    serde_json::from_slice(&body)
+        .map_err(|e| AppError::internal("Unexpected response from Google", e))
+}
+
+/// The state kept in the request's cookie, if any
+#[must_use]
+pub fn state_from_cookies(headers: &HeaderMap) -> Option<&str> {
+    extract::cookie(headers, STATE_COOKIE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header;
+
+    fn oauth(redirect_uri: &str) -> GoogleOAuth {
+        GoogleOAuth::new(GoogleOAuthConfig::new("client", "secret", redirect_uri))
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let url = oauth("https://app.example.com/api/auth/google/callback").authorization_url("s1");
+
+        assert!(url.starts_with(DEFAULT_AUTH_URL));
+        assert!(url.contains("client_id=client"));
+        assert!(url.contains(
+            "redirect_uri=https%3A%2F%2Fapp.example.com%2Fapi%2Fauth%2Fgoogle%2Fcallback"
+        ));
+        assert!(url.contains("response_type=code"));
+        assert!(url.contains("state=s1"));
+    }
+
+    #[test]
+    fn test_state_cookie() {
+        let https = oauth("https://app.example.com/callback");
+        let http = oauth("http://localhost:3000/callback");
+
+        assert!(
+            https
+                .state_cookie("s1")
+                .starts_with("google_oauth_state=s1;")
+        );
+        assert!(https.state_cookie("s1").ends_with("; Secure"));
+        assert!(!http.state_cookie("s1").contains("Secure"));
+        assert!(http.clear_state_cookie().contains("Max-Age=0"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            "theme=dark; google_oauth_state=s1".parse().unwrap(),
+        );
+        assert_eq!(state_from_cookies(&headers), Some("s1"));
+        assert_eq!(state_from_cookies(&HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,347 @@
+//! Pagination for list endpoints
+//!
+//! Most list endpoints take `page` (1-based) and `per_page` query parameters.
+//! Endpoints whose clients walk through the list take `limit` and `offset`
+//! instead, and also tell where the next page starts. Both respond with a
+//! [`Paginated`] page holding the items and the total number of matches, and
+//! with an RFC 8288 `Link` header to the previous and next pages:
+//!
+//! ```text
+//! Link: </api/users?q=ann&page=1>; rel="prev", </api/users?q=ann&page=3>; rel="next"
+//! ```
+
+use crate::error::{AppError, Result};
+use axum::{
+    Json,
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Page size used when `per_page` is not given
+pub const DEFAULT_PER_PAGE: u32 = 20;
+
+/// Largest accepted page size
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// A validated page request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// 1-based page number
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl Pagination {
+    /// Validate the `page` and `per_page` query parameters, applying defaults
+    ///
+    /// # Errors
+    /// Returns validation error if `page` is 0 or `per_page` is outside 1-100
+    pub fn from_query(page: Option<u32>, per_page: Option<u32>) -> Result<Self> {
+        let page = page.unwrap_or(1);
+        if page == 0 {
+            return Err(AppError::ValidationError(
+                "Page must be 1 or greater".to_string(),
+            ));
+        }
+
+        let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(AppError::ValidationError(format!(
+                "per_page must be between 1 and {MAX_PER_PAGE}"
+            )));
+        }
+
+        Ok(Self { page, per_page })
+    }
+
+    /// Maximum number of rows to fetch (SQL `LIMIT`)
+    #[must_use]
+    pub fn limit(self) -> i64 {
+        i64::from(self.per_page)
+    }
+
+    /// Number of rows to skip (SQL `OFFSET`)
+    #[must_use]
+    pub fn offset(self) -> i64 {
+        i64::from(self.page - 1) * i64::from(self.per_page)
+    }
+}
+
+/// A validated `limit`/`offset` page request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetPagination {
+    /// Maximum number of items in the page
+    pub limit: u32,
+    /// Number of items skipped before the page
+    pub offset: u64,
+}
+
+impl OffsetPagination {
+    /// Validate the `limit` and `offset` query parameters, applying defaults
+    /// (`limit` as `per_page`, `offset` 0)
+    ///
+    /// # Errors
+    /// Returns validation error if `limit` is outside 1-100
+    pub fn from_query(limit: Option<u32>, offset: Option<u64>) -> Result<Self> {
+        let limit = limit.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&limit) {
+            return Err(AppError::ValidationError(format!(
+                "limit must be between 1 and {MAX_PER_PAGE}"
+            )));
+        }
+
+        Ok(Self {
+            limit,
+            offset: offset.unwrap_or(0),
+        })
+    }
+}
+
+/// Position of a page in the list, serialized next to its items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum PageInfo {
+    /// A `page`/`per_page` page
+    Numbered { page: u32, per_page: u32 },
+    /// A `limit`/`offset` page
+    Offset {
+        limit: u32,
+        offset: u64,
+        /// Offset of the next page (`None` on the last page)
+        next_offset: Option<u64>,
+    },
+}
+
+/// One page of a list response
+///
+/// Responds with the items, the total number of matches and the
+/// [`PageInfo`] fields, and with `Link` headers to the neighbouring pages
+/// (added by [`link_headers`]).
+#[derive(Debug, Serialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Number of matching items across all pages
+    pub total: u64,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
+}
+
+impl<T> Paginated<T> {
+    /// Wrap the items of a `page`/`per_page` page, converting each into its
+    /// response type
+    pub fn page<U: Into<T>>(items: Vec<U>, pagination: Pagination, total: i64) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            total: u64::try_from(total).unwrap_or(0),
+            page_info: PageInfo::Numbered {
+                page: pagination.page,
+                per_page: pagination.per_page,
+            },
+        }
+    }
+
+    /// Wrap the items of a `limit`/`offset` page, converting each into its
+    /// response type
+    pub fn offset<U: Into<T>>(items: Vec<U>, pagination: OffsetPagination, total: u64) -> Self {
+        let end = pagination.offset.saturating_add(items.len() as u64);
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            total,
+            page_info: PageInfo::Offset {
+                limit: pagination.limit,
+                offset: pagination.offset,
+                next_offset: (end < total).then_some(end),
+            },
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Paginated<T> {
+    fn into_response(self) -> Response {
+        let links = PageLinks {
+            page_info: self.page_info,
+            total: self.total,
+        };
+        let mut response = Json(self).into_response();
+        response.extensions_mut().insert(links);
+        response
+    }
+}
+
+/// Page of a list response, for [`link_headers`]
+#[derive(Debug, Clone, Copy)]
+struct PageLinks {
+    page_info: PageInfo,
+    total: u64,
+}
+
+impl PageLinks {
+    /// The query parameter selecting a page and its value for the previous
+    /// and next pages
+    fn targets(self) -> (&'static str, Option<u64>, Option<u64>) {
+        match self.page_info {
+            PageInfo::Numbered { page, per_page } => {
+                let last = self.total.div_ceil(u64::from(per_page)).max(1);
+                let page = u64::from(page);
+                let prev = (page > 1).then(|| (page - 1).min(last));
+                let next = (page < last).then_some(page + 1);
+                ("page", prev, next)
+            }
+            PageInfo::Offset {
+                limit,
+                offset,
+                next_offset,
+            } => {
+                let prev = (offset > 0).then(|| offset.saturating_sub(u64::from(limit)));
+                ("offset", prev, next_offset)
+            }
+        }
+    }
+
+    /// RFC 8288 `Link` header value pointing to the previous and next pages,
+    /// or `None` for a single page
+    fn header(self, path: &str, query: Option<&str>) -> Option<String> {
+        let (param, prev, next) = self.targets();
+        let links: Vec<String> = [("prev", prev), ("next", next)]
+            .into_iter()
+            .filter_map(|(rel, value)| {
+                let value = value?;
+                Some(format!(
+                    "<{path}?{}>; rel=\"{rel}\"",
+                    with_param(query, param, value)
+                ))
+            })
+            .collect();
+        (!links.is_empty()).then(|| links.join(", "))
+    }
+}
+
+/// `query` with `param` set to `value`, keeping the other parameters as sent
+fn with_param(query: Option<&str>, param: &str, value: u64) -> String {
+    query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(param))
+        .map(str::to_string)
+        .chain(std::iter::once(format!("{param}={value}")))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Middleware adding `Link` headers to the pages of list responses
+pub async fn link_headers(request: Request, next: Next) -> Response {
+    let uri = request.uri().clone();
+    let mut response = next.run(request).await;
+
+    if let Some(links) = response.extensions().get::<PageLinks>().copied()
+        && let Some(link) = links.header(uri.path(), uri.query())
+        && let Ok(value) = HeaderValue::from_str(&link)
+    {
+        response.headers_mut().insert(header::LINK, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let pagination = Pagination::from_query(None, None).unwrap();
+        assert_eq!(
+            pagination,
+            Pagination {
+                page: 1,
+                per_page: 20
+            }
+        );
+        assert_eq!(pagination.limit(), 20);
+        assert_eq!(pagination.offset(), 0);
+    }
+
+    #[test]
+    fn test_offset() {
+        let pagination = Pagination::from_query(Some(3), Some(50)).unwrap();
+        assert_eq!(pagination.limit(), 50);
+        assert_eq!(pagination.offset(), 100);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(Pagination::from_query(Some(0), None).is_err());
+        assert!(Pagination::from_query(None, Some(0)).is_err());
+        assert!(Pagination::from_query(None, Some(101)).is_err());
+        assert!(Pagination::from_query(Some(u32::MAX), Some(100)).is_ok());
+    }
+
+    #[test]
+    fn test_offset_pagination() {
+        assert_eq!(
+            OffsetPagination::from_query(None, None).unwrap(),
+            OffsetPagination {
+                limit: 20,
+                offset: 0
+            }
+        );
+        assert!(OffsetPagination::from_query(Some(0), None).is_err());
+        assert!(OffsetPagination::from_query(Some(101), None).is_err());
+        assert!(OffsetPagination::from_query(Some(100), Some(u64::MAX)).is_ok());
+    }
+
+    fn next_offset<T>(page: &Paginated<T>) -> Option<u64> {
+        match page.page_info {
+            PageInfo::Offset { next_offset, .. } => next_offset,
+            PageInfo::Numbered { .. } => panic!("not an offset page"),
+        }
+    }
+
+    #[test]
+    fn test_next_offset() {
+        let pagination = OffsetPagination::from_query(Some(2), Some(4)).unwrap();
+
+        let middle: Paginated<u8> = Paginated::offset(vec![1, 2], pagination, 7);
+        let last: Paginated<u8> = Paginated::offset(vec![1, 2], pagination, 6);
+        let beyond: Paginated<u8> = Paginated::offset(Vec::<u8>::new(), pagination, 3);
+
+        assert_eq!(next_offset(&middle), Some(6));
+        assert_eq!(next_offset(&last), None);
+        assert_eq!(next_offset(&beyond), None);
+    }
+
+    #[test]
+    fn test_link_header() {
+        let pagination = Pagination::from_query(Some(2), Some(10)).unwrap();
+        let middle: Paginated<u8> = Paginated::page(vec![1], pagination, 25);
+        let only: Paginated<u8> =
+            Paginated::page(vec![1], Pagination::from_query(None, None).unwrap(), 1);
+        let offset: Paginated<u8> = Paginated::offset(
+            vec![1, 2],
+            OffsetPagination::from_query(Some(2), Some(1)).unwrap(),
+            3,
+        );
+        let links = |page: &Paginated<u8>, query| {
+            PageLinks {
+                page_info: page.page_info,
+                total: page.total,
+            }
+            .header("/api/users", query)
+        };
+
+        assert_eq!(
+            links(&middle, Some("q=ann&page=2&per_page=10")).as_deref(),
+            Some(
+                "</api/users?q=ann&per_page=10&page=1>; rel=\"prev\", \
+                 </api/users?q=ann&per_page=10&page=3>; rel=\"next\""
+            )
+        );
+        assert_eq!(links(&only, None), None);
+        assert_eq!(
+            links(&offset, Some("offset=1&limit=2")).as_deref(),
+            Some("</api/users?limit=2&offset=0>; rel=\"prev\"")
+        );
+    }
+}
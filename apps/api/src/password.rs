@@ -0,0 +1,213 @@
+//! Password hashing
+//!
+//! Passwords are hashed with Argon2id (default parameters) and a random salt.
+//! Hashes are stored in PHC string format, which records the algorithm and
+//! parameters, so they can be verified after the defaults change.
+
+use crate::error::{AppError, Result};
+use argon2::Argon2;
+use chrono::Duration;
+use password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng};
+
+/// Shortest accepted password
+pub const MIN_LENGTH: usize = 8;
+
+/// Longest accepted password; bounds the hashing work per request
+pub const MAX_LENGTH: usize = 128;
+
+/// Well-formed hash (default parameters) that no password is expected to match
+///
+/// Verified instead of a real hash when there is none, so that the work done does
+/// not reveal whether an account exists.
+pub const UNUSABLE_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$AAAAAAAAAAAAAAAAAAAAAA$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+/// Default lifetime of a password reset token
+const DEFAULT_RESET_TTL_MINUTES: i64 = 30;
+
+/// Most password reset emails sent to one user per hour
+const MAX_RESETS_PER_HOUR: i64 = 3;
+
+/// Default failed logins in a row that lock an account
+const DEFAULT_LOCKOUT_MAX_ATTEMPTS: i32 = 5;
+
+/// Default time an account stays locked
+const DEFAULT_LOCKOUT_MINUTES: i64 = 15;
+
+/// Hash a password for storage
+///
+/// # Errors
+/// Returns `InternalServerError` if hashing fails
+pub fn hash(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::InternalServerError(format!("Failed to hash password: {e}")))
+}
+
+/// Check a password against a stored hash
+///
+/// A malformed stored hash never matches.
+#[must_use]
+pub fn verify(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// [`hash`] on the blocking thread pool, as hashing takes tens of milliseconds
+///
+/// # Errors
+/// Returns `InternalServerError` if hashing fails
+pub async fn hash_async(password: String) -> Result<String> {
+    tokio::task::spawn_blocking(move || hash(&password))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Hashing task failed: {e}")))?
+}
+
+/// [`verify`] on the blocking thread pool
+///
+/// # Errors
+/// Returns `InternalServerError` if the hashing task fails
+pub async fn verify_async(password: String, hash: String) -> Result<bool> {
+    tokio::task::spawn_blocking(move || verify(&password, &hash))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Hashing task failed: {e}")))
+}
+
+/// Validate a new password
+///
+/// # Errors
+/// Returns validation error if the password:
+/// - Is shorter than 8 or longer than 128 characters
+/// - Does not contain both a letter and a digit
+pub fn validate(password: &str) -> Result<()> {
+    let length = password.chars().count();
+    if !(MIN_LENGTH..=MAX_LENGTH).contains(&length) {
+        return Err(AppError::ValidationError(format!(
+            "Password must be between {MIN_LENGTH} and {MAX_LENGTH} characters"
+        )));
+    }
+    if !password.chars().any(char::is_alphabetic) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(AppError::ValidationError(
+            "Password must contain at least one letter and one digit".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Settings of the forgot-password flow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetSettings {
+    /// How long a reset token is valid
+    pub ttl: Duration,
+    /// Page the emailed link points to; the token is appended as `?token=`
+    /// (`None` = the email contains the bare token)
+    pub url: Option<String>,
+    /// Most reset emails sent to one user per hour
+    pub max_per_hour: i64,
+}
+
+impl Default for ResetSettings {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::minutes(DEFAULT_RESET_TTL_MINUTES),
+            url: None,
+            max_per_hour: MAX_RESETS_PER_HOUR,
+        }
+    }
+}
+
+impl ResetSettings {
+    /// Text of the reset email for a token
+    #[must_use]
+    pub fn email_body(&self, token: &str) -> String {
+        let link = self.url.as_ref().map_or_else(
+            || format!("Reset token: {token}"),
+            |url| format!("{url}?token={token}"),
+        );
+        format!(
+            "A password reset was requested for your account.\n\n\
+             {link}\n\n\
+             This link expires in {} minutes. If you did not request a reset, ignore this email.\n",
+            self.ttl.num_minutes()
+        )
+    }
+}
+
+/// Locking of accounts after repeated failed logins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    /// Failed logins in a row that lock the account
+    pub max_attempts: i32,
+    /// How long the account stays locked
+    pub cooldown: Duration,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_LOCKOUT_MAX_ATTEMPTS,
+            cooldown: Duration::minutes(DEFAULT_LOCKOUT_MINUTES),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify() {
+        let hash = hash("correct horse").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify("correct horse", &hash));
+        assert!(!verify("wrong horse", &hash));
+        assert!(!verify("correct horse", "not a hash"));
+    }
+
+    #[test]
+    fn test_unusable_hash_is_well_formed() {
+        assert!(PasswordHash::new(UNUSABLE_HASH).is_ok());
+        assert!(!verify("", UNUSABLE_HASH));
+        assert!(!verify("password", UNUSABLE_HASH));
+    }
+
+    #[test]
+    fn test_hash_uses_random_salt() {
+        assert_ne!(hash("password").unwrap(), hash("password").unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("passw0rd").is_ok());
+        assert!(validate("pässwört1").is_ok());
+        assert!(validate("passw0r").is_err());
+        assert!(validate(&format!("1{}", "x".repeat(MAX_LENGTH))).is_err());
+    }
+
+    #[test]
+    fn test_validate_requires_letter_and_digit() {
+        assert!(validate("12345678").is_err());
+        assert!(validate("password").is_err());
+        assert!(validate("        ").is_err());
+    }
+
+    #[test]
+    fn test_reset_email_body() {
+        let mut settings = ResetSettings {
+            ttl: Duration::minutes(30),
+            url: Some("https://app.example.com/reset".to_string()),
+            max_per_hour: 3,
+        };
+        let body = settings.email_body("abc");
+        assert!(body.contains("https://app.example.com/reset?token=abc"));
+        assert!(body.contains("30 minutes"));
+
+        settings.url = None;
+        assert!(settings.email_body("abc").contains("Reset token: abc"));
+    }
+}
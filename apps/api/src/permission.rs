@@ -0,0 +1,114 @@
+//! Route-level permissions
+//!
+//! On top of roles, routes that authenticate users require a [`Permission`],
+//! and each role is granted a set of permissions (see `role_permissions`).
+//! [`RequirePermission`] is applied per route in [`crate::router`]; handlers
+//! still check which records the user may access.
+
+use crate::error::AppError;
+use crate::extract::AuthUser;
+use crate::models::Permission;
+use crate::repository::PermissionRepository;
+use crate::state::AppState;
+use axum::{
+    extract::{FromRef, FromRequestParts, Request},
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use futures_util::future::BoxFuture;
+use std::convert::Infallible;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Layer rejecting requests unless the user's role is granted a permission
+///
+/// Authenticates the user like [`AuthUser`] (rejecting with `Unauthorized`),
+/// then rejects with `Forbidden` unless the user's role is granted the
+/// permission. The authenticated user is passed on to the handler, so it is
+/// not looked up twice.
+#[derive(Clone)]
+pub struct RequirePermission {
+    permission: Permission,
+    state: AppState,
+}
+
+impl RequirePermission {
+    /// Require `permission` on a route of the router built from `state`
+    #[must_use]
+    pub fn new(state: &AppState, permission: Permission) -> Self {
+        Self {
+            permission,
+            state: state.clone(),
+        }
+    }
+}
+
+impl<S> Layer<S> for RequirePermission {
+    type Service = RequirePermissionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequirePermissionService {
+            inner,
+            permission: self.permission,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Service of [`RequirePermission`]
+#[derive(Clone)]
+pub struct RequirePermissionService<S> {
+    inner: S,
+    permission: Permission,
+    state: AppState,
+}
+
+impl<S> Service<Request> for RequirePermissionService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The ready service handles this request; its clone waits for the next one
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let permission = self.permission;
+        let state = self.state.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            if let Err(e) = authorize(&mut parts, &state, permission).await {
+                return Ok(e.into_response());
+            }
+            inner.call(Request::from_parts(parts, body)).await
+        })
+    }
+}
+
+/// Authenticate the user and check that their role is granted `permission`
+async fn authorize(
+    parts: &mut Parts,
+    state: &AppState,
+    permission: Permission,
+) -> Result<(), AppError> {
+    let user = AuthUser::from_request_parts(parts, state).await?;
+    if !PermissionRepository::from_ref(state)
+        .is_granted(user.role, permission)
+        .await?
+    {
+        return Err(AppError::Forbidden(format!(
+            "The {permission} permission is required"
+        )));
+    }
+
+    parts.extensions.insert(user);
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Database connection pool statistics
+//!
+//! Operators tune the pool (see `DB_MAX_CONNECTIONS` and friends) with
+//! [`PoolStats`], served as JSON by `GET /api/admin/db/pool` and in the
+//! Prometheus text format by `GET /metrics`.
+//!
+//! sqlx does not time the requests waiting for a connection, so the wait is
+//! sampled instead: every snapshot measures how long acquiring one connection
+//! takes right now. Acquire timeouts and connect errors are counted where the
+//! errors are converted into `AppError`s, so errors handled elsewhere (e.g. by
+//! the readiness check) are not counted. Operations retried after transient
+//! errors (see [`crate::repository::retry`]) are counted as well.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long a snapshot waits for a connection
+const ACQUIRE_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Events of the pool, counted since the process started
+static CONNECTIONS_OPENED: AtomicU64 = AtomicU64::new(0);
+static ACQUIRE_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+static CONNECT_ERRORS: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static RETRIES_EXHAUSTED: AtomicU64 = AtomicU64::new(0);
+
+/// Count a connection opened by the pool
+pub fn record_connection_opened() {
+    CONNECTIONS_OPENED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count a retry of an operation after a transient error
+pub fn record_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an operation given up after its last attempt failed
+pub fn record_retries_exhausted() {
+    RETRIES_EXHAUSTED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count the pool errors among database errors
+///
+/// I/O and TLS errors mostly come from failed connection attempts, so they
+/// are counted as connect errors.
+pub fn record_error(err: &sqlx::Error) {
+    match err {
+        sqlx::Error::PoolTimedOut => {
+            ACQUIRE_TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+        }
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) => {
+            CONNECT_ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+}
+
+/// Snapshot of the pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    /// Open connections, idle or in use
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// Time it took to acquire a connection for this snapshot (`None` if it
+    /// timed out)
+    pub acquire_ms: Option<u64>,
+    pub connections_opened: u64,
+    pub acquire_timeouts: u64,
+    pub connect_errors: u64,
+    /// Attempts repeated after transient errors
+    pub retries: u64,
+    /// Operations still failing after their last attempt
+    pub retries_exhausted: u64,
+}
+
+impl PoolStats {
+    /// Format the snapshot as Prometheus metrics
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: &dyn std::fmt::Display| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {value}");
+        };
+        metric(
+            "db_pool_connections",
+            "gauge",
+            "Open connections of the database pool",
+            &self.size,
+        );
+        metric(
+            "db_pool_idle_connections",
+            "gauge",
+            "Idle connections of the database pool",
+            &self.idle,
+        );
+        metric(
+            "db_pool_max_connections",
+            "gauge",
+            "Most connections the database pool opens",
+            &self.max_connections,
+        );
+        if let Some(ms) = self.acquire_ms {
+            let seconds = Duration::from_millis(ms).as_secs_f64();
+            metric(
+                "db_pool_acquire_seconds",
+                "gauge",
+                "Time a sample request waited for a database connection",
+                &seconds,
+            );
+        }
+        metric(
+            "db_pool_connections_opened_total",
+            "counter",
+            "Connections opened by the database pool",
+            &self.connections_opened,
+        );
+        metric(
+            "db_pool_acquire_timeouts_total",
+            "counter",
+            "Requests that timed out waiting for a database connection",
+            &self.acquire_timeouts,
+        );
+        metric(
+            "db_pool_connect_errors_total",
+            "counter",
+            "Failed attempts to reach the database",
+            &self.connect_errors,
+        );
+        metric(
+            "db_retries_total",
+            "counter",
+            "Database operations retried after transient errors",
+            &self.retries,
+        );
+        metric(
+            "db_retries_exhausted_total",
+            "counter",
+            "Database operations failing after their last retry",
+            &self.retries_exhausted,
+        );
+        out
+    }
+}
+
+/// Takes snapshots of the pool
+#[derive(Debug, Clone)]
+pub struct PoolMonitor {
+    pool: PgPool,
+}
+
+impl PoolMonitor {
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Take a snapshot, sampling the time to acquire a connection
+    pub async fn stats(&self) -> PoolStats {
+        // Counted before the probe, which takes a connection itself
+        let size = self.pool.size();
+        let idle = u32::try_from(self.pool.num_idle()).unwrap_or(u32::MAX);
+        let options = self.pool.options();
+
+        let started = Instant::now();
+        let acquired = tokio::time::timeout(ACQUIRE_PROBE_TIMEOUT, self.pool.acquire()).await;
+        let acquire_ms = match acquired {
+            Ok(Ok(_conn)) => Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)),
+            Ok(Err(e)) => {
+                record_error(&e);
+                tracing::warn!("Failed to acquire a connection for pool statistics: {e}");
+                None
+            }
+            Err(_) => None,
+        };
+
+        PoolStats {
+            size,
+            idle,
+            in_use: size.saturating_sub(idle),
+            max_connections: options.get_max_connections(),
+            min_connections: options.get_min_connections(),
+            acquire_ms,
+            connections_opened: CONNECTIONS_OPENED.load(Ordering::Relaxed),
+            acquire_timeouts: ACQUIRE_TIMEOUTS.load(Ordering::Relaxed),
+            connect_errors: CONNECT_ERRORS.load(Ordering::Relaxed),
+            retries: RETRIES.load(Ordering::Relaxed),
+            retries_exhausted: RETRIES_EXHAUSTED.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_format() {
+        let stats = PoolStats {
+            size: 5,
+            idle: 3,
+            in_use: 2,
+            max_connections: 20,
+            min_connections: 0,
+            acquire_ms: Some(250),
+            connections_opened: 7,
+            acquire_timeouts: 1,
+            connect_errors: 0,
+            retries: 2,
+            retries_exhausted: 0,
+        };
+
+        let text = stats.to_prometheus();
+
+        assert!(text.contains("# TYPE db_pool_connections gauge\ndb_pool_connections 5\n"));
+        assert!(text.contains("db_pool_idle_connections 3\n"));
+        assert!(text.contains("db_pool_acquire_seconds 0.25\n"));
+        assert!(text.contains(
+            "# TYPE db_pool_acquire_timeouts_total counter\ndb_pool_acquire_timeouts_total 1\n"
+        ));
+        assert!(text.contains("db_retries_total 2\n"));
+    }
+}
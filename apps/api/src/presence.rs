@@ -0,0 +1,132 @@
+//! Realtime presence
+//!
+//! `GET /api/ws` pushes the presence status (`present`, `on_break` or
+//! `absent`, as in `/api/attendance/summary`) of the users a client
+//! subscribed to. Handlers recording attendance events publish the new status
+//! of the user to the [`PresenceHub`], which fans it out to every open
+//! connection; each connection forwards the updates of its subscriptions.
+//!
+//! The hub lives in the process: with several API instances, a connection
+//! only receives the updates of events recorded by its own instance.
+
+use crate::attendance::{
+    self,
+    summary::{PresenceStatus, presence_status},
+};
+use crate::error::Result;
+use crate::repository::AttendanceEventRepository;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Heartbeat interval when `presence.heartbeat_seconds` is unset
+const DEFAULT_HEARTBEAT_SECONDS: u64 = 30;
+
+/// Updates buffered for a connection before it misses some
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Presence status of one user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserPresence {
+    pub user_id: Uuid,
+    pub status: PresenceStatus,
+    /// Time of the latest event of the day (`None` without events)
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Settings of presence connections (the `[presence]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresenceSettings {
+    /// Interval between two pings of a connection
+    pub heartbeat: Duration,
+}
+
+impl Default for PresenceSettings {
+    fn default() -> Self {
+        Self {
+            heartbeat: Duration::from_secs(DEFAULT_HEARTBEAT_SECONDS),
+        }
+    }
+}
+
+/// Fans presence updates out to the open WebSocket connections
+#[derive(Debug, Clone)]
+pub struct PresenceHub {
+    sender: broadcast::Sender<UserPresence>,
+    /// Interval between two pings of a connection; connections silent for
+    /// two intervals are closed
+    pub heartbeat: Duration,
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        Self::from_settings(&PresenceSettings::default())
+    }
+}
+
+impl PresenceHub {
+    /// Create a hub pinging connections every `heartbeat`
+    #[must_use]
+    pub fn new(heartbeat: Duration) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender, heartbeat }
+    }
+
+    /// Create a hub with the configured heartbeat interval
+    #[must_use]
+    pub fn from_settings(settings: &PresenceSettings) -> Self {
+        Self::new(settings.heartbeat)
+    }
+
+    /// Receive the updates published from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<UserPresence> {
+        self.sender.subscribe()
+    }
+
+    /// Publish the current status of a user after an event was recorded
+    ///
+    /// Does nothing while no connection is open. Failures are logged; they do
+    /// not fail the request recording the event.
+    pub async fn publish(&self, repo: &AttendanceEventRepository, user_id: Uuid) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        match current(repo, &[user_id], Utc::now()).await {
+            Ok(presence) => {
+                for presence in presence {
+                    // Fails only when the last connection closed meanwhile
+                    let _ = self.sender.send(presence);
+                }
+            }
+            Err(e) => tracing::warn!(user_id = %user_id, "Failed to publish presence: {e}"),
+        }
+    }
+}
+
+/// Current presence status of several users, based on their latest event of
+/// the day
+///
+/// Unknown or deleted users are omitted.
+///
+/// # Errors
+/// Returns error if database operation fails
+pub async fn current(
+    repo: &AttendanceEventRepository,
+    user_ids: &[Uuid],
+    now: DateTime<Utc>,
+) -> Result<Vec<UserPresence>> {
+    let from = attendance::local_day_start(attendance::local_date(now));
+    let activity = repo.find_activity_for_users(user_ids, from, now).await?;
+
+    Ok(activity
+        .into_iter()
+        .map(|a| UserPresence {
+            user_id: a.user_id,
+            status: presence_status(a.latest_event_type),
+            since: a.latest_event_time,
+        })
+        .collect())
+}
@@ -0,0 +1,161 @@
+//! RFC 7807 problem details for error responses
+//!
+//! Errors are returned in the `ErrorResponse` JSON format by default. Clients
+//! that send `Accept: application/problem+json`, or every client when
+//! `server.error_format` is `problem` (`ERROR_FORMAT=problem`), receive an
+//! `application/problem+json` document instead:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Not Found",
+//!   "status": 404,
+//!   "detail": "Todo with id 1 not found",
+//!   "instance": "/api/todos/1",
+//!   "code": "TODO_NOT_FOUND",
+//!   "request_id": "3f2b6c1e-0d4a-4c5e-9a57-2f1d8e6b7c90"
+//! }
+//! ```
+//!
+//! `code` and `request_id` are extension members carrying the same values as
+//! `ErrorResponse`.
+
+use crate::error::{ErrorCode, ErrorDetails};
+use axum::{
+    Json,
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Media type of problem detail documents
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Format of error responses sent to clients that do not ask for one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// `ErrorResponse` JSON (`error`, `code`, `message`)
+    #[default]
+    Json,
+    /// `application/problem+json` documents
+    Problem,
+}
+
+impl FromStr for ErrorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "problem" => Ok(Self::Problem),
+            _ => Err(format!("Error format must be json or problem: {s}")),
+        }
+    }
+}
+
+impl ErrorFormat {
+    /// The format for a request: problem details if the client accepts them
+    fn negotiate(self, headers: &HeaderMap) -> Self {
+        let accepts_problem = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|media_range| {
+                media_range
+                    .split(';')
+                    .next()
+                    .is_some_and(|media_type| media_type.trim() == PROBLEM_JSON)
+            });
+        if accepts_problem { Self::Problem } else { self }
+    }
+}
+
+/// An RFC 7807 problem details document
+#[derive(Debug, Serialize)]
+struct ProblemDetails {
+    #[serde(rename = "type")]
+    problem_type: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: String,
+    instance: String,
+    code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+}
+
+/// Middleware rewriting error responses as problem details when negotiated
+///
+/// Only responses built from an `AppError` are rewritten; their status and
+/// headers (e.g. `Retry-After`) are kept.
+pub async fn problem_details(
+    State(format): State<ErrorFormat>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let format = format.negotiate(request.headers());
+    let instance = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    if format != ErrorFormat::Problem {
+        return response;
+    }
+    let Some(details) = response.extensions().get::<ErrorDetails>().cloned() else {
+        return response;
+    };
+
+    let (mut parts, _) = response.into_parts();
+    let problem = ProblemDetails {
+        problem_type: "about:blank",
+        title: parts.status.canonical_reason().unwrap_or("Error"),
+        status: parts.status.as_u16(),
+        detail: details.message,
+        instance,
+        code: details.code,
+        request_id: details.request_id,
+    };
+    let Ok(body) = serde_json::to_vec(&problem) else {
+        return (parts, Json(problem)).into_response();
+    };
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_negotiate_problem_details() {
+        let json = ErrorFormat::Json;
+
+        assert_eq!(json.negotiate(&HeaderMap::new()), ErrorFormat::Json);
+        assert_eq!(
+            json.negotiate(&accept("application/json")),
+            ErrorFormat::Json
+        );
+        assert_eq!(
+            json.negotiate(&accept("application/json, application/problem+json;q=0.9")),
+            ErrorFormat::Problem
+        );
+        assert_eq!(
+            ErrorFormat::Problem.negotiate(&accept("application/json")),
+            ErrorFormat::Problem
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Per-user rate limiting
+//!
+//! [`limit_requests`] counts the requests of each client in fixed one-minute
+//! windows and rejects them with `429 Too Many Requests` and a `Retry-After`
+//! header once the limit of the route group is reached. Clients are keyed by
+//! the authenticated user (bearer token or session cookie), falling back to
+//! the client IP (see [`ClientMetadata`]).
+//!
+//! Counters go through a [`RateLimitStore`]. The default,
+//! [`InMemoryRateLimitStore`], keeps them in the process; with several API
+//! instances, [`RedisRateLimitStore`] shares them through Redis.
+
+use crate::auth::AuthTokens;
+use crate::error::{AppError, Result};
+use crate::extract::{self, ClientMetadata};
+use crate::models::Session;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use futures_util::future::BoxFuture;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Length of a rate limit window
+const WINDOW: Duration = Duration::from_mins(1);
+
+/// Default requests per minute to the `auth` route group
+const DEFAULT_AUTH_PER_MINUTE: u64 = 20;
+
+/// Default requests per minute to the `api` route group
+const DEFAULT_API_PER_MINUTE: u64 = 600;
+
+/// The in-memory store drops expired windows once it holds this many
+const PURGE_THRESHOLD: usize = 10_000;
+
+/// Routes sharing a rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    /// Sign-in, registration, password reset and password changes, where
+    /// credentials are guessed
+    Auth,
+    /// Every other route under `/api`
+    Api,
+}
+
+impl RouteGroup {
+    /// The group of a request path (`None` for routes that are not limited,
+    /// e.g. `/health` and uploads)
+    #[must_use]
+    pub fn for_path(path: &str) -> Option<Self> {
+        if path.starts_with("/api/auth/")
+            || path == "/api/invitations/accept"
+            || is_password_change(path)
+        {
+            Some(Self::Auth)
+        } else if path.starts_with("/api/") {
+            Some(Self::Api)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::Api => "api",
+        }
+    }
+}
+
+/// Whether the path is `/api/users/{id}/password`
+fn is_password_change(path: &str) -> bool {
+    path.strip_prefix("/api/users/")
+        .and_then(|rest| rest.strip_suffix("/password"))
+        .is_some_and(|id| !id.is_empty() && !id.contains('/'))
+}
+
+impl fmt::Display for RouteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Requests per minute allowed to each route group (`None` = unlimited)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    pub auth_per_minute: Option<u64>,
+    pub api_per_minute: Option<u64>,
+}
+
+impl RateLimits {
+    /// Requests per minute allowed to a route group
+    #[must_use]
+    pub const fn for_group(&self, group: RouteGroup) -> Option<u64> {
+        match group {
+            RouteGroup::Auth => self.auth_per_minute,
+            RouteGroup::Api => self.api_per_minute,
+        }
+    }
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            auth_per_minute: Some(DEFAULT_AUTH_PER_MINUTE),
+            api_per_minute: Some(DEFAULT_API_PER_MINUTE),
+        }
+    }
+}
+
+/// Limits and counter store of the rate limiter
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct RateLimitSettings {
+    pub limits: RateLimits,
+    /// Redis server keeping the counters (`None` keeps them in memory)
+    pub redis_url: Option<String>,
+}
+
+impl fmt::Debug for RateLimitSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitSettings")
+            .field("limits", &self.limits)
+            .field(
+                "redis_url",
+                &self.redis_url.as_deref().map(crate::db::mask_password),
+            )
+            .finish()
+    }
+}
+
+/// A request counted in a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    /// Requests counted in the current window, including this one
+    pub count: u64,
+    /// Time until the current window ends
+    pub reset_after: Duration,
+}
+
+/// A place rate limit counters are kept
+pub trait RateLimitStore: Send + Sync {
+    /// Count a request under `key` in the current window of length `window`
+    fn hit<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<Hit>>;
+}
+
+/// Keeps counters in memory, per process
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    windows: Mutex<HashMap<String, (Instant, u64)>>,
+}
+
+impl InMemoryRateLimitStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn hit<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<Hit>> {
+        Box::pin(async move {
+            let now = Instant::now();
+            let mut windows = self
+                .windows
+                .lock()
+                .map_err(|_| AppError::InternalServerError("Rate limit lock poisoned".into()))?;
+            if windows.len() >= PURGE_THRESHOLD {
+                windows.retain(|_, (started, _)| now.duration_since(*started) < window);
+            }
+
+            let (started, count) = windows.entry(key.to_string()).or_insert((now, 0));
+            if now.duration_since(*started) >= window {
+                *started = now;
+                *count = 0;
+            }
+            *count += 1;
+
+            let hit = Hit {
+                count: *count,
+                reset_after: window.saturating_sub(now.duration_since(*started)),
+            };
+            drop(windows);
+            Ok(hit)
+        })
+    }
+}
+
+/// Keeps counters in Redis, shared by every API instance
+#[derive(Clone)]
+pub struct RedisRateLimitStore {
+    connection: ConnectionManager,
+}
+
+impl RedisRateLimitStore {
+    /// Create a store connecting to `url` (e.g. `redis://localhost:6379`)
+    ///
+    /// The connection is established on first use and re-established when lost.
+    ///
+    /// # Errors
+    /// Returns error if the URL is not valid
+    pub fn new(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_connection_manager_lazy(ConnectionManagerConfig::default())?;
+        Ok(Self { connection })
+    }
+}
+
+impl RateLimitStore for RedisRateLimitStore {
+    fn hit<'a>(&'a self, key: &'a str, window: Duration) -> BoxFuture<'a, Result<Hit>> {
+        Box::pin(async move {
+            let mut connection = self.connection.clone();
+            let (count, ttl_ms): (u64, i64) = redis::pipe()
+                .atomic()
+                .incr(key, 1)
+                .cmd("PEXPIRE")
+                .arg(key)
+                .arg(window.as_millis().try_into().unwrap_or(u64::MAX))
+                .arg("NX")
+                .ignore()
+                .pttl(key)
+                .query_async(&mut connection)
+                .await?;
+
+            Ok(Hit {
+                count,
+                reset_after: u64::try_from(ttl_ms).map_or(window, Duration::from_millis),
+            })
+        })
+    }
+}
+
+/// Shared handle to the rate limits and the configured counter store
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    limits: RateLimits,
+}
+
+impl RateLimiter {
+    /// Create a limiter keeping counters in memory
+    #[must_use]
+    pub fn new(limits: RateLimits) -> Self {
+        Self::with_store(InMemoryRateLimitStore::new(), limits)
+    }
+
+    #[must_use]
+    pub fn with_store(store: impl RateLimitStore + 'static, limits: RateLimits) -> Self {
+        Self {
+            store: Arc::new(store),
+            limits,
+        }
+    }
+
+    /// Create the limiter with the configured limits
+    ///
+    /// Counters are kept in Redis if a URL is configured, otherwise in memory.
+    #[must_use]
+    pub fn from_settings(settings: &RateLimitSettings) -> Self {
+        let Some(url) = &settings.redis_url else {
+            return Self::new(settings.limits);
+        };
+        match RedisRateLimitStore::new(url) {
+            Ok(store) => Self::with_store(store, settings.limits),
+            Err(e) => {
+                tracing::warn!(
+                    "rate_limit.redis_url is not valid, keeping counters in memory: {e}"
+                );
+                Self::new(settings.limits)
+            }
+        }
+    }
+
+    /// Count a request of `client` to a route group
+    ///
+    /// # Errors
+    /// Returns `TooManyRequests` if the client has reached the limit of the group
+    /// in the current window
+    pub async fn check(&self, group: RouteGroup, client: &str) -> Result<()> {
+        let Some(limit) = self.limits.for_group(group) else {
+            return Ok(());
+        };
+
+        let key = format!("rate_limit:{group}:{client}");
+        let hit = match self.store.hit(&key, WINDOW).await {
+            Ok(hit) => hit,
+            Err(e) => {
+                // Failing open: an unavailable store must not take the API down
+                tracing::warn!(error = %e, "Rate limit store is unavailable");
+                return Ok(());
+            }
+        };
+
+        if hit.count > limit {
+            // Whole seconds, rounded up so clients do not retry too early
+            let retry_after =
+                hit.reset_after.as_secs() + u64::from(hit.reset_after.subsec_nanos() > 0);
+            return Err(AppError::TooManyRequests { retry_after });
+        }
+        Ok(())
+    }
+}
+
+/// Middleware applying the rate limit of the route group of each request
+pub async fn limit_requests(
+    State(limiter): State<RateLimiter>,
+    State(auth_tokens): State<AuthTokens>,
+    client: ClientMetadata,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(group) = RouteGroup::for_path(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    // Tokens are only verified, not looked up: invalid ones fall back to the IP
+    let user_id = extract::bearer_token(request.headers())
+        .and_then(|token| auth_tokens.verify(token, Utc::now()).ok())
+        .map(|claims| claims.sub)
+        .or_else(|| request.extensions().get::<Session>().map(|s| s.user_id));
+    let client = match (user_id, client.ip) {
+        (Some(user_id), _) => format!("user:{user_id}"),
+        (None, Some(ip)) => format!("ip:{ip}"),
+        (None, None) => "ip:unknown".to_string(),
+    };
+
+    match limiter.check(group, &client).await {
+        Ok(()) => next.run(request).await,
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,140 @@
+//! Reporting of server errors to an external service
+//!
+//! Every `AppError` answered with a 5xx status is passed to the registered
+//! [`ErrorReporter`], together with the request it occurred in. No reporter is
+//! registered by default; with the `sentry` feature, [`sentry::init_from_env`]
+//! registers one when `SENTRY_DSN` is set.
+//!
+//! Error responses are built without access to the router state, so the
+//! reporter is registered process-wide and the request is captured by the
+//! [`capture_request`] middleware.
+
+use crate::error::AppError;
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::OnceLock;
+
+/// The request an error occurred in
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub method: Method,
+    /// Path of the request, without the query string (which may hold tokens)
+    pub path: String,
+    /// See [`crate::request_id`]
+    pub request_id: Option<String>,
+}
+
+/// A server error to report
+#[derive(Debug)]
+pub struct ErrorReport<'a> {
+    pub status: StatusCode,
+    pub error: &'a AppError,
+    /// `None` for errors outside a request (e.g. background jobs)
+    pub request: Option<&'a RequestContext>,
+}
+
+/// A destination for server errors
+pub trait ErrorReporter: Send + Sync + 'static {
+    /// Report an error; must not block, as it runs while the response is built
+    fn report(&self, report: &ErrorReport<'_>);
+}
+
+static REPORTER: OnceLock<Box<dyn ErrorReporter>> = OnceLock::new();
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// Register the process-wide error reporter
+///
+/// # Errors
+/// Returns the reporter back if one is already registered
+pub fn set_reporter(reporter: impl ErrorReporter) -> Result<(), Box<dyn ErrorReporter>> {
+    REPORTER.set(Box::new(reporter))
+}
+
+/// Middleware capturing the request for errors reported while handling it
+pub async fn capture_request(request: Request, next: Next) -> Response {
+    let context = RequestContext {
+        method: request.method().clone(),
+        path: request.uri().path().to_string(),
+        request_id: crate::request_id::current(),
+    };
+    REQUEST.scope(context, next.run(request)).await
+}
+
+/// Pass a server error to the registered reporter, if any
+pub(crate) fn report(status: StatusCode, error: &AppError) {
+    let Some(reporter) = REPORTER.get() else {
+        return;
+    };
+    let in_request = REQUEST.try_with(|request| {
+        reporter.report(&ErrorReport {
+            status,
+            error,
+            request: Some(request),
+        });
+    });
+    if in_request.is_err() {
+        reporter.report(&ErrorReport {
+            status,
+            error,
+            request: None,
+        });
+    }
+}
+
+/// Reporting to Sentry
+#[cfg(feature = "sentry")]
+pub mod sentry {
+    use super::{ErrorReport, ErrorReporter, set_reporter};
+
+    /// Reports server errors as Sentry events
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct SentryReporter;
+
+    impl ErrorReporter for SentryReporter {
+        fn report(&self, report: &ErrorReport<'_>) {
+            ::sentry::with_scope(
+                |scope| {
+                    scope.set_tag("http.status_code", report.status.as_u16());
+                    if let Some(request) = report.request {
+                        scope.set_tag("http.method", request.method.as_str());
+                        scope.set_tag("http.path", &request.path);
+                        if let Some(request_id) = &request.request_id {
+                            scope.set_tag("request_id", request_id);
+                        }
+                    }
+                },
+                || ::sentry::capture_error(report.error),
+            );
+        }
+    }
+
+    /// Initialize Sentry from `SENTRY_DSN` and `SENTRY_ENVIRONMENT`
+    ///
+    /// Registers [`SentryReporter`] and returns the guard that flushes pending
+    /// events when dropped, or `None` when `SENTRY_DSN` is unset.
+    #[must_use]
+    pub fn init_from_env() -> Option<::sentry::ClientInitGuard> {
+        let dsn = std::env::var("SENTRY_DSN")
+            .ok()
+            .filter(|dsn| !dsn.is_empty())?;
+        let mut options = ::sentry::ClientOptions::new();
+        options.release = ::sentry::release_name!();
+        options.environment = std::env::var("SENTRY_ENVIRONMENT").ok().map(Into::into);
+        let guard = ::sentry::init((dsn, options));
+        if !guard.is_enabled() {
+            tracing::warn!("SENTRY_DSN is not valid, errors are not reported");
+            return None;
+        }
+        if set_reporter(SentryReporter).is_err() {
+            tracing::warn!("An error reporter is already registered, Sentry is not used");
+        }
+        Some(guard)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::error::{AppError, Result};
+use crate::models::{ApiKey, CreateApiKey};
+use crate::repository::retry;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Row of `api_keys`, with the scopes as stored
+struct ApiKeyRow {
+    id: Uuid,
+    name: String,
+    key_prefix: String,
+    scopes: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ApiKeyRow> for ApiKey {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            key_prefix: row.key_prefix,
+            // Scopes no longer known grant nothing
+            scopes: row
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+            expires_at: row.expires_at,
+            revoked_at: row.revoked_at,
+            last_used_at: row.last_used_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Repository for API key database operations
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    pool: PgPool,
+}
+
+impl ApiKeyRepository {
+    /// Create a new `ApiKeyRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all API keys, including revoked and expired ones, newest first
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(&self) -> Result<Vec<ApiKey>> {
+        retry::read("api_keys.list", || async move {
+            let rows = sqlx::query_as!(
+                ApiKeyRow,
+                r#"
+                SELECT id, name, key_prefix, scopes as "scopes: Vec<String>", expires_at, revoked_at,
+                       last_used_at, created_at
+                FROM api_keys
+                ORDER BY created_at DESC
+                "#
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(rows.into_iter().map(Into::into).collect())
+        })
+        .await
+    }
+
+    /// Find an API key by its ID
+    ///
+    /// # Returns
+    /// * `Ok(Some(ApiKey))` - Key found
+    /// * `Ok(None)` - Key not found
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<ApiKey>> {
+        retry::read("api_keys.find_by_id", || async move {
+            let row = sqlx::query_as!(
+                ApiKeyRow,
+                r#"
+                SELECT id, name, key_prefix, scopes as "scopes: Vec<String>", expires_at, revoked_at,
+                       last_used_at, created_at
+                FROM api_keys
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(row.map(Into::into))
+        })
+        .await
+    }
+
+    /// Create an API key
+    ///
+    /// # Arguments
+    /// * `key` - The key creation request data
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn create(&self, key: CreateApiKey) -> Result<ApiKey> {
+        let scopes: Vec<String> = key.scopes.iter().map(ToString::to_string).collect();
+        let row = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            INSERT INTO api_keys (name, key_prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, key_prefix, scopes as "scopes: Vec<String>", expires_at, revoked_at,
+                      last_used_at, created_at
+            "#,
+            key.name,
+            key.key_prefix,
+            key.key_hash,
+            &scopes,
+            key.expires_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(api_key_id = %row.id, "API key created");
+        Ok(row.into())
+    }
+
+    /// Authenticate a presented key, recording that it was used
+    ///
+    /// # Arguments
+    /// * `key_hash` - Hash of the presented key
+    /// * `now` - Current time, compared with the expiry
+    ///
+    /// # Returns
+    /// * `Ok(Some(ApiKey))` - The key is valid
+    /// * `Ok(None)` - The key is unknown, revoked or expired
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn authenticate(&self, key_hash: &str, now: DateTime<Utc>) -> Result<Option<ApiKey>> {
+        let row = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+            UPDATE api_keys
+            SET last_used_at = $2
+            WHERE key_hash = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > $2)
+            RETURNING id, name, key_prefix, scopes as "scopes: Vec<String>", expires_at, revoked_at,
+                      last_used_at, created_at
+            "#,
+            key_hash,
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Revoke an API key
+    /// Revoking a key that is already revoked keeps the original revocation time.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the key does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn revoke(&self, id: Uuid, now: DateTime<Utc>) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE api_keys
+            SET revoked_at = COALESCE(revoked_at, $2)
+            WHERE id = $1
+            "#,
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "API key with id {id} not found"
+            )));
+        }
+
+        tracing::info!(api_key_id = %id, "API key revoked");
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use crate::error::Result;
+use crate::models::{AnomalyKind, AttendanceAnomaly, CreateAttendanceAnomaly};
+use crate::repository::retry;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Attendance anomaly repository for database operations
+/// Handles recording and retrieval of anomalies detected in attendance events
+#[derive(Clone)]
+pub struct AttendanceAnomalyRepository {
+    pool: PgPool,
+}
+
+impl AttendanceAnomalyRepository {
+    /// Create a new `AttendanceAnomalyRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List anomalies, optionally filtered by user and kind
+    /// Returns anomalies ordered by `detected_at` in descending order (most recent first)
+    ///
+    /// # Arguments
+    /// * `user_id` - Only return anomalies for this user (if provided)
+    /// * `kind` - Only return anomalies of this kind (if provided)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceAnomaly>)` - List of anomalies (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(
+        &self,
+        user_id: Option<Uuid>,
+        kind: Option<AnomalyKind>,
+    ) -> Result<Vec<AttendanceAnomaly>> {
+        retry::read("attendance_anomalies.list", || async move {
+            let anomalies = sqlx::query_as!(
+                AttendanceAnomaly,
+                r#"
+                SELECT id, user_id, kind as "kind: AnomalyKind", event_id, details, detected_at
+                FROM attendance_anomalies
+                WHERE ($1::uuid IS NULL OR user_id = $1)
+                  AND ($2::varchar IS NULL OR kind = $2)
+                ORDER BY detected_at DESC
+                "#,
+                user_id,
+                kind.map(AnomalyKind::as_str)
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(anomalies)
+        })
+        .await
+    }
+
+    /// Record an anomaly detected for an event
+    /// An event is reported at most once per kind; repeated reports are ignored
+    ///
+    /// # Arguments
+    /// * `anomaly` - The anomaly creation request data
+    ///
+    /// # Returns
+    /// * `Ok(Some(AttendanceAnomaly))` - The recorded anomaly
+    /// * `Ok(None)` - The event was already reported for this kind
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn create(
+        &self,
+        anomaly: CreateAttendanceAnomaly,
+    ) -> Result<Option<AttendanceAnomaly>> {
+        let created = sqlx::query_as!(
+            AttendanceAnomaly,
+            r#"
+            INSERT INTO attendance_anomalies (user_id, kind, event_id, details)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT ON CONSTRAINT uq_attendance_anomalies_kind_event DO NOTHING
+            RETURNING id, user_id, kind as "kind: AnomalyKind", event_id, details, detected_at
+            "#,
+            anomaly.user_id,
+            anomaly.kind.as_str(),
+            anomaly.event_id,
+            anomaly.details
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(created)
+    }
+
+    /// Record a `missing_clock_out` anomaly for every user whose latest effective
+    /// clock event is a clock-in before `clocked_in_before`
+    /// Clock-ins that were already reported are skipped, so the scan is idempotent
+    ///
+    /// # Arguments
+    /// * `clocked_in_before` - Clock-ins at or after this time are not reported yet
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceAnomaly>)` - The newly recorded anomalies
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn record_missing_clock_outs(
+        &self,
+        clocked_in_before: DateTime<Utc>,
+    ) -> Result<Vec<AttendanceAnomaly>> {
+        let anomalies = sqlx::query_as!(
+            AttendanceAnomaly,
+            r#"
+            INSERT INTO attendance_anomalies (user_id, kind, event_id, details)
+            SELECT latest.user_id, 'missing_clock_out', latest.id,
+                   'Clocked in at ' || to_char(latest.event_time AT TIME ZONE 'UTC',
+                                               'YYYY-MM-DD"T"HH24:MI:SS"Z"')
+                   || ' without clocking out'
+            FROM (
+                SELECT DISTINCT ON (user_id) id, user_id, event_type, event_time
+                FROM attendance_events
+                WHERE event_type IN ('clock_in', 'clock_out')
+                  AND NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  )
+                ORDER BY user_id, event_time DESC
+            ) latest
+            WHERE latest.event_type = 'clock_in' AND latest.event_time < $1
+            ON CONFLICT ON CONSTRAINT uq_attendance_anomalies_kind_event DO NOTHING
+            RETURNING id, user_id, kind as "kind: AnomalyKind", event_id, details, detected_at
+            "#,
+            clocked_in_before
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(anomalies)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    AttendanceCorrection, CorrectionDecision, CorrectionStatus, CreateAttendanceCorrection,
+    EventType,
+};
+use crate::repository::retry;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Attendance correction repository for database operations
+/// Handles creation, retrieval and approval of correction requests for attendance events
+#[derive(Clone)]
+pub struct AttendanceCorrectionRepository {
+    pool: PgPool,
+}
+
+impl AttendanceCorrectionRepository {
+    /// Create a new `AttendanceCorrectionRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Find a correction request by ID
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the correction request
+    ///
+    /// # Returns
+    /// * `Ok(Some(AttendanceCorrection))` - Correction found
+    /// * `Ok(None)` - Correction not found
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<AttendanceCorrection>> {
+        retry::read("attendance_corrections.find_by_id", || async move {
+            let correction = sqlx::query_as!(
+                AttendanceCorrection,
+                r#"
+                SELECT id, event_id, user_id,
+                       proposed_event_type as "proposed_event_type: EventType",
+                       proposed_event_time, reason,
+                       status as "status: CorrectionStatus",
+                       created_at, approver_id, decided_at, decision_comment, applied_event_id
+                FROM attendance_corrections
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(correction)
+        })
+        .await
+    }
+
+    /// List correction requests, optionally filtered by user and status
+    /// Returns corrections ordered by `created_at` in descending order (most recent first)
+    ///
+    /// # Arguments
+    /// * `user_id` - Only return corrections for this user (if provided)
+    /// * `status` - Only return corrections with this status (if provided)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceCorrection>)` - List of corrections (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(
+        &self,
+        user_id: Option<Uuid>,
+        status: Option<CorrectionStatus>,
+    ) -> Result<Vec<AttendanceCorrection>> {
+        retry::read("attendance_corrections.list", || async move {
+            let corrections = sqlx::query_as!(
+                AttendanceCorrection,
+                r#"
+                SELECT id, event_id, user_id,
+                       proposed_event_type as "proposed_event_type: EventType",
+                       proposed_event_time, reason,
+                       status as "status: CorrectionStatus",
+                       created_at, approver_id, decided_at, decision_comment, applied_event_id
+                FROM attendance_corrections
+                WHERE ($1::uuid IS NULL OR user_id = $1)
+                  AND ($2::varchar IS NULL OR status = $2)
+                ORDER BY created_at DESC
+                "#,
+                user_id,
+                status.map(CorrectionStatus::as_str)
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(corrections)
+        })
+        .await
+    }
+
+    /// Create a new correction request for an existing attendance event
+    /// The `user_id` is copied from the referenced event
+    ///
+    /// # Arguments
+    /// * `correction` - The correction creation request data
+    ///
+    /// # Returns
+    /// * `Ok(AttendanceCorrection)` - The created correction with status `pending`
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the referenced attendance event does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn create(
+        &self,
+        correction: CreateAttendanceCorrection,
+    ) -> Result<AttendanceCorrection> {
+        let created = sqlx::query_as!(
+            AttendanceCorrection,
+            r#"
+            INSERT INTO attendance_corrections
+                (event_id, user_id, proposed_event_type, proposed_event_time, reason)
+            SELECT id, user_id, $2, $3, $4
+            FROM attendance_events
+            WHERE id = $1
+            RETURNING id, event_id, user_id,
+                      proposed_event_type as "proposed_event_type: EventType",
+                      proposed_event_time, reason,
+                      status as "status: CorrectionStatus",
+                      created_at, approver_id, decided_at, decision_comment, applied_event_id
+            "#,
+            correction.event_id,
+            correction.proposed_event_type.as_str(),
+            correction.proposed_event_time,
+            correction.reason
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        created.ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Attendance event with id {} not found",
+                correction.event_id
+            ))
+        })
+    }
+
+    /// Lock a pending correction for a decision, on a connection or transaction
+    /// The row stays locked until the transaction ends
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the correction request
+    /// * `approver_id` - The UUID of the deciding user
+    ///
+    /// # Returns
+    /// * `Ok(AttendanceCorrection)` - The pending correction
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the correction does not exist
+    /// Returns `AppError::BadRequest` if the correction is not pending
+    /// Returns `AppError::ValidationError` if the approver is the requester
+    /// Returns `AppError` if database query fails
+    pub async fn lock_pending_in(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        approver_id: Uuid,
+    ) -> Result<AttendanceCorrection> {
+        lock_pending(conn, id, approver_id).await
+    }
+
+    /// Record the decision on a correction locked by [`Self::lock_pending_in`]
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the correction request
+    /// * `status` - `Approved` or `Rejected`
+    /// * `decision` - The approver and optional comment
+    /// * `applied_event_id` - The event appended for an approved correction
+    ///
+    /// # Returns
+    /// * `Ok(AttendanceCorrection)` - The decided correction
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the approver does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn decide_in(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        status: CorrectionStatus,
+        decision: &CorrectionDecision,
+        applied_event_id: Option<Uuid>,
+    ) -> Result<AttendanceCorrection> {
+        decide(conn, id, status, decision, applied_event_id).await
+    }
+}
+
+/// Lock a correction row for a decision and check that it can be decided
+async fn lock_pending(
+    conn: &mut PgConnection,
+    id: Uuid,
+    approver_id: Uuid,
+) -> Result<AttendanceCorrection> {
+    let correction = sqlx::query_as!(
+        AttendanceCorrection,
+        r#"
+        SELECT id, event_id, user_id,
+               proposed_event_type as "proposed_event_type: EventType",
+               proposed_event_time, reason,
+               status as "status: CorrectionStatus",
+               created_at, approver_id, decided_at, decision_comment, applied_event_id
+        FROM attendance_corrections
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Attendance correction with id {id} not found")))?;
+
+    if correction.status != CorrectionStatus::Pending {
+        return Err(AppError::BadRequest(format!(
+            "Attendance correction with id {id} has already been {}",
+            correction.status
+        )));
+    }
+    if correction.user_id == approver_id {
+        return Err(AppError::ValidationError(
+            "Approver cannot decide on their own correction".to_string(),
+        ));
+    }
+
+    Ok(correction)
+}
+
+/// Record the decision on a locked pending correction
+async fn decide(
+    conn: &mut PgConnection,
+    id: Uuid,
+    status: CorrectionStatus,
+    decision: &CorrectionDecision,
+    applied_event_id: Option<Uuid>,
+) -> Result<AttendanceCorrection> {
+    let decided = sqlx::query_as!(
+        AttendanceCorrection,
+        r#"
+        UPDATE attendance_corrections
+        SET status = $2,
+            approver_id = $3,
+            decided_at = CURRENT_TIMESTAMP,
+            decision_comment = $4,
+            applied_event_id = $5
+        WHERE id = $1
+        RETURNING id, event_id, user_id,
+                  proposed_event_type as "proposed_event_type: EventType",
+                  proposed_event_time, reason,
+                  status as "status: CorrectionStatus",
+                  created_at, approver_id, decided_at, decision_comment, applied_event_id
+        "#,
+        id,
+        status.as_str(),
+        decision.approver_id,
+        decision.comment,
+        applied_event_id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => AppError::NotFound(
+            format!("Approver with id {} not found", decision.approver_id),
+        ),
+        e => e.into(),
+    })?;
+
+    Ok(decided)
+}
@@ -1,22 +1,38 @@
-use crate::error::Result;
-use crate::models::{AttendanceEvent, CreateAttendanceEvent};
-use chrono::Utc;
-use sqlx::PgPool;
+use crate::db::{CancelOnDrop, ReadPool};
+use crate::error::{AppError, ErrorCode, Result};
+use crate::models::{AttendanceEvent, CreateAttendanceEvent, EventType, UserDayActivity};
+use crate::repository::retry;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, TryStreamExt};
+use sqlx::{PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 
 /// Attendance event repository for database operations
+///
 /// Handles creation and retrieval of immutable attendance events
-/// Note: Events are immutable, so no update or delete operations are provided
+/// Note: Events are immutable, so no update or delete operations are provided;
+/// a mistaken event is superseded by a new event that amends it
 #[derive(Clone)]
 pub struct AttendanceEventRepository {
     pool: PgPool,
+    /// Lists, reports and exports may read from a replica
+    read_pool: ReadPool,
 }
 
 impl AttendanceEventRepository {
     /// Create a new `AttendanceEventRepository` instance
     #[must_use]
-    pub const fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            read_pool: ReadPool::primary(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Run lists, reports and exports on `read_pool`
+    #[must_use]
+    pub fn with_read_pool(self, read_pool: ReadPool) -> Self {
+        Self { read_pool, ..self }
     }
 
     /// Find an attendance event by ID
@@ -28,54 +44,491 @@ impl AttendanceEventRepository {
     /// * `Ok(Some(AttendanceEvent))` - Event found
     /// * `Ok(None)` - Event not found
     ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
     /// # Errors
     /// Returns `AppError` if database query fails
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<AttendanceEvent>> {
+        retry::read("attendance_events.find_by_id", || {
+            self.find_by_id_in(&self.pool, id)
+        })
+        .await
+    }
+
+    /// Like [`Self::find_by_id`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::find_by_id`]
+    pub async fn find_by_id_in(
+        &self,
+        executor: impl PgExecutor<'_>,
+        id: Uuid,
+    ) -> Result<Option<AttendanceEvent>> {
         let event = sqlx::query_as!(
             AttendanceEvent,
             r#"
-            SELECT id, user_id, event_type, event_time, recorded_at, created_at
+            SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                   latitude, longitude, client_ip, user_agent, device_id, amends_event_id
             FROM attendance_events
             WHERE id = $1
             "#,
             id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(event)
     }
 
-    /// Find all attendance events for a specific user
+    /// Find the attendance events of a specific user
     /// Returns events ordered by `event_time` in descending order (most recent first)
     ///
     /// # Arguments
     /// * `user_id` - The UUID of the user
+    /// * `include_history` - Also return events that have been superseded by an
+    ///   amendment; otherwise only effective events are returned
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceEvent>)` - List of events (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_user_id(
+        &self,
+        user_id: Uuid,
+        include_history: bool,
+    ) -> Result<Vec<AttendanceEvent>> {
+        retry::read("attendance_events.find_by_user_id", || async move {
+            let events = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                       latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+                FROM attendance_events
+                WHERE user_id = $1
+                  AND ($2 OR NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  ))
+                ORDER BY event_time DESC
+                "#,
+                user_id,
+                include_history
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok(events)
+        })
+        .await
+    }
+
+    /// List a page of the attendance events of a specific user
+    /// Returns events ordered by `event_time` in descending order (most recent first)
+    ///
+    /// # Arguments
+    /// * `user_id` - The UUID of the user
+    /// * `include_history` - Also return events that have been superseded by an
+    ///   amendment; otherwise only effective events are returned
+    /// * `limit` - Maximum number of events to return
+    /// * `offset` - Number of events to skip
+    ///
+    /// # Returns
+    /// * `Ok((Vec<AttendanceEvent>, i64))` - The page of events and the total number of events
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_page_by_user_id(
+        &self,
+        user_id: Uuid,
+        include_history: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AttendanceEvent>, i64)> {
+        retry::read("attendance_events.find_page_by_user_id", || async move {
+            let total = sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) as "count!"
+                FROM attendance_events
+                WHERE user_id = $1
+                  AND ($2 OR NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  ))
+                "#,
+                user_id,
+                include_history
+            )
+            .fetch_one(self.read_pool.get())
+            .await?;
+
+            let events = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                       latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+                FROM attendance_events
+                WHERE user_id = $1
+                  AND ($2 OR NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  ))
+                ORDER BY event_time DESC, id
+                LIMIT $3 OFFSET $4
+                "#,
+                user_id,
+                include_history,
+                limit,
+                offset
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok((events, total))
+        })
+        .await
+    }
+
+    /// Find the latest effective attendance event of a user at or before a given time
+    ///
+    /// # Arguments
+    /// * `user_id` - The UUID of the user
+    /// * `at` - Upper bound (inclusive) of `event_time`
+    /// * `excluding` - Event to ignore, e.g. the one about to be superseded
+    ///
+    /// # Returns
+    /// * `Ok(Some(AttendanceEvent))` - The most recent event at or before `at`
+    /// * `Ok(None)` - The user has no events before `at`
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_latest_before(
+        &self,
+        user_id: Uuid,
+        at: DateTime<Utc>,
+        excluding: Option<Uuid>,
+    ) -> Result<Option<AttendanceEvent>> {
+        retry::read("attendance_events.find_latest_before", || async move {
+            let event = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                       latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+                FROM attendance_events
+                WHERE user_id = $1 AND event_time <= $2
+                  AND ($3::uuid IS NULL OR id <> $3)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  )
+                ORDER BY event_time DESC
+                LIMIT 1
+                "#,
+                user_id,
+                at,
+                excluding
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(event)
+        })
+        .await
+    }
+
+    /// Find the effective attendance events of a user within a time range
+    /// Returns events with `from <= event_time < to`, ordered by `event_time` ascending;
+    /// events superseded by an amendment are left out
+    /// Reads the primary, as anomaly detection checks events just recorded
+    ///
+    /// # Arguments
+    /// * `user_id` - The UUID of the user
+    /// * `from` - Inclusive lower bound of `event_time`
+    /// * `to` - Exclusive upper bound of `event_time`
     ///
     /// # Returns
     /// * `Ok(Vec<AttendanceEvent>)` - List of events (may be empty)
     ///
+    /// The query is cancelled if the request is dropped (see [`CancelOnDrop`])
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_user_id_in_range(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AttendanceEvent>> {
+        retry::read("attendance_events.find_by_user_id_in_range", || async move {
+            let mut conn = CancelOnDrop::acquire(&self.pool).await?;
+            let events = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                       latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+                FROM attendance_events
+                WHERE user_id = $1 AND event_time >= $2 AND event_time < $3
+                  AND NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  )
+                ORDER BY event_time ASC
+                "#,
+                user_id,
+                from,
+                to
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+            conn.finish();
+
+            Ok(events)
+        })
+        .await
+    }
+
+    /// Find the effective attendance events of all users within a time range
+    /// Returns events with `from <= event_time < to`, ordered by user and then by
+    /// `event_time` ascending; events superseded by an amendment are left out
+    ///
+    /// # Arguments
+    /// * `from` - Inclusive lower bound of `event_time`
+    /// * `to` - Exclusive upper bound of `event_time`
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceEvent>)` - List of events (may be empty)
+    ///
+    /// The query is cancelled if the request is dropped (see [`CancelOnDrop`])
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_all_in_range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<AttendanceEvent>> {
+        retry::read("attendance_events.find_all_in_range", || async move {
+            let mut conn = CancelOnDrop::acquire(self.read_pool.get()).await?;
+            let events = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                       latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+                FROM attendance_events
+                WHERE event_time >= $1 AND event_time < $2
+                  AND NOT EXISTS (
+                      SELECT 1 FROM attendance_events amendment
+                      WHERE amendment.amends_event_id = attendance_events.id
+                  )
+                ORDER BY user_id, event_time ASC
+                "#,
+                from,
+                to
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+            conn.finish();
+
+            Ok(events)
+        })
+        .await
+    }
+
+    /// Find the attendance activity of several users within a time window
+    /// Only effective events count. Uses a single query for all users. Only active (not deleted) users are
+    /// returned, in the order of `user_ids`; duplicate IDs are returned once.
+    /// Reads the primary, as presence is published right after an event is recorded.
+    ///
+    /// # Arguments
+    /// * `user_ids` - The UUIDs of the users
+    /// * `from` - Inclusive lower bound of `event_time`
+    /// * `to` - Exclusive upper bound of `event_time`
+    ///
+    /// # Returns
+    /// * `Ok(Vec<UserDayActivity>)` - One entry per active user
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_activity_for_users(
+        &self,
+        user_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<UserDayActivity>> {
+        retry::read("attendance_events.find_activity_for_users", || async move {
+            let activity = sqlx::query_as!(
+                UserDayActivity,
+                r#"
+                SELECT u.id as "user_id!",
+                       latest.event_type as "latest_event_type?: EventType",
+                       latest.event_time as "latest_event_time?",
+                       first_in.event_time as "first_clock_in?"
+                FROM (
+                    SELECT id, MIN(ordinality) AS position
+                    FROM UNNEST($1::uuid[]) WITH ORDINALITY AS requested(id, ordinality)
+                    GROUP BY id
+                ) requested
+                JOIN users u ON u.id = requested.id AND u.deleted_at IS NULL
+                LEFT JOIN LATERAL (
+                    SELECT e.event_type, e.event_time
+                    FROM attendance_events e
+                    WHERE e.user_id = u.id AND e.event_time >= $2 AND e.event_time < $3
+                      AND NOT EXISTS (
+                          SELECT 1 FROM attendance_events amendment
+                          WHERE amendment.amends_event_id = e.id
+                      )
+                    ORDER BY e.event_time DESC
+                    LIMIT 1
+                ) latest ON TRUE
+                LEFT JOIN LATERAL (
+                    SELECT MIN(e.event_time) AS event_time
+                    FROM attendance_events e
+                    WHERE e.user_id = u.id AND e.event_type = 'clock_in'
+                      AND e.event_time >= $2 AND e.event_time < $3
+                      AND NOT EXISTS (
+                          SELECT 1 FROM attendance_events amendment
+                          WHERE amendment.amends_event_id = e.id
+                      )
+                ) first_in ON TRUE
+                ORDER BY requested.position
+                "#,
+                user_ids,
+                from,
+                to
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(activity)
+        })
+        .await
+    }
+
+    /// Find the latest effective attendance events of several users
+    /// Uses a single query for all users (ranked with a window function) instead of
+    /// one query per user. Events are grouped by user in the order of `user_ids`
+    /// (duplicate IDs are returned once), most recent first within each user;
+    /// events superseded by an amendment are left out
+    ///
+    /// # Arguments
+    /// * `user_ids` - The UUIDs of the users
+    /// * `per_user_limit` - Maximum number of events per user
+    ///
+    /// # Returns
+    /// * `Ok(Vec<AttendanceEvent>)` - Up to `per_user_limit` events per user (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
     /// # Errors
     /// Returns `AppError` if database query fails
-    pub async fn find_by_user_id(&self, user_id: Uuid) -> Result<Vec<AttendanceEvent>> {
-        let events = sqlx::query_as!(
+    pub async fn find_latest_for_users(
+        &self,
+        user_ids: &[Uuid],
+        per_user_limit: i64,
+    ) -> Result<Vec<AttendanceEvent>> {
+        retry::read("attendance_events.find_latest_for_users", || async move {
+            if user_ids.is_empty() || per_user_limit < 1 {
+                return Ok(Vec::new());
+            }
+
+            let events = sqlx::query_as!(
+                AttendanceEvent,
+                r#"
+                SELECT ranked.id as "id!", ranked.user_id as "user_id!",
+                       ranked.event_type as "event_type!: EventType", ranked.event_time as "event_time!",
+                       ranked.recorded_at as "recorded_at!", ranked.created_at as "created_at!",
+                       ranked.latitude, ranked.longitude, ranked.client_ip, ranked.user_agent,
+                       ranked.device_id, ranked.amends_event_id
+                FROM (
+                    SELECT e.*,
+                           ROW_NUMBER() OVER (
+                               PARTITION BY e.user_id
+                               ORDER BY e.event_time DESC, e.recorded_at DESC
+                           ) AS rank
+                    FROM attendance_events e
+                    WHERE e.user_id = ANY($1)
+                      AND NOT EXISTS (
+                          SELECT 1 FROM attendance_events amendment
+                          WHERE amendment.amends_event_id = e.id
+                      )
+                ) ranked
+                JOIN (
+                    SELECT id, MIN(ordinality) AS position
+                    FROM UNNEST($1::uuid[]) WITH ORDINALITY AS requested(id, ordinality)
+                    GROUP BY id
+                ) requested ON requested.id = ranked.user_id
+                WHERE ranked.rank <= $2
+                ORDER BY requested.position, ranked.rank
+                "#,
+                user_ids,
+                per_user_limit
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(events)
+        })
+        .await
+    }
+
+    /// Stream the attendance events of all users, ordered by `event_time` ascending
+    /// Rows are fetched as the stream is polled, so exports of any size run in
+    /// constant memory. Superseded events are left out unless `include_history` is set
+    ///
+    /// # Arguments
+    /// * `user_id` - Only stream events of this user (if provided)
+    /// * `from` - Inclusive lower bound of `event_time` (if provided)
+    /// * `to` - Exclusive upper bound of `event_time` (if provided)
+    /// * `include_history` - Also stream events superseded by an amendment
+    ///
+    /// # Returns
+    /// A stream of events; each item is `Err(AppError)` if fetching the row fails
+    pub fn stream_all(
+        &self,
+        user_id: Option<Uuid>,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        include_history: bool,
+    ) -> impl Stream<Item = Result<AttendanceEvent>> + Send + '_ {
+        sqlx::query_as!(
             AttendanceEvent,
             r#"
-            SELECT id, user_id, event_type, event_time, recorded_at, created_at
+            SELECT id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                   latitude, longitude, client_ip, user_agent, device_id, amends_event_id
             FROM attendance_events
-            WHERE user_id = $1
-            ORDER BY event_time DESC
+            WHERE ($1::uuid IS NULL OR user_id = $1)
+              AND ($2::timestamptz IS NULL OR event_time >= $2)
+              AND ($3::timestamptz IS NULL OR event_time < $3)
+              AND ($4 OR NOT EXISTS (
+                  SELECT 1 FROM attendance_events amendment
+                  WHERE amendment.amends_event_id = attendance_events.id
+              ))
+            ORDER BY event_time ASC, id ASC
             "#,
-            user_id
+            user_id,
+            from,
+            to,
+            include_history
         )
-        .fetch_all(&self.pool)
-        .await?;
-
-        Ok(events)
+        .fetch(self.read_pool.get())
+        .map_err(AppError::from)
     }
 
     /// Create a new attendance event
-    /// The `recorded_at` timestamp is set to the current server time automatically
+    /// The `recorded_at` timestamp is set to the current server time automatically.
+    /// If `amends_event_id` is set, the new event supersedes that event.
     ///
     /// # Arguments
     /// * `event` - The attendance event creation request data
@@ -84,25 +537,240 @@ impl AttendanceEventRepository {
     /// * `Ok(AttendanceEvent)` - The created event with generated ID and timestamps
     ///
     /// # Errors
+    /// Returns `AppError::NotFound` if the referenced user or amended event does not exist
+    /// Returns `AppError::ValidationError` if the database rejects the event type, or the
+    /// amended event belongs to another user
+    /// Returns `AppError::BadRequest` if the amended event has already been superseded
+    /// Returns `AppError::Forbidden` if the user is deactivated
     /// Returns `AppError` if database query fails
     pub async fn create(&self, event: CreateAttendanceEvent) -> Result<AttendanceEvent> {
-        let recorded_at = Utc::now();
+        let mut conn = self.pool.acquire().await?;
+        self.create_in(&mut conn, event).await
+    }
 
-        let created_event = sqlx::query_as!(
-            AttendanceEvent,
+    /// Like [`Self::create`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::create`]
+    pub async fn create_in(
+        &self,
+        conn: &mut PgConnection,
+        event: CreateAttendanceEvent,
+    ) -> Result<AttendanceEvent> {
+        insert_event(conn, &event).await
+    }
+
+    /// Create an attendance event with a kiosk token, marking the token as used
+    /// Both happen in a single transaction, so a token records at most one event
+    ///
+    /// # Arguments
+    /// * `event` - The attendance event creation request data
+    /// * `jti` - The token id
+    /// * `expires_at` - The token expiry
+    ///
+    /// # Returns
+    /// * `Ok(AttendanceEvent)` - The created event
+    ///
+    /// # Errors
+    /// Returns `AppError::BadRequest` if the token has already been used
+    /// Returns `AppError::NotFound` if the referenced user does not exist
+    /// Returns `AppError::Forbidden` if the user is deactivated
+    /// Returns `AppError` if database query fails
+    pub async fn create_with_kiosk_token(
+        &self,
+        event: CreateAttendanceEvent,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<AttendanceEvent> {
+        let mut tx = self.pool.begin().await?;
+
+        let created_event = insert_event(&mut tx, &event).await?;
+
+        sqlx::query!(
             r#"
-            INSERT INTO attendance_events (user_id, event_type, event_time, recorded_at)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, user_id, event_type, event_time, recorded_at, created_at
+            INSERT INTO kiosk_token_uses (jti, expires_at, event_id)
+            VALUES ($1, $2, $3)
             "#,
-            event.user_id,
-            event.event_type,
-            event.event_time,
-            recorded_at
+            jti,
+            expires_at,
+            created_event.id
         )
-        .fetch_one(&self.pool)
-        .await?;
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::BadRequest("Kiosk token has already been used".to_string())
+                    .with_code(ErrorCode::KioskTokenAlreadyUsed)
+            }
+            e => e.into(),
+        })?;
+
+        tx.commit().await?;
 
         Ok(created_event)
     }
+
+    /// Create many attendance events at once
+    /// All events are inserted by a single statement, so either every event is
+    /// stored or none is. `recorded_at` is set to the current server time.
+    /// Bulk inserts record original events only; `amends_event_id` is ignored.
+    ///
+    /// # Arguments
+    /// * `events` - The attendance events to create
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of created events
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if any referenced user does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn create_many(&self, events: &[CreateAttendanceEvent]) -> Result<u64> {
+        let user_ids: Vec<Uuid> = events.iter().map(|e| e.user_id).collect();
+        let event_types: Vec<String> = events
+            .iter()
+            .map(|e| e.event_type.as_str().to_string())
+            .collect();
+        let event_times: Vec<DateTime<Utc>> = events.iter().map(|e| e.event_time).collect();
+        let latitudes: Vec<Option<f64>> = events.iter().map(|e| e.latitude).collect();
+        let longitudes: Vec<Option<f64>> = events.iter().map(|e| e.longitude).collect();
+        let client_ips: Vec<Option<String>> = events.iter().map(|e| e.client_ip.clone()).collect();
+        let user_agents: Vec<Option<String>> =
+            events.iter().map(|e| e.user_agent.clone()).collect();
+        let device_ids: Vec<Option<String>> = events.iter().map(|e| e.device_id.clone()).collect();
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO attendance_events
+                (user_id, event_type, event_time, recorded_at, latitude, longitude,
+                 client_ip, user_agent, device_id)
+            SELECT user_id, event_type, event_time, $4, latitude, longitude,
+                   client_ip, user_agent, device_id
+            FROM UNNEST(
+                $1::uuid[], $2::varchar[], $3::timestamptz[], $5::float8[], $6::float8[],
+                $7::varchar[], $8::varchar[], $9::varchar[]
+            ) AS t(user_id, event_type, event_time, latitude, longitude,
+                   client_ip, user_agent, device_id)
+            "#,
+            &user_ids,
+            &event_types,
+            &event_times,
+            Utc::now(),
+            &latitudes as &[Option<f64>],
+            &longitudes as &[Option<f64>],
+            &client_ips as &[Option<String>],
+            &user_agents as &[Option<String>],
+            &device_ids as &[Option<String>]
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => {
+                AppError::NotFound("One or more users not found".to_string())
+            }
+            e => e.into(),
+        })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Insert an attendance event on a connection (or transaction)
+/// The `recorded_at` timestamp is set to the current server time; deactivated
+/// users cannot record events
+async fn insert_event(
+    conn: &mut PgConnection,
+    event: &CreateAttendanceEvent,
+) -> Result<AttendanceEvent> {
+    let deactivated = sqlx::query_scalar!(
+        r#"
+        SELECT NOT is_active as "deactivated!"
+        FROM users
+        WHERE id = $1
+        "#,
+        event.user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if deactivated == Some(true) {
+        return Err(AppError::Forbidden(format!(
+            "User with id {} is deactivated",
+            event.user_id
+        )));
+    }
+
+    if let Some(amended_id) = event.amends_event_id {
+        let amended_user_id = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM attendance_events
+            WHERE id = $1
+            "#,
+            amended_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Attendance event with id {amended_id} not found"))
+        })?;
+
+        if amended_user_id != event.user_id {
+            return Err(AppError::ValidationError(
+                "An event can only amend an event of the same user".to_string(),
+            ));
+        }
+    }
+
+    let recorded_at = Utc::now();
+
+    let created_event = sqlx::query_as!(
+        AttendanceEvent,
+        r#"
+        INSERT INTO attendance_events
+            (user_id, event_type, event_time, recorded_at, latitude, longitude,
+             client_ip, user_agent, device_id, amends_event_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, user_id, event_type as "event_type: EventType", event_time, recorded_at, created_at,
+                  latitude, longitude, client_ip, user_agent, device_id, amends_event_id
+        "#,
+        event.user_id,
+        event.event_type.as_str(),
+        event.event_time,
+        recorded_at,
+        event.latitude,
+        event.longitude,
+        event.client_ip,
+        event.user_agent,
+        event.device_id,
+        event.amends_event_id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => {
+            AppError::NotFound(match db_err.constraint() {
+                Some("fk_attendance_events_amends_event_id") => format!(
+                    "Attendance event with id {} not found",
+                    event.amends_event_id.unwrap_or_default()
+                ),
+                _ => format!("User with id {} not found", event.user_id),
+            })
+        }
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::BadRequest(format!(
+                "Attendance event with id {} has already been amended",
+                event.amends_event_id.unwrap_or_default()
+            ))
+        }
+        sqlx::Error::Database(db_err) if db_err.is_check_violation() => {
+            AppError::ValidationError(match db_err.constraint() {
+                Some("chk_attendance_events_event_type") => {
+                    format!("Invalid event type: {}", event.event_type)
+                }
+                _ => "Invalid location".to_string(),
+            })
+        }
+        e => e.into(),
+    })?;
+
+    Ok(created_event)
 }
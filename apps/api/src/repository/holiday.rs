@@ -0,0 +1,283 @@
+use crate::db::ReadPool;
+use crate::error::{AppError, Result};
+use crate::models::{CreateHoliday, Holiday, UpdateHoliday};
+use crate::repository::retry;
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Holiday repository for database operations
+/// Handles CRUD operations for the company-wide holiday calendar
+#[derive(Clone)]
+pub struct HolidayRepository {
+    pool: PgPool,
+    /// Lists, reports and exports may read from a replica
+    read_pool: ReadPool,
+}
+
+impl HolidayRepository {
+    /// Create a new `HolidayRepository` instance
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            read_pool: ReadPool::primary(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Run lists, reports and exports on `read_pool`
+    #[must_use]
+    pub fn with_read_pool(self, read_pool: ReadPool) -> Self {
+        Self { read_pool, ..self }
+    }
+
+    /// List holidays within a date range, ordered by date
+    ///
+    /// # Arguments
+    /// * `from` - First day of the range (inclusive)
+    /// * `to` - Last day of the range (exclusive)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Holiday>)` - List of holidays (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Holiday>> {
+        retry::read("holidays.list", || async move {
+            let holidays = sqlx::query_as!(
+                Holiday,
+                r#"
+                SELECT id, date, name, created_at, updated_at
+                FROM holidays
+                WHERE date >= $1 AND date < $2
+                ORDER BY date
+                "#,
+                from,
+                to
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok(holidays)
+        })
+        .await
+    }
+
+    /// Collect the holiday dates within a date range
+    ///
+    /// # Arguments
+    /// * `from` - First day of the range (inclusive)
+    /// * `to` - Last day of the range (exclusive)
+    ///
+    /// # Returns
+    /// * `Ok(HashSet<NaiveDate>)` - Dates that are holidays
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_dates_in_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<HashSet<NaiveDate>> {
+        retry::read("holidays.find_dates_in_range", || async move {
+            let dates = sqlx::query_scalar!(
+                r#"
+                SELECT date
+                FROM holidays
+                WHERE date >= $1 AND date < $2
+                "#,
+                from,
+                to
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok(dates.into_iter().collect())
+        })
+        .await
+    }
+
+    /// Find a holiday by ID
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the holiday
+    ///
+    /// # Returns
+    /// * `Ok(Some(Holiday))` - Holiday found
+    /// * `Ok(None)` - Holiday not found
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Holiday>> {
+        retry::read("holidays.find_by_id", || async move {
+            let holiday = sqlx::query_as!(
+                Holiday,
+                r#"
+                SELECT id, date, name, created_at, updated_at
+                FROM holidays
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(holiday)
+        })
+        .await
+    }
+
+    /// Create a new holiday
+    ///
+    /// # Arguments
+    /// * `holiday` - The holiday creation request data
+    ///
+    /// # Returns
+    /// * `Ok(Holiday)` - The created holiday with generated ID and timestamps
+    ///
+    /// # Errors
+    /// Returns `AppError::BadRequest` if a holiday already exists on the date
+    /// Returns `AppError` if database query fails
+    pub async fn create(&self, holiday: CreateHoliday) -> Result<Holiday> {
+        let created = sqlx::query_as!(
+            Holiday,
+            r#"
+            INSERT INTO holidays (date, name)
+            VALUES ($1, $2)
+            RETURNING id, date, name, created_at, updated_at
+            "#,
+            holiday.date,
+            holiday.name
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| map_unique_violation(e, &[holiday.date]))?;
+
+        Ok(created)
+    }
+
+    /// Create several holidays at once (e.g. a whole year's calendar)
+    /// Either all holidays are created or none
+    ///
+    /// # Arguments
+    /// * `holidays` - The holidays to create; dates must be distinct
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Holiday>)` - The created holidays, ordered by date
+    ///
+    /// # Errors
+    /// Returns `AppError::BadRequest` if a holiday already exists on one of the dates
+    /// Returns `AppError` if database query fails
+    pub async fn create_many(&self, holidays: &[CreateHoliday]) -> Result<Vec<Holiday>> {
+        let dates: Vec<NaiveDate> = holidays.iter().map(|h| h.date).collect();
+        let names: Vec<String> = holidays.iter().map(|h| h.name.clone()).collect();
+
+        let mut created = sqlx::query_as!(
+            Holiday,
+            r#"
+            INSERT INTO holidays (date, name)
+            SELECT * FROM UNNEST($1::date[], $2::varchar[])
+            RETURNING id, date, name, created_at, updated_at
+            "#,
+            &dates,
+            &names
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| map_unique_violation(e, &dates))?;
+
+        created.sort_by_key(|holiday| holiday.date);
+        tracing::info!(count = created.len(), "Created holidays");
+        Ok(created)
+    }
+
+    /// Update an existing holiday
+    /// Only updates fields that are provided (Some) in the `UpdateHoliday` struct
+    /// Automatically updates the `updated_at` timestamp
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the holiday to update
+    /// * `holiday` - The holiday update request data with optional fields
+    ///
+    /// # Returns
+    /// * `Ok(Holiday)` - The updated holiday
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the holiday does not exist
+    /// Returns `AppError::BadRequest` if another holiday already exists on the new date
+    /// Returns `AppError` if database query fails
+    pub async fn update(&self, id: Uuid, holiday: UpdateHoliday) -> Result<Holiday> {
+        let updated = sqlx::query_as!(
+            Holiday,
+            r#"
+            UPDATE holidays
+            SET
+                date = COALESCE($2, date),
+                name = COALESCE($3, name),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, date, name, created_at, updated_at
+            "#,
+            id,
+            holiday.date,
+            holiday.name
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| map_unique_violation(e, holiday.date.as_slice()))?;
+
+        updated.ok_or_else(|| AppError::NotFound(format!("Holiday with id {id} not found")))
+    }
+
+    /// Delete a holiday
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the holiday to delete
+    ///
+    /// # Returns
+    /// * `Ok(())` - Holiday successfully deleted
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the holiday does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM holidays
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Holiday with id {id} not found"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Map a violation of the one-holiday-per-day constraint to a bad request
+fn map_unique_violation(err: sqlx::Error, dates: &[NaiveDate]) -> AppError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            let dates: Vec<String> = dates.iter().map(ToString::to_string).collect();
+            AppError::BadRequest(format!(
+                "A holiday already exists on one of the dates: {}",
+                dates.join(", ")
+            ))
+        }
+        err => err.into(),
+    }
+}
@@ -0,0 +1,171 @@
+use crate::error::Result;
+use crate::models::{IdempotencyRecord, StoredResponse};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Result of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyClaim {
+    /// The key was unused (or expired); the request should be processed and
+    /// its response stored under this id
+    Claimed(Uuid),
+    /// The key is in use by an earlier request
+    Existing(IdempotencyRecord),
+}
+
+/// Repository for idempotency key database operations
+#[derive(Clone)]
+pub struct IdempotencyKeyRepository {
+    pool: PgPool,
+}
+
+impl IdempotencyKeyRepository {
+    /// Create a new `IdempotencyKeyRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Claim an idempotency key for a request
+    /// An expired key is taken over under a new id, and the expired keys of the
+    /// scope are removed at the same time. The claim expires after `lease`
+    /// unless [`Self::complete`] stores a response, so a request that never
+    /// finishes does not hold the key.
+    ///
+    /// # Arguments
+    /// * `scope_hash` - Hash of the request credentials
+    /// * `key` - The `Idempotency-Key` header
+    /// * `request_hash` - Hash of the request
+    /// * `now` - Current time
+    /// * `lease` - How long the key is held while the request is processed
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn claim(
+        &self,
+        scope_hash: &str,
+        key: &str,
+        request_hash: &str,
+        now: DateTime<Utc>,
+        lease: Duration,
+    ) -> Result<IdempotencyClaim> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE scope_hash = $1 AND expires_at <= $2 AND idempotency_key <> $3
+            "#,
+            scope_hash,
+            now,
+            key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (scope_hash, idempotency_key, request_hash, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (scope_hash, idempotency_key) DO UPDATE
+            SET id = gen_random_uuid(),
+                request_hash = EXCLUDED.request_hash,
+                status_code = NULL,
+                header_names = NULL,
+                header_values = NULL,
+                body = NULL,
+                created_at = EXCLUDED.created_at,
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= EXCLUDED.created_at
+            RETURNING id
+            "#,
+            scope_hash,
+            key,
+            request_hash,
+            now,
+            now + lease
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let claim = if let Some(id) = claimed {
+            IdempotencyClaim::Claimed(id)
+        } else {
+            let row = sqlx::query!(
+                r#"
+                SELECT request_hash, status_code, header_names, header_values, body
+                FROM idempotency_keys
+                WHERE scope_hash = $1 AND idempotency_key = $2
+                "#,
+                scope_hash,
+                key
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let response = row.status_code.map(|status_code| StoredResponse {
+                status_code,
+                header_names: row.header_names.unwrap_or_default(),
+                header_values: row.header_values.unwrap_or_default(),
+                body: row.body.unwrap_or_default(),
+            });
+            IdempotencyClaim::Existing(IdempotencyRecord {
+                request_hash: row.request_hash,
+                response,
+            })
+        };
+
+        tx.commit().await?;
+        Ok(claim)
+    }
+
+    /// Store the response of a claimed key, replayed until `expires_at`
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn complete(
+        &self,
+        id: Uuid,
+        response: &StoredResponse,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $2, header_names = $3, header_values = $4, body = $5,
+                expires_at = $6
+            WHERE id = $1
+            "#,
+            id,
+            response.status_code,
+            &response.header_names,
+            &response.header_values,
+            &response.body,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Release a claimed key without storing a response, so a retry is
+    /// processed again
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn release(&self, id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
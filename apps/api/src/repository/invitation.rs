@@ -0,0 +1,160 @@
+use crate::error::{AppError, ErrorCode, Result};
+use crate::models::{
+    AcceptInvitation, CreateInvitation, Invitation, User, UserRole, normalize_email,
+};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Repository for invitation database operations
+#[derive(Clone)]
+pub struct InvitationRepository {
+    pool: PgPool,
+}
+
+impl InvitationRepository {
+    /// Create a new `InvitationRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create an invitation
+    /// A pending invitation for the same email is replaced, so its token stops working.
+    /// The email address is stored normalized (see [`normalize_email`]).
+    ///
+    /// # Arguments
+    /// * `invitation` - The invitation creation request data
+    ///
+    /// # Returns
+    /// * `Ok(Invitation)` - The created invitation
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if an active user already uses the email address
+    /// Returns `AppError` if database query fails
+    pub async fn create(&self, invitation: CreateInvitation) -> Result<Invitation> {
+        let email = normalize_email(&invitation.email);
+        let mut tx = self.pool.begin().await?;
+
+        let registered = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM users WHERE lower(email) = $1 AND deleted_at IS NULL
+            ) as "exists!"
+            "#,
+            email
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if registered {
+            return Err(
+                AppError::Conflict(format!("A user with email {email} already exists"))
+                    .with_code(ErrorCode::EmailAlreadyExists),
+            );
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM invitations
+            WHERE lower(email) = $1 AND accepted_at IS NULL
+            "#,
+            email
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let created = sqlx::query_as!(
+            Invitation,
+            r#"
+            INSERT INTO invitations (email, role, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, email, role as "role: UserRole", expires_at, accepted_at,
+                      user_id, created_at
+            "#,
+            email,
+            invitation.role as UserRole,
+            invitation.token_hash,
+            invitation.expires_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    /// Accept a pending invitation, creating its user
+    ///
+    /// # Arguments
+    /// * `accept` - The token hash and the profile of the new user
+    /// * `now` - Current time, compared with the expiry
+    ///
+    /// # Returns
+    /// * `Ok(User)` - The created user
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no pending invitation has the token
+    /// Returns `AppError::BadRequest` if the invitation has expired
+    /// Returns `AppError::Conflict` if an active user already uses the email address
+    /// Returns `AppError` if database query fails
+    pub async fn accept(&self, accept: AcceptInvitation, now: DateTime<Utc>) -> Result<User> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the invitation keeps it from being accepted twice at once
+        let invitation = sqlx::query!(
+            r#"
+            SELECT id, email, role as "role: UserRole", expires_at
+            FROM invitations
+            WHERE token_hash = $1 AND accepted_at IS NULL
+            FOR UPDATE
+            "#,
+            accept.token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Invitation not found".to_string()))?;
+
+        if invitation.expires_at <= now {
+            return Err(AppError::BadRequest("Invitation has expired".to_string()));
+        }
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            INSERT INTO users (name, email, picture, role)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+            "#,
+            accept.name,
+            invitation.email,
+            accept.picture,
+            invitation.role as UserRole
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => AppError::Conflict(
+                format!("A user with email {} already exists", invitation.email),
+            ).with_code(ErrorCode::EmailAlreadyExists),
+            e => e.into(),
+        })?;
+
+        sqlx::query!(
+            r#"
+            UPDATE invitations
+            SET accepted_at = $2, user_id = $3
+            WHERE id = $1
+            "#,
+            invitation.id,
+            now,
+            user.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(invitation_id = %invitation.id, user_id = %user.id, "Invitation accepted");
+        Ok(user)
+    }
+}
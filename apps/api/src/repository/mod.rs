@@ -1,5 +1,28 @@
+pub mod api_key;
+pub mod attendance_anomaly;
+pub mod attendance_correction;
 pub mod attendance_event;
+pub mod holiday;
+pub mod idempotency_key;
+pub mod invitation;
+pub mod password_reset;
+pub mod permission;
+pub mod retry;
+pub mod session;
 pub mod user;
+pub mod webhook;
+pub mod work_policy;
 
+pub use api_key::ApiKeyRepository;
+pub use attendance_anomaly::AttendanceAnomalyRepository;
+pub use attendance_correction::AttendanceCorrectionRepository;
 pub use attendance_event::AttendanceEventRepository;
+pub use holiday::HolidayRepository;
+pub use idempotency_key::{IdempotencyClaim, IdempotencyKeyRepository};
+pub use invitation::InvitationRepository;
+pub use password_reset::PasswordResetRepository;
+pub use permission::PermissionRepository;
+pub use session::SessionRepository;
 pub use user::UserRepository;
+pub use webhook::WebhookRepository;
+pub use work_policy::WorkPolicyRepository;
@@ -0,0 +1,192 @@
+use crate::error::{AppError, Result};
+use crate::models::normalize_email;
+use crate::password;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+/// Recipient of a newly issued password reset token
+#[derive(Debug, Clone)]
+pub struct ResetRecipient {
+    pub email: String,
+    pub name: String,
+}
+
+/// Repository for password reset token database operations
+#[derive(Clone)]
+pub struct PasswordResetRepository {
+    pool: PgPool,
+}
+
+impl PasswordResetRepository {
+    /// Create a new `PasswordResetRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Issue a reset token for the active user with an email address
+    ///
+    /// # Arguments
+    /// * `email` - The email address the reset was requested for
+    /// * `token_hash` - Hash of the token to send (see `token::hash_opaque`)
+    /// * `now` - Current time
+    /// * `ttl` - How long the token is valid
+    /// * `max_per_hour` - Most tokens issued to one user within an hour
+    ///
+    /// # Returns
+    /// * `Ok(Some(ResetRecipient))` - The token was stored and should be sent
+    /// * `Ok(None)` - No active user has the email, the user is deactivated, or
+    ///   the user was sent too many tokens within the last hour
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn issue(
+        &self,
+        email: &str,
+        token_hash: &str,
+        now: DateTime<Utc>,
+        ttl: Duration,
+        max_per_hour: i64,
+    ) -> Result<Option<ResetRecipient>> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the user serializes concurrent requests, so the limit holds
+        let Some(user) = sqlx::query!(
+            r#"
+            SELECT id, name, email
+            FROM users
+            WHERE lower(email) = $1 AND deleted_at IS NULL AND is_active
+            FOR UPDATE
+            "#,
+            normalize_email(email)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let recent = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM password_reset_tokens
+            WHERE user_id = $1 AND created_at > $2
+            "#,
+            user.id,
+            now - Duration::hours(1)
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if recent >= max_per_hour {
+            tracing::warn!(user_id = %user.id, "Password reset rate limit reached");
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO password_reset_tokens (user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user.id,
+            token_hash,
+            now + ttl,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(user_id = %user.id, "Password reset token issued");
+        Ok(Some(ResetRecipient {
+            email: user.email,
+            name: user.name,
+        }))
+    }
+
+    /// Set a new password with a reset token
+    /// The token and every other unused token of the user stop working, the
+    /// user is logged out of all cookie sessions, and an account locked after
+    /// failed logins is unlocked.
+    ///
+    /// # Arguments
+    /// * `token_hash` - Hash of the presented token
+    /// * `new_password` - The new plain-text password, stored as an Argon2 hash
+    /// * `now` - Current time, compared with the expiry
+    ///
+    /// # Errors
+    /// Returns `AppError::BadRequest` if the token is unknown, used or expired
+    /// Returns `AppError` if hashing or the database query fails
+    pub async fn reset(
+        &self,
+        token_hash: &str,
+        new_password: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let invalid = || AppError::BadRequest("Reset token is invalid or has expired".to_string());
+
+        // Hash before locking the token, so the lock is held briefly
+        let new_hash = password::hash_async(new_password.to_string()).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the token keeps it from being used twice at once
+        let token = sqlx::query!(
+            r#"
+            SELECT t.user_id, t.expires_at
+            FROM password_reset_tokens t
+            JOIN users u ON u.id = t.user_id
+            WHERE t.token_hash = $1 AND t.used_at IS NULL AND u.deleted_at IS NULL
+            FOR UPDATE OF t
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(invalid)?;
+
+        if token.expires_at <= now {
+            return Err(invalid());
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET password_hash = $2, failed_login_attempts = 0, locked_until = NULL,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            "#,
+            token.user_id,
+            new_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = $2
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+            token.user_id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM sessions
+            WHERE user_id = $1
+            "#,
+            token.user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(user_id = %token.user_id, "Password reset");
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::Result;
+use crate::models::{Permission, UserRole};
+use crate::repository::retry;
+use sqlx::PgPool;
+
+/// Repository for the permissions granted to each role
+#[derive(Clone)]
+pub struct PermissionRepository {
+    pool: PgPool,
+}
+
+impl PermissionRepository {
+    /// Create a new `PermissionRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Check whether a role is granted a permission
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn is_granted(&self, role: UserRole, permission: Permission) -> Result<bool> {
+        retry::read("role_permissions.is_granted", || async move {
+            let granted = sqlx::query_scalar!(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM role_permissions WHERE role = $1 AND permission = $2
+                ) as "granted!"
+                "#,
+                role.as_str(),
+                permission.as_str()
+            )
+            .fetch_one(&self.pool)
+            .await?;
+
+            Ok(granted)
+        })
+        .await
+    }
+
+    /// List the permissions granted to a role, in the order of [`Permission::ALL`]
+    /// Permissions no longer known are left out.
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list_for_role(&self, role: UserRole) -> Result<Vec<Permission>> {
+        retry::read("role_permissions.list_for_role", || async move {
+            let granted = sqlx::query_scalar!(
+                r#"
+                SELECT permission
+                FROM role_permissions
+                WHERE role = $1
+                "#,
+                role.as_str()
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(Permission::ALL
+                .into_iter()
+                .filter(|permission| granted.iter().any(|p| p == permission.as_str()))
+                .collect())
+        })
+        .await
+    }
+
+    /// Replace the permissions granted to a role
+    ///
+    /// # Arguments
+    /// * `role` - The role to change
+    /// * `permissions` - The complete set of permissions the role is granted
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn set_for_role(&self, role: UserRole, permissions: &[Permission]) -> Result<()> {
+        let permissions: Vec<String> = permissions.iter().map(ToString::to_string).collect();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM role_permissions
+            WHERE role = $1 AND permission <> ALL($2)
+            "#,
+            role.as_str(),
+            &permissions
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO role_permissions (role, permission)
+            SELECT $1, permission FROM UNNEST($2::varchar[]) AS permission
+            ON CONFLICT (role, permission) DO NOTHING
+            "#,
+            role.as_str(),
+            &permissions as &[String]
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(%role, ?permissions, "Role permissions changed");
+        Ok(())
+    }
+}
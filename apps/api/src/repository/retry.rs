@@ -0,0 +1,263 @@
+//! Retries of operations failing on transient database errors
+//!
+//! Some errors say nothing about the operation itself, which usually succeeds
+//! when tried again:
+//! - The connection was lost (e.g. reset by a restarting proxy or failover)
+//! - Postgres aborted the transaction on a serialization failure or deadlock
+//!
+//! [`read`] and [`transaction`] run an operation again after such errors, up to
+//! [`MAX_ATTEMPTS`] attempts. Each retry waits a random time below an
+//! exponentially growing ceiling (full jitter), so requests failing together
+//! do not retry together. Retries are counted in [`crate::pool_stats`].
+//!
+//! A write whose connection was lost may have been committed, so only reads
+//! are retried after a lost connection; transactions are only retried when
+//! Postgres reports that it rolled them back.
+//!
+//! The repositories run their reads through [`read`]. Streamed reads (e.g. the
+//! CSV exports) are not retried, as their rows may already have been sent, nor
+//! are reads on a caller's connection or transaction, which the caller retries.
+
+use crate::error::{AppError, Result};
+use crate::pool_stats;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Attempts of an operation, including the first one
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Ceiling of the wait before the first retry, doubled for each further retry
+const BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// Longest wait between attempts
+const MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// SQLSTATEs of transactions rolled back by Postgres
+const SERIALIZATION_FAILURE: &str = "40001";
+const DEADLOCK_DETECTED: &str = "40P01";
+
+/// Kinds of transient errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transient {
+    /// The connection was lost; the statement may or may not have run
+    Disconnected,
+    /// Postgres rolled the transaction back
+    RolledBack,
+}
+
+impl Transient {
+    /// Classify a database error, `None` if retrying would not help
+    #[must_use]
+    pub fn of(err: &sqlx::Error) -> Option<Self> {
+        match err {
+            sqlx::Error::Io(io) => matches!(
+                io.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+            )
+            .then_some(Self::Disconnected),
+            sqlx::Error::Database(db_err) => matches!(
+                db_err.code().as_deref(),
+                Some(SERIALIZATION_FAILURE | DEADLOCK_DETECTED)
+            )
+            .then_some(Self::RolledBack),
+            _ => None,
+        }
+    }
+
+    /// Classify the database error an `AppError` was made from
+    fn of_app_error(err: &AppError) -> Option<Self> {
+        match err {
+            AppError::Internal { source, .. } => source.downcast_ref().and_then(Self::of),
+            _ => None,
+        }
+    }
+}
+
+/// Run a read, retrying it after any transient error
+///
+/// `operation` names the read in logs (e.g. `users.find_by_id`).
+///
+/// # Errors
+/// Returns the error of the last attempt
+pub async fn read<T, F>(operation: &str, attempt: impl FnMut() -> F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    Retry::read(operation).run(attempt).await
+}
+
+/// Run a transaction, retrying it after Postgres rolled it back
+///
+/// Each attempt must start a new transaction, e.g. with
+/// [`crate::service::UnitOfWork::run`].
+///
+/// # Errors
+/// Returns the error of the last attempt
+pub async fn transaction<T, F>(operation: &str, attempt: impl FnMut() -> F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    Retry::transaction(operation).run(attempt).await
+}
+
+/// Decides whether a failed attempt of an operation is retried
+#[derive(Debug)]
+struct Retry<'a> {
+    operation: &'a str,
+    /// Whether to retry after a lost connection
+    after_disconnect: bool,
+    attempts: u32,
+}
+
+impl<'a> Retry<'a> {
+    /// Retry a read after any transient error
+    const fn read(operation: &'a str) -> Self {
+        Self {
+            operation,
+            after_disconnect: true,
+            attempts: 1,
+        }
+    }
+
+    /// Retry a transaction after Postgres rolled it back
+    const fn transaction(operation: &'a str) -> Self {
+        Self {
+            operation,
+            after_disconnect: false,
+            attempts: 1,
+        }
+    }
+
+    /// Run `attempt` until it succeeds or fails for good
+    async fn run<T, F>(mut self, mut attempt: impl FnMut() -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        loop {
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(err) if self.should_retry(&err).await => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Whether to try again after `err`, waiting before returning `true`
+    async fn should_retry(&mut self, err: &AppError) -> bool {
+        let retryable = match Transient::of_app_error(err) {
+            Some(Transient::Disconnected) => self.after_disconnect,
+            Some(Transient::RolledBack) => true,
+            None => false,
+        };
+        if !retryable {
+            return false;
+        }
+        let operation = self.operation;
+        if self.attempts >= MAX_ATTEMPTS {
+            tracing::warn!(
+                operation,
+                attempts = self.attempts,
+                "Giving up after transient database errors"
+            );
+            pool_stats::record_retries_exhausted();
+            return false;
+        }
+
+        let delay = backoff(self.attempts);
+        tracing::warn!(
+            operation,
+            attempt = self.attempts,
+            delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+            "Retrying after a transient database error: {err}"
+        );
+        pool_stats::record_retry();
+        tokio::time::sleep(delay).await;
+        self.attempts += 1;
+        true
+    }
+}
+
+/// Random wait before retrying after the `failures`-th failed attempt
+fn backoff(failures: u32) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(1 << failures.saturating_sub(1).min(16))
+        .min(MAX_DELAY);
+    // uuid's random source spares a dependency for the jitter
+    let fraction = f64::from(uuid::Uuid::new_v4().as_fields().0) / f64::from(u32::MAX);
+    ceiling.mul_f64(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn disconnected() -> AppError {
+        sqlx::Error::Io(std::io::Error::from(ErrorKind::ConnectionReset)).into()
+    }
+
+    #[test]
+    fn test_backoff_stays_below_ceiling() {
+        for failures in 1..40 {
+            let ceiling = BASE_DELAY
+                .saturating_mul(1 << (failures - 1).min(16))
+                .min(MAX_DELAY);
+            assert!(backoff(failures) <= ceiling);
+        }
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        assert_eq!(
+            Transient::of_app_error(&disconnected()),
+            Some(Transient::Disconnected)
+        );
+        assert_eq!(Transient::of(&sqlx::Error::RowNotFound), None);
+        assert_eq!(Transient::of(&sqlx::Error::PoolTimedOut), None);
+        assert_eq!(
+            Transient::of_app_error(&AppError::NotFound("user".to_string())),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_retries_until_success() {
+        let calls = AtomicU32::new(0);
+
+        let result = read("test", || async {
+            if calls.fetch_add(1, Ordering::Relaxed) == 0 {
+                Err(disconnected())
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_attempts_are_capped() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = read("test", || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Err(disconnected())
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), MAX_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_is_not_retried_after_lost_connection() {
+        let mut retry = Retry::transaction("test");
+
+        assert!(!retry.should_retry(&disconnected()).await);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::error::Result;
+use crate::extract::ClientMetadata;
+use crate::models::Session;
+use crate::repository::retry;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for cookie session database operations
+#[derive(Clone)]
+pub struct SessionRepository {
+    pool: PgPool,
+}
+
+impl SessionRepository {
+    /// Create a new `SessionRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start a session for a user
+    /// The expired sessions of the user are removed at the same time.
+    ///
+    /// # Arguments
+    /// * `user_id` - The logged-in user
+    /// * `token_hash` - Hash of the cookie token (see `token::hash_opaque`)
+    /// * `client` - The client that logged in
+    /// * `now` - Current time
+    /// * `ttl` - How long the session lasts without being used
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        client: &ClientMetadata,
+        now: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<Session> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM sessions
+            WHERE user_id = $1 AND expires_at <= $2
+            "#,
+            user_id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO sessions (user_id, token_hash, client_ip, user_agent, created_at, last_seen_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $5, $6)
+            RETURNING id, user_id, client_ip, user_agent, created_at, last_seen_at, expires_at
+            "#,
+            user_id,
+            token_hash,
+            client.ip,
+            client.user_agent,
+            now,
+            now + ttl
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(user_id = %user_id, session_id = %session.id, "Session started");
+        Ok(session)
+    }
+
+    /// Find the unexpired session of a cookie token
+    ///
+    /// # Arguments
+    /// * `token_hash` - Hash of the presented cookie token
+    /// * `now` - Current time, compared with the expiry
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_active(
+        &self,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Session>> {
+        retry::read("sessions.find_active", || async move {
+            let session = sqlx::query_as!(
+                Session,
+                r#"
+                SELECT id, user_id, client_ip, user_agent, created_at, last_seen_at, expires_at
+                FROM sessions
+                WHERE token_hash = $1 AND expires_at > $2
+                "#,
+                token_hash,
+                now
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(session)
+        })
+        .await
+    }
+
+    /// Record that a session was used, pushing its expiry to `now + ttl`
+    ///
+    /// # Returns
+    /// * `Ok(Some(expires_at))` - The new expiry
+    /// * `Ok(None)` - The session has expired or was ended in the meantime
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn touch(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let expires_at = sqlx::query_scalar!(
+            r#"
+            UPDATE sessions
+            SET last_seen_at = $2, expires_at = $3
+            WHERE id = $1 AND expires_at > $2
+            RETURNING expires_at
+            "#,
+            id,
+            now,
+            now + ttl
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(expires_at)
+    }
+
+    /// End the session of a cookie token
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The session was ended
+    /// * `Ok(false)` - No session has the token
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn delete(&self, token_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM sessions
+            WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
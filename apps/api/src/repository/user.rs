@@ -1,6 +1,15 @@
+use crate::db::{CancelOnDrop, ReadPool};
 use crate::error::Result;
-use crate::models::{CreateUser, UpdateUser, User};
-use sqlx::PgPool;
+use crate::models::{
+    ActivityKind, CreateUser, DeletedUser, EventType, ExportedUser, LoginOutcome, SortOrder,
+    UpdateUser, User, UserActivity, UserRole, UserSort, UserStats, normalize_email,
+};
+use crate::password::{self, LockoutPolicy};
+use crate::repository::retry;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, TryStreamExt};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// User repository for database operations
@@ -8,13 +17,24 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct UserRepository {
     pool: PgPool,
+    /// Lists, reports and exports may read from a replica
+    read_pool: ReadPool,
 }
 
 impl UserRepository {
     /// Create a new `UserRepository` instance
     #[must_use]
-    pub const fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            read_pool: ReadPool::primary(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Run lists, reports and exports on `read_pool`
+    #[must_use]
+    pub fn with_read_pool(self, read_pool: ReadPool) -> Self {
+        Self { read_pool, ..self }
     }
 
     /// Find a user by ID (only active users, `deleted_at` IS NULL)
@@ -26,25 +46,534 @@ impl UserRepository {
     /// * `Ok(Some(User))` - User found
     /// * `Ok(None)` - User not found or deleted
     ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
     /// # Errors
     /// Returns `AppError` if database query fails
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>> {
+        retry::read("users.find_by_id", || self.find_by_id_in(&self.pool, id)).await
+    }
+
+    /// Like [`Self::find_by_id`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::find_by_id`]
+    pub async fn find_by_id_in(
+        &self,
+        executor: impl PgExecutor<'_>,
+        id: Uuid,
+    ) -> Result<Option<User>> {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, name, email, picture, created_at, updated_at
+            SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
             FROM users
             WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(user)
     }
 
+    /// List all active users (`deleted_at` IS NULL), ordered by email
+    ///
+    /// # Returns
+    /// * `Ok(Vec<User>)` - List of users (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_all(&self) -> Result<Vec<User>> {
+        retry::read("users.find_all", || async move {
+            let users = sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+                FROM users
+                WHERE deleted_at IS NULL
+                ORDER BY email
+                "#
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok(users)
+        })
+        .await
+    }
+
+    /// Search active users by name and email
+    /// Case-insensitive partial match on either field; without a query all active
+    /// users match. Users with equal sort values are ordered by ID
+    ///
+    /// # Arguments
+    /// * `query` - Text to look for in the name or email (if provided)
+    /// * `sort` - Column to sort by
+    /// * `order` - Sort direction
+    /// * `limit` - Maximum number of users to return
+    /// * `offset` - Number of matching users to skip
+    ///
+    /// # Returns
+    /// * `Ok((Vec<User>, i64))` - The page of users and the total number of matches
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn search(
+        &self,
+        query: Option<&str>,
+        sort: UserSort,
+        order: SortOrder,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<User>, i64)> {
+        retry::read("users.search", || async move {
+            let pattern = query.map(like_pattern);
+
+            let total = sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) as "count!"
+                FROM users
+                WHERE deleted_at IS NULL
+                  AND ($1::text IS NULL OR name ILIKE $1 OR email ILIKE $1)
+                "#,
+                pattern.as_deref()
+            )
+            .fetch_one(self.read_pool.get())
+            .await?;
+
+            let users = sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+                FROM users
+                WHERE deleted_at IS NULL
+                  AND ($1::text IS NULL OR name ILIKE $1 OR email ILIKE $1)
+                ORDER BY
+                    CASE WHEN $2 = 'name' AND $3 = 'asc' THEN name END ASC,
+                    CASE WHEN $2 = 'name' AND $3 = 'desc' THEN name END DESC,
+                    CASE WHEN $2 = 'email' AND $3 = 'asc' THEN email END ASC,
+                    CASE WHEN $2 = 'email' AND $3 = 'desc' THEN email END DESC,
+                    CASE WHEN $2 = 'created_at' AND $3 = 'asc' THEN created_at END ASC,
+                    CASE WHEN $2 = 'created_at' AND $3 = 'desc' THEN created_at END DESC,
+                    id
+                LIMIT $4 OFFSET $5
+                "#,
+                pattern.as_deref(),
+                // Only the fixed strings of the sort enums reach the query, as parameters
+                sort.as_str(),
+                order.as_str(),
+                limit,
+                offset
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok((users, total))
+        })
+        .await
+    }
+
+    /// List soft-deleted users, most recently deleted first
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of users to return
+    /// * `offset` - Number of deleted users to skip
+    ///
+    /// # Returns
+    /// * `Ok((Vec<DeletedUser>, i64))` - The page of users and the total number of deleted users
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_deleted(&self, limit: i64, offset: i64) -> Result<(Vec<DeletedUser>, i64)> {
+        retry::read("users.find_deleted", || async move {
+            let total = sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) as "count!"
+                FROM users
+                WHERE deleted_at IS NOT NULL
+                "#
+            )
+            .fetch_one(self.read_pool.get())
+            .await?;
+
+            let users = sqlx::query_as!(
+                DeletedUser,
+                r#"
+                SELECT id, name, email, picture, created_at, updated_at,
+                       deleted_at as "deleted_at!"
+                FROM users
+                WHERE deleted_at IS NOT NULL
+                ORDER BY deleted_at DESC, id
+                LIMIT $1 OFFSET $2
+                "#,
+                limit,
+                offset
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok((users, total))
+        })
+        .await
+    }
+
+    /// Count the users in each state in one pass over the table
+    ///
+    /// # Arguments
+    /// * `created_since` - Start of the period counted in `created_recently`
+    ///
+    /// # Returns
+    /// * `Ok(UserStats)` - The number of users in each state
+    ///
+    /// The query is cancelled if the request is dropped (see [`CancelOnDrop`])
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn stats(&self, created_since: DateTime<Utc>) -> Result<UserStats> {
+        retry::read("users.stats", || async move {
+            let mut conn = CancelOnDrop::acquire(self.read_pool.get()).await?;
+            let stats = sqlx::query_as!(
+                UserStats,
+                r#"
+                SELECT
+                    COUNT(*) FILTER (WHERE deleted_at IS NULL AND is_active) as "active!",
+                    COUNT(*) FILTER (WHERE deleted_at IS NULL AND NOT is_active) as "deactivated!",
+                    COUNT(*) FILTER (WHERE deleted_at IS NOT NULL) as "deleted!",
+                    COUNT(*) FILTER (WHERE deleted_at IS NULL AND created_at >= $1)
+                        as "created_recently!"
+                FROM users
+                "#,
+                created_since
+            )
+            .fetch_one(&mut *conn)
+            .await?;
+            conn.finish();
+
+            Ok(stats)
+        })
+        .await
+    }
+
+    /// Stream users for export, ordered by creation time (oldest first)
+    /// Rows are fetched as the stream is polled, so exports of any size run in
+    /// constant memory
+    ///
+    /// # Arguments
+    /// * `include_deleted` - Also stream soft-deleted users
+    ///
+    /// # Returns
+    /// A stream of users; each item is `Err(AppError)` if fetching the row fails
+    pub fn stream_for_export(
+        &self,
+        include_deleted: bool,
+    ) -> impl Stream<Item = Result<ExportedUser>> + Send + '_ {
+        sqlx::query_as!(
+            ExportedUser,
+            r#"
+            SELECT id, name, email, created_at, deleted_at
+            FROM users
+            WHERE $1 OR deleted_at IS NULL
+            ORDER BY created_at, id
+            "#,
+            include_deleted
+        )
+        .fetch(self.read_pool.get())
+        .map_err(crate::error::AppError::from)
+    }
+
+    /// Check the password of an active user, counting failed logins
+    /// The email address is compared after normalization, ignoring case
+    ///
+    /// A wrong password counts as a failed login of the account; after
+    /// `policy.max_attempts` in a row, the account is locked for
+    /// `policy.cooldown` and the count starts over. A successful login resets
+    /// the count. Passwords are not checked while the account is locked.
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn authenticate(
+        &self,
+        email: &str,
+        password: &str,
+        policy: LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginOutcome> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at,
+                   password_hash, failed_login_attempts, locked_until
+            FROM users
+            WHERE lower(email) = $1 AND deleted_at IS NULL
+            "#,
+            normalize_email(email)
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(until) = row
+            .as_ref()
+            .and_then(|row| row.locked_until)
+            .filter(|until| *until > now)
+        {
+            return Ok(LoginOutcome::Locked { until });
+        }
+
+        // Verify against a fixed hash when there is nothing to verify, so the
+        // response time does not reveal which email addresses are registered
+        let hash = row
+            .as_ref()
+            .and_then(|row| row.password_hash.clone())
+            .unwrap_or_else(|| password::UNUSABLE_HASH.to_string());
+        let matches = password::verify_async(password.to_string(), hash).await?;
+
+        let Some(row) = row.filter(|row| row.password_hash.is_some() && row.is_active) else {
+            return Ok(LoginOutcome::Failed);
+        };
+
+        if matches {
+            if row.failed_login_attempts > 0 || row.locked_until.is_some() {
+                self.unlock(row.id).await?;
+            }
+            return Ok(LoginOutcome::Succeeded(User {
+                id: row.id,
+                name: row.name,
+                email: row.email,
+                picture: row.picture,
+                role: row.role,
+                is_active: row.is_active,
+                token_version: row.token_version,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            }));
+        }
+
+        self.record_failed_login(row.id, policy, now).await
+    }
+
+    /// Count a failed login of an account, locking it after `policy.max_attempts`
+    /// failures in a row
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    async fn record_failed_login(
+        &self,
+        id: Uuid,
+        policy: LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginOutcome> {
+        // Counted in the database, so concurrent failures are all counted
+        let locked_until = sqlx::query_scalar!(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = CASE
+                    WHEN failed_login_attempts + 1 >= $2 THEN 0
+                    ELSE failed_login_attempts + 1
+                END,
+                locked_until = CASE
+                    WHEN failed_login_attempts + 1 >= $2 THEN $3
+                    ELSE locked_until
+                END
+            WHERE id = $1
+            RETURNING locked_until
+            "#,
+            id,
+            policy.max_attempts,
+            now + policy.cooldown
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(locked_until
+            .filter(|until| *until > now)
+            .map_or(LoginOutcome::Failed, |until| {
+                tracing::warn!(user_id = %id, %until, "Account locked after failed logins");
+                LoginOutcome::Locked { until }
+            }))
+    }
+
+    /// Log a user out everywhere
+    /// Every access token issued to the user so far is revoked (see
+    /// [`User::token_version`]) and every cookie session is ended.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no active user has this ID
+    /// Returns `AppError` if database query fails
+    pub async fn revoke_tokens(&self, id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET token_version = token_version + 1
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(
+                crate::error::AppError::NotFound(format!("User with id {id} not found"))
+                    .with_code(crate::error::ErrorCode::UserNotFound),
+            );
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM sessions
+            WHERE user_id = $1
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(user_id = %id, "Tokens and sessions revoked");
+        Ok(())
+    }
+
+    /// Unlock an account locked after failed logins and reset its count of
+    /// failed logins
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The user exists (whether or not it was locked)
+    /// * `Ok(false)` - No user has this ID
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn unlock(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = 0, locked_until = NULL
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Change the password of an active user after checking the current one
+    /// Automatically updates the `updated_at` timestamp
+    ///
+    /// The current password is checked like a login (see [`Self::authenticate`]):
+    /// a wrong one counts as a failed login of the account and may lock it, and
+    /// passwords are not checked while the account is locked. On success, the
+    /// count of failed logins is reset, every access token issued to the user so
+    /// far is revoked and every cookie session is ended.
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the user
+    /// * `current_password` - The user's current plain-text password
+    /// * `new_password` - The new plain-text password, stored as an Argon2 hash
+    /// * `policy` - When to lock the account after wrong current passwords
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    /// * `LoginOutcome::Succeeded(User)` - The password was changed
+    /// * `LoginOutcome::Failed` - The current password does not match, the user
+    ///   has no password or is deactivated
+    /// * `LoginOutcome::Locked` - The account is locked after failed logins
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no user has this ID
+    /// Returns `AppError` if hashing or the database query fails
+    pub async fn change_password(
+        &self,
+        id: Uuid,
+        current_password: &str,
+        new_password: &str,
+        policy: LockoutPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<LoginOutcome> {
+        let row = sqlx::query!(
+            r#"
+            SELECT is_active, password_hash, locked_until
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            crate::error::AppError::NotFound(format!("User with id {id} not found"))
+                .with_code(crate::error::ErrorCode::UserNotFound)
+        })?;
+
+        if let Some(until) = row.locked_until.filter(|until| *until > now) {
+            return Ok(LoginOutcome::Locked { until });
+        }
+
+        let hash = row
+            .password_hash
+            .unwrap_or_else(|| password::UNUSABLE_HASH.to_string());
+        let matches = password::verify_async(current_password.to_string(), hash).await?;
+        if !row.is_active {
+            return Ok(LoginOutcome::Failed);
+        }
+        if !matches {
+            return self.record_failed_login(id, policy, now).await;
+        }
+
+        let new_hash = password::hash_async(new_password.to_string()).await?;
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET password_hash = $2,
+                failed_login_attempts = 0,
+                locked_until = NULL,
+                token_version = token_version + 1,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+            "#,
+            id,
+            new_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| {
+            crate::error::AppError::NotFound(format!("User with id {id} not found"))
+                .with_code(crate::error::ErrorCode::UserNotFound)
+        })?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM sessions
+            WHERE user_id = $1
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(user_id = %id, "Password changed; tokens and sessions revoked");
+        Ok(LoginOutcome::Succeeded(user))
+    }
+
     /// Find a user by email address (only active users, `deleted_at` IS NULL)
+    /// The email address is compared after normalization, ignoring case
     ///
     /// # Arguments
     /// * `email` - The email address to search for
@@ -53,24 +582,150 @@ impl UserRepository {
     /// * `Ok(Some(User))` - User found
     /// * `Ok(None)` - User not found or deleted
     ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
     /// # Errors
     /// Returns `AppError` if database query fails
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        retry::read("users.find_by_email", || {
+            self.find_by_email_in(&self.pool, email)
+        })
+        .await
+    }
+
+    /// Like [`Self::find_by_email`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::find_by_email`]
+    pub async fn find_by_email_in(
+        &self,
+        executor: impl PgExecutor<'_>,
+        email: &str,
+    ) -> Result<Option<User>> {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, name, email, picture, created_at, updated_at
+            SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
             FROM users
-            WHERE email = $1 AND deleted_at IS NULL
+            WHERE lower(email) = $1 AND deleted_at IS NULL
             "#,
-            email
+            normalize_email(email)
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(user)
     }
 
+    /// Find the active user linked to a Google account
+    ///
+    /// # Arguments
+    /// * `google_sub` - The subject identifier of the Google account
+    ///
+    /// # Returns
+    /// * `Ok(Some(User))` - User found
+    /// * `Ok(None)` - No active user is linked to the account
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_google_sub(&self, google_sub: &str) -> Result<Option<User>> {
+        retry::read("users.find_by_google_sub", || async move {
+            let user = sqlx::query_as!(
+                User,
+                r#"
+                SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+                FROM users
+                WHERE google_sub = $1 AND deleted_at IS NULL
+                "#,
+                google_sub
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(user)
+        })
+        .await
+    }
+
+    /// Link an active user to a Google account
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the user
+    /// * `google_sub` - The subject identifier of the Google account
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no active user has this ID
+    /// Returns `AppError::Conflict` if another active user is linked to the account
+    /// Returns `AppError` if database query fails
+    pub async fn link_google(&self, id: Uuid, google_sub: &str) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET google_sub = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+            google_sub
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                crate::error::AppError::Conflict(
+                    "The Google account is linked to another user".to_string(),
+                )
+                .with_code(crate::error::ErrorCode::GoogleAccountAlreadyLinked)
+            }
+            e => e.into(),
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(
+                crate::error::AppError::NotFound(format!("User with id {id} not found"))
+                    .with_code(crate::error::ErrorCode::UserNotFound),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Look up the IDs of active users by email address
+    ///
+    /// # Arguments
+    /// * `emails` - The email addresses to look up (duplicates are allowed)
+    ///
+    /// # Returns
+    /// * `Ok(HashMap<String, Uuid>)` - Map from normalized email (see
+    ///   [`normalize_email`]) to user ID; emails without an active user are absent
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_ids_by_emails(&self, emails: &[String]) -> Result<HashMap<String, Uuid>> {
+        retry::read("users.find_ids_by_emails", || async move {
+            let emails: Vec<String> = emails.iter().map(|email| normalize_email(email)).collect();
+            let rows = sqlx::query!(
+                r#"
+                SELECT id, email
+                FROM users
+                WHERE lower(email) = ANY($1) AND deleted_at IS NULL
+                "#,
+                &emails
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| (normalize_email(&row.email), row.id))
+                .collect())
+        })
+        .await
+    }
+
     /// Create a new user
     ///
     /// # Arguments
@@ -79,29 +734,111 @@ impl UserRepository {
     /// # Returns
     /// * `Ok(User)` - The created user with generated ID and timestamps
     ///
+    /// The email address is stored normalized (see [`normalize_email`]) and the
+    /// password, if given, is stored as an Argon2 hash.
+    ///
     /// # Errors
-    /// Returns `AppError` if database query fails (e.g., unique constraint violation)
+    /// Returns `AppError::Conflict` if an active user already uses the email address
+    /// Returns `AppError` if hashing the password or the database query fails
     pub async fn create(&self, user: CreateUser) -> Result<User> {
+        self.create_in(&self.pool, user).await
+    }
+
+    /// Like [`Self::create`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::create`]
+    pub async fn create_in(&self, executor: impl PgExecutor<'_>, user: CreateUser) -> Result<User> {
+        let email = normalize_email(&user.email);
+        let password_hash = match user.password {
+            Some(password) => Some(password::hash_async(password).await?),
+            None => None,
+        };
+
         let created_user = sqlx::query_as!(
             User,
             r#"
-            INSERT INTO users (name, email, picture)
-            VALUES ($1, $2, $3)
-            RETURNING id, name, email, picture, created_at, updated_at
+            INSERT INTO users (name, email, picture, password_hash)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
             "#,
             user.name,
-            user.email,
-            user.picture
+            email,
+            user.picture,
+            password_hash
         )
-        .fetch_one(&self.pool)
-        .await?;
+        .fetch_one(executor)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                crate::error::AppError::Conflict(format!(
+                    "A user with email {email} already exists"
+                )).with_code(crate::error::ErrorCode::EmailAlreadyExists)
+            }
+            e => e.into(),
+        })?;
 
         Ok(created_user)
     }
 
+    /// Create many users in one transaction
+    /// Either every user is created or none is.
+    ///
+    /// # Arguments
+    /// * `users` - The user creation request data
+    ///
+    /// # Returns
+    /// * `Ok(Vec<User>)` - The created users, in the order given
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if an active user already uses one of the email addresses
+    /// Returns `AppError` if database query fails
+    pub async fn create_many(&self, users: &[CreateUser]) -> Result<Vec<User>> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut created = Vec::with_capacity(users.len());
+        for user in users {
+            let email = normalize_email(&user.email);
+            let password_hash = match &user.password {
+                Some(password) => Some(password::hash_async(password.clone()).await?),
+                None => None,
+            };
+            let created_user = sqlx::query_as!(
+                User,
+                r#"
+                INSERT INTO users (name, email, picture, password_hash)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+                "#,
+                user.name,
+                email,
+                user.picture,
+                password_hash
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    crate::error::AppError::Conflict(format!(
+                        "A user with email {email} already exists"
+                    )).with_code(crate::error::ErrorCode::EmailAlreadyExists)
+                }
+                e => e.into(),
+            })?;
+            created.push(created_user);
+        }
+
+        tx.commit().await?;
+
+        tracing::info!(count = created.len(), "Created users");
+        Ok(created)
+    }
+
     /// Update an existing user
-    /// Only updates fields that are provided (Some) in the `UpdateUser` struct
-    /// Automatically updates the `updated_at` timestamp
+    /// Only updates fields that are provided (Some) in the `UpdateUser` struct;
+    /// `picture: Some(None)` sets the picture to NULL
+    /// Automatically updates the `updated_at` timestamp and records the changed
+    /// fields in the profile history
     ///
     /// # Arguments
     /// * `id` - The UUID of the user to update
@@ -111,8 +848,32 @@ impl UserRepository {
     /// * `Ok(User)` - The updated user
     ///
     /// # Errors
-    /// Returns `AppError` if database query fails or user not found
+    /// Returns `AppError::NotFound` if no active user has this ID
+    /// Returns `AppError::Conflict` if an active user already uses the new email address
+    /// Returns `AppError` if database query fails
     pub async fn update(&self, id: Uuid, user: UpdateUser) -> Result<User> {
+        let mut tx = self.pool.begin().await?;
+        let updated_user = self.update_in(&mut tx, id, user).await?;
+        tx.commit().await?;
+
+        Ok(updated_user)
+    }
+
+    /// Like [`Self::update`], on a connection or transaction
+    ///
+    /// The user stays locked until the transaction ends; on a bare connection
+    /// the changes are not atomic.
+    ///
+    /// # Errors
+    /// See [`Self::update`]
+    pub async fn update_in(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user: UpdateUser,
+    ) -> Result<User> {
+        let before = lock_active(conn, id).await?;
+
         let updated_user = sqlx::query_as!(
             User,
             r#"
@@ -120,18 +881,29 @@ impl UserRepository {
             SET
                 name = COALESCE($2, name),
                 email = COALESCE($3, email),
-                picture = COALESCE($4, picture),
+                picture = CASE WHEN $4 THEN $5 ELSE picture END,
                 updated_at = CURRENT_TIMESTAMP
-            WHERE id = $1 AND deleted_at IS NULL
-            RETURNING id, name, email, picture, created_at, updated_at
+            WHERE id = $1
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
             "#,
             id,
             user.name,
-            user.email,
-            user.picture
+            user.email.as_deref().map(normalize_email),
+            user.picture.is_some(),
+            user.picture.flatten()
         )
-        .fetch_one(&self.pool)
-        .await?;
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                crate::error::AppError::Conflict(
+                    "Another active user already uses this email address".to_string(),
+                ).with_code(crate::error::ErrorCode::EmailAlreadyExists)
+            }
+            e => e.into(),
+        })?;
+
+        record_profile_changes(conn, &before, &updated_user).await?;
 
         Ok(updated_user)
     }
@@ -148,6 +920,14 @@ impl UserRepository {
     /// # Errors
     /// Returns `AppError` if database query fails or user not found
     pub async fn delete(&self, id: Uuid) -> Result<()> {
+        self.delete_in(&self.pool, id).await
+    }
+
+    /// Like [`Self::delete`], on a connection or transaction
+    ///
+    /// # Errors
+    /// See [`Self::delete`]
+    pub async fn delete_in(&self, executor: impl PgExecutor<'_>, id: Uuid) -> Result<()> {
         let result = sqlx::query!(
             r#"
             UPDATE users
@@ -156,15 +936,398 @@ impl UserRepository {
             "#,
             id
         )
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         if result.rows_affected() == 0 {
-            return Err(crate::error::AppError::NotFound(format!(
-                "User with id {id} not found"
-            )));
+            return Err(
+                crate::error::AppError::NotFound(format!("User with id {id} not found"))
+                    .with_code(crate::error::ErrorCode::UserNotFound),
+            );
         }
 
         Ok(())
     }
+
+    /// Set the role of an active user
+    /// Automatically updates the `updated_at` timestamp and records the change
+    /// in the profile history
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the user
+    /// * `role` - The new role
+    ///
+    /// # Returns
+    /// * `Ok(User)` - The updated user
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no active user has this ID
+    /// Returns `AppError` if database query fails
+    pub async fn set_role(&self, id: Uuid, role: UserRole) -> Result<User> {
+        let mut tx = self.pool.begin().await?;
+        let user = self.set_role_in(&mut tx, id, role).await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Like [`Self::set_role`], on a connection or transaction
+    ///
+    /// The user stays locked until the transaction ends; on a bare connection
+    /// the changes are not atomic.
+    ///
+    /// # Errors
+    /// See [`Self::set_role`]
+    pub async fn set_role_in(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        role: UserRole,
+    ) -> Result<User> {
+        let before = lock_active(conn, id).await?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET role = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+            "#,
+            id,
+            role as UserRole
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        record_profile_changes(conn, &before, &user).await?;
+
+        Ok(user)
+    }
+
+    /// Deactivate or reactivate a user
+    /// Deactivated users keep their data but can neither authenticate nor
+    /// record attendance. Automatically updates the `updated_at` timestamp and
+    /// records the change in the profile history
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the user
+    /// * `is_active` - `false` to deactivate, `true` to activate
+    ///
+    /// # Returns
+    /// * `Ok(User)` - The updated user
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no active user has this ID
+    /// Returns `AppError` if database query fails
+    pub async fn set_active(&self, id: Uuid, is_active: bool) -> Result<User> {
+        let mut tx = self.pool.begin().await?;
+        let user = self.set_active_in(&mut tx, id, is_active).await?;
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Like [`Self::set_active`], on a connection or transaction
+    ///
+    /// The user stays locked until the transaction ends; on a bare connection
+    /// the changes are not atomic.
+    ///
+    /// # Errors
+    /// See [`Self::set_active`]
+    pub async fn set_active_in(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        is_active: bool,
+    ) -> Result<User> {
+        let before = lock_active(conn, id).await?;
+
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET is_active = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+            "#,
+            id,
+            is_active
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        record_profile_changes(conn, &before, &user).await?;
+
+        Ok(user)
+    }
+
+    /// Find which of the given users are deactivated
+    ///
+    /// # Arguments
+    /// * `ids` - The UUIDs of the users to check
+    ///
+    /// # Returns
+    /// * `Ok(HashSet<Uuid>)` - The deactivated users among `ids`
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_deactivated_ids(&self, ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        retry::read("users.find_deactivated_ids", || async move {
+            let deactivated = sqlx::query_scalar!(
+                r#"
+                SELECT id
+                FROM users
+                WHERE id = ANY($1) AND NOT is_active
+                "#,
+                ids
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(deactivated.into_iter().collect())
+        })
+        .await
+    }
+
+    /// List the activity of a user, most recent first
+    /// Combines the changes to the user's profile with their attendance events
+    /// (including superseded ones) into one timeline
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the user
+    /// * `limit` - Maximum number of entries to return
+    /// * `offset` - Number of entries to skip
+    ///
+    /// # Returns
+    /// * `Ok((Vec<UserActivity>, i64))` - The page of entries and the total number of entries
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_activity(
+        &self,
+        id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<UserActivity>, i64)> {
+        retry::read("users.find_activity", || async move {
+            let total = sqlx::query_scalar!(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM user_profile_changes WHERE user_id = $1)
+                    + (SELECT COUNT(*) FROM attendance_events WHERE user_id = $1) as "count!"
+                "#,
+                id
+            )
+            .fetch_one(self.read_pool.get())
+            .await?;
+
+            let activity = sqlx::query_as!(
+                UserActivity,
+                r#"
+                SELECT kind as "kind!: ActivityKind", occurred_at as "occurred_at!",
+                       field, old_value, new_value,
+                       event_id, event_type as "event_type: EventType", amends_event_id
+                FROM (
+                    SELECT 'profile_changed'::varchar AS kind, changed_at AS occurred_at,
+                           field, old_value, new_value,
+                           NULL::uuid AS event_id, NULL::varchar AS event_type,
+                           NULL::uuid AS amends_event_id, id
+                    FROM user_profile_changes
+                    WHERE user_id = $1
+                    UNION ALL
+                    SELECT 'attendance_event'::varchar, event_time,
+                           NULL, NULL, NULL,
+                           id, event_type, amends_event_id, id
+                    FROM attendance_events
+                    WHERE user_id = $1
+                ) activity
+                ORDER BY occurred_at DESC, id
+                LIMIT $2 OFFSET $3
+                "#,
+                id,
+                limit,
+                offset
+            )
+            .fetch_all(self.read_pool.get())
+            .await?;
+
+            Ok((activity, total))
+        })
+        .await
+    }
+
+    /// Restore a soft-deleted user by clearing `deleted_at`
+    /// Automatically updates the `updated_at` timestamp
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the deleted user
+    ///
+    /// # Returns
+    /// * `Ok(User)` - The restored user
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no deleted user has this ID
+    /// Returns `AppError::Conflict` if an active user already uses the email address
+    /// Returns `AppError` if database query fails
+    pub async fn restore(&self, id: Uuid) -> Result<User> {
+        let restored = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users
+            SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                crate::error::AppError::Conflict(format!(
+                    "Another active user already uses the email address of user {id}"
+                )).with_code(crate::error::ErrorCode::EmailAlreadyExists)
+            }
+            e => e.into(),
+        })?;
+
+        restored.ok_or_else(|| {
+            crate::error::AppError::NotFound(format!("Deleted user with id {id} not found"))
+        })
+    }
+
+    /// Permanently remove a soft-deleted user
+    /// Attendance events, corrections and anomalies of the user are removed by
+    /// `ON DELETE CASCADE`; corrections they approved keep no reference to them
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the deleted user
+    ///
+    /// # Returns
+    /// * `Ok(i64)` - Number of attendance events removed with the user
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if no deleted user has this ID
+    /// Returns `AppError` if database query fails
+    pub async fn purge(&self, id: Uuid) -> Result<i64> {
+        // The subquery reads the snapshot taken before the delete, so it still
+        // sees the events that the cascade removes
+        let events = sqlx::query_scalar!(
+            r#"
+            WITH purged AS (
+                DELETE FROM users
+                WHERE id = $1 AND deleted_at IS NOT NULL
+                RETURNING id
+            )
+            SELECT (SELECT COUNT(*) FROM attendance_events e WHERE e.user_id = p.id) AS "events!"
+            FROM purged p
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        events.ok_or_else(|| {
+            crate::error::AppError::NotFound(format!("Deleted user with id {id} not found"))
+        })
+    }
+}
+
+/// Lock an active user for an update and return its current state
+///
+/// # Errors
+/// Returns `AppError::NotFound` if no active user has this ID
+async fn lock_active(conn: &mut PgConnection, id: Uuid) -> Result<User> {
+    sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, name, email, picture, role as "role: UserRole", is_active, token_version, created_at, updated_at
+        FROM users
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| crate::error::AppError::NotFound(format!("User with id {id} not found")).with_code(crate::error::ErrorCode::UserNotFound))
+}
+
+/// Record the fields that differ between two states of a user in the profile history
+async fn record_profile_changes(
+    conn: &mut PgConnection,
+    before: &User,
+    after: &User,
+) -> Result<()> {
+    let fields = [
+        (
+            "name",
+            Some(before.name.as_str()),
+            Some(after.name.as_str()),
+        ),
+        (
+            "email",
+            Some(before.email.as_str()),
+            Some(after.email.as_str()),
+        ),
+        (
+            "picture",
+            before.picture.as_deref(),
+            after.picture.as_deref(),
+        ),
+        (
+            "role",
+            Some(before.role.as_str()),
+            Some(after.role.as_str()),
+        ),
+        (
+            "is_active",
+            Some(bool_str(before.is_active)),
+            Some(bool_str(after.is_active)),
+        ),
+    ];
+
+    for (field, old_value, new_value) in fields {
+        if old_value == new_value {
+            continue;
+        }
+        sqlx::query!(
+            r#"
+            INSERT INTO user_profile_changes (user_id, field, old_value, new_value)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            after.id,
+            field,
+            old_value,
+            new_value
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+const fn bool_str(value: bool) -> &'static str {
+    if value { "true" } else { "false" }
+}
+
+/// Build an `ILIKE` pattern matching `query` anywhere in a value
+/// Wildcards in the query are escaped, so they match literally
+fn like_pattern(query: &str) -> String {
+    let mut pattern = String::with_capacity(query.len() + 2);
+    pattern.push('%');
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
 }
@@ -0,0 +1,409 @@
+use crate::error::{AppError, Result};
+use crate::models::{
+    CreateWebhook, DeliveryAttempt, DeliveryStatus, UpdateWebhook, Webhook, WebhookDelivery,
+};
+use crate::repository::retry;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Webhook repository for database operations
+/// Handles webhook subscribers and their delivery log
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    /// Create a new `WebhookRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all webhooks, ordered by creation time
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Webhook>)` - List of webhooks (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(&self) -> Result<Vec<Webhook>> {
+        retry::read("webhooks.list", || async move {
+            let webhooks = sqlx::query_as!(
+                Webhook,
+                r#"
+                SELECT id, url, secret, description, is_active, created_at, updated_at
+                FROM webhooks
+                ORDER BY created_at
+                "#
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(webhooks)
+        })
+        .await
+    }
+
+    /// Find a webhook by its ID
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the webhook to find
+    ///
+    /// # Returns
+    /// * `Ok(Some(Webhook))` - Webhook found
+    /// * `Ok(None)` - Webhook not found
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Webhook>> {
+        retry::read("webhooks.find_by_id", || async move {
+            let webhook = sqlx::query_as!(
+                Webhook,
+                r#"
+                SELECT id, url, secret, description, is_active, created_at, updated_at
+                FROM webhooks
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(webhook)
+        })
+        .await
+    }
+
+    /// Register a new webhook
+    ///
+    /// # Arguments
+    /// * `webhook` - The webhook creation request data
+    ///
+    /// # Returns
+    /// * `Ok(Webhook)` - The newly created webhook (active)
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn create(&self, webhook: CreateWebhook) -> Result<Webhook> {
+        let created = sqlx::query_as!(
+            Webhook,
+            r#"
+            INSERT INTO webhooks (url, secret, description)
+            VALUES ($1, $2, $3)
+            RETURNING id, url, secret, description, is_active, created_at, updated_at
+            "#,
+            webhook.url,
+            webhook.secret,
+            webhook.description
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(webhook_id = %created.id, "Created webhook");
+        Ok(created)
+    }
+
+    /// Update an existing webhook
+    /// Only updates fields that are provided (Some) in the `UpdateWebhook` struct
+    /// Automatically updates the `updated_at` timestamp
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the webhook to update
+    /// * `webhook` - The webhook update request data with optional fields
+    ///
+    /// # Returns
+    /// * `Ok(Webhook)` - The updated webhook
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the webhook does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn update(&self, id: Uuid, webhook: UpdateWebhook) -> Result<Webhook> {
+        let updated = sqlx::query_as!(
+            Webhook,
+            r#"
+            UPDATE webhooks
+            SET
+                url = COALESCE($2, url),
+                description = COALESCE($3, description),
+                is_active = COALESCE($4, is_active),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, url, secret, description, is_active, created_at, updated_at
+            "#,
+            id,
+            webhook.url,
+            webhook.description,
+            webhook.is_active
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        updated.ok_or_else(|| AppError::NotFound(format!("Webhook with id {id} not found")))
+    }
+
+    /// Delete a webhook together with its delivery log
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the webhook to delete
+    ///
+    /// # Returns
+    /// * `Ok(())` - Webhook successfully deleted
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the webhook does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM webhooks
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Webhook with id {id} not found"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Create a pending delivery of a payload for every active webhook
+    /// The deliveries are leased to the caller until `lease_until`, so the retry
+    /// job does not pick them up while the first attempt is in flight
+    ///
+    /// # Arguments
+    /// * `event` - Notification type (e.g. `attendance_event.created`)
+    /// * `payload` - JSON body to deliver
+    /// * `lease_until` - When the deliveries become due for the retry job
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDelivery>)` - One delivery per active webhook (may be empty)
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn enqueue_deliveries(
+        &self,
+        event: &str,
+        payload: &str,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload, next_attempt_at)
+            SELECT id, $1, $2, $3
+            FROM webhooks
+            WHERE is_active
+            RETURNING id, webhook_id, event, payload, status as "status: DeliveryStatus",
+                      attempts, last_status_code, last_error, next_attempt_at,
+                      created_at, delivered_at
+            "#,
+            event,
+            payload,
+            lease_until
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// List the deliveries of a webhook, most recent first
+    ///
+    /// # Arguments
+    /// * `webhook_id` - The webhook whose delivery log to list
+    /// * `status` - Only return deliveries in this state (if provided)
+    /// * `limit` - Maximum number of deliveries to return
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDelivery>)` - List of deliveries (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list_deliveries(
+        &self,
+        webhook_id: Uuid,
+        status: Option<DeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        retry::read("webhooks.list_deliveries", || async move {
+            let deliveries = sqlx::query_as!(
+                WebhookDelivery,
+                r#"
+                SELECT id, webhook_id, event, payload, status as "status: DeliveryStatus",
+                       attempts, last_status_code, last_error, next_attempt_at,
+                       created_at, delivered_at
+                FROM webhook_deliveries
+                WHERE webhook_id = $1
+                  AND ($2::varchar IS NULL OR status = $2)
+                ORDER BY created_at DESC
+                LIMIT $3
+                "#,
+                webhook_id,
+                status.map(DeliveryStatus::as_str),
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(deliveries)
+        })
+        .await
+    }
+
+    /// Find a delivery of a webhook by its ID
+    ///
+    /// # Arguments
+    /// * `webhook_id` - The webhook the delivery belongs to
+    /// * `id` - The UUID of the delivery to find
+    ///
+    /// # Returns
+    /// * `Ok(Some(WebhookDelivery))` - Delivery found
+    /// * `Ok(None)` - Delivery not found for this webhook
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_delivery(
+        &self,
+        webhook_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<WebhookDelivery>> {
+        retry::read("webhooks.find_delivery", || async move {
+            let delivery = sqlx::query_as!(
+                WebhookDelivery,
+                r#"
+                SELECT id, webhook_id, event, payload, status as "status: DeliveryStatus",
+                       attempts, last_status_code, last_error, next_attempt_at,
+                       created_at, delivered_at
+                FROM webhook_deliveries
+                WHERE webhook_id = $1 AND id = $2
+                "#,
+                webhook_id,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(delivery)
+        })
+        .await
+    }
+
+    /// Claim pending deliveries of active webhooks that are due
+    /// Claimed deliveries are leased until `lease_until`; concurrent callers skip
+    /// rows another caller is claiming, so each delivery is attempted once per lease
+    ///
+    /// # Arguments
+    /// * `now` - Deliveries due at or before this time are claimed
+    /// * `lease_until` - When claimed deliveries become due again if not recorded
+    /// * `limit` - Maximum number of deliveries to claim
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDelivery>)` - The claimed deliveries, oldest due first
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn claim_due_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let mut deliveries = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            UPDATE webhook_deliveries
+            SET next_attempt_at = $2
+            WHERE id IN (
+                SELECT d.id
+                FROM webhook_deliveries d
+                WHERE d.status = 'pending'
+                  AND d.next_attempt_at <= $1
+                  AND EXISTS (
+                      SELECT 1 FROM webhooks w
+                      WHERE w.id = d.webhook_id AND w.is_active
+                  )
+                ORDER BY d.next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, webhook_id, event, payload, status as "status: DeliveryStatus",
+                      attempts, last_status_code, last_error, next_attempt_at,
+                      created_at, delivered_at
+            "#,
+            now,
+            lease_until,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        deliveries.sort_by_key(|delivery| delivery.created_at);
+        Ok(deliveries)
+    }
+
+    /// Record the outcome of a delivery attempt
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the delivery
+    /// * `status` - State of the delivery after the attempt
+    /// * `attempt` - Response status and error of the attempt
+    /// * `next_attempt_at` - When to retry a still pending delivery
+    ///
+    /// # Returns
+    /// * `Ok(WebhookDelivery)` - The updated delivery
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the delivery no longer exists
+    /// Returns `AppError` if database query fails
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        status: DeliveryStatus,
+        attempt: &DeliveryAttempt,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<WebhookDelivery> {
+        let updated = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            UPDATE webhook_deliveries
+            SET
+                status = $2::varchar,
+                attempts = attempts + 1,
+                last_status_code = $3,
+                last_error = $4,
+                next_attempt_at = $5,
+                delivered_at = CASE WHEN $2::varchar = 'succeeded' THEN CURRENT_TIMESTAMP END
+            WHERE id = $1
+            RETURNING id, webhook_id, event, payload, status as "status: DeliveryStatus",
+                      attempts, last_status_code, last_error, next_attempt_at,
+                      created_at, delivered_at
+            "#,
+            id,
+            status.as_str(),
+            attempt.status_code,
+            attempt.error,
+            next_attempt_at
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        updated
+            .ok_or_else(|| AppError::NotFound(format!("Webhook delivery with id {id} not found")))
+    }
+}
@@ -0,0 +1,295 @@
+use crate::error::{AppError, Result};
+use crate::models::{CreateWorkPolicy, RoundingMode, UpdateWorkPolicy, WeekStart, WorkPolicy};
+use crate::repository::retry;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Work policy repository for database operations
+/// Handles CRUD operations for the `work_policies` table and switching the active policy
+#[derive(Clone)]
+pub struct WorkPolicyRepository {
+    pool: PgPool,
+}
+
+impl WorkPolicyRepository {
+    /// Create a new `WorkPolicyRepository` instance
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all work policies ordered by name
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WorkPolicy>)` - List of policies (may be empty)
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn list(&self) -> Result<Vec<WorkPolicy>> {
+        retry::read("work_policies.list", || async move {
+            let policies = sqlx::query_as!(
+                WorkPolicy,
+                r#"
+                SELECT id, name, standard_daily_minutes, weekly_threshold_minutes,
+                       week_start as "week_start: WeekStart",
+                       rounding_mode as "rounding_mode: RoundingMode",
+                       rounding_minutes, is_active, created_at, updated_at
+                FROM work_policies
+                ORDER BY name, created_at
+                "#
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            Ok(policies)
+        })
+        .await
+    }
+
+    /// Find a work policy by ID
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the policy
+    ///
+    /// # Returns
+    /// * `Ok(Some(WorkPolicy))` - Policy found
+    /// * `Ok(None)` - Policy not found
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<WorkPolicy>> {
+        retry::read("work_policies.find_by_id", || async move {
+            let policy = sqlx::query_as!(
+                WorkPolicy,
+                r#"
+                SELECT id, name, standard_daily_minutes, weekly_threshold_minutes,
+                       week_start as "week_start: WeekStart",
+                       rounding_mode as "rounding_mode: RoundingMode",
+                       rounding_minutes, is_active, created_at, updated_at
+                FROM work_policies
+                WHERE id = $1
+                "#,
+                id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(policy)
+        })
+        .await
+    }
+
+    /// Find the policy currently in effect
+    ///
+    /// # Returns
+    /// * `Ok(Some(WorkPolicy))` - The active policy
+    /// * `Ok(None)` - No policy is active
+    ///
+    /// Retried after transient database errors (see [`retry::read`])
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn find_active(&self) -> Result<Option<WorkPolicy>> {
+        retry::read("work_policies.find_active", || async move {
+            let policy = sqlx::query_as!(
+                WorkPolicy,
+                r#"
+                SELECT id, name, standard_daily_minutes, weekly_threshold_minutes,
+                       week_start as "week_start: WeekStart",
+                       rounding_mode as "rounding_mode: RoundingMode",
+                       rounding_minutes, is_active, created_at, updated_at
+                FROM work_policies
+                WHERE is_active
+                "#
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            Ok(policy)
+        })
+        .await
+    }
+
+    /// Create a new, inactive work policy
+    ///
+    /// # Arguments
+    /// * `policy` - The policy creation request data
+    ///
+    /// # Returns
+    /// * `Ok(WorkPolicy)` - The created policy with generated ID and timestamps
+    ///
+    /// # Errors
+    /// Returns `AppError::ValidationError` if a value is outside the allowed range
+    /// Returns `AppError` if database query fails
+    pub async fn create(&self, policy: CreateWorkPolicy) -> Result<WorkPolicy> {
+        let created = sqlx::query_as!(
+            WorkPolicy,
+            r#"
+            INSERT INTO work_policies
+                (name, standard_daily_minutes, weekly_threshold_minutes, week_start,
+                 rounding_mode, rounding_minutes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, standard_daily_minutes, weekly_threshold_minutes,
+                      week_start as "week_start: WeekStart",
+                      rounding_mode as "rounding_mode: RoundingMode",
+                      rounding_minutes, is_active, created_at, updated_at
+            "#,
+            policy.name,
+            policy.standard_daily_minutes,
+            policy.weekly_threshold_minutes,
+            policy.week_start.as_str(),
+            policy.rounding_mode.as_str(),
+            policy.rounding_minutes
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_check_violation)?;
+
+        Ok(created)
+    }
+
+    /// Update an existing work policy
+    /// Only updates fields that are provided (Some) in the `UpdateWorkPolicy` struct
+    /// Automatically updates the `updated_at` timestamp
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the policy to update
+    /// * `policy` - The policy update request data with optional fields
+    ///
+    /// # Returns
+    /// * `Ok(WorkPolicy)` - The updated policy
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the policy does not exist
+    /// Returns `AppError::ValidationError` if a value is outside the allowed range
+    /// Returns `AppError` if database query fails
+    pub async fn update(&self, id: Uuid, policy: UpdateWorkPolicy) -> Result<WorkPolicy> {
+        let updated = sqlx::query_as!(
+            WorkPolicy,
+            r#"
+            UPDATE work_policies
+            SET
+                name = COALESCE($2, name),
+                standard_daily_minutes = COALESCE($3, standard_daily_minutes),
+                weekly_threshold_minutes = COALESCE($4, weekly_threshold_minutes),
+                week_start = COALESCE($5, week_start),
+                rounding_mode = COALESCE($6, rounding_mode),
+                rounding_minutes = COALESCE($7, rounding_minutes),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, name, standard_daily_minutes, weekly_threshold_minutes,
+                      week_start as "week_start: WeekStart",
+                      rounding_mode as "rounding_mode: RoundingMode",
+                      rounding_minutes, is_active, created_at, updated_at
+            "#,
+            id,
+            policy.name,
+            policy.standard_daily_minutes,
+            policy.weekly_threshold_minutes,
+            policy.week_start.map(WeekStart::as_str),
+            policy.rounding_mode.map(RoundingMode::as_str),
+            policy.rounding_minutes
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_check_violation)?;
+
+        updated.ok_or_else(|| AppError::NotFound(format!("Work policy with id {id} not found")))
+    }
+
+    /// Make a policy the one in effect, deactivating any other policy
+    /// Both changes are applied in a single transaction
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the policy to activate
+    ///
+    /// # Returns
+    /// * `Ok(WorkPolicy)` - The activated policy
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the policy does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn activate(&self, id: Uuid) -> Result<WorkPolicy> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE work_policies
+            SET is_active = FALSE, updated_at = CURRENT_TIMESTAMP
+            WHERE is_active AND id <> $1
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let activated = sqlx::query_as!(
+            WorkPolicy,
+            r#"
+            UPDATE work_policies
+            SET is_active = TRUE, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING id, name, standard_daily_minutes, weekly_threshold_minutes,
+                      week_start as "week_start: WeekStart",
+                      rounding_mode as "rounding_mode: RoundingMode",
+                      rounding_minutes, is_active, created_at, updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Work policy with id {id} not found")))?;
+
+        tx.commit().await?;
+
+        tracing::info!(policy_id = %id, "Activated work policy");
+        Ok(activated)
+    }
+
+    /// Delete a work policy
+    /// Deleting the active policy leaves no policy active, so the deployment
+    /// defaults apply again
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the policy to delete
+    ///
+    /// # Returns
+    /// * `Ok(())` - Policy successfully deleted
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the policy does not exist
+    /// Returns `AppError` if database query fails
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM work_policies
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Work policy with id {id} not found"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Map check constraint violations to a validation error
+fn map_check_violation(err: sqlx::Error) -> AppError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_check_violation() => AppError::ValidationError(
+            "Work policy values are outside the allowed range".to_string(),
+        ),
+        err => err.into(),
+    }
+}
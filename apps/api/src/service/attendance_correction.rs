@@ -0,0 +1,132 @@
+use super::UnitOfWork;
+use crate::error::Result;
+use crate::models::{
+    AttendanceCorrection, AttendanceEvent, CorrectionDecision, CorrectionStatus,
+    CreateAttendanceEvent,
+};
+use crate::repository::{AttendanceCorrectionRepository, AttendanceEventRepository, retry};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Decides on attendance correction requests
+#[derive(Clone)]
+pub struct CorrectionService {
+    unit_of_work: UnitOfWork,
+    corrections: AttendanceCorrectionRepository,
+    events: AttendanceEventRepository,
+}
+
+impl CorrectionService {
+    /// Create a new `CorrectionService` instance
+    #[must_use]
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            unit_of_work: UnitOfWork::new(pool.clone()),
+            corrections: AttendanceCorrectionRepository::new(pool.clone()),
+            events: AttendanceEventRepository::new(pool),
+        }
+    }
+
+    /// Approve a pending correction request
+    /// Appends an attendance event with the proposed type and time that amends the
+    /// corrected event, and records the approver and decision timestamp, all in a
+    /// single transaction
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the correction request
+    /// * `decision` - The approver and optional comment
+    ///
+    /// # Returns
+    /// * `Ok((AttendanceCorrection, AttendanceEvent))` - The approved correction and
+    ///   the appended event it links to
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the correction or approver does not exist
+    /// Returns `AppError::BadRequest` if the correction is not pending, or the
+    /// corrected event has already been amended
+    /// Returns `AppError::ValidationError` if the approver is the requester
+    /// Returns `AppError::Forbidden` if the user of the correction is deactivated
+    /// Returns `AppError` if database query fails
+    pub async fn approve(
+        &self,
+        id: Uuid,
+        decision: CorrectionDecision,
+    ) -> Result<(AttendanceCorrection, AttendanceEvent)> {
+        let (approved, event) = retry::transaction("corrections.approve", || {
+            self.unit_of_work.run(async |conn| {
+                let correction = self
+                    .corrections
+                    .lock_pending_in(conn, id, decision.approver_id)
+                    .await?;
+                let event = self
+                    .events
+                    .create_in(
+                        conn,
+                        CreateAttendanceEvent {
+                            user_id: correction.user_id,
+                            event_type: correction.proposed_event_type,
+                            event_time: correction.proposed_event_time,
+                            latitude: None,
+                            longitude: None,
+                            client_ip: None,
+                            user_agent: None,
+                            device_id: None,
+                            amends_event_id: Some(correction.event_id),
+                        },
+                    )
+                    .await?;
+                let approved = self
+                    .corrections
+                    .decide_in(
+                        conn,
+                        id,
+                        CorrectionStatus::Approved,
+                        &decision,
+                        Some(event.id),
+                    )
+                    .await?;
+                Ok((approved, event))
+            })
+        })
+        .await?;
+
+        tracing::info!(correction_id = %id, event_id = %event.id, "Approved attendance correction");
+        Ok((approved, event))
+    }
+
+    /// Reject a pending correction request
+    /// Records the approver and decision timestamp; no attendance event is appended
+    ///
+    /// # Arguments
+    /// * `id` - The UUID of the correction request
+    /// * `decision` - The approver and optional comment
+    ///
+    /// # Returns
+    /// * `Ok(AttendanceCorrection)` - The rejected correction
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the correction or approver does not exist
+    /// Returns `AppError::BadRequest` if the correction is not pending
+    /// Returns `AppError::ValidationError` if the approver is the requester
+    /// Returns `AppError` if database query fails
+    pub async fn reject(
+        &self,
+        id: Uuid,
+        decision: CorrectionDecision,
+    ) -> Result<AttendanceCorrection> {
+        let rejected = retry::transaction("corrections.reject", || {
+            self.unit_of_work.run(async |conn| {
+                self.corrections
+                    .lock_pending_in(conn, id, decision.approver_id)
+                    .await?;
+                self.corrections
+                    .decide_in(conn, id, CorrectionStatus::Rejected, &decision, None)
+                    .await
+            })
+        })
+        .await?;
+
+        tracing::info!(correction_id = %id, "Rejected attendance correction");
+        Ok(rejected)
+    }
+}
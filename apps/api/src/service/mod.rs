@@ -0,0 +1,55 @@
+//! Services for operations spanning several repositories
+//!
+//! Handlers call repositories directly for single-step operations. Operations
+//! made of several steps (e.g. approving a correction appends an event and
+//! records the decision) live in a service, which runs the steps in one
+//! [`UnitOfWork`] through the `*_in` methods of the repositories, so either
+//! every step is stored or none is.
+//!
+//! A unit of work rolled back by Postgres on a serialization failure or
+//! deadlock is run again with [`crate::repository::retry::transaction`].
+
+pub mod attendance_correction;
+
+pub use attendance_correction::CorrectionService;
+
+use crate::error::Result;
+use sqlx::{PgConnection, PgPool};
+
+/// Runs the steps of an operation in a single transaction
+#[derive(Debug, Clone)]
+pub struct UnitOfWork {
+    pool: PgPool,
+}
+
+impl UnitOfWork {
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Run `work` in a transaction, committing it if `work` succeeds and rolling
+    /// it back if it fails
+    ///
+    /// # Errors
+    /// Returns the error of `work`, or `AppError` if the transaction cannot be
+    /// started or committed
+    pub async fn run<T>(
+        &self,
+        work: impl AsyncFnOnce(&mut PgConnection) -> Result<T>,
+    ) -> Result<T> {
+        let mut tx = self.pool.begin().await?;
+        match work(&mut tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(rollback) = tx.rollback().await {
+                    tracing::warn!("Failed to roll back transaction: {rollback}");
+                }
+                Err(e)
+            }
+        }
+    }
+}
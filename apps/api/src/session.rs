@@ -0,0 +1,238 @@
+//! Cookie sessions
+//!
+//! Besides the bearer token, logging in sets a `session` cookie holding an
+//! opaque token (see [`crate::token`]), so browsers can authenticate without
+//! keeping the access token in script-readable storage. [`load_session`] looks
+//! the session up on every request and makes it available to
+//! [`crate::extract::AuthUser`]. Sessions expire after a period of inactivity;
+//! every use extends them (sliding expiration).
+//!
+//! The cookie is `SameSite=Lax`, so browsers do not send it with cross-site
+//! `POST`, `PUT` or `DELETE` requests. As a second line of defense against
+//! cross-site request forgery (e.g. from a compromised sibling subdomain),
+//! [`require_csrf_token`] rejects state-changing requests authenticated by the
+//! cookie unless they carry the session's CSRF token in the `X-CSRF-Token`
+//! header; SPAs fetch it from `GET /api/auth/csrf`.
+
+use crate::error::{AppError, ErrorCode, Result};
+use crate::extract;
+use crate::models::Session;
+use crate::repository::SessionRepository;
+use crate::token;
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Cookie holding the session token
+pub const SESSION_COOKIE: &str = "session";
+
+/// Header carrying the CSRF token of the session
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Default time a session lasts without being used (12 hours)
+const DEFAULT_TTL_MINUTES: i64 = 12 * 60;
+
+/// Longest configurable time a session lasts without being used (30 days)
+pub(crate) const MAX_TTL_MINUTES: i64 = 30 * 24 * 60;
+
+/// A session is extended at most this often, to avoid a write on every request
+const REFRESH_INTERVAL_SECONDS: i64 = 60;
+
+/// Settings of cookie sessions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSettings {
+    /// How long a session lasts without being used
+    pub ttl: Duration,
+    /// Whether the cookie is only sent over HTTPS
+    pub secure: bool,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::minutes(DEFAULT_TTL_MINUTES),
+            secure: true,
+        }
+    }
+}
+
+impl SessionSettings {
+    /// `Set-Cookie` value carrying a session token
+    #[must_use]
+    pub fn cookie(&self, token: &str) -> String {
+        format!(
+            "{SESSION_COOKIE}={token}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+            self.ttl.num_seconds(),
+            self.secure_attribute()
+        )
+    }
+
+    /// `Set-Cookie` value removing the session cookie
+    #[must_use]
+    pub fn clear_cookie(&self) -> String {
+        format!(
+            "{SESSION_COOKIE}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax{}",
+            self.secure_attribute()
+        )
+    }
+
+    const fn secure_attribute(&self) -> &'static str {
+        if self.secure { "; Secure" } else { "" }
+    }
+}
+
+/// Middleware loading the session of the `session` cookie
+///
+/// A valid session is added to the request extensions and extended, and the
+/// cookie is renewed with the new expiry. The cookie of an unknown or expired
+/// session is removed. Requests without the cookie pass through untouched.
+pub async fn load_session(
+    State(sessions): State<SessionRepository>,
+    State(settings): State<SessionSettings>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(session_token) =
+        extract::cookie(request.headers(), SESSION_COOKIE).map(str::to_string)
+    else {
+        return next.run(request).await;
+    };
+
+    let now = Utc::now();
+    let session = match find_and_extend(&sessions, &settings, &session_token, now).await {
+        Ok(session) => session,
+        Err(e) => return e.into_response(),
+    };
+    let renew = match session {
+        Some((session, extended)) => {
+            request.extensions_mut().insert(session);
+            extended.then(|| settings.cookie(&session_token))
+        }
+        None => Some(settings.clear_cookie()),
+    };
+
+    let mut response = next.run(request).await;
+    // Logging in or out sets the cookie itself
+    let sets_cookie = response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .any(|value| {
+            value
+                .as_bytes()
+                .strip_prefix(SESSION_COOKIE.as_bytes())
+                .is_some_and(|rest| rest.starts_with(b"="))
+        });
+    if let Some(cookie) = renew.filter(|_| !sets_cookie)
+        && let Ok(value) = HeaderValue::from_str(&cookie)
+    {
+        response.headers_mut().append(header::SET_COOKIE, value);
+    }
+    response
+}
+
+/// CSRF token of a session
+///
+/// Derived from the session token, so it needs no storage and changes with
+/// every login, but cannot be computed by sites that cannot read the cookie.
+#[must_use]
+pub fn csrf_token(session_token: &str) -> String {
+    token::hash_opaque(&format!("csrf:{session_token}"))
+}
+
+/// Middleware requiring the CSRF token on state-changing requests authenticated
+/// by the session cookie
+///
+/// Requests with safe methods (`GET`, `HEAD`, `OPTIONS`), with a bearer token
+/// (which browsers never attach on their own) or without a valid session pass
+/// through. Others are rejected with `Forbidden` unless the `X-CSRF-Token`
+/// header holds the session's [`csrf_token`]. Must run after [`load_session`].
+pub async fn require_csrf_token(request: Request, next: Next) -> Response {
+    let safe = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if safe
+        || extract::bearer_token(request.headers()).is_some()
+        || request.extensions().get::<Session>().is_none()
+    {
+        return next.run(request).await;
+    }
+
+    let expected = extract::cookie(request.headers(), SESSION_COOKIE).map(csrf_token);
+    let presented = request
+        .headers()
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok());
+    // Compared as hashes, so the comparison time does not reveal the token
+    let matches = match (expected, presented) {
+        (Some(expected), Some(presented)) => {
+            token::hash_opaque(&expected) == token::hash_opaque(presented.trim())
+        }
+        _ => false,
+    };
+    if !matches {
+        return AppError::Forbidden("A valid CSRF token is required".to_string())
+            .with_code(ErrorCode::CsrfTokenInvalid)
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// The active session of a token, and whether it was just extended
+async fn find_and_extend(
+    sessions: &SessionRepository,
+    settings: &SessionSettings,
+    session_token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(Session, bool)>> {
+    let Some(mut session) = sessions
+        .find_active(&token::hash_opaque(session_token), now)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    if now - session.last_seen_at < Duration::seconds(REFRESH_INTERVAL_SECONDS) {
+        return Ok(Some((session, false)));
+    }
+    Ok(sessions
+        .touch(session.id, now, settings.ttl)
+        .await?
+        .map(|expires_at| {
+            session.last_seen_at = now;
+            session.expires_at = expires_at;
+            (session, true)
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie() {
+        let mut settings = SessionSettings {
+            ttl: Duration::minutes(30),
+            secure: true,
+        };
+
+        assert_eq!(
+            settings.cookie("abc"),
+            "session=abc; Path=/; Max-Age=1800; HttpOnly; SameSite=Lax; Secure"
+        );
+        assert!(
+            settings
+                .clear_cookie()
+                .starts_with("session=; Path=/; Max-Age=0;")
+        );
+
+        settings.secure = false;
+        assert!(!settings.cookie("abc").contains("Secure"));
+    }
+}
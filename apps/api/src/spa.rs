@@ -0,0 +1,40 @@
+//! Serving the single-page app (SPA) frontend
+//!
+//! Small deployments can run the API and the frontend in one container: with
+//! `spa.static_dir` (`STATIC_DIR`) set to the frontend build (e.g. `dist/`), the router serves
+//! its files for every path no route matches. Paths without a file
+//! (client-side routes such as `/attendance/2025-11`) get `index.html`, so the
+//! SPA's router handles them. Unknown `/api/...` paths still answer `404`.
+
+use crate::error::{AppError, Result};
+use axum::http::Uri;
+use std::path::PathBuf;
+use tower_http::services::{ServeDir, ServeFile};
+
+/// Page served for paths without a file
+pub(crate) const INDEX_FILE: &str = "index.html";
+
+/// Directory of the frontend build (the `[spa]` configuration section)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpaSettings {
+    /// `None` serves no frontend
+    pub dir: Option<PathBuf>,
+}
+
+impl SpaSettings {
+    /// Service serving the frontend files, falling back to `index.html`, or
+    /// `None` when no frontend is configured
+    #[must_use]
+    pub fn service(&self) -> Option<ServeDir<ServeFile>> {
+        let dir = self.dir.as_ref()?;
+        Some(ServeDir::new(dir).fallback(ServeFile::new(dir.join(INDEX_FILE))))
+    }
+}
+
+/// Answer unknown `/api/...` paths with `404` instead of the SPA's index page
+///
+/// # Errors
+/// Always returns `AppError::NotFound`.
+pub async fn api_not_found(uri: Uri) -> Result<()> {
+    Err(AppError::NotFound(format!("No route for {}", uri.path())))
+}
@@ -0,0 +1,160 @@
+use crate::attendance::anomaly::AnomalyRules;
+use crate::attendance::event_time::EventTimeWindow;
+use crate::attendance::overtime::OvertimePolicy;
+use crate::auth::AuthTokens;
+use crate::body_limit::BodyLimits;
+use crate::compression::CompressionSettings;
+use crate::config::AppConfig;
+use crate::cors::CorsSettings;
+use crate::db::ReadPool;
+use crate::extract::{AdminApiKey, TrustedProxies};
+use crate::health::HealthCheck;
+use crate::kiosk::KioskTokens;
+use crate::load_shed::LoadShedding;
+use crate::mail::Mailer;
+use crate::oauth::GoogleOAuth;
+use crate::password::{LockoutPolicy, ResetSettings};
+use crate::pool_stats::PoolMonitor;
+use crate::presence::PresenceHub;
+use crate::problem::ErrorFormat;
+use crate::rate_limit::RateLimiter;
+use crate::repository::{
+    ApiKeyRepository, AttendanceAnomalyRepository, AttendanceCorrectionRepository,
+    AttendanceEventRepository, HolidayRepository, IdempotencyKeyRepository, InvitationRepository,
+    PasswordResetRepository, PermissionRepository, SessionRepository, UserRepository,
+    WebhookRepository, WorkPolicyRepository,
+};
+use crate::service::CorrectionService;
+use crate::session::SessionSettings;
+use crate::spa::SpaSettings;
+use crate::storage::Storage;
+use crate::store::TodoStore;
+use crate::timeout::Timeouts;
+use crate::webhook::WebhookDispatcher;
+use axum::extract::FromRef;
+use sqlx::PgPool;
+
+/// Shared state of the application router
+///
+/// Handlers extract only the part they need (e.g. `State<UserRepository>`)
+/// through the derived `FromRef` implementations.
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub todo_store: TodoStore,
+    pub users: UserRepository,
+    pub attendance_events: AttendanceEventRepository,
+    pub attendance_corrections: AttendanceCorrectionRepository,
+    pub correction_service: CorrectionService,
+    pub attendance_anomalies: AttendanceAnomalyRepository,
+    pub work_policies: WorkPolicyRepository,
+    pub holidays: HolidayRepository,
+    pub invitations: InvitationRepository,
+    pub password_resets: PasswordResetRepository,
+    pub sessions: SessionRepository,
+    pub api_keys: ApiKeyRepository,
+    pub idempotency_keys: IdempotencyKeyRepository,
+    pub permissions: PermissionRepository,
+    pub webhooks: WebhookRepository,
+    pub webhook_dispatcher: WebhookDispatcher,
+    pub presence: PresenceHub,
+    pub health: HealthCheck,
+    pub pool_monitor: PoolMonitor,
+    pub overtime_policy: OvertimePolicy,
+    pub anomaly_rules: AnomalyRules,
+    pub event_time_window: EventTimeWindow,
+    pub admin_api_key: AdminApiKey,
+    pub trusted_proxies: TrustedProxies,
+    pub kiosk_tokens: KioskTokens,
+    pub auth_tokens: AuthTokens,
+    pub password_reset: ResetSettings,
+    pub lockout_policy: LockoutPolicy,
+    pub session_settings: SessionSettings,
+    pub rate_limiter: RateLimiter,
+    pub error_format: ErrorFormat,
+    pub cors: CorsSettings,
+    pub compression: CompressionSettings,
+    pub body_limits: BodyLimits,
+    pub timeouts: Timeouts,
+    pub load_shedding: LoadShedding,
+    pub mailer: Mailer,
+    pub storage: Storage,
+    pub spa: SpaSettings,
+    /// `None` unless Google sign-in is configured
+    pub google_oauth: Option<GoogleOAuth>,
+}
+
+impl AppState {
+    /// Create the application state from the todo store and database pool
+    ///
+    /// Deployment-specific policies take their defaults; tests override them
+    /// on the returned state.
+    #[must_use]
+    pub fn new(store: TodoStore, pool: PgPool) -> Self {
+        Self::from_config(
+            &AppConfig::default(),
+            store,
+            pool.clone(),
+            ReadPool::primary(pool),
+        )
+    }
+
+    /// Create the application state from the loaded configuration, running
+    /// lists, reports and exports on `read_pool` (e.g. a read replica)
+    #[must_use]
+    pub fn from_config(
+        config: &AppConfig,
+        store: TodoStore,
+        pool: PgPool,
+        read_pool: ReadPool,
+    ) -> Self {
+        if config.google.is_none() {
+            tracing::info!("Google sign-in is disabled: the [google] section is not configured");
+        }
+        Self {
+            todo_store: store,
+            users: UserRepository::new(pool.clone()).with_read_pool(read_pool.clone()),
+            attendance_events: AttendanceEventRepository::new(pool.clone())
+                .with_read_pool(read_pool.clone()),
+            attendance_corrections: AttendanceCorrectionRepository::new(pool.clone()),
+            correction_service: CorrectionService::new(pool.clone()),
+            attendance_anomalies: AttendanceAnomalyRepository::new(pool.clone()),
+            work_policies: WorkPolicyRepository::new(pool.clone()),
+            holidays: HolidayRepository::new(pool.clone()).with_read_pool(read_pool),
+            invitations: InvitationRepository::new(pool.clone()),
+            password_resets: PasswordResetRepository::new(pool.clone()),
+            sessions: SessionRepository::new(pool.clone()),
+            api_keys: ApiKeyRepository::new(pool.clone()),
+            idempotency_keys: IdempotencyKeyRepository::new(pool.clone()),
+            permissions: PermissionRepository::new(pool.clone()),
+            webhooks: WebhookRepository::new(pool.clone()),
+            health: HealthCheck::new(pool.clone()),
+            pool_monitor: PoolMonitor::new(pool.clone()),
+            webhook_dispatcher: WebhookDispatcher::from_settings(
+                WebhookRepository::new(pool),
+                &config.webhooks,
+            ),
+            presence: PresenceHub::from_settings(&config.presence),
+            overtime_policy: config.overtime,
+            anomaly_rules: AnomalyRules::from_settings(&config.anomalies),
+            event_time_window: config.event_time,
+            admin_api_key: AdminApiKey::new(config.auth.admin_api_key.as_deref()),
+            trusted_proxies: config.server.trusted_proxies.clone(),
+            kiosk_tokens: KioskTokens::from_settings(&config.kiosk),
+            auth_tokens: AuthTokens::from_settings(&config.auth.tokens),
+            password_reset: config.password_reset.clone(),
+            lockout_policy: config.lockout,
+            session_settings: config.session.clone(),
+            rate_limiter: RateLimiter::from_settings(&config.rate_limit),
+            error_format: config.server.error_format,
+            cors: config.cors.clone(),
+            compression: config.compression,
+            body_limits: config.body_limits,
+            timeouts: config.timeouts,
+            load_shedding: config.load_shedding,
+            mailer: Mailer::default(),
+            storage: Storage::from_settings(&config.uploads),
+            spa: config.spa.clone(),
+            google_oauth: config.google.clone().map(GoogleOAuth::new),
+        }
+    }
+}
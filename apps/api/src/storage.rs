@@ -0,0 +1,133 @@
+//! File storage for uploads (e.g. user avatars)
+//!
+//! Uploads go through [`Storage`], a cheap-to-clone handle over a
+//! [`StorageBackend`]. The default backend, [`LocalStorage`], writes files to a
+//! local directory that the router serves under [`LOCAL_UPLOADS_PATH`]; other
+//! backends (e.g. an object store) only need to implement [`StorageBackend`].
+
+use crate::error::Result;
+use futures_util::future::BoxFuture;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Route under which the router serves files of a [`LocalStorage`]
+pub const LOCAL_UPLOADS_PATH: &str = "/uploads";
+
+/// Default directory of a [`LocalStorage`]
+const DEFAULT_UPLOAD_DIR: &str = "uploads";
+
+/// A place uploaded files are stored
+pub trait StorageBackend: Send + Sync {
+    /// Store `data` under `key` (a relative path such as `avatars/<id>.png`),
+    /// replacing any file with the same key
+    ///
+    /// Returns the public URL of the stored file.
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BoxFuture<'a, Result<String>>;
+
+    /// Local directory the router should serve under [`LOCAL_UPLOADS_PATH`]
+    /// (`None` if files are served elsewhere)
+    fn local_dir(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Stores files in a local directory
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    dir: PathBuf,
+    base_url: String,
+}
+
+impl LocalStorage {
+    /// Create a storage writing to `dir`, whose files are reachable under `base_url`
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, base_url: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl StorageBackend for LocalStorage {
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let path = self.dir.join(key);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, data).await?;
+            Ok(format!("{}/{key}", self.base_url))
+        })
+    }
+
+    fn local_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+}
+
+/// Directory and public URL of uploaded files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadSettings {
+    pub dir: PathBuf,
+    /// URL the files of `dir` are reachable under (by default served by this API)
+    pub base_url: String,
+}
+
+impl Default for UploadSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(DEFAULT_UPLOAD_DIR),
+            base_url: LOCAL_UPLOADS_PATH.to_string(),
+        }
+    }
+}
+
+/// Shared handle to the configured storage backend
+#[derive(Clone)]
+pub struct Storage(Arc<dyn StorageBackend>);
+
+impl Storage {
+    #[must_use]
+    pub fn new(backend: impl StorageBackend + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+
+    /// Local storage in the configured directory
+    #[must_use]
+    pub fn from_settings(settings: &UploadSettings) -> Self {
+        Self::new(LocalStorage::new(&settings.dir, &settings.base_url))
+    }
+
+    /// Store a file and return its public URL
+    ///
+    /// # Errors
+    /// Returns error if the backend fails to store the file
+    pub async fn put(&self, key: &str, data: &[u8]) -> Result<String> {
+        self.0.put(key, data).await
+    }
+
+    /// See [`StorageBackend::local_dir`]
+    #[must_use]
+    pub fn local_dir(&self) -> Option<&Path> {
+        self.0.local_dir()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_storage_writes_file_and_returns_url() {
+        let dir = std::env::temp_dir().join(format!("storage-test-{}", uuid::Uuid::new_v4()));
+        let storage = Storage::new(LocalStorage::new(&dir, "https://cdn.example.com/files/"));
+
+        let url = storage.put("avatars/a.png", b"data").await.unwrap();
+
+        assert_eq!(url, "https://cdn.example.com/files/avatars/a.png");
+        assert_eq!(std::fs::read(dir.join("avatars/a.png")).unwrap(), b"data");
+        assert_eq!(storage.local_dir(), Some(dir.as_path()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
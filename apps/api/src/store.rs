@@ -1,115 +1,691 @@
-use crate::models::Todo;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+//! Todo storage
+//!
+//! Handlers go through [`TodoStore`], a cheap-to-clone handle over a
+//! [`TodoBackend`]. The default backend, [`InMemoryTodoBackend`], keeps todos in
+//! memory; other backends (e.g. Postgres or Redis) only need to implement
+//! [`TodoBackend`].
 
-/// インメモリのTodoデータストア
-#[derive(Debug, Clone)]
-pub struct TodoStore {
-    todos: Arc<Mutex<HashMap<u64, Todo>>>,
-    next_id: Arc<Mutex<u64>>,
+use crate::error::{AppError, Result};
+use crate::models::{
+    CreateTodoRequest, ExportedTodo, ImportConflict, ImportOutcome, ImportedTodo, SortOrder, Todo,
+    TodoItem, TodoPriority, TodoSort, UpdateTodoItemRequest, UpdateTodoRequest,
+};
+use crate::pagination::OffsetPagination;
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
+
+/// Todo 一覧の絞り込み条件
+/// `None` の条件はすべての Todo に一致する
+#[derive(Debug, Clone, Default)]
+pub struct TodoFilter {
+    /// Only todos of this user
+    pub owner_id: Option<Uuid>,
+    pub priority: Option<TodoPriority>,
+    /// Only todos with this tag
+    pub tag: Option<String>,
+    /// Only todos that are (`true`) or are not (`false`) completed
+    pub completed: Option<bool>,
+    /// Only archived (`true`) or only unarchived (`false`, the default) todos
+    pub archived: bool,
+    /// Only todos whose title or description contains every word of this text,
+    /// ignoring case
+    pub q: Option<String>,
+}
+
+impl TodoFilter {
+    /// Whether a todo meets every condition of the filter
+    #[must_use]
+    pub fn matches(&self, todo: &Todo) -> bool {
+        self.owner_id
+            .is_none_or(|owner_id| todo.owner_id == owner_id)
+            && self
+                .priority
+                .is_none_or(|priority| todo.priority == priority)
+            && self.tag.as_ref().is_none_or(|tag| todo.tags.contains(tag))
+            && self
+                .completed
+                .is_none_or(|completed| todo.completed == completed)
+            && todo.archived == self.archived
+            && self.q.as_deref().is_none_or(|q| matches_search(todo, q))
+    }
+}
+
+/// Whether every word of a search text appears in the title or description
+fn matches_search(todo: &Todo, q: &str) -> bool {
+    let title = todo.title.to_lowercase();
+    let description = todo
+        .description
+        .as_deref()
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    q.to_lowercase()
+        .split_whitespace()
+        .all(|word| title.contains(word) || description.contains(word))
+}
+
+/// Compare two todos by a sort key in a direction
+/// Todos without a due date come last when sorting by due date, whatever the
+/// direction; ties are broken by ID (creation order).
+fn compare(a: &Todo, b: &Todo, sort: TodoSort, order: SortOrder) -> Ordering {
+    let directed = |ordering: Ordering| match order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    };
+    let ordering = match sort {
+        TodoSort::Priority => directed(a.priority.cmp(&b.priority)),
+        TodoSort::Created => directed(a.id.cmp(&b.id)),
+        TodoSort::Title => directed(a.title.to_lowercase().cmp(&b.title.to_lowercase())),
+        TodoSort::DueDate => match (a.due_date, b.due_date) {
+            (Some(a), Some(b)) => directed(a.cmp(&b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    };
+    ordering.then(a.id.cmp(&b.id))
+}
+
+/// Trim tags and drop repeated ones, keeping the first occurrence
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    tags.into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| seen.insert(tag.clone()))
+        .collect()
 }
 
+/// A place todos are kept
+///
+/// Every method returns the updated todo, or `None` if there is no todo (or
+/// checklist item) with the ID. Tags are trimmed and repeated tags dropped on
+/// create and update.
+pub trait TodoBackend: Send + Sync {
+    /// Get a page of the todos matching a filter, sorted by a key in a direction,
+    /// and the total number of matching todos
+    /// Todos with equal keys are in creation order
+    fn get_all<'a>(
+        &'a self,
+        filter: &'a TodoFilter,
+        sort: TodoSort,
+        order: SortOrder,
+        pagination: OffsetPagination,
+    ) -> BoxFuture<'a, Result<(Vec<Todo>, u64)>>;
+
+    /// Get a `Todo` by ID
+    fn get_by_id(&self, id: u64) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Get every todo of a user, archived or not, in creation order
+    fn get_by_owner(&self, owner_id: Uuid) -> BoxFuture<'_, Result<Vec<Todo>>>;
+
+    /// Get every distinct tag used by a todo of `owner_id` (`None` = all
+    /// todos), sorted
+    fn get_tags(&self, owner_id: Option<Uuid>) -> BoxFuture<'_, Result<Vec<String>>>;
+
+    /// Create a new `Todo`
+    fn create(&self, owner_id: Uuid, request: CreateTodoRequest) -> BoxFuture<'_, Result<Todo>>;
+
+    /// Update a `Todo`
+    /// Only the fields given in the request change; given tags replace the
+    /// existing ones
+    fn update(&self, id: u64, request: UpdateTodoRequest) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Add a checklist item, not done, to a `Todo`
+    fn add_item(&self, todo_id: u64, text: String) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Update a checklist item of a `Todo`
+    /// Only the fields given in the request change
+    fn update_item(
+        &self,
+        todo_id: u64,
+        item_id: u64,
+        request: UpdateTodoItemRequest,
+    ) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Delete a checklist item of a `Todo`
+    fn delete_item(&self, todo_id: u64, item_id: u64) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Archive or unarchive a `Todo`
+    fn set_archived(&self, id: u64, archived: bool) -> BoxFuture<'_, Result<Option<Todo>>>;
+
+    /// Mark the todos of a user among `ids` as completed, all at once
+    ///
+    /// Returns the IDs of the completed todos and the IDs with no todo of the
+    /// user, each in request order.
+    fn complete_many<'a>(
+        &'a self,
+        owner_id: Uuid,
+        ids: &'a [u64],
+    ) -> BoxFuture<'a, Result<(Vec<u64>, Vec<u64>)>>;
+
+    /// Delete the todos of a user among `ids`, all at once
+    ///
+    /// Returns the IDs of the deleted todos and the IDs with no todo of the
+    /// user, each in request order.
+    fn delete_many<'a>(
+        &'a self,
+        owner_id: Uuid,
+        ids: &'a [u64],
+    ) -> BoxFuture<'a, Result<(Vec<u64>, Vec<u64>)>>;
+
+    /// Add exported todos to those of a user under new IDs, all at once
+    ///
+    /// An exported todo conflicts with a todo of the user with the same title,
+    /// including one imported before it; `on_conflict` decides what happens.
+    /// Returns the outcome for each exported todo, in order.
+    fn import(
+        &self,
+        owner_id: Uuid,
+        todos: Vec<ExportedTodo>,
+        on_conflict: ImportConflict,
+    ) -> BoxFuture<'_, Result<Vec<ImportedTodo>>>;
+
+    /// Delete a `Todo`
+    ///
+    /// Returns whether there was a todo with the ID.
+    fn delete(&self, id: u64) -> BoxFuture<'_, Result<bool>>;
+}
+
+/// Shared handle to the configured todo backend
+#[derive(Clone)]
+pub struct TodoStore(Arc<dyn TodoBackend>);
+
 impl TodoStore {
-    /// Create a new `TodoStore`
+    /// Create a store keeping todos in memory
     #[must_use]
     pub fn new() -> Self {
+        Self::with_backend(InMemoryTodoBackend::new())
+    }
+
+    #[must_use]
+    pub fn with_backend(backend: impl TodoBackend + 'static) -> Self {
+        Self(Arc::new(backend))
+    }
+}
+
+impl Default for TodoStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for TodoStore {
+    type Target = dyn TodoBackend;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+/// インメモリのTodoデータストア
+///
+/// IDs are allocated in increasing order, so the map iterates in creation order.
+/// Every operation fails with `InternalServerError` instead of panicking once
+/// the lock is poisoned by a panic while it was held for writing.
+#[derive(Debug)]
+pub struct InMemoryTodoBackend {
+    todos: RwLock<BTreeMap<u64, Todo>>,
+    next_id: AtomicU64,
+    /// Checklist item IDs are unique across todos and never reused
+    next_item_id: AtomicU64,
+}
+
+fn lock_poisoned() -> AppError {
+    AppError::InternalServerError("Todo store lock is poisoned".to_string())
+}
+
+impl InMemoryTodoBackend {
+    #[must_use]
+    pub const fn new() -> Self {
         Self {
-            todos: Arc::new(Mutex::new(HashMap::new())),
-            next_id: Arc::new(Mutex::new(1)),
+            todos: RwLock::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            next_item_id: AtomicU64::new(1),
         }
     }
 
-    /// Get all todos
+    /// Lock the todos for reading
     ///
-    /// # Panics
-    /// Panics if the mutex is poisoned
-    #[must_use]
-    pub fn get_all(&self) -> Vec<Todo> {
-        let todos = self.todos.lock().unwrap();
-        todos.values().cloned().collect()
+    /// # Errors
+    /// Returns `InternalServerError` if the lock is poisoned
+    fn read(&self) -> Result<RwLockReadGuard<'_, BTreeMap<u64, Todo>>> {
+        self.todos.read().map_err(|_| lock_poisoned())
     }
 
-    /// Get a `Todo` by ID
+    /// Lock the todos for writing
     ///
-    /// # Panics
-    /// Panics if the mutex is poisoned
-    #[must_use]
-    pub fn get_by_id(&self, id: u64) -> Option<Todo> {
-        let todos = self.todos.lock().unwrap();
-        todos.get(&id).cloned()
+    /// # Errors
+    /// Returns `InternalServerError` if the lock is poisoned
+    fn write(&self) -> Result<RwLockWriteGuard<'_, BTreeMap<u64, Todo>>> {
+        self.todos.write().map_err(|_| lock_poisoned())
     }
 
-    /// Create a new `Todo`
+    /// Apply a change to a todo under the write lock, stamping `updated_at`
     ///
-    /// # Panics
-    /// Panics if the mutex is poisoned
-    pub fn create(&self, title: String, description: Option<String>) -> Todo {
-        let mut next_id = self.next_id.lock().unwrap();
-        let id = *next_id;
-        *next_id += 1;
-        drop(next_id);
-
-        let todo = Todo {
+    /// Returns the updated todo, or `None` if there is no todo with the ID or
+    /// `change` returns `None` (e.g. for a missing checklist item).
+    fn modify(
+        &self,
+        id: u64,
+        change: impl FnOnce(&mut Todo, DateTime<Utc>) -> Option<()>,
+    ) -> Result<Option<Todo>> {
+        let mut todos = self.write()?;
+        let now = Utc::now();
+        Ok(todos.get_mut(&id).and_then(|todo| {
+            change(todo, now)?;
+            todo.updated_at = now;
+            Some(todo.clone())
+        }))
+    }
+
+    /// Build the todo restored from an exported one, with new item IDs
+    fn restore(&self, id: u64, owner_id: Uuid, exported: ExportedTodo, now: DateTime<Utc>) -> Todo {
+        Todo {
             id,
-            title,
-            description,
-            completed: false,
-        };
+            owner_id,
+            title: exported.title,
+            description: exported.description,
+            completed: exported.completed,
+            archived: exported.archived,
+            priority: exported.priority,
+            tags: normalize_tags(exported.tags),
+            due_date: exported.due_date,
+            items: exported
+                .items
+                .into_iter()
+                .map(|item| TodoItem {
+                    id: self.next_item_id.fetch_add(1, AtomicOrdering::Relaxed),
+                    text: item.text,
+                    done: item.done,
+                })
+                .collect(),
+            created_at: exported.created_at.unwrap_or(now),
+            updated_at: now,
+            completed_at: exported
+                .completed
+                .then(|| exported.completed_at.unwrap_or(now)),
+        }
+    }
 
-        self.todos.lock().unwrap().insert(id, todo.clone());
+    /// Split `ids` into the IDs of todos of a user and the others, each in order
+    fn partition_owned(
+        todos: &BTreeMap<u64, Todo>,
+        owner_id: Uuid,
+        ids: &[u64],
+    ) -> (Vec<u64>, Vec<u64>) {
+        ids.iter()
+            .partition(|id| todos.get(id).is_some_and(|todo| todo.owner_id == owner_id))
+    }
+}
 
-        tracing::info!(todo_id = id, "Created new todo");
-        todo
+impl Default for InMemoryTodoBackend {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    /// Update a `Todo`
-    ///
-    /// # Panics
-    /// Panics if the mutex is poisoned
-    pub fn update(
+impl TodoBackend for InMemoryTodoBackend {
+    fn get_all<'a>(
+        &'a self,
+        filter: &'a TodoFilter,
+        sort: TodoSort,
+        order: SortOrder,
+        pagination: OffsetPagination,
+    ) -> BoxFuture<'a, Result<(Vec<Todo>, u64)>> {
+        Box::pin(async move {
+            let todos = self.read()?;
+            let mut matching: Vec<&Todo> =
+                todos.values().filter(|todo| filter.matches(todo)).collect();
+            matching.sort_by(|a, b| compare(a, b, sort, order));
+
+            let total = matching.len() as u64;
+            let page = matching
+                .into_iter()
+                .skip(usize::try_from(pagination.offset).unwrap_or(usize::MAX))
+                .take(pagination.limit as usize)
+                .cloned()
+                .collect();
+            drop(todos);
+            Ok((page, total))
+        })
+    }
+
+    fn get_by_id(&self, id: u64) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move { Ok(self.read()?.get(&id).cloned()) })
+    }
+
+    fn get_by_owner(&self, owner_id: Uuid) -> BoxFuture<'_, Result<Vec<Todo>>> {
+        Box::pin(async move {
+            Ok(self
+                .read()?
+                .values()
+                .filter(|todo| todo.owner_id == owner_id)
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn get_tags(&self, owner_id: Option<Uuid>) -> BoxFuture<'_, Result<Vec<String>>> {
+        Box::pin(async move {
+            let todos = self.read()?;
+            let tags: BTreeSet<&String> = todos
+                .values()
+                .filter(|todo| owner_id.is_none_or(|owner_id| todo.owner_id == owner_id))
+                .flat_map(|todo| &todo.tags)
+                .collect();
+            let tags = tags.into_iter().cloned().collect();
+            drop(todos);
+            Ok(tags)
+        })
+    }
+
+    fn create(&self, owner_id: Uuid, request: CreateTodoRequest) -> BoxFuture<'_, Result<Todo>> {
+        Box::pin(async move {
+            // Allocate the ID while holding the write lock, so todos are
+            // inserted in ID order and a reader never sees a gap being filled
+            let mut todos = self.write()?;
+            let id = self.next_id.fetch_add(1, AtomicOrdering::Relaxed);
+            let now = Utc::now();
+
+            let todo = Todo {
+                id,
+                owner_id,
+                title: request.title,
+                description: request.description,
+                completed: false,
+                archived: false,
+                priority: request.priority,
+                tags: normalize_tags(request.tags),
+                due_date: request.due_date,
+                items: Vec::new(),
+                created_at: now,
+                updated_at: now,
+                completed_at: None,
+            };
+            todos.insert(id, todo.clone());
+            drop(todos);
+
+            tracing::info!(todo_id = id, "Created new todo");
+            Ok(todo)
+        })
+    }
+
+    fn update(&self, id: u64, request: UpdateTodoRequest) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move {
+            let todo = self.modify(id, |todo, now| {
+                if let Some(t) = request.title {
+                    todo.title = t;
+                }
+                if let Some(d) = request.description {
+                    todo.description = d;
+                }
+                if let Some(c) = request.completed {
+                    todo.set_completed(c, now);
+                }
+                if let Some(p) = request.priority {
+                    todo.priority = p;
+                }
+                if let Some(t) = request.tags {
+                    todo.tags = normalize_tags(t);
+                }
+                if let Some(d) = request.due_date {
+                    todo.due_date = d;
+                }
+                Some(())
+            })?;
+
+            if todo.is_some() {
+                tracing::info!(todo_id = id, "Updated todo");
+            }
+            Ok(todo)
+        })
+    }
+
+    fn add_item(&self, todo_id: u64, text: String) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move {
+            self.modify(todo_id, |todo, _| {
+                let id = self.next_item_id.fetch_add(1, AtomicOrdering::Relaxed);
+                todo.items.push(TodoItem {
+                    id,
+                    text,
+                    done: false,
+                });
+                tracing::info!(todo_id, item_id = id, "Added todo item");
+                Some(())
+            })
+        })
+    }
+
+    fn update_item(
         &self,
-        id: u64,
-        title: Option<String>,
-        description: Option<String>,
-        completed: Option<bool>,
-    ) -> Option<Todo> {
-        let mut todos = self.todos.lock().unwrap();
-
-        if let Some(todo) = todos.get_mut(&id) {
-            if let Some(t) = title {
-                todo.title = t;
+        todo_id: u64,
+        item_id: u64,
+        request: UpdateTodoItemRequest,
+    ) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move {
+            self.modify(todo_id, |todo, _| {
+                let item = todo.items.iter_mut().find(|item| item.id == item_id)?;
+                if let Some(text) = request.text {
+                    item.text = text;
+                }
+                if let Some(done) = request.done {
+                    item.done = done;
+                }
+                tracing::info!(todo_id, item_id, "Updated todo item");
+                Some(())
+            })
+        })
+    }
+
+    fn delete_item(&self, todo_id: u64, item_id: u64) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move {
+            self.modify(todo_id, |todo, _| {
+                let position = todo.items.iter().position(|item| item.id == item_id)?;
+                todo.items.remove(position);
+                tracing::info!(todo_id, item_id, "Deleted todo item");
+                Some(())
+            })
+        })
+    }
+
+    fn set_archived(&self, id: u64, archived: bool) -> BoxFuture<'_, Result<Option<Todo>>> {
+        Box::pin(async move {
+            self.modify(id, |todo, _| {
+                todo.archived = archived;
+                tracing::info!(todo_id = id, archived, "Changed todo archive state");
+                Some(())
+            })
+        })
+    }
+
+    fn complete_many<'a>(
+        &'a self,
+        owner_id: Uuid,
+        ids: &'a [u64],
+    ) -> BoxFuture<'a, Result<(Vec<u64>, Vec<u64>)>> {
+        Box::pin(async move {
+            let mut todos = self.write()?;
+
+            let (updated, not_found) = Self::partition_owned(&todos, owner_id, ids);
+            let now = Utc::now();
+            for id in &updated {
+                if let Some(todo) = todos.get_mut(id) {
+                    todo.set_completed(true, now);
+                    todo.updated_at = now;
+                }
             }
-            if let Some(d) = description {
-                todo.description = Some(d);
+
+            tracing::info!(count = updated.len(), "Completed todos in bulk");
+            Ok((updated, not_found))
+        })
+    }
+
+    fn delete_many<'a>(
+        &'a self,
+        owner_id: Uuid,
+        ids: &'a [u64],
+    ) -> BoxFuture<'a, Result<(Vec<u64>, Vec<u64>)>> {
+        Box::pin(async move {
+            let mut todos = self.write()?;
+
+            let (deleted, not_found) = Self::partition_owned(&todos, owner_id, ids);
+            for id in &deleted {
+                todos.remove(id);
             }
-            if let Some(c) = completed {
-                todo.completed = c;
+            drop(todos);
+
+            tracing::info!(count = deleted.len(), "Deleted todos in bulk");
+            Ok((deleted, not_found))
+        })
+    }
+
+    fn import(
+        &self,
+        owner_id: Uuid,
+        imported: Vec<ExportedTodo>,
+        on_conflict: ImportConflict,
+    ) -> BoxFuture<'_, Result<Vec<ImportedTodo>>> {
+        Box::pin(async move {
+            let mut todos = self.write()?;
+            let now = Utc::now();
+
+            let mut results = Vec::with_capacity(imported.len());
+            for exported in imported {
+                let old_id = exported.id;
+                let existing = todos
+                    .values()
+                    .find(|todo| todo.owner_id == owner_id && todo.title == exported.title)
+                    .map(|todo| todo.id);
+                let (new_id, outcome) = match (existing, on_conflict) {
+                    (Some(id), ImportConflict::Skip) => (id, ImportOutcome::Skipped),
+                    (Some(id), ImportConflict::Replace) => (id, ImportOutcome::Replaced),
+                    (None, _) | (Some(_), ImportConflict::KeepBoth) => (
+                        self.next_id.fetch_add(1, AtomicOrdering::Relaxed),
+                        ImportOutcome::Created,
+                    ),
+                };
+                if outcome != ImportOutcome::Skipped {
+                    todos.insert(new_id, self.restore(new_id, owner_id, exported, now));
+                }
+                results.push(ImportedTodo {
+                    old_id,
+                    new_id,
+                    outcome,
+                });
             }
+            drop(todos);
 
-            tracing::info!(todo_id = id, "Updated todo");
-            Some(todo.clone())
-        } else {
-            None
-        }
+            tracing::info!(count = results.len(), "Imported todos");
+            Ok(results)
+        })
     }
 
-    /// Delete a `Todo`
-    ///
-    /// # Panics
-    /// Panics if the mutex is poisoned
-    pub fn delete(&self, id: u64) -> bool {
-        let mut todos = self.todos.lock().unwrap();
-        if todos.remove(&id).is_some() {
-            tracing::info!(todo_id = id, "Deleted todo");
-            true
-        } else {
-            false
-        }
+    fn delete(&self, id: u64) -> BoxFuture<'_, Result<bool>> {
+        Box::pin(async move {
+            let deleted = self.write()?.remove(&id).is_some();
+            if deleted {
+                tracing::info!(todo_id = id, "Deleted todo");
+            }
+            Ok(deleted)
+        })
     }
 }
 
-impl Default for TodoStore {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_store_uses_its_backend() {
+        let store = TodoStore::with_backend(InMemoryTodoBackend::new());
+        let request = CreateTodoRequest {
+            title: "Swap me".to_string(),
+            description: None,
+            priority: TodoPriority::default(),
+            tags: vec![" a ".to_string(), "a".to_string()],
+            due_date: None,
+        };
+
+        let created = store.create(Uuid::new_v4(), request).await.unwrap();
+        let found = store.get_by_id(created.id).await.unwrap().unwrap();
+
+        assert_eq!(found.title, "Swap me");
+        assert_eq!(found.tags, vec!["a"]);
+        assert!(store.delete(created.id).await.unwrap());
+        assert!(store.get_by_id(created.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_concurrent_creates_get_unique_ids() {
+        const TASKS: u64 = 64;
+        const TODOS_PER_TASK: u64 = 50;
+
+        let store = TodoStore::new();
+        let owner_id = Uuid::new_v4();
+        let tasks: Vec<_> = (0..TASKS)
+            .map(|task| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    let mut ids = Vec::new();
+                    for n in 0..TODOS_PER_TASK {
+                        let request = CreateTodoRequest {
+                            title: format!("Task {task} todo {n}"),
+                            description: None,
+                            priority: TodoPriority::default(),
+                            tags: Vec::new(),
+                            due_date: None,
+                        };
+                        ids.push(store.create(owner_id, request).await.unwrap().id);
+                        tokio::task::yield_now().await;
+                    }
+                    ids
+                })
+            })
+            .collect();
+
+        let mut ids = Vec::new();
+        for task in tasks {
+            let task_ids = task.await.unwrap();
+            // IDs increase in the order each task created its todos
+            assert!(task_ids.is_sorted());
+            ids.extend(task_ids);
+        }
+        ids.sort_unstable();
+
+        let count = TASKS * TODOS_PER_TASK;
+        assert_eq!(ids, (1..=count).collect::<Vec<_>>());
+        let stored: Vec<u64> = store
+            .get_by_owner(owner_id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|todo| todo.id)
+            .collect();
+        assert_eq!(stored, ids);
+    }
+
+    #[tokio::test]
+    async fn test_poisoned_lock_is_an_error() {
+        let backend = Arc::new(InMemoryTodoBackend::new());
+        let poisoner = Arc::clone(&backend);
+        let _ = std::thread::spawn(move || {
+            let _todos = poisoner.todos.write().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        assert!(matches!(
+            backend.get_by_id(1).await,
+            Err(AppError::InternalServerError(_))
+        ));
+        assert!(matches!(
+            backend.delete(1).await,
+            Err(AppError::InternalServerError(_))
+        ));
     }
 }
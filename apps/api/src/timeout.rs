@@ -0,0 +1,72 @@
+//! Request timeouts
+//!
+//! A request not answered within its timeout (e.g. because Postgres is slow)
+//! is cancelled with `504 Gateway Timeout`, so slow requests do not pile up
+//! connections. Exports, imports and uploads have a longer timeout than the
+//! other routes. Requests waiting for a database connection fail earlier, with
+//! `503 Service Unavailable` (see `database.acquire_timeout_seconds`).
+//!
+//! The timeout ends when the response headers are sent; streamed response
+//! bodies (e.g. the NDJSON export) are not cut off.
+
+use crate::error::{AppError, ErrorDetails};
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+use tower_http::timeout::TimeoutLayer;
+
+/// Timeout of requests when `timeouts.request_seconds` is unset
+const DEFAULT_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+
+/// Timeout of exports, imports and uploads when `timeouts.export_seconds` is
+/// unset
+const DEFAULT_EXPORT_TIMEOUT_SECONDS: u64 = 120;
+
+/// Request timeouts (the `[timeouts]` configuration section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Timeout of most routes
+    pub request: Duration,
+    /// Timeout of export, import and upload routes
+    pub export: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            request: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECONDS),
+            export: Duration::from_secs(DEFAULT_EXPORT_TIMEOUT_SECONDS),
+        }
+    }
+}
+
+impl Timeouts {
+    /// Layer timing out most routes
+    #[must_use]
+    pub fn layer(&self) -> TimeoutLayer {
+        TimeoutLayer::with_status_code(StatusCode::GATEWAY_TIMEOUT, self.request)
+    }
+
+    /// Layer timing out export, import and upload routes
+    #[must_use]
+    pub fn export_layer(&self) -> TimeoutLayer {
+        TimeoutLayer::with_status_code(StatusCode::GATEWAY_TIMEOUT, self.export)
+    }
+}
+
+/// Middleware turning the empty responses of timed out requests into
+/// `AppError` responses
+pub async fn json_timeouts(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    if response.status() != StatusCode::GATEWAY_TIMEOUT
+        || response.extensions().get::<ErrorDetails>().is_some()
+    {
+        return response;
+    }
+
+    AppError::GatewayTimeout("The request took too long to process".to_string()).into_response()
+}
@@ -0,0 +1,229 @@
+//! Signed and opaque tokens
+//!
+//! A signed token is `<payload>.<signature>`, both base64url encoded without
+//! padding. The payload is the JSON-serialized claims and the signature is
+//! HMAC-SHA256 over the encoded payload. Tokens are not encrypted, so claims
+//! must not contain secrets.
+//!
+//! An opaque token is a random string looked up in the database (e.g.
+//! invitations, password resets). Only its SHA-256 hash is stored, so the
+//! stored rows cannot be used as tokens.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use serde::{Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use std::fmt::{self, Write};
+use std::sync::Arc;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Reasons a token is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenError {
+    /// Not of the form `<payload>.<signature>` or not valid base64/JSON
+    Malformed,
+    /// The signature does not match the payload
+    InvalidSignature,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "Malformed token"),
+            Self::InvalidSignature => write!(f, "Invalid token signature"),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
+
+/// Signing secret and lifetime of one kind of signed token
+#[derive(Clone, PartialEq, Eq)]
+pub struct TokenSettings {
+    /// `None` signs with a random secret, so tokens do not survive restarts
+    /// and are not shared between instances
+    pub secret: Option<String>,
+    pub ttl: chrono::Duration,
+}
+
+impl fmt::Debug for TokenSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenSettings")
+            .field("secret", &self.secret.as_ref().map(|_| "***"))
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl TokenSettings {
+    /// The configured secret, or a random one with a warning naming `setting`
+    pub(crate) fn secret_or_random(&self, setting: &str) -> String {
+        self.secret.clone().unwrap_or_else(|| {
+            tracing::warn!("{setting} is not set, using a random secret");
+            new_opaque()
+        })
+    }
+}
+
+/// Signs and verifies tokens with a shared secret
+#[derive(Clone)]
+pub struct TokenSigner {
+    secret: Arc<[u8]>,
+}
+
+impl fmt::Debug for TokenSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenSigner").finish_non_exhaustive()
+    }
+}
+
+impl TokenSigner {
+    /// Create a signer from a secret key
+    #[must_use]
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            secret: Arc::from(secret),
+        }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        // HMAC accepts keys of any length
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length")
+    }
+
+    /// Sign claims into a token
+    ///
+    /// # Panics
+    /// Panics if the claims cannot be serialized to JSON, which cannot happen
+    /// for plain data structs
+    #[must_use]
+    pub fn sign<T: Serialize>(&self, claims: &T) -> String {
+        let payload = serde_json::to_vec(claims).expect("Claims must serialize to JSON");
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+        format!("{payload}.{signature}")
+    }
+
+    /// Verify a token's signature and decode its claims
+    ///
+    /// Only the signature is checked; expiry and other claims are up to the caller.
+    ///
+    /// # Errors
+    /// Returns `TokenError` if the token is malformed or the signature does not match
+    pub fn verify<T: DeserializeOwned>(&self, token: &str) -> Result<T, TokenError> {
+        let (payload, signature) = token.split_once('.').ok_or(TokenError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| TokenError::Malformed)?;
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| TokenError::InvalidSignature)?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| TokenError::Malformed)?;
+        serde_json::from_slice(&payload).map_err(|_| TokenError::Malformed)
+    }
+}
+
+/// Generate a new opaque token (64 hex characters, 244 random bits)
+#[must_use]
+pub fn new_opaque() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hash of an opaque token, as stored in the database (lowercase hex SHA-256)
+#[must_use]
+pub fn hash_opaque(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Claims {
+        sub: String,
+        exp: i64,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: "user".to_string(),
+            exp: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = TokenSigner::new(b"secret");
+        let token = signer.sign(&claims());
+        assert_eq!(signer.verify::<Claims>(&token), Ok(claims()));
+    }
+
+    #[test]
+    fn test_verify_rejects_other_key() {
+        let token = TokenSigner::new(b"secret").sign(&claims());
+        assert_eq!(
+            TokenSigner::new(b"other").verify::<Claims>(&token),
+            Err(TokenError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_payload() {
+        let signer = TokenSigner::new(b"secret");
+        let token = signer.sign(&claims());
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(br#"{"sub":"admin","exp":1700000000}"#);
+
+        assert_eq!(
+            signer.verify::<Claims>(&format!("{forged}.{signature}")),
+            Err(TokenError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed() {
+        let signer = TokenSigner::new(b"secret");
+        assert_eq!(
+            signer.verify::<Claims>("no-dot"),
+            Err(TokenError::Malformed)
+        );
+        assert_eq!(
+            signer.verify::<Claims>("abc.%%%"),
+            Err(TokenError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_new_opaque_is_random_hex() {
+        let token = new_opaque();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, new_opaque());
+    }
+
+    #[test]
+    fn test_hash_opaque() {
+        assert_eq!(
+            hash_opaque("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::error::{AppError, Result};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Longest email address accepted, matching `users.email VARCHAR(255)`
+const MAX_EMAIL_LENGTH: usize = 255;
+
+/// Longest local part (before the `@`) allowed by RFC 5321
+const MAX_LOCAL_LENGTH: usize = 64;
+
+/// Longest domain name allowed by RFC 1035
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+/// Longest domain label allowed by RFC 1035
+const MAX_LABEL_LENGTH: usize = 63;
+
+/// Validate an email address, ignoring surrounding whitespace
+///
+/// # Errors
+/// Returns validation error if the email address:
+/// - Is empty or only whitespace
+/// - Exceeds 255 characters
+/// - Is not a valid address (see [`is_email`])
+pub fn email(address: &str) -> Result<()> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err(AppError::ValidationError(
+            "Email cannot be empty".to_string(),
+        ));
+    }
+    if address.len() > MAX_EMAIL_LENGTH {
+        return Err(AppError::ValidationError(
+            "Email must be 255 characters or less".to_string(),
+        ));
+    }
+    if !is_email(address) {
+        return Err(AppError::ValidationError(
+            "Email must be a valid email address".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check whether a string is an email address (`addr-spec` of RFC 5322)
+///
+/// The local part is a dot-atom or a quoted string of at most 64 octets. The
+/// domain is either a domain name with at least two labels, none longer than
+/// 63 octets or starting or ending with a hyphen, and a top-level domain that is
+/// not numeric, or an IPv4 or `IPv6:` address literal in brackets.
+///
+/// Non-ASCII letters are allowed in both parts (RFC 6531). Comments and folding
+/// whitespace, which RFC 5322 only keeps for compatibility, are rejected.
+#[must_use]
+pub fn is_email(address: &str) -> bool {
+    let Some((local, domain)) = address.rsplit_once('@') else {
+        return false;
+    };
+    is_local_part(local) && is_domain(domain)
+}
+
+fn is_local_part(local: &str) -> bool {
+    if local.is_empty() || local.len() > MAX_LOCAL_LENGTH {
+        return false;
+    }
+    local
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .map_or_else(|| local.split('.').all(is_atom), is_quoted_content)
+}
+
+/// Atom of a dot-atom: one or more `atext` characters
+fn is_atom(atom: &str) -> bool {
+    !atom.is_empty() && atom.chars().all(is_atext)
+}
+
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || !c.is_ascii()
+}
+
+/// Content of a quoted string: printable characters and spaces, with `"` and `\`
+/// escaped by a backslash
+fn is_quoted_content(quoted: &str) -> bool {
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        let valid = match c {
+            '\\' => chars
+                .next()
+                .is_some_and(|escaped| escaped == ' ' || is_visible(escaped)),
+            '"' => false,
+            c => c == ' ' || is_visible(c),
+        };
+        if !valid {
+            return false;
+        }
+    }
+    true
+}
+
+fn is_visible(c: char) -> bool {
+    c.is_ascii_graphic() || (!c.is_ascii() && !c.is_control() && !c.is_whitespace())
+}
+
+fn is_domain(domain: &str) -> bool {
+    if let Some(literal) = domain
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return literal.strip_prefix("IPv6:").map_or_else(
+            || literal.parse::<Ipv4Addr>().is_ok(),
+            |ipv6| ipv6.parse::<Ipv6Addr>().is_ok(),
+        );
+    }
+
+    if domain.len() > MAX_DOMAIN_LENGTH {
+        return false;
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    labels.len() >= 2
+        && labels.iter().all(|label| is_label(label))
+        && labels
+            .last()
+            .is_some_and(|tld| !tld.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_LABEL_LENGTH
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_addresses() {
+        for address in [
+            "user@example.com",
+            "first.last@example.com",
+            "user+tag@example.com",
+            "o'brien@example.co.uk",
+            "x@sub.domain-with-hyphen.example",
+            "!#$%&'*+-/=?^_`{|}~@example.com",
+            "1234@example.com",
+            "\"john doe\"@example.com",
+            "\"quoted\\\"escape\"@example.com",
+            "\"a@b\"@example.com",
+            "user@[192.168.0.1]",
+            "user@[IPv6:2001:db8::1]",
+            "jörg@bücher.example",
+            "用户@例子.广告",
+        ] {
+            assert!(is_email(address), "{address} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        for address in [
+            "",
+            "plainaddress",
+            "@example.com",
+            "user@",
+            "user@localhost",
+            "user@@example.com",
+            "user@exa mple.com",
+            "us er@example.com",
+            ".user@example.com",
+            "user.@example.com",
+            "first..last@example.com",
+            "user@.example.com",
+            "user@example.com.",
+            "user@example..com",
+            "user@-example.com",
+            "user@example-.com",
+            "user@exam_ple.com",
+            "user@123.456",
+            "user(comment)@example.com",
+            "user@example.com (comment)",
+            "\"unterminated@example.com",
+            "\"bad\"quote\"@example.com",
+            "user@[300.1.1.1]",
+            "user@[IPv6:not-an-address]",
+            "user@[2001:db8::1]",
+            "a.b.c",
+        ] {
+            assert!(!is_email(address), "{address} should be invalid");
+        }
+    }
+
+    #[test]
+    fn test_length_limits() {
+        let local = "a".repeat(MAX_LOCAL_LENGTH);
+        assert!(is_email(&format!("{local}@example.com")));
+        assert!(!is_email(&format!("a{local}@example.com")));
+
+        let label = "a".repeat(MAX_LABEL_LENGTH);
+        assert!(is_email(&format!("user@{label}.com")));
+        assert!(!is_email(&format!("user@a{label}.com")));
+    }
+
+    #[test]
+    fn test_email_messages() {
+        let message = |address: &str| match email(address) {
+            Err(AppError::ValidationError(message)) => message,
+            other => panic!("unexpected result {other:?}"),
+        };
+
+        assert!(email(" user@example.com ").is_ok());
+        assert_eq!(message("   "), "Email cannot be empty");
+        assert_eq!(
+            message(&format!("{}@example.com", "a".repeat(250))),
+            "Email must be 255 characters or less"
+        );
+        assert_eq!(
+            message("user@localhost"),
+            "Email must be a valid email address"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Webhook notifications
+//!
+//! Active subscribers in `webhooks` receive a JSON `POST` for every
+//! notification. `attendance_event.created` is sent when an event is recorded
+//! through `POST /api/attendance-events` or a kiosk (not for CSV imports or
+//! approved corrections). Requests carry:
+//!
+//! - `X-Webhook-Event`: notification type (e.g. `attendance_event.created`)
+//! - `X-Webhook-Delivery`: delivery id, the same for every retry of a delivery
+//! - `X-Webhook-Timestamp`: Unix time (seconds) the request was signed at
+//! - `X-Webhook-Signature`: `sha256=<hex>`, the HMAC-SHA256 of
+//!   `<timestamp>.<body>` keyed with the subscriber's secret
+//!
+//! Every notification is logged as a delivery. Delivery is at least once: an
+//! attempt fails unless the subscriber answers with a 2xx status, and failed
+//! attempts are retried with exponential backoff by the retry job until the
+//! attempts run out. Subscribers should drop repeated delivery ids.
+
+use crate::error::{AppError, Result};
+use crate::models::{DeliveryAttempt, DeliveryStatus, Webhook, WebhookDelivery};
+use crate::repository::WebhookRepository;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::fmt::Write;
+
+/// Notification sent when an attendance event is recorded
+pub const ATTENDANCE_EVENT_CREATED: &str = "attendance_event.created";
+
+/// Default number of attempts before a delivery is given up
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+/// Default delay before the first retry; doubled for every further retry
+const DEFAULT_RETRY_BASE_SECONDS: i64 = 30;
+
+/// Longest delay between two attempts (6 hours)
+const MAX_RETRY_DELAY_SECONDS: i64 = 6 * 60 * 60;
+
+/// Time a subscriber has to answer a request
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How long an attempt in flight keeps other workers off a delivery
+/// Longer than the request timeout, so a delivery is not sent twice at once
+const LEASE_SECONDS: i64 = 60;
+
+/// Longest error message stored in the delivery log
+const MAX_ERROR_LENGTH: usize = 500;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign a request body for a subscriber
+///
+/// Returns the `X-Webhook-Signature` header value: `sha256=` followed by the
+/// lowercase hex HMAC-SHA256 of `<timestamp>.<body>`.
+///
+/// # Panics
+/// Never panics in practice; HMAC accepts keys of any length
+#[must_use]
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    // HMAC accepts keys of any length
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    let mut signature = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
+
+/// Body of a webhook request
+#[derive(Debug, Serialize)]
+struct Envelope<'a, T> {
+    event: &'a str,
+    created_at: DateTime<Utc>,
+    data: &'a T,
+}
+
+/// Retry policy of webhook deliveries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookSettings {
+    /// Attempts before a delivery is given up
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every further retry
+    pub retry_base: Duration,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_base: Duration::seconds(DEFAULT_RETRY_BASE_SECONDS),
+        }
+    }
+}
+
+/// Sends webhook notifications and records their deliveries
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    repo: WebhookRepository,
+    client: reqwest::Client,
+    max_attempts: u32,
+    retry_base: Duration,
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher with an explicit retry policy
+    ///
+    /// # Panics
+    /// Panics if the HTTP client cannot be initialized (no TLS backend available)
+    #[must_use]
+    pub fn new(repo: WebhookRepository, max_attempts: u32, retry_base: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("attendance-api/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("HTTP client must initialize");
+
+        Self {
+            repo,
+            client,
+            max_attempts: max_attempts.max(1),
+            retry_base,
+        }
+    }
+
+    /// Create a dispatcher with the configured retry policy
+    #[must_use]
+    pub fn from_settings(repo: WebhookRepository, settings: &WebhookSettings) -> Self {
+        Self::new(repo, settings.max_attempts, settings.retry_base)
+    }
+
+    /// Notify all active subscribers in the background
+    ///
+    /// Notification is best effort: the triggering change is already stored, so
+    /// failures are logged instead of being returned. Failed attempts are left
+    /// to the retry job.
+    pub fn notify<T: Serialize>(&self, event: &'static str, data: &T) {
+        let envelope = Envelope {
+            event,
+            created_at: Utc::now(),
+            data,
+        };
+        let payload = match serde_json::to_string(&envelope) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!(event, error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        let dispatcher = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = dispatcher.enqueue_and_deliver(event, &payload).await {
+                tracing::error!(event, error = %e, "Webhook notification failed");
+            }
+        });
+    }
+
+    async fn enqueue_and_deliver(&self, event: &str, payload: &str) -> Result<()> {
+        let now = Utc::now();
+        let deliveries = self
+            .repo
+            .enqueue_deliveries(event, payload, now + Duration::seconds(LEASE_SECONDS))
+            .await?;
+
+        for delivery in deliveries {
+            if let Some(webhook) = self.repo.find_by_id(delivery.webhook_id).await? {
+                self.attempt(&webhook, &delivery).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Attempt all due pending deliveries
+    ///
+    /// # Arguments
+    /// * `now` - Deliveries due at or before this time are attempted
+    /// * `limit` - Maximum number of deliveries to attempt
+    ///
+    /// # Returns
+    /// * `Ok(Vec<WebhookDelivery>)` - The attempted deliveries after the attempt
+    ///
+    /// # Errors
+    /// Returns `AppError` if database query fails
+    pub async fn retry_due(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<WebhookDelivery>> {
+        let due = self
+            .repo
+            .claim_due_deliveries(now, now + Duration::seconds(LEASE_SECONDS), limit)
+            .await?;
+
+        let mut attempted = Vec::with_capacity(due.len());
+        for delivery in due {
+            if let Some(webhook) = self.repo.find_by_id(delivery.webhook_id).await? {
+                attempted.push(self.attempt(&webhook, &delivery).await?);
+            }
+        }
+
+        Ok(attempted)
+    }
+
+    /// Attempt a delivery now, regardless of when it is due
+    ///
+    /// # Returns
+    /// * `Ok(WebhookDelivery)` - The delivery after the attempt
+    ///
+    /// # Errors
+    /// Returns `AppError::BadRequest` if the delivery has already succeeded
+    /// Returns `AppError` if database query fails
+    pub async fn redeliver(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<WebhookDelivery> {
+        if delivery.status == DeliveryStatus::Succeeded {
+            return Err(AppError::BadRequest(format!(
+                "Webhook delivery {} has already succeeded",
+                delivery.id
+            )));
+        }
+        self.attempt(webhook, delivery).await
+    }
+
+    /// Send a delivery once and record the outcome
+    async fn attempt(
+        &self,
+        webhook: &Webhook,
+        delivery: &WebhookDelivery,
+    ) -> Result<WebhookDelivery> {
+        let outcome = self.send(webhook, delivery).await;
+        let attempts = u32::try_from(delivery.attempts).unwrap_or(0) + 1;
+
+        let (status, next_attempt_at) = if outcome.error.is_none() {
+            (DeliveryStatus::Succeeded, None)
+        } else if attempts >= self.max_attempts {
+            (DeliveryStatus::Failed, None)
+        } else {
+            (
+                DeliveryStatus::Pending,
+                Some(Utc::now() + retry_delay(self.retry_base, attempts)),
+            )
+        };
+
+        match &outcome.error {
+            None => tracing::debug!(delivery_id = %delivery.id, "Webhook delivered"),
+            Some(error) => tracing::warn!(
+                webhook_id = %webhook.id,
+                delivery_id = %delivery.id,
+                attempts,
+                status = %status,
+                "Webhook delivery failed: {error}"
+            ),
+        }
+
+        self.repo
+            .record_attempt(delivery.id, status, &outcome, next_attempt_at)
+            .await
+    }
+
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> DeliveryAttempt {
+        let timestamp = Utc::now().timestamp();
+        let result = self
+            .client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Webhook-Event", &delivery.event)
+            .header("X-Webhook-Delivery", delivery.id.to_string())
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header(
+                "X-Webhook-Signature",
+                sign(&webhook.secret, timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                DeliveryAttempt {
+                    status_code: Some(i32::from(status.as_u16())),
+                    error: (!status.is_success())
+                        .then(|| format!("Subscriber responded with {status}")),
+                }
+            }
+            Err(e) => {
+                let mut error = e.to_string();
+                if error.len() > MAX_ERROR_LENGTH {
+                    let mut end = MAX_ERROR_LENGTH;
+                    while !error.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    error.truncate(end);
+                }
+                DeliveryAttempt {
+                    status_code: None,
+                    error: Some(error),
+                }
+            }
+        }
+    }
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times
+///
+/// The base delay doubles with every failed attempt, up to 6 hours.
+fn retry_delay(base: Duration, attempts: u32) -> Duration {
+    let factor = 1_i64 << attempts.saturating_sub(1).min(20);
+    let seconds = base.num_seconds().saturating_mul(factor);
+    Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_depends_on_secret_timestamp_and_body() {
+        let signature = sign("secret", 1_700_000_000, "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert!(
+            signature[7..]
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())
+        );
+
+        assert_eq!(signature, sign("secret", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign("other", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign("secret", 1_700_000_001, "{}"));
+        assert_ne!(signature, sign("secret", 1_700_000_000, "{ }"));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_limit() {
+        let base = Duration::seconds(30);
+        assert_eq!(retry_delay(base, 1), Duration::seconds(30));
+        assert_eq!(retry_delay(base, 2), Duration::seconds(60));
+        assert_eq!(retry_delay(base, 4), Duration::seconds(240));
+        assert_eq!(retry_delay(base, 20), Duration::hours(6));
+        assert_eq!(retry_delay(base, u32::MAX), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_sign_matches_hmac_of_timestamp_and_body() {
+        let mut mac = HmacSha256::new_from_slice(b"key").unwrap();
+        mac.update(b"1.body");
+        let expected: String =
+            mac.finalize()
+                .into_bytes()
+                .iter()
+                .fold(String::new(), |mut hex, b| {
+                    let _ = write!(hex, "{b:02x}");
+                    hex
+                });
+
+        assert_eq!(sign("key", 1, "body"), format!("sha256={expected}"));
+    }
+}
@@ -0,0 +1,632 @@
+mod helpers;
+
+use api::extract::AdminApiKey;
+use axum::{
+    Router,
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::net::SocketAddr;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const ADMIN_KEY: &str = "test-admin-key";
+
+/// Helper function to create the test app with a bootstrap admin key configured
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.admin_api_key = AdminApiKey::new(Some(ADMIN_KEY));
+    state.auth_tokens = test_auth_tokens();
+    state.trusted_proxies = "10.0.0.0/8".parse().unwrap();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn get_detail(app: Router, event_id: &str, caller: Option<Uuid>) -> axum::response::Response {
+    let mut request = Request::builder().uri(format!("/api/admin/attendance-events/{event_id}"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+/// Record a clock-in of the user from a peer address with an `X-Forwarded-For`
+/// header and return the event id
+async fn record_event(app: Router, user_id: Uuid, peer: [u8; 4], forwarded_for: &str) -> String {
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(user_id))
+                .header("user-agent", "Kiosk/1.0")
+                .header("x-forwarded-for", forwarded_for)
+                .header("x-device-id", "kiosk-01")
+                .extension(ConnectInfo(SocketAddr::from((peer, 54321))))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Client metadata is not exposed by the regular endpoint
+    let body = parse_json_body(response.into_body()).await;
+    assert!(body.get("client_ip").is_none());
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_attendance_event_detail_includes_client_metadata() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    // Through the trusted proxy, the address the proxy saw is recorded
+    let event_id = record_event(
+        app.clone(),
+        user_id,
+        [10, 0, 0, 1],
+        "198.51.100.9, 203.0.113.7",
+    )
+    .await;
+
+    let response = get_detail(app, &event_id, Some(admin)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["id"], event_id);
+    assert_eq!(body["event_type"], "clock_in");
+    assert_eq!(body["client_ip"], "203.0.113.7");
+    assert_eq!(body["user_agent"], "Kiosk/1.0");
+    assert_eq!(body["device_id"], "kiosk-01");
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_attendance_event_detail_ignores_spoofed_client_address() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    // Clients that are not trusted proxies cannot choose the recorded address
+    let event_id = record_event(app.clone(), user_id, [192, 0, 2, 1], "203.0.113.7").await;
+
+    let response = get_detail(app, &event_id, Some(admin)).await;
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["client_ip"], "192.0.2.1");
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_attendance_event_detail_requires_admin() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let event_id = Uuid::new_v4().to_string();
+
+    let anonymous = get_detail(app.clone(), &event_id, None).await.status();
+    let forbidden = get_detail(app.clone(), &event_id, Some(manager))
+        .await
+        .status();
+
+    // The bootstrap key is accepted only for appointing admins
+    let bootstrap_key = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/admin/attendance-events/{event_id}"))
+                .header("x-admin-key", ADMIN_KEY)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+    let not_found = get_detail(app, &event_id, Some(admin)).await.status();
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(bootstrap_key, StatusCode::UNAUTHORIZED);
+    assert_eq!(not_found, StatusCode::NOT_FOUND);
+}
+
+async fn export_events(app: Router, query: &str, caller: Option<Uuid>) -> axum::response::Response {
+    let mut request =
+        Request::builder().uri(format!("/api/admin/attendance-events/export.ndjson{query}"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_export_attendance_events_ndjson() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    let mut ids = Vec::new();
+    for (event_type, event_time) in [
+        ("clock_out", "2025-11-05T18:00:00Z"),
+        ("clock_in", "2025-11-05T09:00:00Z"),
+        ("clock_in", "2025-11-06T09:00:00Z"),
+    ] {
+        let payload =
+            json!({ "user_id": user_id, "event_type": event_type, "event_time": event_time });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/attendance-events")
+                    .header("content-type", "application/json")
+                    .header("authorization", bearer(user_id))
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = parse_json_body(response.into_body()).await;
+        ids.push(body["id"].as_str().unwrap().to_string());
+    }
+
+    let query = format!("?user_id={user_id}&to=2025-11-06T00:00:00Z");
+    let response = export_events(app.clone(), &query, Some(admin)).await;
+    let status = response.status();
+    let content_type = response.headers()["content-type"].clone();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+    let unauthorized = export_events(app.clone(), &query, None).await.status();
+    let invalid_range = export_events(
+        app,
+        "?from=2025-11-06T00:00:00Z&to=2025-11-05T00:00:00Z",
+        Some(admin),
+    )
+    .await
+    .status();
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/x-ndjson");
+
+    // One event per line in time order, limited to the requested range
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    let lines: Vec<Value> = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(text.ends_with('\n'));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["id"], ids[1]);
+    assert_eq!(lines[1]["id"], ids[0]);
+    assert_eq!(lines[0]["user_id"], user_id.to_string());
+    assert!(lines[0].get("client_ip").is_some());
+
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+    assert_eq!(invalid_range, StatusCode::BAD_REQUEST);
+}
+
+async fn payroll_export(
+    app: Router,
+    query: &str,
+    caller: Option<Uuid>,
+) -> axum::response::Response {
+    let mut request = Request::builder().uri(format!("/api/attendance/payroll-export{query}"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_payroll_export_csv() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let worker = insert_user(&pool).await;
+    let absent = insert_user(&pool).await;
+
+    // 10 hours on Wednesday 2025-11-05 (business timezone)
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("clock_out", "2025-11-05T10:00:00Z"),
+    ] {
+        let payload =
+            json!({ "user_id": worker, "event_type": event_type, "event_time": event_time });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/attendance-events")
+                    .header("content-type", "application/json")
+                    .header("authorization", bearer(worker))
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = payroll_export(app.clone(), "?year=2025&month=11", Some(admin)).await;
+    let status = response.status();
+    let content_type = response.headers()["content-type"].clone();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+    let unauthorized = payroll_export(app.clone(), "?year=2025&month=11", None)
+        .await
+        .status();
+    let forbidden = payroll_export(app.clone(), "?year=2025&month=11", Some(worker))
+        .await
+        .status();
+    let invalid_month = payroll_export(app, "?year=2025&month=13", Some(admin))
+        .await
+        .status();
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, worker).await;
+    cleanup_user(&pool, absent).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "text/csv; charset=utf-8");
+
+    let csv = String::from_utf8(bytes.to_vec()).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("employee_id,regular_hours,overtime_hours,leave_hours")
+    );
+    let lines: Vec<&str> = lines.collect();
+    assert!(lines.contains(&format!("{worker},8.00,2.00,0.00").as_str()));
+    assert!(lines.contains(&format!("{absent},0.00,0.00,0.00").as_str()));
+
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(invalid_month, StatusCode::BAD_REQUEST);
+}
+
+async fn purge(app: Router, user_id: Uuid, caller: Option<Uuid>) -> axum::response::Response {
+    let mut request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/users/{user_id}/purge"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_purge_deleted_user_removes_attendance_events() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+    sqlx::query(
+        "INSERT INTO attendance_events (user_id, event_type, event_time, recorded_at) VALUES ($1, 'clock_in', '2025-11-05T09:00:00Z', NOW()), ($1, 'clock_out', '2025-11-05T18:00:00Z', NOW())",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // Active users must be soft-deleted first
+    let active = purge(app.clone(), user_id, Some(admin)).await.status();
+    sqlx::query("UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let unauthorized = purge(app.clone(), user_id, None).await.status();
+    let response = purge(app, user_id, Some(admin)).await;
+    let status = response.status();
+    let body = parse_json_body(response.into_body()).await;
+
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let events: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(active, StatusCode::NOT_FOUND);
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["attendance_events_deleted"], 2);
+    assert_eq!(users, 0);
+    assert_eq!(events, 0);
+}
+
+#[tokio::test]
+async fn test_list_deleted_users() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let active = insert_user(&pool).await;
+    let older = insert_user(&pool).await;
+    let newer = insert_user(&pool).await;
+    for (id, deleted_at) in [
+        (older, "2025-11-01T09:00:00Z"),
+        (newer, "2025-11-02T09:00:00Z"),
+    ] {
+        sqlx::query("UPDATE users SET deleted_at = $2::timestamptz WHERE id = $1")
+            .bind(id)
+            .bind(deleted_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let list = |uri: &'static str, caller: Option<Uuid>| {
+        let app = app.clone();
+        async move {
+            let mut request = Request::builder().uri(uri);
+            if let Some(caller) = caller {
+                request = request.header("authorization", bearer(caller));
+            }
+            let response = app
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            (status, parse_json_body(response.into_body()).await)
+        }
+    };
+    let (status, body) = list("/api/admin/users/deleted", Some(admin)).await;
+    let (_, first_page) = list("/api/admin/users/deleted?per_page=1", Some(admin)).await;
+    let (unauthorized, _) = list("/api/admin/users/deleted", None).await;
+
+    for id in [admin, active, older, newer] {
+        cleanup_user(&pool, id).await;
+    }
+
+    // Other tests may soft-delete users concurrently, so only look at ours
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["total"].as_i64().unwrap() >= 2);
+    let items = body["items"].as_array().unwrap();
+    let position = |id: Uuid| items.iter().position(|item| item["id"] == id.to_string());
+    assert!(position(active).is_none());
+    assert!(position(newer).unwrap() < position(older).unwrap());
+    assert_eq!(
+        items[position(newer).unwrap()]["deleted_at"],
+        "2025-11-02T09:00:00Z"
+    );
+    assert_eq!(first_page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(first_page["per_page"], 1);
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+}
+
+/// Set the role of a user, authenticating with the given header
+async fn set_role(
+    app: Router,
+    user_id: Uuid,
+    role: &str,
+    credentials: Option<(&str, String)>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method("PUT")
+        .uri(format!("/api/admin/users/{user_id}/role"))
+        .header("content-type", "application/json");
+    if let Some((name, value)) = credentials {
+        request = request.header(name, value);
+    }
+    let response = app
+        .oneshot(
+            request
+                .body(Body::from(json!({ "role": role }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn test_set_user_role() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let user_id = insert_user(&pool).await;
+    let as_admin = || Some(("authorization", bearer(admin)));
+
+    let (status, body) = set_role(app.clone(), user_id, "manager", as_admin()).await;
+    let role: String = sqlx::query_scalar("SELECT role FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let (unauthorized, _) = set_role(app.clone(), user_id, "admin", None).await;
+    let as_member = Some(("authorization", bearer(member)));
+    let (forbidden, _) = set_role(app.clone(), user_id, "admin", as_member).await;
+    let (invalid, _) = set_role(app.clone(), user_id, "owner", as_admin()).await;
+    let (unknown, _) = set_role(app, Uuid::new_v4(), "member", as_admin()).await;
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, member).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["role"], "manager");
+    assert_eq!(role, "manager");
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(invalid, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_bootstrap_key_appoints_the_first_admin() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let key = |key: &str| Some(("x-admin-key", key.to_string()));
+
+    let (status, body) = set_role(app.clone(), user_id, "admin", key(ADMIN_KEY)).await;
+    let (wrong_key, _) = set_role(app, user_id, "member", key("wrong-key")).await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["role"], "admin");
+    assert_eq!(wrong_key, StatusCode::UNAUTHORIZED);
+}
+
+async fn export_users(app: Router, query: &str, caller: Option<Uuid>) -> (StatusCode, String) {
+    let mut request = Request::builder().uri(format!("/api/admin/users/export.csv{query}"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_export_users_csv() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let active = insert_user(&pool).await;
+    let deleted = insert_user(&pool).await;
+    sqlx::query("UPDATE users SET deleted_at = '2025-11-02T09:00:00Z' WHERE id = $1")
+        .bind(deleted)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, csv) = export_users(app.clone(), "", Some(admin)).await;
+    let (_, with_deleted) = export_users(app.clone(), "?include_deleted=true", Some(admin)).await;
+    let (unauthorized, _) = export_users(app, "", None).await;
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, active).await;
+    cleanup_user(&pool, deleted).await;
+
+    // Other tests add users concurrently, so only look at ours
+    let row = |csv: &str, id: Uuid| {
+        csv.lines()
+            .find(|line| line.starts_with(&id.to_string()))
+            .map(str::to_string)
+    };
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        csv.lines().next(),
+        Some("id,name,email,created_at,deleted_at")
+    );
+    let active_row = row(&csv, active).unwrap();
+    assert!(active_row.contains(",Test User,test-"));
+    assert!(active_row.ends_with(','));
+    assert!(row(&csv, deleted).is_none());
+    assert!(
+        row(&with_deleted, deleted)
+            .unwrap()
+            .ends_with(",2025-11-02T09:00:00+00:00")
+    );
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+}
+
+async fn user_stats(app: Router, query: &str, caller: Option<Uuid>) -> (StatusCode, Value) {
+    let mut request = Request::builder().uri(format!("/api/admin/users/stats{query}"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+#[tokio::test]
+async fn test_user_stats() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let active = insert_user(&pool).await;
+    let deactivated = insert_user(&pool).await;
+    let deleted = insert_user(&pool).await;
+    sqlx::query("UPDATE users SET is_active = FALSE WHERE id = $1")
+        .bind(deactivated)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(deleted)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, monthly) = user_stats(app.clone(), "", Some(admin)).await;
+    let (_, weekly) = user_stats(app.clone(), "?recent_days=7", Some(admin)).await;
+    let (invalid, _) = user_stats(app.clone(), "?recent_days=0", Some(admin)).await;
+    let (unauthorized, _) = user_stats(app, "", None).await;
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, active).await;
+    cleanup_user(&pool, deactivated).await;
+    cleanup_user(&pool, deleted).await;
+
+    // Other tests add users concurrently, so the counts are lower bounds
+    let count = |stats: &Value, key: &str| stats[key].as_i64().unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert!(count(&monthly, "active") >= 1);
+    assert!(count(&monthly, "deactivated") >= 1);
+    assert!(count(&monthly, "deleted") >= 1);
+    assert!(count(&monthly, "created_recently") >= 2);
+    assert_eq!(monthly["recent_days"], 30);
+    assert_eq!(weekly["recent_days"], 7);
+    assert!(count(&weekly, "created_recently") >= 2);
+    assert_eq!(invalid, StatusCode::BAD_REQUEST);
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,228 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user_with_role, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Helper function to send an admin request
+async fn send(
+    app: Router,
+    admin: Uuid,
+    method: &str,
+    uri: &str,
+    payload: Option<&Value>,
+) -> axum::response::Response {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", bearer(admin))
+        .header("content-type", "application/json");
+    let body = payload.map_or_else(Body::empty, |p| Body::from(p.to_string()));
+    app.oneshot(request.body(body).unwrap()).await.unwrap()
+}
+
+/// Helper function to export the payroll with an API key
+async fn payroll_export(app: Router, api_key: &str) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .uri("/api/attendance/payroll-export?year=2025&month=11")
+            .header("x-api-key", api_key)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+async fn delete_api_key(pool: &PgPool, id: &Value) {
+    let id: Uuid = id.as_str().unwrap().parse().unwrap();
+    sqlx::query("DELETE FROM api_keys WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_create_use_and_revoke_api_key() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let response = send(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/admin/api-keys",
+        Some(&json!({ "name": "Payroll export", "scopes": ["payroll:read", "payroll:read"] })),
+    )
+    .await;
+    let created_status = response.status();
+    let location = response.headers()[header::LOCATION]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let created = parse_json_body(response.into_body()).await;
+    let key = created["key"].as_str().unwrap().to_string();
+
+    let exported = payroll_export(app.clone(), &key).await;
+    let response = send(app.clone(), admin, "GET", &location, None).await;
+    let fetched = parse_json_body(response.into_body()).await;
+    let response = send(app.clone(), admin, "GET", "/api/admin/api-keys", None).await;
+    let listed = parse_json_body(response.into_body()).await;
+
+    let revoked = send(app.clone(), admin, "DELETE", &location, None)
+        .await
+        .status();
+    let exported_after_revoke = payroll_export(app.clone(), &key).await;
+    let response = send(app, admin, "GET", &location, None).await;
+    let after_revoke = parse_json_body(response.into_body()).await;
+
+    delete_api_key(&pool, &created["id"]).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(created_status, StatusCode::CREATED);
+    assert!(key.starts_with("ak_"));
+    assert_eq!(created["key_prefix"], &key[..11]);
+    assert_eq!(created["scopes"], json!(["payroll:read"]));
+    assert_eq!(created["is_active"], true);
+    assert_eq!(exported, StatusCode::OK);
+    assert!(fetched["last_used_at"].is_string());
+    assert!(fetched.get("key").is_none());
+    let listed = listed.as_array().unwrap();
+    assert!(listed.iter().any(|k| k["id"] == created["id"]));
+    assert!(listed.iter().all(|k| k.get("key").is_none()));
+    assert_eq!(revoked, StatusCode::NO_CONTENT);
+    assert_eq!(exported_after_revoke, StatusCode::UNAUTHORIZED);
+    assert!(after_revoke["revoked_at"].is_string());
+    assert_eq!(after_revoke["is_active"], false);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_and_expiry_are_enforced() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let response = send(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/admin/api-keys",
+        Some(&json!({ "name": "Kiosk", "scopes": ["kiosk:clock"] })),
+    )
+    .await;
+    let kiosk = parse_json_body(response.into_body()).await;
+    let response = send(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/admin/api-keys",
+        Some(&json!({
+            "name": "Short-lived",
+            "scopes": ["payroll:read"],
+            "expires_at": "2999-01-01T00:00:00Z",
+        })),
+    )
+    .await;
+    let expiring = parse_json_body(response.into_body()).await;
+    let expiring_id: Uuid = expiring["id"].as_str().unwrap().parse().unwrap();
+    sqlx::query("UPDATE api_keys SET expires_at = NOW() - INTERVAL '1 second' WHERE id = $1")
+        .bind(expiring_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let wrong_scope = payroll_export(app.clone(), kiosk["key"].as_str().unwrap()).await;
+    let expired = payroll_export(app, expiring["key"].as_str().unwrap()).await;
+
+    delete_api_key(&pool, &kiosk["id"]).await;
+    delete_api_key(&pool, &expiring["id"]).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(wrong_scope, StatusCode::FORBIDDEN);
+    assert_eq!(expired, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_create_api_key_validation() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let member = insert_user_with_role(&pool, "member").await;
+
+    let mut errors = Vec::new();
+    for payload in [
+        json!({ "name": " ", "scopes": ["payroll:read"] }),
+        json!({ "name": "No scopes", "scopes": [] }),
+        json!({ "name": "Unknown scope", "scopes": ["users:write"] }),
+        json!({ "name": "Expired", "scopes": ["payroll:read"], "expires_at": "2000-01-01T00:00:00Z" }),
+    ] {
+        let response = send(
+            app.clone(),
+            admin,
+            "POST",
+            "/api/admin/api-keys",
+            Some(&payload),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        errors.push(parse_json_body(response.into_body()).await["message"].clone());
+    }
+    let anonymous = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/api-keys")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+    let as_member = send(app.clone(), member, "GET", "/api/admin/api-keys", None)
+        .await
+        .status();
+    let unknown = send(
+        app,
+        admin,
+        "DELETE",
+        &format!("/api/admin/api-keys/{}", Uuid::new_v4()),
+        None,
+    )
+    .await
+    .status();
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, member).await;
+
+    assert_eq!(
+        errors[2],
+        "Scope must be one of: kiosk:clock, payroll:read, users:read, attendance:read, attendance:write"
+    );
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(as_member, StatusCode::FORBIDDEN);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+}
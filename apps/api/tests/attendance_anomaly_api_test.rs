@@ -0,0 +1,188 @@
+mod helpers;
+
+use api::jobs::missing_clock_out::MissingClockOutJob;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn post_event(app: Router, user_id: Uuid, event_type: &str, event_time: &str) {
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": event_type,
+        "event_time": event_time
+    });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(user_id))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+/// Send a GET request authenticated as a user
+async fn get_json(app: Router, uri: &str, user_id: Uuid) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+fn job(pool: &PgPool) -> MissingClockOutJob {
+    MissingClockOutJob::new(
+        api::AttendanceAnomalyRepository::new(pool.clone()),
+        Duration::hours(12),
+        std::time::Duration::from_mins(1),
+    )
+}
+
+#[tokio::test]
+async fn test_missing_clock_out_is_detected_once() {
+    let (app, pool) = create_app().await;
+    let forgot = insert_user(&pool).await;
+    let finished = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    post_event(app.clone(), forgot, "clock_in", "2025-11-05T00:00:00Z").await;
+    post_event(app.clone(), finished, "clock_in", "2025-11-05T00:00:00Z").await;
+    post_event(app.clone(), finished, "clock_out", "2025-11-05T09:00:00Z").await;
+
+    let job = job(&pool);
+
+    // Not reported before the threshold has passed
+    let now = "2025-11-05T11:00:00Z".parse::<DateTime<Utc>>().unwrap();
+    let detected = job.run_once(now).await.unwrap();
+    assert!(!detected.iter().any(|a| a.user_id == forgot));
+
+    let now = "2025-11-05T13:00:00Z".parse::<DateTime<Utc>>().unwrap();
+    let detected = job.run_once(now).await.unwrap();
+    assert!(detected.iter().any(|a| a.user_id == forgot));
+    assert!(!detected.iter().any(|a| a.user_id == finished));
+
+    // Repeated scans do not report the same clock-in again
+    let detected = job.run_once(now).await.unwrap();
+    assert!(!detected.iter().any(|a| a.user_id == forgot));
+
+    let (status, body) = get_json(
+        app,
+        &format!("/api/attendance/anomalies?user_id={forgot}&kind=missing_clock_out"),
+        manager,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let anomalies = body.as_array().unwrap();
+    assert_eq!(anomalies.len(), 1);
+    assert_eq!(anomalies[0]["kind"], "missing_clock_out");
+    assert_eq!(anomalies[0]["user_id"], forgot.to_string());
+    assert!(
+        anomalies[0]["details"]
+            .as_str()
+            .unwrap()
+            .contains("2025-11-05T00:00:00Z")
+    );
+
+    cleanup_user(&pool, forgot).await;
+    cleanup_user(&pool, finished).await;
+    cleanup_user(&pool, manager).await;
+}
+
+#[tokio::test]
+async fn test_get_attendance_anomalies_invalid_kind() {
+    let (app, pool) = create_app().await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let member = insert_user(&pool).await;
+
+    let (status, body) = get_json(
+        app.clone(),
+        "/api/attendance/anomalies?kind=unknown",
+        manager,
+    )
+    .await;
+    // Members can only list their own anomalies
+    let (by_member, _) = get_json(app, "/api/attendance/anomalies", member).await;
+    cleanup_user(&pool, manager).await;
+    cleanup_user(&pool, member).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "validation_error");
+    assert_eq!(by_member, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_rules_detect_anomalies_on_event_creation() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    post_event(app.clone(), user_id, "clock_in", "2025-11-05T00:00:00Z").await;
+    post_event(app.clone(), user_id, "clock_in", "2025-11-05T00:00:20Z").await;
+    post_event(app.clone(), user_id, "clock_out", "2025-11-05T18:00:00Z").await;
+
+    let (status, body) = get_json(
+        app.clone(),
+        &format!("/api/users/{user_id}/attendance/anomalies"),
+        user_id,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let mut kinds: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["kind"].as_str().unwrap())
+        .collect();
+    kinds.sort_unstable();
+    assert_eq!(kinds, vec!["duplicate_event", "long_shift"]);
+
+    let (status, body) = get_json(
+        app,
+        &format!("/api/users/{user_id}/attendance/anomalies?kind=long_shift"),
+        user_id,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    cleanup_user(&pool, user_id).await;
+}
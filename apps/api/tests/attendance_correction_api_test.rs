@@ -0,0 +1,472 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Send a JSON POST request authenticated as a user
+async fn post_json(
+    app: Router,
+    uri: &str,
+    payload: &Value,
+    user_id: Uuid,
+) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", bearer(user_id))
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Send a GET request authenticated as a user
+async fn get(app: Router, uri: &str, user_id: Uuid) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .uri(uri)
+            .header("authorization", bearer(user_id))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Record a clock-in event for the user and return the event id
+async fn create_clock_in(app: Router, user_id: Uuid) -> String {
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T00:00:00Z"
+    });
+    let response = post_json(app, "/api/attendance-events", &payload, user_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_create_and_get_correction() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "clock_in",
+        "proposed_event_time": "2025-11-04T23:30:00Z",
+        "reason": "Forgot to clock in when I arrived"
+    });
+    let response = post_json(
+        app.clone(),
+        "/api/attendance-corrections",
+        &payload,
+        user_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["event_id"], event_id);
+    assert_eq!(body["user_id"], user_id.to_string());
+    assert_eq!(body["status"], "pending");
+    let correction_id = body["id"].as_str().unwrap().to_string();
+
+    let response = get(
+        app.clone(),
+        &format!("/api/attendance-corrections/{correction_id}"),
+        user_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["reason"], "Forgot to clock in when I arrived");
+
+    let response = get(
+        app,
+        &format!("/api/attendance-corrections?user_id={user_id}&status=pending"),
+        user_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = parse_json_body(response.into_body()).await;
+    let corrections = body.as_array().unwrap();
+    assert_eq!(corrections.len(), 1);
+    assert_eq!(corrections[0]["id"], correction_id);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_correction_validation() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "clock_in",
+        "proposed_event_time": "2025-11-04T23:30:00Z",
+        "reason": "   "
+    });
+    let response = post_json(
+        app.clone(),
+        "/api/attendance-corrections",
+        &payload,
+        user_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "lunch",
+        "proposed_event_time": "2025-11-04T23:30:00Z",
+        "reason": "Wrong type"
+    });
+    let response = post_json(app, "/api/attendance-corrections", &payload, user_id).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_correction_unknown_event() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "event_id": Uuid::new_v4(),
+        "proposed_event_type": "clock_out",
+        "proposed_event_time": "2025-11-05T09:00:00Z",
+        "reason": "Missing event"
+    });
+    let response = post_json(app, "/api/attendance-corrections", &payload, user_id).await;
+    cleanup_user(&pool, user_id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_get_correction_not_found() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let response = get(
+        app,
+        &format!("/api/attendance-corrections/{}", Uuid::new_v4()),
+        user_id,
+    )
+    .await;
+    cleanup_user(&pool, user_id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_corrections_invalid_status() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let response = get(app, "/api/attendance-corrections?status=unknown", user_id).await;
+    cleanup_user(&pool, user_id).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Submit a pending correction for the event and return the correction id
+async fn create_correction(app: Router, event_id: &str, user_id: Uuid) -> String {
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "clock_in",
+        "proposed_event_time": "2025-11-04T23:30:00Z",
+        "reason": "Arrived earlier than recorded"
+    });
+    let response = post_json(app, "/api/attendance-corrections", &payload, user_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    body["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_approve_correction_appends_event() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager_id = insert_user_with_role(&pool, "manager").await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+    let correction_id = create_correction(app.clone(), &event_id, user_id).await;
+
+    let uri = format!("/api/attendance-corrections/{correction_id}/approve");
+    let payload = json!({ "comment": "Confirmed with badge log" });
+    let response = post_json(app.clone(), &uri, &payload, manager_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["status"], "approved");
+    assert_eq!(body["approver_id"], manager_id.to_string());
+    assert_eq!(body["decision_comment"], "Confirmed with badge log");
+    assert!(body["decided_at"].is_string());
+    let applied_event_id = body["applied_event_id"].as_str().unwrap().to_string();
+
+    // The compensating event is appended; the original event is untouched
+    let response = get(
+        app.clone(),
+        &format!("/api/attendance-events/{applied_event_id}"),
+        user_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["event_time"], "2025-11-04T23:30:00Z");
+    assert_eq!(body["amends_event_id"], event_id);
+
+    let response = get(
+        app.clone(),
+        &format!("/api/attendance-events/{event_id}"),
+        user_id,
+    )
+    .await;
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["event_time"], "2025-11-05T00:00:00Z");
+
+    // A decided correction cannot be decided again
+    let response = post_json(app, &uri, &payload, manager_id).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager_id).await;
+}
+
+#[tokio::test]
+async fn test_reject_correction() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager_id = insert_user_with_role(&pool, "manager").await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+    let correction_id = create_correction(app.clone(), &event_id, user_id).await;
+
+    let payload = json!({});
+    let response = post_json(
+        app.clone(),
+        &format!("/api/attendance-corrections/{correction_id}/reject"),
+        &payload,
+        manager_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["status"], "rejected");
+    assert!(body["applied_event_id"].is_null());
+
+    let response = get(
+        app,
+        &format!("/api/users/{user_id}/attendance-events"),
+        user_id,
+    )
+    .await;
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager_id).await;
+}
+
+#[tokio::test]
+async fn test_approve_own_correction_is_rejected() {
+    let (app, pool) = create_app().await;
+    let manager_id = insert_user_with_role(&pool, "manager").await;
+    let event_id = create_clock_in(app.clone(), manager_id).await;
+    let correction_id = create_correction(app.clone(), &event_id, manager_id).await;
+
+    let response = post_json(
+        app,
+        &format!("/api/attendance-corrections/{correction_id}/approve"),
+        &json!({}),
+        manager_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, manager_id).await;
+}
+
+#[tokio::test]
+async fn test_members_cannot_decide_corrections() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let member_id = insert_user(&pool).await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+    let correction_id = create_correction(app.clone(), &event_id, user_id).await;
+
+    let approve = post_json(
+        app.clone(),
+        &format!("/api/attendance-corrections/{correction_id}/approve"),
+        &json!({}),
+        member_id,
+    )
+    .await;
+    let reject = post_json(
+        app.clone(),
+        &format!("/api/attendance-corrections/{correction_id}/reject"),
+        &json!({}),
+        member_id,
+    )
+    .await;
+    let anonymous = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/attendance-corrections/{correction_id}/approve"
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, member_id).await;
+
+    assert_eq!(approve.status(), StatusCode::FORBIDDEN);
+    assert_eq!(reject.status(), StatusCode::FORBIDDEN);
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_members_access_only_their_own_corrections() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let other_id = insert_user(&pool).await;
+    let manager_id = insert_user_with_role(&pool, "manager").await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+    let correction_id = create_correction(app.clone(), &event_id, user_id).await;
+
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "clock_in",
+        "proposed_event_time": "2025-11-04T23:30:00Z",
+        "reason": "Not my event"
+    });
+    let create_other = post_json(
+        app.clone(),
+        "/api/attendance-corrections",
+        &payload,
+        other_id,
+    )
+    .await;
+    let get_other = get(
+        app.clone(),
+        &format!("/api/attendance-corrections/{correction_id}"),
+        other_id,
+    )
+    .await;
+    let list_other = get(
+        app.clone(),
+        &format!("/api/attendance-corrections?user_id={user_id}"),
+        other_id,
+    )
+    .await;
+    let own_list = get(app.clone(), "/api/attendance-corrections", other_id).await;
+    let own_list = parse_json_body(own_list.into_body()).await;
+    let get_manager = get(
+        app,
+        &format!("/api/attendance-corrections/{correction_id}"),
+        manager_id,
+    )
+    .await;
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, other_id).await;
+    cleanup_user(&pool, manager_id).await;
+
+    assert_eq!(create_other.status(), StatusCode::FORBIDDEN);
+    assert_eq!(get_other.status(), StatusCode::FORBIDDEN);
+    assert_eq!(list_other.status(), StatusCode::FORBIDDEN);
+    assert!(own_list.as_array().unwrap().is_empty());
+    assert_eq!(get_manager.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_failed_approval_appends_no_event() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager_id = insert_user_with_role(&pool, "manager").await;
+    let event_id = create_clock_in(app.clone(), user_id).await;
+    let first_id = create_correction(app.clone(), &event_id, user_id).await;
+    let second_id = create_correction(app.clone(), &event_id, user_id).await;
+
+    let response = post_json(
+        app.clone(),
+        &format!("/api/attendance-corrections/{first_id}/approve"),
+        &json!({}),
+        manager_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The corrected event has already been amended, so the second approval
+    // must fail without appending another event or deciding the correction
+    let response = post_json(
+        app.clone(),
+        &format!("/api/attendance-corrections/{second_id}/approve"),
+        &json!({}),
+        manager_id,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Listings show only effective events, so count the stored rows
+    let events: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(events, 2);
+
+    let response = get(
+        app,
+        &format!("/api/attendance-corrections/{second_id}"),
+        user_id,
+    )
+    .await;
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["status"], "pending");
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager_id).await;
+}
@@ -0,0 +1,1102 @@
+mod helpers;
+
+use api::attendance::event_time::EventTimeWindow;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Record an event, authenticated as the user it is recorded for
+async fn post_event(app: Router, payload: &Value) -> axum::response::Response {
+    let user_id = payload["user_id"].as_str().unwrap().parse().unwrap();
+    post_event_as(app, payload, user_id).await
+}
+
+/// Record an event, authenticated as `as_user`
+async fn post_event_as(app: Router, payload: &Value, as_user: Uuid) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/attendance-events")
+            .header("content-type", "application/json")
+            .header("authorization", bearer(as_user))
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_create_and_get_attendance_event() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+
+    let response = post_event(app.clone(), &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["user_id"], user_id.to_string());
+    assert_eq!(body["event_type"], "clock_in");
+    assert_eq!(body["event_time"], "2025-11-05T09:00:00Z");
+    assert!(body["recorded_at"].is_string());
+    let event_id = body["id"].as_str().unwrap().to_string();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/attendance-events/{event_id}"))
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["id"], event_id);
+    assert_eq!(body["event_type"], "clock_in");
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_invalid_event_type() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "lunch",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["error"], "validation_error");
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_with_location() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z",
+        "latitude": 35.681_236,
+        "longitude": 139.767_125
+    });
+
+    let response = post_event(app.clone(), &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["latitude"], 35.681_236);
+    assert_eq!(body["longitude"], 139.767_125);
+
+    // Events without a location return null coordinates
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_out",
+        "event_time": "2025-11-05T18:00:00Z"
+    });
+    let response = post_event(app, &payload).await;
+    let body = parse_json_body(response.into_body()).await;
+    assert!(body["latitude"].is_null());
+    assert!(body["longitude"].is_null());
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_invalid_location() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    for location in [
+        json!({ "latitude": 91.0, "longitude": 0.0 }),
+        json!({ "latitude": 0.0, "longitude": -180.5 }),
+        json!({ "latitude": 35.0 }),
+    ] {
+        let mut payload = json!({
+            "user_id": user_id,
+            "event_type": "clock_in",
+            "event_time": "2025-11-05T09:00:00Z"
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(location.as_object().unwrap().clone());
+
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = parse_json_body(response.into_body()).await;
+        assert_eq!(body["error"], "validation_error");
+    }
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_in_future_is_rejected() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": Utc::now() + Duration::hours(1)
+    });
+
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["error"], "validation_error");
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("event_time is too far in the future")
+    );
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_outside_backfill_window_is_rejected() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.event_time_window = EventTimeWindow {
+        max_backfill: Some(Duration::days(7)),
+        ..EventTimeWindow::default()
+    };
+    let app = api::router(state);
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": Utc::now() - Duration::days(8)
+    });
+    let response = post_event(app.clone(), &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = parse_json_body(response.into_body()).await;
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("event_time is older than the backfill window")
+    );
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": Utc::now() - Duration::days(6)
+    });
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_create_attendance_event_unknown_user() {
+    let (app, pool) = create_app().await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let payload = json!({
+        "user_id": Uuid::new_v4(),
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+
+    let response = post_event_as(app, &payload, manager).await;
+    cleanup_user(&pool, manager).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_get_attendance_event_not_found() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/attendance-events/{}", Uuid::new_v4()))
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    cleanup_user(&pool, user_id).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_get_user_attendance_events_most_recent_first() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T09:00:00Z"),
+        ("clock_out", "2025-11-05T18:00:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let list = |query: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .header("authorization", bearer(user_id))
+                        .uri(format!("/api/users/{user_id}/attendance-events{query}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let link = response
+                .headers()
+                .get("link")
+                .map(|value| value.to_str().unwrap().to_string());
+            (status, link, parse_json_body(response.into_body()).await)
+        }
+    };
+    let (status, link, body) = list("").await;
+    let (_, second_link, second_page) = list("?page=2&per_page=1").await;
+    let (invalid, _, _) = list("?per_page=0").await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(link, None);
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["page"], 1);
+    assert_eq!(body["per_page"], 20);
+    let events = body["items"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["event_type"], "clock_out");
+    assert_eq!(events[1]["event_type"], "clock_in");
+
+    assert_eq!(second_page["total"], 2);
+    assert_eq!(second_page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(second_page["items"][0]["event_type"], "clock_in");
+    assert_eq!(
+        second_link.unwrap(),
+        format!("</api/users/{user_id}/attendance-events?per_page=1&page=1>; rel=\"prev\"")
+    );
+    assert_eq!(invalid, StatusCode::BAD_REQUEST);
+}
+
+async fn get_user_events(app: Router, user_id: Uuid, query: &str) -> Vec<Value> {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!("/api/users/{user_id}/attendance-events{query}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    parse_json_body(response.into_body()).await["items"]
+        .as_array()
+        .unwrap()
+        .clone()
+}
+
+#[tokio::test]
+async fn test_amend_attendance_event() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+    let response = post_event(app.clone(), &payload).await;
+    let original = parse_json_body(response.into_body()).await;
+    let original_id = original["id"].as_str().unwrap().to_string();
+    assert!(original["amends_event_id"].is_null());
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T08:30:00Z",
+        "amends_event_id": original_id
+    });
+    let response = post_event(app.clone(), &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let amendment = parse_json_body(response.into_body()).await;
+    assert_eq!(amendment["amends_event_id"], original_id);
+
+    // Only the effective event is listed by default
+    let events = get_user_events(app.clone(), user_id, "").await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["id"], amendment["id"]);
+
+    let events = get_user_events(app.clone(), user_id, "?include_history=true").await;
+    assert_eq!(events.len(), 2);
+
+    // An event is superseded at most once
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_amend_event_of_another_user_is_rejected() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let other_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": other_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+    let response = post_event(app.clone(), &payload).await;
+    let other_event = parse_json_body(response.into_body()).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z",
+        "amends_event_id": other_event["id"]
+    });
+    let response = post_event(app.clone(), &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z",
+        "amends_event_id": Uuid::new_v4()
+    });
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, other_id).await;
+}
+
+#[tokio::test]
+async fn test_find_latest_events_for_users() {
+    let (app, pool) = create_app().await;
+    let busy = insert_user(&pool).await;
+    let quiet = insert_user(&pool).await;
+    let idle = insert_user(&pool).await;
+
+    for (user_id, event_type, event_time) in [
+        (busy, "clock_in", "2025-11-05T09:00:00Z"),
+        (busy, "break_start", "2025-11-05T12:00:00Z"),
+        (busy, "break_end", "2025-11-05T13:00:00Z"),
+        (quiet, "clock_in", "2025-11-05T10:00:00Z"),
+    ] {
+        let payload =
+            json!({ "user_id": user_id, "event_type": event_type, "event_time": event_time });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let repo = api::AttendanceEventRepository::new(pool.clone());
+    let events = repo
+        .find_latest_for_users(&[quiet, busy, idle, quiet], 2)
+        .await
+        .unwrap();
+    let none = repo.find_latest_for_users(&[busy], 0).await.unwrap();
+
+    cleanup_user(&pool, busy).await;
+    cleanup_user(&pool, quiet).await;
+    cleanup_user(&pool, idle).await;
+
+    // Grouped in the requested order, most recent first, at most 2 per user
+    let summary: Vec<(Uuid, String)> = events
+        .iter()
+        .map(|e| (e.user_id, e.event_type.to_string()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (quiet, "clock_in".to_string()),
+            (busy, "break_end".to_string()),
+            (busy, "break_start".to_string()),
+        ]
+    );
+    assert!(none.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_monthly_timesheet() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    // 2025-11-05 09:00-18:00 JST with a one hour break
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("break_start", "2025-11-05T03:00:00Z"),
+        ("break_end", "2025-11-05T04:00:00Z"),
+        ("clock_out", "2025-11-05T09:00:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/timesheet?year=2025&month=11"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["user_id"], user_id.to_string());
+    assert_eq!(body["days"].as_array().unwrap().len(), 30);
+    assert_eq!(body["days"][4]["date"], "2025-11-05");
+    assert_eq!(body["days"][4]["worked_minutes"], 480);
+    assert_eq!(body["days"][4]["break_minutes"], 60);
+    assert_eq!(body["totals"]["worked_minutes"], 480);
+    assert_eq!(body["totals"]["days_worked"], 1);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_get_monthly_timesheet_invalid_month() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/timesheet?year=2025&month=13"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    cleanup_user(&pool, user_id).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_break_start_requires_clock_in() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "break_start",
+        "event_time": "2025-11-05T03:00:00Z"
+    });
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["error"], "validation_error");
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_break_end_requires_break_start() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T00:00:00Z"
+    });
+    assert_eq!(
+        post_event(app.clone(), &payload).await.status(),
+        StatusCode::OK
+    );
+
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "break_end",
+        "event_time": "2025-11-05T04:00:00Z"
+    });
+    let response = post_event(app, &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_get_break_summary() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("break_start", "2025-11-05T03:00:00Z"),
+        ("break_end", "2025-11-05T03:45:00Z"),
+        ("break_start", "2025-11-05T06:00:00Z"),
+        ("break_end", "2025-11-05T06:15:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/breaks?date=2025-11-05"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["date"], "2025-11-05");
+    assert_eq!(body["breaks"].as_array().unwrap().len(), 2);
+    assert_eq!(body["breaks"][0]["minutes"], 45);
+    assert_eq!(body["total_break_minutes"], 60);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_get_overtime() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    // 2025-11-05 09:00-19:00 JST: 10 hours against the default 8 hour day
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("clock_out", "2025-11-05T10:00:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/overtime?period=2025-W45"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["period_start"], "2025-11-03");
+    assert_eq!(body["days"][0]["overtime_minutes"], 120);
+    assert_eq!(body["totals"]["overtime_minutes"], 120);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/overtime?period=last-week"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_export_timesheet_xlsx() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/timesheet.xlsx?year=2025&month=11"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    );
+    assert!(
+        response.headers()["content-disposition"]
+            .to_str()
+            .unwrap()
+            .starts_with("attachment;")
+    );
+
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(bytes.starts_with(b"PK"));
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_export_calendar_ics() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("clock_out", "2025-11-05T09:00:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/calendar.ics?from=2025-11-01&to=2025-11-30"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/calendar; charset=utf-8"
+    );
+
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let calendar = String::from_utf8(bytes.to_vec()).unwrap();
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+    assert!(calendar.contains("DTSTART:20251105T000000Z"));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!(
+                    "/api/users/{user_id}/attendance/calendar.ics?from=2025-12-01&to=2025-11-01"
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+/// Import events from a CSV, authenticated as `as_user`
+async fn post_import(app: Router, csv: &str, as_user: Uuid) -> axum::response::Response {
+    let boundary = "import-boundary";
+    let body = format!(
+        "--{boundary}\r\n\
+         Content-Disposition: form-data; name=\"file\"; filename=\"events.csv\"\r\n\
+         Content-Type: text/csv\r\n\r\n\
+         {csv}\r\n\
+         --{boundary}--\r\n"
+    );
+
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/attendance-events/import")
+            .header("authorization", bearer(as_user))
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn user_email(pool: &PgPool, user_id: Uuid) -> String {
+    sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_import_attendance_events() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let email = user_email(&pool, user_id).await;
+
+    let csv = format!(
+        "email,event_type,event_time\n\
+         {email},clock_in,2025-11-05T09:00:00+09:00\n\
+         {email},clock_out,2025-11-05T18:00:00+09:00"
+    );
+    let response = post_import(app.clone(), &csv, manager).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["imported"], 2);
+    assert_eq!(body["errors"], json!([]));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(user_id))
+                .uri(format!("/api/users/{user_id}/attendance-events"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 2);
+    assert_eq!(body["items"][0]["event_type"], "clock_out");
+    assert_eq!(body["items"][0]["event_time"], "2025-11-05T09:00:00Z");
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager).await;
+}
+
+#[tokio::test]
+async fn test_import_attendance_events_reports_row_errors() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let email = user_email(&pool, user_id).await;
+
+    let csv = format!(
+        "email,event_type,event_time\n\
+         {email},clock_in,2025-11-05T09:00:00+09:00\n\
+         {email},lunch,2025-11-05T12:00:00+09:00\n\
+         nobody-{user_id}@example.com,clock_out,2025-11-05T18:00:00+09:00"
+    );
+    let response = post_import(app.clone(), &csv, manager).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["imported"], 0);
+    let lines: Vec<u64> = body["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["line"].as_u64().unwrap())
+        .collect();
+    assert_eq!(lines, vec![3, 4]);
+
+    // Nothing is imported when any row is invalid
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 0);
+
+    let response = post_import(app, "email,event_type\n", manager).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager).await;
+}
+
+#[tokio::test]
+async fn test_get_attendance_summary() {
+    let (app, pool) = create_app().await;
+    let on_break = insert_user(&pool).await;
+    let left = insert_user(&pool).await;
+    let absent = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    for (user_id, event_type, event_time) in [
+        (on_break, "clock_in", "2025-11-05T00:00:00Z"),
+        (on_break, "break_start", "2025-11-05T03:00:00Z"),
+        (left, "clock_in", "2025-11-05T01:00:00Z"),
+        (left, "clock_out", "2025-11-05T09:00:00Z"),
+        // Previous day, not part of the summary
+        (absent, "clock_in", "2025-11-04T00:00:00Z"),
+    ] {
+        let payload = json!({
+            "user_id": user_id,
+            "event_type": event_type,
+            "event_time": event_time
+        });
+        let response = post_event(app.clone(), &payload).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(manager))
+                .uri(format!(
+                    "/api/attendance/summary?date=2025-11-05&user_ids={absent},{on_break},{left},{}",
+                    Uuid::new_v4()
+                ))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["date"], "2025-11-05");
+    let users = body["users"].as_array().unwrap();
+    assert_eq!(users.len(), 3);
+
+    assert_eq!(users[0]["user_id"], absent.to_string());
+    assert_eq!(users[0]["status"], "absent");
+    assert!(users[0]["first_clock_in"].is_null());
+
+    assert_eq!(users[1]["user_id"], on_break.to_string());
+    assert_eq!(users[1]["status"], "on_break");
+    assert_eq!(users[1]["first_clock_in"], "2025-11-05T00:00:00Z");
+    assert_eq!(users[1]["last_event_at"], "2025-11-05T03:00:00Z");
+
+    assert_eq!(users[2]["user_id"], left.to_string());
+    assert_eq!(users[2]["status"], "absent");
+    assert_eq!(users[2]["first_clock_in"], "2025-11-05T01:00:00Z");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .header("authorization", bearer(manager))
+                .uri("/api/attendance/summary?user_ids=not-a-uuid")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    for user_id in [on_break, left, absent, manager] {
+        cleanup_user(&pool, user_id).await;
+    }
+}
+
+#[tokio::test]
+async fn test_deactivated_user_cannot_record_attendance() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    sqlx::query("UPDATE users SET is_active = FALSE WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Deactivated users cannot authenticate, so a manager records for them
+    let response = post_event_as(
+        app,
+        &json!({
+            "user_id": user_id,
+            "event_type": "clock_in",
+            "event_time": "2025-11-05T09:00:00Z"
+        }),
+        manager,
+    )
+    .await;
+    let status = response.status();
+    let body = parse_json_body(response.into_body()).await;
+    let events: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "forbidden");
+    assert_eq!(events, 0);
+}
+
+/// Send a request without a body, authenticated as a user if one is given
+async fn send(app: Router, method: &str, uri: &str, user_id: Option<Uuid>) -> StatusCode {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(user_id) = user_id {
+        request = request.header("authorization", bearer(user_id));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_attendance_access_by_role() {
+    let (app, pool) = create_app().await;
+    let member = insert_user(&pool).await;
+    let other = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let payload = json!({
+        "user_id": other,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+    let events = format!("/api/users/{other}/attendance-events");
+    let timesheet = format!("/api/users/{other}/attendance/timesheet?year=2025&month=11");
+    let summary = format!("/api/attendance/summary?user_ids={member},{other}");
+
+    let anonymous_record = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+    let member_record = post_event_as(app.clone(), &payload, member).await.status();
+    let manager_record = post_event_as(app.clone(), &payload, manager).await;
+    let event_id = parse_json_body(manager_record.into_body()).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let event = format!("/api/attendance-events/{event_id}");
+    let anonymous_events = send(app.clone(), "GET", &events, None).await;
+    let member_events = send(app.clone(), "GET", &events, Some(member)).await;
+    let member_event = send(app.clone(), "GET", &event, Some(member)).await;
+    let member_timesheet = send(app.clone(), "GET", &timesheet, Some(member)).await;
+    let member_summary = send(app.clone(), "GET", &summary, Some(member)).await;
+    let member_import = post_import(app.clone(), "email,event_type,event_time\n", member)
+        .await
+        .status();
+    let manager_events = send(app.clone(), "GET", &events, Some(manager)).await;
+    let manager_event = send(app.clone(), "GET", &event, Some(manager)).await;
+    let manager_timesheet = send(app.clone(), "GET", &timesheet, Some(manager)).await;
+    let manager_summary = send(app, "GET", &summary, Some(manager)).await;
+
+    for id in [member, other, manager] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(anonymous_record, StatusCode::UNAUTHORIZED);
+    assert_eq!(member_record, StatusCode::FORBIDDEN);
+    assert_eq!(anonymous_events, StatusCode::UNAUTHORIZED);
+    assert_eq!(member_events, StatusCode::FORBIDDEN);
+    assert_eq!(member_event, StatusCode::FORBIDDEN);
+    assert_eq!(member_timesheet, StatusCode::FORBIDDEN);
+    assert_eq!(member_summary, StatusCode::FORBIDDEN);
+    assert_eq!(member_import, StatusCode::FORBIDDEN);
+    assert_eq!(manager_events, StatusCode::OK);
+    assert_eq!(manager_event, StatusCode::OK);
+    assert_eq!(manager_timesheet, StatusCode::OK);
+    assert_eq!(manager_summary, StatusCode::OK);
+}
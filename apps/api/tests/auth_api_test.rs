@@ -0,0 +1,1128 @@
+mod helpers;
+
+use api::mail::{Email, MailTransport, Mailer};
+use api::oauth::{GoogleOAuth, GoogleOAuthConfig};
+use axum::{
+    Form, Json, Router,
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Request, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures_util::future::BoxFuture;
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    (
+        api::create_router(
+            &api::AppConfig::default(),
+            api::TodoStore::new(),
+            pool.clone(),
+        ),
+        pool,
+    )
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn post(app: Router, uri: &str, payload: &Value) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+/// Send a JSON POST request authenticated with an access token
+async fn post_with_token(app: Router, uri: &str, token: &str, payload: &Value) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+async fn test_register_and_login() {
+    let (app, pool) = create_app().await;
+    let email = format!("auth-{}@example.com", Uuid::new_v4());
+
+    let (registered, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Auth User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let (duplicate, _) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Again", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let (logged_in, login) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let (wrong_password, error) = post(
+        app,
+        "/api/auth/login",
+        &json!({ "email": email, "password": "wrong-password" }),
+    )
+    .await;
+
+    let hash: Option<String> =
+        sqlx::query_scalar("SELECT password_hash FROM users WHERE email = $1")
+            .bind(&email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    if let Some(id) = body["user"]["id"].as_str().and_then(|id| id.parse().ok()) {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(registered, StatusCode::OK);
+    assert_eq!(body["token_type"], "Bearer");
+    assert!(body["access_token"].as_str().is_some_and(|t| !t.is_empty()));
+    assert_eq!(body["user"]["email"], email);
+    // The hash is stored but never returned
+    assert!(body["user"].get("password_hash").is_none());
+    assert!(hash.is_some_and(|hash| hash.starts_with("$argon2id$")));
+    assert_eq!(duplicate, StatusCode::CONFLICT);
+    assert_eq!(logged_in, StatusCode::OK);
+    assert_eq!(login["user"]["id"], body["user"]["id"]);
+    assert_eq!(wrong_password, StatusCode::UNAUTHORIZED);
+    assert_eq!(error["message"], "Invalid email or password");
+    assert_eq!(error["code"], "INVALID_CREDENTIALS");
+}
+
+#[tokio::test]
+async fn test_emails_are_case_insensitive() {
+    let (app, pool) = create_app().await;
+    let local = Uuid::new_v4();
+    let email = format!(" Mixed-{local}@Example.COM ");
+    let normalized = format!("mixed-{local}@example.com");
+
+    let (registered, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Mixed Case", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let (duplicate, _) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Again", "email": normalized, "password": "s3cret-password" }),
+    )
+    .await;
+    let (logged_in, login) = post(
+        app,
+        "/api/auth/login",
+        &json!({ "email": normalized.to_uppercase(), "password": "s3cret-password" }),
+    )
+    .await;
+
+    if let Some(id) = body["user"]["id"].as_str().and_then(|id| id.parse().ok()) {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(registered, StatusCode::OK);
+    assert_eq!(body["user"]["email"], normalized);
+    assert_eq!(duplicate, StatusCode::CONFLICT);
+    assert_eq!(logged_in, StatusCode::OK);
+    assert_eq!(login["user"]["id"], body["user"]["id"]);
+}
+
+#[tokio::test]
+async fn test_login_rejects_users_without_password() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let (status, _) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": email, "password": "any-password" }),
+    )
+    .await;
+    let (unknown, _) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": "nobody@example.com", "password": "any-password" }),
+    )
+    .await;
+    let (short, _) = post(
+        app,
+        "/api/auth/register",
+        &json!({ "name": "Short", "email": "short@example.com", "password": "short" }),
+    )
+    .await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown, StatusCode::UNAUTHORIZED);
+    assert_eq!(short, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_change_password() {
+    let (app, pool) = create_app().await;
+    let email = format!("change-{}@example.com", Uuid::new_v4());
+    let (_, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Changer", "email": email, "password": "old-passw0rd" }),
+    )
+    .await;
+    let id = body["user"]["id"].as_str().unwrap().to_string();
+    let token = body["access_token"].as_str().unwrap().to_string();
+    let uri = format!("/api/users/{id}/password");
+
+    let (anonymous, _) = post(
+        app.clone(),
+        &uri,
+        &json!({ "current_password": "old-passw0rd", "new_password": "new-passw0rd" }),
+    )
+    .await;
+    let wrong = post_with_token(
+        app.clone(),
+        &uri,
+        &token,
+        &json!({ "current_password": "not-my-passw0rd", "new_password": "new-passw0rd" }),
+    )
+    .await;
+    let weak = post_with_token(
+        app.clone(),
+        &uri,
+        &token,
+        &json!({ "current_password": "old-passw0rd", "new_password": "no-digits-here" }),
+    )
+    .await;
+    // Another user's password cannot be changed
+    let other = post_with_token(
+        app.clone(),
+        &format!("/api/users/{}/password", Uuid::new_v4()),
+        &token,
+        &json!({ "current_password": "old-passw0rd", "new_password": "new-passw0rd" }),
+    )
+    .await;
+    let changed = post_with_token(
+        app.clone(),
+        &uri,
+        &token,
+        &json!({ "current_password": "old-passw0rd", "new_password": "new-passw0rd" }),
+    )
+    .await;
+    // Tokens issued before the change are revoked
+    let old_token = with_token(app.clone(), "GET", "/api/me", &token).await;
+    let (old_login, _) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": email, "password": "old-passw0rd" }),
+    )
+    .await;
+    let (new_login, _) = post(
+        app,
+        "/api/auth/login",
+        &json!({ "email": email, "password": "new-passw0rd" }),
+    )
+    .await;
+
+    cleanup_user(&pool, id.parse().unwrap()).await;
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong, StatusCode::UNAUTHORIZED);
+    assert_eq!(weak, StatusCode::BAD_REQUEST);
+    assert_eq!(other, StatusCode::FORBIDDEN);
+    assert_eq!(changed, StatusCode::OK);
+    assert_eq!(old_token, StatusCode::UNAUTHORIZED);
+    assert_eq!(old_login, StatusCode::UNAUTHORIZED);
+    assert_eq!(new_login, StatusCode::OK);
+}
+
+/// Mail transport keeping sent emails for inspection
+#[derive(Clone, Default)]
+struct Outbox(Arc<Mutex<Vec<Email>>>);
+
+impl MailTransport for Outbox {
+    fn send<'a>(&'a self, email: &'a Email) -> BoxFuture<'a, api::error::Result<()>> {
+        self.0.lock().unwrap().push(email.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Outbox {
+    /// Emails sent to an address, waiting briefly for background sends
+    async fn sent_to(&self, to: &str, expected: usize) -> Vec<Email> {
+        for _ in 0..50 {
+            let sent: Vec<Email> = self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|email| email.to == to)
+                .cloned()
+                .collect();
+            if sent.len() >= expected {
+                return sent;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        Vec::new()
+    }
+}
+
+/// Helper function to create the test app delivering emails to an outbox
+async fn create_app_with_outbox() -> (Router, PgPool, Outbox) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let outbox = Outbox::default();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.mailer = Mailer::new(outbox.clone());
+    state.password_reset.url = Some("https://app.example.com/reset".to_string());
+
+    (api::router(state), pool, outbox)
+}
+
+/// Token of a reset email
+fn reset_token(email: &Email) -> String {
+    let (_, rest) = email.body.split_once("?token=").unwrap();
+    rest.split_whitespace().next().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_forgot_and_reset_password() {
+    let (app, pool, outbox) = create_app_with_outbox().await;
+    let email = format!("forgot-{}@example.com", Uuid::new_v4());
+    let (_, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Forgetful", "email": email, "password": "old-passw0rd" }),
+    )
+    .await;
+
+    let (requested, _) = post(
+        app.clone(),
+        "/api/auth/forgot-password",
+        &json!({ "email": email }),
+    )
+    .await;
+    let sent = outbox.sent_to(&email, 1).await;
+    let token = sent.first().map(reset_token).unwrap_or_default();
+
+    let (weak, _) = post(
+        app.clone(),
+        "/api/auth/reset-password",
+        &json!({ "token": token, "new_password": "short" }),
+    )
+    .await;
+    let (reset, _) = post(
+        app.clone(),
+        "/api/auth/reset-password",
+        &json!({ "token": token, "new_password": "new-passw0rd" }),
+    )
+    .await;
+    // Tokens are single-use
+    let (reused, _) = post(
+        app.clone(),
+        "/api/auth/reset-password",
+        &json!({ "token": token, "new_password": "other-passw0rd" }),
+    )
+    .await;
+    let (login, _) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": email, "password": "new-passw0rd" }),
+    )
+    .await;
+    let (unknown, _) = post(
+        app,
+        "/api/auth/forgot-password",
+        &json!({ "email": format!("nobody-{}@example.com", Uuid::new_v4()) }),
+    )
+    .await;
+
+    cleanup_user(&pool, body["user"]["id"].as_str().unwrap().parse().unwrap()).await;
+
+    assert_eq!(requested, StatusCode::ACCEPTED);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(token.len(), 64);
+    assert_eq!(weak, StatusCode::BAD_REQUEST);
+    assert_eq!(reset, StatusCode::OK);
+    assert_eq!(reused, StatusCode::BAD_REQUEST);
+    assert_eq!(login, StatusCode::OK);
+    // Unknown addresses get the same response
+    assert_eq!(unknown, StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_forgot_password_is_rate_limited() {
+    let (app, pool, outbox) = create_app_with_outbox().await;
+    let user_id = insert_user(&pool).await;
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let mut statuses = Vec::new();
+    for _ in 0..4 {
+        let (status, _) = post(
+            app.clone(),
+            "/api/auth/forgot-password",
+            &json!({ "email": email }),
+        )
+        .await;
+        statuses.push(status);
+    }
+    let sent = outbox.sent_to(&email, 3).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let tokens: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM password_reset_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    cleanup_user(&pool, user_id).await;
+
+    assert!(
+        statuses
+            .iter()
+            .all(|&status| status == StatusCode::ACCEPTED)
+    );
+    assert_eq!(sent.len(), 3);
+    assert_eq!(tokens, 3);
+}
+
+/// Google profiles served by [`mock_google`], by authorization code
+type GoogleAccounts = Arc<Mutex<HashMap<String, Value>>>;
+
+/// Helper function to start a stand-in for Google's token and userinfo endpoints
+///
+/// The access token issued for a code is the code itself.
+async fn mock_google(accounts: GoogleAccounts) -> String {
+    async fn token(
+        State(accounts): State<GoogleAccounts>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Response {
+        match form.get("code") {
+            Some(code) if accounts.lock().unwrap().contains_key(code) => {
+                Json(json!({ "access_token": code, "token_type": "Bearer" })).into_response()
+            }
+            _ => (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "invalid_grant" })),
+            )
+                .into_response(),
+        }
+    }
+
+    async fn userinfo(State(accounts): State<GoogleAccounts>, headers: HeaderMap) -> Response {
+        let code = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        accounts.lock().unwrap().get(code).map_or_else(
+            || StatusCode::UNAUTHORIZED.into_response(),
+            |profile| Json(profile.clone()).into_response(),
+        )
+    }
+
+    let google = Router::new()
+        .route("/token", axum::routing::post(token))
+        .route("/userinfo", axum::routing::get(userinfo))
+        .with_state(accounts);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, google).await.unwrap() });
+    format!("http://{addr}")
+}
+
+/// Helper function to create the test app signing in with the mock Google
+async fn create_app_with_google() -> (Router, PgPool, GoogleAccounts) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let accounts = GoogleAccounts::default();
+    let google = mock_google(accounts.clone()).await;
+
+    let mut config = GoogleOAuthConfig::new(
+        "client-id",
+        "client-secret",
+        "http://localhost:3000/api/auth/google/callback",
+    );
+    config.auth_url = format!("{google}/auth");
+    config.token_url = format!("{google}/token");
+    config.userinfo_url = format!("{google}/userinfo");
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.google_oauth = Some(GoogleOAuth::new(config));
+
+    (api::router(state), pool, accounts)
+}
+
+/// Helper function to sign in through the callback, with `state` in the cookie
+async fn google_callback(app: Router, state: &str, code: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/auth/google/callback?state={state}&code={code}"
+                ))
+                .header(header::COOKIE, format!("google_oauth_state={state}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+fn google_profile(sub: &str, email: &str, verified: bool) -> Value {
+    json!({
+        "sub": sub,
+        "email": email,
+        "email_verified": verified,
+        "name": "Google User",
+        "picture": "https://example.com/google.png",
+    })
+}
+
+#[tokio::test]
+async fn test_google_sign_in_redirects_to_google() {
+    let (app, _pool, _) = create_app_with_google().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/google")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let location = response.headers()[header::LOCATION].to_str().unwrap();
+    let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+    let state = cookie
+        .strip_prefix("google_oauth_state=")
+        .and_then(|rest| rest.split(';').next())
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert!(location.contains("/auth?client_id=client-id&"));
+    assert!(location.contains(&format!("&state={state}")));
+    assert!(!state.is_empty());
+    assert!(cookie.contains("HttpOnly"));
+}
+
+#[tokio::test]
+async fn test_google_sign_in_creates_and_links_users() {
+    let (app, pool, accounts) = create_app_with_google().await;
+    let existing_id = insert_user(&pool).await;
+    let existing_email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(existing_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let new_email = format!("google-{}@example.com", Uuid::new_v4());
+    let (new_sub, existing_sub) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+    {
+        let mut accounts = accounts.lock().unwrap();
+        accounts.insert("new".into(), google_profile(&new_sub, &new_email, true));
+        accounts.insert(
+            "existing".into(),
+            google_profile(&existing_sub, &existing_email, true),
+        );
+    }
+
+    let (created, body) = google_callback(app.clone(), "s1", "new").await;
+    let (again, again_body) = google_callback(app.clone(), "s2", "new").await;
+    let (linked, linked_body) = google_callback(app.clone(), "s3", "existing").await;
+    let new_id: Uuid = body["user"]["id"].as_str().unwrap().parse().unwrap();
+    let linked_sub: Option<String> =
+        sqlx::query_scalar("SELECT google_sub FROM users WHERE id = $1")
+            .bind(existing_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    cleanup_user(&pool, new_id).await;
+    cleanup_user(&pool, existing_id).await;
+
+    assert_eq!(created, StatusCode::OK);
+    assert_eq!(body["token_type"], "Bearer");
+    assert!(body["access_token"].is_string());
+    assert_eq!(body["user"]["email"], new_email.as_str());
+    assert_eq!(body["user"]["name"], "Google User");
+    assert_eq!(again, StatusCode::OK);
+    assert_eq!(again_body["user"]["id"], body["user"]["id"]);
+    assert_eq!(linked, StatusCode::OK);
+    assert_eq!(linked_body["user"]["id"], existing_id.to_string());
+    assert_eq!(linked_sub, Some(existing_sub));
+}
+
+#[tokio::test]
+async fn test_google_callback_rejects_invalid_sign_ins() {
+    let (app, pool, accounts) = create_app_with_google().await;
+    let email = format!("unverified-{}@example.com", Uuid::new_v4());
+    accounts.lock().unwrap().insert(
+        "unverified".into(),
+        google_profile(&Uuid::new_v4().to_string(), &email, false),
+    );
+
+    let forged = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/google/callback?state=forged&code=unverified")
+                .header(header::COOKIE, "google_oauth_state=expected")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let (unknown_code, _) = google_callback(app.clone(), "s1", "unknown").await;
+    let (unverified, error) = google_callback(app, "s2", "unverified").await;
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE email = $1")
+        .bind(&email)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(unknown_code, StatusCode::UNAUTHORIZED);
+    assert_eq!(unverified, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        error["message"],
+        "The Google account has no verified email address"
+    );
+    assert_eq!(users, 0);
+}
+
+/// Helper function to send a request with the session cookie and its CSRF token
+async fn with_session(app: Router, method: &str, uri: &str, session: &str) -> Response {
+    app.oneshot(
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::COOKIE, format!("session={session}"))
+            .header("x-csrf-token", api::session::csrf_token(session))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Value of the `session` cookie set by a response
+fn session_cookie(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(|value| value.strip_prefix("session="))
+        .map(|value| value.split(';').next().unwrap_or_default().to_string())
+}
+
+#[tokio::test]
+async fn test_session_cookie_login_and_logout() {
+    let (app, pool) = create_app().await;
+    let email = format!("session-{}@example.com", Uuid::new_v4());
+    let (_, registered) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Session User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let user_id: Uuid = registered["user"]["id"].as_str().unwrap().parse().unwrap();
+
+    let login = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .header("user-agent", "session-test")
+                .body(Body::from(
+                    json!({ "email": email, "password": "s3cret-password" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let login_status = login.status();
+    let set_cookie = login.headers()[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let session = session_cookie(&login).unwrap();
+
+    let me = with_session(app.clone(), "GET", "/api/me", &session).await;
+    let me_status = me.status();
+    let me_body = parse_json_body(me.into_body()).await;
+    let user_agent: Option<String> = sqlx::query_scalar(
+        "SELECT user_agent FROM sessions WHERE user_id = $1 ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let logout = with_session(app.clone(), "POST", "/api/auth/logout", &session).await;
+    let logout_status = logout.status();
+    let cleared = session_cookie(&logout);
+    let after_logout = with_session(app, "GET", "/api/me", &session).await;
+    let after_logout_status = after_logout.status();
+    let after_logout_cookie = session_cookie(&after_logout);
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(login_status, StatusCode::OK);
+    assert!(set_cookie.contains("HttpOnly"));
+    assert!(set_cookie.contains("SameSite=Lax"));
+    assert_eq!(me_status, StatusCode::OK);
+    assert_eq!(me_body["email"], email.as_str());
+    assert_eq!(user_agent.as_deref(), Some("session-test"));
+    assert_eq!(logout_status, StatusCode::NO_CONTENT);
+    assert_eq!(cleared.as_deref(), Some(""));
+    assert_eq!(after_logout_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(after_logout_cookie.as_deref(), Some(""));
+}
+
+#[tokio::test]
+async fn test_session_expiration_slides() {
+    let (app, pool) = create_app().await;
+    let email = format!("sliding-{}@example.com", Uuid::new_v4());
+    let registered = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "name": "Sliding", "email": email, "password": "s3cret-password" })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let session = session_cookie(&registered).unwrap();
+    let user_id: Uuid = parse_json_body(registered.into_body()).await["user"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    // Used a while ago and about to expire
+    sqlx::query(
+        "UPDATE sessions SET last_seen_at = NOW() - INTERVAL '10 minutes', expires_at = NOW() + INTERVAL '1 minute' WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let extended = with_session(app.clone(), "GET", "/api/me", &session).await;
+    let extended_status = extended.status();
+    let renewed = session_cookie(&extended);
+    let remaining_minutes: f64 = sqlx::query_scalar(
+        "SELECT EXTRACT(EPOCH FROM expires_at - NOW())::float8 / 60 FROM sessions WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // Not used within its lifetime
+    sqlx::query("UPDATE sessions SET expires_at = NOW() - INTERVAL '1 second' WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let expired = with_session(app, "GET", "/api/me", &session).await;
+    let expired_status = expired.status();
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(extended_status, StatusCode::OK);
+    assert_eq!(renewed.as_deref(), Some(session.as_str()));
+    assert!(remaining_minutes > 60.0);
+    assert_eq!(expired_status, StatusCode::UNAUTHORIZED);
+}
+
+/// Helper function to create the test app locking accounts after 3 failed logins
+async fn create_app_with_lockout() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.lockout_policy = api::password::LockoutPolicy {
+        max_attempts: 3,
+        cooldown: chrono::Duration::minutes(15),
+    };
+
+    (api::router(state), pool)
+}
+
+/// Helper function to log in, returning the status, `Retry-After` and body
+async fn login(app: Router, email: &str, password: &str) -> (StatusCode, Option<u64>, Value) {
+    let payload = json!({ "email": email, "password": password });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .map(|value| value.to_str().unwrap().parse().unwrap());
+    (
+        status,
+        retry_after,
+        parse_json_body(response.into_body()).await,
+    )
+}
+
+async fn unlock(app: Router, admin: Uuid, id: &str) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/admin/users/{id}/unlock"))
+            .header("authorization", bearer(admin))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+async fn test_repeated_failed_logins_lock_the_account() {
+    let (app, pool) = create_app_with_lockout().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let email = format!("lockout-{}@example.com", Uuid::new_v4());
+    let (_, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Lockout User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let id = body["user"]["id"].as_str().unwrap().to_string();
+
+    let mut failures = Vec::new();
+    for _ in 0..2 {
+        failures.push(login(app.clone(), &email, "wrong-password").await.0);
+    }
+    let (locked, retry_after, error) = login(app.clone(), &email, "wrong-password").await;
+    let (correct_while_locked, _, _) = login(app.clone(), &email, "s3cret-password").await;
+    let unlocked = unlock(app.clone(), admin, &id).await;
+    let (after_unlock, _, _) = login(app.clone(), &email, "s3cret-password").await;
+    let unknown = unlock(app, admin, &Uuid::new_v4().to_string()).await;
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, id.parse().unwrap()).await;
+
+    assert_eq!(failures, [StatusCode::UNAUTHORIZED; 2]);
+    assert_eq!(locked, StatusCode::LOCKED);
+    assert_eq!(error["error"], "account_locked");
+    assert_eq!(error["code"], "ACCOUNT_LOCKED");
+    assert!(retry_after.is_some_and(|seconds| (14 * 60..=15 * 60 + 1).contains(&seconds)));
+    assert_eq!(correct_while_locked, StatusCode::LOCKED);
+    assert_eq!(unlocked, StatusCode::NO_CONTENT);
+    assert_eq!(after_unlock, StatusCode::OK);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_successful_login_resets_failed_logins_and_locks_expire() {
+    let (app, pool) = create_app_with_lockout().await;
+    let email = format!("lockout-{}@example.com", Uuid::new_v4());
+    let (_, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Lockout User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let id: Uuid = body["user"]["id"].as_str().unwrap().parse().unwrap();
+
+    let mut statuses = Vec::new();
+    for password in ["wrong-password", "wrong-password", "s3cret-password"] {
+        statuses.push(login(app.clone(), &email, password).await.0);
+    }
+    for _ in 0..2 {
+        statuses.push(login(app.clone(), &email, "wrong-password").await.0);
+    }
+    sqlx::query("UPDATE users SET locked_until = NOW() - INTERVAL '1 second' WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (after_expiry, _, _) = login(app, &email, "s3cret-password").await;
+
+    cleanup_user(&pool, id).await;
+
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::OK,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::UNAUTHORIZED,
+        ]
+    );
+    assert_eq!(after_expiry, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_wrong_current_passwords_lock_the_account() {
+    let (app, pool) = create_app_with_lockout().await;
+    let email = format!("lockout-{}@example.com", Uuid::new_v4());
+    let (_, body) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Lockout User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let id: Uuid = body["user"]["id"].as_str().unwrap().parse().unwrap();
+    let token = body["access_token"].as_str().unwrap().to_string();
+    let uri = format!("/api/users/{id}/password");
+
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(
+            post_with_token(
+                app.clone(),
+                &uri,
+                &token,
+                &json!({ "current_password": "wrong-passw0rd", "new_password": "new-passw0rd" }),
+            )
+            .await,
+        );
+    }
+    let correct_while_locked = post_with_token(
+        app.clone(),
+        &uri,
+        &token,
+        &json!({ "current_password": "s3cret-password", "new_password": "new-passw0rd" }),
+    )
+    .await;
+    let (login_while_locked, _, _) = login(app, &email, "s3cret-password").await;
+
+    cleanup_user(&pool, id).await;
+
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::LOCKED,
+        ]
+    );
+    assert_eq!(correct_while_locked, StatusCode::LOCKED);
+    assert_eq!(login_while_locked, StatusCode::LOCKED);
+}
+
+/// Helper function to send a request with a bearer token
+async fn with_token(app: Router, method: &str, uri: &str, token: &str) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+async fn test_logout_all_revokes_tokens_and_sessions() {
+    let (app, pool) = create_app().await;
+    let email = format!("logout-all-{}@example.com", Uuid::new_v4());
+    let (_, registered) = post(
+        app.clone(),
+        "/api/auth/register",
+        &json!({ "name": "Logout User", "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let user_id: Uuid = registered["user"]["id"].as_str().unwrap().parse().unwrap();
+    let first_token = registered["access_token"].as_str().unwrap().to_string();
+
+    let login = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "email": email, "password": "s3cret-password" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let session = session_cookie(&login).unwrap();
+    let second_token = parse_json_body(login.into_body()).await["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let logout_all = with_token(app.clone(), "POST", "/api/auth/logout-all", &first_token).await;
+    let first_after = with_token(app.clone(), "GET", "/api/me", &first_token).await;
+    let second_after = with_token(app.clone(), "GET", "/api/me", &second_token).await;
+    let session_after = with_session(app.clone(), "GET", "/api/me", &session)
+        .await
+        .status();
+    let (_, relogin) = post(
+        app.clone(),
+        "/api/auth/login",
+        &json!({ "email": email, "password": "s3cret-password" }),
+    )
+    .await;
+    let new_token = relogin["access_token"].as_str().unwrap().to_string();
+    let new_after = with_token(app.clone(), "GET", "/api/me", &new_token).await;
+    let anonymous = post(app, "/api/auth/logout-all", &json!({})).await.0;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(logout_all, StatusCode::NO_CONTENT);
+    assert_eq!(first_after, StatusCode::UNAUTHORIZED);
+    assert_eq!(second_after, StatusCode::UNAUTHORIZED);
+    assert_eq!(session_after, StatusCode::UNAUTHORIZED);
+    assert_eq!(new_after, StatusCode::OK);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+}
+
+/// Helper function to log out everywhere with the session cookie and an
+/// optional CSRF token
+async fn logout_all_with_session(
+    app: Router,
+    session: &str,
+    csrf_token: Option<&str>,
+) -> StatusCode {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri("/api/auth/logout-all")
+        .header(header::COOKIE, format!("session={session}"));
+    if let Some(csrf_token) = csrf_token {
+        request = request.header("x-csrf-token", csrf_token);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_session_requests_require_csrf_token() {
+    let (app, pool) = create_app().await;
+    let email = format!("csrf-{}@example.com", Uuid::new_v4());
+    let registered = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "name": "CSRF User", "email": email, "password": "s3cret-password" })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let session = session_cookie(&registered).unwrap();
+    let user_id: Uuid = parse_json_body(registered.into_body()).await["user"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let response = with_session(app.clone(), "GET", "/api/auth/csrf", &session).await;
+    let csrf_status = response.status();
+    let csrf_token = parse_json_body(response.into_body()).await["csrf_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let without_session = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/csrf")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+
+    let missing = logout_all_with_session(app.clone(), &session, None).await;
+    let wrong = logout_all_with_session(app.clone(), &session, Some("wrong-token")).await;
+    let valid = logout_all_with_session(app, &session, Some(&csrf_token)).await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(csrf_status, StatusCode::OK);
+    assert_eq!(without_session, StatusCode::UNAUTHORIZED);
+    assert_eq!(missing, StatusCode::FORBIDDEN);
+    assert_eq!(wrong, StatusCode::FORBIDDEN);
+    assert_eq!(valid, StatusCode::NO_CONTENT);
+}
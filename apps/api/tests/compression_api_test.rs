@@ -0,0 +1,126 @@
+mod helpers;
+
+use api::compression::CompressionSettings;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user, test_auth_tokens};
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app with compression settings and a
+/// user owning a todo with a large description
+///
+/// Tests must call `cleanup_user` for the owner when they are done.
+async fn create_app(compression: CompressionSettings) -> (Router, PgPool, Uuid) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.compression = compression;
+    let owner = insert_user(&pool).await;
+    let app = api::router(state);
+
+    for i in 0..3 {
+        let payload = json!({
+            "title": format!("Large todo {i}"),
+            "description": "Lorem ipsum dolor sit amet. ".repeat(30),
+        });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/todos")
+                    .header("authorization", bearer(owner))
+                    .header("content-type", "application/json")
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    (app, pool, owner)
+}
+
+/// Helper function to list the owner's todos, accepting `accept_encoding`
+async fn list_todos(app: Router, owner: Uuid, accept_encoding: Option<&str>) -> Response {
+    let mut request = Request::builder()
+        .uri("/api/todos")
+        .header("authorization", bearer(owner));
+    if let Some(encoding) = accept_encoding {
+        request = request.header(header::ACCEPT_ENCODING, encoding);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn content_encoding(response: &Response) -> Option<&str> {
+    response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn test_large_responses_are_compressed() {
+    let (app, pool, owner) = create_app(CompressionSettings::default()).await;
+
+    let gzip = list_todos(app.clone(), owner, Some("gzip")).await;
+    let br = list_todos(app.clone(), owner, Some("br;q=1.0, gzip;q=0.5")).await;
+    let identity = list_todos(app.clone(), owner, None).await;
+
+    assert_eq!(gzip.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&gzip), Some("gzip"));
+    assert_eq!(content_encoding(&br), Some("br"));
+    assert_eq!(identity.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&identity), None);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_small_responses_are_not_compressed() {
+    let (app, pool, owner) = create_app(CompressionSettings::default()).await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&response), None);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_compression_can_be_disabled() {
+    let (app, pool, owner) = create_app(CompressionSettings {
+        enabled: false,
+        ..CompressionSettings::default()
+    })
+    .await;
+
+    let response = list_todos(app, owner, Some("gzip")).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(content_encoding(&response), None);
+
+    cleanup_user(&pool, owner).await;
+}
@@ -0,0 +1,97 @@
+mod helpers;
+
+use api::cors::{AllowedOrigins, CorsSettings};
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderValue, Method, Request, header},
+    response::Response,
+};
+use helpers::TestContext;
+use tower::ServiceExt;
+
+/// Origin of the SPA dev server
+const ORIGIN: &str = "http://localhost:5173";
+
+/// Helper function to create the test app with CORS settings
+async fn create_app(cors: CorsSettings) -> Router {
+    let ctx = TestContext::new().await;
+    let mut state = api::AppState::new(api::TodoStore::new(), ctx.pool().clone());
+    state.cors = cors;
+    api::router(state)
+}
+
+/// Settings allowing the SPA dev server with cookies
+fn spa_settings() -> CorsSettings {
+    CorsSettings {
+        origins: Some(AllowedOrigins::List(vec![HeaderValue::from_static(ORIGIN)])),
+        methods: vec![Method::GET, Method::POST],
+        headers: vec![header::CONTENT_TYPE, header::AUTHORIZATION],
+        credentials: true,
+    }
+}
+
+/// Helper function to send a preflight request from an origin
+async fn preflight(app: Router, origin: &str) -> Response {
+    app.oneshot(
+        Request::builder()
+            .method("OPTIONS")
+            .uri("/api/auth/login")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_preflight_from_allowed_origin() {
+    let app = create_app(spa_settings()).await;
+
+    let allowed = preflight(app.clone(), ORIGIN).await;
+    let other = preflight(app.clone(), "https://evil.example.com").await;
+    let health = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(header::ORIGIN, ORIGIN)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let headers = allowed.headers();
+    assert!(allowed.status().is_success());
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], ORIGIN);
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+        .to_str()
+        .unwrap();
+    assert!(methods.contains("POST"));
+    assert!(
+        !other
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+    assert_eq!(
+        health.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        ORIGIN
+    );
+}
+
+#[tokio::test]
+async fn test_cors_is_disabled_by_default() {
+    let app = create_app(CorsSettings::default()).await;
+
+    let response = preflight(app, ORIGIN).await;
+
+    assert!(
+        !response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+}
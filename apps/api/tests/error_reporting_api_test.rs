@@ -0,0 +1,77 @@
+mod helpers;
+
+use api::reporting::{ErrorReport, ErrorReporter, set_reporter};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::TestContext;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+/// Reports recorded as (status, method, path, error)
+type Recorded = Arc<Mutex<Vec<(u16, String, String, String)>>>;
+
+/// Reporter recording every report
+struct RecordingReporter(Recorded);
+
+impl ErrorReporter for RecordingReporter {
+    fn report(&self, report: &ErrorReport<'_>) {
+        let (method, path) = report.request.map_or_else(Default::default, |request| {
+            (request.method.to_string(), request.path.clone())
+        });
+        self.0.lock().unwrap().push((
+            report.status.as_u16(),
+            method,
+            path,
+            report.error.to_string(),
+        ));
+    }
+}
+
+/// Helper function to get a status from the app
+async fn get(app: Router, uri: &str) -> StatusCode {
+    app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_server_errors_are_reported_with_request() {
+    let recorded = Recorded::default();
+    assert!(set_reporter(RecordingReporter(recorded.clone())).is_ok());
+
+    let ctx = TestContext::new().await;
+    let app = api::create_router(
+        &api::AppConfig::default(),
+        api::TodoStore::new(),
+        ctx.pool().clone(),
+    );
+
+    let internal = get(app.clone(), "/test/error/internal?token=secret").await;
+    let unavailable = get(app.clone(), "/test/error/service-unavailable").await;
+    let not_found = get(app, "/test/error/notfound").await;
+
+    assert_eq!(internal, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(unavailable, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(not_found, StatusCode::NOT_FOUND);
+    assert_eq!(
+        *recorded.lock().unwrap(),
+        [
+            (
+                500,
+                "GET".to_string(),
+                "/test/error/internal".to_string(),
+                "Internal server error: This is a test internal error".to_string(),
+            ),
+            (
+                503,
+                "GET".to_string(),
+                "/test/error/service-unavailable".to_string(),
+                "Service unavailable: Service temporarily unavailable".to_string(),
+            ),
+        ]
+    );
+}
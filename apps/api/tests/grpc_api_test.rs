@@ -0,0 +1,166 @@
+mod helpers;
+
+use api::grpc::proto::{
+    EventType, GetUserRequest, ListUserEventsRequest, RecordEventRequest,
+    attendance_service_client::AttendanceServiceClient, user_service_client::UserServiceClient,
+};
+use chrono::Utc;
+use helpers::{TestContext, cleanup_user, insert_api_key, insert_user};
+use prost_types::Timestamp;
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+use tonic::{Code, Request, transport::Channel};
+
+/// Key granting the read scopes
+const READ_KEY: &str = "ak_grpc_read_test_key";
+
+/// Key granting every attendance scope
+const ATTENDANCE_KEY: &str = "ak_grpc_attendance_test_key";
+
+/// Helper function to serve the gRPC router on a local port and connect to it
+async fn connect() -> (Channel, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    insert_api_key(&pool, READ_KEY, &["users:read", "attendance:read"]).await;
+    insert_api_key(
+        &pool,
+        ATTENDANCE_KEY,
+        &["attendance:read", "attendance:write"],
+    )
+    .await;
+
+    let state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, api::grpc::router(state)).into_future());
+
+    let channel = Channel::from_shared(format!("http://{addr}"))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    (channel, pool)
+}
+
+/// A request authenticated with an API key
+fn with_key<T>(message: T, key: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("x-api-key", key.parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn test_get_user() {
+    let (channel, pool) = connect().await;
+    let user_id = insert_user(&pool).await;
+    let mut users = UserServiceClient::new(channel);
+
+    let user = users
+        .get_user(with_key(
+            GetUserRequest {
+                id: user_id.to_string(),
+            },
+            READ_KEY,
+        ))
+        .await;
+    let missing = users
+        .get_user(with_key(
+            GetUserRequest {
+                id: uuid::Uuid::new_v4().to_string(),
+            },
+            READ_KEY,
+        ))
+        .await;
+    let invalid = users
+        .get_user(with_key(
+            GetUserRequest {
+                id: "not-a-uuid".to_string(),
+            },
+            READ_KEY,
+        ))
+        .await;
+
+    cleanup_user(&pool, user_id).await;
+
+    let user = user.unwrap().into_inner();
+    assert_eq!(user.id, user_id.to_string());
+    assert_eq!(user.name, "Test User");
+    assert!(user.is_active);
+    assert_eq!(missing.unwrap_err().code(), Code::NotFound);
+    assert_eq!(invalid.unwrap_err().code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_calls_require_a_key_with_the_scope() {
+    let (channel, pool) = connect().await;
+    let user_id = insert_user(&pool).await;
+    let mut users = UserServiceClient::new(channel);
+    let request = || GetUserRequest {
+        id: user_id.to_string(),
+    };
+
+    let anonymous = users.get_user(Request::new(request())).await;
+    let unknown = users.get_user(with_key(request(), "ak_unknown")).await;
+    let wrong_scope = users.get_user(with_key(request(), ATTENDANCE_KEY)).await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(anonymous.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(unknown.unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(wrong_scope.unwrap_err().code(), Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_record_and_list_events() {
+    let (channel, pool) = connect().await;
+    let user_id = insert_user(&pool).await;
+    let mut attendance = AttendanceServiceClient::new(channel);
+    let record = |event_type: EventType| RecordEventRequest {
+        user_id: user_id.to_string(),
+        event_type: event_type.into(),
+        event_time: Some(Timestamp {
+            seconds: Utc::now().timestamp() - 60,
+            nanos: 0,
+        }),
+        latitude: None,
+        longitude: None,
+        amends_event_id: None,
+    };
+
+    // A break cannot end before it started, as over HTTP
+    let unpaired = attendance
+        .record_event(with_key(record(EventType::BreakEnd), ATTENDANCE_KEY))
+        .await;
+    let unspecified = attendance
+        .record_event(with_key(record(EventType::Unspecified), ATTENDANCE_KEY))
+        .await;
+    let read_only = attendance
+        .record_event(with_key(record(EventType::ClockIn), READ_KEY))
+        .await;
+    let recorded = attendance
+        .record_event(with_key(record(EventType::ClockIn), ATTENDANCE_KEY))
+        .await;
+    let listed = attendance
+        .list_user_events(with_key(
+            ListUserEventsRequest {
+                user_id: user_id.to_string(),
+                include_history: false,
+            },
+            READ_KEY,
+        ))
+        .await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(unpaired.unwrap_err().code(), Code::InvalidArgument);
+    assert_eq!(unspecified.unwrap_err().code(), Code::InvalidArgument);
+    assert_eq!(read_only.unwrap_err().code(), Code::PermissionDenied);
+    let recorded = recorded.unwrap().into_inner();
+    assert_eq!(recorded.user_id, user_id.to_string());
+    assert_eq!(recorded.event_type(), EventType::ClockIn);
+    let events = listed.unwrap().into_inner().events;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, recorded.id);
+}
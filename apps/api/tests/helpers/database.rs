@@ -41,9 +41,8 @@ impl TestContext {
             .await
             .expect("Failed to connect to test database");
 
-        // Run migrations
-        sqlx::migrate!("./db/migrations")
-            .run(&pool)
+        // Run migrations the way the server does with RUN_MIGRATIONS
+        api::run_migrations(&pool)
             .await
             .expect("Failed to run migrations");
 
@@ -151,10 +150,7 @@ impl TestContext {
 // }
 //
 // Note on Router integration:
-// The current implementation uses repositories with stored PgPool,
-// which makes it challenging to use transactions with the router pattern.
-// For now, tests should use direct SQL queries with the transaction.
-// Future improvements could include:
-// 1. Modifying repositories to accept generic Executor trait
-// 2. Creating a test-specific router factory that uses transactions
-// 3. Using repository methods directly with transaction pool
+// The router runs every request on the pool, so API tests cannot share the
+// test transaction and clean up after themselves instead (see `cleanup_user`).
+// Repository methods ending in `_in` (e.g. `UserRepository::create_in`) run on
+// a connection or transaction, so repository tests can pass `&mut **tx`.
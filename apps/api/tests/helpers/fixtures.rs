@@ -0,0 +1,84 @@
+use api::auth::AuthTokens;
+use api::token;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Insert an active user directly and return its id
+///
+/// Each call uses a unique email so tests can run in parallel against the
+/// shared test database. Data created this way is committed, so tests must
+/// call [`cleanup_user`] when they are done.
+pub async fn insert_user(pool: &PgPool) -> Uuid {
+    let email = format!("test-{}@example.com", Uuid::new_v4());
+    let row: (Uuid,) =
+        sqlx::query_as("INSERT INTO users (name, email) VALUES ('Test User', $1) RETURNING id")
+            .bind(email)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to insert user");
+    row.0
+}
+
+/// Insert an active user with a role (`admin`, `manager` or `member`) and return its id
+///
+/// Like [`insert_user`], tests must call [`cleanup_user`] when they are done.
+pub async fn insert_user_with_role(pool: &PgPool, role: &str) -> Uuid {
+    let id = insert_user(pool).await;
+    sqlx::query("UPDATE users SET role = $2 WHERE id = $1")
+        .bind(id)
+        .bind(role)
+        .execute(pool)
+        .await
+        .expect("Failed to set user role");
+    id
+}
+
+/// Access token issuer with a fixed secret, for apps built with
+/// `state.auth_tokens = test_auth_tokens()`
+pub fn test_auth_tokens() -> AuthTokens {
+    AuthTokens::new(b"test-auth-secret", Duration::hours(1))
+}
+
+/// `Authorization` header value authenticating a user with [`test_auth_tokens`]
+/// Only valid while the user has not logged out everywhere (token version 0)
+pub fn bearer(user_id: Uuid) -> String {
+    format!(
+        "Bearer {}",
+        test_auth_tokens().issue(user_id, 0, Utc::now()).token
+    )
+}
+
+/// Permanently remove a user created by a test
+///
+/// Attendance events and other rows referencing the user are removed by
+/// `ON DELETE CASCADE`.
+pub async fn cleanup_user(pool: &PgPool, id: Uuid) {
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .expect("Failed to clean up user");
+}
+
+/// Store an API key with scopes (e.g. `kiosk:clock`) and return its id
+///
+/// The key is upserted, so tests can share a fixed key: storing it again
+/// restores its scopes and un-revokes it. Keys are not tied to users and are
+/// left in the test database.
+pub async fn insert_api_key(pool: &PgPool, key: &str, scopes: &[&str]) -> Uuid {
+    sqlx::query_scalar(
+        r"
+        INSERT INTO api_keys (name, key_prefix, key_hash, scopes)
+        VALUES ('Test key', 'ak_test', $1, $2)
+        ON CONFLICT (key_hash) DO UPDATE
+        SET scopes = EXCLUDED.scopes, revoked_at = NULL, expires_at = NULL
+        RETURNING id
+        ",
+    )
+    .bind(token::hash_opaque(key))
+    .bind(scopes)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to insert API key")
+}
@@ -1,3 +1,10 @@
+// Each integration test binary compiles these helpers separately and uses only some of them
+#![allow(dead_code, unused_imports)]
+
 pub mod database;
+pub mod fixtures;
 
 pub use database::TestContext;
+pub use fixtures::{
+    bearer, cleanup_user, insert_api_key, insert_user, insert_user_with_role, test_auth_tokens,
+};
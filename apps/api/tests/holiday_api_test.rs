@@ -0,0 +1,310 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+// Holidays are shared by all tests, so each test uses its own year far away
+// from the dates used by the attendance tests.
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn send(app: Router, method: &str, uri: &str, payload: Option<Value>) -> (StatusCode, Value) {
+    request(app, Request::builder().method(method).uri(uri), payload).await
+}
+
+/// Send a request authenticated as a user
+async fn send_as(
+    app: Router,
+    user_id: Uuid,
+    method: &str,
+    uri: &str,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", bearer(user_id));
+    request(app, builder, payload).await
+}
+
+/// Send a request with the payload, if any, as its JSON body
+async fn request(
+    app: Router,
+    builder: axum::http::request::Builder,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = match payload {
+        Some(payload) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    };
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+/// Remove all holidays of a year created by a test
+async fn cleanup_holidays(pool: &PgPool, year: i32) {
+    sqlx::query("DELETE FROM holidays WHERE EXTRACT(YEAR FROM date) = $1")
+        .bind(f64::from(year))
+        .execute(pool)
+        .await
+        .expect("Failed to clean up holidays");
+}
+
+#[tokio::test]
+async fn test_holiday_crud() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let (status, created) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/holidays",
+        Some(json!({ "date": "2031-01-01", "name": "New Year's Day" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let id: Uuid = created["id"].as_str().unwrap().parse().unwrap();
+
+    // A second holiday on the same day is rejected
+    let (duplicate_status, _) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/holidays",
+        Some(json!({ "date": "2031-01-01", "name": "Duplicate" })),
+    )
+    .await;
+
+    let (update_status, updated) = send_as(
+        app.clone(),
+        admin,
+        "PUT",
+        &format!("/api/holidays/{id}"),
+        Some(json!({ "name": "Ganjitsu" })),
+    )
+    .await;
+    let (_, list) = send_as(app.clone(), admin, "GET", "/api/holidays?year=2031", None).await;
+    let (delete_status, _) = send_as(
+        app.clone(),
+        admin,
+        "DELETE",
+        &format!("/api/holidays/{id}"),
+        None,
+    )
+    .await;
+    let (get_status, _) = send_as(app, admin, "GET", &format!("/api/holidays/{id}"), None).await;
+    cleanup_holidays(&pool, 2031).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(duplicate_status, StatusCode::BAD_REQUEST);
+    assert_eq!(update_status, StatusCode::OK);
+    assert_eq!(updated["name"], "Ganjitsu");
+    assert_eq!(updated["date"], "2031-01-01");
+    assert_eq!(list.as_array().unwrap().len(), 1);
+    assert_eq!(delete_status, StatusCode::OK);
+    assert_eq!(get_status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_bulk_create_holidays() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let payload = json!({
+        "year": 2032,
+        "holidays": [
+            { "date": "2032-11-03", "name": "Culture Day" },
+            { "date": "2032-01-01", "name": "New Year's Day" },
+            { "date": "2032-11-23", "name": "Labor Thanksgiving Day" }
+        ]
+    });
+
+    let (status, created) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/holidays/bulk",
+        Some(payload.clone()),
+    )
+    .await;
+    // Re-submitting the same calendar creates nothing
+    let (repeat_status, _) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/holidays/bulk",
+        Some(payload),
+    )
+    .await;
+    let (_, list) = send_as(app, admin, "GET", "/api/holidays?year=2032", None).await;
+    cleanup_holidays(&pool, 2032).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let dates: Vec<&str> = created
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|holiday| holiday["date"].as_str().unwrap())
+        .collect();
+    assert_eq!(dates, ["2032-01-01", "2032-11-03", "2032-11-23"]);
+    assert_eq!(repeat_status, StatusCode::BAD_REQUEST);
+    assert_eq!(list.as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_bulk_create_holidays_validation() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    for holidays in [
+        json!([]),
+        json!([{ "date": "2035-01-01", "name": "Wrong year" }]),
+        json!([
+            { "date": "2034-01-01", "name": "New Year's Day" },
+            { "date": "2034-01-01", "name": "Again" }
+        ]),
+        json!([{ "date": "2034-01-02", "name": " " }]),
+    ] {
+        let (status, _) = send_as(
+            app.clone(),
+            admin,
+            "POST",
+            "/api/holidays/bulk",
+            Some(json!({ "year": 2034, "holidays": holidays })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    cleanup_user(&pool, admin).await;
+}
+
+#[tokio::test]
+async fn test_holiday_work_counts_as_overtime() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    // 09:00-17:00 JST on a holiday
+    for (event_type, event_time) in [
+        ("clock_in", "2003-05-03T00:00:00Z"),
+        ("clock_out", "2003-05-03T08:00:00Z"),
+    ] {
+        let (status, _) = send_as(
+            app.clone(),
+            user_id,
+            "POST",
+            "/api/attendance-events",
+            Some(json!({
+                "user_id": user_id,
+                "event_type": event_type,
+                "event_time": event_time
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/holidays",
+        Some(json!({ "date": "2003-05-03", "name": "Constitution Day" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (overtime_status, overtime) = send_as(
+        app.clone(),
+        user_id,
+        "GET",
+        &format!("/api/users/{user_id}/attendance/overtime?period=2003-05"),
+        None,
+    )
+    .await;
+    let (timesheet_status, timesheet) = send_as(
+        app,
+        user_id,
+        "GET",
+        &format!("/api/users/{user_id}/attendance/timesheet?year=2003&month=5"),
+        None,
+    )
+    .await;
+    cleanup_holidays(&pool, 2003).await;
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(overtime_status, StatusCode::OK);
+    assert_eq!(overtime["days"][0]["is_holiday"], true);
+    assert_eq!(overtime["days"][0]["overtime_minutes"], 480);
+
+    assert_eq!(timesheet_status, StatusCode::OK);
+    assert_eq!(timesheet["days"][2]["is_holiday"], true);
+    assert_eq!(timesheet["days"][3]["is_holiday"], false);
+}
+
+#[tokio::test]
+async fn test_holiday_routes_require_permissions() {
+    let (app, pool) = create_app().await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let (anonymous, _) = send(app.clone(), "GET", "/api/holidays?year=2035", None).await;
+    let (listed, _) = send_as(app.clone(), member, "GET", "/api/holidays?year=2035", None).await;
+    let (by_manager, _) = send_as(
+        app.clone(),
+        manager,
+        "POST",
+        "/api/holidays",
+        Some(json!({ "date": "2035-01-01", "name": "New Year's Day" })),
+    )
+    .await;
+    let (created, body) = send_as(
+        app,
+        member,
+        "POST",
+        "/api/holidays",
+        Some(json!({ "date": "2035-01-01", "name": "New Year's Day" })),
+    )
+    .await;
+
+    cleanup_user(&pool, member).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(listed, StatusCode::OK);
+    assert_eq!(by_manager, StatusCode::FORBIDDEN);
+    assert_eq!(created, StatusCode::FORBIDDEN);
+    assert_eq!(body["message"], "The policies:write permission is required");
+}
@@ -0,0 +1,203 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    response::Response,
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app and a user creating todos
+///
+/// Tests must call `cleanup` when they are done.
+async fn create_app() -> (Router, PgPool, Uuid) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    let owner = insert_user(&pool).await;
+
+    (api::router(state), pool, owner)
+}
+
+/// Remove the users and the responses stored for `key`
+async fn cleanup(pool: &PgPool, users: &[Uuid], key: &str) {
+    sqlx::query("DELETE FROM idempotency_keys WHERE idempotency_key = $1")
+        .bind(key)
+        .execute(pool)
+        .await
+        .unwrap();
+    for user in users {
+        cleanup_user(pool, *user).await;
+    }
+}
+
+/// Helper function to create a todo with an idempotency key
+async fn create_todo(app: Router, user: Uuid, key: &str, title: &str) -> Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/todos")
+            .header("authorization", bearer(user))
+            .header("content-type", "application/json")
+            .header("idempotency-key", key)
+            .body(Body::from(json!({ "title": title }).to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn parse_json_body(response: Response) -> Value {
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn count_todos(app: Router, user: Uuid) -> usize {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/todos")
+                .header("authorization", bearer(user))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    parse_json_body(response).await["items"]
+        .as_array()
+        .unwrap()
+        .len()
+}
+
+#[tokio::test]
+async fn test_retries_replay_the_stored_response() {
+    let (app, pool, owner) = create_app().await;
+    let key = Uuid::new_v4().to_string();
+
+    let first = create_todo(app.clone(), owner, &key, "Clock in").await;
+    let retry = create_todo(app.clone(), owner, &key, "Clock in").await;
+    let todos = count_todos(app, owner).await;
+
+    cleanup(&pool, &[owner], &key).await;
+
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(first.headers().get("idempotent-replayed").is_none());
+    assert_eq!(retry.status(), StatusCode::CREATED);
+    assert_eq!(retry.headers()["idempotent-replayed"], "true");
+    assert_eq!(
+        retry.headers()["content-type"],
+        first.headers()["content-type"]
+    );
+    assert_eq!(parse_json_body(retry).await, parse_json_body(first).await);
+    assert_eq!(todos, 1);
+}
+
+#[tokio::test]
+async fn test_key_reused_for_a_different_request() {
+    let (app, pool, owner) = create_app().await;
+    let key = Uuid::new_v4().to_string();
+
+    let first = create_todo(app.clone(), owner, &key, "Clock in").await;
+    let other = create_todo(app.clone(), owner, &key, "Clock out").await;
+    let status = other.status();
+    let body = parse_json_body(other).await;
+
+    cleanup(&pool, &[owner], &key).await;
+
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "IDEMPOTENCY_KEY_REUSED");
+}
+
+#[tokio::test]
+async fn test_keys_are_scoped_to_the_user() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+    let key = Uuid::new_v4().to_string();
+
+    let first = create_todo(app.clone(), owner, &key, "Clock in").await;
+    let second = create_todo(app.clone(), other, &key, "Clock in").await;
+
+    cleanup(&pool, &[owner, other], &key).await;
+
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert_eq!(second.status(), StatusCode::CREATED);
+    assert!(second.headers().get("idempotent-replayed").is_none());
+}
+
+#[tokio::test]
+async fn test_invalid_idempotency_key() {
+    let (app, pool, owner) = create_app().await;
+
+    let response = create_todo(app, owner, "has space", "Clock in").await;
+    let status = response.status();
+
+    cleanup(&pool, &[owner], "has space").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_key_of_a_dropped_request_is_released_after_its_lease() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.timeouts = api::timeout::Timeouts {
+        request: Duration::from_secs(1),
+        export: Duration::from_secs(1),
+    };
+    let app = api::router(state);
+    let owner = insert_user(&pool).await;
+    let key = Uuid::new_v4().to_string();
+    let token = bearer(owner);
+    let clock_in = || {
+        app.clone().oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("authorization", &token)
+                .header("content-type", "application/json")
+                .header("idempotency-key", &key)
+                .body(Body::from(
+                    json!({
+                        "user_id": owner,
+                        "event_type": "clock_in",
+                        "event_time": "2025-11-05T09:00:00Z"
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+
+    // The event insert waits for the user row, and the client gives up meanwhile
+    let mut lock = pool.begin().await.unwrap();
+    sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+        .bind(owner)
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+    let dropped = tokio::time::timeout(Duration::from_millis(300), clock_in()).await;
+    lock.rollback().await.unwrap();
+
+    let in_progress = clock_in().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let retry = clock_in().await.unwrap();
+
+    cleanup(&pool, &[owner], &key).await;
+
+    assert!(dropped.is_err());
+    assert_eq!(in_progress.status(), StatusCode::CONFLICT);
+    assert_eq!(retry.status(), StatusCode::OK);
+    assert!(retry.headers().get("idempotent-replayed").is_none());
+}
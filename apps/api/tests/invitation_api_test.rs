@@ -0,0 +1,181 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Send a JSON POST request, authenticated as a user if given
+async fn post(app: Router, uri: &str, user: Option<Uuid>, payload: &Value) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(user) = user {
+        request = request.header("authorization", bearer(user));
+    }
+    let response = app
+        .oneshot(request.body(Body::from(payload.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    // Axum's own rejections (e.g. an unknown role) are plain text
+    if status == StatusCode::UNPROCESSABLE_ENTITY {
+        return (status, Value::Null);
+    }
+    (status, parse_json_body(response.into_body()).await)
+}
+
+async fn cleanup_invitations(pool: &PgPool, email: &str) {
+    sqlx::query("DELETE FROM invitations WHERE email = $1")
+        .bind(email)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup invitations");
+}
+
+#[tokio::test]
+async fn test_invite_and_accept() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let email = format!("invited-{}@example.com", Uuid::new_v4());
+
+    let invite = json!({ "email": email, "role": "manager" });
+    let (unauthorized, _) = post(app.clone(), "/api/invitations", None, &invite).await;
+    let (forbidden, _) = post(app.clone(), "/api/invitations", Some(manager), &invite).await;
+    let (status, invitation) = post(app.clone(), "/api/invitations", Some(admin), &invite).await;
+    let token = invitation["token"].as_str().unwrap_or_default().to_string();
+
+    let accept = json!({ "token": token, "name": "Invited Person" });
+    let (accepted, user) = post(app.clone(), "/api/invitations/accept", None, &accept).await;
+    // A token can only be used once
+    let (again, _) = post(app, "/api/invitations/accept", None, &accept).await;
+
+    let stored: (Option<Uuid>, bool) =
+        sqlx::query_as("SELECT user_id, accepted_at IS NOT NULL FROM invitations WHERE email = $1")
+            .bind(&email)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    cleanup_invitations(&pool, &email).await;
+    if let Some(id) = user["id"].as_str().and_then(|id| id.parse().ok()) {
+        cleanup_user(&pool, id).await;
+    }
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(unauthorized, StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(invitation["role"], "manager");
+    assert!(invitation["accepted_at"].is_null());
+    assert_eq!(token.len(), 64);
+    assert_eq!(accepted, StatusCode::OK);
+    assert_eq!(user["email"], email);
+    assert_eq!(user["name"], "Invited Person");
+    // The user gets the role they were invited with
+    assert_eq!(user["role"], "manager");
+    assert_eq!(
+        stored.0.map(|id| id.to_string()),
+        user["id"].as_str().map(String::from)
+    );
+    assert!(stored.1);
+    assert_eq!(again, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_accept_expired_or_replaced_invitation() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let email = format!("expired-{}@example.com", Uuid::new_v4());
+
+    let invite = json!({ "email": email, "role": "member" });
+    let (_, first) = post(app.clone(), "/api/invitations", Some(admin), &invite).await;
+    let (_, second) = post(app.clone(), "/api/invitations", Some(admin), &invite).await;
+
+    // Inviting again replaces the first invitation
+    let accept_first = json!({ "token": first["token"], "name": "Expired" });
+    let (replaced, _) = post(app.clone(), "/api/invitations/accept", None, &accept_first).await;
+
+    sqlx::query("UPDATE invitations SET expires_at = NOW() - INTERVAL '1 minute' WHERE email = $1")
+        .bind(&email)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let accept_second = json!({ "token": second["token"], "name": "Expired" });
+    let (expired, body) = post(app, "/api/invitations/accept", None, &accept_second).await;
+
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE email = $1")
+        .bind(&email)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    cleanup_invitations(&pool, &email).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(replaced, StatusCode::NOT_FOUND);
+    assert_eq!(expired, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "Invitation has expired");
+    assert_eq!(users, 0);
+}
+
+#[tokio::test]
+async fn test_invite_existing_user_conflicts() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let (status, _) = post(
+        app.clone(),
+        "/api/invitations",
+        Some(admin),
+        &json!({ "email": email, "role": "member" }),
+    )
+    .await;
+    let (invalid_role, _) = post(
+        app,
+        "/api/invitations",
+        Some(admin),
+        &json!({ "email": "someone@example.com", "role": "owner" }),
+    )
+    .await;
+
+    cleanup_invitations(&pool, &email).await;
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(invalid_role, StatusCode::UNPROCESSABLE_ENTITY);
+}
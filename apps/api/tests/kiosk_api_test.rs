@@ -0,0 +1,332 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_api_key, insert_user, insert_user_with_role,
+    test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// API key of the test kiosk device
+const KIOSK_KEY: &str = "ak_test-kiosk-device";
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    insert_api_key(&pool, KIOSK_KEY, &["kiosk:clock"]).await;
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn post_json(app: Router, uri: &str, payload: &Value) -> axum::response::Response {
+    post_json_with_key(app, uri, payload, KIOSK_KEY).await
+}
+
+async fn post_json_with_key(
+    app: Router,
+    uri: &str,
+    payload: &Value,
+    api_key: &str,
+) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("x-device-id", "kiosk-01")
+            .header("x-api-key", api_key)
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Request a kiosk token for `user_id`, authenticated as `caller` if given
+async fn request_token(
+    app: Router,
+    caller: Option<Uuid>,
+    user_id: Uuid,
+) -> axum::response::Response {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/users/{user_id}/kiosk-token"));
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn issue_token(app: Router, user_id: Uuid) -> String {
+    let response = request_token(app, Some(user_id), user_id).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert!(body["expires_at"].is_string());
+    body["token"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_kiosk_clock_records_event_for_token_user() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let token = issue_token(app.clone(), user_id).await;
+
+    let payload = json!({ "token": token, "event_type": "clock_in" });
+    let response = post_json(app.clone(), "/api/attendance/kiosk-clock", &payload).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["user_id"], user_id.to_string());
+    assert_eq!(body["event_type"], "clock_in");
+
+    let device_id: Option<String> =
+        sqlx::query_scalar("SELECT device_id FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(device_id.as_deref(), Some("kiosk-01"));
+
+    // A token can only be used once
+    let payload = json!({ "token": token, "event_type": "clock_out" });
+    let response = post_json(app, "/api/attendance/kiosk-clock", &payload).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_kiosk_clock_rejects_invalid_token() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let token = issue_token(app.clone(), user_id).await;
+    let (payload, _) = token.split_once('.').unwrap();
+
+    for token in [format!("{payload}.forged"), "garbage".to_string()] {
+        let body = json!({ "token": token, "event_type": "clock_in" });
+        let response = post_json(app.clone(), "/api/attendance/kiosk-clock", &body).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let body = json!({ "token": token, "event_type": "lunch" });
+    let response = post_json(app, "/api/attendance/kiosk-clock", &body).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, user_id).await;
+}
+
+#[tokio::test]
+async fn test_issue_kiosk_token_unknown_user() {
+    let (app, pool) = create_app().await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let response = request_token(app, Some(manager), Uuid::new_v4()).await;
+
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_kiosk_tokens_are_issued_to_the_user_or_a_manager() {
+    let (app, pool) = create_app().await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let other = insert_user_with_role(&pool, "member").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let anonymous = request_token(app.clone(), None, member).await.status();
+    let by_other = request_token(app.clone(), Some(other), member)
+        .await
+        .status();
+    let by_manager = request_token(app, Some(manager), member).await.status();
+
+    cleanup_user(&pool, member).await;
+    cleanup_user(&pool, other).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(by_other, StatusCode::FORBIDDEN);
+    assert_eq!(by_manager, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_kiosk_clock_requires_kiosk_api_key() {
+    let (app, pool) = create_app().await;
+    let payroll_key = format!("ak_test-{}", Uuid::new_v4());
+    let payroll_key_id = insert_api_key(&pool, &payroll_key, &["payroll:read"]).await;
+    let user_id = insert_user(&pool).await;
+    let token = issue_token(app.clone(), user_id).await;
+    let payload = json!({ "token": token, "event_type": "clock_in" });
+
+    let unknown_key = post_json_with_key(
+        app.clone(),
+        "/api/attendance/kiosk-clock",
+        &payload,
+        "ak_unknown",
+    )
+    .await;
+    let wrong_scope = post_json_with_key(
+        app.clone(),
+        "/api/attendance/kiosk-clock",
+        &payload,
+        &payroll_key,
+    )
+    .await;
+    let recorded = post_json(app, "/api/attendance/kiosk-clock", &payload).await;
+
+    sqlx::query("DELETE FROM api_keys WHERE id = $1")
+        .bind(payroll_key_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(unknown_key.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong_scope.status(), StatusCode::FORBIDDEN);
+    // Rejected requests do not use up the token
+    assert_eq!(recorded.status(), StatusCode::OK);
+}
+
+async fn issue_device_token(
+    app: Router,
+    caller: Uuid,
+    payload: &Value,
+) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/admin/device-tokens")
+            .header("content-type", "application/json")
+            .header("authorization", bearer(caller))
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn kiosk_clock_with_device_token(
+    app: Router,
+    device_token: &str,
+    device_id: &str,
+    payload: &Value,
+) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/attendance/kiosk-clock")
+            .header("content-type", "application/json")
+            .header("x-device-id", device_id)
+            .header("x-device-token", device_token)
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_kiosk_clock_with_device_token() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    let response = issue_device_token(
+        app.clone(),
+        admin,
+        &json!({ "device_id": "kiosk-02", "ttl_minutes": 60 }),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["device_id"], "kiosk-02");
+    assert!(body["expires_at"].is_string());
+    let device_token = body["token"].as_str().unwrap().to_string();
+
+    let token = issue_token(app.clone(), user_id).await;
+    let payload = json!({ "token": token, "event_type": "clock_in" });
+    let other_device =
+        kiosk_clock_with_device_token(app.clone(), &device_token, "kiosk-03", &payload).await;
+    let forged = kiosk_clock_with_device_token(app.clone(), "garbage", "kiosk-02", &payload).await;
+    let recorded =
+        kiosk_clock_with_device_token(app.clone(), &device_token, "kiosk-02", &payload).await;
+    let recorded_status = recorded.status();
+    let body = parse_json_body(recorded.into_body()).await;
+
+    // The device token grants nothing beyond recording clock events
+    let read_user = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/users/{user_id}"))
+                .header("authorization", format!("Bearer {device_token}"))
+                .header("x-device-id", "kiosk-02")
+                .header("x-device-token", &device_token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let device_id: Option<String> =
+        sqlx::query_scalar("SELECT device_id FROM attendance_events WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(other_device.status(), StatusCode::FORBIDDEN);
+    assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(recorded_status, StatusCode::OK);
+    assert_eq!(body["user_id"], user_id.to_string());
+    assert_eq!(device_id.as_deref(), Some("kiosk-02"));
+    assert_eq!(read_user.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_issue_device_token_requires_admin_and_valid_payload() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let payload = json!({ "device_id": "kiosk-02" });
+    let forbidden = issue_device_token(app.clone(), manager, &payload).await;
+
+    for payload in [
+        json!({ "device_id": " " }),
+        json!({ "device_id": "k".repeat(256) }),
+        json!({ "device_id": "kiosk-02", "ttl_minutes": 0 }),
+        json!({ "device_id": "kiosk-02", "ttl_minutes": 24 * 60 + 1 }),
+    ] {
+        let response = issue_device_token(app.clone(), admin, &payload).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{payload}");
+    }
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+    assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,94 @@
+mod helpers;
+
+use api::load_shed::LoadShedding;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use std::time::Duration;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_excess_requests_are_shed() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.load_shedding = LoadShedding {
+        max_concurrent_requests: Some(1),
+    };
+    let app = api::router(state);
+    let owner = insert_user(&pool).await;
+
+    // Queries on the locked table wait, like queries on a slow database
+    let mut lock = pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE users IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+
+    let me = |app: axum::Router| {
+        app.oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("authorization", bearer(owner))
+                .body(Body::empty())
+                .unwrap(),
+        )
+    };
+    let slow = tokio::spawn(me(app.clone()));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let shed = me(app.clone()).await.unwrap();
+    let health = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    lock.rollback().await.unwrap();
+    let slow = slow.await.unwrap().unwrap();
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(shed.headers()[header::RETRY_AFTER], "1");
+    let bytes = shed.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["code"], "SERVICE_UNAVAILABLE");
+    // Health checks are not limited
+    assert_eq!(health.status(), StatusCode::OK);
+    // The request holding the slot completes once the database answers
+    assert_eq!(slow.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_requests_within_limit_are_served() {
+    let ctx = TestContext::new().await;
+    let mut state = api::AppState::new(api::TodoStore::new(), ctx.pool().clone());
+    state.load_shedding = LoadShedding {
+        max_concurrent_requests: Some(1),
+    };
+    let app = api::router(state);
+
+    for _ in 0..3 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/todos/unknown")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
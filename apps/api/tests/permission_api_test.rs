@@ -0,0 +1,238 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user_with_role, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const MANAGER_DEFAULTS: [&str; 6] = [
+    "todos:read",
+    "todos:write",
+    "users:read",
+    "attendance:read",
+    "attendance:write",
+    "corrections:approve",
+];
+
+/// The manager defaults without `todos:write`
+const MANAGER_REVOKED: [&str; 5] = [
+    "todos:read",
+    "users:read",
+    "attendance:read",
+    "attendance:write",
+    "corrections:approve",
+];
+
+/// Helper function to create the test app
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Helper function to replace the permissions of a role
+async fn set_permissions(
+    app: Router,
+    admin: Uuid,
+    role: &str,
+    permissions: &[&str],
+) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("PUT")
+            .uri(format!("/api/admin/roles/{role}/permissions"))
+            .header("authorization", bearer(admin))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "permissions": permissions }).to_string(),
+            ))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper function to create a todo as a user
+async fn create_todo(app: Router, user_id: Uuid) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/todos")
+            .header("authorization", bearer(user_id))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({ "title": "Permission test" }).to_string(),
+            ))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper function to list todos as a user
+async fn list_todos(app: Router, user_id: Uuid) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .uri("/api/todos")
+            .header("authorization", bearer(user_id))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+async fn test_list_role_permissions() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/permissions")
+                .header("authorization", bearer(admin))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = parse_json_body(response.into_body()).await;
+    let anonymous = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/permissions")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+    let forbidden = set_permissions(app, manager, "manager", &MANAGER_DEFAULTS)
+        .await
+        .status();
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let roles = body.as_array().unwrap();
+    assert_eq!(roles.len(), 3);
+    let admin = roles.iter().find(|r| r["role"] == "admin").unwrap();
+    assert_eq!(
+        admin["permissions"],
+        json!([
+            "todos:read",
+            "todos:write",
+            "users:read",
+            "users:write",
+            "attendance:read",
+            "attendance:write",
+            "corrections:approve",
+            "policies:write"
+        ])
+    );
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_revoked_permission_is_enforced_per_route() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let response = set_permissions(app.clone(), admin, "manager", &MANAGER_REVOKED).await;
+    let revoked_status = response.status();
+    let revoked = parse_json_body(response.into_body()).await;
+    let create_without = create_todo(app.clone(), manager).await;
+    let list_without = list_todos(app.clone(), manager).await;
+    let create_without_status = create_without.status();
+    let create_without_body = parse_json_body(create_without.into_body()).await;
+
+    let response = set_permissions(app.clone(), admin, "manager", &MANAGER_DEFAULTS).await;
+    let restored_status = response.status();
+    let restored = parse_json_body(response.into_body()).await;
+    let create_with = create_todo(app, manager).await.status();
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(revoked_status, StatusCode::OK);
+    assert_eq!(revoked["role"], "manager");
+    assert_eq!(revoked["permissions"], json!(MANAGER_REVOKED));
+    assert_eq!(create_without_status, StatusCode::FORBIDDEN);
+    assert_eq!(
+        create_without_body["message"],
+        "The todos:write permission is required"
+    );
+    assert_eq!(list_without, StatusCode::OK);
+    assert_eq!(restored_status, StatusCode::OK);
+    assert_eq!(restored["permissions"], json!(MANAGER_DEFAULTS));
+    assert_eq!(create_with, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_permission_route_requires_authentication() {
+    let (app, _pool) = create_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/todos")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "title": "Anonymous" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_set_role_permissions_validation() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let invalid = ["todos:read", "attendance:fly"];
+    let response = set_permissions(app.clone(), admin, "manager", &invalid).await;
+    let unknown_permission = response.status();
+    let message = parse_json_body(response.into_body()).await["message"].clone();
+    let unknown_role = set_permissions(app, admin, "owner", &["todos:read"])
+        .await
+        .status();
+
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(unknown_permission, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        message,
+        "Permission must be one of: todos:read, todos:write, users:read, users:write, \
+         attendance:read, attendance:write, corrections:approve, policies:write"
+    );
+    assert_eq!(unknown_role, StatusCode::NOT_FOUND);
+}
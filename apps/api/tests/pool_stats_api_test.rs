@@ -0,0 +1,83 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user_with_role, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    (api::router(state), pool)
+}
+
+/// Helper function to send a GET request
+async fn get(app: Router, uri: &str, caller: Option<Uuid>) -> Response {
+    let mut request = Request::builder().uri(uri);
+    if let Some(caller) = caller {
+        request = request.header("authorization", bearer(caller));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_pool_stats() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let response = get(app, "/api/admin/db/pool", Some(admin)).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(body["size"].as_u64().unwrap() >= 1);
+    assert!(body["max_connections"].as_u64().unwrap() >= 1);
+    assert!(body["acquire_ms"].is_u64());
+    assert!(body["acquire_timeouts"].is_u64());
+}
+
+#[tokio::test]
+async fn test_pool_stats_requires_admin() {
+    let (app, pool) = create_app().await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let anonymous = get(app.clone(), "/api/admin/db/pool", None).await;
+    let forbidden = get(app, "/api/admin/db/pool", Some(manager)).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_metrics() {
+    let (app, _pool) = create_app().await;
+
+    let response = get(app, "/metrics", None).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+    assert!(text.contains("# TYPE db_pool_connections gauge"));
+    assert!(text.contains("db_pool_acquire_timeouts_total "));
+}
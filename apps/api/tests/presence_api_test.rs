@@ -0,0 +1,175 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use futures_util::{SinkExt, StreamExt};
+use helpers::{TestContext, bearer, cleanup_user, insert_user_with_role, test_auth_tokens};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Message, client::IntoClientRequest},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Helper function to create the test app, served on a local port
+async fn serve_app() -> (Router, PgPool, String) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    let app = api::router(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/api/ws", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app.clone()).into_future());
+
+    (app, pool, url)
+}
+
+/// Open a presence connection authenticated as `user`
+async fn connect(url: &str, user: Uuid) -> Socket {
+    let mut request = url.into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("authorization", bearer(user).parse().unwrap());
+    let (socket, _) = connect_async(request).await.unwrap();
+    socket
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+/// Next JSON message from the server, skipping heartbeat frames
+async fn receive(socket: &mut Socket) -> Value {
+    loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("no message within 5 seconds")
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+async fn clock(app: Router, user: Uuid, event_type: &str) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(user))
+                .body(Body::from(
+                    json!({
+                        "user_id": user,
+                        "event_type": event_type,
+                        "event_time": Utc::now() - Duration::seconds(1),
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_presence_changes_are_pushed_to_subscribers() {
+    let (app, pool, url) = serve_app().await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let member = insert_user_with_role(&pool, "member").await;
+
+    let mut socket = connect(&url, manager).await;
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "user_ids": [member] }),
+    )
+    .await;
+    let snapshot = receive(&mut socket).await;
+
+    clock(app.clone(), member, "clock_in").await;
+    let clocked_in = receive(&mut socket).await;
+    clock(app, member, "break_start").await;
+    let on_break = receive(&mut socket).await;
+
+    cleanup_user(&pool, manager).await;
+    cleanup_user(&pool, member).await;
+
+    assert_eq!(snapshot["type"], "snapshot");
+    assert_eq!(snapshot["users"][0]["user_id"], member.to_string());
+    assert_eq!(snapshot["users"][0]["status"], "absent");
+    assert_eq!(clocked_in["type"], "presence");
+    assert_eq!(clocked_in["user_id"], member.to_string());
+    assert_eq!(clocked_in["status"], "present");
+    assert!(clocked_in["since"].is_string());
+    assert_eq!(on_break["status"], "on_break");
+}
+
+#[tokio::test]
+async fn test_members_subscribe_only_to_themselves() {
+    let (_, pool, url) = serve_app().await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let other = insert_user_with_role(&pool, "member").await;
+
+    let mut socket = connect(&url, member).await;
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "user_ids": [other] }),
+    )
+    .await;
+    let rejected = receive(&mut socket).await;
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "user_ids": [member] }),
+    )
+    .await;
+    let own = receive(&mut socket).await;
+    send(&mut socket, json!({ "type": "ping" })).await;
+    let pong = receive(&mut socket).await;
+
+    cleanup_user(&pool, member).await;
+    cleanup_user(&pool, other).await;
+
+    assert_eq!(rejected["type"], "error");
+    assert_eq!(own["type"], "snapshot");
+    assert_eq!(own["users"][0]["user_id"], member.to_string());
+    assert_eq!(pong["type"], "pong");
+}
+
+#[tokio::test]
+async fn test_upgrade_requires_authentication() {
+    let (app, _, _) = serve_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/ws")
+                .header("connection", "upgrade")
+                .header("upgrade", "websocket")
+                .header("sec-websocket-version", "13")
+                .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
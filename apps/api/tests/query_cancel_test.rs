@@ -0,0 +1,86 @@
+mod helpers;
+
+use api::{AppConfig, db::CancelOnDrop, init_db_pool, run_migrations};
+use helpers::TestContext;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_statement_timeout_cancels_slow_queries() {
+    let mut config = AppConfig::default().database;
+    config.url =
+        std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set for tests");
+    config.statement_timeout = Some(Duration::from_secs(1));
+    let pool = init_db_pool(&config).await.unwrap();
+
+    let err = sqlx::query("SELECT pg_sleep(3)")
+        .execute(&pool)
+        .await
+        .unwrap_err();
+
+    let code = err
+        .as_database_error()
+        .and_then(sqlx::error::DatabaseError::code);
+    assert_eq!(code.as_deref(), Some("57014"));
+}
+
+#[tokio::test]
+async fn test_migrations_leave_the_statement_timeout_of_the_pool() {
+    let mut config = AppConfig::default().database;
+    config.url =
+        std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set for tests");
+    config.max_connections = 1;
+    config.statement_timeout = Some(Duration::from_secs(1));
+    let pool = init_db_pool(&config).await.unwrap();
+
+    run_migrations(&pool).await.unwrap();
+    let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(timeout, "1s");
+}
+
+#[tokio::test]
+async fn test_dropped_query_is_cancelled() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut conn = CancelOnDrop::acquire(&pool).await.unwrap();
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+    // The request gives up while the query is still running
+    let query = async move {
+        sqlx::query("SELECT pg_sleep(30)")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        conn.finish();
+    };
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), query)
+            .await
+            .is_err()
+    );
+
+    let mut running = true;
+    for _ in 0..50 {
+        running = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pg_stat_activity WHERE pid = $1 AND state = 'active')",
+        )
+        .bind(pid)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        if !running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(
+        !running,
+        "the query kept running after the request was dropped"
+    );
+}
@@ -0,0 +1,240 @@
+mod helpers;
+
+use api::problem::ErrorFormat;
+use api::rate_limit::{RateLimiter, RateLimits};
+use axum::{
+    Router,
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode, header},
+};
+use helpers::{TestContext, bearer, cleanup_user, insert_user, test_auth_tokens};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::net::SocketAddr;
+use tower::ServiceExt;
+
+/// Address of the reverse proxy in front of the test app
+const PROXY: [u8; 4] = [10, 0, 0, 1];
+
+/// Helper function to create the test app with low rate limits
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.trusted_proxies = "10.0.0.0/8".parse().unwrap();
+    state.rate_limiter = RateLimiter::new(RateLimits {
+        auth_per_minute: Some(2),
+        api_per_minute: Some(3),
+    });
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Helper function to get the profile of a user
+async fn get_me(app: Router, authorization: &str) -> axum::response::Response {
+    app.oneshot(
+        Request::builder()
+            .uri("/api/me")
+            .header("authorization", authorization)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Helper function to log in from an IP address, through the proxy
+async fn login(app: Router, ip: &str) -> StatusCode {
+    login_from(app, PROXY, ip).await
+}
+
+/// Helper function to log in from a peer address with an `X-Forwarded-For` header
+async fn login_from(app: Router, peer: [u8; 4], forwarded_for: &str) -> StatusCode {
+    let payload = json!({ "email": "nobody@example.com", "password": "wrong-password" });
+    app.oneshot(
+        Request::builder()
+            .method("POST")
+            .uri("/api/auth/login")
+            .header("x-forwarded-for", forwarded_for)
+            .header("content-type", "application/json")
+            .extension(ConnectInfo(SocketAddr::from((peer, 54321))))
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[tokio::test]
+async fn test_requests_are_limited_per_user() {
+    let (app, pool) = create_app().await;
+    let user = insert_user(&pool).await;
+    let other_user = insert_user(&pool).await;
+
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        statuses.push(get_me(app.clone(), &bearer(user)).await.status());
+    }
+    let limited = get_me(app.clone(), &bearer(user)).await;
+    let other = get_me(app, &bearer(other_user)).await.status();
+
+    cleanup_user(&pool, user).await;
+    cleanup_user(&pool, other_user).await;
+
+    assert_eq!(statuses, [StatusCode::OK; 3]);
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = limited.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let body = parse_json_body(limited.into_body()).await;
+    assert_eq!(body["error"], "too_many_requests");
+    assert_eq!(body["code"], "TOO_MANY_REQUESTS");
+    assert_eq!(other, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_anonymous_requests_are_limited_per_ip_and_route_group() {
+    let (app, pool) = create_app().await;
+    let user = insert_user(&pool).await;
+
+    let first = login(app.clone(), "203.0.113.1").await;
+    let second = login(app.clone(), "203.0.113.1").await;
+    let limited = login(app.clone(), "203.0.113.1").await;
+    let other_ip = login(app.clone(), "203.0.113.2").await;
+    // The auth group has its own counter
+    let api = get_me(app.clone(), &bearer(user)).await.status();
+    let health = app
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header("x-forwarded-for", "203.0.113.1")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status();
+
+    cleanup_user(&pool, user).await;
+
+    assert_eq!(first, StatusCode::UNAUTHORIZED);
+    assert_eq!(second, StatusCode::UNAUTHORIZED);
+    assert_eq!(limited, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(other_ip, StatusCode::UNAUTHORIZED);
+    assert_eq!(api, StatusCode::OK);
+    assert_eq!(health, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_spoofed_forwarded_for_does_not_reset_the_limit() {
+    let (app, _pool) = create_app().await;
+
+    // Clients that are not trusted proxies are limited by their own address
+    let first = login_from(app.clone(), [192, 0, 2, 10], "203.0.113.21").await;
+    let second = login_from(app.clone(), [192, 0, 2, 10], "203.0.113.22").await;
+    let limited = login_from(app.clone(), [192, 0, 2, 10], "203.0.113.23").await;
+    let other_client = login_from(app, [192, 0, 2, 11], "203.0.113.21").await;
+
+    assert_eq!(first, StatusCode::UNAUTHORIZED);
+    assert_eq!(second, StatusCode::UNAUTHORIZED);
+    assert_eq!(limited, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(other_client, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_limited_requests_as_problem_details() {
+    let ctx = TestContext::new().await;
+    let mut state = api::AppState::new(api::TodoStore::new(), ctx.pool().clone());
+    state.rate_limiter = RateLimiter::new(RateLimits {
+        auth_per_minute: Some(1),
+        api_per_minute: None,
+    });
+    state.error_format = ErrorFormat::Problem;
+    let app = api::router(state);
+
+    let first = login(app.clone(), "203.0.113.3").await;
+    let limited = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header("x-forwarded-for", "203.0.113.3")
+                .header("content-type", "application/json")
+                .extension(ConnectInfo(SocketAddr::from((PROXY, 54321))))
+                .body(Body::from(
+                    json!({ "email": "nobody@example.com", "password": "x" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(first, StatusCode::UNAUTHORIZED);
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        limited.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+    assert!(limited.headers().contains_key(header::RETRY_AFTER));
+    let body = parse_json_body(limited.into_body()).await;
+    assert_eq!(body["status"], 429);
+    assert_eq!(body["title"], "Too Many Requests");
+    assert_eq!(body["instance"], "/api/auth/login");
+    assert_eq!(body["code"], "TOO_MANY_REQUESTS");
+}
+
+#[tokio::test]
+async fn test_password_changes_share_the_auth_limit() {
+    let (app, pool) = create_app().await;
+    let user = insert_user(&pool).await;
+
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        let status = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/users/{user}/password"))
+                    .header("authorization", bearer(user))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({ "current_password": "wrong-passw0rd", "new_password": "new-passw0rd" })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status();
+        statuses.push(status);
+    }
+    // Other routes keep their own counter
+    let api = get_me(app, &bearer(user)).await.status();
+
+    cleanup_user(&pool, user).await;
+
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::UNAUTHORIZED,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::TOO_MANY_REQUESTS,
+        ]
+    );
+    assert_eq!(api, StatusCode::OK);
+}
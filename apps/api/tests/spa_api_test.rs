@@ -0,0 +1,115 @@
+mod helpers;
+
+use api::spa::SpaSettings;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    response::Response,
+};
+use helpers::TestContext;
+use http_body_util::BodyExt;
+use std::path::PathBuf;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to write a small frontend build to a fresh directory
+///
+/// Tests must remove the directory when they are done.
+fn create_frontend() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("spa-test-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    std::fs::write(dir.join("index.html"), "<div id=\"app\"></div>").unwrap();
+    std::fs::write(dir.join("assets/app.js"), "console.log('app');").unwrap();
+    dir
+}
+
+/// Helper function to create the test app serving a frontend directory
+async fn create_app(dir: Option<PathBuf>) -> Router {
+    let ctx = TestContext::new().await;
+    let mut state = api::AppState::new(api::TodoStore::new(), ctx.pool().clone());
+    state.spa = SpaSettings { dir };
+    api::router(state)
+}
+
+/// Helper function to send a GET request
+async fn get(app: Router, uri: &str) -> Response {
+    app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+/// Helper function to read a response body as text
+async fn body_text(response: Response) -> String {
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_spa_serves_index_at_root() {
+    let dir = create_frontend();
+    let app = create_app(Some(dir.clone())).await;
+
+    let response = get(app, "/").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "<div id=\"app\"></div>");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_spa_serves_static_files() {
+    let dir = create_frontend();
+    let app = create_app(Some(dir.clone())).await;
+
+    let response = get(app, "/assets/app.js").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "console.log('app');");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_spa_falls_back_to_index_for_client_routes() {
+    let dir = create_frontend();
+    let app = create_app(Some(dir.clone())).await;
+
+    let response = get(app, "/attendance/2025-11").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "<div id=\"app\"></div>");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_spa_keeps_api_routes() {
+    let dir = create_frontend();
+    let app = create_app(Some(dir.clone())).await;
+
+    let response = get(app, "/health").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(body_text(response).await.contains("ok"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_spa_unknown_api_path_returns_not_found() {
+    let dir = create_frontend();
+    let app = create_app(Some(dir.clone())).await;
+
+    let response = get(app, "/api/does-not-exist").await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(body_text(response).await.contains("not_found"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_no_frontend_without_static_dir() {
+    let app = create_app(None).await;
+
+    let response = get(app, "/").await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
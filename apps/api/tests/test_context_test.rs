@@ -1,5 +1,8 @@
 mod helpers;
 
+use api::models::{CreateAttendanceEvent, CreateUser, EventType, UpdateUser};
+use api::{AttendanceEventRepository, UserRepository};
+use chrono::Utc;
 use helpers::TestContext;
 
 /// Test that `TestContext` can be initialized successfully
@@ -117,3 +120,71 @@ async fn test_explicit_rollback() {
 
     assert_eq!(count.0, 0, "User should not exist after explicit rollback");
 }
+
+/// Test that repository operations compose in the test transaction
+#[tokio::test]
+async fn test_repositories_in_transaction() {
+    let mut ctx = TestContext::new().await;
+    let users = UserRepository::new(ctx.pool().clone());
+    let events = AttendanceEventRepository::new(ctx.pool().clone());
+    let email = format!("tx-{}@example.com", uuid::Uuid::new_v4());
+    let tx = ctx.begin_transaction().await;
+
+    let user = users
+        .create_in(
+            &mut **tx,
+            CreateUser {
+                name: "Transaction User".to_string(),
+                email: email.clone(),
+                picture: None,
+                password: None,
+            },
+        )
+        .await
+        .expect("Failed to create user");
+    let updated = users
+        .update_in(
+            tx,
+            user.id,
+            UpdateUser {
+                name: Some("Renamed User".to_string()),
+                email: None,
+                picture: None,
+            },
+        )
+        .await
+        .expect("Failed to update user");
+    let event = events
+        .create_in(
+            tx,
+            CreateAttendanceEvent {
+                user_id: user.id,
+                event_type: EventType::ClockIn,
+                event_time: Utc::now(),
+                latitude: None,
+                longitude: None,
+                client_ip: None,
+                user_agent: None,
+                device_id: None,
+                amends_event_id: None,
+            },
+        )
+        .await
+        .expect("Failed to create event");
+
+    // Both are visible within the transaction, but not outside of it
+    assert_eq!(updated.name, "Renamed User");
+    assert!(
+        events
+            .find_by_id_in(&mut **tx, event.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(users.find_by_id(user.id).await.unwrap().is_none());
+
+    ctx.rollback().await;
+
+    assert!(users.find_by_email(&email).await.unwrap().is_none());
+    assert!(events.find_by_id(event.id).await.unwrap().is_none());
+}
@@ -0,0 +1,71 @@
+mod helpers;
+
+use api::timeout::Timeouts;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use std::time::Duration;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_slow_requests_time_out() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.timeouts = Timeouts {
+        request: Duration::from_millis(200),
+        export: Duration::from_secs(10),
+    };
+    let app = api::router(state);
+    let owner = insert_user(&pool).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    // Queries on the locked table wait, like queries on a slow database
+    let mut lock = pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE users IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+
+    let me = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("authorization", bearer(owner))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let export = tokio::spawn(
+        app.oneshot(
+            Request::builder()
+                .uri("/api/admin/users/export.csv")
+                .header("authorization", bearer(admin))
+                .body(Body::empty())
+                .unwrap(),
+        ),
+    );
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    lock.rollback().await.unwrap();
+    let export = export.await.unwrap().unwrap();
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(me.status(), StatusCode::GATEWAY_TIMEOUT);
+    let bytes = me.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"], "gateway_timeout");
+    assert_eq!(body["code"], "GATEWAY_TIMEOUT");
+    // Exports have a longer timeout, so this one waited for the lock
+    assert_eq!(export.status(), StatusCode::OK);
+}
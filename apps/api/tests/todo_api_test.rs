@@ -1,23 +1,31 @@
+mod helpers;
+
 use axum::{
     Router,
     body::Body,
     http::{Request, StatusCode},
 };
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
 use http_body_util::BodyExt;
 use serde_json::{Value, json};
+use sqlx::PgPool;
 use tower::ServiceExt;
+use uuid::Uuid;
 
-/// Helper function to create the test app
-async fn create_app() -> Router {
-    let store = api::TodoStore::new();
+/// Helper function to create the test app and the user who owns the todos
+///
+/// Tests must call `cleanup_user` for the owner when they are done.
+async fn create_app() -> (Router, PgPool, Uuid) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
 
-    // Initialize test database pool
-    // Note: Tests require a running PostgreSQL instance with TEST_DATABASE_URL set
-    let pool = api::init_db_pool()
-        .await
-        .expect("Failed to initialize test database pool");
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    let owner = insert_user(&pool).await;
 
-    api::create_router(store, pool)
+    (api::router(state), pool, owner)
 }
 
 /// Helper function to parse JSON response body
@@ -28,7 +36,7 @@ async fn parse_json_body(body: Body) -> Value {
 
 #[tokio::test]
 async fn test_health_check() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let response = app
         .oneshot(
@@ -44,11 +52,13 @@ async fn test_health_check() {
 
     let body = parse_json_body(response.into_body()).await;
     assert_eq!(body["status"], "ok");
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_create_todo() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let payload = json!({
         "title": "Test Todo",
@@ -60,6 +70,7 @@ async fn test_create_todo() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -67,18 +78,22 @@ async fn test_create_todo() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let location = response.headers()["location"].to_str().unwrap().to_string();
 
     let body = parse_json_body(response.into_body()).await;
+    assert_eq!(location, format!("/api/todos/{}", body["id"]));
     assert_eq!(body["title"], "Test Todo");
     assert_eq!(body["description"], "This is a test todo");
     assert_eq!(body["completed"], false);
     assert!(body["id"].is_number());
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_create_todo_validation_empty_title() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let payload = json!({
         "title": "   ",
@@ -90,6 +105,7 @@ async fn test_create_todo_validation_empty_title() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -98,11 +114,13 @@ async fn test_create_todo_validation_empty_title() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_create_todo_validation_title_too_long() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let long_title = "a".repeat(201);
     let payload = json!({
@@ -115,6 +133,7 @@ async fn test_create_todo_validation_title_too_long() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -123,16 +142,19 @@ async fn test_create_todo_validation_title_too_long() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_get_all_todos_empty() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let response = app
         .oneshot(
             Request::builder()
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -142,13 +164,16 @@ async fn test_get_all_todos_empty() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let body = parse_json_body(response.into_body()).await;
-    assert!(body.is_array());
-    assert_eq!(body.as_array().unwrap().len(), 0);
+    assert_eq!(body["items"], json!([]));
+    assert_eq!(body["total"], 0);
+    assert_eq!(body["next_offset"], Value::Null);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_get_all_todos_with_items() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // Create first todo
     let payload1 = json!({
@@ -162,6 +187,7 @@ async fn test_get_all_todos_with_items() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload1.to_string()))
                 .unwrap(),
@@ -181,6 +207,7 @@ async fn test_get_all_todos_with_items() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload2.to_string()))
                 .unwrap(),
@@ -193,6 +220,7 @@ async fn test_get_all_todos_with_items() {
         .oneshot(
             Request::builder()
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -202,14 +230,16 @@ async fn test_get_all_todos_with_items() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let body = parse_json_body(response.into_body()).await;
-    assert!(body.is_array());
-    let todos = body.as_array().unwrap();
+    let todos = body["items"].as_array().unwrap();
     assert_eq!(todos.len(), 2);
+    assert_eq!(body["total"], 2);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_get_todo_by_id() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // Create a todo
     let payload = json!({
@@ -223,6 +253,7 @@ async fn test_get_todo_by_id() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -238,6 +269,7 @@ async fn test_get_todo_by_id() {
         .oneshot(
             Request::builder()
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -250,28 +282,82 @@ async fn test_get_todo_by_id() {
     assert_eq!(body["id"], todo_id);
     assert_eq!(body["title"], "Test Todo");
     assert_eq!(body["description"], "Test description");
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_get_todo_not_found() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/todos/999")
+                .header("authorization", bearer(owner))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(Uuid::parse_str(&request_id).is_ok());
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["error"], "not_found");
+    assert_eq!(body["code"], "TODO_NOT_FOUND");
+    assert_eq!(body["request_id"], request_id);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_get_todo_not_found_as_problem_details() {
+    let (app, pool, owner) = create_app().await;
 
     let response = app
         .oneshot(
             Request::builder()
                 .uri("/api/todos/999")
+                .header("authorization", bearer(owner))
+                .header("accept", "application/problem+json")
+                .header("x-request-id", "proxy-request-42")
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
+    cleanup_user(&pool, owner).await;
+
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["x-request-id"], "proxy-request-42");
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(
+        body,
+        json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Todo with id 999 not found",
+            "instance": "/api/todos/999",
+            "code": "TODO_NOT_FOUND",
+            "request_id": "proxy-request-42",
+        })
+    );
 }
 
 #[tokio::test]
 async fn test_update_todo() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // Create a todo
     let payload = json!({
@@ -285,6 +371,7 @@ async fn test_update_todo() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -306,6 +393,7 @@ async fn test_update_todo() {
             Request::builder()
                 .method("PUT")
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(update_payload.to_string()))
                 .unwrap(),
@@ -320,11 +408,65 @@ async fn test_update_todo() {
     assert_eq!(body["title"], "Updated Title");
     assert_eq!(body["description"], "Original description"); // Description should remain unchanged
     assert_eq!(body["completed"], true);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_update_todo_clears_description_with_null() {
+    let (app, pool, owner) = create_app().await;
+
+    // Create a todo
+    let payload = json!({
+        "title": "Todo with description",
+        "description": "To be removed"
+    });
+
+    let create_response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/todos")
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let created_todo = parse_json_body(create_response.into_body()).await;
+    let todo_id = created_todo["id"].as_u64().unwrap();
+
+    // An explicit null clears the description
+    let update_payload = json!({ "description": null });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(update_payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["title"], "Todo with description");
+    assert!(body["description"].is_null());
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_update_todo_not_found() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let update_payload = json!({
         "title": "Updated Title"
@@ -335,6 +477,7 @@ async fn test_update_todo_not_found() {
             Request::builder()
                 .method("PUT")
                 .uri("/api/todos/999")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(update_payload.to_string()))
                 .unwrap(),
@@ -343,11 +486,13 @@ async fn test_update_todo_not_found() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_update_todo_validation() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // Create a todo
     let payload = json!({
@@ -360,6 +505,7 @@ async fn test_update_todo_validation() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -380,6 +526,7 @@ async fn test_update_todo_validation() {
             Request::builder()
                 .method("PUT")
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(update_payload.to_string()))
                 .unwrap(),
@@ -388,11 +535,13 @@ async fn test_update_todo_validation() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_delete_todo() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // Create a todo
     let payload = json!({
@@ -405,6 +554,7 @@ async fn test_delete_todo() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(payload.to_string()))
                 .unwrap(),
@@ -422,27 +572,23 @@ async fn test_delete_todo() {
             Request::builder()
                 .method("DELETE")
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = parse_json_body(response.into_body()).await;
-    assert!(
-        body["message"]
-            .as_str()
-            .unwrap()
-            .contains("deleted successfully")
-    );
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert!(body.is_empty());
 
     // Verify the todo is deleted
     let get_response = app
         .oneshot(
             Request::builder()
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -450,17 +596,20 @@ async fn test_delete_todo() {
         .unwrap();
 
     assert_eq!(get_response.status(), StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_delete_todo_not_found() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("DELETE")
                 .uri("/api/todos/999")
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -468,11 +617,13 @@ async fn test_delete_todo_not_found() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, owner).await;
 }
 
 #[tokio::test]
 async fn test_full_crud_workflow() {
-    let app = create_app().await;
+    let (app, pool, owner) = create_app().await;
 
     // 1. Create a todo
     let create_payload = json!({
@@ -486,6 +637,7 @@ async fn test_full_crud_workflow() {
             Request::builder()
                 .method("POST")
                 .uri("/api/todos")
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(create_payload.to_string()))
                 .unwrap(),
@@ -493,7 +645,7 @@ async fn test_full_crud_workflow() {
         .await
         .unwrap();
 
-    assert_eq!(create_response.status(), StatusCode::OK);
+    assert_eq!(create_response.status(), StatusCode::CREATED);
     let created_todo = parse_json_body(create_response.into_body()).await;
     let todo_id = created_todo["id"].as_u64().unwrap();
 
@@ -503,6 +655,7 @@ async fn test_full_crud_workflow() {
         .oneshot(
             Request::builder()
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -522,6 +675,7 @@ async fn test_full_crud_workflow() {
             Request::builder()
                 .method("PUT")
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .header("content-type", "application/json")
                 .body(Body::from(update_payload.to_string()))
                 .unwrap(),
@@ -540,19 +694,21 @@ async fn test_full_crud_workflow() {
             Request::builder()
                 .method("DELETE")
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    assert_eq!(delete_response.status(), StatusCode::OK);
+    assert_eq!(delete_response.status(), StatusCode::NO_CONTENT);
 
     // 5. Verify deletion
     let verify_response = app
         .oneshot(
             Request::builder()
                 .uri(format!("/api/todos/{todo_id}"))
+                .header("authorization", bearer(owner))
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -560,4 +716,1095 @@ async fn test_full_crud_workflow() {
         .unwrap();
 
     assert_eq!(verify_response.status(), StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, owner).await;
+}
+
+/// Helper function to create a todo and return the response body
+/// (`Value::Null` if the body is not JSON, as when the payload is rejected)
+async fn post_todo(app: Router, owner: Uuid, payload: &Value) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/todos")
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+/// Helper function to list todos with a query string
+async fn list_todos(app: Router, owner: Uuid, query: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/todos{query}"))
+                .header("authorization", bearer(owner))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+#[tokio::test]
+async fn test_paginated_todos_link_to_neighbouring_pages() {
+    let (app, pool, owner) = create_app().await;
+    for n in 1..=5 {
+        post_todo(app.clone(), owner, &json!({ "title": format!("Todo {n}") })).await;
+    }
+
+    let link = |query: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/api/todos{query}"))
+                        .header("authorization", bearer(owner))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            response
+                .headers()
+                .get("link")
+                .map(|value| value.to_str().unwrap().to_string())
+        }
+    };
+    let middle = link("?q=todo&limit=2&offset=2").await;
+    let first = link("?limit=2").await;
+    let single = link("?limit=10").await;
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(
+        middle.as_deref(),
+        Some(
+            "</api/todos?q=todo&limit=2&offset=0>; rel=\"prev\", \
+             </api/todos?q=todo&limit=2&offset=4>; rel=\"next\""
+        )
+    );
+    assert_eq!(
+        first.as_deref(),
+        Some("</api/todos?limit=2&offset=2>; rel=\"next\"")
+    );
+    assert_eq!(single, None);
+}
+
+#[tokio::test]
+async fn test_todo_priority() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, low) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Low", "priority": "low" }),
+    )
+    .await;
+    let (_, default) = post_todo(app.clone(), owner, &json!({ "title": "Default" })).await;
+    let (_, urgent) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Urgent", "priority": "urgent" }),
+    )
+    .await;
+    let (_, high) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "High", "priority": "high" }),
+    )
+    .await;
+    let (invalid, _) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Bad", "priority": "critical" }),
+    )
+    .await;
+
+    // Raising a priority moves the todo up the list
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/todos/{}", low["id"]))
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "priority": "high" }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, all) = list_todos(app.clone(), owner, "").await;
+    let (_, only_high) = list_todos(app.clone(), owner, "?priority=high").await;
+    let (unknown, error) = list_todos(app, owner, "?priority=critical").await;
+
+    let titles = |todos: &Value| -> Vec<String> {
+        todos["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(default["priority"], "medium");
+    assert_eq!(urgent["priority"], "urgent");
+    assert_eq!(high["priority"], "high");
+    assert_eq!(invalid, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&all), ["Urgent", "Low", "High", "Default"]);
+    assert_eq!(titles(&only_high), ["Low", "High"]);
+    assert_eq!(unknown, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        error["message"],
+        "Priority must be one of: low, medium, high, urgent"
+    );
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_todo_tags() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, report) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Report", "tags": ["work", " urgent ", "work"] }),
+    )
+    .await;
+    let (_, groceries) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Groceries", "tags": ["home"] }),
+    )
+    .await;
+    let (_, untagged) = post_todo(app.clone(), owner, &json!({ "title": "Untagged" })).await;
+    let (blank, blank_error) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Blank", "tags": ["  "] }),
+    )
+    .await;
+    let (too_long, too_long_error) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Long", "tags": ["x".repeat(51)] }),
+    )
+    .await;
+
+    // Tags given on update replace the existing ones
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/todos/{}", groceries["id"]))
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "tags": ["home", "weekend"] }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let updated = parse_json_body(response.into_body()).await;
+
+    let (_, work) = list_todos(app.clone(), owner, "?tag=work").await;
+    let (_, none) = list_todos(app.clone(), owner, "?tag=missing").await;
+    let (status, tags) = list_todos(app, owner, "/tags").await;
+
+    assert_eq!(report["tags"], json!(["work", "urgent"]));
+    assert_eq!(untagged["tags"], json!([]));
+    assert_eq!(blank, StatusCode::BAD_REQUEST);
+    assert_eq!(blank_error["message"], "Tags cannot be empty");
+    assert_eq!(too_long, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        too_long_error["message"],
+        "Tags must be 50 characters or less"
+    );
+    assert_eq!(updated["tags"], json!(["home", "weekend"]));
+    assert_eq!(work["items"].as_array().unwrap().len(), 1);
+    assert_eq!(work["items"][0]["id"], report["id"]);
+    assert_eq!(none["items"], json!([]));
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tags, json!(["home", "urgent", "weekend", "work"]));
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_search_and_filter_todos() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, report) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Quarterly report", "description": "Send to the Finance team" }),
+    )
+    .await;
+    let (_, budget) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Finance budget", "tags": ["work"] }),
+    )
+    .await;
+    let (_, groceries) = post_todo(app.clone(), owner, &json!({ "title": "Groceries" })).await;
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/todos/{}", budget["id"]))
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "completed": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let ids = |todos: &Value| -> Vec<Value> {
+        todos["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["id"].clone())
+            .collect()
+    };
+    let (_, finance) = list_todos(app.clone(), owner, "?q=FINANCE").await;
+    let (_, all_words) = list_todos(app.clone(), owner, "?q=finance%20team").await;
+    let (_, open) = list_todos(app.clone(), owner, "?completed=false").await;
+    let (_, done) = list_todos(app.clone(), owner, "?completed=true").await;
+    let (_, open_finance) = list_todos(app.clone(), owner, "?q=finance&completed=false").await;
+    let (_, work_open) = list_todos(app.clone(), owner, "?tag=work&completed=false").await;
+    let (_, blank) = list_todos(app.clone(), owner, "?q=%20%20").await;
+    let (too_long, _) = list_todos(app, owner, &format!("?q={}", "x".repeat(101))).await;
+
+    assert_eq!(ids(&finance), [report["id"].clone(), budget["id"].clone()]);
+    assert_eq!(ids(&all_words), [report["id"].clone()]);
+    assert_eq!(ids(&open), [report["id"].clone(), groceries["id"].clone()]);
+    assert_eq!(ids(&done), [budget["id"].clone()]);
+    assert_eq!(ids(&open_finance), [report["id"].clone()]);
+    assert_eq!(work_open["items"], json!([]));
+    assert_eq!(blank["total"], 3);
+    assert_eq!(too_long, StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_paginate_todos() {
+    let (app, pool, owner) = create_app().await;
+    for n in 1..=5 {
+        post_todo(app.clone(), owner, &json!({ "title": format!("Todo {n}") })).await;
+    }
+
+    let (status, first) = list_todos(app.clone(), owner, "?limit=2").await;
+    let (_, last) = list_todos(app.clone(), owner, "?limit=2&offset=4").await;
+    let (_, filtered) = list_todos(app.clone(), owner, "?q=todo%203&limit=2").await;
+    let (_, beyond) = list_todos(app.clone(), owner, "?offset=10").await;
+    let (_, default) = list_todos(app.clone(), owner, "").await;
+    let (zero, _) = list_todos(app.clone(), owner, "?limit=0").await;
+    let (too_large, error) = list_todos(app, owner, "?limit=101").await;
+
+    let titles = |page: &Value| -> Vec<String> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&first), ["Todo 1", "Todo 2"]);
+    assert_eq!(first["limit"], 2);
+    assert_eq!(first["offset"], 0);
+    assert_eq!(first["total"], 5);
+    assert_eq!(first["next_offset"], 2);
+    assert_eq!(titles(&last), ["Todo 5"]);
+    assert_eq!(last["next_offset"], Value::Null);
+    assert_eq!(titles(&filtered), ["Todo 3"]);
+    assert_eq!(filtered["total"], 1);
+    assert_eq!(beyond["items"], json!([]));
+    assert_eq!(beyond["total"], 5);
+    assert_eq!(default["limit"], 20);
+    assert_eq!(zero, StatusCode::BAD_REQUEST);
+    assert_eq!(too_large, StatusCode::BAD_REQUEST);
+    assert_eq!(error["message"], "limit must be between 1 and 100");
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_sort_todos() {
+    let (app, pool, owner) = create_app().await;
+    post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "banana", "priority": "low", "due_date": "2025-12-01" }),
+    )
+    .await;
+    post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Cherry", "priority": "urgent" }),
+    )
+    .await;
+    post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "apple", "due_date": "2025-11-20" }),
+    )
+    .await;
+
+    let titles = |page: &Value| -> Vec<String> {
+        page["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let (_, default) = list_todos(app.clone(), owner, "").await;
+    let (_, lowest_first) = list_todos(app.clone(), owner, "?order=asc").await;
+    let (_, created) = list_todos(app.clone(), owner, "?sort=created").await;
+    let (_, newest) = list_todos(app.clone(), owner, "?sort=created&order=desc").await;
+    let (_, title) = list_todos(app.clone(), owner, "?sort=title").await;
+    let (_, due) = list_todos(app.clone(), owner, "?sort=due_date").await;
+    let (_, due_desc) = list_todos(app.clone(), owner, "?sort=due_date&order=desc").await;
+    let (_, paged) = list_todos(app.clone(), owner, "?sort=title&limit=1&offset=1").await;
+    let (bad_sort, sort_error) = list_todos(app.clone(), owner, "?sort=size").await;
+    let (bad_order, _) = list_todos(app, owner, "?order=up").await;
+
+    assert_eq!(titles(&default), ["Cherry", "apple", "banana"]);
+    assert_eq!(titles(&lowest_first), ["banana", "apple", "Cherry"]);
+    assert_eq!(titles(&created), ["banana", "Cherry", "apple"]);
+    assert_eq!(titles(&newest), ["apple", "Cherry", "banana"]);
+    assert_eq!(titles(&title), ["apple", "banana", "Cherry"]);
+    // Todos without a due date come last in either order
+    assert_eq!(titles(&due), ["apple", "banana", "Cherry"]);
+    assert_eq!(titles(&due_desc), ["banana", "apple", "Cherry"]);
+    assert_eq!(titles(&paged), ["banana"]);
+    assert_eq!(bad_sort, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        sort_error["message"],
+        "Sort must be one of: priority, created, title, due_date"
+    );
+    assert_eq!(bad_order, StatusCode::BAD_REQUEST);
+
+    cleanup_user(&pool, owner).await;
+}
+
+#[tokio::test]
+async fn test_update_todo_due_date() {
+    let (app, pool, owner) = create_app().await;
+    let (_, todo) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Pay rent", "due_date": "2025-12-01" }),
+    )
+    .await;
+
+    let put = |payload: Value| {
+        let app = app.clone();
+        let uri = format!("/api/todos/{}", todo["id"]);
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("PUT")
+                        .uri(uri)
+                        .header("authorization", bearer(owner))
+                        .header("content-type", "application/json")
+                        .body(Body::from(payload.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            parse_json_body(response.into_body()).await
+        }
+    };
+    let unchanged = put(json!({ "title": "Pay rent now" })).await;
+    let moved = put(json!({ "due_date": "2025-12-05" })).await;
+    let cleared = put(json!({ "due_date": null })).await;
+
+    assert_eq!(todo["due_date"], "2025-12-01");
+    assert_eq!(unchanged["due_date"], "2025-12-01");
+    assert_eq!(moved["due_date"], "2025-12-05");
+    assert_eq!(cleared["due_date"], Value::Null);
+
+    cleanup_user(&pool, owner).await;
+}
+
+/// Helper function to send a request as a user (`None` = without a token)
+async fn send_as(app: Router, user: Option<Uuid>, method: &str, uri: &str) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(user) = user {
+        request = request.header("authorization", bearer(user));
+    }
+    let response = app
+        .oneshot(
+            request
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "completed": true }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_todos_are_scoped_to_their_owner() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let (_, mine) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Mine", "tags": ["a"] }),
+    )
+    .await;
+    let (_, theirs) = post_todo(
+        app.clone(),
+        other,
+        &json!({ "title": "Theirs", "tags": ["b"] }),
+    )
+    .await;
+    let uri = format!("/api/todos/{}", mine["id"]);
+
+    let (_, owner_list) = list_todos(app.clone(), owner, "").await;
+    let (_, owner_tags) = list_todos(app.clone(), owner, "/tags").await;
+    let (other_get, _) = send_as(app.clone(), Some(other), "GET", &uri).await;
+    let (other_put, _) = send_as(app.clone(), Some(other), "PUT", &uri).await;
+    let (other_delete, _) = send_as(app.clone(), Some(other), "DELETE", &uri).await;
+    let (_, admin_list) = list_todos(app.clone(), admin, "").await;
+    let (_, admin_tags) = list_todos(app.clone(), admin, "/tags").await;
+    let (admin_get, _) = send_as(app.clone(), Some(admin), "GET", &uri).await;
+    let (admin_put, _) = send_as(app.clone(), Some(admin), "PUT", &uri).await;
+    let (anonymous, _) = send_as(app.clone(), None, "GET", "/api/todos").await;
+    let (owner_put, updated) = send_as(app.clone(), Some(owner), "PUT", &uri).await;
+    let (owner_delete, _) = send_as(app, Some(owner), "DELETE", &uri).await;
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, other).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(mine["owner_id"], owner.to_string());
+    assert_eq!(theirs["owner_id"], other.to_string());
+    assert_eq!(owner_list["total"], 1);
+    assert_eq!(owner_list["items"][0]["id"], mine["id"]);
+    assert_eq!(owner_tags, json!(["a"]));
+    assert_eq!(other_get, StatusCode::NOT_FOUND);
+    assert_eq!(other_put, StatusCode::NOT_FOUND);
+    assert_eq!(other_delete, StatusCode::NOT_FOUND);
+    assert_eq!(admin_list["total"], 2);
+    assert_eq!(admin_tags, json!(["a", "b"]));
+    assert_eq!(admin_get, StatusCode::OK);
+    assert_eq!(admin_put, StatusCode::FORBIDDEN);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(owner_put, StatusCode::OK);
+    assert_eq!(updated["completed"], true);
+    assert_eq!(owner_delete, StatusCode::NO_CONTENT);
+}
+
+/// Helper function to send a request with a JSON body as the owner
+async fn send_json(
+    app: Router,
+    owner: Uuid,
+    method: &str,
+    uri: &str,
+    payload: &Value,
+) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_todo_items() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+
+    let (_, todo) = post_todo(app.clone(), owner, &json!({ "title": "Move house" })).await;
+    let id = todo["id"].as_u64().unwrap();
+    let items_uri = format!("/api/todos/{id}/items");
+
+    let mut item_ids = Vec::new();
+    for text in ["Pack", "Book a van", "Clean"] {
+        let (status, body) = send_json(
+            app.clone(),
+            owner,
+            "POST",
+            &items_uri,
+            &json!({ "text": text }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        item_ids.push(body["items"].as_array().unwrap().last().unwrap()["id"].clone());
+    }
+    let first_uri = format!("{items_uri}/{}", item_ids[0]);
+
+    let (done_status, done) = send_json(
+        app.clone(),
+        owner,
+        "PUT",
+        &first_uri,
+        &json!({ "done": true }),
+    )
+    .await;
+    let (renamed_status, renamed) = send_json(
+        app.clone(),
+        owner,
+        "PUT",
+        &first_uri,
+        &json!({ "text": "Pack boxes" }),
+    )
+    .await;
+    let (deleted_status, deleted) = send_json(
+        app.clone(),
+        owner,
+        "DELETE",
+        &format!("{items_uri}/{}", item_ids[2]),
+        &json!({}),
+    )
+    .await;
+    let (_, fetched) = list_todos(app.clone(), owner, &format!("/{id}")).await;
+    let (empty_status, _) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        &items_uri,
+        &json!({ "text": " " }),
+    )
+    .await;
+    let (missing_status, missing) = send_json(
+        app.clone(),
+        owner,
+        "PUT",
+        &format!("{items_uri}/999999"),
+        &json!({ "done": true }),
+    )
+    .await;
+    let (other_status, _) = send_json(
+        app,
+        other,
+        "POST",
+        &items_uri,
+        &json!({ "text": "Sneak in" }),
+    )
+    .await;
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, other).await;
+
+    assert_eq!(todo["items"], json!([]));
+    assert_eq!(todo["completion_percentage"], Value::Null);
+
+    assert_eq!(done_status, StatusCode::OK);
+    assert_eq!(done["items"][0]["done"], true);
+    assert_eq!(done["completion_percentage"], 33);
+
+    assert_eq!(renamed_status, StatusCode::OK);
+    assert_eq!(renamed["items"][0]["text"], "Pack boxes");
+    assert_eq!(renamed["items"][0]["done"], true);
+
+    assert_eq!(deleted_status, StatusCode::OK);
+    assert_eq!(deleted["items"].as_array().unwrap().len(), 2);
+    assert_eq!(deleted["completion_percentage"], 50);
+    assert_eq!(fetched["items"], deleted["items"]);
+    assert_eq!(fetched["completion_percentage"], 50);
+
+    assert_eq!(empty_status, StatusCode::BAD_REQUEST);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+    assert_eq!(missing["message"], "Item with id 999999 not found");
+    assert_eq!(other_status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_complete_todos_bulk() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+
+    let mut ids = Vec::new();
+    for title in ["One", "Two"] {
+        let (_, todo) = post_todo(app.clone(), owner, &json!({ "title": title })).await;
+        ids.push(todo["id"].as_u64().unwrap());
+    }
+    let (_, others) = post_todo(app.clone(), other, &json!({ "title": "Theirs" })).await;
+    let others_id = others["id"].as_u64().unwrap();
+
+    let (status, report) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        "/api/todos/bulk/complete",
+        &json!({ "ids": [ids[1], 999_999, ids[0], others_id, ids[1]] }),
+    )
+    .await;
+    let (_, completed) = list_todos(app.clone(), owner, "?completed=true").await;
+    let (_, theirs) = list_todos(app.clone(), other, &format!("/{others_id}")).await;
+    let (empty_status, _) = send_json(
+        app,
+        owner,
+        "POST",
+        "/api/todos/bulk/complete",
+        &json!({ "ids": [] }),
+    )
+    .await;
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, other).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["updated"], json!([ids[1], ids[0]]));
+    assert_eq!(report["not_found"], json!([999_999, others_id]));
+    assert_eq!(completed["total"], 2);
+    assert_eq!(theirs["completed"], false);
+    assert_eq!(empty_status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_delete_todos_bulk() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+
+    let mut ids = Vec::new();
+    for title in ["One", "Two", "Three"] {
+        let (_, todo) = post_todo(app.clone(), owner, &json!({ "title": title })).await;
+        ids.push(todo["id"].as_u64().unwrap());
+    }
+    let (_, others) = post_todo(app.clone(), other, &json!({ "title": "Theirs" })).await;
+    let others_id = others["id"].as_u64().unwrap();
+
+    let (status, report) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        "/api/todos/bulk/delete",
+        &json!({ "ids": [ids[2], others_id, ids[0], 999_999, ids[2]] }),
+    )
+    .await;
+    let (_, remaining) = list_todos(app.clone(), owner, "").await;
+    let (_, theirs) = list_todos(app.clone(), other, "").await;
+    let (too_many_status, _) = send_json(
+        app,
+        owner,
+        "POST",
+        "/api/todos/bulk/delete",
+        &json!({ "ids": (1..=101).collect::<Vec<u64>>() }),
+    )
+    .await;
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, other).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["deleted"], json!([ids[2], ids[0]]));
+    assert_eq!(report["not_found"], json!([others_id, 999_999]));
+    assert_eq!(remaining["total"], 1);
+    assert_eq!(remaining["items"][0]["id"], ids[1]);
+    assert_eq!(theirs["total"], 1);
+    assert_eq!(too_many_status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_archive_todo() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, kept) = post_todo(app.clone(), owner, &json!({ "title": "Keep" })).await;
+    let (_, old) = post_todo(app.clone(), owner, &json!({ "title": "Old" })).await;
+    let uri = format!("/api/todos/{}", old["id"]);
+
+    let (archive_status, archived) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        &format!("{uri}/archive"),
+        &json!({}),
+    )
+    .await;
+    let (_, listed) = list_todos(app.clone(), owner, "").await;
+    let (_, archived_list) = list_todos(app.clone(), owner, "?archived=true").await;
+    let (_, fetched) = list_todos(app.clone(), owner, &format!("/{}", old["id"])).await;
+    let (unarchive_status, unarchived) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        &format!("{uri}/unarchive"),
+        &json!({}),
+    )
+    .await;
+    let (_, relisted) = list_todos(app.clone(), owner, "").await;
+    let (missing_status, _) =
+        send_json(app, owner, "POST", "/api/todos/999999/archive", &json!({})).await;
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(kept["archived"], false);
+    assert_eq!(archive_status, StatusCode::OK);
+    assert_eq!(archived["archived"], true);
+    assert_eq!(listed["total"], 1);
+    assert_eq!(listed["items"][0]["id"], kept["id"]);
+    assert_eq!(archived_list["total"], 1);
+    assert_eq!(archived_list["items"][0]["id"], old["id"]);
+    assert_eq!(fetched["archived"], true);
+    assert_eq!(unarchive_status, StatusCode::OK);
+    assert_eq!(unarchived["archived"], false);
+    assert_eq!(relisted["total"], 2);
+    assert_eq!(missing_status, StatusCode::NOT_FOUND);
+}
+
+/// Helper function to send a JSON Merge Patch to a todo as the owner
+async fn patch_todo(app: Router, owner: Uuid, id: &Value, patch: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/todos/{id}"))
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/merge-patch+json")
+                .body(Body::from(patch.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_patch_todo() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, todo) = post_todo(
+        app.clone(),
+        owner,
+        &json!({
+            "title": "Patch me",
+            "description": "Remove me",
+            "tags": ["a", "b"],
+            "due_date": "2025-12-01"
+        }),
+    )
+    .await;
+    let id = &todo["id"];
+
+    let (status, patched) = patch_todo(
+        app.clone(),
+        owner,
+        id,
+        r#"{"description": null, "completed": true, "tags": ["c"]}"#,
+    )
+    .await;
+    let (removed_title, removed_error) =
+        patch_todo(app.clone(), owner, id, r#"{"title": null}"#).await;
+    let (not_object, _) = patch_todo(app.clone(), owner, id, r#"["title"]"#).await;
+    let (empty_title, _) = patch_todo(app.clone(), owner, id, r#"{"title": " "}"#).await;
+    let (missing, _) = patch_todo(app, owner, &json!(999_999), r#"{"completed": true}"#).await;
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(patched["title"], "Patch me");
+    assert_eq!(patched["description"], Value::Null);
+    assert_eq!(patched["completed"], true);
+    assert_eq!(patched["tags"], json!(["c"]));
+    assert_eq!(patched["due_date"], "2025-12-01");
+    assert_eq!(removed_title, StatusCode::BAD_REQUEST);
+    assert_eq!(removed_error["message"], "title cannot be removed");
+    assert_eq!(not_object, StatusCode::BAD_REQUEST);
+    assert_eq!(empty_title, StatusCode::BAD_REQUEST);
+    assert_eq!(missing, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_export_and_import_todos() {
+    let (app, pool, owner) = create_app().await;
+    let other = insert_user(&pool).await;
+
+    let (_, todo) = post_todo(
+        app.clone(),
+        owner,
+        &json!({ "title": "Pack", "priority": "high", "tags": ["move"] }),
+    )
+    .await;
+    let id = todo["id"].as_u64().unwrap();
+    send_json(
+        app.clone(),
+        owner,
+        "POST",
+        &format!("/api/todos/{id}/items"),
+        &json!({ "text": "Boxes" }),
+    )
+    .await;
+    let (_, archived) = post_todo(app.clone(), owner, &json!({ "title": "Old" })).await;
+    send_json(
+        app.clone(),
+        owner,
+        "POST",
+        &format!("/api/todos/{}/archive", archived["id"]),
+        &json!({}),
+    )
+    .await;
+    post_todo(app.clone(), other, &json!({ "title": "Pack" })).await;
+
+    let (export_status, export) = list_todos(app.clone(), owner, "/export").await;
+    let (_, imported) = send_json(app.clone(), other, "POST", "/api/todos/import", &export).await;
+    let (_, replaced) = send_json(
+        app.clone(),
+        other,
+        "POST",
+        "/api/todos/import?on_conflict=replace",
+        &export,
+    )
+    .await;
+    let (_, others_list) = list_todos(app.clone(), other, "?q=pack").await;
+    let (_, kept_both) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        "/api/todos/import?on_conflict=keep_both",
+        &export,
+    )
+    .await;
+    let mut invalid = export.clone();
+    invalid["todos"][0]["title"] = json!("");
+    let (invalid_status, invalid_error) =
+        send_json(app.clone(), owner, "POST", "/api/todos/import", &invalid).await;
+    let (version_status, _) = send_json(
+        app.clone(),
+        owner,
+        "POST",
+        "/api/todos/import",
+        &json!({ "version": 2, "todos": [] }),
+    )
+    .await;
+    let (conflict_status, _) = send_json(
+        app,
+        owner,
+        "POST",
+        "/api/todos/import?on_conflict=merge",
+        &export,
+    )
+    .await;
+
+    cleanup_user(&pool, owner).await;
+    cleanup_user(&pool, other).await;
+
+    assert_eq!(export_status, StatusCode::OK);
+    assert_eq!(export["version"], 1);
+    let todos = export["todos"].as_array().unwrap();
+    assert_eq!(todos.len(), 2);
+    assert_eq!(todos[0]["id"], id);
+    assert_eq!(todos[0]["priority"], "high");
+    assert_eq!(
+        todos[0]["items"],
+        json!([{ "text": "Boxes", "done": false }])
+    );
+    assert_eq!(todos[1]["archived"], true);
+    assert!(todos[0].get("owner_id").is_none());
+
+    // "Pack" already exists for the other user and is skipped by default
+    assert_eq!(imported["created"], 1);
+    assert_eq!(imported["skipped"], 1);
+    assert_eq!(imported["todos"][0]["old_id"], id);
+    assert_eq!(imported["todos"][0]["outcome"], "skipped");
+    assert_ne!(imported["todos"][1]["new_id"], archived["id"]);
+
+    assert_eq!(replaced["replaced"], 2);
+    assert_eq!(others_list["total"], 1);
+    assert_eq!(others_list["items"][0]["priority"], "high");
+    assert_eq!(others_list["items"][0]["items"][0]["text"], "Boxes");
+
+    assert_eq!(kept_both["created"], 2);
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        invalid_error["message"],
+        format!("Todo {id}: Title cannot be empty")
+    );
+    assert_eq!(version_status, StatusCode::BAD_REQUEST);
+    assert_eq!(conflict_status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_todo_timestamps() {
+    let (app, pool, owner) = create_app().await;
+
+    let (_, created) = post_todo(app.clone(), owner, &json!({ "title": "Stamp me" })).await;
+    let uri = format!("/api/todos/{}", created["id"]);
+    let (_, completed) = send_json(
+        app.clone(),
+        owner,
+        "PUT",
+        &uri,
+        &json!({ "completed": true }),
+    )
+    .await;
+    let (_, renamed) = send_json(
+        app.clone(),
+        owner,
+        "PUT",
+        &uri,
+        &json!({ "title": "Stamped" }),
+    )
+    .await;
+    let (_, reopened) = send_json(app, owner, "PUT", &uri, &json!({ "completed": false })).await;
+
+    cleanup_user(&pool, owner).await;
+
+    let time = |todo: &Value, field: &str| {
+        chrono::DateTime::parse_from_rfc3339(todo[field].as_str().unwrap()).unwrap()
+    };
+    assert_eq!(created["created_at"], created["updated_at"]);
+    assert_eq!(created["completed_at"], Value::Null);
+
+    assert_eq!(completed["created_at"], created["created_at"]);
+    assert!(time(&completed, "updated_at") >= time(&created, "updated_at"));
+    assert_eq!(completed["completed_at"], completed["updated_at"]);
+
+    // Other changes keep the completion time
+    assert_eq!(renamed["completed_at"], completed["completed_at"]);
+    assert!(time(&renamed, "updated_at") >= time(&completed, "updated_at"));
+
+    assert_eq!(reopened["completed_at"], Value::Null);
+}
+
+/// Send a POST request to create a todo with a raw body and content type
+async fn post_raw_todo(
+    app: Router,
+    owner: Uuid,
+    content_type: &str,
+    body: &str,
+) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/todos")
+                .header("authorization", bearer(owner))
+                .header("content-type", content_type)
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+#[tokio::test]
+async fn test_extraction_failures_return_json_errors() {
+    let (app, pool, owner) = create_app().await;
+
+    let (syntax, syntax_body) =
+        post_raw_todo(app.clone(), owner, "application/json", "{\"title\":").await;
+    let (data, data_body) =
+        post_raw_todo(app.clone(), owner, "application/json", "{\"title\": 1}").await;
+    let (media_type, media_type_body) =
+        post_raw_todo(app.clone(), owner, "text/plain", "{\"title\": \"x\"}").await;
+    let path = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/todos/not-a-number")
+                .header("authorization", bearer(owner))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let path_status = path.status();
+    let path_body = parse_json_body(path.into_body()).await;
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(syntax, StatusCode::BAD_REQUEST);
+    assert_eq!(syntax_body["error"], "bad_request");
+    assert_eq!(syntax_body["code"], "MALFORMED_REQUEST");
+    assert!(syntax_body["message"].as_str().unwrap().contains("JSON"));
+    assert_eq!(data, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(data_body["error"], "unprocessable_entity");
+    assert_eq!(data_body["code"], "UNPROCESSABLE_ENTITY");
+    assert_eq!(media_type, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(media_type_body["code"], "UNSUPPORTED_MEDIA_TYPE");
+    assert_eq!(path_status, StatusCode::BAD_REQUEST);
+    assert_eq!(path_body["code"], "MALFORMED_REQUEST");
+}
+
+#[tokio::test]
+async fn test_oversized_bodies_are_rejected() {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.body_limits = api::body_limit::BodyLimits {
+        default: 2048,
+        upload: 64 * 1024,
+    };
+    let app = api::router(state);
+    let owner = insert_user(&pool).await;
+
+    let description = "Lorem ipsum dolor sit amet. ".repeat(30);
+    let todos: Vec<Value> = (0..5)
+        .map(|id| json!({"id": id, "title": format!("Todo {id}"), "description": description}))
+        .collect();
+    let document = json!({"version": 1, "todos": todos}).to_string();
+    assert!(document.len() > 2048);
+
+    let (oversized, oversized_body) =
+        post_raw_todo(app.clone(), owner, "application/json", &document).await;
+    let import = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/todos/import")
+                .header("authorization", bearer(owner))
+                .header("content-type", "application/json")
+                .body(Body::from(document))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let import_status = import.status();
+    let import_body = parse_json_body(import.into_body()).await;
+
+    cleanup_user(&pool, owner).await;
+
+    assert_eq!(oversized, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(oversized_body["error"], "payload_too_large");
+    assert_eq!(oversized_body["code"], "PAYLOAD_TOO_LARGE");
+    assert_eq!(import_status, StatusCode::OK);
+    assert_eq!(import_body["created"], 5);
 }
@@ -0,0 +1,925 @@
+mod helpers;
+
+use api::storage::{LocalStorage, Storage};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Send a GET request authenticated as a user
+async fn get(app: Router, uri: &str, user_id: Uuid) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+/// Insert a user with the given name and email
+async fn insert_named_user(pool: &PgPool, name: &str, email: &str) -> Uuid {
+    sqlx::query_scalar("INSERT INTO users (name, email) VALUES ($1, $2) RETURNING id")
+        .bind(name)
+        .bind(email)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to insert user")
+}
+
+#[tokio::test]
+async fn test_search_users_by_name_and_email() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    // A marker unique to this test run keeps other users out of the results
+    let marker = Uuid::new_v4().simple().to_string();
+    let alice = insert_named_user(
+        &pool,
+        &format!("Alice {marker}"),
+        &format!("alice-{marker}@example.com"),
+    )
+    .await;
+    let bob = insert_named_user(&pool, "Bob Builder", &format!("bob-{marker}@example.com")).await;
+    let carol = insert_named_user(
+        &pool,
+        &format!("Carol {marker}"),
+        &format!("carol-{marker}@example.org"),
+    )
+    .await;
+
+    let upper = marker.to_uppercase();
+    let (status, all) = get(app.clone(), &format!("/api/users?q={upper}"), admin).await;
+    let (_, by_name) = get(
+        app.clone(),
+        &format!("/api/users?q=alice%20{marker}"),
+        admin,
+    )
+    .await;
+    let (_, by_email) = get(
+        app.clone(),
+        &format!("/api/users?q={marker}@example.org"),
+        admin,
+    )
+    .await;
+    let (_, second_page) = get(
+        app.clone(),
+        &format!("/api/users?q={marker}&page=2&per_page=2"),
+        admin,
+    )
+    .await;
+    let (_, wildcard) = get(app.clone(), &format!("/api/users?q={marker}%25"), admin).await;
+    let (invalid, _) = get(app, "/api/users?per_page=0", admin).await;
+
+    for id in [admin, alice, bob, carol] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(all["total"], 3);
+    assert_eq!(all["page"], 1);
+    assert_eq!(all["per_page"], 20);
+    let ids: Vec<&str> = all["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, [alice.to_string(), bob.to_string(), carol.to_string()]);
+
+    assert_eq!(by_name["total"], 1);
+    assert_eq!(by_name["items"][0]["id"], alice.to_string());
+
+    assert_eq!(by_email["total"], 1);
+    assert_eq!(by_email["items"][0]["id"], carol.to_string());
+
+    assert_eq!(second_page["total"], 3);
+    assert_eq!(second_page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(second_page["items"][0]["id"], carol.to_string());
+
+    // '%' in the query matches literally
+    assert_eq!(wildcard["total"], 0);
+
+    assert_eq!(invalid, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_search_excludes_deleted_users() {
+    let (app, pool) = create_app().await;
+    let marker = Uuid::new_v4().simple().to_string();
+    let id = insert_named_user(
+        &pool,
+        "Deleted User",
+        &format!("deleted-{marker}@example.com"),
+    )
+    .await;
+    sqlx::query("UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let (status, body) = get(app, &format!("/api/users?q={marker}"), admin).await;
+    cleanup_user(&pool, id).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 0);
+    assert!(body["items"].as_array().unwrap().is_empty());
+}
+
+/// Send a POST request without a body, authenticated as a user
+async fn post_as(app: Router, uri: &str, user_id: Uuid) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+async fn soft_delete(pool: &PgPool, id: Uuid) {
+    sqlx::query("UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_restore_deleted_user() {
+    let (app, pool) = create_app().await;
+    let email = format!("restore-{}@example.com", Uuid::new_v4());
+    let id = insert_named_user(&pool, "Restored User", &email).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    soft_delete(&pool, id).await;
+    let uri = format!("/api/users/{id}/restore");
+
+    let by_member = send(app.clone(), "POST", &uri, Some(id)).await;
+    let (status, body) = post_as(app.clone(), &uri, admin).await;
+    let (found, _) = get(app.clone(), &format!("/api/users/{id}"), id).await;
+    // An active user cannot be restored
+    let (again, _) = post_as(app, &uri, admin).await;
+
+    cleanup_user(&pool, id).await;
+    cleanup_user(&pool, admin).await;
+
+    // Tokens of deleted users stop working
+    assert_eq!(by_member, StatusCode::UNAUTHORIZED);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["id"], id.to_string());
+    assert_eq!(body["email"], email);
+    assert_eq!(found, StatusCode::OK);
+    assert_eq!(again, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_restore_user_with_reused_email_conflicts() {
+    let (app, pool) = create_app().await;
+    let email = format!("reused-{}@example.com", Uuid::new_v4());
+    let deleted = insert_named_user(&pool, "Former User", &email).await;
+    soft_delete(&pool, deleted).await;
+    let current = insert_named_user(&pool, "Current User", &email).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let by_member = send(
+        app.clone(),
+        "POST",
+        &format!("/api/users/{deleted}/restore"),
+        Some(current),
+    )
+    .await;
+    let (status, body) =
+        post_as(app.clone(), &format!("/api/users/{deleted}/restore"), admin).await;
+    let (unknown, _) = post_as(
+        app,
+        &format!("/api/users/{}/restore", Uuid::new_v4()),
+        admin,
+    )
+    .await;
+
+    for id in [deleted, current, admin] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(by_member, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "conflict");
+    assert_eq!(body["code"], "EMAIL_ALREADY_EXISTS");
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_user() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let email = format!("created-{}@example.com", Uuid::new_v4());
+    let create = |user_id: Option<Uuid>| {
+        let app = app.clone();
+        let payload = json!({ "name": "Created User", "email": email });
+        async move {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header("content-type", "application/json");
+            if let Some(user_id) = user_id {
+                request = request.header("authorization", bearer(user_id));
+            }
+            app.oneshot(request.body(Body::from(payload.to_string())).unwrap())
+                .await
+                .unwrap()
+        }
+    };
+
+    let anonymous = create(None).await.status();
+    let by_manager = create(Some(manager)).await.status();
+    let response = create(Some(admin)).await;
+    let status = response.status();
+    let location = response.headers()["location"].to_str().unwrap().to_string();
+    let body = parse_json_body(response.into_body()).await;
+    let id: Uuid = body["id"].as_str().unwrap().parse().unwrap();
+
+    for id in [id, admin, manager] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(by_manager, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(location, format!("/api/users/{id}"));
+    assert_eq!(body["email"], email);
+}
+
+async fn post_bulk(app: Router, payload: &Value, admin: Uuid) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/users/bulk")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(admin))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+async fn count_users_by_marker(pool: &PgPool, marker: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE email LIKE '%' || $1 || '%'")
+        .bind(marker)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_bulk_create_users() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let member = insert_user(&pool).await;
+    let marker = Uuid::new_v4().simple().to_string();
+    let payload = json!([
+        { "name": "First", "email": format!("first-{marker}@example.com") },
+        { "name": "Second", "email": format!("second-{marker}@example.com"), "picture": "https://example.com/2.png" }
+    ]);
+
+    let (by_member, _) = post_bulk(app.clone(), &payload, member).await;
+    let (status, body) = post_bulk(app, &payload, admin).await;
+    let ids: Vec<Uuid> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|result| result["id"].as_str()?.parse().ok())
+        .collect();
+    let count = count_users_by_marker(&pool, &marker).await;
+    for &id in ids.iter().chain([&admin, &member]) {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(by_member, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["created"], 2);
+    assert_eq!(ids.len(), 2);
+    assert_eq!(body["results"][1]["index"], 1);
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_bulk_create_users_reports_every_invalid_entry() {
+    let (app, pool) = create_app().await;
+    let marker = Uuid::new_v4().simple().to_string();
+    let taken = format!("taken-{marker}@example.com");
+    let existing = insert_named_user(&pool, "Existing", &taken).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let repeated = format!("repeated-{marker}@example.com");
+    let payload = json!([
+        { "name": "Valid", "email": format!("valid-{marker}@example.com") },
+        { "name": "", "email": format!("blank-{marker}@example.com") },
+        { "name": "Repeated", "email": repeated },
+        { "name": "Repeated again", "email": repeated },
+        { "name": "Taken", "email": taken }
+    ]);
+
+    let (status, body) = post_bulk(app.clone(), &payload, admin).await;
+    let (empty, _) = post_bulk(app, &json!([]), admin).await;
+    // Nothing is created when any entry is invalid
+    let count = count_users_by_marker(&pool, &marker).await;
+    cleanup_user(&pool, existing).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["created"], 0);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 5);
+    assert!(results[0].get("error").is_none());
+    assert!(results[0].get("id").is_none());
+    assert_eq!(results[1]["error"], "Name cannot be empty");
+    assert!(results[2].get("error").is_none());
+    assert!(
+        results[3]["error"]
+            .as_str()
+            .unwrap()
+            .contains("more than once")
+    );
+    assert!(
+        results[4]["error"]
+            .as_str()
+            .unwrap()
+            .contains("already exists")
+    );
+    assert_eq!(count, 1);
+    assert_eq!(empty, StatusCode::BAD_REQUEST);
+}
+
+/// Create the test app storing uploads in a fresh temporary directory
+async fn create_app_with_storage() -> (Router, PgPool, std::path::PathBuf) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+    let dir = std::env::temp_dir().join(format!("avatar-test-{}", Uuid::new_v4()));
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+    state.storage = Storage::new(LocalStorage::new(&dir, "/uploads"));
+
+    (api::router(state), pool, dir)
+}
+
+/// Upload an avatar for a user, authenticated as `as_user`
+async fn upload_avatar(
+    app: Router,
+    user_id: Uuid,
+    as_user: Uuid,
+    content_type: &str,
+    data: &[u8],
+) -> (StatusCode, Value) {
+    let boundary = "avatar-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"avatar\"\r\nContent-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/users/{user_id}/avatar"))
+                .header("authorization", bearer(as_user))
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+#[tokio::test]
+async fn test_upload_avatar() {
+    let (app, pool, dir) = create_app_with_storage().await;
+    let user_id = insert_user(&pool).await;
+    let other = insert_user(&pool).await;
+    let png = b"\x89PNG\r\n\x1a\nnot really an image";
+
+    let (by_other, _) = upload_avatar(app.clone(), user_id, other, "image/png", png).await;
+    let (status, body) = upload_avatar(app.clone(), user_id, user_id, "image/png", png).await;
+    let picture = body["picture"].as_str().unwrap_or_default().to_string();
+    let served = app
+        .oneshot(
+            Request::builder()
+                .uri(&picture)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let served_status = served.status();
+    let served_body = served.into_body().collect().await.unwrap().to_bytes();
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, other).await;
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(by_other, StatusCode::FORBIDDEN);
+    assert_eq!(status, StatusCode::OK);
+    assert!(picture.starts_with(&format!("/uploads/avatars/{user_id}-")));
+    assert_eq!(
+        std::path::Path::new(&picture).extension(),
+        Some("png".as_ref())
+    );
+    assert_eq!(served_status, StatusCode::OK);
+    assert_eq!(served_body.as_ref(), png);
+}
+
+#[tokio::test]
+async fn test_upload_avatar_rejects_invalid_files() {
+    let (app, pool, dir) = create_app_with_storage().await;
+    let user_id = insert_user(&pool).await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let (unsupported, _) =
+        upload_avatar(app.clone(), user_id, user_id, "text/plain", b"hello").await;
+    let (mismatched, _) =
+        upload_avatar(app.clone(), user_id, user_id, "image/png", b"GIF89a...").await;
+    let too_large = vec![0xFF; 1024 * 1024 + 1];
+    let (oversized, _) =
+        upload_avatar(app.clone(), user_id, user_id, "image/jpeg", &too_large).await;
+    let (unknown, _) = upload_avatar(app, Uuid::new_v4(), admin, "image/gif", b"GIF89a...").await;
+    let (_, user) = get(
+        create_app().await.0,
+        &format!("/api/users/{user_id}"),
+        user_id,
+    )
+    .await;
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, admin).await;
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(unsupported, StatusCode::BAD_REQUEST);
+    assert_eq!(mismatched, StatusCode::BAD_REQUEST);
+    assert_eq!(oversized, StatusCode::BAD_REQUEST);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+    assert!(user["picture"].is_null());
+}
+
+/// Send a request without a body, authenticated as a user if one is given
+async fn send(app: Router, method: &str, uri: &str, user_id: Option<Uuid>) -> StatusCode {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(user_id) = user_id {
+        request = request.header("authorization", bearer(user_id));
+    }
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_user_access_by_role() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let other = insert_user(&pool).await;
+    let own = format!("/api/users/{member}");
+    let others = format!("/api/users/{other}");
+
+    let anonymous = send(app.clone(), "GET", &own, None).await;
+    let (member_own, body) = get(app.clone(), &own, member).await;
+    let member_other = send(app.clone(), "GET", &others, Some(member)).await;
+    let manager_other = send(app.clone(), "GET", &others, Some(manager)).await;
+    let member_list = send(app.clone(), "GET", "/api/users", Some(member)).await;
+    let manager_list = send(app.clone(), "GET", "/api/users", Some(manager)).await;
+    let admin_list = send(app.clone(), "GET", "/api/users", Some(admin)).await;
+    let member_delete = send(app.clone(), "DELETE", &others, Some(member)).await;
+    let manager_delete = send(app.clone(), "DELETE", &others, Some(manager)).await;
+    let admin_delete = send(app.clone(), "DELETE", &others, Some(admin)).await;
+    // Tokens of deleted users stop working
+    let deleted_user = send(app, "GET", &others, Some(other)).await;
+
+    for id in [admin, manager, member, other] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(member_own, StatusCode::OK);
+    assert_eq!(body["role"], "member");
+    assert_eq!(member_other, StatusCode::FORBIDDEN);
+    assert_eq!(manager_other, StatusCode::OK);
+    assert_eq!(member_list, StatusCode::FORBIDDEN);
+    assert_eq!(manager_list, StatusCode::FORBIDDEN);
+    assert_eq!(admin_list, StatusCode::OK);
+    assert_eq!(member_delete, StatusCode::FORBIDDEN);
+    assert_eq!(manager_delete, StatusCode::FORBIDDEN);
+    assert_eq!(admin_delete, StatusCode::NO_CONTENT);
+    assert_eq!(deleted_user, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_get_and_update_own_profile() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    let (status, me) = get(app.clone(), "/api/me", user_id).await;
+    let put = |payload: Value, user_id: Option<Uuid>| {
+        let app = app.clone();
+        async move {
+            let mut request = Request::builder()
+                .method("PUT")
+                .uri("/api/me")
+                .header("content-type", "application/json");
+            if let Some(user_id) = user_id {
+                request = request.header("authorization", bearer(user_id));
+            }
+            let response = app
+                .oneshot(request.body(Body::from(payload.to_string())).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            (status, parse_json_body(response.into_body()).await)
+        }
+    };
+    let (updated, body) = put(
+        json!({ "name": "Renamed User", "picture": "https://example.com/me.png" }),
+        Some(user_id),
+    )
+    .await;
+    // Omitted fields are left unchanged, and null clears the picture
+    let (_, unchanged) = put(json!({ "email": null }), Some(user_id)).await;
+    let (_, cleared) = put(json!({ "picture": null }), Some(user_id)).await;
+    let (invalid, _) = put(json!({ "email": "not-an-email" }), Some(user_id)).await;
+    let (anonymous, _) = put(json!({ "name": "Anonymous" }), None).await;
+    let unauthenticated = send(app, "GET", "/api/me", None).await;
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(me["id"], user_id.to_string());
+    assert_eq!(me["name"], "Test User");
+    assert_eq!(updated, StatusCode::OK);
+    assert_eq!(body["id"], user_id.to_string());
+    assert_eq!(body["name"], "Renamed User");
+    assert_eq!(body["picture"], "https://example.com/me.png");
+    assert_eq!(unchanged["picture"], "https://example.com/me.png");
+    assert_eq!(cleared["name"], "Renamed User");
+    assert!(cleared["picture"].is_null());
+    assert_eq!(invalid, StatusCode::BAD_REQUEST);
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(unauthenticated, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_update_user_requires_own_profile_or_admin() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    let member = insert_user(&pool).await;
+    let put = |name: &str, user_id: Option<Uuid>| {
+        let app = app.clone();
+        let payload = json!({ "name": name });
+        async move {
+            let mut request = Request::builder()
+                .method("PUT")
+                .uri(format!("/api/users/{member}"))
+                .header("content-type", "application/json");
+            if let Some(user_id) = user_id {
+                request = request.header("authorization", bearer(user_id));
+            }
+            let response = app
+                .oneshot(request.body(Body::from(payload.to_string())).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            (status, parse_json_body(response.into_body()).await)
+        }
+    };
+
+    let (anonymous, _) = put("Anonymous", None).await;
+    let (by_manager, _) = put("Taken Over", Some(manager)).await;
+    let (own, _) = put("Renamed Myself", Some(member)).await;
+    let (by_admin, body) = put("Renamed By Admin", Some(admin)).await;
+
+    for id in [admin, manager, member] {
+        cleanup_user(&pool, id).await;
+    }
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(by_manager, StatusCode::FORBIDDEN);
+    assert_eq!(own, StatusCode::OK);
+    assert_eq!(by_admin, StatusCode::OK);
+    assert_eq!(body["name"], "Renamed By Admin");
+}
+
+#[tokio::test]
+async fn test_sort_users() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let marker = Uuid::new_v4().simple().to_string();
+    // Each sort key puts the users in a different order
+    let mut ids = Vec::new();
+    for (name, email, created_at) in [
+        ("Bravo", "c", "2025-01-01T00:00:00Z"),
+        ("Charlie", "a", "2025-01-02T00:00:00Z"),
+        ("Alpha", "b", "2025-01-03T00:00:00Z"),
+    ] {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (name, email, created_at) VALUES ($1, $2, $3::timestamptz) RETURNING id",
+        )
+        .bind(format!("{name} {marker}"))
+        .bind(format!("{email}-{marker}@example.com"))
+        .bind(created_at)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+    let [bravo, charlie, alpha] = [ids[0], ids[1], ids[2]];
+
+    let list = |params: &'static str| {
+        let app = app.clone();
+        let marker = marker.clone();
+        async move {
+            let (status, body) = get(app, &format!("/api/users?q={marker}{params}"), admin).await;
+            let ids: Vec<String> = body["items"]
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|user| user["id"].as_str().unwrap().to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            (status, ids)
+        }
+    };
+    let (_, default) = list("").await;
+    let (_, by_name) = list("&sort=name").await;
+    let (_, by_name_desc) = list("&sort=name&order=desc").await;
+    let (_, by_email) = list("&sort=email&order=asc").await;
+    let (_, by_email_desc) = list("&sort=email&order=desc").await;
+    let (_, by_created_desc) = list("&sort=created_at&order=desc").await;
+    let (invalid_sort, _) = list("&sort=password_hash").await;
+    let (invalid_order, _) = list("&sort=name&order=sideways").await;
+
+    for id in [admin, alpha, bravo, charlie] {
+        cleanup_user(&pool, id).await;
+    }
+
+    let ids = |users: [Uuid; 3]| users.map(|id| id.to_string()).to_vec();
+    assert_eq!(default, ids([bravo, charlie, alpha]));
+    assert_eq!(by_name, ids([alpha, bravo, charlie]));
+    assert_eq!(by_name_desc, ids([charlie, bravo, alpha]));
+    assert_eq!(by_email, ids([charlie, alpha, bravo]));
+    assert_eq!(by_email_desc, ids([bravo, alpha, charlie]));
+    assert_eq!(by_created_desc, ids([alpha, charlie, bravo]));
+    assert_eq!(invalid_sort, StatusCode::BAD_REQUEST);
+    assert_eq!(invalid_order, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_user_activity_timeline() {
+    let (app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+    let other = insert_user(&pool).await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+    sqlx::query(
+        "INSERT INTO attendance_events (user_id, event_type, event_time, recorded_at) VALUES ($1, 'clock_in', '2000-01-01T09:00:00Z', NOW())",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let put = |payload: Value| {
+        let app = app.clone();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri("/api/me")
+                    .header("content-type", "application/json")
+                    .header("authorization", bearer(user_id))
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        }
+    };
+    // Only fields whose value changes are recorded
+    let updated =
+        put(json!({ "name": "New Name", "email": null, "picture": "https://example.com/a.png" }))
+            .await;
+
+    let uri = format!("/api/users/{user_id}/activity");
+    let (status, body) = get(app.clone(), &uri, user_id).await;
+    let (_, first_page) = get(app.clone(), &format!("{uri}?per_page=1"), user_id).await;
+    let forbidden = send(app.clone(), "GET", &uri, Some(other)).await;
+    let unknown = send(
+        app,
+        "GET",
+        &format!("/api/users/{}/activity", Uuid::new_v4()),
+        Some(manager),
+    )
+    .await;
+
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, other).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(updated, StatusCode::OK);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 3);
+    let items = body["items"].as_array().unwrap();
+    let kinds: Vec<&str> = items.iter().map(|i| i["kind"].as_str().unwrap()).collect();
+    assert_eq!(
+        kinds,
+        ["profile_changed", "profile_changed", "attendance_event"]
+    );
+    let name_change = items.iter().find(|i| i["field"] == "name").unwrap();
+    assert_eq!(name_change["old_value"], "Test User");
+    assert_eq!(name_change["new_value"], "New Name");
+    let picture_change = items.iter().find(|i| i["field"] == "picture").unwrap();
+    assert!(picture_change["old_value"].is_null());
+    assert_eq!(items[2]["event_type"], "clock_in");
+    assert_eq!(items[2]["occurred_at"], "2000-01-01T09:00:00Z");
+    assert_eq!(first_page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(forbidden, StatusCode::FORBIDDEN);
+    assert_eq!(unknown, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_deactivate_and_activate_user() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let email = format!("deactivated-{}@example.com", Uuid::new_v4());
+    let register = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/register")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "name": "Member", "email": email, "password": "s3cret-password" })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let member: Uuid = parse_json_body(register.into_body()).await["user"]["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let login = || {
+        let app = app.clone();
+        let email = email.clone();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/auth/login")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({ "email": email, "password": "s3cret-password" }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        }
+    };
+
+    let by_member = send(
+        app.clone(),
+        "POST",
+        &format!("/api/users/{member}/deactivate"),
+        Some(member),
+    )
+    .await;
+    let (deactivated, body) = post_as(
+        app.clone(),
+        &format!("/api/users/{member}/deactivate"),
+        admin,
+    )
+    .await;
+    let me_while_deactivated = send(app.clone(), "GET", "/api/me", Some(member)).await;
+    let login_while_deactivated = login().await;
+    // Deactivated users are still listed
+    let (_, listed) = get(app.clone(), &format!("/api/users/{member}"), admin).await;
+    let (itself, _) = post_as(
+        app.clone(),
+        &format!("/api/users/{admin}/deactivate"),
+        admin,
+    )
+    .await;
+    let (activated, reactivated) =
+        post_as(app.clone(), &format!("/api/users/{member}/activate"), admin).await;
+    let me_after = send(app.clone(), "GET", "/api/me", Some(member)).await;
+    let login_after = login().await;
+
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, member).await;
+
+    assert_eq!(by_member, StatusCode::FORBIDDEN);
+    assert_eq!(deactivated, StatusCode::OK);
+    assert_eq!(body["is_active"], false);
+    assert_eq!(me_while_deactivated, StatusCode::UNAUTHORIZED);
+    assert_eq!(login_while_deactivated, StatusCode::UNAUTHORIZED);
+    assert_eq!(listed["is_active"], false);
+    assert_eq!(itself, StatusCode::BAD_REQUEST);
+    assert_eq!(activated, StatusCode::OK);
+    assert_eq!(reactivated["is_active"], true);
+    assert_eq!(me_after, StatusCode::OK);
+    assert_eq!(login_after, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_exhausted_connection_pool_responds_service_unavailable() {
+    let (_app, pool) = create_app().await;
+    let user_id = insert_user(&pool).await;
+
+    // A pool with a single connection that is held for the whole request
+    let database_url = std::env::var("TEST_DATABASE_URL").unwrap();
+    let busy_pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(std::time::Duration::from_millis(200))
+        .connect(&database_url)
+        .await
+        .unwrap();
+    let held = busy_pool.acquire().await.unwrap();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), busy_pool);
+    state.auth_tokens = test_auth_tokens();
+    let response = api::router(state)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/users/{user_id}"))
+                .header("authorization", bearer(user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    drop(held);
+
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "5");
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["error"], "service_unavailable");
+    assert_eq!(body["code"], "SERVICE_UNAVAILABLE");
+}
@@ -0,0 +1,381 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    http::{HeaderMap, Request, StatusCode},
+    routing::post,
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &str = "test-webhook-secret";
+
+/// Helper function to create the test app
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn send(
+    app: Router,
+    admin: Uuid,
+    method: &str,
+    uri: &str,
+    payload: Option<&Value>,
+) -> axum::response::Response {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", bearer(admin))
+        .header("content-type", "application/json");
+    let body = payload.map_or_else(Body::empty, |p| Body::from(p.to_string()));
+    app.oneshot(request.body(body).unwrap()).await.unwrap()
+}
+
+/// A request received by the test subscriber
+struct Received {
+    headers: HeaderMap,
+    body: String,
+}
+
+/// Start a subscriber on a local port answering every request with `status`
+/// Returns its URL and the received requests
+async fn start_subscriber(status: StatusCode) -> (String, mpsc::UnboundedReceiver<Received>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let subscriber = Router::new().route(
+        "/hook",
+        post(move |headers: HeaderMap, body: Bytes| {
+            let tx = tx.clone();
+            async move {
+                let body = String::from_utf8(body.to_vec()).unwrap();
+                let _ = tx.send(Received { headers, body });
+                status
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, subscriber).await.unwrap() });
+
+    (format!("http://{addr}/hook"), rx)
+}
+
+async fn create_webhook(app: Router, admin: Uuid, url: &str) -> String {
+    let payload = json!({ "url": url, "secret": SECRET, "description": "test subscriber" });
+    let response = send(app, admin, "POST", "/api/admin/webhooks", Some(&payload)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    body["id"].as_str().unwrap().to_string()
+}
+
+/// Record a clock-in of the user and return the id of the event
+async fn record_clock_in(app: Router, user_id: Uuid) -> String {
+    let payload = json!({
+        "user_id": user_id,
+        "event_type": "clock_in",
+        "event_time": "2025-11-05T09:00:00Z"
+    });
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-events")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(user_id))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = parse_json_body(response.into_body()).await;
+    body["id"].as_str().unwrap().to_string()
+}
+
+/// Wait for the webhook's delivery of the user's event to satisfy `done`
+async fn wait_for_delivery(
+    app: Router,
+    admin: Uuid,
+    webhook_id: &str,
+    user_id: Uuid,
+    done: impl Fn(&Value) -> bool,
+) -> Value {
+    for _ in 0..50 {
+        let uri = format!("/api/admin/webhooks/{webhook_id}/deliveries");
+        let response = send(app.clone(), admin, "GET", &uri, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = parse_json_body(response.into_body()).await;
+
+        let delivery = body.as_array().unwrap().iter().find(|d| {
+            let payload: Value = serde_json::from_str(d["payload"].as_str().unwrap()).unwrap();
+            payload["data"]["user_id"] == user_id.to_string()
+        });
+        if let Some(delivery) = delivery.filter(|d| done(d)) {
+            return delivery.clone();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("No matching delivery for user {user_id}");
+}
+
+#[tokio::test]
+async fn test_webhook_crud() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    // An admin is required
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/admin/webhooks")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = send(app.clone(), manager, "GET", "/api/admin/webhooks", None).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let payload = json!({ "url": "ftp://example.com/hook" });
+    let response = send(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/admin/webhooks",
+        Some(&payload),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // A secret is generated when none is given, and only returned on creation
+    let payload = json!({ "url": "https://example.com/hook" });
+    let response = send(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/admin/webhooks",
+        Some(&payload),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = parse_json_body(response.into_body()).await;
+    assert_eq!(body["is_active"], true);
+    assert_eq!(body["secret"].as_str().unwrap().len(), 64);
+    let id = body["id"].as_str().unwrap().to_string();
+    let uri = format!("/api/admin/webhooks/{id}");
+
+    let payload = json!({ "is_active": false });
+    let response = send(app.clone(), admin, "PUT", &uri, Some(&payload)).await;
+    let status = response.status();
+    let body = parse_json_body(response.into_body()).await;
+    send(app.clone(), admin, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["is_active"], false);
+    assert_eq!(body["url"], "https://example.com/hook");
+    assert!(body.get("secret").is_none());
+
+    let response = send(app, admin, "GET", &uri, None).await;
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, manager).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_attendance_event_is_delivered_signed() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let (url, mut received) = start_subscriber(StatusCode::NO_CONTENT).await;
+    let webhook_id = create_webhook(app.clone(), admin, &url).await;
+    let user_id = insert_user(&pool).await;
+
+    record_clock_in(app.clone(), user_id).await;
+
+    let request = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let request = received.recv().await.unwrap();
+            let body: Value = serde_json::from_str(&request.body).unwrap();
+            if body["data"]["user_id"] == user_id.to_string() {
+                return request;
+            }
+        }
+    })
+    .await;
+    let delivery = wait_for_delivery(app.clone(), admin, &webhook_id, user_id, |d| {
+        d["status"] == "succeeded"
+    })
+    .await;
+
+    send(
+        app,
+        admin,
+        "DELETE",
+        &format!("/api/admin/webhooks/{webhook_id}"),
+        None,
+    )
+    .await;
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    let request = request.expect("Subscriber was not called");
+    let header = |name: &str| request.headers[name].to_str().unwrap().to_string();
+    let timestamp: i64 = header("x-webhook-timestamp").parse().unwrap();
+    assert_eq!(
+        header("x-webhook-signature"),
+        api::webhook::sign(SECRET, timestamp, &request.body)
+    );
+    assert_eq!(header("x-webhook-event"), "attendance_event.created");
+    assert_eq!(
+        header("x-webhook-delivery"),
+        delivery["id"].as_str().unwrap()
+    );
+
+    let body: Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["event"], "attendance_event.created");
+    assert_eq!(body["data"]["event_type"], "clock_in");
+    assert_eq!(delivery["attempts"], 1);
+    assert_eq!(delivery["last_status_code"], 204);
+    assert!(delivery["delivered_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_failed_delivery_is_logged_and_retried() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let (url, _received) = start_subscriber(StatusCode::INTERNAL_SERVER_ERROR).await;
+    let webhook_id = create_webhook(app.clone(), admin, &url).await;
+    let user_id = insert_user(&pool).await;
+
+    record_clock_in(app.clone(), user_id).await;
+
+    let failed = wait_for_delivery(app.clone(), admin, &webhook_id, user_id, |d| {
+        d["attempts"] == 1
+    })
+    .await;
+    let uri = format!(
+        "/api/admin/webhooks/{webhook_id}/deliveries/{}/retry",
+        failed["id"].as_str().unwrap()
+    );
+    let response = send(app.clone(), admin, "POST", &uri, None).await;
+    let status = response.status();
+    let retried = parse_json_body(response.into_body()).await;
+
+    let uri = format!("/api/admin/webhooks/{webhook_id}/deliveries?status=unknown");
+    let invalid_status = send(app.clone(), admin, "GET", &uri, None).await.status();
+
+    send(
+        app,
+        admin,
+        "DELETE",
+        &format!("/api/admin/webhooks/{webhook_id}"),
+        None,
+    )
+    .await;
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(failed["status"], "pending");
+    assert_eq!(failed["last_status_code"], 500);
+    assert!(failed["last_error"].is_string());
+    assert!(failed["next_attempt_at"].is_string());
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(retried["id"], failed["id"]);
+    assert_eq!(retried["attempts"], 2);
+    assert_eq!(retried["status"], "pending");
+
+    assert_eq!(invalid_status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_approved_correction_is_delivered() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let (url, mut received) = start_subscriber(StatusCode::NO_CONTENT).await;
+    let webhook_id = create_webhook(app.clone(), admin, &url).await;
+    let user_id = insert_user(&pool).await;
+
+    let event_id = record_clock_in(app.clone(), user_id).await;
+    let payload = json!({
+        "event_id": event_id,
+        "proposed_event_type": "clock_in",
+        "proposed_event_time": "2025-11-05T08:30:00Z",
+        "reason": "Forgot to clock in on arrival"
+    });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/attendance-corrections")
+                .header("content-type", "application/json")
+                .header("authorization", bearer(user_id))
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let correction = parse_json_body(response.into_body()).await;
+    let uri = format!(
+        "/api/attendance-corrections/{}/approve",
+        correction["id"].as_str().unwrap()
+    );
+    let approved = send(app.clone(), admin, "POST", &uri, Some(&json!({}))).await;
+    let approved_status = approved.status();
+    let approved = parse_json_body(approved.into_body()).await;
+
+    let body = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let request = received.recv().await.unwrap();
+            let body: Value = serde_json::from_str(&request.body).unwrap();
+            if body["data"]["amends_event_id"] == event_id {
+                return body;
+            }
+        }
+    })
+    .await;
+
+    send(
+        app,
+        admin,
+        "DELETE",
+        &format!("/api/admin/webhooks/{webhook_id}"),
+        None,
+    )
+    .await;
+    cleanup_user(&pool, admin).await;
+    cleanup_user(&pool, user_id).await;
+
+    assert_eq!(approved_status, StatusCode::OK);
+    let body = body.expect("Subscriber was not called for the appended event");
+    assert_eq!(body["event"], "attendance_event.created");
+    assert_eq!(body["data"]["id"], approved["applied_event_id"]);
+    assert_eq!(body["data"]["user_id"], user_id.to_string());
+    assert_eq!(body["data"]["event_time"], "2025-11-05T08:30:00Z");
+}
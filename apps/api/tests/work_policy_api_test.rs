@@ -0,0 +1,342 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use helpers::{
+    TestContext, bearer, cleanup_user, insert_user, insert_user_with_role, test_auth_tokens,
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Helper function to create the test app backed by the migrated test database
+async fn create_app() -> (Router, PgPool) {
+    let ctx = TestContext::new().await;
+    let pool = ctx.pool().clone();
+
+    let mut state = api::AppState::new(api::TodoStore::new(), pool.clone());
+    state.auth_tokens = test_auth_tokens();
+
+    (api::router(state), pool)
+}
+
+/// Helper function to parse JSON response body
+async fn parse_json_body(body: Body) -> Value {
+    let bytes = body.collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+async fn send(app: Router, method: &str, uri: &str, payload: Option<Value>) -> (StatusCode, Value) {
+    request(app, Request::builder().method(method).uri(uri), payload).await
+}
+
+/// Send a request authenticated as a user
+async fn send_as(
+    app: Router,
+    user_id: Uuid,
+    method: &str,
+    uri: &str,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", bearer(user_id));
+    request(app, builder, payload).await
+}
+
+/// Send a request with the payload, if any, as its JSON body
+async fn request(
+    app: Router,
+    builder: axum::http::request::Builder,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = match payload {
+        Some(payload) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(payload.to_string()))
+            .unwrap(),
+        None => builder.body(Body::empty()).unwrap(),
+    };
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    (status, parse_json_body(response.into_body()).await)
+}
+
+fn policy_id(policy: &Value) -> Uuid {
+    policy["id"].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_work_policy_crud() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    let (status, created) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/work-policies",
+        Some(json!({
+            "name": "Part-time",
+            "standard_daily_minutes": 360,
+            "weekly_threshold_minutes": 1800,
+            "week_start": "sunday"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["week_start"], "sunday");
+    assert_eq!(created["rounding_mode"], "none");
+    assert_eq!(created["rounding_minutes"], 1);
+    assert_eq!(created["is_active"], false);
+    let id = policy_id(&created);
+
+    let (status, updated) = send_as(
+        app.clone(),
+        admin,
+        "PUT",
+        &format!("/api/work-policies/{id}"),
+        Some(json!({ "rounding_mode": "up", "rounding_minutes": 15 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["rounding_mode"], "up");
+    assert_eq!(updated["rounding_minutes"], 15);
+    assert_eq!(updated["standard_daily_minutes"], 360);
+
+    let (status, list) = send_as(app.clone(), admin, "GET", "/api/work-policies", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        list.as_array()
+            .unwrap()
+            .iter()
+            .any(|policy| policy_id(policy) == id)
+    );
+
+    let (status, _) = send_as(
+        app.clone(),
+        admin,
+        "DELETE",
+        &format!("/api/work-policies/{id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send_as(app, admin, "GET", &format!("/api/work-policies/{id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, admin).await;
+}
+
+#[tokio::test]
+async fn test_create_work_policy_validation() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+
+    for payload in [
+        json!({ "name": "", "standard_daily_minutes": 480, "weekly_threshold_minutes": 2400 }),
+        json!({ "name": "Zero", "standard_daily_minutes": 0, "weekly_threshold_minutes": 2400 }),
+        json!({
+            "name": "Bad week",
+            "standard_daily_minutes": 480,
+            "weekly_threshold_minutes": 2400,
+            "week_start": "someday"
+        }),
+        json!({
+            "name": "Bad rounding",
+            "standard_daily_minutes": 480,
+            "weekly_threshold_minutes": 2400,
+            "rounding_mode": "nearest",
+            "rounding_minutes": 90
+        }),
+    ] {
+        let (status, _) = send_as(
+            app.clone(),
+            admin,
+            "POST",
+            "/api/work-policies",
+            Some(payload),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    cleanup_user(&pool, admin).await;
+}
+
+#[tokio::test]
+async fn test_missing_work_policy_returns_not_found() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let id = Uuid::new_v4();
+
+    let (status, _) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        &format!("/api/work-policies/{id}/activate"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send_as(
+        app,
+        admin,
+        "PUT",
+        &format!("/api/work-policies/{id}"),
+        Some(json!({ "name": "Renamed" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    cleanup_user(&pool, admin).await;
+}
+
+#[tokio::test]
+async fn test_active_work_policy_drives_overtime() {
+    let (app, pool) = create_app().await;
+    let admin = insert_user_with_role(&pool, "admin").await;
+    let user_id = insert_user(&pool).await;
+
+    // 09:00-17:40 JST: 8h 40m of work
+    for (event_type, event_time) in [
+        ("clock_in", "2025-11-05T00:00:00Z"),
+        ("clock_out", "2025-11-05T08:40:00Z"),
+    ] {
+        let (status, _) = send_as(
+            app.clone(),
+            user_id,
+            "POST",
+            "/api/attendance-events",
+            Some(json!({
+                "user_id": user_id,
+                "event_type": event_type,
+                "event_time": event_time
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, created) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        "/api/work-policies",
+        Some(json!({
+            "name": "Hourly rounding",
+            "standard_daily_minutes": 420,
+            "weekly_threshold_minutes": 2400,
+            "rounding_mode": "down",
+            "rounding_minutes": 60
+        })),
+    )
+    .await;
+    let id = policy_id(&created);
+
+    let (status, activated) = send_as(
+        app.clone(),
+        admin,
+        "POST",
+        &format!("/api/work-policies/{id}/activate"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(activated["is_active"], true);
+
+    let (status, active) =
+        send_as(app.clone(), admin, "GET", "/api/work-policies/active", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(policy_id(&active), id);
+
+    let (overtime_status, overtime) = send_as(
+        app.clone(),
+        user_id,
+        "GET",
+        &format!("/api/users/{user_id}/attendance/overtime?period=2025-11"),
+        None,
+    )
+    .await;
+    let (timesheet_status, timesheet) = send_as(
+        app.clone(),
+        user_id,
+        "GET",
+        &format!("/api/users/{user_id}/attendance/timesheet?year=2025&month=11"),
+        None,
+    )
+    .await;
+
+    // Deleting the active policy restores the deployment defaults; do it before
+    // asserting so a failure does not leak an active policy into other tests
+    send_as(
+        app,
+        admin,
+        "DELETE",
+        &format!("/api/work-policies/{id}"),
+        None,
+    )
+    .await;
+    cleanup_user(&pool, user_id).await;
+    cleanup_user(&pool, admin).await;
+
+    assert_eq!(overtime_status, StatusCode::OK);
+    assert_eq!(overtime["standard_daily_minutes"], 420);
+    // 520 minutes rounded down to 480, one hour beyond the 7 hour standard
+    assert_eq!(overtime["days"][0]["worked_minutes"], 480);
+    assert_eq!(overtime["totals"]["daily_overtime_minutes"], 60);
+
+    assert_eq!(timesheet_status, StatusCode::OK);
+    assert_eq!(timesheet["totals"]["worked_minutes"], 480);
+}
+
+#[tokio::test]
+async fn test_work_policy_routes_require_permissions() {
+    let (app, pool) = create_app().await;
+    let member = insert_user_with_role(&pool, "member").await;
+    let manager = insert_user_with_role(&pool, "manager").await;
+
+    let (anonymous, _) = send(app.clone(), "GET", "/api/work-policies", None).await;
+    let (listed, _) = send_as(app.clone(), member, "GET", "/api/work-policies", None).await;
+    let (by_manager, _) = send_as(
+        app.clone(),
+        manager,
+        "POST",
+        "/api/work-policies",
+        Some(json!({
+            "name": "Manager policy",
+            "standard_daily_minutes": 480,
+            "weekly_threshold_minutes": 2400
+        })),
+    )
+    .await;
+    let (created, body) = send_as(
+        app,
+        member,
+        "POST",
+        "/api/work-policies",
+        Some(json!({
+            "name": "Member policy",
+            "standard_daily_minutes": 480,
+            "weekly_threshold_minutes": 2400
+        })),
+    )
+    .await;
+
+    cleanup_user(&pool, member).await;
+    cleanup_user(&pool, manager).await;
+
+    assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+    assert_eq!(listed, StatusCode::OK);
+    assert_eq!(by_manager, StatusCode::FORBIDDEN);
+    assert_eq!(created, StatusCode::FORBIDDEN);
+    assert_eq!(body["message"], "The policies:write permission is required");
+}